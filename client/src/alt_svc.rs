@@ -0,0 +1,151 @@
+//! HTTP alternative services (RFC 7838) learning for discovering http/3 endpoints.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::http::{
+    header::{HeaderMap, ALT_SVC},
+    uri::Authority,
+};
+
+// default freshness of alternative service when `ma` parameter is absent.
+const DEFAULT_MAX_AGE: u64 = 24 * 60 * 60;
+
+/// Cache of http/3 endpoints advertised by `Alt-Svc` response header, keyed by origin authority.
+///
+/// Only alternatives on the same host with a different(or the same) udp port are learned.
+/// Alternatives pointing to other hosts are ignored as they require separate dns resolving and
+/// certificate validation against the origin.
+pub(crate) struct AltSvc {
+    map: Mutex<HashMap<Authority, Entry>>,
+}
+
+struct Entry {
+    port: u16,
+    expires: Instant,
+}
+
+impl AltSvc {
+    pub(crate) fn new() -> Self {
+        Self {
+            map: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get udp port of fresh http/3 alternative of authority.
+    pub(crate) fn get(&self, authority: &Authority) -> Option<u16> {
+        let mut map = self.map.lock().unwrap();
+        match map.get(authority) {
+            Some(entry) if entry.expires > Instant::now() => Some(entry.port),
+            Some(_) => {
+                map.remove(authority);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Forget alternative of authority. Used when connecting to the alternative failed.
+    pub(crate) fn remove(&self, authority: &Authority) {
+        self.map.lock().unwrap().remove(authority);
+    }
+
+    /// Learn from `Alt-Svc` header of response received from authority. Header without usable
+    /// http/3 alternative replaces previously learned one.
+    pub(crate) fn learn(&self, authority: &Authority, headers: &HeaderMap) {
+        let mut values = headers.get_all(ALT_SVC).iter().peekable();
+
+        if values.peek().is_none() {
+            return;
+        }
+
+        let alt = values
+            .filter_map(|v| v.to_str().ok())
+            .find_map(|v| parse(v, authority.host()));
+
+        let mut map = self.map.lock().unwrap();
+        match alt {
+            Some((port, max_age)) => {
+                let expires = Instant::now() + Duration::from_secs(max_age);
+                map.insert(authority.clone(), Entry { port, expires });
+            }
+            None => {
+                map.remove(authority);
+            }
+        }
+    }
+}
+
+// parse header value and return port and max age of the first usable http/3 alternative.
+fn parse(value: &str, host: &str) -> Option<(u16, u64)> {
+    value.split(',').find_map(|alt| {
+        let mut params = alt.split(';');
+        let (protocol, authority) = params.next()?.trim().split_once('=')?;
+
+        if !matches!(protocol.trim(), "h3" | "h3-29") {
+            return None;
+        }
+
+        let authority = authority.trim().trim_matches('"');
+        let (alt_host, port) = authority.rsplit_once(':')?;
+        if !alt_host.is_empty() && !alt_host.eq_ignore_ascii_case(host) {
+            return None;
+        }
+        let port = port.parse().ok()?;
+
+        let max_age = params
+            .filter_map(|param| param.trim().strip_prefix("ma="))
+            .find_map(|ma| ma.trim_matches('"').parse().ok())
+            .unwrap_or(DEFAULT_MAX_AGE);
+
+        (max_age > 0).then_some((port, max_age))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::http::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn parse_value() {
+        assert_eq!(parse("h3=\":443\"; ma=3600", "example.com"), Some((443, 3600)));
+        assert_eq!(
+            parse("h2=\":443\", h3-29=\":8443\"", "example.com"),
+            Some((8443, DEFAULT_MAX_AGE))
+        );
+        assert_eq!(
+            parse("h3=\"EXAMPLE.com:443\"", "example.com"),
+            Some((443, DEFAULT_MAX_AGE))
+        );
+        assert_eq!(parse("h3=\"other.com:443\"", "example.com"), None);
+        assert_eq!(parse("h3=\":443\"; ma=0", "example.com"), None);
+        assert_eq!(parse("clear", "example.com"), None);
+    }
+
+    #[test]
+    fn learn() {
+        let alt_svc = AltSvc::new();
+        let authority = Authority::from_static("example.com");
+
+        let mut headers = HeaderMap::new();
+        alt_svc.learn(&authority, &headers);
+        assert_eq!(alt_svc.get(&authority), None);
+
+        headers.insert(ALT_SVC, HeaderValue::from_static("h3=\":443\"; ma=60"));
+        alt_svc.learn(&authority, &headers);
+        assert_eq!(alt_svc.get(&authority), Some(443));
+
+        // response without the header keeps learned alternative.
+        alt_svc.learn(&authority, &HeaderMap::new());
+        assert_eq!(alt_svc.get(&authority), Some(443));
+
+        headers.insert(ALT_SVC, HeaderValue::from_static("clear"));
+        alt_svc.learn(&authority, &headers);
+        assert_eq!(alt_svc.get(&authority), None);
+    }
+}
@@ -0,0 +1,129 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use std::collections::BTreeMap;
+
+use futures_core::stream::Stream;
+
+use crate::{body::BodyError, bytes::Bytes, error::Error, request::Request, response::Response};
+
+type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<Response<'a>, Error>> + 'a>>;
+
+/// Stream of responses for a batch of requests sent concurrently.
+///
+/// Every item is a tuple of the index of request in the batch and the result of sending it.
+/// Items are yielded in the order requests finish by default. See [SendAll::ordered] for
+/// yielding them in the order of batch.
+///
+/// At most `max_concurrency` requests are in flight at any time and the next request from the
+/// batch is only started when a previous one is finished. In ordered mode finished responses
+/// waiting for earlier ones are counted as in flight so they keep their connections bounded.
+///
+/// See [Client::send_all](crate::Client::send_all) for constructing it.
+pub struct SendAll<'a, I> {
+    reqs: I,
+    next: usize,
+    max: usize,
+    in_flight: Vec<(usize, SendFuture<'a>)>,
+    ordered: Option<Ordered<'a>>,
+}
+
+struct Ordered<'a> {
+    next: usize,
+    done: BTreeMap<usize, Result<Response<'a>, Error>>,
+}
+
+impl<'a, I> SendAll<'a, I> {
+    /// # Panics:
+    /// When pass 0 as max concurrency.
+    pub(crate) fn new(reqs: I, max: usize) -> Self {
+        assert_ne!(max, 0, "max_concurrency must be greater than 0");
+        Self {
+            reqs,
+            next: 0,
+            max,
+            in_flight: Vec::with_capacity(max),
+            ordered: None,
+        }
+    }
+
+    /// Yield responses in the order of requests in batch instead of the order they finish.
+    pub fn ordered(mut self) -> Self {
+        self.ordered = Some(Ordered {
+            next: 0,
+            done: BTreeMap::new(),
+        });
+        self
+    }
+
+    fn occupied(&self) -> usize {
+        self.in_flight.len() + self.ordered.as_ref().map(|o| o.done.len()).unwrap_or(0)
+    }
+}
+
+impl<'a, I, B, E> Stream for SendAll<'a, I>
+where
+    I: Iterator<Item = Request<'a, B>> + Unpin,
+    B: Stream<Item = Result<Bytes, E>> + 'a,
+    E: 'a,
+    BodyError: From<E>,
+{
+    type Item = (usize, Result<Response<'a>, Error>);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            while this.occupied() < this.max {
+                match this.reqs.next() {
+                    Some(req) => {
+                        this.in_flight.push((this.next, Box::pin(req.send())));
+                        this.next += 1;
+                    }
+                    None => break,
+                }
+            }
+
+            if let Some(ordered) = this.ordered.as_mut() {
+                if let Some(res) = ordered.done.remove(&ordered.next) {
+                    let idx = ordered.next;
+                    ordered.next += 1;
+                    return Poll::Ready(Some((idx, res)));
+                }
+            }
+
+            let finished =
+                this.in_flight
+                    .iter_mut()
+                    .enumerate()
+                    .find_map(|(i, (_, fut))| match fut.as_mut().poll(cx) {
+                        Poll::Ready(res) => Some((i, res)),
+                        Poll::Pending => None,
+                    });
+
+            match finished {
+                Some((i, res)) => {
+                    let (idx, _) = this.in_flight.swap_remove(i);
+                    match this.ordered.as_mut() {
+                        // response is buffered and the loop continues to yield it when it's next.
+                        Some(ordered) => {
+                            ordered.done.insert(idx, res);
+                        }
+                        None => return Poll::Ready(Some((idx, res))),
+                    }
+                }
+                None if this.in_flight.is_empty() => return Poll::Ready(None),
+                None => return Poll::Pending,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self.reqs.size_hint();
+        let pending = self.occupied();
+        (low + pending, high.map(|h| h + pending))
+    }
+}
@@ -25,6 +25,8 @@ pub enum ResponseBody<'c> {
     H2(crate::h2::body::ResponseBody),
     #[cfg(feature = "http3")]
     H3(crate::h3::body::ResponseBody),
+    Throttle(Box<crate::throttle::Throttle<ResponseBody<'c>>>),
+    Progress(Box<crate::progress::Progress<ResponseBody<'c>>>),
     // TODO: add http1 eof resposne body variant.
     #[allow(dead_code)]
     Eof(PhantomData<&'c ()>),
@@ -36,6 +38,14 @@ impl ResponseBody<'_> {
         if let Self::H1(ref mut body) = *self {
             body.conn().destroy_on_drop()
         }
+
+        if let Self::Throttle(ref mut body) = *self {
+            body.get_mut().destroy_on_drop()
+        }
+
+        if let Self::Progress(ref mut body) = *self {
+            body.get_mut().destroy_on_drop()
+        }
     }
 
     pub(crate) fn can_destroy_on_drop(&mut self) -> bool {
@@ -44,10 +54,27 @@ impl ResponseBody<'_> {
             return body.conn().is_destroy_on_drop();
         }
 
+        if let Self::Throttle(ref mut body) = *self {
+            return body.get_mut().can_destroy_on_drop();
+        }
+
+        if let Self::Progress(ref mut body) = *self {
+            return body.get_mut().can_destroy_on_drop();
+        }
+
         false
     }
 }
 
+impl Drop for ResponseBody<'_> {
+    fn drop(&mut self) {
+        #[cfg(feature = "http1")]
+        if let Self::H1(ref mut body) = *self {
+            body.drain_or_destroy();
+        }
+    }
+}
+
 impl fmt::Debug for ResponseBody<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
@@ -57,6 +84,8 @@ impl fmt::Debug for ResponseBody<'_> {
             Self::H2(_) => write!(f, "ResponseBody::H2(..)"),
             #[cfg(feature = "http3")]
             Self::H3(_) => write!(f, "ResponseBody::H3(..)"),
+            Self::Throttle(_) => write!(f, "ResponseBody::Throttle(..)"),
+            Self::Progress(_) => write!(f, "ResponseBody::Progress(..)"),
             Self::Eof(_) => write!(f, "ResponseBody::Eof"),
         }
     }
@@ -73,6 +102,8 @@ impl Stream for ResponseBody<'_> {
             Self::H2(body) => Pin::new(body).poll_next(_cx),
             #[cfg(feature = "http3")]
             Self::H3(body) => Pin::new(body).poll_next(_cx),
+            Self::Throttle(body) => Pin::new(&mut **body).poll_next(_cx),
+            Self::Progress(body) => Pin::new(&mut **body).poll_next(_cx),
             Self::Eof(_) => Poll::Ready(None),
         }
     }
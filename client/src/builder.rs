@@ -1,11 +1,11 @@
 use std::{net::SocketAddr, time::Duration};
 
-use xitca_http::http::version::Version;
+use xitca_http::http::{header::HeaderMap, uri, version::Version};
 
 use crate::{
     client::Client,
     date::DateTimeService,
-    pool::Pool,
+    pool::{HealthConfig, Pool},
     resolver::{Resolve, Resolver},
     timeout::TimeoutConfig,
     tls::connector::{Connector, TlsConnect},
@@ -16,9 +16,17 @@ pub struct ClientBuilder {
     connector: Connector,
     resolver: Resolver,
     pool_capacity: usize,
+    pool_health: Option<HealthConfig>,
+    warm_up_size: usize,
     timeout_config: TimeoutConfig,
     local_addr: Option<SocketAddr>,
     max_http_version: Version,
+    default_headers: HeaderMap,
+    base_url: Option<uri::Uri>,
+    #[cfg(any(feature = "openssl", feature = "rustls"))]
+    tls_config: crate::tls::config::TlsConfig,
+    #[cfg(feature = "http3")]
+    h3_zero_rtt: bool,
 }
 
 impl Default for ClientBuilder {
@@ -33,23 +41,41 @@ impl ClientBuilder {
             connector: Connector::Nop,
             resolver: Resolver::default(),
             pool_capacity: 128,
+            pool_health: None,
+            warm_up_size: 1,
             timeout_config: TimeoutConfig::default(),
             local_addr: None,
             max_http_version: max_http_version(),
+            default_headers: HeaderMap::new(),
+            base_url: None,
+            #[cfg(any(feature = "openssl", feature = "rustls"))]
+            tls_config: crate::tls::config::TlsConfig::new(),
+            #[cfg(feature = "http3")]
+            h3_zero_rtt: false,
         }
     }
 
     #[cfg(feature = "openssl")]
     /// enable openssl as tls connector.
     pub fn openssl(mut self) -> Self {
-        self.connector = Connector::openssl(self.alpn_from_version());
+        self.connector = Connector::openssl(self.alpn_from_version(), &self.tls_config);
         self
     }
 
     #[cfg(feature = "rustls")]
     /// enable rustls as tls connector.
     pub fn rustls(mut self) -> Self {
-        self.connector = Connector::rustls(self.alpn_from_version());
+        self.connector = Connector::rustls(self.alpn_from_version(), &self.tls_config);
+        self
+    }
+
+    #[cfg(any(feature = "openssl", feature = "rustls"))]
+    /// Set configuration of builtin tls connectors. Must be called before [ClientBuilder::openssl]
+    /// or [ClientBuilder::rustls] for the configuration to take effect.
+    ///
+    /// See [TlsConfig](crate::TlsConfig) for detail.
+    pub fn set_tls_config(mut self, config: crate::TlsConfig) -> Self {
+        self.tls_config = config;
         self
     }
 
@@ -141,10 +167,53 @@ impl ClientBuilder {
         self
     }
 
+    /// Enable passive health check of connection pool.
+    ///
+    /// An authority failing to establish new connection(tcp connect, tls handshake etc) for
+    /// `max_failures` times in a row is ejected for `cool_down` duration. Requests need new
+    /// connection to ejected authority fail fast with [Error::HostUnhealthy] in the mean time.
+    /// After cool down one connection attempt is made to probe the authority and success of it
+    /// brings the authority back.
+    ///
+    /// Default to disabled.
+    ///
+    /// # Panics:
+    /// When pass 0 as max failures.
+    ///
+    /// [Error::HostUnhealthy]: crate::error::Error::HostUnhealthy
+    pub fn set_pool_health_check(mut self, max_failures: usize, cool_down: Duration) -> Self {
+        assert_ne!(max_failures, 0);
+        self.pool_health = Some(HealthConfig {
+            max_failures,
+            cool_down,
+        });
+        self
+    }
+
+    /// Set the number of connections established and pooled for each authority when
+    /// calling [Client::warm_up].
+    ///
+    /// Multiplexable connections(http/2 and http/3) would only be established once per authority
+    /// regardless of this setting. The size is capped by the capacity of connection pool.
+    ///
+    /// Default to 1
+    ///
+    /// # Panics:
+    /// When pass 0 as warm up size.
+    pub fn set_warm_up_size(mut self, size: usize) -> Self {
+        assert_ne!(size, 0);
+        self.warm_up_size = size;
+        self
+    }
+
     /// Set max http version client would be used.
     ///
     /// Default to the max version of http feature enabled within Cargo.toml
     ///
+    /// When max version is http/3 new connections to authority advertising http/3 endpoint through
+    /// `Alt-Svc` response header are upgraded to http/3 until the advertisement expires. Failing
+    /// to connect to the endpoint falls back to tcp and forgets the advertisement.
+    ///
     /// # Examples
     /// ```(no_run)
     /// // default max http version would be Version::HTTP_2
@@ -155,6 +224,40 @@ impl ClientBuilder {
         self
     }
 
+    #[cfg(feature = "http3")]
+    /// Enable sending idempotent requests as QUIC 0-RTT data when resuming session with known
+    /// http/3 server.
+    ///
+    /// Non idempotent requests always wait for handshake confirmation. Request sent with 0-RTT
+    /// data rejected by server would fail and can be safely retried.
+    ///
+    /// Default to false.
+    pub fn set_h3_zero_rtt(mut self, enable: bool) -> Self {
+        self.h3_zero_rtt = enable;
+        self
+    }
+
+    /// Set headers added to every request started from [Client]. Typical usage is
+    /// User-Agent, Accept or authorization header shared by all requests.
+    ///
+    /// Default headers are added when a request is constructed and a header name already
+    /// present in the request is not overridden. Per request override or removal can be done
+    /// through [Request::headers_mut](crate::Request::headers_mut).
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Set base url joined with every request uri without scheme and authority.
+    ///
+    /// Path of base url is kept as prefix. For example with base url `https://example.com/v1`
+    /// request to `/users` is sent to `https://example.com/v1/users`. Request uri with scheme is
+    /// not affected. See [expand_path](crate::expand_path) for building request uri from template.
+    pub fn base_url(mut self, url: uri::Uri) -> Self {
+        self.base_url = Some(url);
+        self
+    }
+
     /// Finish the builder and construct [Client] instance.
     pub fn finish(self) -> Client {
         #[cfg(feature = "http3")]
@@ -186,6 +289,7 @@ impl ClientBuilder {
                     .with_no_client_auth();
 
                 crypto.alpn_protocols = vec![b"h3-29".to_vec()];
+                crypto.enable_early_data = self.h3_zero_rtt;
 
                 let config = ClientConfig::new(Arc::new(crypto));
 
@@ -227,6 +331,7 @@ impl ClientBuilder {
                     .with_custom_certificate_verifier(SkipServerVerification::new())
                     .with_no_client_auth();
                 crypto.alpn_protocols = vec![b"h3-29".to_vec()];
+                crypto.enable_early_data = self.h3_zero_rtt;
 
                 let config = ClientConfig::new(Arc::new(crypto));
 
@@ -241,24 +346,32 @@ impl ClientBuilder {
             };
 
             Client {
-                pool: Pool::with_capacity(self.pool_capacity),
+                pool: Pool::with_capacity(self.pool_capacity, self.pool_health),
+                warm_up_size: self.warm_up_size.min(self.pool_capacity),
                 connector: self.connector,
                 resolver: self.resolver,
                 timeout_config: self.timeout_config,
                 max_http_version: self.max_http_version,
+                default_headers: self.default_headers,
+                base_url: self.base_url,
                 local_addr: self.local_addr,
                 date_service: DateTimeService::new(),
                 h3_client,
+                h3_zero_rtt: self.h3_zero_rtt,
+                alt_svc: crate::alt_svc::AltSvc::new(),
             }
         }
 
         #[cfg(not(feature = "http3"))]
         Client {
-            pool: Pool::with_capacity(self.pool_capacity),
+            pool: Pool::with_capacity(self.pool_capacity, self.pool_health),
+            warm_up_size: self.warm_up_size.min(self.pool_capacity),
             connector: self.connector,
             resolver: self.resolver,
             timeout_config: self.timeout_config,
             max_http_version: self.max_http_version,
+            default_headers: self.default_headers,
+            base_url: self.base_url,
             local_addr: self.local_addr,
             date_service: DateTimeService::new(),
         }
@@ -7,20 +7,21 @@ use tokio::{
 };
 
 use crate::{
+    batch::SendAll,
     body::{BodyError, NoneBody},
     builder::ClientBuilder,
     bytes::Bytes,
     connect::Connect,
-    connection::{Connection, ConnectionKey},
+    connection::{Connection, ConnectionKey, Multiplex},
     date::DateTimeService,
     error::{Error, TimeoutError},
-    http::{self, uri, Method, Version},
+    http::{self, header::HeaderMap, uri, Method, Version},
     pool::Pool,
     request::Request,
     resolver::Resolver,
     timeout::{Timeout, TimeoutConfig},
     tls::connector::Connector,
-    uri::Uri,
+    uri::{expand_path, join_base, Uri},
 };
 
 /// http client type used for sending [Request] and receive [Response].
@@ -28,14 +29,21 @@ use crate::{
 /// [Response]: crate::response::Response
 pub struct Client {
     pub(crate) pool: Pool<ConnectionKey, Connection>,
+    pub(crate) warm_up_size: usize,
     pub(crate) connector: Connector,
     pub(crate) resolver: Resolver,
     pub(crate) timeout_config: TimeoutConfig,
     pub(crate) max_http_version: Version,
+    pub(crate) default_headers: HeaderMap,
+    pub(crate) base_url: Option<uri::Uri>,
     pub(crate) local_addr: Option<SocketAddr>,
     pub(crate) date_service: DateTimeService,
     #[cfg(feature = "http3")]
     pub(crate) h3_client: h3_quinn::quinn::Endpoint,
+    #[cfg(feature = "http3")]
+    pub(crate) h3_zero_rtt: bool,
+    #[cfg(feature = "http3")]
+    pub(crate) alt_svc: crate::alt_svc::AltSvc,
 }
 
 impl Default for Client {
@@ -74,6 +82,7 @@ impl Client {
         Error: From<<uri::Uri as TryFrom<U>>::Error>,
     {
         let uri = uri::Uri::try_from(url)?;
+        let uri = join_base(self.base_url.as_ref(), uri)?;
 
         let mut req = http::Request::new(Default::default());
         *req.uri_mut() = uri;
@@ -152,8 +161,144 @@ impl Client {
         Ok(self.get(url)?.method(Method::CONNECT))
     }
 
+    /// Start a new GET request with uri expanded from template and empty request body.
+    ///
+    /// See [expand_path] for template syntax and [ClientBuilder::base_url] for joining the
+    /// expanded uri with base url. For requests with other methods pass [expand_path] output to
+    /// [Client::post] and the like.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_client::{error::Error, http::Uri, Client};
+    /// # fn get_path() -> Result<(), Error> {
+    /// let client = Client::builder()
+    ///     .base_url(Uri::from_static("https://api.example.com/v1"))
+    ///     .finish();
+    ///
+    /// // request is sent to https://api.example.com/v1/users/42
+    /// let req = client.get_path("/users/{id}", &[("id", "42")])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_path(&self, template: &str, params: &[(&str, &str)]) -> Result<Request<'_, NoneBody<Bytes>>, Error> {
+        self.get(expand_path(template, params)?)
+    }
+
+    /// Establish connections to given uris ahead of time and keep them in connection pool.
+    ///
+    /// DNS resolving, tcp connecting, tls and http/2 handshake happen eagerly so the first requests
+    /// to these authorities can skip them. See [ClientBuilder::set_warm_up_size] for the number of
+    /// connections established per authority.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_client::{error::Error, Client};
+    /// # async fn warm_up() -> Result<(), Error> {
+    /// let client = Client::builder().set_warm_up_size(4).finish();
+    ///
+    /// client.warm_up(&["http://localhost:8080", "http://127.0.0.1:8081"]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn warm_up<U>(&self, uris: &[U]) -> Result<(), Error>
+    where
+        U: Clone,
+        uri::Uri: TryFrom<U>,
+        Error: From<<uri::Uri as TryFrom<U>>::Error>,
+    {
+        for u in uris {
+            let u = uri::Uri::try_from(u.clone())?;
+            let uri = Uri::try_parse(&u)?;
+
+            let mut timer = Box::pin(tokio::time::sleep(self.timeout_config.resolve_timeout));
+
+            // hold all acquired connections until the end so each iteration gets a distinct one.
+            let mut conns = Vec::with_capacity(self.warm_up_size);
+
+            for _ in 0..self.warm_up_size {
+                let mut conn = self.pool.acquire(&uri).await?;
+
+                if conn.is_none() {
+                    conn.check_health()?;
+                    timer
+                        .as_mut()
+                        .reset(Instant::now() + self.timeout_config.resolve_timeout);
+                    let mut connect = Connect::new(uri.clone());
+                    let c = self
+                        .make_connection(&mut connect, &mut timer, self.max_http_version, &self.resolver)
+                        .await;
+                    conn.report_connect(&c);
+                    conn.add(c?);
+                }
+
+                let multiplexable = conn.is_multiplexable();
+                conns.push(conn);
+
+                // one connection is enough for multiplexing.
+                if multiplexable {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send a batch of requests concurrently with at most `max_concurrency` of them in flight.
+    ///
+    /// Returned [SendAll] stream yields index of request in batch together with its result in the
+    /// order requests finish. Call [SendAll::ordered] to yield them in the order of batch.
+    ///
+    /// # Panics:
+    /// When pass 0 as `max_concurrency`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use core::future::poll_fn;
+    /// # use core::pin::pin;
+    /// # use futures_core::Stream;
+    /// # use xitca_client::{error::Error, Client};
+    /// # async fn send_all() -> Result<(), Error> {
+    /// let client = Client::new();
+    ///
+    /// let reqs = ["http://localhost:8080/1", "http://localhost:8080/2", "http://localhost:8080/3"]
+    ///     .into_iter()
+    ///     .map(|url| client.get(url))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// let mut stream = pin!(client.send_all(reqs, 2).ordered());
+    ///
+    /// while let Some((idx, res)) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+    ///     println!("request {idx} responded with {}", res?.status());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn send_all<'a, I, B, E>(&'a self, requests: I, max_concurrency: usize) -> SendAll<'a, I::IntoIter>
+    where
+        I: IntoIterator<Item = Request<'a, B>>,
+        B: Stream<Item = Result<Bytes, E>> + 'a,
+        E: 'a,
+        BodyError: From<E>,
+    {
+        SendAll::new(requests.into_iter(), max_concurrency)
+    }
+
+    #[cfg(feature = "http3")]
+    /// Migrate http/3 connections to a new local udp socket. e.g. when client switches network.
+    ///
+    /// Existing QUIC connections continue on the new network path without new handshakes and
+    /// following connections are established from the new socket.
+    pub fn rebind_h3(&self, socket: std::net::UdpSocket) -> std::io::Result<()> {
+        self.h3_client.rebind(socket)
+    }
+
     #[cfg(feature = "websocket")]
     /// Start a new websocket request.
+    ///
+    /// When `http2` feature is enabled and a pooled http/2 connection to the same authority
+    /// exists the websocket is tunneled through it with extended CONNECT method (RFC 8441) if
+    /// server advertises support of it. Otherwise a dedicated http/1 connection is used.
     pub fn ws(&self, url: &str) -> Result<crate::ws::WsRequest<'_, NoneBody<Bytes>>, Error> {
         self._ws(url, Version::HTTP_11)
     }
@@ -178,10 +323,11 @@ impl Client {
         connect: &mut Connect<'_>,
         timer: &mut Pin<Box<Sleep>>,
         max_version: Version,
+        resolver: &Resolver,
     ) -> Result<Connection, Error> {
         match connect.uri {
             Uri::Tcp(_) => {
-                self.resolver
+                resolver
                     .resolve(connect)
                     .timeout(timer.as_mut())
                     .await
@@ -190,17 +336,29 @@ impl Client {
                 self.make_tcp(connect, timer).await.map(Into::into)
             }
             Uri::Tls(_) => {
-                self.resolver
+                resolver
                     .resolve(connect)
                     .timeout(timer.as_mut())
                     .await
                     .map_err(|_| TimeoutError::Resolve)??;
 
                 #[cfg(feature = "http3")]
-                // TODO: find better way to discover http3.
-                if max_version == Version::HTTP_3 {
-                    if let Ok(conn) = self.make_h3(connect, timer).await {
-                        return Ok(conn);
+                {
+                    // http3 is used when requested explicitly or advertised by server through
+                    // Alt-Svc header of previous responses.
+                    let authority = connect.uri.authority().unwrap();
+                    let alt_port = match self.max_http_version {
+                        Version::HTTP_3 => self.alt_svc.get(authority),
+                        _ => None,
+                    };
+
+                    if max_version == Version::HTTP_3 || alt_port.is_some() {
+                        match self.make_h3(connect, timer, alt_port).await {
+                            Ok(conn) => return Ok(conn),
+                            // forget broken alternative so following connections skip it.
+                            Err(_) if alt_port.is_some() => self.alt_svc.remove(authority),
+                            Err(_) => {}
+                        }
                     }
                 }
                 // Fallback to tcp if http3 failed.
@@ -317,13 +475,18 @@ impl Client {
     }
 
     #[cfg(feature = "http3")]
-    async fn make_h3(&self, connect: &Connect<'_>, timer: &mut Pin<Box<Sleep>>) -> Result<Connection, Error> {
+    async fn make_h3(
+        &self,
+        connect: &Connect<'_>,
+        timer: &mut Pin<Box<Sleep>>,
+        port: Option<u16>,
+    ) -> Result<Connection, Error> {
         timer
             .as_mut()
             .reset(Instant::now() + self.timeout_config.connect_timeout);
 
         let stream = self
-            .make_h3_inner(connect)
+            .make_h3_inner(connect, port)
             .timeout(timer.as_mut())
             .await
             .map_err(|_| TimeoutError::Connect)??;
@@ -332,7 +495,7 @@ impl Client {
     }
 
     #[cfg(feature = "http3")]
-    async fn make_h3_inner(&self, connect: &Connect<'_>) -> Result<Connection, Error> {
+    async fn make_h3_inner(&self, connect: &Connect<'_>, port: Option<u16>) -> Result<Connection, Error> {
         let mut iter = connect.addrs();
 
         let mut addr = iter.next().ok_or(Error::Resolve)?;
@@ -340,7 +503,11 @@ impl Client {
         // try to connect with all addresses resolved by dns resolver.
         // return the last error when all are fail to be connected.
         loop {
-            match crate::h3::proto::connect(&self.h3_client, &addr, connect.hostname()).await {
+            // alternative service can listen on a different port.
+            if let Some(port) = port {
+                addr.set_port(port);
+            }
+            match crate::h3::proto::connect(&self.h3_client, &addr, connect.hostname(), connect.zero_rtt).await {
                 Ok(connection) => return Ok(connection.into()),
                 Err(e) => match iter.next() {
                     Some(a) => addr = a,
@@ -0,0 +1,136 @@
+//! helpers for conditional request and revalidation of stored response.
+
+use std::time::SystemTime;
+
+use crate::http::header::{
+    HeaderMap, HeaderName, HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG,
+    LAST_MODIFIED, TRANSFER_ENCODING,
+};
+
+/// Validators of a stored response used for revalidating it with conditional request.
+///
+/// # Examples:
+/// ```rust
+/// # async fn revalidate(client: &xitca_client::Client, mut stored: xitca_client::http::HeaderMap) -> Result<(), xitca_client::error::Error> {
+/// use xitca_client::{merge_not_modified, Validators};
+///
+/// let validators = Validators::from_headers(&stored);
+/// let res = client.get("https://example.com/feed")?.revalidate(&validators).send().await?;
+///
+/// if res.is_not_modified() {
+///     // stored response is still fresh. refresh it's headers with the ones from server.
+///     merge_not_modified(&mut stored, res.headers());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Validators {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+}
+
+impl Validators {
+    /// Collect `ETag` and `Last-Modified` validators from response headers.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            etag: headers.get(ETAG).cloned(),
+            last_modified: headers.get(LAST_MODIFIED).cloned(),
+        }
+    }
+
+    /// Entity tag of stored response.
+    pub fn etag(&self) -> Option<&HeaderValue> {
+        self.etag.as_ref()
+    }
+
+    /// Last modification time of stored response. None when absent or not a valid http date.
+    pub fn last_modified(&self) -> Option<SystemTime> {
+        self.last_modified
+            .as_ref()
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| httpdate::parse_http_date(v).ok())
+    }
+
+    /// Raw `Last-Modified` header value of stored response.
+    pub(crate) fn last_modified_raw(&self) -> Option<&HeaderValue> {
+        self.last_modified.as_ref()
+    }
+
+    /// Returns true when stored response carries no validator and can not be revalidated.
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Merge headers of `304 Not Modified` response into headers of stored response it revalidated.
+///
+/// Header fields from the 304 response replace the fields with the same name in stored headers.
+/// Fields describing the representation body are kept as is because a 304 response has no body.
+pub fn merge_not_modified(stored: &mut HeaderMap, not_modified: &HeaderMap) {
+    const KEEP: [HeaderName; 5] = [
+        CONTENT_LENGTH,
+        CONTENT_TYPE,
+        CONTENT_ENCODING,
+        CONTENT_RANGE,
+        TRANSFER_ENCODING,
+    ];
+
+    for name in not_modified.keys() {
+        if KEEP.contains(name) {
+            continue;
+        }
+        stored.remove(name);
+        for value in not_modified.get_all(name) {
+            stored.append(name.clone(), value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::http::header::{CACHE_CONTROL, DATE};
+
+    use super::*;
+
+    #[test]
+    fn validators() {
+        let mut headers = HeaderMap::new();
+        assert!(Validators::from_headers(&headers).is_empty());
+
+        headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        headers.insert(LAST_MODIFIED, HeaderValue::from_static("Thu, 01 Jan 1970 00:00:10 GMT"));
+
+        let validators = Validators::from_headers(&headers);
+        assert_eq!(validators.etag().unwrap(), "\"v1\"");
+        assert_eq!(
+            validators.last_modified().unwrap(),
+            UNIX_EPOCH + Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let mut stored = HeaderMap::new();
+        stored.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        stored.insert(CONTENT_LENGTH, HeaderValue::from_static("5"));
+        stored.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        stored.append(CACHE_CONTROL, HeaderValue::from_static("max-age=10"));
+        stored.append(CACHE_CONTROL, HeaderValue::from_static("public"));
+
+        let mut not_modified = HeaderMap::new();
+        not_modified.insert(CONTENT_LENGTH, HeaderValue::from_static("0"));
+        not_modified.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+        not_modified.insert(DATE, HeaderValue::from_static("Thu, 01 Jan 1970 00:00:10 GMT"));
+
+        merge_not_modified(&mut stored, &not_modified);
+
+        assert_eq!(stored.get(CONTENT_TYPE).unwrap(), "text/plain");
+        assert_eq!(stored.get(CONTENT_LENGTH).unwrap(), "5");
+        assert_eq!(stored.get(ETAG).unwrap(), "\"v1\"");
+        assert_eq!(stored.get_all(CACHE_CONTROL).iter().collect::<Vec<_>>(), ["max-age=60"]);
+        assert_eq!(stored.get(DATE).unwrap(), "Thu, 01 Jan 1970 00:00:10 GMT");
+    }
+}
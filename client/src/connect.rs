@@ -82,6 +82,8 @@ pub struct Connect<'a> {
     pub(crate) uri: Uri<'a>,
     pub(crate) port: u16,
     pub(crate) addr: Addrs,
+    #[cfg(feature = "http3")]
+    pub(crate) zero_rtt: bool,
 }
 
 impl<'a> Connect<'a> {
@@ -93,6 +95,8 @@ impl<'a> Connect<'a> {
             uri,
             port: port.unwrap_or(0),
             addr: Addrs::None,
+            #[cfg(feature = "http3")]
+            zero_rtt: false,
         }
     }
 
@@ -11,9 +11,12 @@ pub enum Error {
     Std(Box<dyn error::Error + Send + Sync>),
     InvalidUri(InvalidUri),
     Resolve,
+    HostUnhealthy,
     Timeout(TimeoutError),
     TlsNotEnabled,
     Body(BodyError),
+    /// request body is too large to be buffered for sending more than once.
+    NotReplayable,
     #[cfg(feature = "http1")]
     H1(crate::h1::Error),
     #[cfg(feature = "http2")]
@@ -25,6 +28,7 @@ pub enum Error {
     #[cfg(feature = "rustls")]
     Rustls(_rustls::RustlsError),
     Parse(ParseError),
+    Sse(crate::sse::SseError),
 }
 
 impl fmt::Display for Error {
@@ -35,6 +39,31 @@ impl fmt::Display for Error {
 
 impl error::Error for Error {}
 
+impl Error {
+    /// Check if request failed with error was not processed by server and can be retried safely.
+    /// e.g. idempotent http/3 request sent as QUIC 0-RTT data and rejected by server.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "http3")]
+            Self::H3(e) => e.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Error is caused by failing to connect to remote authority rather than local configuration
+    /// or dns resolving.
+    pub(crate) fn is_connect_failure(&self) -> bool {
+        !matches!(
+            self,
+            Self::InvalidUri(_)
+                | Self::Resolve
+                | Self::HostUnhealthy
+                | Self::Timeout(TimeoutError::Resolve)
+                | Self::TlsNotEnabled
+        )
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(e: io::Error) -> Self {
         Self::Io(e)
@@ -72,6 +101,8 @@ pub enum InvalidUri {
     MissingAuthority,
     MissingPathQuery,
     UnknownScheme,
+    /// uri template is malformed or parameter of it's placeholder is absent.
+    Template,
     Other(uri::InvalidUri),
 }
 
@@ -2,7 +2,7 @@ use std::{
     io,
     ops::DerefMut,
     pin::Pin,
-    task::{ready, Context, Poll},
+    task::{ready, Context, Poll, Waker},
 };
 
 use futures_core::stream::Stream;
@@ -13,6 +13,12 @@ use xitca_http::{
     h1::proto::codec::{ChunkResult, TransferCoding},
 };
 
+use crate::connection::ConnectionWithKey;
+
+// max bytes of unread response body that can be drained when body is dropped before reaching the end.
+// connection with more remaining body than this would be destroyed instead of returning to pool.
+const DRAIN_THRESHOLD: usize = 64 * 1024;
+
 pub struct ResponseBody<C> {
     conn: C,
     buf: BytesMut,
@@ -37,6 +43,37 @@ impl<C> ResponseBody<C> {
     }
 }
 
+impl ResponseBody<ConnectionWithKey<'_>> {
+    // drain remaining response body that is immediately available without blocking so the connection
+    // can be reused for following requests. destroy connection on drop when the body can not be
+    // drained completely.
+    pub(crate) fn drain_or_destroy(&mut self) {
+        if self.conn.is_destroy_on_drop() {
+            return;
+        }
+
+        if !self.decoder.is_upgrade() {
+            let mut cx = Context::from_waker(Waker::noop());
+            let mut drained = 0;
+
+            loop {
+                match Pin::new(&mut *self).poll_next(&mut cx) {
+                    Poll::Ready(None) => return,
+                    Poll::Ready(Some(Ok(bytes))) => {
+                        drained += bytes.len();
+                        if drained > DRAIN_THRESHOLD {
+                            break;
+                        }
+                    }
+                    Poll::Ready(Some(Err(_))) | Poll::Pending => break,
+                }
+            }
+        }
+
+        self.conn.destroy_on_drop();
+    }
+}
+
 impl<C> Stream for ResponseBody<C>
 where
     C: DerefMut + Unpin,
@@ -1,6 +1,6 @@
 use std::{
     pin::Pin,
-    task::{Context, Poll},
+    task::{ready, Context, Poll},
 };
 
 use xitca_http::{bytes::Bytes, error::BodyError, h2::body::RequestBody};
@@ -16,6 +16,7 @@ pub struct ResponseBody {
     // TODO: use new type and import from xitca_http?
     tx: h2::SendStream<Bytes>,
     want_poll_cap: bool,
+    is_eof: bool,
 }
 
 impl ResponseBody {
@@ -24,6 +25,17 @@ impl ResponseBody {
             tx,
             rx,
             want_poll_cap: false,
+            is_eof: false,
+        }
+    }
+}
+
+impl Drop for ResponseBody {
+    fn drop(&mut self) {
+        // cancel the stream when body is dropped before reaching the end so server can stop sending
+        // and the flow control capacity of connection is released.
+        if !self.is_eof {
+            self.tx.send_reset(h2::Reason::CANCEL);
         }
     }
 }
@@ -67,6 +79,11 @@ impl Stream for ResponseBody {
     type Item = Result<Bytes, BodyError>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.get_mut().rx).poll_next(cx)
+        let this = self.get_mut();
+        let res = ready!(Pin::new(&mut this.rx).poll_next(cx));
+        if res.is_none() {
+            this.is_eof = true;
+        }
+        Poll::Ready(res)
     }
 }
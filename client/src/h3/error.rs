@@ -11,6 +11,16 @@ pub enum Error {
     H3(h3::error::Error),
     H3Connect(ConnectError),
     H3Connection(ConnectionError),
+    /// Request sent as QUIC 0-RTT data is rejected by server and not processed. The request is
+    /// idempotent and can be retried safely.
+    ZeroRttRejected,
+}
+
+impl Error {
+    /// Check if request failed with error can be retried safely without being processed twice.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::ZeroRttRejected)
+    }
 }
 
 impl From<h3::error::Error> for Error {
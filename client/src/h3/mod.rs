@@ -7,6 +7,183 @@ pub use error::Error;
 
 use h3::client;
 use h3_quinn::OpenStreams;
+use tokio::sync::watch;
 use xitca_http::bytes::Bytes;
 
-pub type Connection = client::SendRequest<OpenStreams, Bytes>;
+use crate::http::Method;
+
+pub struct Connection {
+    pub(crate) send: client::SendRequest<OpenStreams, Bytes>,
+    pub(crate) handshake: Handshake,
+}
+
+impl Connection {
+    pub(crate) fn new(send: client::SendRequest<OpenStreams, Bytes>, handshake: Handshake) -> Self {
+        Self { send, handshake }
+    }
+}
+
+/// check if request with given method can be sent as 0-RTT data.
+///
+/// 0-RTT data can be replayed by attacker so only idempotent request is allowed.
+pub(crate) fn zero_rtt_eligible(method: &Method) -> bool {
+    method.is_idempotent()
+}
+
+/// state of QUIC handshake of connection established with 0-RTT.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum HandshakeState {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+// receiver of handshake state when connection is established with 0-RTT.
+// None when handshake is already confirmed.
+pub(crate) struct Handshake(Option<watch::Receiver<HandshakeState>>);
+
+impl Handshake {
+    pub(crate) fn confirmed() -> Self {
+        Self(None)
+    }
+
+    pub(crate) fn zero_rtt(rx: watch::Receiver<HandshakeState>) -> Self {
+        Self(Some(rx))
+    }
+
+    /// prepare sending request with given method. return true when request is sent as 0-RTT data.
+    ///
+    /// non idempotent request waits for handshake confirmation so it's never sent as 0-RTT data.
+    pub(crate) async fn prepare(&mut self, method: &Method) -> bool {
+        if !zero_rtt_eligible(method) {
+            self.confirm().await;
+            return false;
+        }
+
+        match self.0 {
+            Some(ref rx) => *rx.borrow() == HandshakeState::Pending,
+            None => false,
+        }
+    }
+
+    /// map error of request sent with [Handshake::prepare]. request sent as 0-RTT data and
+    /// rejected by server surfaces as [Error::ZeroRttRejected].
+    pub(crate) async fn map_err(&mut self, early: bool, err: Error) -> Error {
+        if early && self.confirm().await == HandshakeState::Rejected {
+            return Error::ZeroRttRejected;
+        }
+        err
+    }
+
+    // wait for handshake confirmation so request can not be replayed through 0-RTT data.
+    async fn confirm(&mut self) -> HandshakeState {
+        let Some(ref mut rx) = self.0 else {
+            return HandshakeState::Accepted;
+        };
+
+        // sender dropped means handshake task is gone together with connection.
+        let state = rx
+            .wait_for(|state| *state != HandshakeState::Pending)
+            .await
+            .map(|state| *state)
+            .unwrap_or(HandshakeState::Accepted);
+
+        // rejected state is kept for requests sent as 0-RTT data before it's known.
+        if state == HandshakeState::Accepted {
+            self.0 = None;
+        }
+
+        state
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{future::Future, pin::pin, task::Poll};
+
+    use std::future::poll_fn;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    // poll future once and return it's output when it's ready.
+    async fn poll_once<F: Future>(fut: F) -> Option<F::Output> {
+        let mut fut = pin!(fut);
+        poll_fn(|cx| match fut.as_mut().poll(cx) {
+            Poll::Ready(res) => Poll::Ready(Some(res)),
+            Poll::Pending => Poll::Ready(None),
+        })
+        .await
+    }
+
+    #[test]
+    fn eligible() {
+        for method in [Method::GET, Method::HEAD, Method::OPTIONS, Method::PUT, Method::DELETE] {
+            assert!(zero_rtt_eligible(&method));
+        }
+
+        for method in [Method::POST, Method::PATCH, Method::CONNECT] {
+            assert!(!zero_rtt_eligible(&method));
+        }
+    }
+
+    #[test]
+    fn non_idempotent_never_early() {
+        let (tx, rx) = watch::channel(HandshakeState::Pending);
+        let mut handshake = Handshake::zero_rtt(rx);
+
+        // idempotent request goes out as 0-RTT data while handshake is pending.
+        assert_eq!(poll_once(handshake.prepare(&Method::GET)).now_or_panic(), Some(true));
+
+        // non idempotent request waits for handshake.
+        assert_eq!(poll_once(handshake.prepare(&Method::POST)).now_or_panic(), None);
+
+        tx.send(HandshakeState::Accepted).unwrap();
+
+        assert!(!handshake.prepare(&Method::POST).now_or_panic());
+        // handshake is confirmed and following requests are not 0-RTT data.
+        assert!(!handshake.prepare(&Method::GET).now_or_panic());
+
+        // connection without 0-RTT.
+        let mut handshake = Handshake::confirmed();
+        assert!(!handshake.prepare(&Method::GET).now_or_panic());
+        assert!(!handshake.prepare(&Method::POST).now_or_panic());
+    }
+
+    #[test]
+    fn rejected_is_retryable() {
+        let (tx, rx) = watch::channel(HandshakeState::Pending);
+        let mut handshake = Handshake::zero_rtt(rx);
+
+        let early = handshake.prepare(&Method::GET).now_or_panic();
+        assert!(early);
+
+        tx.send(HandshakeState::Rejected).unwrap();
+
+        let err = handshake
+            .map_err(early, Error::Std("stream reset".into()))
+            .now_or_panic();
+        assert!(matches!(err, Error::ZeroRttRejected));
+        assert!(err.is_retryable());
+
+        // request sent after handshake is not affected by rejection.
+        let early = handshake.prepare(&Method::GET).now_or_panic();
+        assert!(!early);
+        let err = handshake
+            .map_err(early, Error::Std("stream reset".into()))
+            .now_or_panic();
+        assert!(matches!(err, Error::Std(_)));
+        assert!(!err.is_retryable());
+
+        // error of accepted 0-RTT request is passed through.
+        let (tx, rx) = watch::channel(HandshakeState::Pending);
+        let mut handshake = Handshake::zero_rtt(rx);
+        let early = handshake.prepare(&Method::GET).now_or_panic();
+        tx.send(HandshakeState::Accepted).unwrap();
+        let err = handshake
+            .map_err(early, Error::Std("stream reset".into()))
+            .now_or_panic();
+        assert!(matches!(err, Error::Std(_)));
+    }
+}
@@ -4,13 +4,14 @@ use std::net::SocketAddr;
 
 use ::h3_quinn::quinn::Endpoint;
 use futures_core::stream::Stream;
+use tokio::sync::watch;
 use xitca_http::date::DateTime;
 
 use crate::{
     body::{BodyError, BodySize, ResponseBody},
     bytes::{Buf, Bytes},
     date::DateTimeHandle,
-    h3::{Connection, Error},
+    h3::{Connection, Error, Handshake, HandshakeState},
     http::{
         header::{HeaderValue, CONTENT_LENGTH, DATE},
         Method, Request, Response, Version,
@@ -18,7 +19,7 @@ use crate::{
 };
 
 pub(crate) async fn send<B, E>(
-    stream: &mut Connection,
+    conn: &mut Connection,
     date: DateTimeHandle<'_>,
     mut req: Request<B>,
 ) -> Result<Response<ResponseBody<'static>>, Error>
@@ -60,19 +61,32 @@ where
 
     let is_head_method = *req.method() == Method::HEAD;
 
-    let mut stream = stream.send_request(req).await?;
+    // only idempotent request can be sent as 0-RTT data where it's exposed to replay attack.
+    let early = conn.handshake.prepare(req.method()).await;
 
-    if !is_eof {
-        let mut body = pin!(body);
-        while let Some(bytes) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
-            let bytes = bytes.map_err(BodyError::from)?;
-            stream.send_data(bytes).await?;
+    let res = async {
+        let mut stream = conn.send.send_request(req).await?;
+
+        if !is_eof {
+            let mut body = pin!(body);
+            while let Some(bytes) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+                let bytes = bytes.map_err(BodyError::from)?;
+                stream.send_data(bytes).await?;
+            }
         }
-    }
 
-    stream.finish().await?;
+        stream.finish().await?;
+
+        let res = stream.recv_response().await?;
+
+        Ok::<_, Error>((stream, res))
+    }
+    .await;
 
-    let res = stream.recv_response().await?;
+    let (mut stream, res) = match res {
+        Ok(res) => res,
+        Err(e) => return Err(conn.handshake.map_err(early, e).await),
+    };
 
     let res = if is_head_method {
         res.map(|_| ResponseBody::Eof(PhantomData))
@@ -90,10 +104,41 @@ where
     Ok(res)
 }
 
-pub(crate) async fn connect(client: &Endpoint, addr: &SocketAddr, hostname: &str) -> Result<Connection, Error> {
-    let conn = client.connect(*addr, hostname)?.await?;
+pub(crate) async fn connect(
+    client: &Endpoint,
+    addr: &SocketAddr,
+    hostname: &str,
+    zero_rtt: bool,
+) -> Result<Connection, Error> {
+    let connecting = client.connect(*addr, hostname)?;
+
+    // 0-RTT is only possible when resuming a session with known server.
+    let connecting = if zero_rtt {
+        connecting.into_0rtt()
+    } else {
+        Err(connecting)
+    };
+
+    let (conn, handshake) = match connecting {
+        Ok((conn, accepted)) => {
+            let (tx, rx) = watch::channel(HandshakeState::Pending);
+            tokio::spawn(async move {
+                // rejected 0-RTT data is dropped by server. request sent with it would observe
+                // stream error and surface as retryable error.
+                let state = if accepted.await {
+                    HandshakeState::Accepted
+                } else {
+                    tracing::debug!("http3 0-RTT data rejected by server");
+                    HandshakeState::Rejected
+                };
+                let _ = tx.send(state);
+            });
+            (conn, Handshake::zero_rtt(rx))
+        }
+        Err(connecting) => (connecting.await?, Handshake::confirmed()),
+    };
 
-    let (mut task, conn) = h3::client::new(h3_quinn::Connection::new(conn)).await?;
+    let (mut task, send) = h3::client::new(h3_quinn::Connection::new(conn)).await?;
 
     tokio::spawn(async move {
         poll_fn(|cx| task.poll_close(cx))
@@ -101,5 +146,5 @@ pub(crate) async fn connect(client: &Endpoint, addr: &SocketAddr, hostname: &str
             .expect("http3 connection failed");
     });
 
-    Ok(conn)
+    Ok(Connection::new(send, handshake))
 }
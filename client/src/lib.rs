@@ -1,15 +1,22 @@
 #![forbid(unsafe_code)]
 
+#[cfg(feature = "http3")]
+mod alt_svc;
+mod batch;
 mod body;
 mod builder;
 mod client;
+mod conditional;
 mod connect;
 mod connection;
 mod date;
 mod pool;
+mod progress;
+mod replay;
 mod request;
 mod resolver;
 mod response;
+mod throttle;
 mod timeout;
 mod tls;
 mod uri;
@@ -27,13 +34,23 @@ mod h3;
 pub mod ws;
 
 pub mod error;
+pub mod sse;
 
+pub use self::batch::SendAll;
 pub use self::builder::ClientBuilder;
 pub use self::client::Client;
+pub use self::conditional::{merge_not_modified, Validators};
+pub use self::progress::Progress;
+pub use self::replay::ReplayableBody;
 pub use self::request::Request;
 pub use self::resolver::Resolve;
-pub use self::response::Response;
+pub use self::response::{Response, ResponseBodyStream};
+pub use self::throttle::Throttle;
 pub use self::tls::{connector::TlsConnect, stream::Io};
+pub use self::uri::expand_path;
+
+#[cfg(any(feature = "openssl", feature = "rustls"))]
+pub use self::tls::config::TlsConfig;
 
 // re-export http crate.
 pub use xitca_http::http;
@@ -14,6 +14,25 @@ use crate::{connection::Multiplex, error::Error};
 pub struct Pool<K, C> {
     conns: Mutex<HashMap<K, Value<C>>>,
     permits: Semaphore,
+    health: Option<Health<K>>,
+}
+
+/// Passive health check of authorities. Consecutive connect failures to an authority eject it
+/// from pool for a cool down period and new connections to it fail fast in the mean time.
+struct Health<K> {
+    config: HealthConfig,
+    hosts: Mutex<HashMap<K, HostState>>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct HealthConfig {
+    pub(crate) max_failures: usize,
+    pub(crate) cool_down: Duration,
+}
+
+struct HostState {
+    failures: usize,
+    ejected_until: Option<Instant>,
 }
 
 enum Value<C> {
@@ -26,10 +45,14 @@ where
     K: Eq + Hash + Clone,
     C: Multiplex,
 {
-    pub(crate) fn with_capacity(size: usize) -> Self {
+    pub(crate) fn with_capacity(size: usize, health: Option<HealthConfig>) -> Self {
         Self {
             conns: Mutex::new(HashMap::new()),
             permits: Semaphore::new(size),
+            health: health.map(|config| Health {
+                config,
+                hosts: Mutex::new(HashMap::new()),
+            }),
         }
     }
 
@@ -66,6 +89,22 @@ where
             conn,
             permit,
             destroy_on_drop: false,
+            detached: false,
+        })
+    }
+
+    /// Acquire an empty [Conn] that is not shared with pool. Connection added to it is dropped
+    /// together with it and never affects pooled connections of the same key.
+    pub(crate) async fn acquire_detached(&self, key: impl Into<K>) -> Result<Conn<'_, K, C>, Error> {
+        let permit = self.permits.acquire().await.unwrap();
+
+        Ok(Conn {
+            pool: self,
+            key: key.into(),
+            conn: None,
+            permit,
+            destroy_on_drop: false,
+            detached: true,
         })
     }
 }
@@ -80,6 +119,7 @@ where
     conn: Option<PooledConn<C>>,
     permit: SemaphorePermit<'a>,
     destroy_on_drop: bool,
+    detached: bool,
 }
 
 impl<K, C> Deref for Conn<'_, K, C>
@@ -126,6 +166,58 @@ where
         });
     }
 
+    /// Fail fast when authority of connection is ejected from pool. After cool down period one
+    /// connect attempt is let through to probe the authority.
+    pub(crate) fn check_health(&self) -> Result<(), Error> {
+        let Some(ref health) = self.pool.health else {
+            return Ok(());
+        };
+
+        if self.detached {
+            return Ok(());
+        }
+
+        let mut hosts = health.hosts.lock().unwrap();
+        match hosts.get_mut(&self.key).and_then(|state| state.ejected_until.as_mut()) {
+            Some(until) if Instant::now() < *until => Err(Error::HostUnhealthy),
+            Some(until) => {
+                // half open. concurrent requests keep failing fast until the probe is finished.
+                *until = Instant::now() + health.config.cool_down;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Report outcome of establishing new connection for authority of connection.
+    pub(crate) fn report_connect<T>(&self, res: &Result<T, Error>) {
+        let Some(ref health) = self.pool.health else {
+            return;
+        };
+
+        if self.detached {
+            return;
+        }
+
+        let mut hosts = health.hosts.lock().unwrap();
+        match res {
+            Ok(_) => {
+                hosts.remove(&self.key);
+            }
+            Err(e) if e.is_connect_failure() => {
+                let state = hosts.entry(self.key.clone()).or_insert(HostState {
+                    failures: 0,
+                    ejected_until: None,
+                });
+                state.failures += 1;
+                if state.failures >= health.config.max_failures {
+                    state.ejected_until = Some(Instant::now() + health.config.cool_down);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
     #[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
     pub(crate) fn destroy_on_drop(&mut self) {
         self.destroy_on_drop = true;
@@ -133,7 +225,7 @@ where
 
     #[cfg(feature = "http1")]
     pub(crate) fn is_destroy_on_drop(&self) -> bool {
-        self.destroy_on_drop
+        self.destroy_on_drop || self.detached
     }
 }
 
@@ -144,6 +236,10 @@ where
 {
     fn drop(&mut self) {
         if let Some(mut conn) = self.conn.take() {
+            if self.detached {
+                return;
+            }
+
             let want_drop = conn.state.is_expired() || self.destroy_on_drop;
             let mut conns = self.pool.conns.lock().unwrap();
             match conns.get_mut(&self.key) {
@@ -226,3 +322,68 @@ impl<C: Multiplex> Multiplex for PooledConn<C> {
         self.conn.is_multiplexable()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::error::TimeoutError;
+
+    use super::*;
+
+    struct Dummy;
+
+    impl Multiplex for Dummy {
+        fn multiplex(&mut self) -> Self {
+            unimplemented!()
+        }
+
+        fn is_multiplexable(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn health_check() {
+        let pool = Pool::<&str, Dummy>::with_capacity(
+            8,
+            Some(HealthConfig {
+                max_failures: 2,
+                cool_down: Duration::from_millis(50),
+            }),
+        );
+
+        let fail = Err::<(), _>(Error::Timeout(TimeoutError::Connect));
+
+        let conn = pool.acquire("a").now_or_panic().unwrap();
+        conn.check_health().unwrap();
+        conn.report_connect(&fail);
+        conn.check_health().unwrap();
+
+        // resolve error is not counted as connect failure.
+        conn.report_connect(&Err::<(), _>(Error::Resolve));
+        conn.check_health().unwrap();
+
+        conn.report_connect(&fail);
+        assert!(matches!(conn.check_health(), Err(Error::HostUnhealthy)));
+
+        // other authority and detached connection are not affected.
+        pool.acquire("b").now_or_panic().unwrap().check_health().unwrap();
+        pool.acquire_detached("a")
+            .now_or_panic()
+            .unwrap()
+            .check_health()
+            .unwrap();
+        drop(conn);
+
+        std::thread::sleep(Duration::from_millis(60));
+
+        // one probe is let through after cool down.
+        let conn = pool.acquire("a").now_or_panic().unwrap();
+        conn.check_health().unwrap();
+        assert!(matches!(conn.check_health(), Err(Error::HostUnhealthy)));
+
+        conn.report_connect(&Ok(()));
+        conn.check_health().unwrap();
+    }
+}
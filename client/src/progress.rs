@@ -0,0 +1,107 @@
+use core::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+
+use crate::bytes::Bytes;
+
+pub(crate) type Observer = Box<dyn FnMut(u64, Option<u64>) + Send>;
+
+pin_project! {
+    /// Observing layer over body stream that report transferred bytes to a callback.
+    ///
+    /// The callback is called after every chunk with cumulative byte count and optional total
+    /// byte count of the body.
+    pub struct Progress<B> {
+        #[pin]
+        body: B,
+        transferred: u64,
+        total: Option<u64>,
+        observer: Observer,
+    }
+}
+
+impl<B> Progress<B> {
+    pub(crate) fn new(body: B, total: Option<u64>, observer: Observer) -> Self {
+        Self {
+            body,
+            transferred: 0,
+            total,
+            observer,
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut B {
+        &mut self.body
+    }
+}
+
+impl<B, E> Stream for Progress<B>
+where
+    B: Stream<Item = Result<Bytes, E>>,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        let res = ready!(this.body.poll_next(cx));
+
+        if let Some(Ok(ref bytes)) = res {
+            *this.transferred += bytes.len() as u64;
+            (this.observer)(*this.transferred, *this.total);
+        }
+
+        Poll::Ready(res)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.body.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{convert::Infallible, future::poll_fn};
+
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    struct Chunks(usize);
+
+    impl Stream for Chunks {
+        type Item = Result<Bytes, Infallible>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            if self.0 == 0 {
+                return Poll::Ready(None);
+            }
+            self.0 -= 1;
+            Poll::Ready(Some(Ok(Bytes::from_static(&[0; 256]))))
+        }
+    }
+
+    #[tokio::test]
+    async fn progress() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let observer = {
+            let events = events.clone();
+            Box::new(move |n, total| events.lock().unwrap().push((n, total)))
+        };
+
+        let mut body = Box::pin(Progress::new(Chunks(3), Some(768), observer));
+
+        while poll_fn(|cx| body.as_mut().poll_next(cx)).await.is_some() {}
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [(256, Some(768)), (512, Some(768)), (768, Some(768))]
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use core::{
+    future::poll_fn,
+    pin::{pin, Pin},
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{ready, Context, Poll},
+};
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures_core::stream::Stream;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{
+    body::BodyError,
+    bytes::{Bytes, BytesMut},
+    error::Error,
+};
+
+// size of chunk read from spill file.
+const SPILL_CHUNK: usize = 16 * 1024;
+
+/// Request body that can be sent more than once.
+///
+/// Source body is buffered ahead of sending. The first `limit` bytes are kept in memory and the
+/// rest is either rejected with [Error::NotReplayable] or spilled to a temporary file when a spill
+/// directory is given. The temporary file is removed when the last body sharing it is dropped.
+///
+/// Use [ReplayableBody::replay] to get a fresh body starting from the first byte. Replaying is
+/// cheap as buffered data is shared between all replays.
+pub struct ReplayableBody {
+    data: Arc<Data>,
+    state: State,
+}
+
+struct Data {
+    memory: Vec<Bytes>,
+    spill: Option<Spill>,
+    len: u64,
+}
+
+// temporary file removed on drop.
+struct Spill {
+    path: PathBuf,
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+enum State {
+    Memory(usize),
+    File(tokio::fs::File),
+    Done,
+}
+
+impl ReplayableBody {
+    /// Buffer body in memory. Body larger than limit bytes is rejected with [Error::NotReplayable].
+    pub async fn buffer<B, E>(body: B, limit: usize) -> Result<Self, Error>
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+        BodyError: From<E>,
+    {
+        Self::_buffer(body, limit, None).await
+    }
+
+    /// Buffer body in memory up to limit bytes and spill the rest to a temporary file created in
+    /// given directory.
+    pub async fn buffer_with_spill<B, E>(body: B, limit: usize, dir: impl AsRef<Path>) -> Result<Self, Error>
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+        BodyError: From<E>,
+    {
+        Self::_buffer(body, limit, Some(dir.as_ref())).await
+    }
+
+    async fn _buffer<B, E>(body: B, limit: usize, dir: Option<&Path>) -> Result<Self, Error>
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+        BodyError: From<E>,
+    {
+        let mut body = pin!(body);
+
+        let mut memory = Vec::new();
+        let mut spill = None;
+        let mut file = None;
+        let mut len = 0;
+
+        while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            let chunk = chunk.map_err(BodyError::from)?;
+
+            if chunk.is_empty() {
+                continue;
+            }
+
+            len += chunk.len() as u64;
+
+            if file.is_none() && len <= limit as u64 {
+                memory.push(chunk);
+                continue;
+            }
+
+            if file.is_none() {
+                let dir = dir.ok_or(Error::NotReplayable)?;
+                let (s, f) = Spill::create(dir)?;
+                spill = Some(s);
+                file = Some(tokio::fs::File::from_std(f));
+            }
+
+            write_all(file.as_mut().unwrap(), &chunk).await?;
+        }
+
+        if let Some(mut file) = file {
+            poll_fn(|cx| Pin::new(&mut file).poll_flush(cx)).await?;
+        }
+
+        Ok(Self::from_data(Data { memory, spill, len }))
+    }
+
+    fn from_data(data: Data) -> Self {
+        Self {
+            data: Arc::new(data),
+            state: State::Memory(0),
+        }
+    }
+
+    /// Construct a new body of the same content starting from the first byte.
+    pub fn replay(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            state: State::Memory(0),
+        }
+    }
+
+    /// Total length of body in bytes.
+    pub fn len(&self) -> u64 {
+        self.data.len
+    }
+
+    /// Return true when body is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.len == 0
+    }
+
+    /// Return true when part of body is spilled to temporary file.
+    pub fn is_spilled(&self) -> bool {
+        self.data.spill.is_some()
+    }
+}
+
+impl From<Bytes> for ReplayableBody {
+    fn from(bytes: Bytes) -> Self {
+        let len = bytes.len() as u64;
+        let memory = if bytes.is_empty() { Vec::new() } else { vec![bytes] };
+        Self::from_data(Data {
+            memory,
+            spill: None,
+            len,
+        })
+    }
+}
+
+impl Stream for ReplayableBody {
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                State::Memory(ref mut idx) => {
+                    if let Some(chunk) = this.data.memory.get(*idx) {
+                        *idx += 1;
+                        return Poll::Ready(Some(Ok(chunk.clone())));
+                    }
+                    this.state = match this.data.spill {
+                        Some(ref spill) => match fs::File::open(&spill.path) {
+                            Ok(file) => State::File(tokio::fs::File::from_std(file)),
+                            Err(e) => {
+                                this.state = State::Done;
+                                return Poll::Ready(Some(Err(e)));
+                            }
+                        },
+                        None => State::Done,
+                    };
+                }
+                State::File(ref mut file) => {
+                    let mut buf = BytesMut::zeroed(SPILL_CHUNK);
+                    let mut read_buf = ReadBuf::new(&mut buf);
+                    if let Err(e) = ready!(Pin::new(file).poll_read(cx, &mut read_buf)) {
+                        this.state = State::Done;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    let n = read_buf.filled().len();
+                    if n == 0 {
+                        this.state = State::Done;
+                        continue;
+                    }
+                    buf.truncate(n);
+                    return Poll::Ready(Some(Ok(buf.freeze())));
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // only report exact size of fresh body so it can be used as content length.
+        match self.state {
+            State::Memory(0) => {
+                let len = self.data.len as usize;
+                (len, Some(len))
+            }
+            _ => (0, None),
+        }
+    }
+}
+
+impl Spill {
+    fn create(dir: &Path) -> io::Result<(Self, fs::File)> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        loop {
+            let name = format!(
+                "xitca-client-body-{}-{}",
+                std::process::id(),
+                COUNT.fetch_add(1, Ordering::Relaxed)
+            );
+            let path = dir.join(name);
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok((Self { path }, file)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+async fn write_all(file: &mut tokio::fs::File, mut buf: &[u8]) -> io::Result<()> {
+    while !buf.is_empty() {
+        let n = poll_fn(|cx| Pin::new(&mut *file).poll_write(cx, buf)).await?;
+        if n == 0 {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+        buf = &buf[n..];
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::body::Once;
+
+    use super::*;
+
+    struct Chunks(usize);
+
+    impl Stream for Chunks {
+        type Item = Result<Bytes, io::Error>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            if self.0 == 0 {
+                return Poll::Ready(None);
+            }
+            self.0 -= 1;
+            Poll::Ready(Some(Ok(Bytes::from_static(b"996"))))
+        }
+    }
+
+    async fn collect(body: ReplayableBody) -> Vec<u8> {
+        let mut body = pin!(body);
+        let mut buf = Vec::new();
+        while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            buf.extend_from_slice(&chunk.unwrap());
+        }
+        buf
+    }
+
+    #[tokio::test]
+    async fn memory() {
+        let body = ReplayableBody::buffer(Once::new(Bytes::from_static(b"hello")), 5)
+            .await
+            .unwrap();
+        assert_eq!(body.len(), 5);
+        assert_eq!(body.size_hint(), (5, Some(5)));
+
+        let replay = body.replay();
+        assert_eq!(collect(body).await, b"hello");
+        assert_eq!(collect(replay).await, b"hello");
+
+        let err = ReplayableBody::buffer(Chunks(3), 8).await.err().unwrap();
+        assert!(matches!(err, Error::NotReplayable));
+    }
+
+    #[tokio::test]
+    async fn spill() {
+        let body = ReplayableBody::buffer_with_spill(Chunks(4), 6, std::env::temp_dir())
+            .await
+            .unwrap();
+        assert!(body.is_spilled());
+        assert_eq!(body.len(), 12);
+
+        let path = body.data.spill.as_ref().unwrap().path.clone();
+        assert!(path.exists());
+
+        let replay = body.replay();
+        assert_eq!(collect(body).await, b"996996996996");
+        assert_eq!(collect(replay).await, b"996996996996");
+
+        // temporary file is removed with the last body.
+        assert!(!path.exists());
+    }
+}
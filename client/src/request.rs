@@ -1,20 +1,34 @@
-use std::time::Duration;
+use core::future::Future;
+
+use std::{
+    marker::PhantomData,
+    mem,
+    net::SocketAddr,
+    path::Path,
+    time::{Duration, SystemTime},
+};
 
 use futures_core::Stream;
 use tokio::time::Instant;
 
 use crate::{
-    body::{BodyError, Once},
+    body::{BodyError, Once, ResponseBody},
     bytes::Bytes,
     client::Client,
+    conditional::Validators,
     connect::Connect,
     error::Error,
     http::{
         self, const_header_value,
-        header::{HeaderMap, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE},
+        header::{HeaderMap, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, IF_MODIFIED_SINCE, IF_NONE_MATCH},
         Extensions, Method, Version,
     },
+    progress::{Observer, Progress},
+    replay::ReplayableBody,
+    resolver::{Resolve, Resolver},
     response::Response,
+    sse::Source,
+    throttle::Throttle,
     uri::Uri,
 };
 
@@ -30,14 +44,32 @@ pub struct Request<'a, B = Once<Bytes>> {
     /// Request level timeout setting. When Some(Duration) would override
     /// timeout configuration from Client.
     timeout: Duration,
+    /// Optional download rate limit in bytes per second.
+    throttle_download: Option<usize>,
+    /// Request level resolver. When Some(Resolver) would override resolver of Client.
+    resolver: Option<Resolver>,
+    /// Optional observer of response body download progress.
+    download_progress: Option<Observer>,
 }
 
 impl<'a, B> Request<'a, B> {
-    pub(crate) fn new(req: http::Request<B>, client: &'a Client) -> Self {
+    pub(crate) fn new(mut req: http::Request<B>, client: &'a Client) -> Self {
+        let headers = req.headers_mut();
+        for name in client.default_headers.keys() {
+            if !headers.contains_key(name) {
+                for value in client.default_headers.get_all(name) {
+                    headers.append(name, value.clone());
+                }
+            }
+        }
+
         Self {
             req,
             client,
             timeout: client.timeout_config.request_timeout,
+            throttle_download: None,
+            resolver: None,
+            download_progress: None,
         }
     }
 
@@ -92,6 +124,141 @@ impl<'a, B> Request<'a, B> {
         self
     }
 
+    /// Limit upload rate of request body to given bytes per second.
+    ///
+    /// Request body is paced by delaying the polling of it's next chunk and the transfer would take
+    /// longer than usual. Consider increase the timeout with [Request::timeout] accordingly.
+    ///
+    /// # Panics:
+    /// When pass 0 as bytes per second.
+    pub fn throttle_upload<E>(self, bytes_per_sec: usize) -> Request<'a, Throttle<B>>
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+        BodyError: From<E>,
+    {
+        self.map_body(|body| Throttle::new(body, bytes_per_sec))
+    }
+
+    /// Limit download rate of response body to given bytes per second.
+    ///
+    /// Response body is paced by delaying the reading of it's next chunk and collecting it would take
+    /// longer than usual. Consider increase the timeout with [Response::timeout] accordingly.
+    ///
+    /// # Panics:
+    /// When pass 0 as bytes per second.
+    pub fn throttle_download(mut self, bytes_per_sec: usize) -> Self {
+        assert_ne!(bytes_per_sec, 0);
+        self.throttle_download = Some(bytes_per_sec);
+        self
+    }
+
+    /// Observe upload progress of request body.
+    ///
+    /// Callback receives cumulative count of sent bytes and total bytes of request body when it's
+    /// known ahead of time.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_client::{error::Error, Client};
+    /// # async fn upload(client: &Client) -> Result<(), Error> {
+    /// let res = client
+    ///     .post("http://localhost:8080/upload")?
+    ///     .body(vec![0; 1024 * 1024])
+    ///     .on_upload_progress(|sent, total| println!("uploaded {sent} of {total:?} bytes"))
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_upload_progress<F, E>(self, func: F) -> Request<'a, Progress<B>>
+    where
+        F: FnMut(u64, Option<u64>) + Send + 'static,
+        B: Stream<Item = Result<Bytes, E>>,
+        BodyError: From<E>,
+    {
+        self.map_body(|body| {
+            let total = match body.size_hint() {
+                (low, Some(up)) if low == up => Some(up as u64),
+                _ => None,
+            };
+            Progress::new(body, total, Box::new(func))
+        })
+    }
+
+    /// Observe download progress of response body.
+    ///
+    /// Callback receives cumulative count of received bytes and total bytes of response body when
+    /// response comes with `Content-Length` header.
+    pub fn on_download_progress<F>(mut self, func: F) -> Self
+    where
+        F: FnMut(u64, Option<u64>) + Send + 'static,
+    {
+        self.download_progress = Some(Box::new(func));
+        self
+    }
+
+    /// Connect to given address directly and skip DNS resolving of request's hostname.
+    ///
+    /// Hostname is still used for `Host` header and tls server name. Useful for reaching a specific
+    /// backend replica behind a shared hostname. Connection of this request is not shared with
+    /// other requests and is closed after response is dropped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_client::{error::Error, Client};
+    /// # async fn health_check(client: &Client) -> Result<(), Error> {
+    /// let res = client
+    ///     .get("https://api.example.com/health")?
+    ///     .resolve_to(([10, 0, 0, 2], 443))
+    ///     .send()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolve_to(mut self, addr: impl Into<SocketAddr>) -> Self {
+        self.resolver = Some(Resolver::Static(vec![addr.into()]));
+        self
+    }
+
+    /// Use custom DNS resolver for this request. Override [ClientBuilder::resolver].
+    ///
+    /// Connection of this request is not shared with other requests like [Request::resolve_to].
+    ///
+    /// [ClientBuilder::resolver]: crate::builder::ClientBuilder::resolver
+    pub fn resolver(mut self, resolver: impl Resolve + 'static) -> Self {
+        self.resolver = Some(Resolver::custom(resolver));
+        self
+    }
+
+    /// Make request conditional on the representation not matching given entity tag. Server
+    /// responds with `304 Not Modified` when it still matches. See [Response::is_not_modified].
+    pub fn if_none_match(mut self, etag: HeaderValue) -> Self {
+        self.headers_mut().insert(IF_NONE_MATCH, etag);
+        self
+    }
+
+    /// Make request conditional on the representation being modified after given time. Server
+    /// responds with `304 Not Modified` when it's not. See [Response::is_not_modified].
+    pub fn if_modified_since(mut self, time: SystemTime) -> Self {
+        let date = httpdate::fmt_http_date(time);
+        // http date is always a valid header value.
+        self.headers_mut()
+            .insert(IF_MODIFIED_SINCE, HeaderValue::try_from(date).unwrap());
+        self
+    }
+
+    /// Make request conditional on validators of a stored response. `If-None-Match` and
+    /// `If-Modified-Since` headers are set when the corresponding validator is present.
+    pub fn revalidate(mut self, validators: &Validators) -> Self {
+        if let Some(etag) = validators.etag() {
+            self.headers_mut().insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(date) = validators.last_modified_raw() {
+            self.headers_mut().insert(IF_MODIFIED_SINCE, date.clone());
+        }
+        self
+    }
+
     /// Use text(utf-8 encoded) as request body.
     ///
     /// [CONTENT_TYPE] header would be set with value: `text/plain; charset=utf-8`.
@@ -138,24 +305,137 @@ impl<'a, B> Request<'a, B> {
         self.map_body(move |_| body)
     }
 
+    /// Buffer request body so the request can be sent more than once with [Request::replay].
+    ///
+    /// Body larger than limit bytes is rejected with [Error::NotReplayable]. See
+    /// [ReplayableBody] for detail.
+    pub async fn replayable<E>(self, limit: usize) -> Result<Request<'a, ReplayableBody>, Error>
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+        BodyError: From<E>,
+    {
+        self.try_map_body(|body| ReplayableBody::buffer(body, limit)).await
+    }
+
+    /// Same as [Request::replayable] except body exceeding limit bytes is spilled to a temporary
+    /// file created in given directory.
+    pub async fn replayable_with_spill<E>(
+        self,
+        limit: usize,
+        dir: impl AsRef<Path>,
+    ) -> Result<Request<'a, ReplayableBody>, Error>
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+        BodyError: From<E>,
+    {
+        self.try_map_body(|body| ReplayableBody::buffer_with_spill(body, limit, dir))
+            .await
+    }
+
+    async fn try_map_body<F, Fut, B1>(self, f: F) -> Result<Request<'a, B1>, Error>
+    where
+        F: FnOnce(B) -> Fut,
+        Fut: Future<Output = Result<B1, Error>>,
+    {
+        let Self {
+            req,
+            client,
+            timeout,
+            throttle_download,
+            resolver,
+            download_progress,
+        } = self;
+        let (parts, body) = req.into_parts();
+
+        let body = f(body).await?;
+        let req = http::Request::from_parts(parts, body);
+
+        Ok(Request {
+            req,
+            client,
+            timeout,
+            throttle_download,
+            resolver,
+            download_progress,
+        })
+    }
+
     fn map_body<F, B1, E1>(self, f: F) -> Request<'a, B1>
     where
         F: FnOnce(B) -> B1,
         B1: Stream<Item = Result<Bytes, E1>>,
         BodyError: From<E1>,
     {
-        let Self { req, client, timeout } = self;
+        let Self {
+            req,
+            client,
+            timeout,
+            throttle_download,
+            resolver,
+            download_progress,
+        } = self;
         let (parts, body_old) = req.into_parts();
 
         let body = f(body_old);
         let req = http::Request::from_parts(parts, body);
 
-        Request::new(req, client).timeout(timeout)
+        Request {
+            req,
+            client,
+            timeout,
+            throttle_download,
+            resolver,
+            download_progress,
+        }
     }
 
     /// Send the request and wait for response asynchronously.
+    pub async fn send<E>(mut self) -> Result<Response<'a>, Error>
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+        BodyError: From<E>,
+    {
+        let throttle_download = self.throttle_download;
+        let download_progress = self.download_progress.take();
+        let sse = Source::try_new(self.client, &self.req, self.timeout);
+
+        #[cfg(feature = "http3")]
+        let alt_svc = match (self.req.uri().scheme_str(), self.req.uri().authority()) {
+            (Some("https"), Some(authority)) if self.resolver.is_none() => Some((self.client, authority.clone())),
+            _ => None,
+        };
+
+        let mut res = self._send().await?;
+        res.sse = sse;
+
+        #[cfg(feature = "http3")]
+        if let Some((client, authority)) = alt_svc {
+            if res.res.version() != Version::HTTP_3 {
+                client.alt_svc.learn(&authority, res.res.headers());
+            }
+        }
+
+        if let Some(bytes_per_sec) = throttle_download {
+            let body = mem::replace(res.res.body_mut(), ResponseBody::Eof(PhantomData));
+            *res.res.body_mut() = ResponseBody::Throttle(Box::new(Throttle::new(body, bytes_per_sec)));
+        }
+
+        if let Some(observer) = download_progress {
+            let total = res
+                .res
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok());
+            let body = mem::replace(res.res.body_mut(), ResponseBody::Eof(PhantomData));
+            *res.res.body_mut() = ResponseBody::Progress(Box::new(Progress::new(body, total, observer)));
+        }
+
+        Ok(res)
+    }
+
     #[allow(unused_variables, unused_mut)]
-    pub async fn send<E>(self) -> Result<Response<'a>, Error>
+    async fn _send<E>(self) -> Result<Response<'a>, Error>
     where
         B: Stream<Item = Result<Bytes, E>>,
         BodyError: From<E>,
@@ -164,12 +444,35 @@ impl<'a, B> Request<'a, B> {
             mut req,
             client,
             timeout,
+            resolver,
+            ..
         } = self;
 
         let uri = Uri::try_parse(req.uri())?;
 
-        // Try to grab a connection from pool.
-        let mut conn = client.pool.acquire(&uri).await?;
+        // Try to grab a connection from pool. request with it's own resolver can not share
+        // connection with others as it may connect to a different address.
+        let mut conn = match resolver {
+            Some(_) => client.pool.acquire_detached(&uri).await?,
+            None => client.pool.acquire(&uri).await?,
+        };
+
+        // http/1 websocket upgrade can not be sent over pooled http/2 connection as is. tunnel it
+        // through the shared connection with extended CONNECT when server advertises support of
+        // it. otherwise fall back to a dedicated http/1 connection.
+        #[cfg(all(feature = "websocket", feature = "http2"))]
+        let mut ws_tunnel = false;
+        #[cfg(all(feature = "websocket", feature = "http2"))]
+        if !conn.is_none() && crate::ws::is_upgrade(&req) {
+            if let Connection::H2(ref c) = *conn {
+                if c.is_extended_connect_protocol_enabled() {
+                    ws_tunnel = true;
+                } else {
+                    drop(conn);
+                    conn = client.pool.acquire_detached(&uri).await?;
+                }
+            }
+        }
 
         let conn_is_none = conn.is_none();
 
@@ -185,9 +488,23 @@ impl<'a, B> Request<'a, B> {
 
         // Nothing in the pool. construct new connection and add it to Conn.
         if conn_is_none {
+            conn.check_health()?;
             let mut connect = Connect::new(uri);
-            let c = client.make_connection(&mut connect, &mut timer, req.version()).await?;
-            conn.add(c);
+            #[cfg(feature = "http3")]
+            {
+                connect.zero_rtt = client.h3_zero_rtt && crate::h3::zero_rtt_eligible(req.method());
+            }
+            let resolver = resolver.as_ref().unwrap_or(&client.resolver);
+            let c = client
+                .make_connection(&mut connect, &mut timer, req.version(), resolver)
+                .await;
+            conn.report_connect(&c);
+            conn.add(c?);
+        }
+
+        #[cfg(all(feature = "websocket", feature = "http2"))]
+        if ws_tunnel {
+            crate::ws::upgrade_to_h2(&mut req);
         }
 
         let date = client.date_service.handle();
@@ -280,3 +597,131 @@ impl<'a, B> Request<'a, B> {
         }
     }
 }
+
+impl<'a> Request<'a, ReplayableBody> {
+    /// Construct a copy of request with body starting from the first byte. Used for resending
+    /// request on redirect or retry.
+    ///
+    /// Method, uri, version, headers and request level settings are copied. [Extensions] and
+    /// progress observers are not copied.
+    pub fn replay(&self) -> Self {
+        let mut req = http::Request::new(self.req.body().replay());
+        *req.method_mut() = self.req.method().clone();
+        *req.uri_mut() = self.req.uri().clone();
+        *req.version_mut() = self.req.version();
+        *req.headers_mut() = self.req.headers().clone();
+
+        Self {
+            req,
+            client: self.client,
+            timeout: self.timeout,
+            throttle_download: self.throttle_download,
+            resolver: self.resolver.clone(),
+            download_progress: None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "http1"))]
+mod test {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use crate::{body::NoneBody, http::StatusCode};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn resolve_to() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            // request is pinned to listener while hostname is kept.
+            assert!(buf[..n].windows(15).any(|w| w == b"backend.invalid"));
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let client = Client::new();
+        let res = client
+            .get("http://backend.invalid/health")
+            .unwrap()
+            .resolve_to(addr)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn default_headers() {
+        use crate::http::header::{ACCEPT, USER_AGENT};
+
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("xitca"));
+        headers.append(ACCEPT, HeaderValue::from_static("text/html"));
+        headers.append(ACCEPT, HeaderValue::from_static("application/json"));
+
+        let client = Client::builder().default_headers(headers).finish();
+
+        let req = client.get("http://localhost/").unwrap();
+        assert_eq!(req.headers().get(USER_AGENT).unwrap(), "xitca");
+        assert_eq!(req.headers().get_all(ACCEPT).iter().count(), 2);
+
+        // header from request is not overridden by default.
+        let mut req = http::Request::new(NoneBody::<Bytes>::default());
+        req.headers_mut().insert(USER_AGENT, HeaderValue::from_static("custom"));
+        let mut req = client.request(req);
+        assert_eq!(req.headers().get(USER_AGENT).unwrap(), "custom");
+        assert_eq!(req.headers().get_all(ACCEPT).iter().count(), 2);
+
+        req.headers_mut().remove(ACCEPT);
+        assert!(req.headers().get(ACCEPT).is_none());
+    }
+
+    #[tokio::test]
+    async fn conditional() {
+        use crate::http::header::{ETAG, LAST_MODIFIED};
+
+        let client = Client::new();
+
+        let req = client
+            .get("http://localhost/")
+            .unwrap()
+            .if_none_match(HeaderValue::from_static("\"v1\""))
+            .if_modified_since(std::time::UNIX_EPOCH + Duration::from_secs(10));
+        assert_eq!(req.headers().get(IF_NONE_MATCH).unwrap(), "\"v1\"");
+        assert_eq!(
+            req.headers().get(IF_MODIFIED_SINCE).unwrap(),
+            "Thu, 01 Jan 1970 00:00:10 GMT"
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("W/\"v2\""));
+        let req = client
+            .get("http://localhost/")
+            .unwrap()
+            .revalidate(&Validators::from_headers(&headers));
+        assert_eq!(req.headers().get(IF_NONE_MATCH).unwrap(), "W/\"v2\"");
+        assert!(req.headers().get(IF_MODIFIED_SINCE).is_none());
+
+        headers.insert(LAST_MODIFIED, HeaderValue::from_static("Thu, 01 Jan 1970 00:00:10 GMT"));
+        let req = client
+            .get("http://localhost/")
+            .unwrap()
+            .revalidate(&Validators::from_headers(&headers));
+        assert_eq!(
+            req.headers().get(IF_MODIFIED_SINCE).unwrap(),
+            "Thu, 01 Jan 1970 00:00:10 GMT"
+        );
+    }
+}
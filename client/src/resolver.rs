@@ -1,14 +1,19 @@
 use core::future::Future;
 
-use std::net::{SocketAddr, ToSocketAddrs};
+use std::{
+    net::{SocketAddr, ToSocketAddrs},
+    sync::Arc,
+};
 
 use futures_core::future::BoxFuture;
 
 use crate::{connect::Connect, error::Error};
 
+#[derive(Clone)]
 pub(crate) enum Resolver {
     Std,
-    Custom(Box<dyn ResolveDyn>),
+    Static(Vec<SocketAddr>),
+    Custom(Arc<dyn ResolveDyn>),
 }
 
 impl Default for Resolver {
@@ -19,7 +24,7 @@ impl Default for Resolver {
 
 impl Resolver {
     pub(crate) fn custom(resolver: impl Resolve + 'static) -> Self {
-        Self::Custom(Box::new(resolver))
+        Self::Custom(Arc::new(resolver))
     }
 
     pub(crate) async fn resolve(&self, connect: &mut Connect<'_>) -> Result<(), Error> {
@@ -32,6 +37,7 @@ impl Resolver {
                     .await
                     .unwrap()?
             }
+            Self::Static(ref addrs) => addrs.clone().into_iter(),
             Self::Custom(ref resolve) => resolve.resolve_dyn(host, port).await?.into_iter(),
         };
 
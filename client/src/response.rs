@@ -3,16 +3,22 @@ use core::{
     future::poll_fn,
     ops::{Deref, DerefMut},
     pin::{pin, Pin},
+    task::{Context, Poll},
     time::Duration,
 };
 use futures_core::stream::Stream;
 use tokio::time::{Instant, Sleep};
 use tracing::debug;
-use xitca_http::{bytes::BytesMut, http};
+use xitca_http::{
+    bytes::{Bytes, BytesMut},
+    http,
+};
 
 use crate::{
     body::ResponseBody,
+    conditional::Validators,
     error::{Error, TimeoutError},
+    sse::{EventStream, Source},
     timeout::Timeout,
 };
 
@@ -23,6 +29,7 @@ pub struct Response<'a, const PAYLOAD_LIMIT: usize = DEFAULT_PAYLOAD_LIMIT> {
     pub(crate) res: http::Response<ResponseBody<'a>>,
     timer: Pin<Box<Sleep>>,
     timeout: Duration,
+    pub(crate) sse: Option<Box<Source<'a>>>,
 }
 
 impl<'a, const PAYLOAD_LIMIT: usize> Deref for Response<'a, PAYLOAD_LIMIT> {
@@ -48,7 +55,12 @@ impl<const PAYLOAD_LIMIT: usize> fmt::Debug for Response<'_, PAYLOAD_LIMIT> {
 impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
     #[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
     pub(crate) fn new(res: http::Response<ResponseBody<'a>>, timer: Pin<Box<Sleep>>, timeout: Duration) -> Self {
-        Self { res, timer, timeout }
+        Self {
+            res,
+            timer,
+            timeout,
+            sse: None,
+        }
     }
 
     /// Get a reference of the inner response type.
@@ -61,6 +73,19 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
         &mut self.res
     }
 
+    /// Returns true when response is `304 Not Modified` to a conditional request. Stored response
+    /// is still fresh and can be reused. See [merge_not_modified](crate::merge_not_modified).
+    #[inline]
+    pub fn is_not_modified(&self) -> bool {
+        self.res.status() == http::StatusCode::NOT_MODIFIED
+    }
+
+    /// Validators of response for revalidating it with conditional request later.
+    #[inline]
+    pub fn validators(&self) -> Validators {
+        Validators::from_headers(self.res.headers())
+    }
+
     /// Set payload size limit in bytes. Payload size beyond limit would be discarded.
     ///
     /// Default to 8 Mb.
@@ -70,6 +95,7 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
             res: self.res,
             timer: self.timer,
             timeout: self.timeout,
+            sse: self.sse,
         }
     }
 
@@ -83,9 +109,56 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
             res: self.res,
             timer: self.timer,
             timeout: dur,
+            sse: self.sse,
         }
     }
 
+    /// Convert response into a plain [Stream] of it's body. Response is consumed.
+    ///
+    /// Dropping the stream before reaching the end of body is allowed and the underlying connection
+    /// would be recycled or released accordingly. See [ResponseBodyStream] for detail.
+    ///
+    /// Unlike collecting methods like [Response::body] the response timeout is not applied to the
+    /// stream and caller is responsible for handling it's own timeout.
+    #[inline]
+    pub fn into_body_stream(self) -> ResponseBodyStream<'a> {
+        ResponseBodyStream {
+            body: self.res.into_body(),
+        }
+    }
+
+    /// Convert response into a [Stream] of parsed server-sent [Event](crate::sse::Event). Response
+    /// is consumed.
+    ///
+    /// Response to a `GET` request with `Accept: text/event-stream` header reconnects automatically
+    /// when the body ended or failed. See [EventStream] for detail.
+    ///
+    /// Like [Response::into_body_stream] the response timeout is not applied to the stream.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # async fn events(client: &xitca_client::Client) -> Result<(), xitca_client::error::Error> {
+    /// use core::{future::poll_fn, pin::Pin};
+    /// use futures_core::stream::Stream;
+    /// use xitca_client::http::header::{HeaderValue, ACCEPT};
+    ///
+    /// let mut req = client.get("https://example.com/events")?;
+    /// req.headers_mut().insert(ACCEPT, HeaderValue::from_static("text/event-stream"));
+    ///
+    /// let mut stream = req.send().await?.into_sse();
+    ///
+    /// while let Some(event) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+    ///     let event = event?;
+    ///     println!("{}: {}", event.event, event.data);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn into_sse(self) -> EventStream<'a> {
+        EventStream::new(self.res.into_body(), self.sse)
+    }
+
     /// Collect response body as String. Response is consumed.
     #[inline]
     pub async fn string(self) -> Result<String, Error> {
@@ -177,6 +250,36 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
     }
 }
 
+/// Streaming type of response body produced by [Response::into_body_stream].
+///
+/// It's safe to drop the stream before reaching the end of body:
+/// - for http/1 remaining body would be drained when it's small and immediately available so the
+///   connection can be reused. Otherwise the connection is closed.
+/// - for http/2 the stream is reset and the connection is kept alive for other streams.
+pub struct ResponseBodyStream<'a> {
+    body: ResponseBody<'a>,
+}
+
+impl fmt::Debug for ResponseBodyStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.body, f)
+    }
+}
+
+impl Stream for ResponseBodyStream<'_> {
+    type Item = Result<Bytes, Error>;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().body).poll_next(cx).map_err(Into::into)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.body.size_hint()
+    }
+}
+
 trait Collectable {
     fn with_capacity(cap: usize) -> Self;
 
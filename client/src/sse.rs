@@ -0,0 +1,494 @@
+//! server-sent events stream handling.
+
+use core::{
+    fmt,
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use std::error;
+
+use futures_core::stream::Stream;
+use tracing::debug;
+use xitca_http::bytes::{Buf, BytesMut};
+
+use crate::{
+    body::{NoneBody, ResponseBody},
+    bytes::Bytes,
+    client::Client,
+    error::Error,
+    http::{
+        self,
+        header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE},
+        Method, StatusCode,
+    },
+    response::Response,
+};
+
+const LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+
+/// Default delay before reconnecting. Can be changed by server with `retry` field.
+pub const DEFAULT_RETRY: Duration = Duration::from_secs(3);
+
+/// A parsed server-sent event.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Event {
+    /// Last event id seen by the stream when event is dispatched. None when it's empty.
+    pub id: Option<String>,
+    /// Type of event. Default to "message".
+    pub event: String,
+    /// Data of event. Multiple data fields are joined with line feed.
+    pub data: String,
+    /// Reconnection delay carried by event block, if any.
+    pub retry: Option<Duration>,
+}
+
+/// Error type of [EventStream] reconnection.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SseError {
+    /// Server responded to reconnection with status code other than `200 OK`.
+    Status(StatusCode),
+    /// Server responded to reconnection with content type other than `text/event-stream`.
+    ContentType,
+}
+
+impl fmt::Display for SseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Status(status) => write!(f, "event stream responded with unexpected status: {status}"),
+            Self::ContentType => f.write_str("event stream responded with unexpected content type"),
+        }
+    }
+}
+
+impl error::Error for SseError {}
+
+impl From<SseError> for Error {
+    fn from(e: SseError) -> Self {
+        Self::Sse(e)
+    }
+}
+
+// request head retained for reconnecting event stream.
+pub(crate) struct Source<'a> {
+    client: &'a Client,
+    uri: http::Uri,
+    headers: HeaderMap,
+    timeout: Duration,
+}
+
+impl<'a> Source<'a> {
+    // only GET request accepting event stream is retained.
+    pub(crate) fn try_new<B>(client: &'a Client, req: &http::Request<B>, timeout: Duration) -> Option<Box<Self>> {
+        if req.method() != Method::GET || !is_event_stream(req.headers().get(ACCEPT)) {
+            return None;
+        }
+
+        Some(Box::new(Self {
+            client,
+            uri: req.uri().clone(),
+            headers: req.headers().clone(),
+            timeout,
+        }))
+    }
+
+    fn reconnect(&self, last_event_id: &str, delay: Duration) -> ConnectFuture<'a> {
+        let client = self.client;
+        let timeout = self.timeout;
+
+        let mut req = http::Request::new(NoneBody::<Bytes>::default());
+        *req.uri_mut() = self.uri.clone();
+        *req.headers_mut() = self.headers.clone();
+
+        if !last_event_id.is_empty() {
+            // id containing invalid header value bytes is not sent.
+            if let Ok(value) = HeaderValue::from_str(last_event_id) {
+                req.headers_mut().insert(LAST_EVENT_ID, value);
+            }
+        }
+
+        Box::pin(async move {
+            tokio::time::sleep(delay).await;
+            client.request(req).timeout(timeout).send().await
+        })
+    }
+}
+
+fn is_event_stream(value: Option<&HeaderValue>) -> bool {
+    value
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/event-stream"))
+}
+
+type ConnectFuture<'a> = Pin<Box<dyn Future<Output = Result<Response<'a>, Error>> + 'a>>;
+
+/// Stream of [Event] produced by [Response::into_sse].
+///
+/// When the response is for a `GET` request with `Accept: text/event-stream` header the stream
+/// reconnects automatically after the response body ended or failed. Reconnection waits for
+/// the delay given by server with `retry` field([DEFAULT_RETRY] by default) and carries the last
+/// seen event id with `Last-Event-ID` header. Failed reconnection is yielded as error and retried
+/// after the same delay. The stream ends when server responds to reconnection with `204 No
+/// Content` and yields an [SseError] before ending when it responds with other unexpected status
+/// or content type.
+///
+/// Without reconnection the stream ends with the response body.
+pub struct EventStream<'a> {
+    body: Option<ResponseBody<'a>>,
+    parser: Parser,
+    source: Option<Box<Source<'a>>>,
+    connecting: Option<ConnectFuture<'a>>,
+    done: bool,
+}
+
+impl fmt::Debug for EventStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStream")
+            .field("last_event_id", &self.parser.last_event_id)
+            .field("retry", &self.parser.retry)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> EventStream<'a> {
+    pub(crate) fn new(body: ResponseBody<'a>, source: Option<Box<Source<'a>>>) -> Self {
+        Self {
+            body: Some(body),
+            parser: Parser::default(),
+            source,
+            connecting: None,
+            done: false,
+        }
+    }
+
+    /// Last event id seen by the stream.
+    pub fn last_event_id(&self) -> &str {
+        &self.parser.last_event_id
+    }
+
+    // body ended or failed. returns true when stream is reconnecting.
+    fn reconnect(&mut self) -> bool {
+        self.body = None;
+        self.parser.reset();
+        match self.source {
+            Some(ref source) => {
+                self.connecting = Some(source.reconnect(&self.parser.last_event_id, self.parser.retry));
+                true
+            }
+            None => {
+                self.done = true;
+                false
+            }
+        }
+    }
+}
+
+impl Stream for EventStream<'_> {
+    type Item = Result<Event, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            if let Some(event) = this.parser.parse() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            if let Some(fut) = this.connecting.as_mut() {
+                let res = ready!(fut.as_mut().poll(cx));
+                this.connecting = None;
+                match res {
+                    Ok(res) if res.status() == StatusCode::NO_CONTENT => {
+                        this.done = true;
+                    }
+                    Ok(res) if res.status() != StatusCode::OK => {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(SseError::Status(res.status()).into())));
+                    }
+                    Ok(res) if !is_event_stream(res.headers().get(CONTENT_TYPE)) => {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(SseError::ContentType.into())));
+                    }
+                    Ok(res) => this.body = Some(res.res.into_body()),
+                    Err(e) => {
+                        this.reconnect();
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+                continue;
+            }
+
+            let Some(body) = this.body.as_mut() else {
+                return Poll::Ready(None);
+            };
+
+            match ready!(Pin::new(body).poll_next(cx)) {
+                Some(Ok(bytes)) => this.parser.buf.extend_from_slice(&bytes),
+                Some(Err(e)) => {
+                    if !this.reconnect() {
+                        return Poll::Ready(Some(Err(e.into())));
+                    }
+                    debug!("event stream body error: {e:?}. reconnecting");
+                }
+                None => {
+                    this.parser.finish();
+                    this.reconnect();
+                }
+            }
+        }
+    }
+}
+
+struct Parser {
+    buf: BytesMut,
+    bom_checked: bool,
+    // body ended and trailing carriage return can be treated as line end.
+    eof: bool,
+    event: String,
+    data: String,
+    retry_field: Option<Duration>,
+    last_event_id: String,
+    retry: Duration,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self {
+            buf: BytesMut::new(),
+            bom_checked: false,
+            eof: false,
+            event: String::new(),
+            data: String::new(),
+            retry_field: None,
+            last_event_id: String::new(),
+            retry: DEFAULT_RETRY,
+        }
+    }
+}
+
+impl Parser {
+    // discard partial event and prepare for new response body.
+    fn reset(&mut self) {
+        self.buf.clear();
+        self.bom_checked = false;
+        self.eof = false;
+        self.event.clear();
+        self.data.clear();
+        self.retry_field = None;
+    }
+
+    fn finish(&mut self) {
+        self.eof = true;
+    }
+
+    // parse buffered lines until an event is dispatched or more bytes are needed.
+    fn parse(&mut self) -> Option<Event> {
+        if !self.bom_checked {
+            if self.buf.len() < 3 && b"\xEF\xBB\xBF".starts_with(&self.buf) && !self.eof {
+                return None;
+            }
+            if self.buf.starts_with(b"\xEF\xBB\xBF") {
+                self.buf.advance(3);
+            }
+            self.bom_checked = true;
+        }
+
+        loop {
+            let pos = self.buf.iter().position(|b| *b == b'\r' || *b == b'\n')?;
+
+            let len = match self.buf[pos] {
+                // carriage return at the end of buffer may be followed by line feed.
+                b'\r' if pos + 1 == self.buf.len() && !self.eof => return None,
+                b'\r' if self.buf.get(pos + 1) == Some(&b'\n') => 2,
+                _ => 1,
+            };
+
+            let line = self.buf.split_to(pos + len);
+            let line = String::from_utf8_lossy(&line[..pos]);
+
+            if let Some(event) = self.line(&line) {
+                return Some(event);
+            }
+        }
+    }
+
+    fn line(&mut self, line: &str) -> Option<Event> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => value.clone_into(&mut self.event),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => value.clone_into(&mut self.last_event_id),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(millis) = value.parse() {
+                    let dur = Duration::from_millis(millis);
+                    self.retry = dur;
+                    self.retry_field = Some(dur);
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<Event> {
+        let retry = self.retry_field.take();
+
+        if self.data.is_empty() {
+            self.event.clear();
+            return None;
+        }
+
+        let mut data = mem::take(&mut self.data);
+        data.pop();
+
+        let event = match mem::take(&mut self.event) {
+            event if event.is_empty() => String::from("message"),
+            event => event,
+        };
+
+        let id = (!self.last_event_id.is_empty()).then(|| self.last_event_id.clone());
+
+        Some(Event { id, event, data, retry })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse_all(parser: &mut Parser, input: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+        // feed byte by byte to exercise partial line handling.
+        for b in input {
+            parser.buf.extend_from_slice(&[*b]);
+            while let Some(event) = parser.parse() {
+                events.push(event);
+            }
+        }
+        parser.finish();
+        while let Some(event) = parser.parse() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn parse() {
+        let mut parser = Parser::default();
+        let input = b"\xEF\xBB\xBFretry: 10\nid: 1\ndata: a\ndata:b\n\n: comment\r\nevent: ping\r\ndata\r\rid\r\ndata:  c\n\ndata: partial";
+        let events = parse_all(&mut parser, input);
+
+        assert_eq!(
+            events,
+            [
+                Event {
+                    id: Some("1".into()),
+                    event: "message".into(),
+                    data: "a\nb".into(),
+                    retry: Some(Duration::from_millis(10)),
+                },
+                Event {
+                    id: Some("1".into()),
+                    event: "ping".into(),
+                    data: "".into(),
+                    retry: None,
+                },
+                Event {
+                    id: None,
+                    event: "message".into(),
+                    data: " c".into(),
+                    retry: None,
+                },
+            ]
+        );
+        assert_eq!(parser.retry, Duration::from_millis(10));
+        assert!(parser.last_event_id.is_empty());
+    }
+}
+
+#[cfg(all(test, feature = "http1"))]
+mod test_reconnect {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn reconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let responses: [(&str, &str); 3] = [
+                ("", "retry: 10\nid: 1\ndata: a\n\n"),
+                ("last-event-id: 1", "id: 2\r\ndata: b\r\n\r\n"),
+                ("last-event-id: 2", ""),
+            ];
+
+            for (expect, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 1024];
+                let mut n = 0;
+                while !buf[..n].windows(4).any(|w| w == b"\r\n\r\n") {
+                    n += stream.read(&mut buf[n..]).await.unwrap();
+                }
+                let req = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                assert!(req.contains(expect));
+
+                let res = if body.is_empty() {
+                    String::from("HTTP/1.1 204 No Content\r\nconnection: close\r\ncontent-length: 0\r\n\r\n")
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nconnection: close\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\n\r\n{body}",
+                        body.len()
+                    )
+                };
+                stream.write_all(res.as_bytes()).await.unwrap();
+            }
+        });
+
+        let client = Client::new();
+        let mut req = client.get(format!("http://{addr}/events")).unwrap();
+        req.headers_mut()
+            .insert(ACCEPT, HeaderValue::from_static("text/event-stream"));
+
+        let mut stream = req.send().await.unwrap().into_sse();
+
+        let mut events = Vec::new();
+        while let Some(event) = core::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+            events.push(event.unwrap());
+        }
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].data, "a");
+        assert_eq!(events[1].id.as_deref(), Some("2"));
+        assert_eq!(events[1].data, "b");
+        assert_eq!(stream.last_event_id(), "2");
+    }
+}
@@ -0,0 +1,108 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use tokio::time::{sleep, Instant, Sleep};
+
+use crate::bytes::Bytes;
+
+pin_project! {
+    /// Pacing layer over body stream that limit it's throughput to given bytes per second.
+    ///
+    /// Every chunk is yielded as is and the following chunk would be delayed until the average
+    /// rate drops below the limit.
+    pub struct Throttle<B> {
+        #[pin]
+        body: B,
+        bytes_per_sec: usize,
+        deadline: Pin<Box<Sleep>>,
+    }
+}
+
+impl<B> Throttle<B> {
+    /// # Panics:
+    /// When pass 0 as bytes per second.
+    pub(crate) fn new(body: B, bytes_per_sec: usize) -> Self {
+        assert_ne!(bytes_per_sec, 0);
+        Self {
+            body,
+            bytes_per_sec,
+            deadline: Box::pin(sleep(Duration::ZERO)),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut B {
+        &mut self.body
+    }
+}
+
+impl<B, E> Stream for Throttle<B>
+where
+    B: Stream<Item = Result<Bytes, E>>,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        ready!(this.deadline.as_mut().poll(cx));
+
+        let res = ready!(this.body.poll_next(cx));
+
+        if let Some(Ok(ref bytes)) = res {
+            let dur = Duration::from_secs_f64(bytes.len() as f64 / *this.bytes_per_sec as f64);
+            // the deadline is extended from now or previous deadline, whichever is later, so
+            // idle time from slow producer/consumer does not accumulate into a burst.
+            let start = Instant::now().max(this.deadline.deadline());
+            this.deadline.as_mut().reset(start + dur);
+        }
+
+        Poll::Ready(res)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.body.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{convert::Infallible, future::poll_fn};
+
+    use super::*;
+
+    struct Chunks(usize);
+
+    impl Stream for Chunks {
+        type Item = Result<Bytes, Infallible>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            if self.0 == 0 {
+                return Poll::Ready(None);
+            }
+            self.0 -= 1;
+            Poll::Ready(Some(Ok(Bytes::from_static(&[0; 256]))))
+        }
+    }
+
+    #[tokio::test]
+    async fn throttle() {
+        let mut body = Box::pin(Throttle::new(Chunks(3), 1024));
+
+        let now = Instant::now();
+
+        let mut len = 0;
+        while let Some(res) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            len += res.unwrap().len();
+        }
+
+        assert_eq!(len, 768);
+        assert!(now.elapsed() >= Duration::from_millis(750));
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Configuration for builtin openssl and rustls tls connectors.
+///
+/// Applied through [ClientBuilder::set_tls_config](crate::ClientBuilder::set_tls_config) which must
+/// be called before `ClientBuilder::openssl` or `ClientBuilder::rustls`.
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    pub(crate) session_resumption: bool,
+    pub(crate) session_cache_size: usize,
+    pub(crate) max_early_data: u32,
+    pub(crate) cipher_suites: Vec<String>,
+    pub(crate) key_log_file: Option<PathBuf>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TlsConfig {
+    pub const fn new() -> Self {
+        Self {
+            session_resumption: true,
+            session_cache_size: 256,
+            max_early_data: 0,
+            cipher_suites: Vec::new(),
+            key_log_file: None,
+        }
+    }
+
+    /// Enable resumption of tls sessions with session tickets and session ids.
+    ///
+    /// Default to true. Reusing cached sessions is supported by rustls connector. openssl connector
+    /// only stops requesting session tickets when resumption is disabled.
+    pub fn set_session_resumption(mut self, enable: bool) -> Self {
+        self.session_resumption = enable;
+        self
+    }
+
+    /// Set max count of cached sessions for resumption.
+    ///
+    /// Default to 256. Only used by rustls connector.
+    pub fn set_session_cache_size(mut self, size: usize) -> Self {
+        self.session_cache_size = size;
+        self
+    }
+
+    /// Set max size in byte unit of TLS 1.3 early data(0-RTT).
+    ///
+    /// Default to 0 where early data is disabled. rustls connector enables early data for any
+    /// non zero value and the actual limit is decided by server.
+    pub fn set_max_early_data(mut self, size: u32) -> Self {
+        self.max_early_data = size;
+        self
+    }
+
+    /// Set cipher suites in the order of preference. Default to the tls library's own defaults.
+    ///
+    /// rustls connector expects IANA names like `TLS13_AES_128_GCM_SHA256`. openssl connector
+    /// expects openssl names where TLS 1.3 suites start with `TLS_` like `TLS_AES_128_GCM_SHA256`
+    /// and others are TLS 1.2 cipher names like `ECDHE-RSA-AES128-GCM-SHA256`.
+    ///
+    /// # Panics:
+    /// Constructing connector panics when any of given cipher suites is not supported.
+    pub fn set_cipher_suites<I, S>(mut self, suites: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.cipher_suites = suites.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Write tls secrets to given file in NSS key log format. Can be used to decrypt captured
+    /// traffic with tools like wireshark.
+    ///
+    /// *. This is meant for debugging. DO NOT use in production environment.
+    ///
+    /// # Panics:
+    /// Constructing connector panics when the file can not be opened.
+    pub fn set_key_log_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.key_log_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+}
+
+/// Append only key log file shared by all connections of a connector.
+pub(crate) struct KeyLogFile(Mutex<File>);
+
+impl KeyLogFile {
+    pub(crate) fn new(path: &Path) -> Self {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap_or_else(|e| panic!("Can not open key log file: {e:?}"));
+        Self(Mutex::new(file))
+    }
+
+    pub(crate) fn write_line(&self, line: &str) {
+        let mut file = self.0.lock().unwrap();
+        if let Err(e) = writeln!(file, "{line}") {
+            tracing::warn!("failed to write tls key log: {e}");
+        }
+    }
+}
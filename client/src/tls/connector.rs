@@ -24,8 +24,8 @@ impl Default for Connector {
 
 impl Connector {
     #[cfg(feature = "openssl")]
-    pub(crate) fn openssl(protocols: &[&[u8]]) -> Self {
-        use openssl_crate::ssl::{SslConnector, SslMethod};
+    pub(crate) fn openssl(protocols: &[&[u8]], config: &super::config::TlsConfig) -> Self {
+        use openssl_crate::ssl::{SslConnector, SslMethod, SslOptions};
         use tokio_openssl::SslStream;
         use xitca_http::bytes::BufMut;
 
@@ -62,15 +62,48 @@ impl Connector {
         ssl.set_alpn_protos(&alpn)
             .unwrap_or_else(|e| panic!("Can not set ALPN protocol: {e:?}"));
 
+        if !config.session_resumption {
+            ssl.set_options(SslOptions::NO_TICKET);
+        }
+
+        if config.max_early_data > 0 {
+            ssl.set_max_early_data(config.max_early_data)
+                .unwrap_or_else(|e| panic!("Can not set max early data: {e:?}"));
+        }
+
+        if !config.cipher_suites.is_empty() {
+            let (tls13, tls12): (Vec<_>, Vec<_>) = config
+                .cipher_suites
+                .iter()
+                .map(String::as_str)
+                .partition(|s| s.starts_with("TLS_"));
+            if !tls13.is_empty() {
+                ssl.set_ciphersuites(&tls13.join(":"))
+                    .unwrap_or_else(|e| panic!("Can not set cipher suites: {e:?}"));
+            }
+            if !tls12.is_empty() {
+                ssl.set_cipher_list(&tls12.join(":"))
+                    .unwrap_or_else(|e| panic!("Can not set cipher suites: {e:?}"));
+            }
+        }
+
+        if let Some(ref path) = config.key_log_file {
+            let file = super::config::KeyLogFile::new(path);
+            ssl.set_keylog_callback(move |_, line| file.write_line(line));
+        }
+
         Self::custom(ssl.build())
     }
 
     #[cfg(feature = "rustls")]
-    pub(crate) fn rustls(protocols: &[&[u8]]) -> Self {
-        use std::sync::Arc;
+    pub(crate) fn rustls(protocols: &[&[u8]], tls_config: &super::config::TlsConfig) -> Self {
+        use std::{fmt::Write, sync::Arc};
 
         use tokio_rustls::{
-            rustls::{client::ServerName, ClientConfig, OwnedTrustAnchor, RootCertStore},
+            rustls::{
+                client::{Resumption, ServerName},
+                ClientConfig, KeyLog, OwnedTrustAnchor, RootCertStore, ALL_CIPHER_SUITES, DEFAULT_CIPHER_SUITES,
+            },
             TlsConnector,
         };
         use webpki_roots::TLS_SERVER_ROOTS;
@@ -100,13 +133,62 @@ impl Connector {
             root_certs.add_trust_anchors([cert].into_iter());
         }
 
+        let suites = if tls_config.cipher_suites.is_empty() {
+            DEFAULT_CIPHER_SUITES.to_vec()
+        } else {
+            tls_config
+                .cipher_suites
+                .iter()
+                .map(|name| {
+                    ALL_CIPHER_SUITES
+                        .iter()
+                        .find(|suite| suite.suite().as_str() == Some(name.as_str()))
+                        .copied()
+                        .unwrap_or_else(|| panic!("Can not set cipher suite: {name} is not supported"))
+                })
+                .collect()
+        };
+
         let mut config = ClientConfig::builder()
-            .with_safe_defaults()
+            .with_cipher_suites(&suites)
+            .with_safe_default_kx_groups()
+            .with_safe_default_protocol_versions()
+            .unwrap_or_else(|e| panic!("Can not set cipher suites: {e:?}"))
             .with_root_certificates(root_certs)
             .with_no_client_auth();
 
         config.alpn_protocols = protocols.iter().map(|p| p.to_vec()).collect();
 
+        config.resumption = if tls_config.session_resumption {
+            Resumption::in_memory_sessions(tls_config.session_cache_size)
+        } else {
+            Resumption::disabled()
+        };
+
+        config.enable_early_data = tls_config.max_early_data > 0;
+
+        if let Some(ref path) = tls_config.key_log_file {
+            struct KeyLogFile(super::config::KeyLogFile);
+
+            impl KeyLog for KeyLogFile {
+                fn log(&self, label: &str, client_random: &[u8], secret: &[u8]) {
+                    let mut line = String::with_capacity(label.len() + (client_random.len() + secret.len()) * 2 + 2);
+                    line.push_str(label);
+                    line.push(' ');
+                    for b in client_random {
+                        let _ = write!(line, "{b:02x}");
+                    }
+                    line.push(' ');
+                    for b in secret {
+                        let _ = write!(line, "{b:02x}");
+                    }
+                    self.0.write_line(&line);
+                }
+            }
+
+            config.key_log = Arc::new(KeyLogFile(super::config::KeyLogFile::new(path)));
+        }
+
         Self::custom(TlsConnector::from(Arc::new(config)))
     }
 
@@ -182,3 +264,24 @@ where
         Box::pin(self.connect(domain, io))
     }
 }
+
+#[cfg(all(test, feature = "rustls"))]
+mod test {
+    use super::{super::config::TlsConfig, *};
+
+    #[test]
+    fn rustls_config() {
+        let config = TlsConfig::new()
+            .set_session_resumption(false)
+            .set_max_early_data(1024)
+            .set_cipher_suites(["TLS13_CHACHA20_POLY1305_SHA256", "TLS13_AES_128_GCM_SHA256"]);
+        let _ = Connector::rustls(&[b"http/1.1"], &config);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can not set cipher suite")]
+    fn rustls_unknown_cipher_suite() {
+        let config = TlsConfig::new().set_cipher_suites(["TLS_NOT_A_SUITE"]);
+        let _ = Connector::rustls(&[b"http/1.1"], &config);
+    }
+}
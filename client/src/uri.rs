@@ -1,4 +1,4 @@
-use std::ops::Deref;
+use std::{fmt::Write, ops::Deref};
 
 use crate::{error::InvalidUri, http::uri};
 
@@ -40,10 +40,106 @@ impl<'a> Uri<'a> {
     }
 }
 
+/// Expand uri template by replacing every `{name}` placeholder with percent-encoded value of
+/// matching parameter.
+///
+/// All characters other than unreserved ones(`A-Z a-z 0-9 - . _ ~`) of values are encoded so a
+/// value can not escape it's path segment or query parameter. Expanded uri without scheme is
+/// joined with [ClientBuilder::base_url] when used for starting request.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_client::expand_path;
+/// let uri = expand_path("/users/{id}/posts?tag={tag}", &[("id", "42"), ("tag", "a&b c")]).unwrap();
+/// assert_eq!(uri, "/users/42/posts?tag=a%26b%20c");
+/// ```
+///
+/// [ClientBuilder::base_url]: crate::ClientBuilder::base_url
+pub fn expand_path(template: &str, params: &[(&str, &str)]) -> Result<uri::Uri, InvalidUri> {
+    let mut buf = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        buf.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or(InvalidUri::Template)? + start;
+        let name = &rest[start + 1..end];
+        let (_, value) = params.iter().find(|(n, _)| *n == name).ok_or(InvalidUri::Template)?;
+        percent_encode(&mut buf, value);
+        rest = &rest[end + 1..];
+    }
+
+    buf.push_str(rest);
+
+    uri::Uri::try_from(buf).map_err(InvalidUri::from)
+}
+
+fn percent_encode(buf: &mut String, value: &str) {
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => buf.push(b as char),
+            b => {
+                let _ = write!(buf, "%{b:02X}");
+            }
+        }
+    }
+}
+
+// join uri without scheme to base url. path of base is kept as prefix of uri's path.
+pub(crate) fn join_base(base: Option<&uri::Uri>, uri: uri::Uri) -> Result<uri::Uri, InvalidUri> {
+    let base = match base {
+        Some(base) if uri.scheme().is_none() => base,
+        _ => return Ok(uri),
+    };
+
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("");
+
+    let mut parts = base.clone().into_parts();
+    let joined = format!("{}/{}", base.path().trim_end_matches('/'), path.trim_start_matches('/'));
+    parts.path_and_query = Some(joined.parse()?);
+
+    uri::Uri::from_parts(parts).map_err(|_| InvalidUri::MissingPathQuery)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn template() {
+        let uri = expand_path("/v1/users/{id}", &[("id", "42")]).unwrap();
+        assert_eq!(uri, "/v1/users/42");
+
+        let uri = expand_path("/files/{name}?q={q}", &[("name", "a/b"), ("q", "x=1&y")]).unwrap();
+        assert_eq!(uri, "/files/a%2Fb?q=x%3D1%26y");
+
+        let uri = expand_path("/{a}{b}", &[("a", "~"), ("b", "中")]).unwrap();
+        assert_eq!(uri, "/~%E4%B8%AD");
+
+        assert!(matches!(expand_path("/users/{id}", &[]), Err(InvalidUri::Template)));
+        assert!(matches!(
+            expand_path("/users/{id", &[("id", "1")]),
+            Err(InvalidUri::Template)
+        ));
+    }
+
+    #[test]
+    fn join() {
+        let base = uri::Uri::from_static("https://example.com/api/");
+
+        let uri = join_base(Some(&base), uri::Uri::from_static("/users?page=2")).unwrap();
+        assert_eq!(uri, "https://example.com/api/users?page=2");
+
+        let uri = join_base(Some(&base), uri::Uri::from_static("http://other.com/users")).unwrap();
+        assert_eq!(uri, "http://other.com/users");
+
+        let base = uri::Uri::from_static("https://example.com");
+        let uri = join_base(Some(&base), uri::Uri::from_static("/users")).unwrap();
+        assert_eq!(uri, "https://example.com/users");
+
+        let uri = join_base(None, uri::Uri::from_static("/users")).unwrap();
+        assert_eq!(uri, "/users");
+    }
+
     #[test]
     fn uri_parse() {
         let uri = uri::Uri::from_static("http2://example.com");
@@ -82,6 +82,34 @@ impl<'a, B> WsRequest<'a, B> {
     }
 }
 
+// http/1 websocket upgrade request.
+#[cfg(feature = "http2")]
+pub(crate) fn is_upgrade<B>(req: &crate::http::Request<B>) -> bool {
+    use crate::http::header::UPGRADE;
+
+    req.method() == Method::GET
+        && req
+            .headers()
+            .get(UPGRADE)
+            .is_some_and(|v| v.as_bytes().eq_ignore_ascii_case(b"websocket"))
+}
+
+// convert http/1 websocket upgrade request to http/2 extended CONNECT request. (RFC 8441)
+// connection specific headers are removed by http/2 dispatcher.
+#[cfg(feature = "http2")]
+pub(crate) fn upgrade_to_h2<B>(req: &mut crate::http::Request<B>) {
+    use crate::http::{
+        const_header_name::PROTOCOL,
+        header::{HeaderValue, SEC_WEBSOCKET_KEY},
+    };
+
+    *req.method_mut() = Method::CONNECT;
+    *req.version_mut() = Version::HTTP_2;
+    req.headers_mut().remove(SEC_WEBSOCKET_KEY);
+    req.headers_mut()
+        .insert(PROTOCOL, HeaderValue::from_static("websocket"));
+}
+
 /// sender part of websocket connection.
 /// [Sink] trait is used to asynchronously send message.
 pub struct WebSocketSink<'a, 'b>(&'a WebSocket<'b>);
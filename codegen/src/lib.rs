@@ -1,9 +1,30 @@
 use proc_macro::TokenStream;
-use quote::{__private::Span, quote};
+use quote::{__private::Span, quote, quote_spanned};
 use syn::{
-    Data, FnArg, GenericArgument, Ident, ImplItem, ImplItemFn, Pat, PatIdent, PathArguments, ReturnType, Stmt, Type,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+    Data, FnArg, GenericArgument, Ident, ImplItem, ImplItemFn, ItemFn, Lifetime, Pat, PatIdent, PathArguments,
+    ReturnType, Stmt, Type, TypeReference,
 };
 
+mod query;
+mod sql;
+
+#[proc_macro_derive(ToSql, attributes(postgres))]
+pub fn to_sql_impl(item: TokenStream) -> TokenStream {
+    sql::to_sql(item)
+}
+
+#[proc_macro_derive(FromSqlExt, attributes(postgres))]
+pub fn from_sql_ext_impl(item: TokenStream) -> TokenStream {
+    sql::from_sql_ext(item)
+}
+
+#[proc_macro]
+pub fn query_typed(item: TokenStream) -> TokenStream {
+    query::query_typed(item)
+}
+
 #[proc_macro_derive(State, attributes(borrow))]
 pub fn state_impl(item: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(item as syn::DeriveInput);
@@ -152,6 +173,220 @@ pub fn middleware_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+/// check argument and return types of async function used by `handler_service` one by one.
+/// type that does not satisfy `FromRequest` or `Responder` would be pointed out individually
+/// in compile error.
+#[proc_macro_attribute]
+pub fn debug_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut state_ty = None;
+    let mut body_ty = None;
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("state") {
+            state_ty = Some(meta.value()?.parse::<Type>()?);
+            Ok(())
+        } else if meta.path.is_ident("body") {
+            body_ty = Some(meta.value()?.parse::<Type>()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported debug_handler attribute. expecting state = <Type> or body = <Type>"))
+        }
+    });
+
+    syn::parse_macro_input!(attr with parser);
+
+    let func = syn::parse_macro_input!(item as ItemFn);
+
+    // generic function can not be checked without concrete types.
+    if !func.sig.generics.params.is_empty() {
+        return quote! { #func }.into();
+    }
+
+    let state_ty = state_ty.map(|ty| quote! { #ty }).unwrap_or_else(|| quote! { () });
+    let body_ty = body_ty
+        .map(|ty| quote! { #ty })
+        .unwrap_or_else(|| quote! { ::xitca_web::body::RequestBody });
+
+    let arg_checks = func
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(ty) if !matches!(*ty.ty, Type::ImplTrait(_)) => Some(&ty.ty),
+            _ => None,
+        })
+        .map(|ty| {
+            quote_spanned! { ty.span() =>
+                ::xitca_web::codegen::__private::assert_from_request::<#ty, #state_ty, #body_ty>();
+            }
+        });
+
+    let ret_check = match func.sig.output {
+        ReturnType::Default => Some(quote! {
+            ::xitca_web::codegen::__private::assert_responder::<(), #state_ty, #body_ty>();
+        }),
+        ReturnType::Type(_, ref ty) if !matches!(**ty, Type::ImplTrait(_)) => Some(quote_spanned! { ty.span() =>
+            ::xitca_web::codegen::__private::assert_responder::<#ty, #state_ty, #body_ty>();
+        }),
+        _ => None,
+    };
+
+    quote! {
+        #func
+
+        const _: () = {
+            #[allow(dead_code)]
+            fn __debug_handler() {
+                #(#arg_checks)*
+                #ret_check
+            }
+        };
+    }
+    .into()
+}
+
+/// generate a companion function named `<handler>_service` returning handler service of the
+/// function wrapped with cache middleware configured by attribute arguments. the handler function
+/// itself is left untouched.
+#[proc_macro_attribute]
+pub fn cache(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut ttl = None;
+    let mut key = None;
+    let mut state_ty = None;
+    let mut body_ty = None;
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("ttl") {
+            let lit = meta.value()?.parse::<syn::LitStr>()?;
+            let millis = parse_duration_millis(&lit.value()).ok_or_else(|| {
+                syn::Error::new(
+                    lit.span(),
+                    "invalid ttl. expecting duration like \"500ms\", \"30s\", \"5m\" or \"1h\"",
+                )
+            })?;
+            ttl = Some(millis);
+            Ok(())
+        } else if meta.path.is_ident("key") {
+            let lit = meta.value()?.parse::<syn::LitStr>()?;
+            key = Some(match lit.value().as_str() {
+                "path" => quote! { Path },
+                "query" => quote! { Query },
+                _ => {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "invalid key. expecting \"path\" or \"query\"",
+                    ))
+                }
+            });
+            Ok(())
+        } else if meta.path.is_ident("state") {
+            state_ty = Some(meta.value()?.parse::<Type>()?);
+            Ok(())
+        } else if meta.path.is_ident("body") {
+            body_ty = Some(meta.value()?.parse::<Type>()?);
+            Ok(())
+        } else {
+            Err(meta.error(
+                "unsupported cache attribute. expecting ttl = \"<duration>\", key = \"path\" | \"query\", state = <Type> or body = <Type>",
+            ))
+        }
+    });
+
+    syn::parse_macro_input!(attr with parser);
+
+    let func = syn::parse_macro_input!(item as ItemFn);
+
+    let Some(ttl) = ttl else {
+        return syn::Error::new(Span::call_site(), "cache attribute requires ttl = \"<duration>\"")
+            .to_compile_error()
+            .into();
+    };
+    let key = key.unwrap_or_else(|| quote! { Query });
+
+    if !func.sig.generics.params.is_empty() {
+        return syn::Error::new(
+            func.sig.generics.span(),
+            "cache attribute does not support generic handler",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // extractor types with all lifetimes replaced by 'static. it's the type handler service uses
+    // to extract arguments and the source of it's error type.
+    let mut args = Vec::new();
+    for arg in func.sig.inputs.iter() {
+        match arg {
+            FnArg::Typed(ty) if !matches!(*ty.ty, Type::ImplTrait(_)) => {
+                let mut ty = (*ty.ty).clone();
+                StaticLifetime.visit_type_mut(&mut ty);
+                args.push(ty);
+            }
+            arg => {
+                return syn::Error::new(arg.span(), "cache attribute does not support this argument")
+                    .to_compile_error()
+                    .into()
+            }
+        }
+    }
+
+    let state_ty = state_ty.map(|ty| quote! { #ty }).unwrap_or_else(|| quote! { () });
+    let body_ty = body_ty
+        .map(|ty| quote! { #ty })
+        .unwrap_or_else(|| quote! { ::xitca_web::body::RequestBody });
+
+    let vis = &func.vis;
+    let handler = &func.sig.ident;
+    let ident = Ident::new(&format!("{handler}_service"), handler.span());
+
+    quote! {
+        #func
+
+        /// handler service wrapped with cache middleware generated by `#[cache]` attribute.
+        #vis fn #ident() -> ::xitca_web::middleware::cache::CacheHandler<
+            #state_ty,
+            #body_ty,
+            <(#(#args,)*) as ::xitca_web::handler::FromRequest<'static, ::xitca_web::WebContext<'static, #state_ty, #body_ty>>>::Error,
+        > {
+            ::xitca_web::middleware::cache::CacheHandler::new(
+                ::xitca_web::handler::handler_service(#handler),
+                ::xitca_web::middleware::cache::Cache::new(::core::time::Duration::from_millis(#ttl))
+                    .key(::xitca_web::middleware::cache::CacheKey::#key),
+            )
+        }
+    }
+    .into()
+}
+
+// replace all lifetimes of type with 'static.
+struct StaticLifetime;
+
+impl VisitMut for StaticLifetime {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        *lifetime = Lifetime::new("'static", lifetime.span());
+    }
+
+    fn visit_type_reference_mut(&mut self, ty: &mut TypeReference) {
+        ty.lifetime = Some(Lifetime::new("'static", ty.and_token.span()));
+        visit_mut::visit_type_reference_mut(self, ty);
+    }
+}
+
+// parse duration with unit suffix into milliseconds.
+fn parse_duration_millis(s: &str) -> Option<u64> {
+    let idx = s.find(|c: char| !c.is_ascii_digit())?;
+    let (num, unit) = s.split_at(idx);
+    let num = num.parse::<u64>().ok()?;
+    let factor = match unit {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        _ => return None,
+    };
+    num.checked_mul(factor)
+}
+
 fn find_async_method<'a>(items: &'a [ImplItem], ident_str: &str) -> Option<&'a ImplItemFn> {
     items.iter().find_map(|item| match item {
         ImplItem::Fn(func) if func.sig.ident.to_string().as_str() == ident_str => {
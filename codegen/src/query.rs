@@ -0,0 +1,251 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, LitStr, Token, Visibility,
+};
+
+// query_typed! { schema = "<path>", <vis> struct <Ident> = "<sql>" }
+struct Input {
+    schema: LitStr,
+    vis: Visibility,
+    ident: Ident,
+    sql: LitStr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse::<Ident>()?;
+        if key != "schema" {
+            return Err(syn::Error::new(key.span(), "expecting schema = \"<path>\""));
+        }
+        input.parse::<Token![=]>()?;
+        let schema = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let vis = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let sql = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self {
+            schema,
+            vis,
+            ident,
+            sql,
+        })
+    }
+}
+
+struct Column {
+    name: String,
+    ty: String,
+    nullable: bool,
+}
+
+// schema snapshot maps table name to it's columns in declared order.
+type Schema = HashMap<String, Vec<Column>>;
+
+pub(crate) fn query_typed(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as Input);
+    expand(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand(input: Input) -> syn::Result<proc_macro2::TokenStream> {
+    let Input {
+        schema,
+        vis,
+        ident,
+        sql,
+    } = input;
+
+    let path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join(schema.value());
+    let text = fs::read_to_string(&path).map_err(|e| {
+        syn::Error::new(
+            schema.span(),
+            format!("failed to read schema snapshot {}: {e}", path.display()),
+        )
+    })?;
+    let tables = parse_schema(&text).map_err(|e| syn::Error::new(schema.span(), e))?;
+
+    let columns = resolve(&tables, &sql.value()).map_err(|e| syn::Error::new(sql.span(), e))?;
+
+    let mut borrowed = false;
+    let mut fields = Vec::with_capacity(columns.len());
+    let mut getters = Vec::with_capacity(columns.len());
+
+    for (idx, (name, col)) in columns.iter().enumerate() {
+        let (ty, borrow) = rust_type(&col.ty)
+            .ok_or_else(|| syn::Error::new(sql.span(), format!("column `{name}` has unsupported type `{}`", col.ty)))?;
+        borrowed |= borrow;
+        let ty = match col.nullable {
+            true => quote! { ::core::option::Option<#ty> },
+            false => ty,
+        };
+        let field = syn::parse_str::<Ident>(name).map_err(|_| {
+            syn::Error::new(
+                sql.span(),
+                format!("column `{name}` is not valid field name. rename it with AS"),
+            )
+        })?;
+        fields.push(quote! { pub #field: #ty });
+        getters.push(quote! { #field: row.try_get(#idx)? });
+    }
+
+    let path = path.to_string_lossy().into_owned();
+    let sql = sql.value();
+
+    let (generic, row_lifetime) = match borrowed {
+        true => (quote! { <'r> }, quote! { 'r }),
+        false => (quote! {}, quote! { '_ }),
+    };
+
+    Ok(quote! {
+        #vis struct #ident #generic {
+            #(#fields),*
+        }
+
+        impl #generic #ident #generic {
+            /// sql statement checked against schema snapshot.
+            pub const SQL: &'static str = #sql;
+
+            /// construct from row returned by executing [Self::SQL].
+            pub fn from_row(
+                row: &#row_lifetime ::xitca_postgres::row::Row<'_>,
+            ) -> ::core::result::Result<Self, ::xitca_postgres::error::Error> {
+                // recompile when schema snapshot changes.
+                const _: &str = ::core::include_str!(#path);
+                ::core::result::Result::Ok(Self { #(#getters),* })
+            }
+        }
+    })
+}
+
+// every non empty line is `<table>.<column> <type> [null]`. lines starting with `#` are comments.
+fn parse_schema(text: &str) -> Result<Schema, String> {
+    let mut schema = Schema::new();
+
+    for (no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let malformed = || format!("malformed schema snapshot line {}: {line}", no + 1);
+
+        let mut parts = line.split_whitespace();
+        let (table, column) = parts.next().and_then(|p| p.split_once('.')).ok_or_else(malformed)?;
+        let ty = parts.next().ok_or_else(malformed)?;
+        let nullable = match parts.next() {
+            None => false,
+            Some(null) if null.eq_ignore_ascii_case("null") => true,
+            Some(_) => return Err(malformed()),
+        };
+
+        schema.entry(table.to_ascii_lowercase()).or_default().push(Column {
+            name: column.to_ascii_lowercase(),
+            ty: ty.to_ascii_lowercase(),
+            nullable,
+        });
+    }
+
+    Ok(schema)
+}
+
+// resolve select list of single table query into (field name, column) pairs in order.
+fn resolve<'a>(schema: &'a Schema, sql: &str) -> Result<Vec<(String, &'a Column)>, String> {
+    let lower = sql.to_ascii_lowercase();
+    let tokens = lower.split_whitespace().collect::<Vec<_>>();
+
+    if tokens.first() != Some(&"select") {
+        return Err("only SELECT statement is supported".into());
+    }
+
+    let from = tokens
+        .iter()
+        .position(|t| *t == "from")
+        .ok_or("SELECT statement without FROM clause is not supported")?;
+
+    if tokens.contains(&"join") {
+        return Err("JOIN is not supported. query single table instead".into());
+    }
+
+    let table = tokens.get(from + 1).ok_or("table name is missing after FROM")?;
+    let table = table.trim_end_matches(';').rsplit('.').next().unwrap();
+    let alias = tokens
+        .get(from + 2)
+        .filter(|t| {
+            !matches!(
+                **t,
+                "where" | "order" | "group" | "limit" | "offset" | "for" | "having" | ";"
+            )
+        })
+        .map(|t| {
+            if *t == "as" {
+                tokens.get(from + 3).copied()
+            } else {
+                Some(*t)
+            }
+        })
+        .unwrap_or(None);
+
+    let columns = schema
+        .get(table)
+        .ok_or_else(|| format!("table `{table}` not found in schema snapshot"))?;
+
+    let list = tokens[1..from].join(" ");
+    let mut resolved = Vec::new();
+
+    for item in list.split(',').map(str::trim) {
+        if item == "*" {
+            resolved.extend(columns.iter().map(|c| (c.name.clone(), c)));
+            continue;
+        }
+
+        let (expr, name) = match item.split_once(" as ") {
+            Some((expr, name)) => (expr.trim(), Some(name.trim())),
+            None => (item, None),
+        };
+
+        let column = match expr.split_once('.') {
+            Some((qualifier, column)) if qualifier == table || Some(qualifier) == alias => column,
+            Some((qualifier, _)) => return Err(format!("unknown table qualifier `{qualifier}`")),
+            None => expr,
+        };
+
+        if !column.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') || column.is_empty() {
+            return Err(format!(
+                "expression `{expr}` is not supported. select plain columns only"
+            ));
+        }
+
+        let col = columns
+            .iter()
+            .find(|c| c.name == column)
+            .ok_or_else(|| format!("column `{column}` not found in table `{table}` of schema snapshot"))?;
+
+        resolved.push((name.unwrap_or(column).to_owned(), col));
+    }
+
+    Ok(resolved)
+}
+
+// map postgres type name to rust type. borrowed types are parsed with zero copy from row.
+fn rust_type(ty: &str) -> Option<(proc_macro2::TokenStream, bool)> {
+    let ty = match ty {
+        "bool" | "boolean" => quote! { bool },
+        "char" => quote! { i8 },
+        "int2" | "smallint" => quote! { i16 },
+        "int4" | "int" | "integer" => quote! { i32 },
+        "int8" | "bigint" => quote! { i64 },
+        "oid" => quote! { u32 },
+        "float4" | "real" => quote! { f32 },
+        "float8" => quote! { f64 },
+        "text" | "varchar" | "bpchar" | "name" => return Some((quote! { &'r str }, true)),
+        "bytea" => return Some((quote! { &'r [u8] }, true)),
+        _ => return None,
+    };
+    Some((ty, false))
+}
@@ -0,0 +1,201 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Fields, LitStr};
+
+// postgres type shape a rust type maps to.
+enum Shape {
+    // enum type with variant labels and rust variant idents.
+    Enum(Vec<(String, syn::Ident)>),
+    // domain type with it's base type represented by inner type of newtype.
+    Domain(syn::Type),
+}
+
+struct Input {
+    ident: syn::Ident,
+    name: String,
+    shape: Shape,
+}
+
+fn parse(input: DeriveInput) -> Input {
+    assert!(
+        input.generics.params.is_empty(),
+        "postgres derive macros do not support generic type"
+    );
+
+    let name = postgres_name(&input.attrs).unwrap_or_else(|| input.ident.to_string());
+
+    let shape = match input.data {
+        Data::Enum(data) if data.variants.is_empty() => panic!("postgres enum can not be derived from empty enum"),
+        Data::Enum(data) => Shape::Enum(
+            data.variants
+                .into_iter()
+                .map(|variant| {
+                    assert!(
+                        matches!(variant.fields, Fields::Unit),
+                        "postgres enum can only be derived from enum with unit variants"
+                    );
+                    let label = postgres_name(&variant.attrs).unwrap_or_else(|| variant.ident.to_string());
+                    (label, variant.ident)
+                })
+                .collect(),
+        ),
+        Data::Struct(data) => match data.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Shape::Domain(fields.unnamed.into_iter().next().unwrap().ty)
+            }
+            _ => panic!("postgres domain can only be derived from newtype struct"),
+        },
+        Data::Union(_) => panic!("postgres derive macros do not support union type"),
+    };
+
+    Input {
+        ident: input.ident,
+        name,
+        shape,
+    }
+}
+
+// parse #[postgres(name = "...")] attribute.
+fn postgres_name(attrs: &[Attribute]) -> Option<String> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("postgres")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported postgres attribute. expecting name = \"...\""))
+            }
+        })
+        .unwrap_or_else(|e| panic!("{e}"));
+    }
+    name
+}
+
+// domain's base type is checked with the given trait of inner type.
+fn accepts(input: &Input, inner_trait: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let name = &input.name;
+    match input.shape {
+        Shape::Enum(ref variants) => {
+            let labels = variants.iter().map(|(label, _)| label);
+            let len = variants.len();
+            quote! {
+                fn accepts(ty: &::xitca_postgres::Type) -> bool {
+                    ty.name() == #name
+                        && matches!(
+                            ty.kind(),
+                            ::xitca_postgres::Kind::Enum(variants)
+                                if variants.len() == #len && variants.iter().all(|v| matches!(v.as_str(), #(#labels)|*))
+                        )
+                }
+            }
+        }
+        Shape::Domain(ref inner) => quote! {
+            fn accepts(ty: &::xitca_postgres::Type) -> bool {
+                match ty.kind() {
+                    ::xitca_postgres::Kind::Domain(base) => ty.name() == #name && <#inner as #inner_trait>::accepts(base),
+                    _ => <#inner as #inner_trait>::accepts(ty),
+                }
+            }
+        },
+    }
+}
+
+pub(crate) fn to_sql(item: TokenStream) -> TokenStream {
+    let input = parse(syn::parse_macro_input!(item as DeriveInput));
+    let ident = &input.ident;
+    let accepts = accepts(&input, quote! { ::xitca_postgres::ToSql });
+
+    let to_sql = match input.shape {
+        Shape::Enum(ref variants) => {
+            let arms = variants
+                .iter()
+                .map(|(label, variant)| quote! { #ident::#variant => #label, });
+            quote! {
+                fn to_sql(
+                    &self,
+                    _: &::xitca_postgres::Type,
+                    out: &mut ::xitca_postgres::codegen::__private::BytesMut,
+                ) -> Result<::xitca_postgres::codegen::__private::IsNull, ::xitca_postgres::FromSqlError> {
+                    let label = match *self {
+                        #(#arms)*
+                    };
+                    out.extend_from_slice(label.as_bytes());
+                    Ok(::xitca_postgres::codegen::__private::IsNull::No)
+                }
+            }
+        }
+        Shape::Domain(ref inner) => quote! {
+            fn to_sql(
+                &self,
+                ty: &::xitca_postgres::Type,
+                out: &mut ::xitca_postgres::codegen::__private::BytesMut,
+            ) -> Result<::xitca_postgres::codegen::__private::IsNull, ::xitca_postgres::FromSqlError> {
+                let ty = match ty.kind() {
+                    ::xitca_postgres::Kind::Domain(base) => base,
+                    _ => ty,
+                };
+                <#inner as ::xitca_postgres::ToSql>::to_sql(&self.0, ty, out)
+            }
+        },
+    };
+
+    quote! {
+        impl ::xitca_postgres::ToSql for #ident {
+            #to_sql
+
+            #accepts
+
+            ::xitca_postgres::codegen::__private::to_sql_checked!();
+        }
+    }
+    .into()
+}
+
+pub(crate) fn from_sql_ext(item: TokenStream) -> TokenStream {
+    let input = parse(syn::parse_macro_input!(item as DeriveInput));
+    let ident = &input.ident;
+    let accepts = accepts(&input, quote! { ::xitca_postgres::FromSqlExt<'a> });
+
+    let from_sql = match input.shape {
+        Shape::Enum(ref variants) => {
+            let arms = variants.iter().map(|(label, variant)| {
+                let label = proc_macro2::Literal::byte_string(label.as_bytes());
+                quote! { #label => Ok(#ident::#variant), }
+            });
+            quote! {
+                fn from_sql_nullable_ext(
+                    _: &::xitca_postgres::Type,
+                    buf: Option<(&::core::ops::Range<usize>, &'a ::xitca_postgres::codegen::__private::Bytes)>,
+                ) -> Result<Self, ::xitca_postgres::FromSqlError> {
+                    let (range, buf) = buf.ok_or_else(|| Box::new(::xitca_postgres::codegen::__private::WasNull))?;
+                    match &buf[range.start..range.end] {
+                        #(#arms)*
+                        label => Err(format!("unknown variant {}", String::from_utf8_lossy(label)).into()),
+                    }
+                }
+            }
+        }
+        Shape::Domain(ref inner) => quote! {
+            fn from_sql_nullable_ext(
+                ty: &::xitca_postgres::Type,
+                buf: Option<(&::core::ops::Range<usize>, &'a ::xitca_postgres::codegen::__private::Bytes)>,
+            ) -> Result<Self, ::xitca_postgres::FromSqlError> {
+                let ty = match ty.kind() {
+                    ::xitca_postgres::Kind::Domain(base) => base,
+                    _ => ty,
+                };
+                <#inner as ::xitca_postgres::FromSqlExt<'a>>::from_sql_nullable_ext(ty, buf).map(#ident)
+            }
+        },
+    };
+
+    quote! {
+        impl<'a> ::xitca_postgres::FromSqlExt<'a> for #ident {
+            #from_sql
+
+            #accepts
+        }
+    }
+    .into()
+}
@@ -15,7 +15,7 @@ fn main() -> io::Result<()> {
         .init();
     App::new()
         .at("/", handler_service(root))
-        .enclosed(Compress)
+        .enclosed(Compress::new())
         .enclosed(Decompress)
         .serve()
         .bind("127.0.0.1:8080")?
@@ -25,7 +25,7 @@ fn main() -> std::io::Result<()> {
             Route::new([Method::GET, Method::HEAD]).route(handler_service(index)),
         )
         // compression middleware
-        .enclosed(Compress)
+        .enclosed(Compress::new())
         // simple function middleware that intercept empty path and replace it with index.html
         .enclosed_fn(path)
         .serve()
@@ -18,6 +18,7 @@ pin_project! {
         #[pin]
         body: S,
         coder: C,
+        flush: bool,
     }
 }
 
@@ -29,7 +30,25 @@ where
 {
     /// Construct a new coder.
     pub fn new(body: S, coder: C) -> Self {
-        Self { body, coder }
+        Self {
+            body,
+            coder,
+            flush: false,
+        }
+    }
+
+    /// Flush coder's internal buffer after every item of input stream.
+    ///
+    /// Input item is treated as message boundary and coded output is emitted right away instead
+    /// of waiting for coder to fill it's buffer. Useful for streaming response like SSE.
+    pub fn set_flush(mut self, flush: bool) -> Self {
+        self.flush = flush;
+        self
+    }
+
+    /// Destruct coder into input stream and the coder doing (de)compress work.
+    pub fn into_parts(self) -> (S, C) {
+        (self.body, self.coder)
     }
 }
 
@@ -45,7 +64,12 @@ where
 
         while let Some(res) = ready!(this.body.as_mut().poll_next(cx)) {
             let item = res.map_err(CoderError::Stream)?;
-            if let Some(item) = this.coder.code(item)? {
+            let item = if *this.flush {
+                this.coder.code_flush(item)?
+            } else {
+                this.coder.code(item)?
+            };
+            if let Some(item) = item {
                 return Poll::Ready(Some(Ok(item)));
             }
         }
@@ -70,6 +94,23 @@ pub trait Code<T>: Sized {
 
     fn code_eof(&mut self) -> io::Result<Option<Self::Item>>;
 
+    /// Code item and flush coder's internal buffer so all data coded so far is emitted.
+    /// By default it's the same as [Code::code] for coders that don't buffer.
+    #[inline]
+    fn code_flush(&mut self, item: T) -> io::Result<Option<Self::Item>> {
+        self.code(item)
+    }
+
+    /// Limit the bytes each following call of [Code::code], [Code::code_flush] and [Code::code_eof]
+    /// can output. Going beyond the limit fails the call with [OutputLimitExceeded] error as soon as
+    /// it happens instead of producing the whole output first.
+    /// By default it does nothing for coders that can't enforce a limit.
+    ///
+    /// [OutputLimitExceeded]: crate::error::OutputLimitExceeded
+    #[allow(unused_variables)]
+    #[inline]
+    fn set_output_limit(&mut self, limit: usize) {}
+
     /// A helper method for overriding associated input stream's size_hint.
     /// by default it returns value the same as [Stream::size_hint]'s default value.
     /// in other word the default prediction is (de)compress can not hint an exact size.
@@ -123,6 +164,10 @@ pub enum FeaturedCode {
     DecodeDe(super::deflate::Decoder),
     #[cfg(feature = "de")]
     EncodeDe(super::deflate::Encoder),
+    #[cfg(feature = "zs")]
+    DecodeZs(super::zstd::Decoder),
+    #[cfg(feature = "zs")]
+    EncodeZs(super::zstd::Encoder),
 }
 
 impl Default for FeaturedCode {
@@ -152,6 +197,10 @@ where
             Self::DecodeDe(ref mut coder) => coder.code(item),
             #[cfg(feature = "de")]
             Self::EncodeDe(ref mut coder) => coder.code(item),
+            #[cfg(feature = "zs")]
+            Self::DecodeZs(ref mut coder) => coder.code(item),
+            #[cfg(feature = "zs")]
+            Self::EncodeZs(ref mut coder) => coder.code(item),
         }
     }
 
@@ -170,6 +219,54 @@ where
             Self::DecodeDe(ref mut coder) => <super::deflate::Decoder as Code<T>>::code_eof(coder),
             #[cfg(feature = "de")]
             Self::EncodeDe(ref mut coder) => <super::deflate::Encoder as Code<T>>::code_eof(coder),
+            #[cfg(feature = "zs")]
+            Self::DecodeZs(ref mut coder) => <super::zstd::Decoder as Code<T>>::code_eof(coder),
+            #[cfg(feature = "zs")]
+            Self::EncodeZs(ref mut coder) => <super::zstd::Encoder as Code<T>>::code_eof(coder),
+        }
+    }
+
+    fn code_flush(&mut self, item: T) -> io::Result<Option<Self::Item>> {
+        match self {
+            Self::NoOp(ref mut coder) => coder.code_flush(item),
+            #[cfg(feature = "br")]
+            Self::DecodeBr(ref mut coder) => coder.code_flush(item),
+            #[cfg(feature = "br")]
+            Self::EncodeBr(ref mut coder) => coder.code_flush(item),
+            #[cfg(feature = "gz")]
+            Self::DecodeGz(ref mut coder) => coder.code_flush(item),
+            #[cfg(feature = "gz")]
+            Self::EncodeGz(ref mut coder) => coder.code_flush(item),
+            #[cfg(feature = "de")]
+            Self::DecodeDe(ref mut coder) => coder.code_flush(item),
+            #[cfg(feature = "de")]
+            Self::EncodeDe(ref mut coder) => coder.code_flush(item),
+            #[cfg(feature = "zs")]
+            Self::DecodeZs(ref mut coder) => coder.code_flush(item),
+            #[cfg(feature = "zs")]
+            Self::EncodeZs(ref mut coder) => coder.code_flush(item),
+        }
+    }
+
+    fn set_output_limit(&mut self, limit: usize) {
+        match self {
+            Self::NoOp(ref mut coder) => <NoOpCode as Code<T>>::set_output_limit(coder, limit),
+            #[cfg(feature = "br")]
+            Self::DecodeBr(ref mut coder) => <super::brotli::Decoder as Code<T>>::set_output_limit(coder, limit),
+            #[cfg(feature = "br")]
+            Self::EncodeBr(ref mut coder) => <super::brotli::Encoder as Code<T>>::set_output_limit(coder, limit),
+            #[cfg(feature = "gz")]
+            Self::DecodeGz(ref mut coder) => <super::gzip::Decoder as Code<T>>::set_output_limit(coder, limit),
+            #[cfg(feature = "gz")]
+            Self::EncodeGz(ref mut coder) => <super::gzip::Encoder as Code<T>>::set_output_limit(coder, limit),
+            #[cfg(feature = "de")]
+            Self::DecodeDe(ref mut coder) => <super::deflate::Decoder as Code<T>>::set_output_limit(coder, limit),
+            #[cfg(feature = "de")]
+            Self::EncodeDe(ref mut coder) => <super::deflate::Encoder as Code<T>>::set_output_limit(coder, limit),
+            #[cfg(feature = "zs")]
+            Self::DecodeZs(ref mut coder) => <super::zstd::Decoder as Code<T>>::set_output_limit(coder, limit),
+            #[cfg(feature = "zs")]
+            Self::EncodeZs(ref mut coder) => <super::zstd::Encoder as Code<T>>::set_output_limit(coder, limit),
         }
     }
 
@@ -188,6 +285,10 @@ where
             Self::DecodeDe(ref coder) => <super::deflate::Decoder as Code<T>>::size_hint(coder, stream),
             #[cfg(feature = "de")]
             Self::EncodeDe(ref coder) => <super::deflate::Encoder as Code<T>>::size_hint(coder, stream),
+            #[cfg(feature = "zs")]
+            Self::DecodeZs(ref coder) => <super::zstd::Decoder as Code<T>>::size_hint(coder, stream),
+            #[cfg(feature = "zs")]
+            Self::EncodeZs(ref coder) => <super::zstd::Encoder as Code<T>>::size_hint(coder, stream),
         }
     }
 }
@@ -213,6 +314,19 @@ macro_rules! code_impl {
                 }
             }
 
+            fn code_flush(&mut self, item: T) -> ::std::io::Result<Option<Self::Item>> {
+                use ::std::io::Write;
+
+                self.write_all(item.as_ref())?;
+                self.flush()?;
+                let b = self.get_mut().take();
+                if !b.is_empty() {
+                    Ok(Some(b))
+                } else {
+                    Ok(None)
+                }
+            }
+
             fn code_eof(&mut self) -> ::std::io::Result<Option<Self::Item>> {
                 self.try_finish()?;
                 let b = self.get_mut().take();
@@ -222,6 +336,11 @@ macro_rules! code_impl {
                     Ok(None)
                 }
             }
+
+            #[inline]
+            fn set_output_limit(&mut self, limit: usize) {
+                self.get_mut().set_limit(limit);
+            }
         }
     };
 }
@@ -247,4 +366,34 @@ mod test {
         let bytes = Vec::<u8>::new();
         assert!(try_downcast_to_bytes(bytes).is_err());
     }
+
+    #[cfg(feature = "gz")]
+    #[test]
+    fn output_limit() {
+        use std::io::Write;
+
+        use flate2::{write::GzEncoder, Compression};
+
+        use crate::{error::OutputLimitExceeded, writer::BytesMutWriter};
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&[0; 1024 * 1024]).unwrap();
+        let compressed = Bytes::from(encoder.finish().unwrap());
+
+        let decoder = || FeaturedCode::DecodeGz(crate::gzip::Decoder::new(BytesMutWriter::new()));
+
+        let mut coder = decoder();
+        Code::<Bytes>::set_output_limit(&mut coder, 1024);
+        let e = coder.code(compressed.clone()).err().unwrap();
+        assert!(e.get_ref().unwrap().is::<OutputLimitExceeded>());
+
+        let mut coder = decoder();
+        Code::<Bytes>::set_output_limit(&mut coder, 1024 * 1024);
+        let mut len = coder.code(compressed).unwrap().map(|b| b.len()).unwrap_or(0);
+        len += Code::<Bytes>::code_eof(&mut coder)
+            .unwrap()
+            .map(|b| b.len())
+            .unwrap_or(0);
+        assert_eq!(len, 1024 * 1024);
+    }
 }
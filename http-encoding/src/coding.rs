@@ -11,6 +11,8 @@ pub enum ContentEncoding {
     Deflate,
     /// Gzip algorithm.
     Gzip,
+    /// A format using the Zstandard algorithm.
+    Zstd,
     /// Indicates no operation is done with encoding.
     #[default]
     NoOp,
@@ -52,6 +54,8 @@ impl ContentEncoding {
             Ok(Self::Deflate)
         } else if s.eq_ignore_ascii_case("br") {
             Ok(Self::Br)
+        } else if s.eq_ignore_ascii_case("zstd") {
+            Ok(Self::Zstd)
         } else if s.eq_ignore_ascii_case("identity") {
             Ok(Self::NoOp)
         } else {
@@ -84,6 +88,8 @@ impl ContentEncodingWithQValue {
                 ContentEncoding::Deflate => return,
                 #[cfg(not(feature = "gz"))]
                 ContentEncoding::Gzip => return,
+                #[cfg(not(feature = "zs"))]
+                ContentEncoding::Zstd => return,
                 _ => {}
             };
             *self = other;
@@ -157,3 +163,23 @@ impl QValue {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use http::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn zstd() {
+        assert_eq!(ContentEncoding::try_parse("zstd").ok(), Some(ContentEncoding::Zstd));
+        assert_eq!(ContentEncoding::try_parse("ZSTD").ok(), Some(ContentEncoding::Zstd));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("zstd"));
+        #[cfg(feature = "zs")]
+        assert_eq!(ContentEncoding::from_headers(&headers), ContentEncoding::Zstd);
+        #[cfg(not(feature = "zs"))]
+        assert_eq!(ContentEncoding::from_headers(&headers), ContentEncoding::NoOp);
+    }
+}
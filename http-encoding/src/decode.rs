@@ -62,6 +62,16 @@ fn from_headers(headers: &HeaderMap) -> Result<FeaturedCode, EncodingError> {
                 Err(super::error::FeatureError::Deflate.into())
             }
         }
+        ContentEncoding::Zstd => {
+            #[cfg(feature = "zs")]
+            {
+                Ok(FeaturedCode::DecodeZs(super::zstd::decoder()))
+            }
+            #[cfg(not(feature = "zs"))]
+            {
+                Err(super::error::FeatureError::Zstd.into())
+            }
+        }
         ContentEncoding::NoOp => Ok(FeaturedCode::default()),
     }
 }
@@ -8,8 +8,86 @@ use super::{
     coding::ContentEncoding,
 };
 
+#[cfg(feature = "zs")]
+use super::zstd::ZstdOptions;
+
+/// Policy of flushing encoder's internal buffer for streaming response body.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FlushPolicy {
+    /// Flush on every chunk of response body when response is `text/event-stream` type.
+    /// Otherwise let encoder decide when compressed data is emitted.
+    #[default]
+    Auto,
+    /// Flush on every chunk of response body. Every chunk is treated as message boundary.
+    Always,
+    /// Let encoder decide when compressed data is emitted for best compression ratio.
+    Never,
+}
+
+impl FlushPolicy {
+    pub(crate) fn should_flush(&self, headers: &header::HeaderMap) -> bool {
+        match *self {
+            Self::Auto => headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.split(';').next())
+                .map(|v| v.trim().eq_ignore_ascii_case("text/event-stream"))
+                .unwrap_or(false),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
 /// Construct from headers and stream body. Use for encoding.
-pub fn encoder<S, T, E>(response: Response<S>, mut encoding: ContentEncoding) -> Response<Coder<S, FeaturedCode>>
+pub fn encoder<S, T, E>(response: Response<S>, encoding: ContentEncoding) -> Response<Coder<S, FeaturedCode>>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]> + 'static,
+{
+    encoder_with_flush(response, encoding, FlushPolicy::default())
+}
+
+/// Construct from headers and stream body with given [FlushPolicy]. Use for encoding.
+pub fn encoder_with_flush<S, T, E>(
+    response: Response<S>,
+    encoding: ContentEncoding,
+    flush: FlushPolicy,
+) -> Response<Coder<S, FeaturedCode>>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]> + 'static,
+{
+    _encoder(response, encoding, flush, Default::default())
+}
+
+/// Construct from headers and stream body with given [FlushPolicy] and [ZstdOptions]. Use for encoding.
+#[cfg(feature = "zs")]
+pub fn encoder_with_zstd<S, T, E>(
+    response: Response<S>,
+    encoding: ContentEncoding,
+    flush: FlushPolicy,
+    zstd: ZstdOptions,
+) -> Response<Coder<S, FeaturedCode>>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]> + 'static,
+{
+    _encoder(response, encoding, flush, zstd)
+}
+
+// placeholder of zstd options when zs feature is not enabled.
+#[cfg(not(feature = "zs"))]
+#[derive(Default)]
+struct ZstdOptions;
+
+#[allow(unused_variables)]
+fn _encoder<S, T, E>(
+    response: Response<S>,
+    mut encoding: ContentEncoding,
+    flush: FlushPolicy,
+    zstd: ZstdOptions,
+) -> Response<Coder<S, FeaturedCode>>
 where
     S: Stream<Item = Result<T, E>>,
     T: AsRef<[u8]> + 'static,
@@ -47,17 +125,112 @@ where
                 update_header(&mut parts.headers, "br");
                 FeaturedCode::EncodeBr(super::brotli::Encoder::new(3))
             }
+            #[cfg(feature = "zs")]
+            ContentEncoding::Zstd => {
+                update_header(&mut parts.headers, "zstd");
+                FeaturedCode::EncodeZs(super::zstd::encoder(zstd))
+            }
             _ => FeaturedCode::default(),
         }
     };
 
-    let body = Coder::new(body, encoder);
+    let flush = flush.should_flush(&parts.headers);
+    let body = Coder::new(body, encoder).set_flush(flush);
     Response::from_parts(parts, body)
 }
 
-#[cfg(any(feature = "br", feature = "gz", feature = "de"))]
+#[cfg(any(feature = "br", feature = "gz", feature = "de", feature = "zs"))]
 fn update_header(headers: &mut header::HeaderMap, value: &'static str) {
     headers.insert(header::CONTENT_ENCODING, header::HeaderValue::from_static(value));
     headers.remove(header::CONTENT_LENGTH);
     headers.insert(header::TRANSFER_ENCODING, header::HeaderValue::from_static("chunked"));
 }
+
+#[cfg(test)]
+mod test {
+    use http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+
+    use super::*;
+
+    #[test]
+    fn flush_policy() {
+        let mut headers = HeaderMap::new();
+        assert!(!FlushPolicy::Auto.should_flush(&headers));
+        assert!(FlushPolicy::Always.should_flush(&headers));
+
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/event-stream; charset=utf-8"),
+        );
+        assert!(FlushPolicy::Auto.should_flush(&headers));
+        assert!(!FlushPolicy::Never.should_flush(&headers));
+    }
+
+    #[cfg(feature = "gz")]
+    #[test]
+    fn gzip_flush() {
+        use crate::coder::Code;
+
+        let mut encoder =
+            super::super::gzip::Encoder::new(super::super::writer::BytesMutWriter::new(), flate2::Compression::fast());
+
+        // gzip header is emitted on first write.
+        let _ = Code::code(&mut encoder, b"data: hello\n\n").unwrap();
+        // small chunk stays in encoder buffer without flushing.
+        assert!(Code::code(&mut encoder, b"data: foo\n\n").unwrap().is_none());
+        assert!(Code::code_flush(&mut encoder, b"data: bar\n\n").unwrap().is_some());
+        assert!(Code::<&[u8]>::code_eof(&mut encoder).unwrap().is_some());
+    }
+
+    #[cfg(feature = "zs")]
+    #[test]
+    fn zstd_encoder() {
+        use core::{
+            pin::pin,
+            task::{Context, Poll, Waker},
+        };
+
+        use bytes::Bytes;
+        use http::header::{CONTENT_ENCODING, CONTENT_LENGTH};
+
+        use crate::coder::Code;
+
+        struct Once(Option<Bytes>);
+
+        impl Stream for Once {
+            type Item = Result<Bytes, ()>;
+
+            fn poll_next(self: core::pin::Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+                Poll::Ready(self.get_mut().0.take().map(Ok))
+            }
+        }
+
+        let input = Bytes::from("xitca-web zstd ".repeat(1024));
+
+        let res = Response::builder()
+            .header(CONTENT_LENGTH, input.len())
+            .body(Once(Some(input.clone())))
+            .unwrap();
+
+        let res = encoder_with_zstd(
+            res,
+            ContentEncoding::Zstd,
+            FlushPolicy::Never,
+            ZstdOptions::default().quality(9).window_log(16),
+        );
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "zstd");
+        assert!(res.headers().get(CONTENT_LENGTH).is_none());
+
+        let mut body = pin!(res.into_body());
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut compressed = Vec::new();
+        while let Poll::Ready(Some(res)) = body.as_mut().poll_next(&mut cx) {
+            compressed.extend_from_slice(&res.unwrap());
+        }
+        assert!(compressed.len() < input.len() / 10);
+
+        let mut decoder = crate::zstd::decoder();
+        let decompressed = Code::code(&mut decoder, compressed).unwrap().unwrap();
+        assert_eq!(decompressed, input);
+    }
+}
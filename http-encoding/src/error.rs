@@ -24,6 +24,7 @@ pub enum FeatureError {
     Br,
     Gzip,
     Deflate,
+    Zstd,
     Unknown(Box<str>),
 }
 
@@ -33,6 +34,7 @@ impl fmt::Display for FeatureError {
             Self::Br => feature_error_fmt("brotil", f),
             Self::Gzip => feature_error_fmt("gzip", f),
             Self::Deflate => feature_error_fmt("deflate", f),
+            Self::Zstd => feature_error_fmt("zstd", f),
             Self::Unknown(ref encoding) => feature_error_fmt(encoding, f),
         }
     }
@@ -52,6 +54,19 @@ impl From<FeatureError> for EncodingError {
     }
 }
 
+/// Error for coder output going beyond the limit set by [Code::set_output_limit](crate::Code::set_output_limit).
+/// It's carried as inner error of [io::Error] returned by coder.
+#[derive(Debug)]
+pub struct OutputLimitExceeded;
+
+impl fmt::Display for OutputLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("coder output reached limit")
+    }
+}
+
+impl error::Error for OutputLimitExceeded {}
+
 /// Error occur when decode/encode request/response body stream.
 pub enum CoderError<E> {
     Io(io::Error),
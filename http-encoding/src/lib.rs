@@ -10,9 +10,12 @@ mod coding;
 mod decode;
 mod encode;
 
-#[cfg(any(feature = "br", feature = "gz", feature = "de"))]
+#[cfg(any(feature = "br", feature = "gz", feature = "de", feature = "zs"))]
 mod writer;
 
+#[cfg(feature = "zs")]
+mod zstd;
+
 #[cfg(feature = "br")]
 mod brotli {
     use std::io::{self, Write};
@@ -56,6 +59,11 @@ mod brotli {
                 Ok(None)
             }
         }
+
+        #[inline]
+        fn set_output_limit(&mut self, limit: usize) {
+            self.get_mut().set_limit(limit);
+        }
     }
 
     impl<T> Code<T> for Encoder
@@ -86,6 +94,13 @@ mod brotli {
                 None => Ok(None),
             }
         }
+
+        #[inline]
+        fn set_output_limit(&mut self, limit: usize) {
+            if let Some(encoder) = self.0.as_mut() {
+                encoder.get_mut().set_limit(limit);
+            }
+        }
     }
 }
 
@@ -117,4 +132,9 @@ mod deflate {
 pub use self::coder::{Code, Coder, FeaturedCode};
 pub use self::coding::ContentEncoding;
 pub use self::decode::try_decoder;
-pub use self::encode::encoder;
+pub use self::encode::{encoder, encoder_with_flush, FlushPolicy};
+
+#[cfg(feature = "zs")]
+pub use self::encode::encoder_with_zstd;
+#[cfg(feature = "zs")]
+pub use self::zstd::ZstdOptions;
@@ -2,27 +2,43 @@ use std::io;
 
 use bytes::{Bytes, BytesMut};
 
-pub struct BytesMutWriter(BytesMut);
+use super::error::OutputLimitExceeded;
+
+pub struct BytesMutWriter {
+    buf: BytesMut,
+    limit: usize,
+}
 
 impl BytesMutWriter {
     pub(super) fn new() -> Self {
-        Self(BytesMut::new())
+        Self {
+            buf: BytesMut::new(),
+            limit: usize::MAX,
+        }
+    }
+
+    // limit the bytes can be written before next take.
+    pub(super) fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
     }
 
     pub(super) fn take(&mut self) -> Bytes {
-        self.0.split().freeze()
+        self.buf.split().freeze()
     }
 
     #[cfg(feature = "br")]
     pub(super) fn take_owned(self) -> Bytes {
-        self.0.freeze()
+        self.buf.freeze()
     }
 }
 
 impl io::Write for BytesMutWriter {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.0.extend_from_slice(buf);
+        if buf.len() > self.limit.saturating_sub(self.buf.len()) {
+            return Err(io::Error::other(OutputLimitExceeded));
+        }
+        self.buf.extend_from_slice(buf);
         Ok(buf.len())
     }
 
@@ -0,0 +1,237 @@
+use std::io::{self, Write};
+
+use bytes::Bytes;
+use zstd::stream::write;
+
+use super::{coder::Code, writer::BytesMutWriter};
+
+pub type Decoder = write::Decoder<'static, BytesMutWriter>;
+pub type Encoder = write::Encoder<'static, BytesMutWriter>;
+
+/// Options of zstd encoding.
+#[derive(Clone, Copy, Debug)]
+pub struct ZstdOptions {
+    quality: u32,
+    window_log: u32,
+}
+
+impl Default for ZstdOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZstdOptions {
+    /// Minimal window log of zstd format.
+    pub const MIN_WINDOW_LOG: u32 = 10;
+
+    /// Maximum window log. Window larger than 8MB is not required to be supported by decoders
+    /// of Content-Encoding: zstd.
+    pub const MAX_WINDOW_LOG: u32 = 23;
+
+    pub const fn new() -> Self {
+        Self {
+            quality: 3,
+            window_log: 21,
+        }
+    }
+
+    /// Set compression quality in range of 1 to 19. Higher quality searches for matches harder
+    /// and produces smaller output at the cost of speed.
+    ///
+    /// Default to 3.
+    ///
+    /// # Panics
+    /// When quality is out of range.
+    pub fn quality(mut self, quality: u32) -> Self {
+        assert!((1..=19).contains(&quality), "zstd quality must be in range of 1 to 19");
+        self.quality = quality;
+        self
+    }
+
+    /// Set log2 of window size in range of [Self::MIN_WINDOW_LOG] to [Self::MAX_WINDOW_LOG].
+    /// Window is the distance encoder can look back for matches and the amount of memory decoder
+    /// must keep.
+    ///
+    /// Default to 21 (2MB).
+    ///
+    /// # Panics
+    /// When window log is out of range.
+    pub fn window_log(mut self, window_log: u32) -> Self {
+        assert!(
+            (Self::MIN_WINDOW_LOG..=Self::MAX_WINDOW_LOG).contains(&window_log),
+            "zstd window log must be in range of 10 to 23"
+        );
+        self.window_log = window_log;
+        self
+    }
+}
+
+// zstd context creation only fails when it's out of memory and parameters are checked by
+// ZstdOptions. both are treated as unrecoverable.
+pub(super) fn decoder() -> Decoder {
+    let mut decoder = Decoder::new(BytesMutWriter::new()).expect("failed to create zstd decoder");
+    // frame asking for window larger than Content-Encoding: zstd allows is rejected.
+    decoder
+        .window_log_max(ZstdOptions::MAX_WINDOW_LOG)
+        .expect("failed to set zstd decoder window");
+    decoder
+}
+
+pub(super) fn encoder(opts: ZstdOptions) -> Encoder {
+    let mut encoder = Encoder::new(BytesMutWriter::new(), opts.quality as i32).expect("failed to create zstd encoder");
+    encoder
+        .window_log(opts.window_log)
+        .expect("failed to set zstd encoder window");
+    encoder
+}
+
+impl<T> Code<T> for Decoder
+where
+    T: AsRef<[u8]>,
+{
+    type Item = Bytes;
+
+    fn code(&mut self, item: T) -> io::Result<Option<Self::Item>> {
+        self.write_all(item.as_ref())?;
+        self.flush()?;
+        Ok(non_empty(self.get_mut().take()))
+    }
+
+    fn code_eof(&mut self) -> io::Result<Option<Self::Item>> {
+        self.flush()?;
+        Ok(non_empty(self.get_mut().take()))
+    }
+
+    #[inline]
+    fn set_output_limit(&mut self, limit: usize) {
+        self.get_mut().set_limit(limit);
+    }
+}
+
+impl<T> Code<T> for Encoder
+where
+    T: AsRef<[u8]>,
+{
+    type Item = Bytes;
+
+    fn code(&mut self, item: T) -> io::Result<Option<Self::Item>> {
+        self.write_all(item.as_ref())?;
+        Ok(non_empty(self.get_mut().take()))
+    }
+
+    fn code_flush(&mut self, item: T) -> io::Result<Option<Self::Item>> {
+        self.write_all(item.as_ref())?;
+        self.flush()?;
+        Ok(non_empty(self.get_mut().take()))
+    }
+
+    fn code_eof(&mut self) -> io::Result<Option<Self::Item>> {
+        self.do_finish()?;
+        Ok(non_empty(self.get_mut().take()))
+    }
+
+    #[inline]
+    fn set_output_limit(&mut self, limit: usize) {
+        self.get_mut().set_limit(limit);
+    }
+}
+
+fn non_empty(b: Bytes) -> Option<Bytes> {
+    (!b.is_empty()).then_some(b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip(input: &[u8], opts: ZstdOptions, flush: bool) {
+        let mut encoder = encoder(opts);
+        let mut compressed = Vec::new();
+        for item in input.chunks(4096) {
+            let res = if flush {
+                Code::code_flush(&mut encoder, item)
+            } else {
+                Code::code(&mut encoder, item)
+            };
+            if let Some(b) = res.unwrap() {
+                compressed.extend_from_slice(&b);
+            }
+        }
+        compressed.extend_from_slice(&Code::<&[u8]>::code_eof(&mut encoder).unwrap().unwrap());
+
+        let mut decoder = decoder();
+        let mut decompressed = Vec::new();
+        // feed compressed data in small pieces to exercise buffering of decoder.
+        for item in compressed.chunks(7) {
+            if let Some(b) = Code::code(&mut decoder, item).unwrap() {
+                decompressed.extend_from_slice(&b);
+            }
+        }
+        if let Some(b) = Code::<&[u8]>::code_eof(&mut decoder).unwrap() {
+            decompressed.extend_from_slice(&b);
+        }
+
+        assert_eq!(decompressed, input);
+    }
+
+    #[test]
+    fn round_trips() {
+        let input = "xitca-web zstd round trip\n".repeat(10_000);
+
+        round_trip(b"", ZstdOptions::default(), false);
+        round_trip(input.as_bytes(), ZstdOptions::default(), false);
+        round_trip(input.as_bytes(), ZstdOptions::default(), true);
+        round_trip(
+            input.as_bytes(),
+            ZstdOptions::default().quality(19).window_log(10),
+            false,
+        );
+        round_trip(
+            input.as_bytes(),
+            ZstdOptions::default().quality(1).window_log(23),
+            false,
+        );
+    }
+
+    #[test]
+    fn decode_cli_frame() {
+        // "what is the goal of life" compressed by zstd cli.
+        const FRAME: &[u8] = &[
+            0x28, 0xb5, 0x2f, 0xfd, 0x24, 0x18, 0xc1, 0x00, 0x00, 0x77, 0x68, 0x61, 0x74, 0x20, 0x69, 0x73, 0x20, 0x74,
+            0x68, 0x65, 0x20, 0x67, 0x6f, 0x61, 0x6c, 0x20, 0x6f, 0x66, 0x20, 0x6c, 0x69, 0x66, 0x65, 0x4f, 0x19, 0x72,
+            0x0f,
+        ];
+
+        let mut decoder = decoder();
+        let b = Code::code(&mut decoder, FRAME).unwrap().unwrap();
+        assert_eq!(b.as_ref(), b"what is the goal of life");
+    }
+
+    #[test]
+    fn output_limit() {
+        let mut encoder = encoder(ZstdOptions::default());
+        let mut compressed = Code::code(&mut encoder, vec![0; 1024 * 1024])
+            .unwrap()
+            .unwrap_or_default()
+            .to_vec();
+        compressed.extend_from_slice(&Code::<&[u8]>::code_eof(&mut encoder).unwrap().unwrap());
+
+        let mut decoder = decoder();
+        Code::<&[u8]>::set_output_limit(&mut decoder, 1024);
+        let e = Code::code(&mut decoder, compressed).err().unwrap();
+        assert!(e.get_ref().unwrap().is::<crate::error::OutputLimitExceeded>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn quality_out_of_range() {
+        let _ = ZstdOptions::default().quality(20);
+    }
+
+    #[test]
+    #[should_panic]
+    fn window_log_out_of_range() {
+        let _ = ZstdOptions::default().window_log(24);
+    }
+}
@@ -5,7 +5,12 @@ use futures_core::stream::Stream;
 use http::header::HeaderMap;
 use memchr::memmem;
 
-use super::{content_disposition::ContentDisposition, error::MultipartError, Multipart};
+use super::{
+    content_disposition::ContentDisposition,
+    error::MultipartError,
+    storage::{Checksum, NoChecksum, SaveError, Storage},
+    Multipart,
+};
 
 pub struct Field<'a, S> {
     typ: FieldType,
@@ -116,6 +121,53 @@ where
     }
 }
 
+impl<S, T, E> Field<'_, S>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]> + 'static,
+{
+    /// Stream remaining data of field to given [Storage] and return the number of bytes written.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use futures_core::stream::Stream;
+    /// # use http_multipart::{Field, SaveError, Storage};
+    /// async fn save<S, St, E>(mut field: Field<'_, S>, storage: &mut St) -> Result<u64, SaveError<E, St::Error>>
+    /// where
+    ///     S: Stream<Item = Result<bytes::Bytes, E>>,
+    ///     St: Storage,
+    /// {
+    ///     field.save_to(storage).await
+    /// }
+    /// ```
+    pub async fn save_to<St>(&mut self, storage: &mut St) -> Result<u64, SaveError<E, St::Error>>
+    where
+        St: Storage,
+    {
+        self.save_to_with_checksum(storage, &mut NoChecksum).await
+    }
+
+    /// [Field::save_to] with a [Checksum] updated by every chunk written to storage.
+    pub async fn save_to_with_checksum<St, C>(
+        &mut self,
+        storage: &mut St,
+        checksum: &mut C,
+    ) -> Result<u64, SaveError<E, St::Error>>
+    where
+        St: Storage,
+        C: Checksum,
+    {
+        let mut len = 0;
+        while let Some(chunk) = self.try_next().await? {
+            len += chunk.len() as u64;
+            checksum.update(chunk.as_ref());
+            storage.write(chunk).await.map_err(SaveError::Storage)?;
+        }
+        storage.finish().await.map_err(SaveError::Storage)?;
+        Ok(len)
+    }
+}
+
 fn try_find_split_idx<T, E>(item: &T, boundary: &[u8], typ: &mut FieldType) -> Result<Option<usize>, MultipartError<E>>
 where
     T: AsRef<[u8]>,
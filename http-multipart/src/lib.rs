@@ -4,8 +4,13 @@ mod content_disposition;
 mod error;
 mod field;
 mod header;
+mod storage;
 
-pub use self::{error::MultipartError, field::Field};
+pub use self::{
+    error::MultipartError,
+    field::Field,
+    storage::{Checksum, SaveError, Storage},
+};
 
 use core::{future::poll_fn, pin::Pin};
 
@@ -398,4 +403,63 @@ mod test {
             MultipartError::BufferOverflow
         );
     }
+
+    #[test]
+    fn save_to() {
+        use core::hash::Hasher;
+        use std::collections::hash_map::DefaultHasher;
+
+        struct VecStorage(Vec<u8>, bool);
+
+        impl Storage for VecStorage {
+            type Error = ();
+
+            async fn write(&mut self, chunk: Bytes) -> Result<(), Self::Error> {
+                self.0.extend_from_slice(&chunk);
+                Ok(())
+            }
+
+            async fn finish(&mut self) -> Result<(), Self::Error> {
+                self.1 = true;
+                Ok(())
+            }
+        }
+
+        let body = b"\
+            --abbc761f78ff4d7cb7573b5a23f96ef0\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"foo.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            testdata\r\n\
+            --abbc761f78ff4d7cb7573b5a23f96ef0--\r\n\
+            ";
+
+        let mut req = Request::new(());
+        *req.method_mut() = Method::POST;
+        req.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("multipart/mixed; boundary=abbc761f78ff4d7cb7573b5a23f96ef0"),
+        );
+
+        let body = once_body(Bytes::copy_from_slice(body));
+        let multipart = multipart(&req, body).unwrap();
+        let mut multipart = pin!(multipart);
+
+        let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+
+        let mut storage = VecStorage(Vec::new(), false);
+        let mut checksum = DefaultHasher::new();
+        let len = field
+            .save_to_with_checksum(&mut storage, &mut checksum)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(len, 8);
+        assert_eq!(storage.0, b"testdata");
+        assert!(storage.1);
+
+        let mut expected = DefaultHasher::new();
+        expected.write(b"testdata");
+        assert_eq!(checksum.finish(), expected.finish());
+    }
 }
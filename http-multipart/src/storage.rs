@@ -0,0 +1,97 @@
+use core::{fmt, future::Future};
+
+use std::error;
+
+use bytes::Bytes;
+
+use super::error::MultipartError;
+
+/// Destination of field data streamed by [Field::save_to](crate::Field::save_to).
+///
+/// Chunks are passed in order as they arrive from request body so implementor can write them to
+/// disk or object storage without buffering the whole field in memory.
+pub trait Storage {
+    type Error;
+
+    /// write a chunk of field data.
+    fn write(&mut self, chunk: Bytes) -> impl Future<Output = Result<(), Self::Error>>;
+
+    /// called once when all field data has been written.
+    fn finish(&mut self) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+/// Checksum calculated over field data while streaming it to [Storage].
+///
+/// Implemented for all [core::hash::Hasher] types.
+pub trait Checksum {
+    fn update(&mut self, chunk: &[u8]);
+}
+
+// no-op checksum used by Field::save_to.
+pub(crate) struct NoChecksum;
+
+impl Checksum for NoChecksum {
+    #[inline]
+    fn update(&mut self, _: &[u8]) {}
+}
+
+impl<H> Checksum for H
+where
+    H: core::hash::Hasher + ?Sized,
+{
+    #[inline]
+    fn update(&mut self, chunk: &[u8]) {
+        self.write(chunk)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<W> Storage for W
+where
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    type Error = std::io::Error;
+
+    async fn write(&mut self, chunk: Bytes) -> Result<(), Self::Error> {
+        tokio::io::AsyncWriteExt::write_all(self, &chunk).await
+    }
+
+    async fn finish(&mut self) -> Result<(), Self::Error> {
+        tokio::io::AsyncWriteExt::flush(self).await
+    }
+}
+
+/// Error type of [Field::save_to](crate::Field::save_to).
+#[derive(Debug)]
+pub enum SaveError<E, SE> {
+    /// error from reading multipart field.
+    Multipart(MultipartError<E>),
+    /// error from writing to storage.
+    Storage(SE),
+}
+
+impl<E, SE> fmt::Display for SaveError<E, SE>
+where
+    E: fmt::Display,
+    SE: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Multipart(ref e) => fmt::Display::fmt(e, f),
+            Self::Storage(ref e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl<E, SE> error::Error for SaveError<E, SE>
+where
+    E: fmt::Debug + fmt::Display,
+    SE: fmt::Debug + fmt::Display,
+{
+}
+
+impl<E, SE> From<MultipartError<E>> for SaveError<E, SE> {
+    fn from(e: MultipartError<E>) -> Self {
+        Self::Multipart(e)
+    }
+}
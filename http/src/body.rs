@@ -48,10 +48,18 @@ pub enum RequestBody {
     H2(super::h2::RequestBody),
     #[cfg(feature = "http3")]
     H3(super::h3::RequestBody),
+    /// Body buffered in memory. e.g. constructed by server side for a request not read from io.
+    Bytes(Once<Bytes>),
     #[default]
     None,
 }
 
+impl From<Bytes> for RequestBody {
+    fn from(bytes: Bytes) -> Self {
+        Self::Bytes(Once::new(bytes))
+    }
+}
+
 impl Stream for RequestBody {
     type Item = Result<Bytes, BodyError>;
 
@@ -64,6 +72,7 @@ impl Stream for RequestBody {
             Self::H2(body) => Pin::new(body).poll_next(_cx),
             #[cfg(feature = "http3")]
             Self::H3(body) => Pin::new(body).poll_next(_cx),
+            Self::Bytes(body) => Pin::new(body).poll_next(_cx).map_err(|e| match e {}),
             Self::None => Poll::Ready(None),
         }
     }
@@ -6,6 +6,7 @@ use xitca_service::{EnclosedFactory, Service, ServiceExt};
 use super::{
     body::RequestBody,
     config::{HttpServiceConfig, DEFAULT_HEADER_LIMIT, DEFAULT_READ_BUF_LIMIT, DEFAULT_WRITE_BUF_LIMIT},
+    drain::DrainHandle,
     service::HttpService,
     tls,
     util::middleware::Logger,
@@ -36,6 +37,7 @@ pub struct HttpServiceBuilder<
 > {
     pub(crate) tls_factory: FA,
     pub(crate) config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
+    pub(crate) drain: Option<DrainHandle>,
     pub(crate) _body: PhantomData<fn(V, St)>,
 }
 
@@ -68,6 +70,7 @@ impl
         HttpServiceBuilder {
             tls_factory: tls::NoOpTlsAcceptorBuilder,
             config,
+            drain: None,
             _body: PhantomData,
         }
     }
@@ -85,6 +88,7 @@ impl
         HttpServiceBuilder {
             tls_factory: tls::NoOpTlsAcceptorBuilder,
             config: HttpServiceConfig::default(),
+            drain: None,
             _body: PhantomData,
         }
     }
@@ -102,6 +106,7 @@ impl
         HttpServiceBuilder {
             tls_factory: tls::NoOpTlsAcceptorBuilder,
             config: HttpServiceConfig::default(),
+            drain: None,
             _body: PhantomData,
         }
     }
@@ -124,10 +129,20 @@ impl<V, St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WR
         HttpServiceBuilder {
             tls_factory: self.tls_factory,
             config,
+            drain: self.drain,
             _body: PhantomData,
         }
     }
 
+    /// set handle for draining connections of constructed service. See [DrainHandle] for detail.
+    ///
+    /// Every service constructed from builder shares the same handle so draining applies to all
+    /// worker threads of server.
+    pub fn drain(mut self, handle: DrainHandle) -> Self {
+        self.drain = Some(handle);
+        self
+    }
+
     /// replace tls service. tls service is used for Http/1 and Http/2 protocols.
     pub fn with_tls<TlsF>(
         self,
@@ -136,10 +151,15 @@ impl<V, St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WR
         HttpServiceBuilder {
             tls_factory,
             config: self.config,
+            drain: self.drain,
             _body: PhantomData,
         }
     }
 
+    pub(crate) fn drain_handle(&self) -> DrainHandle {
+        self.drain.clone().unwrap_or_default()
+    }
+
     /// Finish builder with default logger.
     ///
     /// Would consume input.
@@ -169,6 +189,27 @@ impl<V, St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WR
         self.with_tls(tls::rustls::TlsAcceptorBuilder::new(config))
     }
 
+    #[cfg(feature = "openssl")]
+    /// use openssl as tls service and run tls handshakes on given [HandshakePool](crate::HandshakePool).
+    pub fn openssl_with_handshake_pool(
+        self,
+        acceptor: tls::openssl::TlsAcceptor,
+        pool: crate::HandshakePool,
+    ) -> HttpServiceBuilder<V, St, tls::openssl::TlsAcceptorBuilder, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>
+    {
+        self.with_tls(tls::openssl::TlsAcceptorBuilder::new(acceptor).handshake_pool(pool))
+    }
+
+    #[cfg(feature = "rustls")]
+    /// use rustls as tls service and run tls handshakes on given [HandshakePool](crate::HandshakePool).
+    pub fn rustls_with_handshake_pool(
+        self,
+        config: tls::rustls::RustlsConfig,
+        pool: crate::HandshakePool,
+    ) -> HttpServiceBuilder<V, St, tls::rustls::TlsAcceptorBuilder, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT> {
+        self.with_tls(tls::rustls::TlsAcceptorBuilder::new(config).handshake_pool(pool))
+    }
+
     #[cfg(feature = "rustls-uring")]
     /// use rustls on io-uring as tls service. io-uring (either with or without) is used for Http/1 protocol only.
     pub fn rustls_uring(
@@ -203,6 +244,6 @@ where
         self.tls_factory
             .call(())
             .await
-            .map(|tls_acceptor| HttpService::new(self.config, service, tls_acceptor))
+            .map(|tls_acceptor| HttpService::new(self.config, service, tls_acceptor, self.drain_handle()))
     }
 }
@@ -19,6 +19,41 @@ pub const DEFAULT_WRITE_BUF_LIMIT: usize = 8192 + 4096 * 100;
 /// 64 chosen for no particular reason.
 pub const DEFAULT_HEADER_LIMIT: usize = 64;
 
+/// Policy for request header field appearing more than once in one request head.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum DuplicateHeader {
+    /// Keep all values in the order they are received.
+    #[default]
+    Keep,
+    /// Join all values into one with comma separator. Values of `cookie` header are joined with
+    /// semicolon separator instead.
+    Join,
+    /// Keep the first value and drop the rest.
+    First,
+    /// Reject request with 400 Bad Request response.
+    Reject,
+}
+
+/// Emission order of response header fields written by Http/1 encoder.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum HeaderOrder {
+    /// Iteration order of response header map. Fields are in the order they are first inserted
+    /// as long as no field is removed from the map.
+    #[default]
+    Map,
+    /// Fields are sorted by name. Values of the same name keep their relative order. Fields
+    /// generated by encoder are emitted after them in fixed order of `connection`,
+    /// `transfer-encoding` or `content-length`, `date` and `server`.
+    Sorted,
+}
+
+// normalization applied to request headers after they are decoded.
+#[derive(Copy, Clone)]
+pub(crate) struct HeaderPolicy {
+    pub(crate) duplicate: DuplicateHeader,
+    pub(crate) trim: bool,
+}
+
 #[derive(Copy, Clone)]
 pub struct HttpServiceConfig<
     const HEADER_LIMIT: usize = DEFAULT_HEADER_LIMIT,
@@ -30,6 +65,16 @@ pub struct HttpServiceConfig<
     pub(crate) request_head_timeout: Duration,
     pub(crate) tls_accept_timeout: Duration,
     pub(crate) peek_protocol: bool,
+    pub(crate) h2c_upgrade: bool,
+    pub(crate) header_policy: HeaderPolicy,
+    pub(crate) keep_alive_header: bool,
+    pub(crate) lingering_close_timeout: Duration,
+    pub(crate) request_error_body: bool,
+    pub(crate) request_body_timeout: Duration,
+    pub(crate) request_body_min_bytes: usize,
+    pub(crate) date_header: bool,
+    pub(crate) server_header: Option<&'static str>,
+    pub(crate) header_order: HeaderOrder,
 }
 
 impl Default for HttpServiceConfig {
@@ -46,6 +91,19 @@ impl HttpServiceConfig {
             request_head_timeout: Duration::from_secs(5),
             tls_accept_timeout: Duration::from_secs(3),
             peek_protocol: false,
+            h2c_upgrade: false,
+            header_policy: HeaderPolicy {
+                duplicate: DuplicateHeader::Keep,
+                trim: false,
+            },
+            keep_alive_header: false,
+            lingering_close_timeout: Duration::from_secs(2),
+            request_error_body: true,
+            request_body_timeout: Duration::ZERO,
+            request_body_min_bytes: 1,
+            date_header: true,
+            server_header: None,
+            header_order: HeaderOrder::Map,
         }
     }
 }
@@ -122,12 +180,127 @@ impl<const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIM
     /// of alpn negotiation.
     ///
     /// This API is used to bypass alpn setting from tls and enable Http/2 protocol over
-    /// plain Tcp connection.
+    /// plain Tcp connection. Connection starts with Http/2 connection preface would be handled
+    /// as Http/2 with prior knowledge.
     pub fn peek_protocol(mut self) -> Self {
         self.peek_protocol = true;
         self
     }
 
+    /// Enable Http/1 request with `Upgrade: h2c` header be upgraded to Http/2 protocol over
+    /// plain Tcp connection.
+    ///
+    /// This API only take effect when both http1 and http2 features are enabled. Request with
+    /// body is not upgraded and would be handled as Http/1 request.
+    pub fn h2c_upgrade(mut self) -> Self {
+        self.h2c_upgrade = true;
+        self
+    }
+
+    /// Define how request header field appearing more than once is handled.
+    ///
+    /// The policy applies to Http/1 and Http/2 requests. See [DuplicateHeader] for default value
+    /// and behavior.
+    pub fn duplicate_header(mut self, policy: DuplicateHeader) -> Self {
+        self.header_policy.duplicate = policy;
+        self
+    }
+
+    /// Enable trimming of leading and trailing whitespace from request header values.
+    ///
+    /// Header names are always lower cased by decoders regardless of this setting.
+    pub fn trim_header_value(mut self) -> Self {
+        self.header_policy.trim = true;
+        self
+    }
+
+    /// Enable `Connection: keep-alive` header on every Http/1 response when connection is kept
+    /// alive afterwards.
+    ///
+    /// By default the header is only emitted to Http/1.0 request asking for keep-alive and
+    /// `Connection: close` header is emitted when connection is about to be closed. Response
+    /// carrying it's own `Connection` header is left untouched.
+    pub fn keep_alive_header(mut self) -> Self {
+        self.keep_alive_header = true;
+        self
+    }
+
+    /// Define duration of how long Http/1 connection keeps reading and discarding request bytes
+    /// after it's write half is shut down.
+    ///
+    /// Lingering close happens when connection is closed with request body not fully read or
+    /// after responding to malformed request. Closing a socket with unread bytes makes peer
+    /// receive a reset which can make it drop the response it has not read yet. Lingering ends
+    /// early when peer closes the connection.
+    ///
+    /// Default to 2 seconds. [Duration::ZERO] disables lingering close.
+    pub fn lingering_close_timeout(mut self, dur: Duration) -> Self {
+        self.lingering_close_timeout = dur;
+        self
+    }
+
+    /// Disable plain text body of error response Http/1 connection writes before closing on
+    /// malformed or timed out request.
+    ///
+    /// By default the response carries a short body describing it's status code. e.g.
+    /// `400 Bad Request`. With this setting only the response head is written.
+    pub fn disable_request_error_body(mut self) -> Self {
+        self.request_error_body = false;
+        self
+    }
+
+    /// Define duration of how long Http/1 connection waits for request body bytes while service is
+    /// reading request body.
+    ///
+    /// The duration is granted again every time at least [Self::request_body_min_bytes] bytes
+    /// are received. Peer sending request body slower than that is responded with
+    /// `408 Request Timeout` and the connection is closed.
+    ///
+    /// Default to [Duration::ZERO] where the timeout is disabled. The timeout is not applied to
+    /// io-uring dispatcher and request body read after service has produced it's response.
+    pub fn request_body_timeout(mut self, dur: Duration) -> Self {
+        self.request_body_timeout = dur;
+        self
+    }
+
+    /// Define minimum bytes of request body must be received within every
+    /// [Self::request_body_timeout] duration.
+    ///
+    /// Default to 1 where the timeout acts as an inactivity timeout.
+    ///
+    /// # Panics:
+    /// When `bytes` is 0.
+    pub fn request_body_min_bytes(mut self, bytes: usize) -> Self {
+        assert_ne!(bytes, 0, "minimum request body bytes must be greater than 0");
+        self.request_body_min_bytes = bytes;
+        self
+    }
+
+    /// Disable automatic `Date` header on Http/1 response not carrying one.
+    pub fn disable_date_header(mut self) -> Self {
+        self.date_header = false;
+        self
+    }
+
+    /// Enable automatic `Server` header with given value on Http/1 response not carrying one.
+    ///
+    /// # Panics:
+    /// When value is not valid header value.
+    pub fn server_header(mut self, value: &'static str) -> Self {
+        // validate early so encoder can write it as is.
+        let _ = crate::http::header::HeaderValue::from_static(value);
+        self.server_header = Some(value);
+        self
+    }
+
+    /// Define emission order of Http/1 response header fields.
+    ///
+    /// See [HeaderOrder] for default value and behavior.
+    pub fn header_order(mut self, order: HeaderOrder) -> Self {
+        self.header_order = order;
+        self
+    }
+
     #[doc(hidden)]
     /// A shortcut for mutating const generic params.
     pub fn mutate_const_generic<
@@ -143,6 +316,16 @@ impl<const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIM
             request_head_timeout: self.request_head_timeout,
             tls_accept_timeout: self.tls_accept_timeout,
             peek_protocol: self.peek_protocol,
+            h2c_upgrade: self.h2c_upgrade,
+            header_policy: self.header_policy,
+            keep_alive_header: self.keep_alive_header,
+            lingering_close_timeout: self.lingering_close_timeout,
+            request_error_body: self.request_error_body,
+            request_body_timeout: self.request_body_timeout,
+            request_body_min_bytes: self.request_body_min_bytes,
+            date_header: self.date_header,
+            server_header: self.server_header,
+            header_order: self.header_order,
         }
     }
 }
@@ -0,0 +1,167 @@
+//! connection draining for graceful reload of http service.
+
+use core::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use std::sync::{Arc, Mutex};
+
+use tokio::{
+    sync::Notify,
+    time::{sleep_until, timeout, Instant},
+};
+
+/// Handle for draining connections of [HttpServiceBuilder](crate::HttpServiceBuilder) constructed
+/// http service. Useful for process manager doing socket handoff to a new process without dropping
+/// in-flight requests.
+///
+/// After draining starts new connections are refused, http/1 connections are closed after their
+/// in-flight response with `Connection: close` header and http/2 connections are sent `GOAWAY`
+/// frame so no new stream is accepted. Connections still alive when deadline is reached are closed
+/// forcefully.
+///
+/// Handle is cheap to clone and shared by all services constructed from the same builder.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_http::{DrainHandle, HttpServiceBuilder};
+/// # async fn drain() {
+/// let handle = DrainHandle::new();
+/// let builder = HttpServiceBuilder::new().drain(handle.clone());
+///
+/// // on reload signal.
+/// let graceful = handle.drain(Duration::from_secs(30)).await;
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct DrainHandle(Arc<Inner>);
+
+#[derive(Default)]
+struct Inner {
+    draining: AtomicBool,
+    notify: Notify,
+    conns: AtomicUsize,
+    idle: Notify,
+    deadline: Mutex<Option<Instant>>,
+}
+
+impl DrainHandle {
+    /// Construct a new handle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if draining has started.
+    pub fn is_draining(&self) -> bool {
+        self.0.draining.load(Ordering::Acquire)
+    }
+
+    /// Number of connections currently alive.
+    pub fn connections(&self) -> usize {
+        self.0.conns.load(Ordering::Acquire)
+    }
+
+    /// Start draining and wait for all connections to finish with given timeout as deadline.
+    ///
+    /// Return true when all connections finished before deadline. Connections left are closed
+    /// forcefully at deadline.
+    pub async fn drain(&self, dur: Duration) -> bool {
+        {
+            let mut deadline = self.0.deadline.lock().unwrap();
+            if deadline.is_none() {
+                *deadline = Some(Instant::now() + dur);
+            }
+        }
+        self.0.draining.store(true, Ordering::Release);
+        self.0.notify.notify_waiters();
+
+        timeout(dur, async {
+            loop {
+                let idle = self.0.idle.notified();
+                if self.connections() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+
+    // track a connection alive until returned guard is dropped.
+    pub(crate) fn guard(&self) -> ConnGuard<'_> {
+        self.0.conns.fetch_add(1, Ordering::AcqRel);
+        ConnGuard(self)
+    }
+
+    // resolve when draining starts.
+    pub(crate) async fn draining(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            if self.is_draining() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    // resolve when deadline of draining is reached.
+    pub(crate) async fn expired(&self) {
+        self.draining().await;
+        let deadline = *self.0.deadline.lock().unwrap();
+        if let Some(deadline) = deadline {
+            sleep_until(deadline).await;
+        }
+    }
+}
+
+pub(crate) struct ConnGuard<'a>(&'a DrainHandle);
+
+impl Drop for ConnGuard<'_> {
+    fn drop(&mut self) {
+        if self.0 .0.conns.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0 .0.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn drain() {
+        let handle = DrainHandle::new();
+        assert!(!handle.is_draining());
+
+        let guard = handle.guard();
+        let guard2 = handle.guard();
+        assert_eq!(handle.connections(), 2);
+        drop(guard2);
+        assert_eq!(handle.connections(), 1);
+
+        let h = handle.clone();
+        let task = tokio::spawn(async move { h.drain(Duration::from_secs(10)).await });
+
+        handle.draining().await;
+        assert!(handle.is_draining());
+        drop(guard);
+
+        assert!(task.await.unwrap());
+        assert_eq!(handle.connections(), 0);
+    }
+
+    #[tokio::test]
+    async fn drain_deadline() {
+        let handle = DrainHandle::new();
+        let _guard = handle.guard();
+
+        let h = handle.clone();
+        let expired = async move { h.expired().await };
+        let (graceful, _) = tokio::join!(handle.drain(Duration::from_millis(10)), expired);
+        assert!(!graceful);
+        assert_eq!(handle.connections(), 1);
+    }
+}
@@ -121,6 +121,11 @@ impl RequestBodySender {
         }
     }
 
+    // return true when request body is dropped by it's consumer.
+    pub(super) fn is_dropped(&self) -> bool {
+        matches!(self.0, RequestBodyInner::Some(ref inner) if Rc::strong_count(inner) == 1)
+    }
+
     pub(super) fn feed_error(&mut self, e: io::Error) {
         if let Some(mut inner) = self.try_inner_infallible() {
             inner.feed_error(e);
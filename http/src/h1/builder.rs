@@ -18,6 +18,7 @@ impl<St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE
         HttpServiceBuilder {
             tls_factory: self.tls_factory,
             config: self.config,
+            drain: self.drain,
             _body: std::marker::PhantomData,
         }
     }
@@ -40,6 +41,7 @@ impl<St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE
         HttpServiceBuilder {
             tls_factory: self.tls_factory,
             config: self.config,
+            drain: self.drain,
             _body: std::marker::PhantomData,
         }
     }
@@ -55,7 +57,7 @@ where
 
     async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
         let tls_acceptor = self.tls_factory.call(()).await?;
-        Ok(H1Service::new(self.config, service, tls_acceptor))
+        Ok(H1Service::new(self.config, service, tls_acceptor, self.drain_handle()))
     }
 }
 
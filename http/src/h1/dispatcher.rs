@@ -9,26 +9,30 @@ use core::{
 use std::{io, net::SocketAddr};
 
 use futures_core::stream::Stream;
-use tracing::trace;
+use tracing::{trace, Instrument, Span};
 use xitca_io::io::{AsyncIo, Interest, Ready};
 use xitca_service::Service;
 use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 
 use crate::{
-    body::NoneBody,
+    body::{NoneBody, Once},
     bytes::{Bytes, EitherBuf},
-    config::HttpServiceConfig,
+    config::{HeaderPolicy, HttpServiceConfig},
     date::DateTime,
+    drain::DrainHandle,
     h1::{
         body::{RequestBody, RequestBodySender},
         error::Error,
     },
     http::{
+        const_header_value::TEXT_UTF8,
+        header::CONTENT_TYPE,
         response::{Parts, Response},
         StatusCode,
     },
     util::{
         buffered::{BufferedIo, ListWriteBuf, ReadBuf, WriteBuf},
+        span,
         timer::{KeepAlive, Timeout},
     },
 };
@@ -62,7 +66,86 @@ pub(crate) async fn run<
     config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
     service: &'a S,
     date: &'a D,
+    drain: &'a DrainHandle,
 ) -> Result<(), Error<S::Error, BE>>
+where
+    S: Service<ExtRequest<ReqB>, Response = Response<ResB>>,
+    ReqB: From<RequestBody>,
+    ResB: Stream<Item = Result<Bytes, BE>>,
+    St: AsyncIo,
+    D: DateTime,
+{
+    _run(io, addr, timer, config, service, date, drain, false)
+        .await
+        .map(|_| ())
+}
+
+/// Http/1 request with `Upgrade: h2c` header and it's remaining read buffer.
+/// It's handed to Http/2 dispatcher after `101 Switching Protocols` response is sent.
+#[cfg(feature = "http2")]
+pub(crate) struct H2cUpgrade {
+    pub(crate) req: ExtRequest<()>,
+    pub(crate) read_buf: crate::bytes::BytesMut,
+}
+
+#[cfg(not(feature = "http2"))]
+type H2cUpgrade = Infallible;
+
+/// same as [run] but returns [H2cUpgrade] when an upgrade request is accepted.
+/// upgrade is only accepted when [HttpServiceConfig::h2c_upgrade] is enabled.
+#[cfg(feature = "http2")]
+pub(crate) async fn run_h2c<
+    'a,
+    St,
+    S,
+    ReqB,
+    ResB,
+    BE,
+    D,
+    const HEADER_LIMIT: usize,
+    const READ_BUF_LIMIT: usize,
+    const WRITE_BUF_LIMIT: usize,
+>(
+    io: &'a mut St,
+    addr: SocketAddr,
+    timer: Pin<&'a mut KeepAlive>,
+    config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
+    service: &'a S,
+    date: &'a D,
+    drain: &'a DrainHandle,
+) -> Result<Option<H2cUpgrade>, Error<S::Error, BE>>
+where
+    S: Service<ExtRequest<ReqB>, Response = Response<ResB>>,
+    ReqB: From<RequestBody>,
+    ResB: Stream<Item = Result<Bytes, BE>>,
+    St: AsyncIo,
+    D: DateTime,
+{
+    _run(io, addr, timer, config, service, date, drain, config.h2c_upgrade).await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn _run<
+    'a,
+    St,
+    S,
+    ReqB,
+    ResB,
+    BE,
+    D,
+    const HEADER_LIMIT: usize,
+    const READ_BUF_LIMIT: usize,
+    const WRITE_BUF_LIMIT: usize,
+>(
+    io: &'a mut St,
+    addr: SocketAddr,
+    timer: Pin<&'a mut KeepAlive>,
+    config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
+    service: &'a S,
+    date: &'a D,
+    drain: &'a DrainHandle,
+    _h2c: bool,
+) -> Result<Option<H2cUpgrade>, Error<S::Error, BE>>
 where
     S: Service<ExtRequest<ReqB>, Response = Response<ResB>>,
     ReqB: From<RequestBody>,
@@ -76,9 +159,15 @@ where
         EitherBuf::Right(WriteBuf::<WRITE_BUF_LIMIT>::default())
     };
 
-    Dispatcher::new(io, addr, timer, config, service, date, write_buf)
-        .run()
-        .await
+    #[allow(unused_mut)]
+    let mut dispatcher = Dispatcher::new(io, addr, timer, config, service, date, drain, write_buf);
+
+    #[cfg(feature = "http2")]
+    {
+        dispatcher.h2c = _h2c;
+    }
+
+    dispatcher.run().await
 }
 
 /// Http/1 dispatcher
@@ -87,6 +176,19 @@ struct Dispatcher<'a, St, S, ReqB, W, D, const HEADER_LIMIT: usize, const READ_B
     timer: Timer<'a>,
     ctx: Context<'a, D, HEADER_LIMIT>,
     service: &'a S,
+    drain: &'a DrainHandle,
+    span: Span,
+    header_policy: HeaderPolicy,
+    lingering_close_timeout: Duration,
+    request_error_body: bool,
+    request_body_timeout: Duration,
+    request_body_min_bytes: usize,
+    // connection is closed with unread request bytes possibly left in socket.
+    linger: bool,
+    #[cfg(feature = "http2")]
+    h2c: bool,
+    #[cfg(feature = "http2")]
+    upgrade: Option<ExtRequest<()>>,
     _phantom: PhantomData<ReqB>,
 }
 
@@ -165,6 +267,7 @@ where
     W: H1BufWrite,
     D: DateTime,
 {
+    #[allow(clippy::too_many_arguments)]
     fn new<const WRITE_BUF_LIMIT: usize>(
         io: &'a mut St,
         addr: SocketAddr,
@@ -172,59 +275,126 @@ where
         config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
         service: &'a S,
         date: &'a D,
+        drain: &'a DrainHandle,
         write_buf: W,
     ) -> Self {
+        let mut ctx = Context::with_addr(addr, date);
+        if config.keep_alive_header {
+            ctx.enable_keep_alive_header();
+        }
+        if !config.date_header {
+            ctx.disable_date_header();
+        }
+        if let Some(value) = config.server_header {
+            ctx.set_server_header(value);
+        }
+        ctx.set_header_order(config.header_order);
+
         Self {
             io: BufferedIo::new(io, write_buf),
             timer: Timer::new(timer, config.keep_alive_timeout, config.request_head_timeout),
-            ctx: Context::with_addr(addr, date),
+            ctx,
             service,
+            drain,
+            span: span::connection(&addr, "http/1.1"),
+            header_policy: config.header_policy,
+            lingering_close_timeout: config.lingering_close_timeout,
+            request_error_body: config.request_error_body,
+            request_body_timeout: config.request_body_timeout,
+            request_body_min_bytes: config.request_body_min_bytes,
+            linger: false,
+            #[cfg(feature = "http2")]
+            h2c: false,
+            #[cfg(feature = "http2")]
+            upgrade: None,
             _phantom: PhantomData,
         }
     }
 
-    async fn run(mut self) -> Result<(), Error<S::Error, BE>> {
+    async fn run(mut self) -> Result<Option<H2cUpgrade>, Error<S::Error, BE>> {
         loop {
             match self._run().await {
                 Ok(_) => {}
                 Err(Error::KeepAliveExpire) => {
                     trace!(target: "h1_dispatcher", "Connection keep-alive expired. Shutting down");
-                    return Ok(());
+                    return Ok(None);
                 }
-                Err(Error::RequestTimeout) => self.request_error(|| status_only(StatusCode::REQUEST_TIMEOUT)),
+                Err(Error::RequestTimeout) => self.request_error(StatusCode::REQUEST_TIMEOUT),
                 Err(Error::Proto(ProtoError::HeaderTooLarge)) => {
-                    self.request_error(|| status_only(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE))
+                    self.request_error(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE)
                 }
-                Err(Error::Proto(_)) => self.request_error(|| status_only(StatusCode::BAD_REQUEST)),
+                Err(Error::Proto(_)) => self.request_error(StatusCode::BAD_REQUEST),
                 Err(e) => return Err(e),
             }
 
             // TODO: add timeout for drain write?
             self.io.drain_write().await?;
 
+            #[cfg(feature = "http2")]
+            if let Some(req) = self.upgrade.take() {
+                let read_buf = core::mem::take(&mut self.io.read_buf).into_inner();
+                return Ok(Some(H2cUpgrade { req, read_buf }));
+            }
+
             if self.ctx.is_connection_closed() {
-                return self.io.shutdown().await.map_err(Into::into);
+                self.io.shutdown().await?;
+                if self.linger {
+                    self.lingering_close().await;
+                }
+                return Ok(None);
             }
         }
     }
 
     async fn _run(&mut self) -> Result<(), Error<S::Error, BE>> {
         self.timer.update(self.ctx.date().now());
-        self.io
-            .read()
-            .timeout(self.timer.get())
-            .await
-            .map_err(|_| self.timer.map_to_err())??;
 
-        while let Some((req, decoder)) = self.ctx.decode_head::<READ_BUF_LIMIT>(&mut self.io.read_buf)? {
+        // idle connection is closed when draining starts. partial request in read buffer is
+        // waited for and served.
+        let idle = self.io.read_buf.is_empty();
+        let drain = self.drain;
+        let draining = async move {
+            if idle {
+                drain.draining().await
+            } else {
+                pending().await
+            }
+        };
+
+        match self.io.read().timeout(self.timer.get()).select(draining).await {
+            SelectOutput::A(res) => res.map_err(|_| self.timer.map_to_err())??,
+            SelectOutput::B(_) => {
+                trace!(target: "h1_dispatcher", "Connection draining. Shutting down");
+                self.ctx.set_close();
+                return Ok(());
+            }
+        }
+
+        while let Some((mut req, decoder)) = self.ctx.decode_head::<READ_BUF_LIMIT>(&mut self.io.read_buf)? {
             self.timer.reset_state();
 
+            if let Err(name) = self.header_policy.apply(req.headers_mut()) {
+                trace!(target: "h1_dispatcher", "Duplicate header field {name}. Rejecting request");
+                return Err(ProtoError::DuplicateHeader.into());
+            }
+
+            #[cfg(feature = "http2")]
+            if self.h2c && crate::h2::h2c::is_upgrade_request(&req) {
+                self.io.write_buf.write_buf_static(crate::h2::h2c::SWITCHING_PROTOCOLS);
+                self.upgrade = Some(req);
+                break;
+            }
+
+            let span = span::request(&self.span, &req);
+            *req.body_mut().span_mut() = span.clone();
+
             let (mut body_reader, body) = BodyReader::from_coding(decoder);
             let req = req.map(|ext| ext.map_body(|_| ReqB::from(body)));
 
             let (parts, body) = match self
                 .service
                 .call(req)
+                .instrument(span)
                 .select(self.request_body_handler(&mut body_reader))
                 .await
             {
@@ -234,6 +404,18 @@ where
                 SelectOutput::B(Ok(i)) => match i {},
             };
 
+            // request body dropped before fully read makes the connection can not be reused.
+            // decide it before encoding so response can carry `Connection: close` header.
+            if !body_reader.decoder.is_eof() && body_reader.tx.is_dropped() {
+                self.ctx.set_close();
+                self.linger = true;
+            }
+
+            // response carries `Connection: close` header when draining.
+            if self.drain.is_draining() {
+                self.ctx.set_close();
+            }
+
             let encoder = &mut self.encode_head(parts, &body)?;
             let mut body = pin!(body);
 
@@ -265,6 +447,7 @@ where
 
             if !body_reader.decoder.is_eof() {
                 self.ctx.set_close();
+                self.linger = true;
                 break;
             }
         }
@@ -288,9 +471,39 @@ where
             }
         }
 
+        if self.request_body_timeout.is_zero() {
+            loop {
+                body_reader.ready(&mut self.io.read_buf).await;
+                self.io.read().await?;
+            }
+        }
+
+        // deadline is armed when body reader is ready for bytes and re-armed after enough bytes
+        // are received.
+        let mut armed = false;
+        let mut received = 0;
+
         loop {
             body_reader.ready(&mut self.io.read_buf).await;
-            self.io.read().await?;
+
+            if !armed {
+                armed = true;
+                let deadline = self.ctx.date().now() + self.request_body_timeout;
+                self.timer.get().update(deadline);
+            }
+
+            let len = self.io.read_buf.len();
+            self.io
+                .read()
+                .timeout(self.timer.get())
+                .await
+                .map_err(|_| Error::RequestTimeout)??;
+
+            received += self.io.read_buf.len().saturating_sub(len);
+            if received >= self.request_body_min_bytes {
+                armed = false;
+                received = 0;
+            }
         }
     }
 
@@ -323,12 +536,37 @@ where
         }
     }
 
+    // read and discard request bytes after write half of io is shut down until peer closes the
+    // connection or lingering close timeout is reached.
+    #[cold]
+    #[inline(never)]
+    async fn lingering_close(&mut self) {
+        if self.lingering_close_timeout.is_zero() {
+            return;
+        }
+
+        let deadline = self.ctx.date().now() + self.lingering_close_timeout;
+        self.timer.get().update(deadline);
+
+        loop {
+            self.io.read_buf.clear();
+            match self.io.read().timeout(self.timer.get()).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(_)) => return,
+                Err(_) => {
+                    trace!(target: "h1_dispatcher", "Lingering close timeout reached. Shutting down");
+                    return;
+                }
+            }
+        }
+    }
+
     #[cold]
     #[inline(never)]
-    fn request_error(&mut self, func: impl FnOnce() -> Response<NoneBody<Bytes>>) {
+    fn request_error(&mut self, status: StatusCode) {
         self.ctx.set_close();
-        let (parts, body) = func().into_parts();
-        self.encode_head(parts, &body).expect("request_error must be correct");
+        self.linger = true;
+        encode_request_error(&mut self.ctx, status, self.request_error_body, &mut self.io.write_buf);
     }
 }
 
@@ -382,6 +620,39 @@ impl BodyReader {
 
 #[cold]
 #[inline(never)]
-pub(super) fn status_only(status: StatusCode) -> Response<NoneBody<Bytes>> {
-    Response::builder().status(status).body(NoneBody::default()).unwrap()
+// encode response for request dispatcher failed to hand to service. optionally with a plain text
+// body so peer has a hint why the connection is closed.
+pub(super) fn encode_request_error<D, W, const HEADER_LIMIT: usize>(
+    ctx: &mut Context<'_, D, HEADER_LIMIT>,
+    status: StatusCode,
+    with_body: bool,
+    buf: &mut W,
+) where
+    D: DateTime,
+    W: H1BufWrite,
+{
+    if !with_body {
+        let (parts, body) = Response::builder()
+            .status(status)
+            .body(NoneBody::<Bytes>::default())
+            .unwrap()
+            .into_parts();
+        ctx.encode_head(parts, &body, buf)
+            .expect("request_error must be correct");
+        return;
+    }
+
+    let reason = status.canonical_reason().unwrap_or_default();
+    let bytes = Bytes::from(format!("{} {reason}", status.as_str()));
+    let (parts, body) = Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, TEXT_UTF8)
+        .body(Once::new(bytes.clone()))
+        .unwrap()
+        .into_parts();
+    let mut encoder = ctx
+        .encode_head(parts, &body, buf)
+        .expect("request_error must be correct");
+    encoder.encode(bytes, buf);
+    encoder.encode_eof(buf);
 }
@@ -7,6 +7,7 @@ use core::{
     ops::{Deref, DerefMut},
     pin::{pin, Pin},
     task::{self, ready, Poll, Waker},
+    time::Duration,
 };
 
 use std::{
@@ -17,7 +18,7 @@ use std::{
 
 use futures_core::stream::Stream;
 use pin_project_lite::pin_project;
-use tracing::trace;
+use tracing::{trace, Instrument, Span};
 use xitca_io::{
     bytes::BytesMut,
     io_uring::{write_all, AsyncBufRead, AsyncBufWrite, IoBuf},
@@ -26,17 +27,19 @@ use xitca_service::Service;
 use xitca_unsafe_collection::futures::SelectOutput;
 
 use crate::{
-    body::NoneBody,
     bytes::Bytes,
-    config::HttpServiceConfig,
+    config::{HeaderPolicy, HttpServiceConfig},
     date::DateTime,
     h1::{body::RequestBody, error::Error},
     http::{response::Response, StatusCode},
-    util::timer::{KeepAlive, Timeout},
+    util::{
+        span,
+        timer::{KeepAlive, Timeout},
+    },
 };
 
 use super::{
-    dispatcher::{status_only, Timer},
+    dispatcher::{encode_request_error, Timer},
     proto::{
         codec::{ChunkResult, TransferCoding},
         context::Context,
@@ -53,6 +56,12 @@ pub(super) struct Dispatcher<'a, Io, S, ReqB, D, const H_LIMIT: usize, const R_L
     timer: Timer<'a>,
     ctx: Context<'a, D, H_LIMIT>,
     service: &'a S,
+    span: Span,
+    header_policy: HeaderPolicy,
+    lingering_close_timeout: Duration,
+    request_error_body: bool,
+    // connection is closed with unread request bytes possibly left in socket.
+    linger: bool,
     read_buf: BufOwned,
     write_buf: BufOwned,
     notify: Notify<BufOwned>,
@@ -125,11 +134,28 @@ where
         service: &'a S,
         date: &'a D,
     ) -> Self {
+        let mut ctx = Context::<_, H_LIMIT>::with_addr(addr, date);
+        if config.keep_alive_header {
+            ctx.enable_keep_alive_header();
+        }
+        if !config.date_header {
+            ctx.disable_date_header();
+        }
+        if let Some(value) = config.server_header {
+            ctx.set_server_header(value);
+        }
+        ctx.set_header_order(config.header_order);
+
         Self {
             io: Rc::new(io),
             timer: Timer::new(timer, config.keep_alive_timeout, config.request_head_timeout),
-            ctx: Context::<_, H_LIMIT>::with_addr(addr, date),
+            ctx,
             service,
+            span: span::connection(&addr, "http/1.1"),
+            header_policy: config.header_policy,
+            lingering_close_timeout: config.lingering_close_timeout,
+            request_error_body: config.request_error_body,
+            linger: false,
             read_buf: BufOwned::new(),
             write_buf: BufOwned::new(),
             notify: Notify::new(),
@@ -145,17 +171,22 @@ where
                     trace!(target: "h1_dispatcher", "Connection keep-alive expired. Shutting down");
                     return Ok(());
                 }
-                Err(Error::RequestTimeout) => self.request_error(|| status_only(StatusCode::REQUEST_TIMEOUT)),
+                Err(Error::RequestTimeout) => self.request_error(StatusCode::REQUEST_TIMEOUT),
                 Err(Error::Proto(ProtoError::HeaderTooLarge)) => {
-                    self.request_error(|| status_only(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE))
+                    self.request_error(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE)
                 }
-                Err(Error::Proto(_)) => self.request_error(|| status_only(StatusCode::BAD_REQUEST)),
+                Err(Error::Proto(_)) => self.request_error(StatusCode::BAD_REQUEST),
                 Err(e) => return Err(e),
             }
 
             self.write_buf.write_io(&*self.io).await?;
 
             if self.ctx.is_connection_closed() {
+                if self.linger {
+                    self.io.shutdown(Shutdown::Write)?;
+                    self.lingering_close().await;
+                    return Ok(());
+                }
                 return self.io.shutdown(Shutdown::Both).map_err(Into::into);
             }
         }
@@ -176,9 +207,17 @@ where
             return Ok(());
         }
 
-        while let Some((req, decoder)) = self.ctx.decode_head::<R_LIMIT>(&mut self.read_buf)? {
+        while let Some((mut req, decoder)) = self.ctx.decode_head::<R_LIMIT>(&mut self.read_buf)? {
             self.timer.reset_state();
 
+            if let Err(name) = self.header_policy.apply(req.headers_mut()) {
+                trace!(target: "h1_dispatcher", "Duplicate header field {name}. Rejecting request");
+                return Err(ProtoError::DuplicateHeader.into());
+            }
+
+            let span = span::request(&self.span, &req);
+            *req.body_mut().span_mut() = span.clone();
+
             let (waiter, body) = if decoder.is_eof() {
                 (None, RequestBody::default())
             } else {
@@ -196,7 +235,13 @@ where
 
             let req = req.map(|ext| ext.map_body(|_| ReqB::from(body)));
 
-            let (parts, body) = self.service.call(req).await.map_err(Error::Service)?.into_parts();
+            let (parts, body) = self
+                .service
+                .call(req)
+                .instrument(span)
+                .await
+                .map_err(Error::Service)?
+                .into_parts();
 
             let mut encoder = self.ctx.encode_head(parts, &body, &mut *self.write_buf)?;
 
@@ -240,6 +285,7 @@ where
                     Some(read_buf) => self.read_buf = read_buf,
                     None => {
                         self.ctx.set_close();
+                        self.linger = true;
                         break;
                     }
                 }
@@ -249,14 +295,40 @@ where
         Ok(())
     }
 
+    // read and discard request bytes after write half of io is shut down until peer closes the
+    // connection or lingering close timeout is reached.
+    #[cold]
+    #[inline(never)]
+    async fn lingering_close(&mut self) {
+        if self.lingering_close_timeout.is_zero() {
+            return;
+        }
+
+        let deadline = self.ctx.date().now() + self.lingering_close_timeout;
+        self.timer.get().update(deadline);
+
+        // read buffer may be taken by request body.
+        self.read_buf = BufOwned::new();
+
+        loop {
+            self.read_buf.clear();
+            match self.read_buf.read_io(&*self.io).timeout(self.timer.get()).await {
+                Ok(Ok(n)) if n > 0 => {}
+                Ok(_) => return,
+                Err(_) => {
+                    trace!(target: "h1_dispatcher", "Lingering close timeout reached. Shutting down");
+                    return;
+                }
+            }
+        }
+    }
+
     #[cold]
     #[inline(never)]
-    fn request_error(&mut self, func: impl FnOnce() -> Response<NoneBody<Bytes>>) {
+    fn request_error(&mut self, status: StatusCode) {
         self.ctx.set_close();
-        let (parts, body) = func().into_parts();
-        self.ctx
-            .encode_head(parts, &body, &mut *self.write_buf)
-            .expect("request_error must be correct");
+        self.linger = true;
+        encode_request_error(&mut self.ctx, status, self.request_error_body, &mut *self.write_buf);
     }
 }
 
@@ -11,6 +11,7 @@ mod service;
 
 pub use self::body::RequestBody;
 pub use self::error::Error;
+pub use self::proto::header::HeaderCase;
 pub use self::service::H1Service;
 
 #[cfg(feature = "io-uring")]
@@ -2,7 +2,10 @@ use core::mem;
 
 use std::net::SocketAddr;
 
-use crate::http::{header::HeaderMap, Extensions};
+use crate::{
+    config::HeaderOrder,
+    http::{header::HeaderMap, Extensions},
+};
 
 /// Context is connection specific struct contain states for processing.
 pub struct Context<'a, D, const HEADER_LIMIT: usize> {
@@ -13,6 +16,10 @@ pub struct Context<'a, D, const HEADER_LIMIT: usize> {
     // http extensions reused by next request.
     exts: Extensions,
     date: &'a D,
+    keep_alive_header: bool,
+    date_header: bool,
+    server_header: Option<&'static str>,
+    header_order: HeaderOrder,
 }
 
 // A set of state for current request that are used after request's ownership is passed
@@ -28,6 +35,8 @@ impl ContextState {
     const HEAD: u8 = 0b_0100;
     // Enable when current connection is supposed to be closed after current response is sent.
     const CLOSE: u8 = 0b_1000;
+    // Enable when current request is Http/1.0.
+    const HTTP_10: u8 = 0b1_0000;
 
     const fn new() -> Self {
         Self(0)
@@ -66,9 +75,39 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
             header: None,
             exts: Extensions::new(),
             date,
+            keep_alive_header: false,
+            date_header: true,
+            server_header: None,
+            header_order: HeaderOrder::Map,
         }
     }
 
+    /// Emit `Connection: keep-alive` header on every response when connection is kept alive.
+    #[inline]
+    pub fn enable_keep_alive_header(&mut self) {
+        self.keep_alive_header = true;
+    }
+
+    /// Skip `Date` header on response not carrying one.
+    #[inline]
+    pub fn disable_date_header(&mut self) {
+        self.date_header = false;
+    }
+
+    /// Emit `Server` header with given value on response not carrying one.
+    ///
+    /// Value must be valid header value.
+    #[inline]
+    pub fn set_server_header(&mut self, value: &'static str) {
+        self.server_header = Some(value);
+    }
+
+    /// Set emission order of response header fields.
+    #[inline]
+    pub fn set_header_order(&mut self, order: HeaderOrder) {
+        self.header_order = order;
+    }
+
     /// Get Date type from Context.
     #[inline]
     pub fn date(&self) -> &D {
@@ -127,6 +166,12 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
         self.state.insert(ContextState::HEAD)
     }
 
+    /// Set Context's state to Http/1.0 request received.
+    #[inline]
+    pub fn set_http_10(&mut self) {
+        self.state.insert(ContextState::HTTP_10)
+    }
+
     /// Set Context's state to Close.
     #[inline]
     pub fn set_close(&mut self) {
@@ -157,6 +202,37 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
         self.state.contains(ContextState::HEAD)
     }
 
+    /// Get Http/1.0 request state.
+    #[inline]
+    pub const fn is_http_10(&self) -> bool {
+        self.state.contains(ContextState::HTTP_10)
+    }
+
+    /// Return true if `Connection: keep-alive` header should be emitted for response of current
+    /// request.
+    #[inline]
+    pub const fn is_keep_alive_header(&self) -> bool {
+        !self.is_connection_closed() && (self.keep_alive_header || self.is_http_10())
+    }
+
+    /// Return true if `Date` header should be emitted for response not carrying one.
+    #[inline]
+    pub const fn is_date_header(&self) -> bool {
+        self.date_header
+    }
+
+    /// Get value of `Server` header emitted for response not carrying one.
+    #[inline]
+    pub const fn server_header(&self) -> Option<&'static str> {
+        self.server_header
+    }
+
+    /// Get emission order of response header fields.
+    #[inline]
+    pub const fn header_order(&self) -> HeaderOrder {
+        self.header_order
+    }
+
     /// Return true if connection type is `Connection: Close`.
     #[inline]
     pub const fn is_connection_closed(&self) -> bool {
@@ -55,6 +55,7 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
                     // Default ctype is KeepAlive so set_ctype is skipped here.
                     Version::HTTP_11
                 } else {
+                    self.set_http_10();
                     self.set_close();
                     Version::HTTP_10
                 };
@@ -216,6 +217,32 @@ mod test {
         assert!(!ctx.is_connection_closed());
     }
 
+    #[test]
+    fn http_10_keep_alive() {
+        let mut ctx = Context::<_, 4>::new(&());
+
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.0\r\n\r\n"[..]);
+        let _ = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        assert!(ctx.is_http_10());
+        assert!(ctx.is_connection_closed());
+        assert!(!ctx.is_keep_alive_header());
+
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.0\r\nConnection: keep-alive\r\n\r\n"[..]);
+        let _ = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        assert!(!ctx.is_connection_closed());
+        assert!(ctx.is_keep_alive_header());
+
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\n\r\n"[..]);
+        let _ = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        assert!(!ctx.is_http_10());
+        assert!(!ctx.is_keep_alive_header());
+
+        ctx.enable_keep_alive_header();
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\n\r\n"[..]);
+        let _ = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        assert!(ctx.is_keep_alive_header());
+    }
+
     #[test]
     fn transfer_encoding() {
         let mut ctx = Context::<_, 4>::new(&());
@@ -4,15 +4,22 @@ use tracing::{debug, warn};
 use crate::{
     body::BodySize,
     bytes::{Bytes, BytesMut},
+    config::HeaderOrder,
     date::DateTime,
     http::{
-        header::{HeaderMap, CONNECTION, CONTENT_LENGTH, DATE, TE, TRANSFER_ENCODING, UPGRADE},
+        header::{HeaderMap, HeaderName, CONNECTION, CONTENT_LENGTH, DATE, SERVER, TE, TRANSFER_ENCODING, UPGRADE},
         response::Parts,
         Extensions, StatusCode, Version,
     },
 };
 
-use super::{buf_write::H1BufWrite, codec::TransferCoding, context::Context, error::ProtoError, header};
+use super::{
+    buf_write::H1BufWrite,
+    codec::TransferCoding,
+    context::Context,
+    error::ProtoError,
+    header::{self, HeaderCase},
+};
 
 pub const CONTINUE: &[u8; 25] = b"HTTP/1.1 100 Continue\r\n\r\n";
 
@@ -110,10 +117,20 @@ where
     {
         let size = BodySize::from_stream(body);
 
-        let mut skip_date = false;
+        let mut skip_date = !self.is_date_header();
+
+        let mut skip_server = false;
+
+        let mut skip_connection = false;
+
+        let case = extensions.remove::<HeaderCase>();
 
         let mut encoding = TransferCoding::eof();
 
+        if self.header_order() == HeaderOrder::Sorted {
+            sort_headers(&mut headers);
+        }
+
         // use the shortest header name as default
         let mut name = TE;
 
@@ -145,9 +162,11 @@ where
                         continue;
                     }
                     self.try_set_close_from_header(&value)?;
+                    skip_connection = true;
                 }
                 UPGRADE => encoding = TransferCoding::upgrade(),
                 DATE => skip_date = true,
+                SERVER => skip_server = true,
                 _ => {}
             }
 
@@ -157,6 +176,12 @@ where
                 buf.reserve(value.len() + 2);
                 buf.extend_from_slice(b", ");
                 buf.extend_from_slice(value);
+            } else if let Some(ref case) = case {
+                buf.reserve(name.as_str().len() + value.len() + 4);
+                buf.extend_from_slice(b"\r\n");
+                case.write_name(&name, buf);
+                buf.extend_from_slice(b": ");
+                buf.extend_from_slice(value);
             } else {
                 let name = name.as_str().as_bytes();
                 buf.reserve(name.len() + value.len() + 4);
@@ -167,8 +192,14 @@ where
             }
         }
 
-        if self.is_connection_closed() {
-            buf.extend_from_slice(b"\r\nconnection: close");
+        if !skip_connection {
+            if self.is_connection_closed() {
+                write_header_name(buf, case.as_ref(), CONNECTION, b"\r\nconnection: ");
+                buf.extend_from_slice(b"close");
+            } else if self.is_keep_alive_header() {
+                write_header_name(buf, case.as_ref(), CONNECTION, b"\r\nconnection: ");
+                buf.extend_from_slice(b"keep-alive");
+            }
         }
 
         // encode transfer-encoding or content-length
@@ -178,7 +209,8 @@ where
                     encoding = TransferCoding::eof();
                 }
                 BodySize::Stream => {
-                    buf.extend_from_slice(b"\r\ntransfer-encoding: chunked");
+                    write_header_name(buf, case.as_ref(), TRANSFER_ENCODING, b"\r\ntransfer-encoding: ");
+                    buf.extend_from_slice(b"chunked");
                     encoding = TransferCoding::encode_chunked();
                 }
                 BodySize::Sized(size) => {
@@ -186,7 +218,7 @@ where
                     let buffer = buffer.format(size).as_bytes();
 
                     buf.reserve(buffer.len() + 18);
-                    buf.extend_from_slice(b"\r\ncontent-length: ");
+                    write_header_name(buf, case.as_ref(), CONTENT_LENGTH, b"\r\ncontent-length: ");
                     buf.extend_from_slice(buffer);
 
                     encoding = TransferCoding::length(size as u64);
@@ -206,10 +238,17 @@ where
         // set date header if there is not any.
         if !skip_date {
             buf.reserve(D::DATE_VALUE_LENGTH + 12);
-            buf.extend_from_slice(b"\r\ndate: ");
+            write_header_name(buf, case.as_ref(), DATE, b"\r\ndate: ");
             self.date().with_date(|slice| buf.extend_from_slice(slice));
         }
 
+        // set server header if configured and there is not any.
+        if let (false, Some(value)) = (skip_server, self.server_header()) {
+            buf.reserve(value.len() + 10);
+            write_header_name(buf, case.as_ref(), SERVER, b"\r\nserver: ");
+            buf.extend_from_slice(value.as_bytes());
+        }
+
         buf.extend_from_slice(b"\r\n\r\n");
 
         // put header map back to cache.
@@ -223,6 +262,39 @@ where
     }
 }
 
+// re-insert header fields sorted by name. header map iterates in insertion order when no field is
+// removed from it.
+#[cold]
+#[inline(never)]
+fn sort_headers(headers: &mut HeaderMap) {
+    let mut fields = Vec::with_capacity(headers.len());
+    let mut name = TE;
+    for (next_name, value) in headers.drain() {
+        if let Some(next_name) = next_name {
+            name = next_name;
+        }
+        fields.push((name.clone(), value));
+    }
+    // stable sort keeps relative order of values with the same name.
+    fields.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+    for (name, value) in fields {
+        headers.append(name, value);
+    }
+}
+
+// write header name of given lower case default line when no casing is set.
+#[inline]
+fn write_header_name(buf: &mut BytesMut, case: Option<&HeaderCase>, name: HeaderName, default: &[u8]) {
+    match case {
+        None => buf.extend_from_slice(default),
+        Some(case) => {
+            buf.extend_from_slice(b"\r\n");
+            case.write_name(&name, buf);
+            buf.extend_from_slice(b": ");
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
@@ -268,4 +340,128 @@ mod test {
             })
             .await
     }
+
+    #[tokio::test]
+    async fn header_case() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let date = DateTimeService::new();
+                let mut ctx = Context::<_, 64>::new(date.get());
+
+                let mut res = Response::new(BoxStream::new(Once::new(Bytes::from_static(b"996"))));
+
+                res.headers_mut().insert("x-custom-id", HeaderValue::from_static("251"));
+                res.headers_mut()
+                    .insert("x-powered-by", HeaderValue::from_static("xitca"));
+
+                let mut case = HeaderCase::title_case();
+                case.insert("X-CUSTOM-id").unwrap();
+                res.extensions_mut().insert(case);
+
+                let (parts, body) = res.into_parts();
+
+                let mut buf = BytesMut::new();
+                ctx.encode_head(parts, &body, &mut buf).unwrap();
+
+                let head = std::str::from_utf8(&buf).unwrap();
+                assert!(head.contains("\r\nX-CUSTOM-id: 251\r\n"));
+                assert!(head.contains("\r\nX-Powered-By: xitca\r\n"));
+                assert!(head.contains("\r\nContent-Length: 3\r\n"));
+                assert!(head.contains("\r\nDate: "));
+
+                // casing is opt-in per response.
+                let res = Response::new(BoxStream::new(Once::new(Bytes::from_static(b"996"))));
+                let (parts, body) = res.into_parts();
+
+                let mut buf = BytesMut::new();
+                ctx.encode_head(parts, &body, &mut buf).unwrap();
+
+                let head = std::str::from_utf8(&buf).unwrap();
+                assert!(head.contains("\r\ncontent-length: 3\r\n"));
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn connection_header() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let date = DateTimeService::new();
+                let mut ctx = Context::<_, 64>::new(date.get());
+
+                let encode = |ctx: &mut Context<'_, _, 64>, res: Response<BoxStream>| {
+                    let (parts, body) = res.into_parts();
+                    let mut buf = BytesMut::new();
+                    ctx.encode_head(parts, &body, &mut buf).unwrap();
+                    String::from_utf8(buf.to_vec()).unwrap()
+                };
+
+                let res = || Response::new(BoxStream::new(Once::new(Bytes::new())));
+
+                let head = encode(&mut ctx, res());
+                assert!(!head.contains("connection"));
+
+                ctx.enable_keep_alive_header();
+                let head = encode(&mut ctx, res());
+                assert!(head.contains("\r\nconnection: keep-alive\r\n"));
+
+                // connection header of response is not duplicated.
+                let mut close = res();
+                close
+                    .headers_mut()
+                    .insert(CONNECTION, HeaderValue::from_static("close"));
+                let head = encode(&mut ctx, close);
+                assert_eq!(head.matches("connection").count(), 1);
+                assert!(head.contains("\r\nconnection: close\r\n"));
+                assert!(ctx.is_connection_closed());
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn header_order_and_injection() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let date = DateTimeService::new();
+                let mut ctx = Context::<_, 64>::new(date.get());
+
+                let encode = |ctx: &mut Context<'_, _, 64>, res: Response<BoxStream>| {
+                    let (parts, body) = res.into_parts();
+                    let mut buf = BytesMut::new();
+                    ctx.encode_head(parts, &body, &mut buf).unwrap();
+                    String::from_utf8(buf.to_vec()).unwrap()
+                };
+
+                let res = || {
+                    let mut res = Response::new(BoxStream::new(Once::new(Bytes::from_static(b"996"))));
+                    res.headers_mut().insert("x-b", HeaderValue::from_static("1"));
+                    res.headers_mut().insert("x-a", HeaderValue::from_static("2"));
+                    res.headers_mut().append("x-b", HeaderValue::from_static("3"));
+                    res.headers_mut().insert("x-c", HeaderValue::from_static("4"));
+                    res
+                };
+
+                let head = encode(&mut ctx, res());
+                assert!(head.contains("\r\nx-b: 1, 3\r\nx-a: 2\r\nx-c: 4\r\n"));
+                assert!(head.contains("\r\ndate: "));
+                assert!(!head.contains("server"));
+
+                ctx.set_header_order(HeaderOrder::Sorted);
+                ctx.disable_date_header();
+                ctx.set_server_header("xitca");
+                let head = encode(&mut ctx, res());
+                assert!(
+                    head.ends_with("\r\nx-a: 2\r\nx-b: 1, 3\r\nx-c: 4\r\ncontent-length: 3\r\nserver: xitca\r\n\r\n")
+                );
+                assert!(!head.contains("date"));
+
+                // server header of response is not duplicated.
+                let mut server = res();
+                server.headers_mut().insert(SERVER, HeaderValue::from_static("nginx"));
+                let head = encode(&mut ctx, server);
+                assert_eq!(head.matches("server").count(), 1);
+                assert!(head.contains("\r\nserver: nginx\r\n"));
+            })
+            .await
+    }
 }
@@ -5,6 +5,7 @@ pub enum ProtoError {
     HeaderName,
     HeaderValue,
     HeaderTooLarge,
+    DuplicateHeader,
     Method,
     Uri,
     NewLine,
@@ -1,12 +1,17 @@
 use core::mem::MaybeUninit;
 
+use std::collections::HashMap;
+
 use xitca_unsafe_collection::uninit::PartialInit;
 
 use httparse::Header;
 
 use super::error::ProtoError;
 
-use crate::http::header::HeaderValue;
+use crate::{
+    bytes::BytesMut,
+    http::header::{HeaderName, HeaderValue, InvalidHeaderName},
+};
 
 #[derive(Clone, Copy)]
 pub struct HeaderIndex {
@@ -37,3 +42,52 @@ pub(super) fn parse_content_length(val: &HeaderValue) -> Result<u64, ProtoError>
         .and_then(|v| v.parse().ok())
         .ok_or(ProtoError::HeaderValue)
 }
+
+/// Casing of header names written on the wire for http/1 response.
+///
+/// Header names are written in lower case by default. Insert this type into response extensions
+/// to opt-in preserving or mapping the casing for legacy clients doing case-sensitive matching of
+/// header names. Header names written by encoder itself(`content-length`, `date` etc) are mapped
+/// in the same way. It has no effect on other http versions.
+#[derive(Clone, Debug, Default)]
+pub struct HeaderCase {
+    title_case: bool,
+    names: HashMap<HeaderName, Box<str>>,
+}
+
+impl HeaderCase {
+    /// Construct an empty casing where unmapped header names are written in lower case.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct an empty casing where unmapped header names are written in Title-Case.
+    /// (e.g. `content-length` to `Content-Length`)
+    pub fn title_case() -> Self {
+        Self {
+            title_case: true,
+            names: HashMap::new(),
+        }
+    }
+
+    /// Preserve the casing of given header name as is.
+    pub fn insert(&mut self, name: &str) -> Result<(), InvalidHeaderName> {
+        let key = HeaderName::from_bytes(name.as_bytes())?;
+        self.names.insert(key, Box::from(name));
+        Ok(())
+    }
+
+    pub(super) fn write_name(&self, name: &HeaderName, buf: &mut BytesMut) {
+        match self.names.get(name) {
+            Some(name) => buf.extend_from_slice(name.as_bytes()),
+            None if self.title_case => {
+                let mut upper = true;
+                for &b in name.as_str().as_bytes() {
+                    buf.extend_from_slice(&[if upper { b.to_ascii_uppercase() } else { b }]);
+                    upper = b == b'-';
+                }
+            }
+            None => buf.extend_from_slice(name.as_str().as_bytes()),
+        }
+    }
+}
@@ -33,19 +33,30 @@ where
     type Error = HttpServiceError<S::Error, BE>;
 
     async fn call(&self, (io, addr): (St, SocketAddr)) -> Result<Self::Response, Self::Error> {
-        // at this stage keep-alive timer is used to tracks tls accept timeout.
-        let mut timer = pin!(self.keep_alive());
-
-        let mut io = self
-            .tls_acceptor
-            .call(io)
-            .timeout(timer.as_mut())
-            .await
-            .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
-
-        super::dispatcher::run(&mut io, addr, timer, self.config, &self.service, self.date.get())
+        self.drained(async {
+            // at this stage keep-alive timer is used to tracks tls accept timeout.
+            let mut timer = pin!(self.keep_alive());
+
+            let mut io = self
+                .tls_acceptor
+                .call(io)
+                .timeout(timer.as_mut())
+                .await
+                .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
+
+            super::dispatcher::run(
+                &mut io,
+                addr,
+                timer,
+                self.config,
+                &self.service,
+                self.date.get(),
+                &self.drain,
+            )
             .await
             .map_err(Into::into)
+        })
+        .await
     }
 }
 
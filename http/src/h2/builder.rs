@@ -14,6 +14,6 @@ where
 
     async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
         let tls_acceptor = self.tls_factory.call(()).await?;
-        Ok(H2Service::new(self.config, service, tls_acceptor))
+        Ok(H2Service::new(self.config, service, tls_acceptor, self.drain_handle()))
     }
 }
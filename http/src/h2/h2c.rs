@@ -0,0 +1,462 @@
+//! cleartext http/2 (h2c) support.
+//!
+//! h2c can be established with prior knowledge where client start a connection with http/2
+//! preface directly, or with `Upgrade: h2c` header from a http/1 request. For the latter the
+//! upgraded request is encoded as a synthetic HEADERS frame on stream 1 and fed to http/2
+//! connection right after client's connection preface.
+
+use core::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use std::io;
+
+use xitca_io::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{
+    bytes::{BufMut, Bytes, BytesMut},
+    http::{
+        header::{HeaderMap, HeaderName, CONNECTION, CONTENT_LENGTH, HOST, TE, TRANSFER_ENCODING, UPGRADE},
+        Request, Version,
+    },
+};
+
+/// http/2 connection preface send by client.
+pub(crate) const PREFACE: &[u8; 24] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// response head for accepted `Upgrade: h2c` request.
+pub(crate) const SWITCHING_PROTOCOLS: &[u8] =
+    b"HTTP/1.1 101 Switching Protocols\r\nconnection: upgrade\r\nupgrade: h2c\r\n\r\n";
+
+const HTTP2_SETTINGS: HeaderName = HeaderName::from_static("http2-settings");
+
+const FRAME_HEAD_LEN: usize = 9;
+const DEFAULT_MAX_FRAME_SIZE: usize = 16_384;
+
+const TYPE_HEADERS: u8 = 0x1;
+const TYPE_CONTINUATION: u8 = 0x9;
+
+const FLAG_END_STREAM: u8 = 0x1;
+const FLAG_END_HEADERS: u8 = 0x4;
+
+const SETTINGS_ENABLE_PUSH: u16 = 0x2;
+const SETTINGS_INITIAL_WINDOW_SIZE: u16 = 0x4;
+const SETTINGS_MAX_FRAME_SIZE: u16 = 0x5;
+
+/// check if given request is a valid h2c upgrade request that can be accepted.
+///
+/// request with body or malformed `HTTP2-Settings` header is not upgraded and would be handled
+/// as http/1 request.
+pub(crate) fn is_upgrade_request<B>(req: &Request<B>) -> bool {
+    let headers = req.headers();
+
+    let mut settings = headers.get_all(HTTP2_SETTINGS).iter();
+
+    req.version() == Version::HTTP_11
+        && header_contains(headers, &UPGRADE, "h2c")
+        && header_contains(headers, &CONNECTION, "upgrade")
+        && matches!((settings.next(), settings.next()), (Some(v), None) if decode_settings(v.as_bytes()).is_some())
+        && !headers.contains_key(TRANSFER_ENCODING)
+        && headers.get(CONTENT_LENGTH).map(|v| v == "0").unwrap_or(true)
+}
+
+/// decode and validate `HTTP2-Settings` header value. See RFC 7540 section 3.2.1
+///
+/// value is the base64url encoded payload of a SETTINGS frame. on success the decoded
+/// `(identifier, value)` pairs are returned.
+///
+/// settings are only validated here. client is required to send it's SETTINGS frame right after
+/// connection preface and http/2 connection applies client settings from it.
+pub(crate) fn decode_settings(value: &[u8]) -> Option<Vec<(u16, u32)>> {
+    let payload = decode_base64url(value)?;
+
+    if payload.len() % 6 != 0 {
+        return None;
+    }
+
+    payload
+        .chunks_exact(6)
+        .map(|entry| {
+            let id = u16::from_be_bytes([entry[0], entry[1]]);
+            let val = u32::from_be_bytes([entry[2], entry[3], entry[4], entry[5]]);
+
+            let valid = match id {
+                SETTINGS_ENABLE_PUSH => val <= 1,
+                SETTINGS_INITIAL_WINDOW_SIZE => val <= i32::MAX as u32,
+                SETTINGS_MAX_FRAME_SIZE => (1 << 14..1 << 24).contains(&val),
+                // unknown settings must be ignored.
+                _ => true,
+            };
+
+            valid.then_some((id, val))
+        })
+        .collect()
+}
+
+// base64url decoding without padding. See RFC 4648 section 5
+fn decode_base64url(value: &[u8]) -> Option<Vec<u8>> {
+    // trailing padding is tolerated.
+    let value = match value.iter().position(|b| *b == b'=') {
+        Some(idx) if value[idx..].iter().all(|b| *b == b'=') => &value[..idx],
+        Some(_) => return None,
+        None => value,
+    };
+
+    if value.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(value.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+
+    for b in value {
+        let v = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'-' => 62,
+            b'_' => 63,
+            _ => return None,
+        };
+
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+
+    Some(out)
+}
+
+fn header_contains(headers: &HeaderMap, name: &HeaderName, token: &str) -> bool {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|v| v.trim().eq_ignore_ascii_case(token))
+}
+
+/// check if given bytes is a prefix of or starts with http/2 connection preface.
+pub(crate) fn is_preface(buf: &[u8]) -> bool {
+    let len = core::cmp::min(buf.len(), PREFACE.len());
+    !buf.is_empty() && buf[..len] == PREFACE[..len]
+}
+
+/// encode request head to HEADERS(and CONTINUATION if needed) frame(s) on stream 1.
+///
+/// header fields are encoded as literal without indexing so hpack decoder's dynamic table is
+/// not affected.
+pub(crate) fn encode_headers<B>(req: &Request<B>) -> Bytes {
+    let mut block = BytesMut::new();
+
+    let authority = req
+        .uri()
+        .authority()
+        .map(|a| a.as_str().as_bytes())
+        .or_else(|| req.headers().get(HOST).map(|v| v.as_bytes()));
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str())
+        .filter(|p| !p.is_empty())
+        .unwrap_or("/");
+
+    encode_literal(&mut block, b":method", req.method().as_str().as_bytes());
+    encode_literal(&mut block, b":scheme", b"http");
+    encode_literal(&mut block, b":path", path.as_bytes());
+    if let Some(authority) = authority {
+        encode_literal(&mut block, b":authority", authority);
+    }
+
+    for (name, value) in req.headers() {
+        if is_connection_header(name) || (name == TE && value != "trailers") {
+            continue;
+        }
+        encode_literal(&mut block, name.as_str().as_bytes(), value.as_bytes());
+    }
+
+    let mut block = block.freeze();
+    let mut buf = BytesMut::with_capacity(block.len() + FRAME_HEAD_LEN);
+
+    let mut kind = TYPE_HEADERS;
+    let mut flags = FLAG_END_STREAM;
+
+    loop {
+        let len = core::cmp::min(block.len(), DEFAULT_MAX_FRAME_SIZE);
+        let chunk = block.split_to(len);
+
+        if block.is_empty() {
+            flags |= FLAG_END_HEADERS;
+        }
+
+        buf.put_uint(len as u64, 3);
+        buf.put_u8(kind);
+        buf.put_u8(flags);
+        buf.put_u32(1);
+        buf.put_slice(&chunk);
+
+        if block.is_empty() {
+            return buf.freeze();
+        }
+
+        kind = TYPE_CONTINUATION;
+        flags = 0;
+    }
+}
+
+fn is_connection_header(name: &HeaderName) -> bool {
+    matches!(*name, CONNECTION | HOST | UPGRADE | TRANSFER_ENCODING | CONTENT_LENGTH)
+        || name == HTTP2_SETTINGS
+        || name == "keep-alive"
+        || name == "proxy-connection"
+}
+
+// literal header field without indexing with new name. See RFC 7541 section 6.2.2
+fn encode_literal(buf: &mut BytesMut, name: &[u8], value: &[u8]) {
+    buf.put_u8(0);
+    encode_str(buf, name);
+    encode_str(buf, value);
+}
+
+fn encode_str(buf: &mut BytesMut, val: &[u8]) {
+    encode_int(buf, val.len(), 7, 0);
+    buf.put_slice(val);
+}
+
+// integer representation with N bit prefix. See RFC 7541 section 5.1
+fn encode_int(buf: &mut BytesMut, mut val: usize, prefix_bits: u8, first_byte: u8) {
+    let max = (1 << prefix_bits) - 1;
+
+    if val < max {
+        buf.put_u8(first_byte | val as u8);
+        return;
+    }
+
+    buf.put_u8(first_byte | max as u8);
+    val -= max;
+
+    while val >= 128 {
+        buf.put_u8((val % 128) as u8 | 128);
+        val /= 128;
+    }
+
+    buf.put_u8(val as u8);
+}
+
+/// Io type for upgraded h2c connection.
+///
+/// Bytes already read by http/1 dispatcher are yielded first. The synthetic HEADERS frame of
+/// upgraded request is yielded right after client's connection preface and it's first SETTINGS
+/// frame.
+pub(crate) struct H2cIo<St> {
+    io: St,
+    buf: BytesMut,
+    headers: Option<Bytes>,
+}
+
+impl<St> H2cIo<St> {
+    pub(crate) fn new(io: St, buf: BytesMut, headers: Bytes) -> Self {
+        Self {
+            io,
+            buf,
+            headers: Some(headers),
+        }
+    }
+
+    // try to insert headers frame into buffer. return false when more bytes are needed.
+    fn try_insert_headers(&mut self) -> bool {
+        let head = PREFACE.len() + FRAME_HEAD_LEN;
+
+        if self.buf.len() < head {
+            return false;
+        }
+
+        let len = u32::from_be_bytes([0, self.buf[24], self.buf[25], self.buf[26]]) as usize;
+
+        if self.buf.len() < head + len {
+            return false;
+        }
+
+        let rest = self.buf.split_off(head + len);
+        self.buf.extend_from_slice(&self.headers.take().unwrap());
+        self.buf.extend_from_slice(&rest);
+
+        true
+    }
+}
+
+impl<St> AsyncRead for H2cIo<St>
+where
+    St: AsyncRead + Unpin,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        while this.headers.is_some() && !this.try_insert_headers() {
+            let mut chunk = [0; 1024];
+            let mut read_buf = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.io).poll_read(cx, &mut read_buf))?;
+
+            let filled = read_buf.filled();
+
+            if filled.is_empty() {
+                // connection closed before preface is finished. let http/2 connection figure out
+                // the error from partial bytes.
+                this.headers = None;
+                break;
+            }
+
+            this.buf.extend_from_slice(filled);
+        }
+
+        if this.buf.is_empty() {
+            return Pin::new(&mut this.io).poll_read(cx, buf);
+        }
+
+        let len = core::cmp::min(this.buf.len(), buf.remaining());
+        buf.put_slice(&this.buf.split_to(len));
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<St> AsyncWrite for H2cIo<St>
+where
+    St: AsyncWrite + Unpin,
+{
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+    }
+
+    #[inline]
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write_vectored(cx, bufs)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn upgrade_request() {
+        let req = Request::builder()
+            .uri("/")
+            .header(HOST, "localhost")
+            .header(CONNECTION, "Upgrade, HTTP2-Settings")
+            .header(UPGRADE, "h2c")
+            .header(HTTP2_SETTINGS, "")
+            .body(())
+            .unwrap();
+
+        assert!(is_upgrade_request(&req));
+
+        let mut req2 = Request::new(());
+        *req2.headers_mut() = req.headers().clone();
+        req2.headers_mut().insert(CONTENT_LENGTH, "3".parse().unwrap());
+        assert!(!is_upgrade_request(&req2));
+
+        let mut req2 = Request::new(());
+        *req2.headers_mut() = req.headers().clone();
+        req2.headers_mut().insert(HTTP2_SETTINGS, "AAUAAAAA".parse().unwrap());
+        assert!(!is_upgrade_request(&req2));
+
+        let mut req2 = req;
+        req2.headers_mut().append(HTTP2_SETTINGS, "".parse().unwrap());
+        assert!(!is_upgrade_request(&req2));
+    }
+
+    #[test]
+    fn settings() {
+        assert_eq!(decode_settings(b"").unwrap(), []);
+
+        // SETTINGS_MAX_CONCURRENT_STREAMS = 100, SETTINGS_INITIAL_WINDOW_SIZE = 65535
+        assert_eq!(
+            decode_settings(b"AAMAAABkAAQAAP__").unwrap(),
+            [(0x3, 100), (SETTINGS_INITIAL_WINDOW_SIZE, 65535)]
+        );
+        // SETTINGS_ENABLE_PUSH = 0 with tolerated padding.
+        assert_eq!(decode_settings(b"AAIAAAAA").unwrap(), [(SETTINGS_ENABLE_PUSH, 0)]);
+        assert_eq!(decode_settings(b"AAIAAAAA==").unwrap(), [(SETTINGS_ENABLE_PUSH, 0)]);
+
+        // not base64url.
+        assert!(decode_settings(b"AAMAAABk+AQAAP//").is_none());
+        // length not multiple of 6.
+        assert!(decode_settings(b"AAMAAAB").is_none());
+        // SETTINGS_ENABLE_PUSH = 2
+        assert!(decode_settings(b"AAIAAAAC").is_none());
+        // SETTINGS_MAX_FRAME_SIZE = 0
+        assert!(decode_settings(b"AAUAAAAA").is_none());
+        // SETTINGS_INITIAL_WINDOW_SIZE = 2^31
+        assert!(decode_settings(b"AASAAAAA").is_none());
+    }
+
+    #[test]
+    fn preface() {
+        assert!(is_preface(b"PRI *"));
+        assert!(is_preface(PREFACE));
+        assert!(!is_preface(b"GET / HTTP/1.1\r\n"));
+        assert!(!is_preface(b""));
+    }
+
+    #[test]
+    fn int_encode() {
+        let mut buf = BytesMut::new();
+        encode_int(&mut buf, 10, 5, 0);
+        assert_eq!(&buf[..], &[10]);
+
+        // example from RFC 7541 C.1.2
+        let mut buf = BytesMut::new();
+        encode_int(&mut buf, 1337, 5, 0);
+        assert_eq!(&buf[..], &[31, 154, 10]);
+    }
+
+    #[test]
+    fn headers_frame() {
+        let req = Request::builder()
+            .uri("/foo?bar")
+            .header(HOST, "localhost")
+            .header(UPGRADE, "h2c")
+            .body(())
+            .unwrap();
+
+        let frame = encode_headers(&req);
+
+        let len = u32::from_be_bytes([0, frame[0], frame[1], frame[2]]) as usize;
+        assert_eq!(frame.len(), FRAME_HEAD_LEN + len);
+        assert_eq!(frame[3], TYPE_HEADERS);
+        assert_eq!(frame[4], FLAG_END_STREAM | FLAG_END_HEADERS);
+        assert_eq!(&frame[5..9], &[0, 0, 0, 1]);
+
+        let block = &frame[FRAME_HEAD_LEN..];
+        assert!(block.windows(8).any(|w| w == b"/foo?bar"));
+        assert!(block.windows(9).any(|w| w == b"localhost"));
+        assert!(!block.windows(3).any(|w| w == b"h2c"));
+    }
+}
@@ -2,6 +2,8 @@
 
 mod builder;
 mod error;
+#[cfg_attr(not(feature = "http1"), allow(dead_code))]
+pub(crate) mod h2c;
 mod proto;
 mod service;
 
@@ -1,6 +1,6 @@
 use core::{
     cmp, fmt,
-    future::{poll_fn, Future},
+    future::{pending, poll_fn, Future},
     marker::PhantomData,
     pin::{pin, Pin},
     task::{ready, Context, Poll},
@@ -14,7 +14,7 @@ use ::h2::{
     Ping, PingPong,
 };
 use futures_core::stream::Stream;
-use tracing::trace;
+use tracing::{trace, Instrument};
 use xitca_io::io::{AsyncRead, AsyncWrite};
 use xitca_service::Service;
 use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
@@ -22,24 +22,30 @@ use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 use crate::{
     body::BodySize,
     bytes::Bytes,
+    config::HeaderPolicy,
     date::{DateTime, DateTimeHandle},
+    drain::DrainHandle,
     error::HttpServiceError,
     h2::{body::RequestBody, error::Error},
     http::{
-        header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRAILER},
-        Extension, Request, RequestExt, Response, Version,
+        header::{self, HeaderMap, HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRAILER},
+        Extension, Request, RequestExt, Response, StatusCode, Version,
     },
-    util::{futures::Queue, timer::KeepAlive},
+    util::{futures::Queue, span, timer::KeepAlive},
 };
 
+use super::scheduler::{Priority, Scheduler};
+
 /// Http/2 dispatcher
 pub(crate) struct Dispatcher<'a, TlsSt, S, ReqB> {
     io: &'a mut Connection<TlsSt, Bytes>,
     addr: SocketAddr,
     keep_alive: Pin<&'a mut KeepAlive>,
     ka_dur: Duration,
+    header_policy: HeaderPolicy,
     service: &'a S,
     date: &'a DateTimeHandle,
+    drain: &'a DrainHandle,
     _req_body: PhantomData<ReqB>,
 }
 
@@ -54,21 +60,26 @@ where
     TlsSt: AsyncRead + AsyncWrite + Unpin,
     ReqB: From<RequestBody>,
 {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         io: &'a mut Connection<TlsSt, Bytes>,
         addr: SocketAddr,
         keep_alive: Pin<&'a mut KeepAlive>,
         ka_dur: Duration,
+        header_policy: HeaderPolicy,
         service: &'a S,
         date: &'a DateTimeHandle,
+        drain: &'a DrainHandle,
     ) -> Self {
         Self {
             io,
             addr,
             keep_alive,
             ka_dur,
+            header_policy,
             service,
             date,
+            drain,
             _req_body: PhantomData,
         }
     }
@@ -79,8 +90,10 @@ where
             addr,
             mut keep_alive,
             ka_dur,
+            header_policy,
             service,
             date,
+            drain,
             ..
         } = self;
 
@@ -99,33 +112,72 @@ where
             ka_dur,
         };
 
+        let scheduler = Scheduler::default();
         let mut queue = Queue::new();
 
+        let conn_span = span::connection(&addr, "h2");
+
+        // GOAWAY is sent once when draining starts.
+        let mut going_away = false;
+
         loop {
-            match io.accept().select(try_poll_queue(&mut queue, &mut ping_pong)).await {
-                SelectOutput::A(Some(Ok((req, tx)))) => {
+            let draining = async move {
+                if going_away {
+                    pending().await
+                } else {
+                    drain.draining().await
+                }
+            };
+
+            match io
+                .accept()
+                .select(try_poll_queue(&mut queue, &mut ping_pong))
+                .select(draining)
+                .await
+            {
+                SelectOutput::A(SelectOutput::A(Some(Ok((mut req, mut tx))))) => {
+                    if let Err(name) = header_policy.apply(req.headers_mut()) {
+                        trace!("Duplicate header field {name}. Rejecting request");
+                        let mut res = Response::new(());
+                        *res.status_mut() = StatusCode::BAD_REQUEST;
+                        let _ = tx.send_response(res, true);
+                        continue;
+                    }
+
+                    let priority = Priority::from_headers(req.headers()).unwrap_or_default();
+
+                    let span = span::request(&conn_span, &req);
+
                     // Convert http::Request body type to crate::h2::Body
                     // and reconstruct as HttpRequest.
-                    let req = req.map(|body| {
+                    let mut req = req.map(|body| {
                         let body = ReqB::from(RequestBody::from(body));
                         RequestExt::from_parts(body, Extension::new(addr))
                     });
+                    *req.body_mut().span_mut() = span.clone();
 
+                    let scheduler = &scheduler;
                     queue.push(async move {
-                        let fut = service.call(req);
-                        h2_handler(fut, tx, date).await
+                        let fut = service.call(req).instrument(span);
+                        h2_handler(fut, tx, date, scheduler, priority).await
                     });
                 }
-                SelectOutput::B(SelectOutput::A(_)) => io.graceful_shutdown(),
-                SelectOutput::B(SelectOutput::B(Ok(_))) => {
+                SelectOutput::A(SelectOutput::B(SelectOutput::A(_))) => io.graceful_shutdown(),
+                SelectOutput::A(SelectOutput::B(SelectOutput::B(Ok(_)))) => {
                     trace!("Connection keep-alive timeout. Shutting down");
                     return Ok(());
                 }
-                SelectOutput::A(None) => {
+                SelectOutput::A(SelectOutput::A(None)) => {
                     trace!("Connection closed by remote. Shutting down");
                     break;
                 }
-                SelectOutput::A(Some(Err(e))) | SelectOutput::B(SelectOutput::B(Err(e))) => return Err(From::from(e)),
+                SelectOutput::A(SelectOutput::A(Some(Err(e))))
+                | SelectOutput::A(SelectOutput::B(SelectOutput::B(Err(e)))) => return Err(From::from(e)),
+                SelectOutput::B(_) => {
+                    trace!("Connection draining. Sending GOAWAY");
+                    going_away = true;
+                    io.graceful_shutdown();
+                }
             }
         }
 
@@ -219,6 +271,8 @@ async fn h2_handler<Fut, B, SE, BE>(
     fut: Fut,
     mut tx: SendResponse<Bytes>,
     date: &DateTimeHandle,
+    scheduler: &Scheduler,
+    priority: Priority,
 ) -> Result<ConnectionState, Error<SE, BE>>
 where
     Fut: Future<Output = Result<Response<B>, SE>>,
@@ -251,12 +305,31 @@ where
         }
     };
 
+    // headers named by trailer header are moved to trailers. when response has no body they are
+    // kept in header block instead and sent as a trailers only response.
     let mut trailers = HeaderMap::with_capacity(0);
 
-    while let Some(value) = res.headers_mut().remove(TRAILER) {
-        let name = HeaderName::from_bytes(value.as_bytes()).unwrap();
-        let value = res.headers_mut().remove(name.clone()).unwrap();
-        trailers.append(name, value);
+    if !is_eof {
+        if let header::Entry::Occupied(entry) = res.headers_mut().entry(TRAILER) {
+            let names = entry
+                .remove_entry_mult()
+                .1
+                .filter_map(|value| value.to_str().map(str::to_owned).ok())
+                .collect::<Vec<_>>();
+
+            for name in names
+                .iter()
+                .flat_map(|names| names.split(','))
+                .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+            {
+                if let header::Entry::Occupied(entry) = res.headers_mut().entry(name) {
+                    let (name, values) = entry.remove_entry_mult();
+                    for value in values {
+                        trailers.append(name.clone(), value);
+                    }
+                }
+            }
+        }
     }
 
     if !res.headers().contains_key(DATE) {
@@ -275,10 +348,14 @@ where
         })
         .unwrap_or(ConnectionState::KeepAlive);
 
+    // response can override priority of request.
+    let priority = Priority::from_headers(res.headers()).unwrap_or(priority);
+
     // send response and body(if there is one).
     let mut stream = tx.send_response(res, is_eof)?;
 
     if !is_eof {
+        let turn = scheduler.register(priority);
         let mut body = pin!(body);
 
         while let Some(res) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
@@ -287,6 +364,11 @@ where
             while !chunk.is_empty() {
                 let len = chunk.len();
 
+                // wait for turn among concurrent streams of the connection before reserving
+                // capacity. h2 assigns connection level capacity to streams in the order they
+                // reserve it so the turn decides which stream's data is queued first.
+                turn.acquire().await;
+
                 stream.reserve_capacity(cmp::min(len, CHUNK_SIZE));
 
                 let cap = poll_fn(|cx| stream.poll_capacity(cx))
@@ -296,14 +378,118 @@ where
                 // Split chuck to writeable size and send to client.
                 let bytes = chunk.split_to(cmp::min(cap, len));
 
+                turn.sent(bytes.len());
                 stream.send_data(bytes, false)?;
             }
         }
     }
 
-    stream.send_trailers(trailers)?;
+    if !is_eof {
+        if trailers.is_empty() {
+            stream.send_data(Bytes::new(), true)?;
+        } else {
+            stream.send_trailers(trailers)?;
+        }
+    }
 
     Ok(state)
 }
 
 const CHUNK_SIZE: usize = 16_384;
+
+#[cfg(test)]
+mod test {
+    use core::convert::Infallible;
+
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        body::Once,
+        config::HttpServiceConfig,
+        date::DateTimeService,
+        http::{header::HeaderValue, Method},
+    };
+
+    use super::{super::scheduler::PRIORITY, *};
+
+    struct Payload;
+
+    impl Service<Request<RequestExt<RequestBody>>> for Payload {
+        type Response = Response<Once<Bytes>>;
+        type Error = Infallible;
+
+        async fn call(&self, _: Request<RequestExt<RequestBody>>) -> Result<Self::Response, Self::Error> {
+            Ok(Response::new(Once::new(Bytes::from(vec![0; CHUNK_SIZE * 16]))))
+        }
+    }
+
+    // open streams with given priority in order on one connection and return their names in the
+    // order their response bodies are finished.
+    async fn finish_order(streams: [(&'static str, &'static str); 2]) -> Vec<&'static str> {
+        let (client_io, server_io) = tokio::io::duplex(CHUNK_SIZE * 64);
+
+        tokio::task::spawn_local(async move {
+            let date = DateTimeService::new();
+            let drain = DrainHandle::new();
+            let ka_dur = Duration::from_secs(60);
+            let keep_alive = pin!(KeepAlive::new(date.get().now() + ka_dur));
+            let mut conn = ::h2::server::handshake(server_io).await.unwrap();
+            let _ = Dispatcher::<_, _, RequestBody>::new(
+                &mut conn,
+                ([127, 0, 0, 1], 8080).into(),
+                keep_alive,
+                ka_dur,
+                HttpServiceConfig::default().header_policy,
+                &Payload,
+                date.get(),
+                &drain,
+            )
+            .run()
+            .await;
+        });
+
+        let (mut client, conn) = ::h2::client::handshake(client_io).await.unwrap();
+        tokio::task::spawn_local(conn);
+
+        let finished = Rc::new(RefCell::new(Vec::new()));
+
+        let mut tasks = Vec::new();
+        for (name, priority) in streams {
+            let mut req = Request::new(());
+            *req.method_mut() = Method::GET;
+            *req.uri_mut() = "https://localhost/".parse().unwrap();
+            req.headers_mut().insert(PRIORITY, HeaderValue::from_static(priority));
+            let (res, _) = client.send_request(req, true).unwrap();
+
+            let finished = finished.clone();
+            tasks.push(tokio::task::spawn_local(async move {
+                let mut body = res.await.unwrap().into_body();
+                while let Some(chunk) = body.data().await {
+                    let len = chunk.unwrap().len();
+                    body.flow_control().release_capacity(len).unwrap();
+                }
+                finished.borrow_mut().push(name);
+            }));
+        }
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        Rc::try_unwrap(finished).unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn urgency() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                // more urgent stream finishes first regardless of the order streams are opened.
+                let order = finish_order([("high", "u=0"), ("low", "u=6")]).await;
+                assert_eq!(order, ["high", "low"]);
+
+                let order = finish_order([("low", "u=6"), ("high", "u=0")]).await;
+                assert_eq!(order, ["high", "low"]);
+            })
+            .await
+    }
+}
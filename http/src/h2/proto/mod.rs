@@ -7,6 +7,8 @@ mod head;
 mod headers;
 mod hpack;
 mod priority;
+mod reset;
+mod scheduler;
 mod settings;
 mod stream_id;
 
@@ -48,12 +50,15 @@ mod io_uring {
         data,
         error::Error,
         head, headers, hpack,
+        reset::{Reset, REFUSED_STREAM},
         settings::{self, Settings},
         stream_id::StreamId,
     };
 
     const PREFACE: &[u8; 24] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
 
+    const MAX_CONCURRENT_STREAMS: u32 = 256;
+
     struct H2Context {
         max_header_list_size: usize,
         decoder: hpack::Decoder,
@@ -157,7 +162,10 @@ mod io_uring {
                         let id = data.stream_id();
                         let payload = data.into_payload();
 
-                        let tx = self.tx_map.get_mut(&id).unwrap();
+                        // stream is refused or closed already. discard data frame.
+                        let Some(tx) = self.tx_map.get_mut(&id) else {
+                            continue;
+                        };
 
                         tx.send(Ok(payload)).unwrap();
 
@@ -261,7 +269,7 @@ mod io_uring {
         read_buf = prefix_check(&io, read_buf).await?;
 
         let mut settings = settings::Settings::default();
-        settings.set_max_concurrent_streams(Some(256));
+        settings.set_max_concurrent_streams(Some(MAX_CONCURRENT_STREAMS));
 
         settings.encode(&mut write_buf);
         let (res, buf) = write_io(write_buf, &io).await;
@@ -269,7 +277,10 @@ mod io_uring {
         res?;
 
         let mut ctx = H2Context::new(settings);
-        let mut queue = Queue::new();
+        // queue is bounded by advertised max concurrent streams and excess streams opened by
+        // client are refused.
+        let mut queue = Queue::with_capacity(MAX_CONCURRENT_STREAMS as usize);
+        let mut refused = Vec::new();
 
         let mut read_task = pin!(read_io(read_buf, &io));
 
@@ -283,7 +294,9 @@ mod io_uring {
 
                     let res = ctx.try_decode(&mut read_buf, |req, stream_id| {
                         let s = &service;
-                        queue.push(async move { (s.call(req).await, stream_id) });
+                        if queue.try_push(async move { (s.call(req).await, stream_id) }).is_err() {
+                            refused.push(stream_id);
+                        }
                     });
 
                     if let Err(e) = res {
@@ -291,6 +304,18 @@ mod io_uring {
                     }
 
                     read_task.set(read_io(read_buf, &io));
+
+                    if !refused.is_empty() {
+                        for id in refused.drain(..) {
+                            // request body of refused stream is never read.
+                            ctx.tx_map.remove(&id);
+                            Reset::new(id, REFUSED_STREAM).encode(&mut write_buf);
+                        }
+
+                        let (res, buf) = write_io(write_buf, &io).await;
+                        write_buf = buf;
+                        res?;
+                    }
                 }
                 SelectOutput::B((res, id)) => {
                     let (parts, _) = match res {
@@ -0,0 +1,35 @@
+use xitca_io::bytes::BufMut;
+
+use super::{
+    head::{Head, Kind},
+    stream_id::StreamId,
+};
+
+/// The endpoint refused the stream prior to performing any application processing.
+pub const REFUSED_STREAM: u32 = 0x7;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Reset {
+    stream_id: StreamId,
+    error_code: u32,
+}
+
+impl Reset {
+    pub fn new(stream_id: StreamId, error_code: u32) -> Reset {
+        Reset { stream_id, error_code }
+    }
+
+    pub fn stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    pub fn error_code(&self) -> u32 {
+        self.error_code
+    }
+
+    pub fn encode<B: BufMut>(&self, dst: &mut B) {
+        let head = Head::new(Kind::Reset, 0, self.stream_id);
+        head.encode(4, dst);
+        dst.put_u32(self.error_code);
+    }
+}
@@ -0,0 +1,312 @@
+//! send scheduler interleaving DATA frames of concurrent streams of one connection.
+//!
+//! Streams are prioritized by the `priority` header field of RFC 9218. A response can override
+//! priority of it's request by carrying the same header. Deprecated PRIORITY frames of RFC 7540 are
+//! consumed by `h2` crate and never surface to dispatcher therefore they are not taken into account.
+//!
+//! Among streams waiting to send:
+//! - lower urgency value is always served first.
+//! - with the same urgency non incremental stream is served first in the order of arrival.
+//! - with the same urgency incremental streams share bandwidth fairly by bytes sent.
+
+use core::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use std::collections::HashMap;
+
+use crate::http::header::{HeaderMap, HeaderName};
+
+pub(super) const PRIORITY: HeaderName = HeaderName::from_static("priority");
+
+/// Priority of a stream according to RFC 9218.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) struct Priority {
+    urgency: u8,
+    incremental: bool,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self {
+            urgency: 3,
+            incremental: false,
+        }
+    }
+}
+
+impl Priority {
+    /// parse priority header field. unknown and malformed parameters are ignored and fall back to
+    /// default value.
+    pub(super) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let value = headers.get(PRIORITY)?.to_str().ok()?;
+        let mut priority = Self::default();
+        for item in value.split(',') {
+            let (key, value) = match item.trim().split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value.trim())),
+                None => (item.trim(), None),
+            };
+            match (key, value) {
+                ("u", Some(value)) => {
+                    if let Some(urgency) = value.parse().ok().filter(|u| *u <= 7) {
+                        priority.urgency = urgency;
+                    }
+                }
+                ("i", None | Some("?1")) => priority.incremental = true,
+                ("i", Some("?0")) => priority.incremental = false,
+                _ => {}
+            }
+        }
+        Some(priority)
+    }
+}
+
+/// Per connection scheduler. Dispatcher and all it's streams share the same thread so interior
+/// mutability is enough.
+#[derive(Default)]
+pub(super) struct Scheduler {
+    inner: RefCell<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_seq: u64,
+    // pass of the last stream granted a turn. new incremental stream starts from it so it does not
+    // starve existing ones while catching up.
+    pass: u64,
+    streams: HashMap<u64, Entry>,
+}
+
+struct Entry {
+    priority: Priority,
+    pass: u64,
+    waiting: bool,
+    waker: Option<Waker>,
+}
+
+impl Inner {
+    fn next(&self) -> Option<u64> {
+        self.streams
+            .iter()
+            .filter(|(_, e)| e.waiting)
+            .min_by_key(|(seq, e)| {
+                let order = if e.priority.incremental { e.pass } else { 0 };
+                (e.priority.urgency, e.priority.incremental, order, **seq)
+            })
+            .map(|(seq, _)| *seq)
+    }
+
+    fn wake_next(&mut self) {
+        if let Some(seq) = self.next() {
+            if let Some(waker) = self.streams.get_mut(&seq).and_then(|e| e.waker.take()) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl Scheduler {
+    /// register a stream with data to send. stream is removed from scheduler when returned
+    /// [StreamTurn] is dropped.
+    pub(super) fn register(&self, priority: Priority) -> StreamTurn<'_> {
+        let mut inner = self.inner.borrow_mut();
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        let pass = inner.pass;
+        inner.streams.insert(
+            seq,
+            Entry {
+                priority,
+                pass,
+                waiting: false,
+                waker: None,
+            },
+        );
+        StreamTurn { scheduler: self, seq }
+    }
+}
+
+pub(super) struct StreamTurn<'a> {
+    scheduler: &'a Scheduler,
+    seq: u64,
+}
+
+impl<'a> StreamTurn<'a> {
+    /// wait for the turn to send DATA frame. caller must send without yielding after the turn is
+    /// granted and report the length of sent data with [StreamTurn::sent].
+    pub(super) fn acquire(&self) -> Acquire<'_, 'a> {
+        Acquire {
+            turn: self,
+            yielded: false,
+            granted: false,
+        }
+    }
+
+    pub(super) fn sent(&self, len: usize) {
+        let mut inner = self.scheduler.inner.borrow_mut();
+        let entry = inner.streams.get_mut(&self.seq).unwrap();
+        entry.pass += len as u64;
+    }
+}
+
+pub(super) struct Acquire<'t, 'a> {
+    turn: &'t StreamTurn<'a>,
+    yielded: bool,
+    granted: bool,
+}
+
+impl Future for Acquire<'_, '_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let seq = this.turn.seq;
+        let mut inner = this.turn.scheduler.inner.borrow_mut();
+
+        // fast path for connection with single active stream.
+        if inner.streams.len() > 1 {
+            let entry = inner.streams.get_mut(&seq).unwrap();
+            entry.waiting = true;
+
+            // yield once so streams become ready at the same time can all enter contention before
+            // a turn is granted.
+            if !this.yielded {
+                this.yielded = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            if inner.next() != Some(seq) {
+                inner.streams.get_mut(&seq).unwrap().waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+
+        let entry = inner.streams.get_mut(&seq).unwrap();
+        entry.waiting = false;
+        entry.waker = None;
+        inner.pass = entry.pass;
+        inner.wake_next();
+        this.granted = true;
+
+        Poll::Ready(())
+    }
+}
+
+impl Drop for Acquire<'_, '_> {
+    fn drop(&mut self) {
+        if self.granted {
+            return;
+        }
+        let mut inner = self.turn.scheduler.inner.borrow_mut();
+        if let Some(entry) = inner.streams.get_mut(&self.turn.seq) {
+            if entry.waiting {
+                entry.waiting = false;
+                entry.waker = None;
+                inner.wake_next();
+            }
+        }
+    }
+}
+
+impl Drop for StreamTurn<'_> {
+    fn drop(&mut self) {
+        let mut inner = self.scheduler.inner.borrow_mut();
+        inner.streams.remove(&self.seq);
+        inner.wake_next();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::pin::pin;
+
+    use crate::http::header::HeaderValue;
+
+    use super::*;
+
+    fn priority(value: &'static str) -> Priority {
+        let mut headers = HeaderMap::new();
+        headers.insert(PRIORITY, HeaderValue::from_static(value));
+        Priority::from_headers(&headers).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            priority("u=1"),
+            Priority {
+                urgency: 1,
+                incremental: false
+            }
+        );
+        assert_eq!(
+            priority("u=5, i"),
+            Priority {
+                urgency: 5,
+                incremental: true
+            }
+        );
+        assert_eq!(
+            priority("i=?1, foo=bar"),
+            Priority {
+                urgency: 3,
+                incremental: true
+            }
+        );
+        assert_eq!(priority("u=9, i=?0"), Priority::default());
+        assert!(Priority::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    // poll acquire futures of given streams in contention and return index of the granted one.
+    fn next(turns: &[&StreamTurn<'_>]) -> usize {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut futs = turns.iter().map(|t| Box::pin(t.acquire())).collect::<Vec<_>>();
+        for fut in futs.iter_mut() {
+            assert!(fut.as_mut().poll(&mut cx).is_pending());
+        }
+        futs.iter_mut()
+            .position(|fut| fut.as_mut().poll(&mut cx).is_ready())
+            .unwrap()
+    }
+
+    #[test]
+    fn urgency() {
+        let scheduler = Scheduler::default();
+        let low = scheduler.register(priority("u=5"));
+        let high = scheduler.register(priority("u=1"));
+        let default = scheduler.register(Priority::default());
+
+        // the most urgent stream is granted in contention.
+        assert_eq!(next(&[&low, &default, &high]), 2);
+
+        drop(high);
+        assert_eq!(next(&[&low, &default]), 1);
+    }
+
+    #[test]
+    fn incremental() {
+        let scheduler = Scheduler::default();
+        let a = scheduler.register(priority("i"));
+        let b = scheduler.register(priority("i"));
+
+        assert_eq!(next(&[&a, &b]), 0);
+        a.sent(100);
+
+        // stream sent less data is served first.
+        assert_eq!(next(&[&a, &b]), 1);
+        b.sent(200);
+        assert_eq!(next(&[&a, &b]), 0);
+    }
+
+    #[test]
+    fn single_stream() {
+        let scheduler = Scheduler::default();
+        let a = scheduler.register(Priority::default());
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(pin!(a.acquire()).poll(&mut cx).is_ready());
+    }
+}
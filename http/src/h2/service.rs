@@ -47,38 +47,43 @@ where
     type Error = HttpServiceError<S::Error, BE>;
 
     async fn call(&self, (io, addr): (St, SocketAddr)) -> Result<Self::Response, Self::Error> {
-        // tls accept timer.
-        let timer = self.keep_alive();
-        let mut timer = pin!(timer);
-
-        let tls_stream = self
-            .tls_acceptor
-            .call(io)
-            .timeout(timer.as_mut())
-            .await
-            .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
-
-        // update timer to first request timeout.
-        self.update_first_request_deadline(timer.as_mut());
-
-        let mut conn = ::h2::server::Builder::new()
-            .enable_connect_protocol()
-            .handshake(tls_stream)
-            .timeout(timer.as_mut())
-            .await
-            .map_err(|_| HttpServiceError::Timeout(TimeoutError::H2Handshake))??;
-
-        let dispatcher = Dispatcher::new(
-            &mut conn,
-            addr,
-            timer,
-            self.config.keep_alive_timeout,
-            &self.service,
-            self.date.get(),
-        );
-
-        dispatcher.run().await?;
-
-        Ok(())
+        self.drained(async {
+            // tls accept timer.
+            let timer = self.keep_alive();
+            let mut timer = pin!(timer);
+
+            let tls_stream = self
+                .tls_acceptor
+                .call(io)
+                .timeout(timer.as_mut())
+                .await
+                .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
+
+            // update timer to first request timeout.
+            self.update_first_request_deadline(timer.as_mut());
+
+            let mut conn = ::h2::server::Builder::new()
+                .enable_connect_protocol()
+                .handshake(tls_stream)
+                .timeout(timer.as_mut())
+                .await
+                .map_err(|_| HttpServiceError::Timeout(TimeoutError::H2Handshake))??;
+
+            let dispatcher = Dispatcher::new(
+                &mut conn,
+                addr,
+                timer,
+                self.config.keep_alive_timeout,
+                self.config.header_policy,
+                &self.service,
+                self.date.get(),
+                &self.drain,
+            );
+
+            dispatcher.run().await?;
+
+            Ok(())
+        })
+        .await
     }
 }
@@ -14,6 +14,7 @@ use ::h3::{
 };
 use futures_core::stream::Stream;
 use pin_project_lite::pin_project;
+use tracing::Instrument;
 use xitca_io::net::UdpStream;
 use xitca_service::Service;
 use xitca_unsafe_collection::futures::{Select, SelectOutput};
@@ -23,7 +24,7 @@ use crate::{
     error::HttpServiceError,
     h3::{body::RequestBody, error::Error},
     http::{Extension, Request, RequestExt, Response},
-    util::futures::Queue,
+    util::{futures::Queue, span},
 };
 
 /// Http/3 dispatcher
@@ -63,6 +64,8 @@ where
 
         let mut queue = Queue::new();
 
+        let conn_span = span::connection(&self.addr, "h3");
+
         // accept loop
         loop {
             match conn.accept().select(queue.next()).await {
@@ -76,14 +79,17 @@ where
                         Ok(res.map(|bytes| (Bytes::copy_from_slice(bytes.chunk()), stream)))
                     }));
 
+                    let span = span::request(&conn_span, &req);
+
                     // Reconstruct Request to attach crate body type.
-                    let req = req.map(|_| {
+                    let mut req = req.map(|_| {
                         let body = ReqB::from(RequestBody(body));
                         RequestExt::from_parts(body, Extension::new(self.addr))
                     });
+                    *req.body_mut().span_mut() = span.clone();
 
                     queue.push(async move {
-                        let fut = self.service.call(req);
+                        let fut = self.service.call(req).instrument(span);
                         h3_handler(fut, tx).await
                     });
                 }
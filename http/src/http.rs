@@ -12,6 +12,7 @@ use std::net::SocketAddr;
 
 use futures_core::stream::Stream;
 use pin_project_lite::pin_project;
+use tracing::Span;
 
 /// Some often used header value.
 #[allow(clippy::declare_interior_mutable_const)]
@@ -49,7 +50,11 @@ pub mod const_header_name {
             }
         }
 
-    const_name!((PROTOCOL, "protocol"));
+    const_name!(
+        (PROTOCOL, "protocol"),
+        (GRPC_STATUS, "grpc-status"),
+        (GRPC_MESSAGE, "grpc-message")
+    );
 }
 
 /// Helper trait for convert a [Request] to [Response].
@@ -89,7 +94,7 @@ where
 }
 
 #[cfg(feature = "router")]
-use super::util::service::router::Params;
+use super::util::service::router::{MatchedPath, Params};
 
 pin_project! {
     /// typed http extension
@@ -110,8 +115,11 @@ impl Extension {
     pub(crate) fn new(addr: SocketAddr) -> Self {
         Self(Box::new(_Extension {
             addr,
+            span: Span::none(),
             #[cfg(feature = "router")]
             params: Default::default(),
+            #[cfg(feature = "router")]
+            matched_path: Default::default(),
         }))
     }
 }
@@ -119,8 +127,11 @@ impl Extension {
 #[derive(Debug)]
 struct _Extension {
     addr: SocketAddr,
+    span: Span,
     #[cfg(feature = "router")]
     params: Params,
+    #[cfg(feature = "router")]
+    matched_path: MatchedPath,
 }
 
 impl<B> RequestExt<B> {
@@ -138,6 +149,28 @@ impl<B> RequestExt<B> {
         &mut self.ext.0.addr
     }
 
+    /// tracing span of current request. Dispatcher creates it as a child of the connection span
+    /// and the request is served inside it.
+    #[inline]
+    pub fn span(&self) -> &Span {
+        &self.ext.0.span
+    }
+
+    #[inline]
+    pub fn span_mut(&mut self) -> &mut Span {
+        &mut self.ext.0.span
+    }
+
+    #[inline]
+    pub fn body(&self) -> &B {
+        &self.body
+    }
+
+    #[inline]
+    pub fn body_mut(&mut self) -> &mut B {
+        &mut self.body
+    }
+
     #[inline]
     pub fn map_body<F, B1>(self, func: F) -> RequestExt<B1>
     where
@@ -168,6 +201,16 @@ impl<B> RequestExt<B> {
     pub fn params_mut(&mut self) -> &mut Params {
         &mut self.ext.0.params
     }
+
+    #[inline]
+    pub fn matched_path(&self) -> &MatchedPath {
+        &self.ext.0.matched_path
+    }
+
+    #[inline]
+    pub fn matched_path_mut(&mut self) -> &mut MatchedPath {
+        &mut self.ext.0.matched_path
+    }
 }
 
 impl<B> Default for RequestExt<B>
@@ -203,6 +246,13 @@ impl<B> Borrow<SocketAddr> for RequestExt<B> {
     }
 }
 
+impl<B> Borrow<Span> for RequestExt<B> {
+    #[inline]
+    fn borrow(&self) -> &Span {
+        self.span()
+    }
+}
+
 #[cfg(feature = "router")]
 impl<B> Borrow<Params> for RequestExt<B> {
     #[inline]
@@ -219,6 +269,22 @@ impl<B> BorrowMut<Params> for RequestExt<B> {
     }
 }
 
+#[cfg(feature = "router")]
+impl<B> Borrow<MatchedPath> for RequestExt<B> {
+    #[inline]
+    fn borrow(&self) -> &MatchedPath {
+        self.matched_path()
+    }
+}
+
+#[cfg(feature = "router")]
+impl<B> BorrowMut<MatchedPath> for RequestExt<B> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut MatchedPath {
+        self.matched_path_mut()
+    }
+}
+
 /// trait for Borrow &T from &Self.
 /// used for foreign types (from xitca-http pov) that can be impl with [Borrow] trait.
 pub trait BorrowReq<T> {
@@ -39,6 +39,8 @@
 #[cfg(feature = "runtime")]
 mod builder;
 #[cfg(feature = "runtime")]
+mod drain;
+#[cfg(feature = "runtime")]
 mod service;
 mod tls;
 mod version;
@@ -65,8 +67,12 @@ pub use xitca_io::bytes;
 pub use self::body::{RequestBody, ResponseBody};
 #[cfg(feature = "runtime")]
 pub use self::builder::HttpServiceBuilder;
+#[cfg(feature = "runtime")]
+pub use self::drain::DrainHandle;
 pub use self::error::{BodyError, HttpServiceError};
 pub use self::http::{Request, Response};
+#[cfg(any(feature = "openssl", feature = "rustls"))]
+pub use self::tls::HandshakePool;
 
 // TODO: enable this conflict feature check.
 // temporary compile error for conflicted feature combination.
@@ -1,18 +1,21 @@
-use core::{fmt, marker::PhantomData, pin::pin};
+use core::{fmt, future::Future, marker::PhantomData, pin::pin};
 
 use futures_core::Stream;
+use tracing::trace;
 use xitca_io::{
     io::{AsyncIo, AsyncRead, AsyncWrite},
     net::Stream as ServerStream,
     net::TcpStream,
 };
 use xitca_service::{ready::ReadyService, Service};
+use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 
 use super::{
     body::RequestBody,
     bytes::Bytes,
     config::HttpServiceConfig,
     date::{DateTime, DateTimeService},
+    drain::DrainHandle,
     error::{HttpServiceError, TimeoutError},
     http::{Request, RequestExt, Response},
     util::timer::{KeepAlive, Timeout},
@@ -32,6 +35,7 @@ pub struct HttpService<
     pub(crate) date: DateTimeService,
     pub(crate) service: S,
     pub(crate) tls_acceptor: A,
+    pub(crate) drain: DrainHandle,
     _body: PhantomData<(St, ReqB)>,
 }
 
@@ -42,16 +46,44 @@ impl<St, S, ReqB, A, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, con
         config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
         service: S,
         tls_acceptor: A,
+        drain: DrainHandle,
     ) -> Self {
         Self {
             config,
             date: DateTimeService::new(),
             service,
             tls_acceptor,
+            drain,
             _body: PhantomData,
         }
     }
 
+    /// Get handle for draining connections of service. See [DrainHandle] for detail.
+    pub fn drain_handle(&self) -> DrainHandle {
+        self.drain.clone()
+    }
+
+    // run connection and track it with drain handle. new connection is refused when draining has
+    // started and connection alive is closed forcefully when deadline of draining is reached.
+    pub(crate) async fn drained<Fut, E>(&self, fut: Fut) -> Result<(), E>
+    where
+        Fut: Future<Output = Result<(), E>>,
+    {
+        if self.drain.is_draining() {
+            return Ok(());
+        }
+
+        let _guard = self.drain.guard();
+
+        match fut.select(self.drain.expired()).await {
+            SelectOutput::A(res) => res,
+            SelectOutput::B(_) => {
+                trace!(target: "http_service", "Connection draining deadline reached. Shutting down");
+                Ok(())
+            }
+        }
+    }
+
     #[cfg(feature = "http2")]
     pub(crate) fn update_first_request_deadline(&self, timer: core::pin::Pin<&mut KeepAlive>) {
         let request_dur = self.config.request_head_timeout;
@@ -85,96 +117,187 @@ where
     type Error = HttpServiceError<S::Error, BE>;
 
     async fn call(&self, io: ServerStream) -> Result<Self::Response, Self::Error> {
-        // tls accept timer.
-        let timer = self.keep_alive();
-        let mut timer = pin!(timer);
-
-        match io {
-            #[cfg(feature = "http3")]
-            ServerStream::Udp(io, addr) => super::h3::Dispatcher::new(io, addr, &self.service)
-                .run()
-                .await
-                .map_err(From::from),
-            ServerStream::Tcp(io, _addr) => {
-                let io = TcpStream::from_std(io).expect("TODO: handle io error");
-                let mut _tls_stream = self
-                    .tls_acceptor
-                    .call(io)
-                    .timeout(timer.as_mut())
-                    .await
-                    .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
+        self.drained(async {
+            // tls accept timer.
+            let timer = self.keep_alive();
+            let mut timer = pin!(timer);
 
-                let version = if self.config.peek_protocol {
-                    // peek version from connection to figure out the real protocol used
-                    // regardless of AsVersion's outcome.
-                    todo!("peek version is not implemented yet!")
-                } else {
-                    _tls_stream.as_version()
-                };
-
-                match version {
-                    #[cfg(feature = "http1")]
-                    super::http::Version::HTTP_11 | super::http::Version::HTTP_10 => super::h1::dispatcher::run(
-                        &mut _tls_stream,
-                        _addr,
-                        timer.as_mut(),
-                        self.config,
-                        &self.service,
-                        self.date.get(),
-                    )
+            match io {
+                #[cfg(feature = "http3")]
+                ServerStream::Udp(io, addr) => super::h3::Dispatcher::new(io, addr, &self.service)
+                    .run()
                     .await
                     .map_err(From::from),
-                    #[cfg(feature = "http2")]
-                    super::http::Version::HTTP_2 => {
-                        // update timer to first request timeout.
-                        self.update_first_request_deadline(timer.as_mut());
+                ServerStream::Tcp(io, _addr) => {
+                    let io = TcpStream::from_std(io).expect("TODO: handle io error");
 
-                        let mut conn = ::h2::server::Builder::new()
-                            .enable_connect_protocol()
-                            .handshake(_tls_stream)
+                    // peek raw connection before tls accept. tls connection would never match http/2
+                    // preface and fall back to tls stream's protocol negotiation outcome.
+                    #[cfg(feature = "http2")]
+                    let is_h2c = self.config.peek_protocol
+                        && peek_preface(&io)
                             .timeout(timer.as_mut())
                             .await
-                            .map_err(|_| HttpServiceError::Timeout(TimeoutError::H2Handshake))??;
+                            .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))?;
+
+                    #[cfg(not(feature = "http2"))]
+                    let is_h2c = false;
+
+                    let mut _tls_stream = self
+                        .tls_acceptor
+                        .call(io)
+                        .timeout(timer.as_mut())
+                        .await
+                        .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
+
+                    let version = if is_h2c {
+                        super::http::Version::HTTP_2
+                    } else {
+                        _tls_stream.as_version()
+                    };
 
-                        super::h2::Dispatcher::new(
-                            &mut conn,
+                    match version {
+                        #[cfg(all(feature = "http1", not(feature = "http2")))]
+                        super::http::Version::HTTP_11 | super::http::Version::HTTP_10 => super::h1::dispatcher::run(
+                            &mut _tls_stream,
                             _addr,
                             timer.as_mut(),
-                            self.config.keep_alive_timeout,
+                            self.config,
                             &self.service,
                             self.date.get(),
+                            &self.drain,
                         )
-                        .run()
                         .await
-                        .map_err(Into::into)
+                        .map_err(From::from),
+                        #[cfg(all(feature = "http1", feature = "http2"))]
+                        super::http::Version::HTTP_11 | super::http::Version::HTTP_10 => {
+                            let upgrade = super::h1::dispatcher::run_h2c(
+                                &mut _tls_stream,
+                                _addr,
+                                timer.as_mut(),
+                                self.config,
+                                &self.service,
+                                self.date.get(),
+                                &self.drain,
+                            )
+                            .await?;
+
+                            let Some(upgrade) = upgrade else { return Ok(()) };
+
+                            self.update_first_request_deadline(timer.as_mut());
+
+                            let headers = super::h2::h2c::encode_headers(&upgrade.req);
+                            let io = super::h2::h2c::H2cIo::new(_tls_stream, upgrade.read_buf, headers);
+
+                            let mut conn = ::h2::server::Builder::new()
+                                .enable_connect_protocol()
+                                .handshake(io)
+                                .timeout(timer.as_mut())
+                                .await
+                                .map_err(|_| HttpServiceError::Timeout(TimeoutError::H2Handshake))??;
+
+                            super::h2::Dispatcher::new(
+                                &mut conn,
+                                _addr,
+                                timer.as_mut(),
+                                self.config.keep_alive_timeout,
+                                self.config.header_policy,
+                                &self.service,
+                                self.date.get(),
+                                &self.drain,
+                            )
+                            .run()
+                            .await
+                            .map_err(Into::into)
+                        }
+                        #[cfg(feature = "http2")]
+                        super::http::Version::HTTP_2 => {
+                            // update timer to first request timeout.
+                            self.update_first_request_deadline(timer.as_mut());
+
+                            let mut conn = ::h2::server::Builder::new()
+                                .enable_connect_protocol()
+                                .handshake(_tls_stream)
+                                .timeout(timer.as_mut())
+                                .await
+                                .map_err(|_| HttpServiceError::Timeout(TimeoutError::H2Handshake))??;
+
+                            super::h2::Dispatcher::new(
+                                &mut conn,
+                                _addr,
+                                timer.as_mut(),
+                                self.config.keep_alive_timeout,
+                                self.config.header_policy,
+                                &self.service,
+                                self.date.get(),
+                                &self.drain,
+                            )
+                            .run()
+                            .await
+                            .map_err(Into::into)
+                        }
+                        version => Err(HttpServiceError::UnSupportedVersion(version)),
                     }
-                    version => Err(HttpServiceError::UnSupportedVersion(version)),
-                }
-            }
-            #[cfg(unix)]
-            ServerStream::Unix(_io, _) => {
-                #[cfg(not(feature = "http1"))]
-                {
-                    Err(HttpServiceError::UnSupportedVersion(super::http::Version::HTTP_11))
                 }
+                #[cfg(unix)]
+                ServerStream::Unix(_io, _) => {
+                    #[cfg(not(feature = "http1"))]
+                    {
+                        Err(HttpServiceError::UnSupportedVersion(super::http::Version::HTTP_11))
+                    }
 
-                #[cfg(feature = "http1")]
-                {
-                    let mut io = xitca_io::net::UnixStream::from_std(_io).expect("TODO: handle io error");
-
-                    super::h1::dispatcher::run(
-                        &mut io,
-                        crate::unspecified_socket_addr(),
-                        timer.as_mut(),
-                        self.config,
-                        &self.service,
-                        self.date.get(),
-                    )
-                    .await
-                    .map_err(From::from)
+                    #[cfg(feature = "http1")]
+                    {
+                        let mut io = xitca_io::net::UnixStream::from_std(_io).expect("TODO: handle io error");
+
+                        super::h1::dispatcher::run(
+                            &mut io,
+                            crate::unspecified_socket_addr(),
+                            timer.as_mut(),
+                            self.config,
+                            &self.service,
+                            self.date.get(),
+                            &self.drain,
+                        )
+                        .await
+                        .map_err(From::from)
+                    }
                 }
             }
+        })
+        .await
+    }
+}
+
+// peek connection until it's known if it starts with http/2 connection preface.
+// io error is ignored here and would be surfaced by following tls accept or dispatcher.
+#[cfg(feature = "http2")]
+async fn peek_preface(io: &TcpStream) -> bool {
+    use core::time::Duration;
+
+    use super::h2::h2c::{is_preface, PREFACE};
+
+    const MAX_BACKOFF: Duration = Duration::from_millis(32);
+
+    let mut buf = [0; PREFACE.len()];
+    let mut backoff = Duration::from_millis(1);
+
+    loop {
+        let Ok(n) = io.peek(&mut buf).await else { return false };
+
+        if n == 0 || !is_preface(&buf[..n]) {
+            return false;
         }
+
+        if n == PREFACE.len() {
+            return true;
+        }
+
+        // partial preface. peeked bytes stay in socket buffer and keep it readable so waiting for
+        // readiness would resolve immediately. back off with timer until more bytes arrive.
+        // the total wait is bounded by caller's timer.
+        tokio::time::sleep(backoff).await;
+        backoff = core::cmp::min(backoff * 2, MAX_BACKOFF);
     }
 }
 
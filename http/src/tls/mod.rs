@@ -14,9 +14,14 @@ pub(crate) mod rustls;
 pub(crate) mod rustls_uring;
 
 mod error;
+#[cfg(any(feature = "openssl", feature = "rustls"))]
+mod pool;
 
 pub use error::TlsError;
 
+#[cfg(any(feature = "openssl", feature = "rustls"))]
+pub use pool::HandshakePool;
+
 use xitca_service::Service;
 
 /// A NoOp Tls Acceptor pass through input Stream type.
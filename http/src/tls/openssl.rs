@@ -19,7 +19,7 @@ use xitca_service::Service;
 
 use crate::{http::Version, version::AsVersion};
 
-use super::error::TlsError;
+use super::{error::TlsError, HandshakePool};
 
 /// A wrapper type for [SslStream].
 ///
@@ -41,11 +41,18 @@ impl<Io> AsVersion for TlsStream<Io> {
 #[derive(Clone)]
 pub struct TlsAcceptorBuilder {
     acceptor: TlsAcceptor,
+    pool: Option<HandshakePool>,
 }
 
 impl TlsAcceptorBuilder {
     pub fn new(acceptor: TlsAcceptor) -> Self {
-        Self { acceptor }
+        Self { acceptor, pool: None }
+    }
+
+    /// run tls handshakes on given pool instead of worker threads.
+    pub fn handshake_pool(mut self, pool: HandshakePool) -> Self {
+        self.pool = Some(pool);
+        self
     }
 }
 
@@ -56,6 +63,7 @@ impl Service for TlsAcceptorBuilder {
     async fn call(&self, _: ()) -> Result<Self::Response, Self::Error> {
         let service = TlsAcceptorService {
             acceptor: self.acceptor.clone(),
+            pool: self.pool.clone(),
         };
         Ok(service)
     }
@@ -64,18 +72,36 @@ impl Service for TlsAcceptorBuilder {
 /// Openssl Acceptor. Used to accept a unsecure Stream and upgrade it to a TlsStream.
 pub struct TlsAcceptorService {
     acceptor: TlsAcceptor,
+    pool: Option<HandshakePool>,
 }
 
 impl TlsAcceptorService {
     #[inline(never)]
-    async fn accept<Io: AsyncIo>(&self, io: Io) -> Result<TlsStream<Io>, OpensslError> {
+    async fn accept<Io>(&self, io: Io) -> Result<TlsStream<Io>, OpensslError>
+    where
+        Io: AsyncIo + Send + 'static,
+    {
         let ctx = self.acceptor.context();
         let ssl = Ssl::new(ctx)?;
         let mut io = SslStream::new(ssl, io)?;
         let mut interest = Interest::READABLE;
         loop {
             io.get_mut().ready(interest).await?;
-            match io.accept() {
+            let res = match self.pool {
+                // handshake is driven on pool. only waiting for io readiness happens on worker thread.
+                Some(ref pool) => {
+                    let res;
+                    (res, io) = pool
+                        .run(move || {
+                            let res = io.accept();
+                            (res, io)
+                        })
+                        .await;
+                    res
+                }
+                None => io.accept(),
+            };
+            match res {
                 Ok(_) => return Ok(TlsStream { io }),
                 Err(ref e) if e.code() == ErrorCode::WANT_READ => {
                     interest = Interest::READABLE;
@@ -89,7 +115,10 @@ impl TlsAcceptorService {
     }
 }
 
-impl<Io: AsyncIo> Service<Io> for TlsAcceptorService {
+impl<Io> Service<Io> for TlsAcceptorService
+where
+    Io: AsyncIo + Send + 'static,
+{
     type Response = TlsStream<Io>;
     type Error = OpensslError;
 
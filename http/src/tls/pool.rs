@@ -0,0 +1,115 @@
+use std::{
+    io, panic,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use tokio::sync::{oneshot, Semaphore};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A dedicated thread pool for running cpu heavy tls handshakes.
+///
+/// Handshakes offloaded to the pool do not occupy worker threads that are processing requests of
+/// established connections. The pool is bounded and handshakes would wait asynchronously when the
+/// pool is saturated. It's cheap to clone and clones share the same threads.
+#[derive(Clone)]
+pub struct HandshakePool {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    tx: mpsc::Sender<Job>,
+    permits: Semaphore,
+}
+
+impl HandshakePool {
+    /// Construct a pool with given number of threads. `max_pending` is the max number of
+    /// handshakes that can be queued or running in the pool at the same time.
+    ///
+    /// Threads exit when all clones of the pool are dropped.
+    ///
+    /// # Panics:
+    /// When pass 0 as number of threads or max pending handshakes.
+    pub fn new(threads: usize, max_pending: usize) -> io::Result<Self> {
+        assert_ne!(threads, 0, "There must be at least one handshake thread");
+        assert_ne!(max_pending, 0, "Max pending handshakes must be higher than 0");
+
+        let (tx, rx) = mpsc::channel::<Job>();
+        let rx = Arc::new(Mutex::new(rx));
+
+        for idx in 0..threads {
+            let rx = rx.clone();
+            thread::Builder::new()
+                .name(format!("xitca-http-tls-handshake-{idx}"))
+                .spawn(move || loop {
+                    let job = match rx.lock().unwrap().recv() {
+                        Ok(job) => job,
+                        Err(_) => return,
+                    };
+                    job();
+                })?;
+        }
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                tx,
+                permits: Semaphore::new(max_pending),
+            }),
+        })
+    }
+
+    /// run given function on the pool and wait for it's output.
+    pub(crate) async fn run<F, O>(&self, func: F) -> O
+    where
+        F: FnOnce() -> O + Send + 'static,
+        O: Send + 'static,
+    {
+        let _permit = self
+            .inner
+            .permits
+            .acquire()
+            .await
+            .expect("semaphore must not be closed");
+
+        let (tx, rx) = oneshot::channel();
+        let job = Box::new(move || {
+            let res = panic::catch_unwind(panic::AssertUnwindSafe(func));
+            let _ = tx.send(res);
+        });
+        self.inner
+            .tx
+            .send(job)
+            .expect("handshake threads must not exit before pool");
+
+        match rx.await.expect("handshake job must send it's output") {
+            Ok(o) => o,
+            Err(e) => panic::resume_unwind(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn run() {
+        let pool = HandshakePool::new(2, 1).unwrap();
+
+        let name = pool
+            .run(|| thread::current().name().map(ToOwned::to_owned))
+            .await
+            .unwrap();
+        assert!(name.starts_with("xitca-http-tls-handshake-"));
+
+        let res = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.run(|| panic!("handshake panic")).await }
+        })
+        .await;
+        assert!(res.unwrap_err().is_panic());
+
+        assert_eq!(pool.run(|| 996).await, 996);
+    }
+}
@@ -15,7 +15,7 @@ use xitca_tls::rustls::TlsStream as _TlsStream;
 
 use crate::{http::Version, version::AsVersion};
 
-use super::error::TlsError;
+use super::{error::TlsError, HandshakePool};
 
 pub(crate) type RustlsConfig = Arc<ServerConfig>;
 
@@ -43,11 +43,18 @@ where
 #[derive(Clone)]
 pub struct TlsAcceptorBuilder {
     acceptor: Arc<ServerConfig>,
+    pool: Option<HandshakePool>,
 }
 
 impl TlsAcceptorBuilder {
     pub fn new(acceptor: Arc<ServerConfig>) -> Self {
-        Self { acceptor }
+        Self { acceptor, pool: None }
+    }
+
+    /// run tls handshakes on given pool instead of worker threads.
+    pub fn handshake_pool(mut self, pool: HandshakePool) -> Self {
+        self.pool = Some(pool);
+        self
     }
 }
 
@@ -58,6 +65,7 @@ impl Service for TlsAcceptorBuilder {
     async fn call(&self, _: ()) -> Result<Self::Response, Self::Error> {
         let service = TlsAcceptorService {
             acceptor: self.acceptor.clone(),
+            pool: self.pool.clone(),
         };
         Ok(service)
     }
@@ -66,19 +74,60 @@ impl Service for TlsAcceptorBuilder {
 /// Rustls Acceptor. Used to accept a unsecure Stream and upgrade it to a TlsStream.
 pub struct TlsAcceptorService {
     acceptor: Arc<ServerConfig>,
+    pool: Option<HandshakePool>,
 }
 
-impl<Io: AsyncIo> Service<Io> for TlsAcceptorService {
+impl<Io> Service<Io> for TlsAcceptorService
+where
+    Io: AsyncIo + Send + 'static,
+{
     type Response = TlsStream<Io>;
     type Error = RustlsError;
 
     async fn call(&self, io: Io) -> Result<Self::Response, Self::Error> {
         let conn = ServerConnection::new(self.acceptor.clone())?;
-        let inner = _TlsStream::handshake(io, conn).await?;
+        let inner = match self.pool {
+            Some(ref pool) => handshake_offload(pool, io, conn).await?,
+            None => _TlsStream::handshake(io, conn).await?,
+        };
         Ok(TlsStream { inner })
     }
 }
 
+// drive handshake on pool. only waiting for io readiness happens on worker thread.
+async fn handshake_offload<Io>(
+    pool: &HandshakePool,
+    mut io: Io,
+    mut conn: ServerConnection,
+) -> io::Result<_TlsStream<ServerConnection, Io>>
+where
+    Io: AsyncIo + Send + 'static,
+{
+    loop {
+        let res;
+        (res, io, conn) = pool
+            .run(move || {
+                let res = conn.complete_io(&mut io);
+                (res, io, conn)
+            })
+            .await;
+
+        match res {
+            Ok(_) => return Ok(_TlsStream::from_parts(io, conn)),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e),
+        }
+
+        let interest = match (conn.wants_read(), conn.wants_write()) {
+            (true, true) => Interest::READABLE | Interest::WRITABLE,
+            (true, false) => Interest::READABLE,
+            (false, true) => Interest::WRITABLE,
+            (false, false) => unreachable!(),
+        };
+        io.ready(interest).await?;
+    }
+}
+
 impl<Io> AsyncIo for TlsStream<Io>
 where
     Io: AsyncIo,
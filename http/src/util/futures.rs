@@ -7,11 +7,32 @@ mod queue {
 
     use futures_util::stream::{FuturesUnordered, StreamExt};
 
-    pub(crate) struct Queue<F>(FuturesUnordered<F>);
+    /// Queue of concurrent futures of one connection.
+    ///
+    /// Futures are polled in the order they are woken up and every future is polled at most once
+    /// per [Queue::next2] call. A future keeps waking itself up can not starve other ones.
+    pub(crate) struct Queue<F> {
+        futures: FuturesUnordered<F>,
+        #[cfg(all(feature = "http2", feature = "io-uring"))]
+        cap: usize,
+    }
 
     impl<F: Future> Queue<F> {
         pub(crate) fn new() -> Self {
-            Self(FuturesUnordered::new())
+            Self {
+                futures: FuturesUnordered::new(),
+                #[cfg(all(feature = "http2", feature = "io-uring"))]
+                cap: usize::MAX,
+            }
+        }
+
+        /// Construct a queue holding at most `cap` futures at the same time.
+        #[cfg(all(feature = "http2", feature = "io-uring"))]
+        pub(crate) fn with_capacity(cap: usize) -> Self {
+            Self {
+                futures: FuturesUnordered::new(),
+                cap,
+            }
         }
 
         #[cfg(any(all(feature = "http2", feature = "io-uring"), feature = "http3"))]
@@ -24,22 +45,90 @@ mod queue {
         }
 
         pub(crate) fn is_empty(&self) -> bool {
-            self.0.is_empty()
+            self.futures.is_empty()
+        }
+
+        #[cfg(all(feature = "http2", feature = "io-uring"))]
+        pub(crate) fn is_full(&self) -> bool {
+            self.futures.len() >= self.cap
         }
 
         pub(crate) async fn next2(&mut self) -> F::Output {
-            self.0
+            self.futures
                 .next()
                 .await
                 .expect("Queue::next2 must be called when queue is not empty")
         }
 
+        /// Push future to queue regardless of it's capacity.
         pub(crate) fn push(&self, future: F) {
-            self.0.push(future);
+            self.futures.push(future);
+        }
+
+        /// Push future to queue when it's not full. Future is returned on failure.
+        #[cfg(all(feature = "http2", feature = "io-uring"))]
+        pub(crate) fn try_push(&self, future: F) -> Result<(), F> {
+            if self.is_full() {
+                return Err(future);
+            }
+            self.push(future);
+            Ok(())
         }
 
         pub(crate) async fn drain(&mut self) {
-            while self.0.next().await.is_some() {}
+            while self.futures.next().await.is_some() {}
         }
     }
 }
+
+#[cfg(all(test, any(feature = "http2", feature = "http3")))]
+mod test {
+    use core::{
+        future::{poll_fn, Future},
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    #[cfg(all(feature = "http2", feature = "io-uring"))]
+    #[test]
+    fn capacity() {
+        use core::future::ready;
+
+        let queue = Queue::with_capacity(1);
+        assert!(queue.try_push(ready(())).is_ok());
+        assert!(queue.is_full());
+        assert!(queue.try_push(ready(())).is_err());
+    }
+
+    #[test]
+    fn fairness() {
+        let polled = Rc::new(Cell::new(0));
+
+        let mut queue = Queue::<Pin<Box<dyn Future<Output = usize>>>>::new();
+
+        // a future always wakes itself up and never finishes.
+        let p = polled.clone();
+        queue.push(Box::pin(poll_fn(move |cx| {
+            p.set(p.get() + 1);
+            cx.waker().wake_by_ref();
+            Poll::<usize>::Pending
+        })));
+        queue.push(Box::pin(async { 996 }));
+
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut next = pin!(queue.next2());
+
+        let res = loop {
+            if let Poll::Ready(res) = next.as_mut().poll(&mut cx) {
+                break res;
+            }
+        };
+
+        assert_eq!(res, 996);
+        assert!(polled.get() <= 2);
+    }
+}
@@ -0,0 +1,362 @@
+//! minimal gRPC support on top of http/2 dispatcher.
+//!
+//! This module offers [Grpc] middleware for content-type enforcement and status mapping and
+//! [Codec] for length-prefixed message framing. Protobuf (de)serialization is not a concern of
+//! this module and left to user of it.
+
+use core::{convert::Infallible, fmt};
+
+use std::error;
+
+use xitca_service::{ready::ReadyService, Service};
+
+use crate::{
+    bytes::{Buf, BufMut, Bytes, BytesMut},
+    http::{
+        const_header_name::{GRPC_MESSAGE, GRPC_STATUS},
+        const_header_value::GRPC,
+        header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, TRAILER},
+        IntoResponse, Request, Response, StatusCode,
+    },
+};
+
+/// gRPC status codes.
+///
+/// See <https://github.com/grpc/grpc/blob/master/doc/statuscodes.md> for detail.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Code {
+    Ok = 0,
+    Cancelled = 1,
+    Unknown = 2,
+    InvalidArgument = 3,
+    DeadlineExceeded = 4,
+    NotFound = 5,
+    AlreadyExists = 6,
+    PermissionDenied = 7,
+    ResourceExhausted = 8,
+    FailedPrecondition = 9,
+    Aborted = 10,
+    OutOfRange = 11,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+    DataLoss = 15,
+    Unauthenticated = 16,
+}
+
+impl Code {
+    /// Map http status code to gRPC status code.
+    ///
+    /// See <https://github.com/grpc/grpc/blob/master/doc/http-grpc-status-mapping.md> for detail.
+    pub fn from_http_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::OK => Self::Ok,
+            StatusCode::BAD_REQUEST => Self::Internal,
+            StatusCode::UNAUTHORIZED => Self::Unauthenticated,
+            StatusCode::FORBIDDEN => Self::PermissionDenied,
+            StatusCode::NOT_FOUND => Self::Unimplemented,
+            StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => Self::Unavailable,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Header value of code for `grpc-status` header.
+    pub fn header_value(self) -> HeaderValue {
+        HeaderValue::from(self as u16)
+    }
+}
+
+/// middleware for enforcing gRPC content type and mapping response status to gRPC status.
+///
+/// - request without `application/grpc` content type is responded with `415 Unsupported Media Type`.
+/// - response with non `200 OK` status is rewritten to trailers-only `200 OK` response with according
+///   `grpc-status` header and empty body.
+/// - response without `grpc-status` header gets `grpc-status: 0` as trailer.
+#[derive(Clone, Copy, Default)]
+pub struct Grpc;
+
+impl Grpc {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Service<S> for Grpc {
+    type Response = GrpcService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(GrpcService { service })
+    }
+}
+
+pub struct GrpcService<S> {
+    service: S,
+}
+
+impl<S, ReqB, ResB> Service<Request<ReqB>> for GrpcService<S>
+where
+    S: Service<Request<ReqB>, Response = Response<ResB>>,
+    Bytes: Into<ResB>,
+{
+    type Response = Response<ResB>;
+    type Error = S::Error;
+
+    async fn call(&self, req: Request<ReqB>) -> Result<Self::Response, Self::Error> {
+        if !is_grpc_content_type(req.headers().get(CONTENT_TYPE)) {
+            let mut res = req.into_response(Bytes::new());
+            *res.status_mut() = StatusCode::UNSUPPORTED_MEDIA_TYPE;
+            return Ok(res);
+        }
+
+        let mut res = self.service.call(req).await?;
+
+        let status = res.status();
+        if status != StatusCode::OK {
+            // body of http error is not a gRPC message and must not be sent to client.
+            *res.body_mut() = Bytes::new().into();
+            *res.status_mut() = StatusCode::OK;
+            let headers = res.headers_mut();
+            headers.remove(CONTENT_LENGTH);
+            if !headers.contains_key(GRPC_STATUS) {
+                headers.insert(GRPC_STATUS, Code::from_http_status(status).header_value());
+            }
+            if !headers.contains_key(GRPC_MESSAGE) {
+                if let Ok(msg) = HeaderValue::from_str(status.canonical_reason().unwrap_or_default()) {
+                    headers.insert(GRPC_MESSAGE, msg);
+                }
+            }
+        }
+
+        let headers = res.headers_mut();
+        if !headers.contains_key(GRPC_STATUS) {
+            headers.insert(GRPC_STATUS, Code::Ok.header_value());
+            headers.append(TRAILER, HeaderValue::from_static("grpc-status"));
+        }
+        if !headers.contains_key(CONTENT_TYPE) {
+            headers.insert(CONTENT_TYPE, GRPC);
+        }
+
+        Ok(res)
+    }
+}
+
+impl<S> ReadyService for GrpcService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+// gRPC content type can carry sub type like application/grpc+proto.
+fn is_grpc_content_type(value: Option<&HeaderValue>) -> bool {
+    value
+        .map(|value| {
+            let value = value.as_bytes();
+            value.starts_with(GRPC.as_bytes()) && matches!(value.get(GRPC.len()), None | Some(b'+') | Some(b';'))
+        })
+        .unwrap_or(false)
+}
+
+/// length of prefix for every gRPC message.
+pub const PREFIX_LEN: usize = 5;
+
+/// default max size of a single decoded gRPC message.
+pub const DEFAULT_MESSAGE_LIMIT: usize = 4 * 1024 * 1024;
+
+/// a single length-prefixed gRPC message.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Message {
+    /// compressed flag of message. compression is determined by `grpc-encoding` header.
+    pub compressed: bool,
+    /// payload of message without prefix.
+    pub payload: Bytes,
+}
+
+/// codec for length-prefixed gRPC message framing.
+#[derive(Debug, Clone, Copy)]
+pub struct Codec {
+    limit: usize,
+}
+
+impl Default for Codec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codec {
+    pub const fn new() -> Self {
+        Self {
+            limit: DEFAULT_MESSAGE_LIMIT,
+        }
+    }
+
+    /// Set max size in byte unit a single decoded message can be.
+    pub fn set_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Try to decode a message from given buffer.
+    ///
+    /// `Ok(None)` means more bytes are needed to finish decoding.
+    pub fn decode(&self, buf: &mut BytesMut) -> Result<Option<Message>, ProtocolError> {
+        if buf.len() < PREFIX_LEN {
+            return Ok(None);
+        }
+
+        let compressed = match buf[0] {
+            0 => false,
+            1 => true,
+            flag => return Err(ProtocolError::CompressFlag(flag)),
+        };
+
+        let len = u32::from_be_bytes([buf[1], buf[2], buf[3], buf[4]]) as usize;
+
+        if len > self.limit {
+            return Err(ProtocolError::MessageOverSize(self.limit));
+        }
+
+        if buf.len() < PREFIX_LEN + len {
+            buf.reserve(PREFIX_LEN + len - buf.len());
+            return Ok(None);
+        }
+
+        buf.advance(PREFIX_LEN);
+        let payload = buf.split_to(len).freeze();
+
+        Ok(Some(Message { compressed, payload }))
+    }
+
+    /// Encode given message payload with prefix and write it into buffer.
+    pub fn encode(&self, msg: &[u8], compressed: bool, buf: &mut BytesMut) -> Result<(), ProtocolError> {
+        if msg.len() > self.limit || msg.len() > u32::MAX as usize {
+            return Err(ProtocolError::MessageOverSize(self.limit));
+        }
+
+        buf.reserve(PREFIX_LEN + msg.len());
+        buf.put_u8(compressed as u8);
+        buf.put_u32(msg.len() as u32);
+        buf.put_slice(msg);
+
+        Ok(())
+    }
+}
+
+/// error type for gRPC message framing.
+#[derive(Debug)]
+pub enum ProtocolError {
+    /// compressed flag is neither 0 nor 1.
+    CompressFlag(u8),
+    /// message size goes beyond codec limit.
+    MessageOverSize(usize),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::CompressFlag(flag) => write!(f, "Invalid compressed flag: {flag}"),
+            Self::MessageOverSize(size) => write!(f, "Message size reached limit: {size} bytes."),
+        }
+    }
+}
+
+impl error::Error for ProtocolError {}
+
+#[cfg(test)]
+mod test {
+    use xitca_service::{fn_service, ServiceExt};
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::http::RequestExt;
+
+    use super::*;
+
+    #[test]
+    fn codec() {
+        let codec = Codec::new().set_limit(8);
+        let mut buf = BytesMut::new();
+
+        codec.encode(b"996", false, &mut buf).unwrap();
+        codec.encode(b"251", true, &mut buf).unwrap();
+        assert!(codec.encode(b"996996996", false, &mut buf).is_err());
+
+        let mut partial = buf.split_to(PREFIX_LEN + 1);
+        assert!(codec.decode(&mut partial).unwrap().is_none());
+
+        partial.unsplit(buf);
+        let mut buf = partial;
+
+        let msg = codec.decode(&mut buf).unwrap().unwrap();
+        assert!(!msg.compressed);
+        assert_eq!(msg.payload, b"996".as_slice());
+
+        let msg = codec.decode(&mut buf).unwrap().unwrap();
+        assert!(msg.compressed);
+        assert_eq!(msg.payload, b"251".as_slice());
+
+        assert!(buf.is_empty());
+
+        buf.extend_from_slice(&[2, 0, 0, 0, 0]);
+        assert!(matches!(codec.decode(&mut buf), Err(ProtocolError::CompressFlag(2))));
+    }
+
+    #[test]
+    fn content_type() {
+        assert!(is_grpc_content_type(Some(&GRPC)));
+        assert!(is_grpc_content_type(Some(&HeaderValue::from_static(
+            "application/grpc+proto"
+        ))));
+        assert!(!is_grpc_content_type(Some(&HeaderValue::from_static(
+            "application/grpc-web"
+        ))));
+        assert!(!is_grpc_content_type(None));
+    }
+
+    #[test]
+    fn grpc_middleware() {
+        let service = fn_service(|req: Request<RequestExt<()>>| async move {
+            let (status, body) = if req.uri().path() == "/missing" {
+                (StatusCode::NOT_FOUND, Bytes::from_static(b"not found"))
+            } else {
+                (StatusCode::OK, Bytes::from_static(b"message"))
+            };
+            let mut res = Response::new(body);
+            *res.status_mut() = status;
+            Ok::<_, Infallible>(res)
+        })
+        .enclosed(Grpc::new())
+        .call(())
+        .now_or_panic()
+        .unwrap();
+
+        let res = service.call(Request::default()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let mut req = Request::default();
+        req.headers_mut().insert(CONTENT_TYPE, GRPC);
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(GRPC_STATUS).unwrap(), "0");
+        assert_eq!(res.headers().get(TRAILER).unwrap(), "grpc-status");
+        assert_eq!(res.body().as_ref(), b"message");
+
+        let mut req = Request::builder().uri("/missing").body(RequestExt::default()).unwrap();
+        req.headers_mut().insert(CONTENT_TYPE, GRPC);
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(GRPC_STATUS).unwrap(), "12");
+        assert!(res.headers().get(TRAILER).is_none());
+        assert!(res.body().is_empty());
+    }
+}
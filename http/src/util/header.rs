@@ -0,0 +1,122 @@
+//! normalization of decoded request headers.
+
+use crate::{
+    bytes::BytesMut,
+    config::{DuplicateHeader, HeaderPolicy},
+    http::header::{HeaderMap, HeaderName, HeaderValue, COOKIE},
+};
+
+impl HeaderPolicy {
+    /// apply policy to headers. return name of the duplicate header field when request must be
+    /// rejected.
+    pub(crate) fn apply(&self, headers: &mut HeaderMap) -> Result<(), HeaderName> {
+        if self.trim {
+            for value in headers.values_mut() {
+                let trimmed = value.as_bytes().trim_ascii();
+                if trimmed.len() != value.len() {
+                    // a sub slice of valid header value is always valid.
+                    *value = HeaderValue::from_bytes(trimmed).unwrap();
+                }
+            }
+        }
+
+        // nothing to do when duplicates are kept or every key has exactly one value.
+        if self.duplicate == DuplicateHeader::Keep || headers.keys_len() == headers.len() {
+            return Ok(());
+        }
+
+        let mut names = headers
+            .keys()
+            .filter(|name| headers.get_all(*name).iter().nth(1).is_some())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        match self.duplicate {
+            DuplicateHeader::Keep => unreachable!(),
+            DuplicateHeader::Reject => return Err(names.swap_remove(0)),
+            DuplicateHeader::First => {
+                for name in names {
+                    let value = headers.get(&name).unwrap().clone();
+                    headers.insert(name, value);
+                }
+            }
+            DuplicateHeader::Join => {
+                for name in names {
+                    let sep: &[u8] = if name == COOKIE { b"; " } else { b", " };
+                    let mut buf = BytesMut::new();
+                    for (i, value) in headers.get_all(&name).iter().enumerate() {
+                        if i > 0 {
+                            buf.extend_from_slice(sep);
+                        }
+                        buf.extend_from_slice(value.as_bytes());
+                    }
+                    // valid header values joined with visible separator is always valid.
+                    let value = HeaderValue::from_maybe_shared(buf.freeze()).unwrap();
+                    headers.insert(name, value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.append("accept", HeaderValue::from_static(" text/html "));
+        headers.append("accept", HeaderValue::from_static("text/plain"));
+        headers.append("cookie", HeaderValue::from_static("a=1"));
+        headers.append("cookie", HeaderValue::from_static("b=2"));
+        headers.append("host", HeaderValue::from_static("localhost"));
+        headers
+    }
+
+    fn policy(duplicate: DuplicateHeader, trim: bool) -> HeaderPolicy {
+        HeaderPolicy { duplicate, trim }
+    }
+
+    #[test]
+    fn keep() {
+        let mut h = headers();
+        policy(DuplicateHeader::Keep, false).apply(&mut h).unwrap();
+        assert_eq!(h, headers());
+
+        policy(DuplicateHeader::Keep, true).apply(&mut h).unwrap();
+        let accept = h.get_all("accept").iter().collect::<Vec<_>>();
+        assert_eq!(accept, ["text/html", "text/plain"]);
+    }
+
+    #[test]
+    fn join() {
+        let mut h = headers();
+        policy(DuplicateHeader::Join, true).apply(&mut h).unwrap();
+        assert_eq!(h.len(), 3);
+        assert_eq!(h.get("accept").unwrap(), "text/html, text/plain");
+        assert_eq!(h.get("cookie").unwrap(), "a=1; b=2");
+        assert_eq!(h.get("host").unwrap(), "localhost");
+    }
+
+    #[test]
+    fn first() {
+        let mut h = headers();
+        policy(DuplicateHeader::First, false).apply(&mut h).unwrap();
+        assert_eq!(h.len(), 3);
+        assert_eq!(h.get("accept").unwrap(), " text/html ");
+        assert_eq!(h.get("cookie").unwrap(), "a=1");
+    }
+
+    #[test]
+    fn reject() {
+        let mut h = headers();
+        let name = policy(DuplicateHeader::Reject, false).apply(&mut h).unwrap_err();
+        assert!(name == "accept" || name == "cookie");
+
+        h.remove("accept");
+        h.remove("cookie");
+        policy(DuplicateHeader::Reject, false).apply(&mut h).unwrap();
+    }
+}
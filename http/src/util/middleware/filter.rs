@@ -0,0 +1,189 @@
+use core::convert::Infallible;
+
+use std::net::SocketAddr;
+
+use xitca_service::{ready::ReadyService, Service};
+
+use crate::{
+    body::ResponseBody,
+    http::{header::HeaderMap, Method, Request, RequestExt, Response, Uri, Version},
+};
+
+/// A middleware running a predicate against request head before the request is passed to
+/// inner service.
+///
+/// The predicate sees method, uri, version, headers and peer address of request and can reject
+/// it by returning a response. Rejected request is never passed to inner service and it's body
+/// is not read. For Http/1 connection with unread request body the connection is closed after
+/// the response is sent.
+///
+/// The middleware is meant to sit directly in front of routing for cheap rejections like WAF
+/// style filtering and auth failures. Anything expensive or async belongs to inner service.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_http::{
+/// #     bytes::Bytes,
+/// #     http::{header::AUTHORIZATION, Response, StatusCode},
+/// #     util::middleware::RequestFilter,
+/// # };
+/// let filter = RequestFilter::new(|head| {
+///     if head.headers().contains_key(AUTHORIZATION) {
+///         return None;
+///     }
+///     let mut res = Response::new(Bytes::new());
+///     *res.status_mut() = StatusCode::UNAUTHORIZED;
+///     Some(res)
+/// });
+/// ```
+#[derive(Clone)]
+pub struct RequestFilter<F> {
+    filter: F,
+}
+
+impl<F> RequestFilter<F> {
+    /// Construct a new filter with given predicate. Returning `None` from predicate lets the
+    /// request pass and `Some(Response)` rejects it with the response.
+    pub fn new<R>(filter: F) -> Self
+    where
+        F: Fn(RequestHead<'_>) -> Option<Response<R>>,
+    {
+        Self { filter }
+    }
+}
+
+impl<F, S> Service<S> for RequestFilter<F>
+where
+    F: Clone,
+{
+    type Response = RequestFilterService<F, S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(RequestFilterService {
+            filter: self.filter.clone(),
+            service,
+        })
+    }
+}
+
+pub struct RequestFilterService<F, S> {
+    filter: F,
+    service: S,
+}
+
+impl<F, S, ReqB, ResB, R> Service<Request<RequestExt<ReqB>>> for RequestFilterService<F, S>
+where
+    F: Fn(RequestHead<'_>) -> Option<Response<R>>,
+    S: Service<Request<RequestExt<ReqB>>, Response = Response<ResponseBody<ResB>>>,
+    ResponseBody<ResB>: From<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    async fn call(&self, req: Request<RequestExt<ReqB>>) -> Result<Self::Response, Self::Error> {
+        let head = RequestHead {
+            method: req.method(),
+            uri: req.uri(),
+            version: req.version(),
+            headers: req.headers(),
+            addr: req.body().socket_addr(),
+        };
+
+        match (self.filter)(head) {
+            None => self.service.call(req).await,
+            Some(res) => Ok(res.map(ResponseBody::from)),
+        }
+    }
+}
+
+impl<F, S> ReadyService for RequestFilterService<F, S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// Borrowed head of request passed to predicate of [RequestFilter].
+#[derive(Clone, Copy)]
+pub struct RequestHead<'a> {
+    method: &'a Method,
+    uri: &'a Uri,
+    version: Version,
+    headers: &'a HeaderMap,
+    addr: &'a SocketAddr,
+}
+
+impl<'a> RequestHead<'a> {
+    #[inline]
+    pub fn method(&self) -> &'a Method {
+        self.method
+    }
+
+    #[inline]
+    pub fn uri(&self) -> &'a Uri {
+        self.uri
+    }
+
+    #[inline]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    #[inline]
+    pub fn headers(&self) -> &'a HeaderMap {
+        self.headers
+    }
+
+    /// Socket address of peer.
+    #[inline]
+    pub fn socket_addr(&self) -> &'a SocketAddr {
+        self.addr
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_service::fn_service;
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{bytes::Bytes, http::StatusCode};
+
+    use super::*;
+
+    #[test]
+    fn filter() {
+        let service = fn_service(|_: Request<RequestExt<()>>| async {
+            let mut res = Response::new(ResponseBody::<()>::None);
+            *res.status_mut() = StatusCode::NO_CONTENT;
+            Ok::<_, Infallible>(res)
+        })
+        .call(())
+        .now_or_panic()
+        .unwrap();
+
+        let service = RequestFilter::new(|head| {
+            if head.method() == Method::POST {
+                return Some(Response::new(Bytes::from_static(b"rejected")));
+            }
+            None
+        })
+        .call(service)
+        .now_or_panic()
+        .unwrap();
+
+        let res = service.call(Request::default()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+        let mut req = Request::default();
+        *req.method_mut() = Method::POST;
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(matches!(res.body(), ResponseBody::Bytes { bytes } if bytes == "rejected"));
+    }
+}
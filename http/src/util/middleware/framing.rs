@@ -0,0 +1,375 @@
+use core::{
+    convert::Infallible,
+    fmt,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use tracing::error;
+use xitca_service::{ready::ReadyService, Service};
+
+use crate::{
+    body::NONE_BODY_HINT,
+    bytes::Buf,
+    http::{
+        header::{HeaderMap, CONTENT_LENGTH, TRANSFER_ENCODING},
+        response::Parts,
+        BorrowReq, Method, Response, StatusCode,
+    },
+};
+
+/// A debug middleware validating framing invariants of outgoing responses.
+///
+/// Following violations are reported:
+/// - response to HEAD request or with 1xx/204/304 status carrying body.
+/// - 1xx/204 response carrying `Content-Length` header.
+/// - response carrying both `Transfer-Encoding` and `Content-Length` headers.
+/// - `Content-Length` header not matching exact size hint of body or the bytes body actually yields.
+///
+/// Violations are logged with `tracing::error` by default and can be turned into panics with
+/// [FramingAudit::set_panic]. The middleware adds per chunk overhead and is meant for catching
+/// handler bugs in development and tests.
+#[derive(Clone, Copy, Default)]
+pub struct FramingAudit {
+    panic: bool,
+}
+
+impl FramingAudit {
+    pub const fn new() -> Self {
+        Self { panic: false }
+    }
+
+    /// Panic on violation instead of logging it.
+    pub fn set_panic(mut self, panic: bool) -> Self {
+        self.panic = panic;
+        self
+    }
+
+    fn report(&self, violation: Violation) {
+        if self.panic {
+            panic!("framing violation: {violation}");
+        }
+        error!("framing violation: {violation}");
+    }
+}
+
+impl<S> Service<S> for FramingAudit {
+    type Response = FramingAuditService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(FramingAuditService { service, audit: *self })
+    }
+}
+
+pub struct FramingAuditService<S> {
+    service: S,
+    audit: FramingAudit,
+}
+
+impl<S, Req, ResB, T, E> Service<Req> for FramingAuditService<S>
+where
+    Req: BorrowReq<Method>,
+    S: Service<Req, Response = Response<ResB>>,
+    ResB: Stream<Item = Result<T, E>>,
+    T: Buf,
+{
+    type Response = Response<AuditBody<ResB>>;
+    type Error = S::Error;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let head = req.borrow() == Method::HEAD;
+        let res = self.service.call(req).await?;
+
+        let (parts, body) = res.into_parts();
+
+        let bodyless = head || is_bodyless(parts.status);
+        if let Err(violation) = check(bodyless, &parts, body.size_hint()) {
+            self.audit.report(violation);
+        }
+
+        let expect = if bodyless {
+            None
+        } else {
+            content_length(&parts.headers).ok().flatten()
+        };
+
+        let body = AuditBody {
+            body,
+            expect,
+            seen: 0,
+            audit: self.audit,
+        };
+
+        Ok(Response::from_parts(parts, body))
+    }
+}
+
+impl<S> ReadyService for FramingAuditService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+pin_project! {
+    /// Body type counting bytes yielded by inner body against declared `Content-Length`.
+    pub struct AuditBody<B> {
+        #[pin]
+        body: B,
+        expect: Option<usize>,
+        seen: usize,
+        audit: FramingAudit,
+    }
+}
+
+impl<B, T, E> Stream for AuditBody<B>
+where
+    B: Stream<Item = Result<T, E>>,
+    T: Buf,
+{
+    type Item = B::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let res = ready!(this.body.poll_next(cx));
+
+        if let Some(expect) = *this.expect {
+            match res {
+                Some(Ok(ref chunk)) => {
+                    *this.seen += chunk.remaining();
+                    if *this.seen > expect {
+                        // report once.
+                        *this.expect = None;
+                        this.audit.report(Violation::BodyOverflow {
+                            expect,
+                            seen: *this.seen,
+                        });
+                    }
+                }
+                None if *this.seen != expect => {
+                    *this.expect = None;
+                    this.audit.report(Violation::BodyUnderflow {
+                        expect,
+                        seen: *this.seen,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Poll::Ready(res)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.body.size_hint()
+    }
+}
+
+fn is_bodyless(status: StatusCode) -> bool {
+    status.is_informational() || status == StatusCode::NO_CONTENT || status == StatusCode::NOT_MODIFIED
+}
+
+fn content_length(headers: &HeaderMap) -> Result<Option<usize>, Violation> {
+    headers
+        .get(CONTENT_LENGTH)
+        .map(|v| {
+            v.to_str()
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .ok_or(Violation::InvalidContentLength)
+        })
+        .transpose()
+}
+
+fn check(bodyless: bool, parts: &Parts, hint: (usize, Option<usize>)) -> Result<(), Violation> {
+    let headers = &parts.headers;
+
+    if headers.contains_key(TRANSFER_ENCODING) && headers.contains_key(CONTENT_LENGTH) {
+        return Err(Violation::TransferEncodingWithContentLength);
+    }
+
+    if (parts.status.is_informational() || parts.status == StatusCode::NO_CONTENT)
+        && headers.contains_key(CONTENT_LENGTH)
+    {
+        return Err(Violation::ContentLengthNotAllowed(parts.status));
+    }
+
+    if bodyless {
+        return match hint {
+            NONE_BODY_HINT | (0, Some(0)) => Ok(()),
+            _ => Err(Violation::BodyNotAllowed(parts.status)),
+        };
+    }
+
+    match (content_length(headers)?, hint) {
+        (Some(len), (low, Some(up))) if low == up && hint != NONE_BODY_HINT && len != up => {
+            Err(Violation::SizeHintMismatch { expect: len, hint: up })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Violation {
+    TransferEncodingWithContentLength,
+    ContentLengthNotAllowed(StatusCode),
+    InvalidContentLength,
+    BodyNotAllowed(StatusCode),
+    SizeHintMismatch { expect: usize, hint: usize },
+    BodyOverflow { expect: usize, seen: usize },
+    BodyUnderflow { expect: usize, seen: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::TransferEncodingWithContentLength => {
+                f.write_str("response has both Transfer-Encoding and Content-Length headers")
+            }
+            Self::ContentLengthNotAllowed(status) => {
+                write!(f, "response with {status} status has Content-Length header")
+            }
+            Self::InvalidContentLength => f.write_str("response has invalid Content-Length header"),
+            Self::BodyNotAllowed(status) => {
+                write!(f, "response with {status} status or to HEAD request has body")
+            }
+            Self::SizeHintMismatch { expect, hint } => {
+                write!(f, "Content-Length is {expect} but body size hint is {hint}")
+            }
+            Self::BodyOverflow { expect, seen } => {
+                write!(f, "Content-Length is {expect} but body yielded at least {seen} bytes")
+            }
+            Self::BodyUnderflow { expect, seen } => {
+                write!(f, "Content-Length is {expect} but body yielded {seen} bytes")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{future::poll_fn, pin::pin};
+
+    use xitca_service::{fn_service, ServiceExt};
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::Once,
+        bytes::Bytes,
+        http::{header::HeaderValue, Request, RequestExt},
+    };
+
+    use super::*;
+
+    fn parts(status: StatusCode, headers: &[(&'static str, &'static str)]) -> Parts {
+        let mut res = Response::new(());
+        *res.status_mut() = status;
+        for (k, v) in headers {
+            res.headers_mut().insert(*k, HeaderValue::from_static(v));
+        }
+        res.into_parts().0
+    }
+
+    #[test]
+    fn check_head() {
+        let p = parts(
+            StatusCode::OK,
+            &[("content-length", "5"), ("transfer-encoding", "chunked")],
+        );
+        assert_eq!(
+            check(false, &p, (5, Some(5))),
+            Err(Violation::TransferEncodingWithContentLength)
+        );
+
+        let p = parts(StatusCode::NO_CONTENT, &[("content-length", "0")]);
+        assert_eq!(
+            check(true, &p, NONE_BODY_HINT),
+            Err(Violation::ContentLengthNotAllowed(StatusCode::NO_CONTENT))
+        );
+
+        let p = parts(StatusCode::NOT_MODIFIED, &[]);
+        assert_eq!(check(true, &p, NONE_BODY_HINT), Ok(()));
+        assert_eq!(
+            check(true, &p, (3, Some(3))),
+            Err(Violation::BodyNotAllowed(StatusCode::NOT_MODIFIED))
+        );
+
+        let p = parts(StatusCode::OK, &[("content-length", "5")]);
+        assert_eq!(check(false, &p, (5, Some(5))), Ok(()));
+        assert_eq!(check(false, &p, (0, None)), Ok(()));
+        assert_eq!(
+            check(false, &p, (3, Some(3))),
+            Err(Violation::SizeHintMismatch { expect: 5, hint: 3 })
+        );
+
+        let p = parts(StatusCode::OK, &[("content-length", "five")]);
+        assert_eq!(check(false, &p, (0, None)), Err(Violation::InvalidContentLength));
+    }
+
+    // stream without exact size hint so mismatch can only be caught when streaming.
+    struct Unsized(Option<Bytes>);
+
+    impl Stream for Unsized {
+        type Item = Result<Bytes, Infallible>;
+
+        fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.get_mut().0.take().map(Ok))
+        }
+    }
+
+    async fn short(_: Request<RequestExt<()>>) -> Result<Response<Unsized>, Infallible> {
+        let mut res = Response::new(Unsized(Some(Bytes::from_static(b"foo"))));
+        res.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from_static("5"));
+        Ok(res)
+    }
+
+    async fn exact(_: Request<RequestExt<()>>) -> Result<Response<Once<Bytes>>, Infallible> {
+        let mut res = Response::new(Once::new(Bytes::from_static(b"hello")));
+        res.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from_static("5"));
+        Ok(res)
+    }
+
+    fn drain<B, T, E>(body: B)
+    where
+        B: Stream<Item = Result<T, E>>,
+    {
+        let mut body = pin!(body);
+        while poll_fn(|cx| body.as_mut().poll_next(cx)).now_or_panic().is_some() {}
+    }
+
+    #[test]
+    #[should_panic(expected = "framing violation: Content-Length is 5 but body yielded 3 bytes")]
+    fn body_underflow() {
+        let res = fn_service(short)
+            .enclosed(FramingAudit::new().set_panic(true))
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(Request::new(RequestExt::default()))
+            .now_or_panic()
+            .unwrap();
+        drain(res.into_body());
+    }
+
+    #[test]
+    fn body_exact() {
+        let res = fn_service(exact)
+            .enclosed(FramingAudit::new().set_panic(true))
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(Request::new(RequestExt::default()))
+            .now_or_panic()
+            .unwrap();
+        drain(res.into_body());
+    }
+}
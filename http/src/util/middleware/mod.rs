@@ -1,5 +1,7 @@
 mod context_priv;
 mod extension;
+mod filter;
+mod framing;
 mod logger;
 
 pub mod context {
@@ -11,6 +13,8 @@ pub mod context {
 mod socket_config;
 
 pub use extension::Extension;
+pub use filter::{RequestFilter, RequestFilterService, RequestHead};
+pub use framing::{AuditBody, FramingAudit, FramingAuditService};
 pub use logger::Logger;
 
 #[cfg(not(target_family = "wasm"))]
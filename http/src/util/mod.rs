@@ -7,5 +7,10 @@ pub mod service;
 #[cfg(any(feature = "http1", feature = "http2"))]
 pub mod buffered;
 pub(crate) mod futures;
+pub(crate) mod span;
+#[cfg(any(feature = "http1", feature = "http2"))]
+pub(crate) mod header;
+#[cfg(feature = "http2")]
+pub mod grpc;
 #[cfg(feature = "runtime")]
 pub(crate) mod timer;
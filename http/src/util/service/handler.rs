@@ -97,6 +97,12 @@ where
 /// assert_eq!(extract.0, input.as_str());
 /// # }
 /// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can not be extracted from `{Req}`",
+    label = "`{Self}` does not implement `FromRequest<'_, {Req}>`",
+    note = "async function passed to `handler_service` can only receive types that implement `FromRequest`",
+    note = "up to 16 arguments are supported. consider grouping arguments into tuple when more are needed"
+)]
 pub trait FromRequest<'a, Req>: Sized {
     // Used to construct the type for any lifetime 'b.
     type Type<'b>: FromRequest<'b, Req, Error = Self::Error>;
@@ -138,9 +144,21 @@ from_req_impl! { A, B, C, D, E, F, }
 from_req_impl! { A, B, C, D, E, F, G, }
 from_req_impl! { A, B, C, D, E, F, G, H, }
 from_req_impl! { A, B, C, D, E, F, G, H, I, }
+from_req_impl! { A, B, C, D, E, F, G, H, I, J, }
+from_req_impl! { A, B, C, D, E, F, G, H, I, J, K, }
+from_req_impl! { A, B, C, D, E, F, G, H, I, J, K, L, }
+from_req_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, }
+from_req_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, N, }
+from_req_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, }
+from_req_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, }
 
 /// Make Response with ownership of Req.
 /// The Output type is what returns from [handler_service] function.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can not be used as response of `{Req}`",
+    label = "`{Self}` does not implement `Responder<{Req}>`",
+    note = "return type of async function passed to `handler_service` must implement `Responder`"
+)]
 pub trait Responder<Req> {
     type Output;
 
@@ -249,6 +267,44 @@ mod test {
         assert_eq!(res.status(), StatusCode::MULTI_STATUS);
     }
 
+    #[allow(clippy::too_many_arguments)]
+    async fn handler_16(
+        e1: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        _: u32,
+        e16: u64,
+    ) -> StatusCode {
+        assert_eq!(e1, 996);
+        assert_eq!(e16, 996);
+
+        StatusCode::MULTI_STATUS
+    }
+
+    #[test]
+    fn extract_16_arity() {
+        let res = handler_service(handler_16)
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(Request::default())
+            .now_or_panic()
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::MULTI_STATUS);
+    }
+
     #[cfg(feature = "router")]
     #[test]
     fn handler_in_router() {
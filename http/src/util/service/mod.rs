@@ -1,12 +1,17 @@
 pub mod handler;
 pub mod route;
 
+#[cfg(feature = "router")]
+mod router_dynamic;
 #[cfg(feature = "router")]
 mod router_priv;
 
 #[cfg(feature = "router")]
 pub mod router {
-    pub use super::router_priv::{IntoObject, MatchError, Params, Router, RouterError, RouterGen, RouterMapErr};
+    pub use super::router_dynamic::{DynamicRouter, DynamicRouterService, RouterHandle};
+    pub use super::router_priv::{
+        IntoObject, MatchError, MatchedPath, Params, Router, RouterError, RouterGen, RouterMapErr,
+    };
 }
 
 #[cfg(feature = "router")]
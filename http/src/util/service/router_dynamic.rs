@@ -0,0 +1,312 @@
+use core::{
+    cell::RefCell,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use xitca_service::{object::ServiceObject, ready::ReadyService, Service};
+
+use crate::http::{BorrowReq, BorrowReqMut, Uri};
+
+use super::router_priv::{IntoObject, MatchedPath, Params, RouterError, RouterGen};
+
+/// Router with route table that can be updated at runtime through [RouterHandle].
+///
+/// Constructed from [Router::into_dynamic](super::router_priv::Router::into_dynamic).
+/// Every worker thread lazily rebuild it's local routes when it observes a route table update.
+/// Only newly inserted route services are constructed and unchanged ones are reused.
+pub struct DynamicRouter<Obj> {
+    shared: Arc<Shared<Obj>>,
+}
+
+/// Handle for inserting and removing routes of a running [DynamicRouter].
+///
+/// Handle is thread safe and can be cloned and shared freely.
+pub struct RouterHandle<Obj> {
+    shared: Arc<Shared<Obj>>,
+}
+
+impl<Obj> Clone for RouterHandle<Obj> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+type Routes<Obj> = HashMap<Cow<'static, str>, Arc<Obj>>;
+
+struct Shared<Obj> {
+    routes: Mutex<Routes<Obj>>,
+    version: AtomicUsize,
+}
+
+impl<Obj> DynamicRouter<Obj> {
+    pub(super) fn new(routes: HashMap<Cow<'static, str>, Obj>) -> (Self, RouterHandle<Obj>) {
+        let routes = routes.into_iter().map(|(path, obj)| (path, Arc::new(obj))).collect();
+        let shared = Arc::new(Shared {
+            routes: Mutex::new(routes),
+            version: AtomicUsize::new(0),
+        });
+        (Self { shared: shared.clone() }, RouterHandle { shared })
+    }
+}
+
+impl<Obj> RouterHandle<Obj> {
+    /// Insert a new service builder to given path. Existing service on the same path would be
+    /// replaced. See [Router::insert](super::router_priv::Router::insert) for detail.
+    ///
+    /// Running workers pick up the change on their next request.
+    pub fn insert<F, Arg, Req>(&self, path: &'static str, mut builder: F)
+    where
+        F: Service<Arg> + RouterGen + Send + Sync,
+        F::Response: Service<Req>,
+        Req: IntoObject<F::ErrGen<F>, Arg, Object = Obj>,
+    {
+        let path = builder.path_gen(path);
+        let obj = Arc::new(Req::into_object(F::err_gen(builder)));
+        self.update(|routes| {
+            routes.insert(path, obj);
+        });
+    }
+
+    /// Remove service from given path. Return false when there is no service on the path.
+    ///
+    /// Nested router inserted on a prefix path can be removed with the same prefix path.
+    pub fn remove(&self, path: &str) -> bool {
+        let mut removed = false;
+        self.update(|routes| {
+            removed = routes.remove(path).is_some() || {
+                let nest = format!("{}/:r", path.strip_suffix('/').unwrap_or(path));
+                routes.remove(nest.as_str()).is_some()
+            };
+        });
+        removed
+    }
+
+    fn update<F>(&self, func: F)
+    where
+        F: FnOnce(&mut Routes<Obj>),
+    {
+        let mut routes = self.shared.routes.lock().unwrap();
+        func(&mut routes);
+        // bump version while holding the lock so readers always observe a table matching version.
+        self.shared.version.fetch_add(1, Ordering::Release);
+    }
+}
+
+impl<Obj, Arg> Service<Arg> for DynamicRouter<Obj>
+where
+    Obj: Service<Arg>,
+    Obj::Error: fmt::Debug,
+    Arg: Clone,
+{
+    type Response = DynamicRouterService<Obj, Obj::Response, Arg>;
+    type Error = Obj::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let (version, snapshot) = self.shared.snapshot();
+
+        let mut services = HashMap::new();
+        let mut routes = xitca_router::Router::new();
+        for (path, obj) in snapshot {
+            let service = Rc::new(Service::call(&*obj, arg.clone()).await?);
+            routes
+                .insert(path.to_string(), (MatchedPath::new(&path), service.clone()))
+                .unwrap();
+            services.insert(path, (obj, service));
+        }
+
+        Ok(DynamicRouterService {
+            shared: self.shared.clone(),
+            arg,
+            state: RefCell::new(State {
+                version,
+                services,
+                routes: Rc::new(routes),
+            }),
+        })
+    }
+}
+
+impl<Obj> Shared<Obj> {
+    fn snapshot(&self) -> (usize, Routes<Obj>) {
+        let routes = self.routes.lock().unwrap();
+        let version = self.version.load(Ordering::Acquire);
+        (version, routes.clone())
+    }
+}
+
+pub struct DynamicRouterService<Obj, S, Arg> {
+    shared: Arc<Shared<Obj>>,
+    arg: Arg,
+    state: RefCell<State<Obj, S>>,
+}
+
+struct State<Obj, S> {
+    version: usize,
+    services: HashMap<Cow<'static, str>, (Arc<Obj>, Rc<S>)>,
+    routes: Rc<xitca_router::Router<(MatchedPath, Rc<S>)>>,
+}
+
+impl<Obj, S, Arg> DynamicRouterService<Obj, S, Arg>
+where
+    Obj: Service<Arg, Response = S>,
+    Obj::Error: fmt::Debug,
+    Arg: Clone,
+{
+    async fn try_update(&self) -> Rc<xitca_router::Router<(MatchedPath, Rc<S>)>> {
+        {
+            let state = self.state.borrow();
+            if state.version == self.shared.version.load(Ordering::Acquire) {
+                return state.routes.clone();
+            }
+        }
+
+        let (version, snapshot) = self.shared.snapshot();
+
+        let mut services = HashMap::new();
+        let mut routes = xitca_router::Router::new();
+        for (path, obj) in snapshot {
+            let service = self
+                .state
+                .borrow()
+                .services
+                .get(&path)
+                .filter(|(o, _)| Arc::ptr_eq(o, &obj))
+                .map(|(_, s)| s.clone());
+
+            let service = match service {
+                Some(service) => service,
+                // a failed route is excluded from route table and would be treated as not found.
+                None => match Service::call(&*obj, self.arg.clone()).await {
+                    Ok(service) => Rc::new(service),
+                    Err(e) => {
+                        tracing::error!("dynamic router failed to construct service for path: {path}. error: {e:?}");
+                        continue;
+                    }
+                },
+            };
+
+            if let Err(e) = routes.insert(path.to_string(), (MatchedPath::new(&path), service.clone())) {
+                tracing::error!("dynamic router failed to insert path: {path}. error: {e}");
+                continue;
+            }
+
+            services.insert(path, (obj, service));
+        }
+
+        let routes = Rc::new(routes);
+
+        let mut state = self.state.borrow_mut();
+        // concurrent update from other request may have finished with a newer version.
+        if version >= state.version {
+            *state = State {
+                version,
+                services,
+                routes: routes.clone(),
+            };
+        }
+
+        routes
+    }
+}
+
+impl<Obj, S, Arg, Req, E> Service<Req> for DynamicRouterService<Obj, S, Arg>
+where
+    Obj: Service<Arg, Response = S>,
+    Obj::Error: fmt::Debug,
+    Arg: Clone,
+    S: ServiceObject<Req, Error = RouterError<E>>,
+    Req: BorrowReq<Uri> + BorrowReqMut<Params> + BorrowReqMut<MatchedPath>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    async fn call(&self, mut req: Req) -> Result<Self::Response, Self::Error> {
+        let routes = self.try_update().await;
+        let xitca_router::Match {
+            value: (path, service),
+            params,
+        } = routes.at(req.borrow().path()).map_err(RouterError::First)?;
+        *BorrowReqMut::<Params>::borrow_mut(&mut req) = params;
+        *BorrowReqMut::<MatchedPath>::borrow_mut(&mut req) = path.clone();
+        ServiceObject::call(&**service, req).await
+    }
+}
+
+impl<Obj, S, Arg> ReadyService for DynamicRouterService<Obj, S, Arg> {
+    type Ready = ();
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {}
+}
+
+#[cfg(test)]
+mod test {
+    use core::convert::Infallible;
+
+    use xitca_service::{fn_service, Service};
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        http::{Request, RequestExt, Response, StatusCode},
+        util::service::router::Router,
+    };
+
+    async fn ok(_: Request<RequestExt<()>>) -> Result<Response<()>, Infallible> {
+        Ok(Response::new(()))
+    }
+
+    async fn accepted(_: Request<RequestExt<()>>) -> Result<Response<()>, Infallible> {
+        let mut res = Response::new(());
+        *res.status_mut() = StatusCode::ACCEPTED;
+        Ok(res)
+    }
+
+    fn req(path: &str) -> Request<RequestExt<()>> {
+        Request::builder().uri(path).body(Default::default()).unwrap()
+    }
+
+    #[test]
+    fn insert_remove() {
+        let (router, handle) = Router::new().insert("/", fn_service(ok)).into_dynamic();
+
+        let service = router.call(()).now_or_panic().unwrap();
+
+        assert!(service.call(req("/")).now_or_panic().is_ok());
+        assert!(service.call(req("/foo")).now_or_panic().is_err());
+
+        handle.insert("/foo", fn_service(ok));
+        assert!(service.call(req("/foo")).now_or_panic().is_ok());
+
+        handle.insert("/foo", fn_service(accepted));
+        let res = service.call(req("/foo")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+
+        assert!(handle.remove("/foo"));
+        assert!(!handle.remove("/foo"));
+        assert!(service.call(req("/foo")).now_or_panic().is_err());
+        assert!(service.call(req("/")).now_or_panic().is_ok());
+    }
+
+    #[test]
+    fn nest() {
+        let (router, handle) = Router::new().insert("/", fn_service(ok)).into_dynamic();
+
+        let service = router.call(()).now_or_panic().unwrap();
+
+        handle.insert("/scope", Router::new().insert("/nest", fn_service(ok)));
+        assert!(service.call(req("/scope/nest")).now_or_panic().is_ok());
+
+        assert!(handle.remove("/scope"));
+        assert!(service.call(req("/scope/nest")).now_or_panic().is_err());
+    }
+}
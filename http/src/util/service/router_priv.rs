@@ -2,7 +2,7 @@ pub use xitca_router::{params::Params, MatchError};
 
 use core::marker::PhantomData;
 
-use std::{borrow::Cow, collections::HashMap};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
 
 use xitca_service::{
     object::{BoxedServiceObject, BoxedSyncServiceObject},
@@ -13,7 +13,32 @@ use xitca_service::{
 
 use crate::http::{BorrowReq, BorrowReqMut, Request, Uri};
 
-use super::{handler::HandlerService, route::Route};
+use super::{
+    handler::HandlerService,
+    route::Route,
+    router_dynamic::{DynamicRouter, RouterHandle},
+};
+
+/// Route pattern matched by [Router] for current request. e.g. `/users/:id` instead of
+/// the concrete `/users/996`.
+///
+/// Empty when request has not been routed. When routers are nested the innermost matched
+/// pattern is kept.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MatchedPath(Option<Arc<str>>);
+
+impl MatchedPath {
+    pub(super) fn new(path: &str) -> Self {
+        Self(Some(Arc::from(path)))
+    }
+
+    /// Returns matched route pattern as string slice. Empty string when request has not been
+    /// routed.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self.0.as_deref().unwrap_or("")
+    }
+}
 
 /// Simple router for matching path and call according service.
 ///
@@ -61,6 +86,11 @@ impl<Obj> Router<Obj> {
             .is_none());
         self
     }
+
+    /// Convert to a [DynamicRouter] and a [RouterHandle] that can insert/remove routes at runtime.
+    pub fn into_dynamic(self) -> (DynamicRouter<Obj>, RouterHandle<Obj>) {
+        DynamicRouter::new(self.routes)
+    }
 }
 
 /// trait for specialized route generation when utilizing [Router::insert].
@@ -223,7 +253,9 @@ where
 
         for (path, service) in self.routes.iter() {
             let service = service.call(arg.clone()).await?;
-            routes.insert(path.to_string(), service).unwrap();
+            routes
+                .insert(path.to_string(), (MatchedPath::new(path), service))
+                .unwrap();
         }
 
         Ok(RouterService { routes })
@@ -231,13 +263,13 @@ where
 }
 
 pub struct RouterService<S> {
-    routes: xitca_router::Router<S>,
+    routes: xitca_router::Router<(MatchedPath, S)>,
 }
 
 impl<S, Req, E> Service<Req> for RouterService<S>
 where
     S: xitca_service::object::ServiceObject<Req, Error = RouterError<E>>,
-    Req: BorrowReq<Uri> + BorrowReqMut<Params>,
+    Req: BorrowReq<Uri> + BorrowReqMut<Params> + BorrowReqMut<MatchedPath>,
 {
     type Response = S::Response;
     type Error = S::Error;
@@ -248,10 +280,13 @@ where
     #[inline]
     fn call(&self, mut req: Req) -> impl core::future::Future<Output = Result<Self::Response, Self::Error>> {
         async {
-            let xitca_router::Match { value, params } =
-                self.routes.at(req.borrow().path()).map_err(RouterError::First)?;
-            *req.borrow_mut() = params;
-            xitca_service::object::ServiceObject::call(value, req).await
+            let xitca_router::Match {
+                value: (path, service),
+                params,
+            } = self.routes.at(req.borrow().path()).map_err(RouterError::First)?;
+            *BorrowReqMut::<Params>::borrow_mut(&mut req) = params;
+            *BorrowReqMut::<MatchedPath>::borrow_mut(&mut req) = path.clone();
+            xitca_service::object::ServiceObject::call(service, req).await
         }
     }
 }
@@ -383,6 +418,28 @@ mod test {
             .unwrap();
     }
 
+    #[test]
+    fn router_matched_path() {
+        let handler = |pattern: &'static str| {
+            fn_service(move |req: Request<RequestExt<()>>| async move {
+                assert_eq!(req.body().matched_path().as_str(), pattern);
+                Ok::<_, Infallible>(Response::new(()))
+            })
+        };
+
+        let service = Router::new()
+            .insert("/users/:id", handler("/users/:id"))
+            .insert("/scope", Router::new().insert("/nest", handler("/scope/nest")))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for uri in ["/users/1", "/users/2", "/scope/nest"] {
+            let req = Request::builder().uri(uri).body(Default::default()).unwrap();
+            service.call(req).now_or_panic().unwrap();
+        }
+    }
+
     #[test]
     fn router_nest() {
         let handler = || get(fn_service(func)).enclosed_fn(enclosed);
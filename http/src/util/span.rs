@@ -0,0 +1,19 @@
+//! tracing spans shared by dispatchers of all http versions.
+
+use std::net::SocketAddr;
+
+use tracing::{info_span, Span};
+
+use crate::http::Request;
+
+// span covering the whole lifetime of a connection.
+pub(crate) fn connection(addr: &SocketAddr, protocol: &'static str) -> Span {
+    info_span!("connection", peer = %addr, protocol)
+}
+
+// span covering a single request. it's a child of connection span and would be stored in request
+// extension for middleware and handlers to attach to.
+pub(crate) fn request<B>(conn: &Span, req: &Request<B>) -> Span {
+    info_span!(parent: conn, "request", method = %req.method(), uri = %req.uri())
+}
+
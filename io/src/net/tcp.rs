@@ -28,6 +28,12 @@ impl TcpStream {
     pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
         self.0.set_nodelay(nodelay)
     }
+
+    /// Receives data on the socket from the remote address to which it is connected, without
+    /// removing that data from the queue.
+    pub async fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.peek(buf).await
+    }
 }
 
 impl TryFrom<Stream> for TcpStream {
@@ -1,20 +1,39 @@
-use std::collections::HashMap;
+use core::mem;
 
+use std::{collections::HashMap, io};
+
+use postgres_protocol::message::frontend;
 use postgres_types::{Oid, Type};
 use xitca_io::bytes::BytesMut;
 use xitca_unsafe_collection::no_hash::NoHashBuilder;
 
 use super::{
+    column::Column,
     driver::{ClientTx, Response},
     error::Error,
+    iter::AsyncIterator,
+    parameters::{ServerParameters, SharedParameters},
     statement::Statement,
-    util::lock::Lock,
+    util::{
+        buf_pool::{BufPool, ColumnRange},
+        lock::Lock,
+    },
 };
 
 pub struct Client {
     pub(crate) tx: ClientTx,
     pub(crate) buf: Lock<BytesMut>,
     cached_typeinfo: Lock<CachedTypeInfo>,
+    // name and query of statements prepared by user and not closed yet. None when tracking is disabled.
+    statements: Option<Lock<HashMap<Box<str>, Box<str>>>>,
+    // name of statements dropped by guard and not closed yet. they are closed in batch together
+    // with the next request to database.
+    pending_close: Lock<Vec<Box<str>>>,
+    // buffers reused by row streams of queries.
+    pub(crate) ranges_pool: BufPool<ColumnRange>,
+    pub(crate) columns_pool: BufPool<Column>,
+    // runtime parameters shared with driver.
+    pub(crate) parameters: SharedParameters,
 }
 
 /// A cache of type info and prepared statements for fetching type info
@@ -46,7 +65,94 @@ impl Client {
                 typeinfo_enum: None,
                 types: HashMap::default(),
             }),
+            statements: None,
+            pending_close: Lock::new(Vec::new()),
+            ranges_pool: BufPool::new(),
+            columns_pool: BufPool::new(),
+            parameters: SharedParameters::default(),
+        }
+    }
+
+    pub(crate) fn enable_statement_tracking(&mut self) {
+        self.statements = Some(Lock::new(HashMap::new()));
+    }
+
+    pub(crate) fn track_statement(&self, name: &str, query: &str) {
+        if let Some(ref statements) = self.statements {
+            statements.lock().insert(name.into(), query.into());
+        }
+    }
+
+    pub(crate) fn untrack_statement(&self, name: &str) {
+        if let Some(ref statements) = self.statements {
+            statements.lock().remove(name);
+        }
+    }
+
+    /// Returns name and query of prepared statements not closed yet.
+    ///
+    /// Always empty when [Config::statement_tracking](crate::Config::statement_tracking) is not
+    /// enabled. Statements used internally for type lookup are not included.
+    pub fn open_statements(&self) -> Vec<(String, String)> {
+        self.statements
+            .as_ref()
+            .map(|statements| {
+                statements
+                    .lock()
+                    .iter()
+                    .map(|(name, query)| (name.to_string(), query.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns name and query of prepared statements of current session on server side.
+    ///
+    /// Unlike [Client::open_statements] statements prepared internally for type lookup and the
+    /// ones prepared with SQL `PREPARE` command are included.
+    pub async fn prepared_statements(&self) -> Result<Vec<(String, String)>, Error> {
+        let mut stream = self
+            .query_simple("SELECT name, statement FROM pg_prepared_statements")
+            .await?;
+
+        let mut statements = Vec::new();
+        while let Some(row) = stream.next().await {
+            let row = row?;
+            let name = row.try_get(0)?.unwrap_or_default();
+            let query = row.try_get(1)?.unwrap_or_default();
+            statements.push((name.to_string(), query.to_string()));
+        }
+
+        Ok(statements)
+    }
+
+    /// Deallocate all prepared statements of current session on server side with SQL
+    /// `DEALLOCATE ALL` command. Useful for releasing server resource of long-lived connection.
+    ///
+    /// Statements prepared by client before this call become invalid and must be prepared again.
+    /// Dropping their [StatementGuarded](crate::statement::StatementGuarded) is still safe.
+    /// Statements used internally for type lookup are prepared again on demand.
+    pub async fn deallocate_all(&self) -> Result<(), Error> {
+        self.execute_simple("DEALLOCATE ALL").await?;
+
+        if let Some(ref statements) = self.statements {
+            statements.lock().clear();
         }
+
+        let mut cache = self.cached_typeinfo.lock();
+        cache.typeinfo = None;
+        cache.typeinfo_composite = None;
+        cache.typeinfo_enum = None;
+
+        Ok(())
+    }
+
+    /// Returns snapshot of runtime parameters reported by server.
+    ///
+    /// The snapshot reflects parameter changes only when [Driver](crate::Driver) of the client is
+    /// running.
+    pub fn parameters(&self) -> ServerParameters {
+        self.parameters.lock().unwrap().clone()
     }
 
     pub fn closed(&self) -> bool {
@@ -54,9 +160,46 @@ impl Client {
     }
 
     pub(crate) async fn send(&self, msg: BytesMut) -> Result<Response, Error> {
+        self.flush_close();
         self.tx.send(msg).await
     }
 
+    #[cfg(not(feature = "quic"))]
+    pub(crate) fn send_streamed(
+        &self,
+        msg: BytesMut,
+        len: usize,
+    ) -> Result<(Response, tokio::sync::mpsc::Sender<BytesMut>), Error> {
+        self.flush_close();
+        self.tx.send_streamed(msg, len)
+    }
+
+    // schedule statement to be closed with the next request.
+    pub(crate) fn close_later(&self, name: Box<str>) {
+        self.pending_close.lock().push(name);
+    }
+
+    // send close messages of pending statements in one batch with a single sync message.
+    fn flush_close(&self) {
+        let names = mem::take(&mut *self.pending_close.lock());
+
+        if names.is_empty() || self.closed() {
+            return;
+        }
+
+        let res = self.try_buf_and_split(|b| {
+            for name in names.iter() {
+                frontend::close(b'S', name, b)?;
+            }
+            frontend::sync(b);
+            Ok::<_, io::Error>(())
+        });
+
+        if let Ok(msg) = res {
+            self.do_send(msg);
+        }
+    }
+
     // send a message in non blocking manner without concerning response.
     pub(crate) fn do_send(&self, msg: BytesMut) {
         self.tx.do_send(msg)
@@ -137,5 +280,13 @@ impl Drop for Client {
         if let Some(stmt) = typeinfo_enum {
             drop(stmt.into_guarded(self));
         }
+
+        self.flush_close();
+
+        if let Some(ref mut statements) = self.statements {
+            for (name, query) in statements.get_mut().iter() {
+                tracing::warn!("statement {name} is never closed before client is dropped. query: {query}");
+            }
+        }
     }
 }
@@ -27,8 +27,15 @@ use std::net::SocketAddr;
 
 use postgres_protocol::message::backend;
 use xitca_io::bytes::BytesMut;
-
-use super::{client::Client, config::Config, error::Error, iter::AsyncIterator};
+use xitca_unsafe_collection::futures::{Select, SelectOutput};
+
+use super::{
+    client::Client,
+    config::{AfterConnect, Config},
+    error::{unexpected_eof_err, Error},
+    iter::AsyncIterator,
+    parameters::SharedParameters,
+};
 
 #[cfg(not(feature = "quic"))]
 use {self::generic::GenericDriver, xitca_io::net::TcpStream};
@@ -45,8 +52,19 @@ pub(super) async fn connect(mut cfg: Config) -> Result<(Client, Driver), Error>
     let mut err = None;
     let hosts = cfg.get_hosts().to_vec();
     for host in hosts {
-        match _connect(host, &mut cfg).await {
-            Ok(t) => return Ok(t),
+        let res = match cfg.get_connect_timeout() {
+            Some(dur) => tokio::time::timeout(dur, _connect(host, &mut cfg))
+                .await
+                .unwrap_or_else(|_| Err(std::io::Error::from(std::io::ErrorKind::TimedOut).into())),
+            None => _connect(host, &mut cfg).await,
+        };
+        match res {
+            Ok((cli, mut drv)) => {
+                if let Some(ref hook) = cfg.after_connect {
+                    after_connect(hook, &cli, &mut drv).await?;
+                }
+                return Ok((cli, drv));
+            }
             Err(e) => err = Some(e),
         }
     }
@@ -54,6 +72,21 @@ pub(super) async fn connect(mut cfg: Config) -> Result<(Client, Driver), Error>
     Err(err.unwrap())
 }
 
+// run hook with newly connected client. driver is polled at the same time so queries issued by
+// hook can make progress. server sent messages not belong to any query are ignored.
+async fn after_connect(hook: &AfterConnect, cli: &Client, drv: &mut Driver) -> Result<(), Error> {
+    let drive = async {
+        while let Some(res) = drv.next().await {
+            res?;
+        }
+        Err(unexpected_eof_err().into())
+    };
+
+    match hook.call(cli).select(drive).await {
+        SelectOutput::A(res) | SelectOutput::B(res) => res,
+    }
+}
+
 /// async driver of [Client](crate::Client).
 /// it handles IO and emit server sent message that do not belong to any query with [AsyncIterator]
 /// trait impl.
@@ -80,43 +113,55 @@ pub(super) async fn connect(mut cfg: Config) -> Result<(Client, Driver), Error>
 /// ```
 pub struct Driver {
     inner: _Driver,
+    parameters: SharedParameters,
 }
 
 #[cfg(not(feature = "quic"))]
 impl Driver {
-    pub(super) fn tcp(drv: GenericDriver<TcpStream>) -> Self {
+    pub(super) fn tcp(drv: GenericDriver<TcpStream>, parameters: SharedParameters) -> Self {
         Self {
             inner: _Driver::Tcp(drv),
+            parameters,
         }
     }
 
     #[cfg(feature = "tls")]
-    pub(super) fn tls(drv: GenericDriver<TlsStream<ClientConnection, TcpStream>>) -> Self {
+    pub(super) fn tls(
+        drv: GenericDriver<TlsStream<ClientConnection, TcpStream>>,
+        parameters: SharedParameters,
+    ) -> Self {
         Self {
             inner: _Driver::Tls(drv),
+            parameters,
         }
     }
 
     #[cfg(unix)]
-    pub(super) fn unix(drv: GenericDriver<UnixStream>) -> Self {
+    pub(super) fn unix(drv: GenericDriver<UnixStream>, parameters: SharedParameters) -> Self {
         Self {
             inner: _Driver::Unix(drv),
+            parameters,
         }
     }
 
     #[cfg(all(unix, feature = "tls"))]
-    pub(super) fn unix_tls(drv: GenericDriver<TlsStream<ClientConnection, UnixStream>>) -> Self {
+    pub(super) fn unix_tls(
+        drv: GenericDriver<TlsStream<ClientConnection, UnixStream>>,
+        parameters: SharedParameters,
+    ) -> Self {
         Self {
             inner: _Driver::UnixTls(drv),
+            parameters,
         }
     }
 }
 
 #[cfg(feature = "quic")]
 impl Driver {
-    pub(super) fn quic(drv: QuicDriver) -> Self {
+    pub(super) fn quic(drv: QuicDriver, parameters: SharedParameters) -> Self {
         Self {
             inner: _Driver::Quic(drv),
+            parameters,
         }
     }
 }
@@ -141,8 +186,20 @@ enum _Driver {
 impl AsyncIterator for Driver {
     type Item<'i> = Result<backend::Message, Error> where Self: 'i;
 
-    #[inline]
     async fn next(&mut self) -> Option<Self::Item<'_>> {
+        let res = self._next().await;
+        if let Some(Ok(backend::Message::ParameterStatus(ref body))) = res {
+            if let Err(e) = self.parameters.lock().unwrap().try_set(body) {
+                return Some(Err(e));
+            }
+        }
+        res
+    }
+}
+
+impl Driver {
+    #[inline]
+    async fn _next(&mut self) -> Option<Result<backend::Message, Error>> {
         #[cfg(not(feature = "quic"))]
         match self.inner {
             _Driver::Tcp(ref mut drv) => drv.next().await,
@@ -161,29 +218,70 @@ impl AsyncIterator for Driver {
     }
 }
 
-impl IntoFuture for Driver {
-    type Output = Result<(), Error>;
-    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
-
-    fn into_future(self) -> Self::IntoFuture {
+impl Driver {
+    /// gracefully shutdown the driver.
+    ///
+    /// the driver stops accepting new queries from [Client](crate::Client) and keeps running until
+    /// all pending queries have received their responses. after that a terminate message is sent to
+    /// server and the returned future resolves when server closes the connection.
+    ///
+    /// server sent messages not belong to any query are ignored while shutting down.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// use xitca_postgres::{AsyncIterator, Driver, Error};
+    ///
+    /// // drive the client until shutdown signal is received. then drain pending queries.
+    /// async fn drive(mut drv: Driver, signal: tokio::sync::oneshot::Receiver<()>) -> Result<(), Error> {
+    ///     let drive = async {
+    ///         while let Some(res) = drv.next().await {
+    ///             res?;
+    ///         }
+    ///         Ok::<_, Error>(())
+    ///     };
+    ///
+    ///     tokio::select! {
+    ///         res = drive => return res,
+    ///         _ = signal => {}
+    ///     }
+    ///
+    ///     drv.shutdown().await
+    /// }
+    /// ```
+    pub async fn shutdown(self) -> Result<(), Error> {
         #[cfg(not(feature = "quic"))]
         match self.inner {
-            _Driver::Tcp(drv) => Box::pin(drv.run()),
+            _Driver::Tcp(drv) => drv.shutdown().await,
             #[cfg(feature = "tls")]
-            _Driver::Tls(drv) => Box::pin(drv.run()),
+            _Driver::Tls(drv) => drv.shutdown().await,
             #[cfg(unix)]
-            _Driver::Unix(drv) => Box::pin(drv.run()),
+            _Driver::Unix(drv) => drv.shutdown().await,
             #[cfg(all(unix, feature = "tls"))]
-            _Driver::UnixTls(drv) => Box::pin(drv.run()),
+            _Driver::UnixTls(drv) => drv.shutdown().await,
         }
 
         #[cfg(feature = "quic")]
         match self.inner {
-            _Driver::Quic(drv) => Box::pin(drv.run()),
+            _Driver::Quic(drv) => drv.shutdown().await,
         }
     }
 }
 
+impl IntoFuture for Driver {
+    type Output = Result<(), Error>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(mut self) -> Self::IntoFuture {
+        // drive through AsyncIterator impl so runtime parameters are kept up to date.
+        Box::pin(async move {
+            while let Some(res) = self.next().await {
+                res?;
+            }
+            Ok(())
+        })
+    }
+}
+
 async fn resolve(host: &str, ports: &[u16]) -> Result<Vec<SocketAddr>, Error> {
     let addrs = tokio::net::lookup_host((host, 0))
         .await?
@@ -211,8 +309,9 @@ impl Driver {
                     tcp,
                     drv.rx.unwrap(),
                     drv.write_buf.into_inner(),
-                    drv.read_buf.into_inner(),
+                    drv.read_buf,
                     drv.res,
+                    drv.stream,
                 )
             }
             _ => todo!(),
@@ -1,5 +1,10 @@
-use postgres_protocol::message::backend;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use core::{
+    cmp,
+    task::{ready, Context, Poll},
+};
+
+use postgres_protocol::message::{backend, frontend};
+use tokio::sync::mpsc::{Receiver, UnboundedReceiver, UnboundedSender};
 use xitca_io::bytes::BytesMut;
 
 use crate::error::Error;
@@ -38,6 +43,7 @@ pub(super) type ResponseReceiver = UnboundedReceiver<BytesMut>;
 pub struct Request {
     pub(super) tx: ResponseSender,
     pub(crate) msg: BytesMut,
+    pub(super) stream: Option<StreamBody>,
 }
 
 impl Request {
@@ -52,7 +58,69 @@ impl Request {
         Self {
             tx: ResponseSender::new(tx, msg_count),
             msg,
+            stream: None,
+        }
+    }
+
+    // a request with a single response message from database where the rest of it's message is
+    // streamed after msg. driver writes the stream exclusively until it's finished.
+    #[cfg(not(feature = "quic"))]
+    pub(crate) fn streamed(tx: UnboundedSender<BytesMut>, msg: BytesMut, stream: StreamBody) -> Self {
+        Self {
+            stream: Some(stream),
+            ..Self::single(tx, msg)
+        }
+    }
+}
+
+/// Remaining part of a message streamed to driver through a bounded channel so streaming is back
+/// pressured by driver's write buffer.
+///
+/// The channel can be closed before the message is complete when sender fails or is cancelled.
+/// In that case the message is padded with zeros and followed by a sync message in place of the
+/// rest of request so nothing is executed by database and connection stays usable.
+#[derive(Debug)]
+pub(crate) struct StreamBody {
+    rx: Receiver<BytesMut>,
+    // bytes left until the streamed message is complete.
+    remaining: usize,
+    closed: bool,
+}
+
+impl StreamBody {
+    #[cfg(not(feature = "quic"))]
+    pub(crate) fn new(rx: Receiver<BytesMut>, len: usize) -> Self {
+        Self {
+            rx,
+            remaining: len,
+            closed: false,
+        }
+    }
+
+    pub(super) fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<BytesMut>> {
+        if !self.closed {
+            match ready!(self.rx.poll_recv(cx)) {
+                Some(buf) => {
+                    self.remaining = self.remaining.saturating_sub(buf.len());
+                    return Poll::Ready(Some(buf));
+                }
+                None => self.closed = true,
+            }
+        }
+
+        if self.remaining == 0 {
+            return Poll::Ready(None);
+        }
+
+        const PAD_SIZE: usize = 64 * 1024;
+
+        let len = cmp::min(self.remaining, PAD_SIZE);
+        self.remaining -= len;
+        let mut buf = BytesMut::zeroed(len);
+        if self.remaining == 0 {
+            frontend::sync(&mut buf);
         }
+        Poll::Ready(Some(buf))
     }
 }
 
@@ -8,48 +8,72 @@ use alloc::collections::VecDeque;
 
 use std::io;
 
-use postgres_protocol::message::backend;
+use postgres_protocol::message::{backend, frontend};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tracing::error;
 use xitca_io::{
-    bytes::{BufInterest, BufRead, BufWrite, BytesMut, WriteBuf},
-    io::{AsyncIo, Interest},
+    bytes::{BufInterest, BufWrite, BytesMut, WriteBuf},
+    io::{AsyncIo, Interest, Ready},
+};
+use xitca_unsafe_collection::{
+    bytes::read_buf,
+    futures::{Select as _, SelectOutput},
 };
-use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 
-use crate::{error::Error, iter::AsyncIterator};
+use crate::{
+    config::{Config, DEFAULT_READ_BUF_PAGE_SIZE, DEFAULT_WRITE_BUF_LIMIT},
+    error::{unexpected_eof_err, Error},
+    iter::AsyncIterator,
+};
 
 use super::{
-    codec::{Request, ResponseMessage, ResponseSender},
+    codec::{Request, ResponseMessage, ResponseSender, StreamBody},
     Drive,
 };
 
-type PagedBytesMut = xitca_unsafe_collection::bytes::PagedBytesMut<4096>;
-
 pub(crate) type GenericDriverTx = UnboundedSender<Request>;
 pub(crate) type GenericDriverRx = UnboundedReceiver<Request>;
 
 pub(crate) struct GenericDriver<Io> {
     pub(crate) io: Io,
     pub(crate) write_buf: WriteBuf,
-    pub(crate) read_buf: PagedBytesMut,
+    pub(crate) read_buf: BytesMut,
     pub(crate) rx: Option<GenericDriverRx>,
     pub(crate) res: VecDeque<ResponseSender>,
+    // remaining part of request message being streamed.
+    pub(crate) stream: Option<StreamBody>,
+    read_buf_page_size: usize,
+    write_buf_limit: usize,
+    terminated: bool,
 }
 
 impl<Io> GenericDriver<Io>
 where
     Io: AsyncIo,
 {
+    #[cfg_attr(not(feature = "quic"), allow(dead_code))]
     pub(crate) fn new(io: Io) -> (Self, GenericDriverTx) {
+        Self::with_buf_size(io, DEFAULT_READ_BUF_PAGE_SIZE, DEFAULT_WRITE_BUF_LIMIT)
+    }
+
+    #[cfg_attr(feature = "quic", allow(dead_code))]
+    pub(crate) fn with_config(io: Io, cfg: &Config) -> (Self, GenericDriverTx) {
+        Self::with_buf_size(io, cfg.get_read_buf_page_size(), cfg.get_write_buf_limit())
+    }
+
+    fn with_buf_size(io: Io, read_buf_page_size: usize, write_buf_limit: usize) -> (Self, GenericDriverTx) {
         let (tx, rx) = unbounded_channel();
         (
             Self {
                 io,
                 write_buf: WriteBuf::new(),
-                read_buf: PagedBytesMut::new(),
+                read_buf: BytesMut::new(),
                 rx: Some(rx),
                 res: VecDeque::new(),
+                stream: None,
+                read_buf_page_size,
+                write_buf_limit,
+                terminated: false,
             },
             tx,
         )
@@ -67,15 +91,46 @@ where
                 Interest::READABLE
             };
 
+            // streamed part of request message must be written before batching new request.
+            if let Some(ref mut stream) = self.stream {
+                let select = if self.write_buf.len() < self.write_buf_limit {
+                    let ready = self.io.ready(interest);
+                    poll_fn(|cx| stream.poll_next(cx)).select(ready).await
+                } else {
+                    SelectOutput::B(self.io.ready(interest).await)
+                };
+
+                match select {
+                    SelectOutput::A(Some(buf)) => self.write_buf_extend(&buf),
+                    SelectOutput::A(None) => self.stream = None,
+                    SelectOutput::B(ready) => self.on_ready(ready?)?,
+                }
+
+                continue;
+            }
+
             let select = match self.rx {
-                Some(ref mut rx) => {
+                // stop batching new request when write buffer reaches it's limit and wait for io to
+                // drain the buffer first.
+                Some(ref mut rx) if self.write_buf.len() < self.write_buf_limit => {
                     let ready = self.io.ready(interest);
                     rx.recv().select(ready).await
                 }
+                Some(_) => {
+                    let ready = self.io.ready(interest);
+                    SelectOutput::B(ready.await)
+                }
                 None => {
                     if !interest.is_writable() && self.res.is_empty() {
                         // no interest to write to io and all response have been finished so
-                        // shutdown io and exit.
+                        // notify server with terminate message before shutdown io.
+                        if !self.terminated {
+                            self.terminated = true;
+                            let mut buf = BytesMut::new();
+                            frontend::terminate(&mut buf);
+                            self.write_buf_extend(&buf);
+                            continue;
+                        }
                         // if there is a better way to exhaust potential remaining backend message
                         // please file an issue.
                         poll_fn(|cx| Pin::new(&mut self.io).poll_shutdown(cx)).await?;
@@ -91,30 +146,51 @@ where
                 SelectOutput::A(Some(req)) => {
                     self.write_buf_extend(req.msg.as_ref());
                     self.res.push_back(req.tx);
+                    self.stream = req.stream;
                 }
-                SelectOutput::B(ready) => {
-                    let ready = ready?;
-                    if ready.is_readable() {
-                        self.try_read()?;
-                    }
-                    if ready.is_writable() && self.try_write().is_err() {
-                        // write failed as server stopped reading.
-                        // drop channel so all pending request in it can be notified.
-                        self.rx = None;
-                    }
-                }
+                SelectOutput::B(ready) => self.on_ready(ready?)?,
                 SelectOutput::A(None) => self.rx = None,
             }
         }
     }
 
-    // TODO: remove this feature gate.
-    #[cfg(not(feature = "quic"))]
-    pub(crate) async fn run(mut self) -> Result<(), Error> {
-        while self.try_next().await?.is_some() {}
+    fn on_ready(&mut self, ready: Ready) -> Result<(), Error> {
+        if ready.is_readable() {
+            self.try_read()?;
+        }
+        if ready.is_writable() && self.try_write().is_err() {
+            // write failed as server stopped reading.
+            // drop channel so all pending request in it can be notified.
+            self.rx = None;
+            self.stream = None;
+        }
         Ok(())
     }
 
+    pub(crate) async fn shutdown(mut self) -> Result<(), Error> {
+        // close channel so no new request can be sent to driver. requests already in channel are
+        // still received and their responses are delivered.
+        if let Some(ref mut rx) = self.rx {
+            rx.close();
+        }
+
+        while self.try_next().await?.is_some() {}
+
+        // wait for server to close the connection.
+        loop {
+            self.read_buf.clear();
+            self.read_buf.reserve(self.read_buf_page_size);
+            match read_buf(&mut self.io, &mut self.read_buf) {
+                Ok(0) => return Ok(()),
+                Ok(_) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    self.io.ready(Interest::READABLE).await?;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
     pub(crate) async fn send(&mut self, msg: BytesMut) -> Result<(), Error> {
         self.write_buf_extend(&msg);
         loop {
@@ -132,7 +208,7 @@ where
         F: FnMut(&mut BytesMut) -> Option<Result<O, Error>>,
     {
         loop {
-            if let Some(o) = func(self.read_buf.get_mut()) {
+            if let Some(o) = func(&mut self.read_buf) {
                 return o;
             }
             let ready = self.io.ready(Interest::READABLE);
@@ -149,7 +225,48 @@ where
     }
 
     fn try_read(&mut self) -> Result<(), Error> {
-        self.read_buf.do_io(&mut self.io).map_err(Into::into)
+        // bytes allowed to be read in one pass. the cap yields control back to decoding and
+        // request batching instead of draining a fast socket until WouldBlock.
+        let mut budget = self.read_buf_page_size;
+
+        // reserve for the whole message up front when a large message is partially read. this
+        // avoids repeated re-allocation and copying when buffering multi-MB rows. the remaining
+        // part of the message is allowed to be read in one pass as it's buffered regardless.
+        if let Ok(Some(header)) = backend::Header::parse(&self.read_buf) {
+            let len = header.len() as usize + 1;
+            if len > self.read_buf.capacity() {
+                self.read_buf.reserve(len - self.read_buf.len());
+            }
+            budget = budget.max(len.saturating_sub(self.read_buf.len()));
+        }
+
+        let len = self.read_buf.len();
+        loop {
+            let read = self.read_buf.len() - len;
+            if read >= budget {
+                break;
+            }
+            if self.read_buf.capacity() == self.read_buf.len() {
+                self.read_buf.reserve(self.read_buf_page_size.min(budget - read));
+            }
+            match read_buf(&mut self.io, &mut self.read_buf) {
+                Ok(0) => {
+                    if self.read_buf.len() == len {
+                        return Err(unexpected_eof_err().into());
+                    }
+                    break;
+                }
+                Ok(_) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    if self.read_buf.len() == len {
+                        return Err(e.into());
+                    }
+                    break;
+                }
+            }
+        }
+        Ok(())
     }
 
     fn try_write(&mut self) -> io::Result<()> {
@@ -164,7 +281,7 @@ where
     }
 
     fn try_decode(&mut self) -> Result<Option<backend::Message>, Error> {
-        while let Some(res) = ResponseMessage::try_from_buf(self.read_buf.get_mut())? {
+        while let Some(res) = ResponseMessage::try_from_buf(&mut self.read_buf)? {
             match res {
                 ResponseMessage::Normal { buf, complete } => {
                     let front = self.res.front_mut().expect("out of bound must not happen");
@@ -184,7 +301,10 @@ impl<Io> AsyncIterator for GenericDriver<Io>
 where
     Io: AsyncIo + Send,
 {
-    type Item<'i> = Result<backend::Message, Error> where Self: 'i;
+    type Item<'i>
+        = Result<backend::Message, Error>
+    where
+        Self: 'i;
 
     #[inline]
     async fn next(&mut self) -> Option<Self::Item<'_>> {
@@ -204,3 +324,59 @@ where
         Box::pin(self.recv_with(|buf| backend::Message::parse(buf).map_err(Error::from).transpose()))
     }
 }
+
+#[cfg(all(test, unix))]
+mod test {
+    use std::io::Write;
+
+    use xitca_io::net::UnixStream;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn read_pass_capped() {
+        const PAGE: usize = 64;
+
+        let (mut tx, rx) = std::os::unix::net::UnixStream::pair().unwrap();
+        rx.set_nonblocking(true).unwrap();
+        let io = UnixStream::from_std(rx).unwrap();
+        let (mut drv, _tx) = GenericDriver::with_buf_size(io, PAGE, DEFAULT_WRITE_BUF_LIMIT);
+
+        tx.write_all(&[0; PAGE * 4]).unwrap();
+
+        drv.io.ready(Interest::READABLE).await.unwrap();
+        drv.try_read().unwrap();
+        assert!(drv.read_buf.len() < PAGE * 4);
+
+        while drv.read_buf.len() < PAGE * 4 {
+            drv.io.ready(Interest::READABLE).await.unwrap();
+            drv.try_read().unwrap();
+        }
+        assert_eq!(drv.read_buf.len(), PAGE * 4);
+    }
+
+    #[tokio::test]
+    async fn read_partial_message() {
+        const PAGE: usize = 64;
+
+        let (mut tx, rx) = std::os::unix::net::UnixStream::pair().unwrap();
+        rx.set_nonblocking(true).unwrap();
+        let io = UnixStream::from_std(rx).unwrap();
+        let (mut drv, _tx) = GenericDriver::with_buf_size(io, PAGE, DEFAULT_WRITE_BUF_LIMIT);
+
+        // DataRow message header with body length much larger than page size.
+        let len = PAGE * 8;
+        let mut msg = vec![b'D'];
+        msg.extend_from_slice(&(len as i32 + 4).to_be_bytes());
+        msg.resize(len + 5, 0);
+        tx.write_all(&msg).unwrap();
+
+        drv.io.ready(Interest::READABLE).await.unwrap();
+        drv.try_read().unwrap();
+
+        // remaining part of partially read message is read in one pass.
+        drv.io.ready(Interest::READABLE).await.unwrap();
+        drv.try_read().unwrap();
+        assert_eq!(drv.read_buf.len(), msg.len());
+    }
+}
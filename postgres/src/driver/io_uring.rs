@@ -18,7 +18,7 @@ use xitca_unsafe_collection::futures::{ReusableLocalBoxFuture, Select, SelectOut
 use crate::error::Error;
 
 use super::{
-    codec::{ResponseMessage, ResponseSender},
+    codec::{ResponseMessage, ResponseSender, StreamBody},
     generic::GenericDriverRx,
 };
 
@@ -70,6 +70,7 @@ pub struct IoUringDriver<Io> {
     write_task: BufTask,
     rx: GenericDriverRx,
     res: VecDeque<ResponseSender>,
+    stream: Option<StreamBody>,
 }
 
 impl<Io> IoUringDriver<Io>
@@ -83,6 +84,7 @@ where
         write_buf: BytesMut,
         read_buf: BytesMut,
         res: VecDeque<ResponseSender>,
+        stream: Option<StreamBody>,
     ) -> Self {
         Self {
             io: Rc::new(io),
@@ -90,6 +92,7 @@ where
             write_task: BufTask::new(write_buf),
             rx,
             res,
+            stream,
         }
     }
 
@@ -127,12 +130,28 @@ where
 
             let write_task = async {
                 while let Some(buf) = self.write_task.try_buf() {
-                    let res = poll_fn(|cx| match self.rx.poll_recv(cx) {
-                        Poll::Ready(Some(req)) => Poll::Ready(SelectOutput::A(Some(req))),
-                        Poll::Ready(None) if !buf.is_empty() => Poll::Ready(SelectOutput::B(())),
-                        Poll::Ready(None) => Poll::Ready(SelectOutput::A(None)),
-                        Poll::Pending if !buf.is_empty() => Poll::Ready(SelectOutput::B(())),
-                        Poll::Pending => Poll::Pending,
+                    let res = poll_fn(|cx| {
+                        // streamed part of request message must be written before batching new
+                        // request.
+                        while let Some(ref mut stream) = self.stream {
+                            match stream.poll_next(cx) {
+                                Poll::Ready(Some(part)) => {
+                                    buf.extend_from_slice(&part);
+                                    return Poll::Ready(SelectOutput::B(()));
+                                }
+                                Poll::Ready(None) => self.stream = None,
+                                Poll::Pending if !buf.is_empty() => return Poll::Ready(SelectOutput::B(())),
+                                Poll::Pending => return Poll::Pending,
+                            }
+                        }
+
+                        match self.rx.poll_recv(cx) {
+                            Poll::Ready(Some(req)) => Poll::Ready(SelectOutput::A(Some(req))),
+                            Poll::Ready(None) if !buf.is_empty() => Poll::Ready(SelectOutput::B(())),
+                            Poll::Ready(None) => Poll::Ready(SelectOutput::A(None)),
+                            Poll::Pending if !buf.is_empty() => Poll::Ready(SelectOutput::B(())),
+                            Poll::Pending => Poll::Pending,
+                        }
                     })
                     .await;
 
@@ -140,6 +159,7 @@ where
                         SelectOutput::A(Some(req)) => {
                             buf.extend_from_slice(req.msg.as_ref());
                             self.res.push_back(req.tx);
+                            self.stream = req.stream;
                         }
                         SelectOutput::A(None) => return Ok(0),
                         SelectOutput::B(_) => {
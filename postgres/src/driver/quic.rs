@@ -18,12 +18,15 @@ use xitca_io::bytes::{Bytes, BytesMut};
 
 use crate::{
     client::Client,
-    config::{Config, Host},
+    config::{Config, Host, SslMode},
     error::{unexpected_eof_err, Error},
     iter::AsyncIterator,
 };
 
-use super::{tls::dangerous_config, Drive, Driver};
+use super::{
+    tls::{dangerous_config, verified_config},
+    Drive, Driver,
+};
 
 pub(crate) const QUIC_ALPN: &[u8] = b"quic";
 
@@ -85,13 +88,14 @@ impl ClientTx {
 pub(super) async fn _connect(host: Host, cfg: &mut Config) -> Result<(Client, Driver), Error> {
     match host {
         Host::Udp(ref host) => {
-            let tx = connect_quic(host, cfg.get_ports()).await?;
+            let tx = connect_quic(host, cfg).await?;
             let streams = tx.inner.open_bi().await.unwrap();
             let mut drv = QuicDriver::new(streams);
             let mut cli = Client::new(tx);
             cli.prepare_session(&mut drv, cfg).await?;
             drv.close_tx().await;
-            Ok((cli, Driver::quic(drv)))
+            let params = cli.parameters.clone();
+            Ok((cli, Driver::quic(drv, params)))
         }
         _ => unreachable!(),
     }
@@ -99,11 +103,18 @@ pub(super) async fn _connect(host: Host, cfg: &mut Config) -> Result<(Client, Dr
 
 #[cold]
 #[inline(never)]
-async fn connect_quic(host: &str, ports: &[u16]) -> Result<ClientTx, Error> {
-    let addrs = super::resolve(host, ports).await?;
+async fn connect_quic(host: &str, cfg: &Config) -> Result<ClientTx, Error> {
+    let addrs = super::resolve(host, cfg.get_ports()).await?;
     let mut endpoint = Endpoint::client("0.0.0.0:0".parse().unwrap())?;
 
-    let cfg = dangerous_config(vec![QUIC_ALPN.to_vec()]);
+    // quic connection is always encrypted. server certificate is verified according to ssl mode
+    // the same way as tls over tcp.
+    let alpn = vec![QUIC_ALPN.to_vec()];
+    let cfg = match cfg.get_ssl_mode() {
+        SslMode::VerifyCa => verified_config(cfg.get_ssl_root_cert(), false, alpn)?,
+        SslMode::VerifyFull => verified_config(cfg.get_ssl_root_cert(), true, alpn)?,
+        _ => dangerous_config(alpn),
+    };
     endpoint.set_default_client_config(ClientConfig::new(cfg));
 
     let mut err = None;
@@ -145,6 +156,11 @@ impl QuicDriver {
         Ok(())
     }
 
+    pub(crate) async fn shutdown(mut self) -> Result<(), Error> {
+        self.close_tx().await;
+        self.run().await
+    }
+
     pub(crate) async fn recv_raw(&mut self) -> Option<Result<Bytes, Error>> {
         self.rx
             .read_chunk(4096, true)
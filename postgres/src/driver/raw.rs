@@ -11,7 +11,7 @@ use core::future::Future;
 use std::io;
 
 use postgres_protocol::message::frontend;
-use tokio::sync::mpsc::unbounded_channel;
+use tokio::sync::mpsc::{channel, unbounded_channel, Sender};
 use xitca_io::{
     bytes::{Buf, BytesMut},
     io::{AsyncIo, Interest},
@@ -25,7 +25,7 @@ use crate::{
 };
 
 use super::{
-    codec::Request,
+    codec::{Request, StreamBody},
     generic::{GenericDriver, GenericDriverTx},
     Driver,
 };
@@ -48,6 +48,16 @@ impl ClientTx {
         Ok(Response::new(rx))
     }
 
+    // send head of a message and stream the rest of it with returned sender. len is the length
+    // of the rest of message.
+    pub(crate) fn send_streamed(&self, msg: BytesMut, len: usize) -> Result<(Response, Sender<BytesMut>), Error> {
+        let (tx, rx) = unbounded_channel();
+        let (stream_tx, stream_rx) = channel(1);
+        self.0
+            .send(Request::streamed(tx, msg, StreamBody::new(stream_rx, len)))?;
+        Ok((Response::new(rx), stream_tx))
+    }
+
     pub(crate) fn do_send(&self, msg: BytesMut) {
         let (tx, _) = unbounded_channel();
         let _ = self.0.send(Request::single(tx, msg));
@@ -66,20 +76,22 @@ pub(super) async fn _connect(host: Host, cfg: &mut Config) -> Result<(Client, Dr
                 #[cfg(feature = "tls")]
                 {
                     let io = tls::connect(io, host, cfg).await?;
-                    let (mut drv, tx) = GenericDriver::new(io);
+                    let (mut drv, tx) = GenericDriver::with_config(io, cfg);
                     let mut cli = Client::new(ClientTx(tx));
                     cli.prepare_session(&mut drv, cfg).await?;
-                    Ok((cli, Driver::tls(drv)))
+                    let params = cli.parameters.clone();
+                    Ok((cli, Driver::tls(drv, params)))
                 }
                 #[cfg(not(feature = "tls"))]
                 {
                     Err(crate::error::FeatureError::Tls.into())
                 }
             } else {
-                let (mut drv, tx) = GenericDriver::new(io);
+                let (mut drv, tx) = GenericDriver::with_config(io, cfg);
                 let mut cli = Client::new(ClientTx(tx));
                 cli.prepare_session(&mut drv, cfg).await?;
-                Ok((cli, Driver::tcp(drv)))
+                let params = cli.parameters.clone();
+                Ok((cli, Driver::tcp(drv, params)))
             }
         }
         #[cfg(unix)]
@@ -90,20 +102,22 @@ pub(super) async fn _connect(host: Host, cfg: &mut Config) -> Result<(Client, Dr
                 {
                     let host = host.to_string_lossy();
                     let io = tls::connect(io, host.as_ref(), cfg).await?;
-                    let (mut drv, tx) = GenericDriver::new(io);
+                    let (mut drv, tx) = GenericDriver::with_config(io, cfg);
                     let mut cli = Client::new(ClientTx(tx));
                     cli.prepare_session(&mut drv, cfg).await?;
-                    Ok((cli, Driver::unix_tls(drv)))
+                    let params = cli.parameters.clone();
+                    Ok((cli, Driver::unix_tls(drv, params)))
                 }
                 #[cfg(not(feature = "tls"))]
                 {
                     Err(crate::error::FeatureError::Tls.into())
                 }
             } else {
-                let (mut drv, tx) = GenericDriver::new(io);
+                let (mut drv, tx) = GenericDriver::with_config(io, cfg);
                 let mut cli = Client::new(ClientTx(tx));
                 cli.prepare_session(&mut drv, cfg).await?;
-                Ok((cli, Driver::unix(drv)))
+                let params = cli.parameters.clone();
+                Ok((cli, Driver::unix(drv, params)))
             }
         }
         _ => unreachable!(),
@@ -135,7 +149,7 @@ where
     match cfg.get_ssl_mode() {
         SslMode::Disable => Ok(false),
         mode => match (_should_connect_tls(io).await?, mode) {
-            (false, SslMode::Require) => Err(Error::ToDo),
+            (false, SslMode::Require | SslMode::VerifyCa | SslMode::VerifyFull) => Err(Error::ToDo),
             (bool, _) => Ok(bool),
         },
     }
@@ -3,14 +3,22 @@ use sha2::{Digest, Sha256};
 use xitca_io::io::AsyncIo;
 use xitca_tls::rustls::TlsStream;
 
-use crate::{config::Config, driver::tls::dangerous_config, error::Error};
+use crate::{
+    config::{Config, SslMode},
+    driver::tls::{dangerous_config, verified_config},
+    error::Error,
+};
 
 pub(super) async fn connect<Io>(io: Io, host: &str, cfg: &mut Config) -> Result<TlsStream<ClientConnection, Io>, Error>
 where
     Io: AsyncIo,
 {
     let name = host.try_into().map_err(|_| Error::ToDo)?;
-    let config = dangerous_config(Vec::new());
+    let config = match cfg.get_ssl_mode() {
+        SslMode::VerifyCa => verified_config(cfg.get_ssl_root_cert(), false, Vec::new())?,
+        SslMode::VerifyFull => verified_config(cfg.get_ssl_root_cert(), true, Vec::new())?,
+        _ => dangerous_config(Vec::new()),
+    };
     let session = ClientConnection::new(config, name).map_err(|_| Error::ToDo)?;
 
     let stream = TlsStream::handshake(io, session).await?;
@@ -1,12 +1,14 @@
 use alloc::sync::Arc;
 
-use std::time::SystemTime;
+use std::{fs::File, io::BufReader, path::Path, time::SystemTime};
 
 use rustls::{
-    client::{ServerCertVerified, ServerCertVerifier},
-    Certificate, ClientConfig, ServerName,
+    client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
+    Certificate, CertificateError, ClientConfig, OwnedTrustAnchor, RootCertStore, ServerName,
 };
 
+use crate::error::Error;
+
 pub(super) fn dangerous_config(alpn: Vec<Vec<u8>>) -> Arc<ClientConfig> {
     let mut cfg = ClientConfig::builder()
         .with_safe_defaults()
@@ -16,6 +18,64 @@ pub(super) fn dangerous_config(alpn: Vec<Vec<u8>>) -> Arc<ClientConfig> {
     Arc::new(cfg)
 }
 
+/// config verifying server certificate against trusted authorities from given PEM file or the
+/// bundled webpki roots. host name check is skipped when `verify_hostname` is false.
+pub(super) fn verified_config(
+    root_cert: Option<&Path>,
+    verify_hostname: bool,
+    alpn: Vec<Vec<u8>>,
+) -> Result<Arc<ClientConfig>, Error> {
+    let mut roots = RootCertStore::empty();
+
+    match root_cert.filter(|path| *path != Path::new("system")) {
+        Some(path) => {
+            let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(path)?))?;
+            let (added, _) = roots.add_parsable_certificates(&certs);
+            if added == 0 {
+                return Err(Error::ToDo);
+            }
+        }
+        None => roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|cert| {
+            OwnedTrustAnchor::from_subject_spki_name_constraints(cert.subject, cert.spki, cert.name_constraints)
+        })),
+    }
+
+    let builder = ClientConfig::builder().with_safe_defaults();
+    let mut cfg = if verify_hostname {
+        builder.with_root_certificates(roots).with_no_client_auth()
+    } else {
+        builder
+            .with_custom_certificate_verifier(Arc::new(SkipHostnameVerification(WebPkiVerifier::new(roots, None))))
+            .with_no_client_auth()
+    };
+    cfg.alpn_protocols = alpn;
+    Ok(Arc::new(cfg))
+}
+
+struct SkipHostnameVerification(WebPkiVerifier);
+
+impl ServerCertVerifier for SkipHostnameVerification {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now)
+        {
+            Err(rustls::Error::InvalidCertificate(CertificateError::NotValidForName)) => {
+                Ok(ServerCertVerified::assertion())
+            }
+            res => res,
+        }
+    }
+}
+
 struct SkipServerVerification;
 
 impl SkipServerVerification {
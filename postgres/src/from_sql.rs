@@ -1,5 +1,10 @@
-use core::ops::Range;
+use core::ops::{Deref, Range};
 
+use std::{borrow::Cow, collections::HashMap, net::IpAddr, time::SystemTime};
+
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::types::array_from_sql;
+use postgres_types::{Field, Kind, WasNull};
 use xitca_io::bytes::Bytes;
 use xitca_unsafe_collection::bytes::BytesStr;
 
@@ -20,6 +25,29 @@ pub type FromSqlError = Box<dyn std::error::Error + Sync + Send>;
 ///     println!("{}", s.as_str());
 /// }
 /// ```
+///
+/// # Compatibility with [FromSql]
+/// `Option<T>`, `Vec<T>`, `Box<[T]>` and `[T; N]` decode their inner values with [FromSqlExt]
+/// so zero copy types like [BytesStr] and user composite types can be nested in them. Types only
+/// implementing [FromSql] (e.g. types from third party crates) are decoded in these wrappers
+/// through [Sql] bridge type:
+/// ```rust
+/// # use xitca_postgres::{row::Row, Sql};
+/// fn parse_row(row: Row<'_>) {
+///     // same as Option<Vec<i32>> but works for any type implementing FromSql.
+///     let v = row.get::<Option<Vec<Sql<i32>>>>(0);
+///     let v: Option<Vec<i32>> = v.map(|v| v.into_iter().map(Sql::into_inner).collect());
+/// }
+/// ```
+/// A blanket implementation of [FromSqlExt] for all [FromSql] types is not possible as it would
+/// conflict with [FromSqlExt] implementations of foreign types like [Bytes] and [BytesStr].
+///
+/// # Third party types
+/// `json`, `time`, `chrono` and `uuid` features implement [FromSqlExt] for `serde_json`, `time`,
+/// `chrono` and `uuid` crate types. Other types supported by `postgres-types` crate are used by
+/// enabling the matching `postgres-types` feature in application and decoding them through [Sql]
+/// bridge type. Encoding them as query parameters works out of the box with their `ToSql`
+/// implementations.
 pub trait FromSqlExt<'a>: Sized {
     /// [Type] represents the Postgres type hint which Self must be matching.
     /// [Bytes] represents the reference of raw bytes of row data Self belongs to.
@@ -52,28 +80,260 @@ macro_rules! default_impl {
 
 impl<'a, T> FromSqlExt<'a> for Option<T>
 where
-    T: FromSql<'a>,
+    T: FromSqlExt<'a>,
 {
-    default_impl!();
+    #[inline]
+    fn from_sql_nullable_ext(ty: &Type, buf: Option<(&Range<usize>, &'a Bytes)>) -> Result<Self, FromSqlError> {
+        match buf {
+            Some(buf) => T::from_sql_nullable_ext(ty, Some(buf)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        T::accepts(ty)
+    }
 }
 
 impl<'a, T> FromSqlExt<'a> for Vec<T>
 where
-    T: FromSql<'a>,
+    T: FromSqlExt<'a>,
 {
-    default_impl!();
+    fn from_sql_nullable_ext(ty: &Type, buf: Option<(&Range<usize>, &'a Bytes)>) -> Result<Self, FromSqlError> {
+        let mut res = Vec::new();
+        array_for_each(ty, buf, |ty, buf| {
+            res.push(T::from_sql_nullable_ext(ty, buf)?);
+            Ok(())
+        })?;
+        Ok(res)
+    }
+
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        array_accepts::<T>(ty)
+    }
 }
 
 impl<'a, T> FromSqlExt<'a> for Box<[T]>
+where
+    T: FromSqlExt<'a>,
+{
+    #[inline]
+    fn from_sql_nullable_ext(ty: &Type, buf: Option<(&Range<usize>, &'a Bytes)>) -> Result<Self, FromSqlError> {
+        Vec::from_sql_nullable_ext(ty, buf).map(Vec::into_boxed_slice)
+    }
+
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        array_accepts::<T>(ty)
+    }
+}
+
+impl<'a, T, const N: usize> FromSqlExt<'a> for [T; N]
+where
+    T: FromSqlExt<'a>,
+{
+    fn from_sql_nullable_ext(ty: &Type, buf: Option<(&Range<usize>, &'a Bytes)>) -> Result<Self, FromSqlError> {
+        Vec::from_sql_nullable_ext(ty, buf)?
+            .try_into()
+            .map_err(|v: Vec<T>| format!("array length mismatch (expected {N}, got {})", v.len()).into())
+    }
+
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        array_accepts::<T>(ty)
+    }
+}
+
+/// bridge type decoding any [FromSql] type with [FromSqlExt] trait.
+/// See [FromSqlExt] for when it's needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Sql<T>(pub T);
+
+impl<T> Sql<T> {
+    /// take ownership of decoded value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Sql<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T> FromSqlExt<'a> for Sql<T>
 where
     T: FromSql<'a>,
 {
-    default_impl!();
+    #[inline]
+    fn from_sql_nullable_ext(ty: &Type, buf: Option<(&Range<usize>, &'a Bytes)>) -> Result<Self, FromSqlError> {
+        T::from_sql_nullable(ty, buf.map(|(r, buf)| &buf[r.start..r.end])).map(Sql)
+    }
+
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        T::accepts(ty)
+    }
+}
+
+fn array_accepts<'a, T>(ty: &Type) -> bool
+where
+    T: FromSqlExt<'a>,
+{
+    match *ty.kind() {
+        Kind::Array(ref inner) => T::accepts(inner),
+        _ => false,
+    }
+}
+
+// iterate over elements of one dimensional array. the callback is called with element's type and
+// it's range into the raw bytes of row so element can be parsed with zero copy.
+fn array_for_each<'a, F>(ty: &Type, buf: Option<(&Range<usize>, &'a Bytes)>, mut func: F) -> Result<(), FromSqlError>
+where
+    F: FnMut(&Type, Option<(&Range<usize>, &'a Bytes)>) -> Result<(), FromSqlError>,
+{
+    let Kind::Array(ref member) = *ty.kind() else {
+        return Err(format!("expected array type, got {ty}").into());
+    };
+
+    let (range, buf) = buf.ok_or_else(|| Box::new(WasNull))?;
+
+    let array = array_from_sql(&buf[range.start..range.end])?;
+    if array.dimensions().count()? > 1 {
+        return Err("array contains too many dimensions".into());
+    }
+
+    let mut values = array.values();
+    while let Some(value) = values.next()? {
+        match value {
+            Some(value) => {
+                let start = value.as_ptr() as usize - buf.as_ptr() as usize;
+                func(member, Some((&(start..start + value.len()), buf)))?;
+            }
+            None => func(member, None)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// helper type for decoding fields of postgres composite type in order with [FromSqlExt] trait.
+/// fields can be parsed with zero copy the same way as row columns.
+///
+/// # Examples
+/// ```rust
+/// # use core::ops::Range;
+/// # use xitca_io::bytes::Bytes;
+/// use xitca_postgres::{Composite, FromSqlError, FromSqlExt, Kind, Type};
+/// use xitca_unsafe_collection::bytes::BytesStr;
+///
+/// // a rust type for composite type created with: CREATE TYPE person AS (name TEXT, age INT4)
+/// struct Person {
+///     name: BytesStr,
+///     age: i32,
+/// }
+///
+/// impl<'a> FromSqlExt<'a> for Person {
+///     fn from_sql_nullable_ext(ty: &Type, buf: Option<(&Range<usize>, &'a Bytes)>) -> Result<Self, FromSqlError> {
+///         let mut fields = Composite::new(ty, buf)?;
+///         Ok(Person {
+///             name: fields.next_field()?,
+///             age: fields.next_field()?,
+///         })
+///     }
+///
+///     fn accepts(ty: &Type) -> bool {
+///         matches!(ty.kind(), Kind::Composite(fields) if fields.len() == 2)
+///     }
+/// }
+/// ```
+pub struct Composite<'t, 'a> {
+    fields: &'t [Field],
+    buf: &'a Bytes,
+    pos: usize,
+    end: usize,
+    idx: usize,
+}
+
+impl<'t, 'a> Composite<'t, 'a> {
+    /// construct a new decoder from composite type and raw bytes of it.
+    pub fn new(ty: &'t Type, buf: Option<(&Range<usize>, &'a Bytes)>) -> Result<Self, FromSqlError> {
+        let Kind::Composite(ref fields) = *ty.kind() else {
+            return Err(format!("expected composite type, got {ty}").into());
+        };
+
+        let (range, buf) = buf.ok_or_else(|| Box::new(WasNull))?;
+
+        let mut this = Self {
+            fields,
+            buf,
+            pos: range.start,
+            end: range.end,
+            idx: 0,
+        };
+
+        let len = this.read_i32()?;
+        if len as usize != fields.len() {
+            return Err(format!("expected {} fields, got {len}", fields.len()).into());
+        }
+
+        Ok(this)
+    }
+
+    /// decode the next field of composite type.
+    pub fn next_field<T>(&mut self) -> Result<T, FromSqlError>
+    where
+        T: FromSqlExt<'a>,
+    {
+        let field = self
+            .fields
+            .get(self.idx)
+            .ok_or("no more field left in composite type")?;
+        self.idx += 1;
+
+        let oid = self.read_i32()? as u32;
+        if oid != field.type_().oid() || !T::accepts(field.type_()) {
+            return Err(format!("can not decode field {} of type {}", field.name(), field.type_()).into());
+        }
+
+        let len = self.read_i32()?;
+        if len < 0 {
+            return T::from_sql_nullable_ext(field.type_(), None);
+        }
+
+        let start = self.pos;
+        self.advance(len as usize)?;
+        T::from_sql_nullable_ext(field.type_(), Some((&(start..self.pos), self.buf)))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, FromSqlError> {
+        let start = self.pos;
+        self.advance(4)?;
+        let mut b = [0; 4];
+        b.copy_from_slice(&self.buf[start..self.pos]);
+        Ok(i32::from_be_bytes(b))
+    }
+
+    fn advance(&mut self, n: usize) -> Result<(), FromSqlError> {
+        if self.end - self.pos < n {
+            return Err("invalid buffer size".into());
+        }
+        self.pos += n;
+        Ok(())
+    }
 }
 
-// forward_impl!(Vec<u8>);
 default_impl!(&'a [u8]);
+default_impl!(Vec<u8>);
 default_impl!(&'a str);
+default_impl!(Cow<'a, str>);
 default_impl!(String);
 default_impl!(Box<str>);
 default_impl!(bool);
@@ -84,6 +344,61 @@ default_impl!(u32);
 default_impl!(i64);
 default_impl!(f32);
 default_impl!(f64);
+default_impl!(HashMap<String, Option<String>>);
+default_impl!(SystemTime);
+default_impl!(IpAddr);
+
+#[cfg(feature = "json")]
+mod json {
+    use postgres_types::Json;
+    use serde_json_1::Value;
+
+    use super::*;
+
+    default_impl!(Value);
+
+    impl<'a, T> FromSqlExt<'a> for Json<T>
+    where
+        Json<T>: FromSql<'a>,
+    {
+        default_impl!();
+    }
+}
+
+#[cfg(feature = "time")]
+mod time {
+    use time_03::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+    use super::*;
+
+    default_impl!(Date);
+    default_impl!(Time);
+    default_impl!(PrimitiveDateTime);
+    default_impl!(OffsetDateTime);
+}
+
+#[cfg(feature = "chrono")]
+mod chrono {
+    use chrono_04::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+    use super::*;
+
+    default_impl!(NaiveDate);
+    default_impl!(NaiveTime);
+    default_impl!(NaiveDateTime);
+    default_impl!(DateTime<Utc>);
+    default_impl!(DateTime<Local>);
+    default_impl!(DateTime<FixedOffset>);
+}
+
+#[cfg(feature = "uuid")]
+mod uuid {
+    use uuid_1::Uuid;
+
+    use super::*;
+
+    default_impl!(Uuid);
+}
 
 impl<'a> FromSqlExt<'a> for BytesStr {
     fn from_sql_nullable_ext(ty: &Type, buf: Option<(&Range<usize>, &'a Bytes)>) -> Result<Self, FromSqlError> {
@@ -131,3 +446,160 @@ impl<'a> FromSqlExt<'a> for Bytes {
         <&[u8] as FromSql>::accepts(ty)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use postgres_types::{Field, ToSql};
+    use xitca_io::bytes::{BufMut, BytesMut};
+
+    use super::*;
+
+    fn to_bytes(value: &(dyn ToSql + Sync), ty: &Type) -> Bytes {
+        let mut buf = BytesMut::new();
+        value.to_sql_checked(ty, &mut buf).unwrap();
+        buf.freeze()
+    }
+
+    #[test]
+    fn array() {
+        let ty = Type::TEXT_ARRAY;
+        let buf = to_bytes(&vec![Some("996"), None, Some("007")], &ty);
+        let range = 0..buf.len();
+
+        let v = <Vec<Option<BytesStr>>>::from_sql_nullable_ext(&ty, Some((&range, &buf))).unwrap();
+        assert_eq!(v[0].as_deref(), Some("996"));
+        assert!(v[1].is_none());
+        assert_eq!(v[2].as_deref(), Some("007"));
+
+        let v = <[Option<&str>; 3]>::from_sql_nullable_ext(&ty, Some((&range, &buf))).unwrap();
+        assert_eq!(v, [Some("996"), None, Some("007")]);
+
+        assert!(<[Option<&str>; 2]>::from_sql_nullable_ext(&ty, Some((&range, &buf))).is_err());
+        assert!(<Vec<Option<&str>>>::from_sql_nullable_ext(&ty, None).is_err());
+        assert!(<Option<Vec<Option<&str>>>>::from_sql_nullable_ext(&ty, None)
+            .unwrap()
+            .is_none());
+        assert!(!<Vec<i32> as FromSqlExt>::accepts(&ty));
+    }
+
+    #[test]
+    fn sql_bridge() {
+        // a type implementing FromSql only.
+        #[derive(Debug, PartialEq)]
+        struct Id(i32);
+
+        impl<'a> FromSql<'a> for Id {
+            fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, FromSqlError> {
+                i32::from_sql(ty, raw).map(Id)
+            }
+
+            fn accepts(ty: &Type) -> bool {
+                <i32 as FromSql>::accepts(ty)
+            }
+        }
+
+        let ty = Type::INT4_ARRAY;
+        let buf = to_bytes(&vec![Some(996), None], &ty);
+        let range = 0..buf.len();
+
+        let v = <Vec<Option<Sql<Id>>>>::from_sql_nullable_ext(&ty, Some((&range, &buf))).unwrap();
+        assert_eq!(v, [Some(Sql(Id(996))), None]);
+        assert!(<Vec<Sql<Id>>>::from_sql_nullable_ext(&ty, Some((&range, &buf))).is_err());
+        assert!(<Option<Sql<Id>> as FromSqlExt>::accepts(&Type::INT4));
+        assert!(!<Sql<Id> as FromSqlExt>::accepts(&Type::TEXT));
+    }
+
+    #[test]
+    fn composite() {
+        let ty = Type::new(
+            "person".into(),
+            0,
+            Kind::Composite(vec![
+                Field::new("name".into(), Type::TEXT),
+                Field::new("age".into(), Type::INT4),
+            ]),
+            "public".into(),
+        );
+
+        let mut buf = BytesMut::new();
+        buf.put_i32(2);
+        buf.put_u32(Type::TEXT.oid());
+        buf.put_i32(3);
+        buf.put_slice(b"996");
+        buf.put_u32(Type::INT4.oid());
+        buf.put_i32(-1);
+        let buf = buf.freeze();
+        let range = 0..buf.len();
+
+        let mut fields = Composite::new(&ty, Some((&range, &buf))).unwrap();
+        assert_eq!(fields.next_field::<BytesStr>().unwrap().as_str(), "996");
+        assert!(fields.next_field::<Option<i32>>().unwrap().is_none());
+        assert!(fields.next_field::<Option<i32>>().is_err());
+
+        let mut fields = Composite::new(&ty, Some((&range, &buf))).unwrap();
+        assert!(fields.next_field::<i32>().is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        use postgres_types::Json;
+        use serde_json_1::{json, Value};
+
+        let value = json!({ "996": 251 });
+        let buf = to_bytes(&value, &Type::JSONB);
+        let range = 0..buf.len();
+
+        let v = Value::from_sql_nullable_ext(&Type::JSONB, Some((&range, &buf))).unwrap();
+        assert_eq!(v, value);
+
+        let Json(v) = <Json<Value>>::from_sql_nullable_ext(&Type::JSONB, Some((&range, &buf))).unwrap();
+        assert_eq!(v, value);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use time_03::{Date, Month};
+
+        let date = Date::from_calendar_date(2023, Month::October, 15).unwrap();
+        let buf = to_bytes(&date, &Type::DATE);
+        let range = 0..buf.len();
+
+        let v = Date::from_sql_nullable_ext(&Type::DATE, Some((&range, &buf))).unwrap();
+        assert_eq!(v, date);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono_04::{DateTime, NaiveDate, Utc};
+
+        let date = NaiveDate::from_ymd_opt(2023, 10, 15).unwrap();
+        let buf = to_bytes(&date, &Type::DATE);
+        let range = 0..buf.len();
+
+        let v = NaiveDate::from_sql_nullable_ext(&Type::DATE, Some((&range, &buf))).unwrap();
+        assert_eq!(v, date);
+
+        let time = date.and_hms_opt(9, 9, 6).unwrap().and_utc();
+        let buf = to_bytes(&time, &Type::TIMESTAMPTZ);
+        let range = 0..buf.len();
+
+        let v = <DateTime<Utc>>::from_sql_nullable_ext(&Type::TIMESTAMPTZ, Some((&range, &buf))).unwrap();
+        assert_eq!(v, time);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid() {
+        use uuid_1::Uuid;
+
+        let uuid = Uuid::from_u128(996);
+        let buf = to_bytes(&uuid, &Type::UUID);
+        let range = 0..buf.len();
+
+        let v = Uuid::from_sql_nullable_ext(&Type::UUID, Some((&range, &buf))).unwrap();
+        assert_eq!(v, uuid);
+    }
+}
@@ -0,0 +1,196 @@
+//! large object support for streaming big binary values.
+//!
+//! Large object lives in `pg_largeobject` system table and is accessed through descriptor
+//! returned by server side `lo_open` function. Unlike bytea value stored in a row it can be read
+//! and written in chunks which avoid loading whole value into memory.
+
+use std::io::SeekFrom;
+
+use postgres_types::Oid;
+use xitca_io::bytes::Bytes;
+
+use crate::{
+    client::Client, error::Error, from_sql::FromSqlExt, iter::AsyncIterator, statement::StatementGuarded, ToSql, Type,
+};
+
+// flags for lo_open mode. see libpq-fs.h
+const INV_WRITE: i32 = 0x0002_0000;
+const INV_READ: i32 = 0x0004_0000;
+
+/// Access mode of opened [LargeObject].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Mode {
+    /// Read only access. Reads observe the content of large object as of the snapshot of
+    /// current transaction.
+    Read,
+    /// Write only access.
+    Write,
+    /// Read and write access. Reads observe the writes of current transaction.
+    ReadWrite,
+}
+
+impl Mode {
+    const fn as_flag(self) -> i32 {
+        match self {
+            Self::Read => INV_READ,
+            Self::Write => INV_WRITE,
+            Self::ReadWrite => INV_READ | INV_WRITE,
+        }
+    }
+}
+
+/// Async handle of an opened large object.
+///
+/// Large object descriptor is only valid inside the transaction it's opened in and it's closed
+/// automatically when the transaction ends. [LargeObject::close] can be used to close it early.
+///
+/// # Examples
+/// ```rust
+/// use xitca_postgres::{large_object::Mode, Client, Error};
+///
+/// async fn copy(cli: &mut Client, data: &[&[u8]]) -> Result<(), Error> {
+///     let tx = cli.transaction().await?;
+///
+///     let oid = tx.create_large_object().await?;
+///     let mut lo = tx.open_large_object(oid, Mode::ReadWrite).await?;
+///
+///     // write data in chunks.
+///     for chunk in data {
+///         lo.write(chunk).await?;
+///     }
+///
+///     // rewind and read data in chunks.
+///     lo.seek(std::io::SeekFrom::Start(0)).await?;
+///     loop {
+///         let chunk = lo.read(8 * 1024).await?;
+///         if chunk.is_empty() {
+///             break;
+///         }
+///     }
+///
+///     lo.close().await?;
+///     tx.commit().await
+/// }
+/// ```
+pub struct LargeObject<'a> {
+    client: &'a Client,
+    fd: i32,
+    // lazily prepared statements for read and write which are repeatedly called.
+    read: Option<StatementGuarded<'a>>,
+    write: Option<StatementGuarded<'a>>,
+}
+
+impl<'a> LargeObject<'a> {
+    pub(crate) async fn create(client: &Client) -> Result<Oid, Error> {
+        query_one(client, "SELECT lo_create($1)", &[Type::OID], &[&0u32]).await
+    }
+
+    pub(crate) async fn open(client: &'a Client, oid: Oid, mode: Mode) -> Result<Self, Error> {
+        let fd = query_one(
+            client,
+            "SELECT lo_open($1, $2)",
+            &[Type::OID, Type::INT4],
+            &[&oid, &mode.as_flag()],
+        )
+        .await?;
+        Ok(Self {
+            client,
+            fd,
+            read: None,
+            write: None,
+        })
+    }
+
+    pub(crate) async fn unlink(client: &Client, oid: Oid) -> Result<(), Error> {
+        query_one::<i32>(client, "SELECT lo_unlink($1)", &[Type::OID], &[&oid])
+            .await
+            .map(|_| ())
+    }
+
+    /// Read up to len bytes from current position of large object. Empty bytes is returned when
+    /// the end of large object is reached.
+    pub async fn read(&mut self, len: usize) -> Result<Bytes, Error> {
+        if self.read.is_none() {
+            let stmt = self
+                .client
+                .prepare("SELECT loread($1, $2)", &[Type::INT4, Type::INT4])
+                .await?;
+            self.read = Some(stmt);
+        }
+        let stmt = self.read.as_ref().unwrap().as_ref();
+        let len = i32::try_from(len).unwrap_or(i32::MAX);
+        let mut rows = self.client.query(stmt, &[&self.fd, &len]).await?;
+        let row = rows.next().await.ok_or(Error::UnexpectedMessage)??;
+        row.try_get::<Bytes>(0)
+    }
+
+    /// Write all bytes to current position of large object and return the number of bytes
+    /// written.
+    pub async fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        if self.write.is_none() {
+            let stmt = self
+                .client
+                .prepare("SELECT lowrite($1, $2)", &[Type::INT4, Type::BYTEA])
+                .await?;
+            self.write = Some(stmt);
+        }
+        let stmt = self.write.as_ref().unwrap().as_ref();
+        let mut rows = self.client.query(stmt, &[&self.fd, &buf]).await?;
+        let row = rows.next().await.ok_or(Error::UnexpectedMessage)??;
+        row.try_get::<i32>(0).map(|n| n as usize)
+    }
+
+    /// Move current position of large object and return the new position from start.
+    pub async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (offset as i64, 0i32),
+            SeekFrom::Current(offset) => (offset, 1),
+            SeekFrom::End(offset) => (offset, 2),
+        };
+        query_one::<i64>(
+            self.client,
+            "SELECT lo_lseek64($1, $2, $3)",
+            &[Type::INT4, Type::INT8, Type::INT4],
+            &[&self.fd, &offset, &whence],
+        )
+        .await
+        .map(|pos| pos as u64)
+    }
+
+    /// Return current position of large object from start.
+    pub async fn tell(&mut self) -> Result<u64, Error> {
+        query_one::<i64>(self.client, "SELECT lo_tell64($1)", &[Type::INT4], &[&self.fd])
+            .await
+            .map(|pos| pos as u64)
+    }
+
+    /// Truncate or extend large object to given length in bytes.
+    pub async fn truncate(&mut self, len: u64) -> Result<(), Error> {
+        query_one::<i32>(
+            self.client,
+            "SELECT lo_truncate64($1, $2)",
+            &[Type::INT4, Type::INT8],
+            &[&self.fd, &(len as i64)],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Close large object descriptor.
+    pub async fn close(self) -> Result<(), Error> {
+        query_one::<i32>(self.client, "SELECT lo_close($1)", &[Type::INT4], &[&self.fd])
+            .await
+            .map(|_| ())
+    }
+}
+
+// prepare a one shot statement and return the first column of the first row.
+async fn query_one<T>(client: &Client, query: &str, types: &[Type], params: &[&(dyn ToSql + Sync)]) -> Result<T, Error>
+where
+    T: for<'r> FromSqlExt<'r>,
+{
+    let stmt = client.prepare(query, types).await?;
+    let mut rows = client.query(stmt.as_ref(), params).await?;
+    let row = rows.next().await.ok_or(Error::UnexpectedMessage)??;
+    row.try_get::<T>(0)
+}
@@ -10,15 +10,19 @@ mod config;
 mod driver;
 mod from_sql;
 mod iter;
+mod parameters;
 mod prepare;
 mod query;
 mod session;
 mod util;
 
 pub mod error;
+pub mod router;
 pub mod row;
 pub mod statement;
 
+#[cfg(not(feature = "quic"))]
+pub mod large_object;
 #[cfg(not(feature = "quic"))]
 pub mod pipeline;
 #[cfg(not(feature = "quic"))]
@@ -27,18 +31,161 @@ mod transaction;
 #[cfg(feature = "quic")]
 pub mod proxy;
 
-pub use postgres_types::{BorrowToSql, FromSql, ToSql, Type};
+pub use postgres_types::{BorrowToSql, FromSql, Kind, ToSql, Type};
+
+#[cfg(feature = "json")]
+pub use postgres_types::Json;
 
 pub use self::{
     client::Client,
-    config::Config,
+    config::{AfterConnectFuture, Config, DEFAULT_READ_BUF_PAGE_SIZE, DEFAULT_WRITE_BUF_LIMIT},
     driver::Driver,
     error::Error,
-    from_sql::FromSqlExt,
+    from_sql::{Composite, FromSqlError, FromSqlExt, Sql},
     iter::AsyncIterator,
-    query::{RowSimpleStream, RowStream},
+    parameters::ServerParameters,
+    query::{CommandComplete, RowSimpleStream, RowStream},
 };
 
+#[cfg(feature = "codegen")]
+pub mod codegen {
+    /// Derive macro for [ToSql](crate::ToSql) trait.
+    ///
+    /// Enum with unit variants maps to postgres enum type by label and newtype struct maps to
+    /// postgres domain type over the base type of inner type. Postgres type name and enum labels
+    /// default to rust identifiers and can be renamed with `#[postgres(name = "...")]` attribute.
+    /// Like all [ToSql](crate::ToSql) types the derived type must implement [Debug].
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use xitca_io::bytes::BytesMut;
+    /// use xitca_postgres::{codegen::ToSql, Kind, ToSql, Type};
+    ///
+    /// // enum type created with: CREATE TYPE mood AS ENUM ('sad', 'happy')
+    /// #[derive(ToSql, Debug)]
+    /// #[postgres(name = "mood")]
+    /// enum Mood {
+    ///     #[postgres(name = "sad")]
+    ///     Sad,
+    ///     #[postgres(name = "happy")]
+    ///     Happy,
+    /// }
+    ///
+    /// let ty = Type::new("mood".into(), 0, Kind::Enum(vec!["sad".into(), "happy".into()]), "public".into());
+    /// assert!(<Mood as ToSql>::accepts(&ty));
+    ///
+    /// let mut buf = BytesMut::new();
+    /// Mood::Happy.to_sql_checked(&ty, &mut buf).unwrap();
+    /// assert_eq!(&buf[..], b"happy");
+    /// ```
+    pub use xitca_codegen::ToSql;
+
+    /// Derive macro for [FromSqlExt](crate::FromSqlExt) trait. Supports the same types and
+    /// attributes as [ToSql](macro@ToSql).
+    ///
+    /// Newtype struct parses inner type with it's [FromSqlExt](crate::FromSqlExt) implementation
+    /// so zero copy types like [BytesStr](xitca_unsafe_collection::bytes::BytesStr) can be used.
+    /// Postgres reports domain type columns as their base type and newtype accepts the base type
+    /// as well.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use xitca_io::bytes::Bytes;
+    /// use xitca_postgres::{codegen::FromSqlExt, FromSqlExt, Kind, Type};
+    /// use xitca_unsafe_collection::bytes::BytesStr;
+    ///
+    /// #[derive(FromSqlExt, Debug, PartialEq)]
+    /// #[postgres(name = "mood")]
+    /// enum Mood {
+    ///     #[postgres(name = "sad")]
+    ///     Sad,
+    ///     #[postgres(name = "happy")]
+    ///     Happy,
+    /// }
+    ///
+    /// // domain type created with: CREATE DOMAIN email AS TEXT
+    /// #[derive(FromSqlExt)]
+    /// #[postgres(name = "email")]
+    /// struct Email(BytesStr);
+    ///
+    /// let ty = Type::new("mood".into(), 0, Kind::Enum(vec!["sad".into(), "happy".into()]), "public".into());
+    /// let buf = Bytes::from_static(b"sad");
+    /// let mood = Mood::from_sql_nullable_ext(&ty, Some((&(0..buf.len()), &buf))).unwrap();
+    /// assert_eq!(mood, Mood::Sad);
+    ///
+    /// let ty = Type::new("email".into(), 0, Kind::Domain(Type::TEXT), "public".into());
+    /// assert!(<Email as FromSqlExt>::accepts(&ty));
+    /// assert!(<Email as FromSqlExt>::accepts(&Type::TEXT));
+    ///
+    /// let buf = Bytes::from_static(b"foo@bar.com");
+    /// let email = Email::from_sql_nullable_ext(&ty, Some((&(0..buf.len()), &buf))).unwrap();
+    /// assert_eq!(email.0.as_str(), "foo@bar.com");
+    /// ```
+    pub use xitca_codegen::FromSqlExt;
+
+    /// Function like macro generating row type of a `SELECT` query checked against schema
+    /// snapshot at compile time. No database connection is needed for compiling.
+    ///
+    /// Schema snapshot is a text file with path relative to `CARGO_MANIFEST_DIR`. Every line of it
+    /// describes a column as `<table>.<column> <type> [null]` and lines starting with `#` are
+    /// comments. Selected columns are looked up from the snapshot and mapped to fields of
+    /// generated struct. Column marked with `null` is mapped to [Option].
+    ///
+    /// | postgres type                | rust type  |
+    /// |------------------------------|------------|
+    /// | bool                         | bool       |
+    /// | "char"                       | i8         |
+    /// | int2, int4, int8             | i16, i32, i64 |
+    /// | oid                          | u32        |
+    /// | float4, float8               | f32, f64   |
+    /// | text, varchar, bpchar, name  | &'r str    |
+    /// | bytea                        | &'r [u8]   |
+    ///
+    /// Text and bytea columns borrow from [Row](crate::row::Row) with zero copy and the generated
+    /// struct carries lifetime `'r` when any of them is selected.
+    ///
+    /// Only plain columns of a single table can be selected. `*`, table qualifier and `AS` alias
+    /// are supported while expressions and joins are rejected.
+    ///
+    /// # Example:
+    /// ```rust
+    /// use xitca_postgres::{codegen::query_typed, row::Row, Error};
+    ///
+    /// // tests/schema.txt:
+    /// // users.id int4
+    /// // users.name text
+    /// // users.email text null
+    /// query_typed! {
+    ///     schema = "tests/schema.txt",
+    ///     pub struct User = "SELECT id, name, email AS mail FROM users WHERE id = $1"
+    /// }
+    ///
+    /// fn parse(row: &Row<'_>) -> Result<(), Error> {
+    ///     let user = User::from_row(row)?;
+    ///     let _: (i32, &str, Option<&str>) = (user.id, user.name, user.mail);
+    ///     Ok(())
+    /// }
+    ///
+    /// assert_eq!(User::SQL, "SELECT id, name, email AS mail FROM users WHERE id = $1");
+    /// ```
+    ///
+    /// Column absent from schema snapshot fails compiling:
+    /// ```compile_fail
+    /// xitca_postgres::codegen::query_typed! {
+    ///     schema = "tests/schema.txt",
+    ///     struct User = "SELECT id, age FROM users"
+    /// }
+    /// ```
+    pub use xitca_codegen::query_typed;
+
+    #[doc(hidden)]
+    /// types used by code generated by derive macros.
+    pub mod __private {
+        pub use postgres_types::{to_sql_checked, IsNull, WasNull};
+        pub use xitca_io::bytes::{Bytes, BytesMut};
+    }
+}
+
 #[derive(Debug)]
 pub struct Postgres<C, const BATCH_LIMIT: usize> {
     cfg: C,
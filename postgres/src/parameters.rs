@@ -0,0 +1,128 @@
+//! runtime parameters reported by server.
+
+use std::sync::{Arc, Mutex};
+
+use postgres_protocol::message::backend::ParameterStatusBody;
+
+use super::error::Error;
+
+// driver is Send regardless of single-thread feature so the snapshot always use thread safe lock.
+pub(crate) type SharedParameters = Arc<Mutex<ServerParameters>>;
+
+/// Snapshot of runtime parameters reported by server with `ParameterStatus` message.
+///
+/// Server reports these parameters when session starts and whenever they are changed afterwards
+/// (by `SET` statement for example). The snapshot is kept up to date as long as the
+/// [Driver](crate::Driver) of the [Client](crate::Client) is running.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServerParameters {
+    server_version: Box<str>,
+    integer_datetimes: bool,
+    time_zone: Box<str>,
+    standard_conforming_strings: bool,
+}
+
+impl Default for ServerParameters {
+    fn default() -> Self {
+        // defaults of every server version supported.
+        Self {
+            server_version: "".into(),
+            integer_datetimes: true,
+            time_zone: "".into(),
+            standard_conforming_strings: true,
+        }
+    }
+}
+
+impl ServerParameters {
+    /// Version string of server. e.g. "16.2" or "16.2 (Debian 16.2-1.pgdg120+2)".
+    ///
+    /// Empty when server did not report it.
+    pub fn server_version(&self) -> &str {
+        &self.server_version
+    }
+
+    /// Numeric form of [Self::server_version] in the form of `major * 10000 + minor` for server
+    /// version 10 and later, and `major * 10000 + minor * 100 + patch` for older versions.
+    /// e.g. 160002 for "16.2" and 90624 for "9.6.24".
+    ///
+    /// None when version string is absent or can not be parsed.
+    pub fn server_version_num(&self) -> Option<u32> {
+        let version = self
+            .server_version
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()?;
+        let mut parts = version.split('.').map(str::parse::<u32>);
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().transpose().ok()?.unwrap_or(0);
+        if major >= 10 {
+            Some(major * 10000 + minor)
+        } else {
+            let patch = parts.next().transpose().ok()?.unwrap_or(0);
+            Some(major * 10000 + minor * 100 + patch)
+        }
+    }
+
+    /// Whether date and time types are stored as 64-bit integers. When false the binary format
+    /// of them is floating point.
+    pub fn integer_datetimes(&self) -> bool {
+        self.integer_datetimes
+    }
+
+    /// Time zone of session used for displaying and interpreting time stamps.
+    ///
+    /// Empty when server did not report it.
+    pub fn time_zone(&self) -> &str {
+        &self.time_zone
+    }
+
+    /// Whether ordinary string literals treat backslashes literally.
+    pub fn standard_conforming_strings(&self) -> bool {
+        self.standard_conforming_strings
+    }
+
+    pub(crate) fn try_set(&mut self, body: &ParameterStatusBody) -> Result<(), Error> {
+        self.set(body.name()?, body.value()?);
+        Ok(())
+    }
+
+    // unknown parameters are ignored.
+    fn set(&mut self, name: &str, value: &str) {
+        match name {
+            "server_version" => self.server_version = value.into(),
+            "integer_datetimes" => self.integer_datetimes = value == "on",
+            "TimeZone" => self.time_zone = value.into(),
+            "standard_conforming_strings" => self.standard_conforming_strings = value == "on",
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set() {
+        let mut params = ServerParameters::default();
+        assert_eq!(params.server_version_num(), None);
+
+        params.set("server_version", "16.2 (Debian 16.2-1.pgdg120+2)");
+        params.set("integer_datetimes", "off");
+        params.set("TimeZone", "Etc/UTC");
+        params.set("standard_conforming_strings", "off");
+        params.set("application_name", "xitca");
+
+        assert_eq!(params.server_version(), "16.2 (Debian 16.2-1.pgdg120+2)");
+        assert_eq!(params.server_version_num(), Some(160002));
+        assert!(!params.integer_datetimes());
+        assert_eq!(params.time_zone(), "Etc/UTC");
+        assert!(!params.standard_conforming_strings());
+
+        params.set("server_version", "9.6.24");
+        assert_eq!(params.server_version_num(), Some(90624));
+
+        params.set("server_version", "17devel");
+        assert_eq!(params.server_version_num(), Some(170000));
+    }
+}
@@ -1,5 +1,3 @@
-use core::ops::Range;
-
 use std::collections::VecDeque;
 
 use postgres_protocol::message::{backend, frontend};
@@ -15,6 +13,7 @@ use super::{
     iter::{slice_iter, AsyncIterator},
     row::Row,
     statement::Statement,
+    util::buf_pool::{BufPool, ColumnRange, PooledBuf},
     ToSql,
 };
 
@@ -44,6 +43,7 @@ use super::{
 /// ```
 pub struct Pipeline<'a, const SYNC_MODE: bool = true> {
     tx: &'a ClientTx,
+    ranges_pool: &'a BufPool<ColumnRange>,
     columns: VecDeque<&'a [Column]>,
     // how many SYNC message we are sending to database.
     // it determines when the driver would shutdown the pipeline.
@@ -59,6 +59,7 @@ impl<'a, const SYNC_MODE: bool> Pipeline<'a, SYNC_MODE> {
     fn new(client: &'a Client) -> Self {
         Self {
             tx: &client.tx,
+            ranges_pool: &client.ranges_pool,
             columns: VecDeque::new(),
             sync_count: 0,
             buf: BytesMut::new(),
@@ -131,7 +132,7 @@ impl<'a, const SYNC_MODE: bool> Pipeline<'a, SYNC_MODE> {
             return Ok(PipelineStream {
                 res: Response::no_op(),
                 columns: VecDeque::new(),
-                ranges: Vec::new(),
+                ranges: PooledBuf::detached(),
             });
         }
 
@@ -145,7 +146,7 @@ impl<'a, const SYNC_MODE: bool> Pipeline<'a, SYNC_MODE> {
         Ok(PipelineStream {
             res,
             columns: self.columns,
-            ranges: Vec::new(),
+            ranges: self.ranges_pool.get(),
         })
     }
 }
@@ -155,7 +156,7 @@ impl<'a, const SYNC_MODE: bool> Pipeline<'a, SYNC_MODE> {
 pub struct PipelineStream<'a> {
     res: Response,
     columns: VecDeque<&'a [Column]>,
-    ranges: Vec<Option<Range<usize>>>,
+    ranges: PooledBuf<ColumnRange>,
 }
 
 impl<'a> AsyncIterator for PipelineStream<'a> {
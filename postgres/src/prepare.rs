@@ -29,7 +29,9 @@ type BoxedFuture<'a> = Pin<Box<dyn Future<Output = Result<Type, Error>> + Send +
 
 impl Client {
     pub async fn prepare(&self, query: &str, types: &[Type]) -> Result<StatementGuarded<'_>, Error> {
-        self._prepare(query, types).await.map(|stmt| stmt.into_guarded(self))
+        let stmt = self._prepare(query, types).await?;
+        self.track_statement(stmt.name(), query);
+        Ok(stmt.into_guarded(self))
     }
 }
 
@@ -1,9 +1,11 @@
 mod base;
 mod row_stream;
 mod simple;
+#[cfg(not(feature = "quic"))]
+mod stream;
 
 pub(crate) mod decode;
 pub(crate) mod encode;
 
 pub use base::RowStream;
-pub use simple::RowSimpleStream;
+pub use simple::{CommandComplete, RowSimpleStream};
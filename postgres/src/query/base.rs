@@ -42,7 +42,7 @@ impl Client {
         self.encode_send(stmt, params).await.map(|res| RowStream {
             col: stmt.columns(),
             res,
-            ranges: Vec::new(),
+            ranges: self.ranges_pool.get(),
         })
     }
 
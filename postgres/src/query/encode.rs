@@ -29,7 +29,7 @@ where
     Ok(())
 }
 
-fn encode_bind<I>(stmt: &Statement, params: I, portal: &str, buf: &mut BytesMut) -> Result<(), Error>
+pub(super) fn encode_bind<I>(stmt: &Statement, params: I, portal: &str, buf: &mut BytesMut) -> Result<(), Error>
 where
     I: ExactSizeIterator,
     I::Item: BorrowToSql,
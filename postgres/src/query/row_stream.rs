@@ -1,9 +1,10 @@
-use core::ops::Range;
-
-use crate::driver::Response;
+use crate::{
+    driver::Response,
+    util::buf_pool::{ColumnRange, PooledBuf},
+};
 
 pub struct GenericRowStream<C> {
     pub(super) res: Response,
     pub(super) col: C,
-    pub(super) ranges: Vec<Option<Range<usize>>>,
+    pub(super) ranges: PooledBuf<ColumnRange>,
 }
@@ -2,18 +2,26 @@ use fallible_iterator::FallibleIterator;
 use postgres_protocol::message::{backend, frontend};
 
 use crate::{
-    client::Client, column::Column, driver::Response, error::Error, iter::AsyncIterator, row::RowSimple, Type,
+    client::Client,
+    column::Column,
+    driver::Response,
+    error::Error,
+    iter::AsyncIterator,
+    row::RowSimple,
+    util::buf_pool::{ColumnRange, PooledBuf},
+    Type,
 };
 
-use super::row_stream::GenericRowStream;
+use super::decode::body_to_affected_rows;
 
 impl Client {
     #[inline]
     pub async fn query_simple(&self, stmt: &str) -> Result<RowSimpleStream, Error> {
         self.encode_send_simple(stmt).await.map(|res| RowSimpleStream {
             res,
-            col: Vec::new(),
-            ranges: Vec::new(),
+            col: self.columns_pool.get(),
+            ranges: self.ranges_pool.get(),
+            state: State::Streaming,
         })
     }
 
@@ -29,35 +37,146 @@ impl Client {
 }
 
 /// A stream of simple query results.
-pub type RowSimpleStream = GenericRowStream<Vec<Column>>;
+///
+/// A simple query can contain multiple statements separated by `;`. [AsyncIterator::next] yields
+/// rows of current statement and returns `None` when the statement is complete. Use
+/// [RowSimpleStream::next_result] to obtain the completion of current statement and advance to
+/// the next one.
+///
+/// # Examples
+/// ```rust
+/// use xitca_postgres::{AsyncIterator, Client, Error};
+///
+/// async fn multi(cli: &Client) -> Result<(), Error> {
+///     let mut stream = cli.query_simple("SELECT 1; UPDATE foo SET bar = 1").await?;
+///     loop {
+///         // columns of current statement. empty for statement without result set.
+///         let _ = stream.columns();
+///         while let Some(row) = stream.next().await {
+///             let _row = row?;
+///         }
+///         match stream.next_result().await? {
+///             Some(done) => println!("{}: {} rows", done.tag(), done.rows_affected()),
+///             None => return Ok(()),
+///         }
+///     }
+/// }
+/// ```
+pub struct RowSimpleStream {
+    res: Response,
+    col: PooledBuf<Column>,
+    ranges: PooledBuf<ColumnRange>,
+    state: State,
+}
+
+enum State {
+    Streaming,
+    Complete(Option<CommandComplete>),
+    Done,
+}
+
+/// Completion of a single statement inside simple query.
+#[derive(Debug, Clone)]
+pub struct CommandComplete {
+    tag: Box<str>,
+    rows: u64,
+}
+
+impl CommandComplete {
+    /// Command tag of statement like `SELECT 2` or `UPDATE 1`.
+    #[inline]
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Number of rows affected or returned by statement. 0 for statement without row count.
+    #[inline]
+    pub fn rows_affected(&self) -> u64 {
+        self.rows
+    }
+}
+
+impl RowSimpleStream {
+    /// Columns of current statement's result set. Columns change when stream advances to a new
+    /// statement and they are empty for statement without result set.
+    #[inline]
+    pub fn columns(&self) -> &[Column] {
+        &self.col
+    }
+
+    /// Skip remaining rows of current statement and return it's completion. Stream is advanced to
+    /// the next statement after this call.
+    ///
+    /// Returns `None` when all statements are complete.
+    pub async fn next_result(&mut self) -> Result<Option<CommandComplete>, Error> {
+        while let Some(row) = self.next().await {
+            row?;
+        }
+
+        match core::mem::replace(&mut self.state, State::Streaming) {
+            State::Complete(Some(complete)) => {
+                self.col.clear();
+                Ok(Some(complete))
+            }
+            // empty query or query complete.
+            _ => {
+                self.state = State::Done;
+                Ok(None)
+            }
+        }
+    }
+}
 
 impl AsyncIterator for RowSimpleStream {
     type Item<'i> = Result<RowSimple<'i>, Error> where Self: 'i;
 
     async fn next(&mut self) -> Option<Self::Item<'_>> {
+        if !matches!(self.state, State::Streaming) {
+            return None;
+        }
+
         loop {
             match self.res.recv().await {
                 Ok(msg) => match msg {
                     backend::Message::RowDescription(body) => {
-                        match body
-                            .fields()
-                            // text type is used to match RowSimple::try_get's implementation
-                            // where column's pg type is always assumed as Option<&str>.
-                            // (no runtime pg type check so this does not really matter. it's
-                            // better to keep the type consistent though)
-                            .map(|f| Ok(Column::new(f.name(), Type::TEXT)))
-                            .collect::<Vec<_>>()
-                        {
-                            Ok(col) => self.col = col,
-                            Err(e) => return Some(Err(e.into())),
+                        // reuse column buffer between statements.
+                        self.col.clear();
+                        let mut fields = body.fields();
+                        loop {
+                            match fields.next() {
+                                // text type is used to match RowSimple::try_get's implementation
+                                // where column's pg type is always assumed as Option<&str>.
+                                // (no runtime pg type check so this does not really matter. it's
+                                // better to keep the type consistent though)
+                                Ok(Some(f)) => self.col.push(Column::new(f.name(), Type::TEXT)),
+                                Ok(None) => break,
+                                Err(e) => return Some(Err(e.into())),
+                            }
                         }
                     }
                     backend::Message::DataRow(body) => {
                         return Some(RowSimple::try_new(&self.col, body, &mut self.ranges));
                     }
-                    backend::Message::CommandComplete(_)
-                    | backend::Message::EmptyQueryResponse
-                    | backend::Message::ReadyForQuery(_) => return None,
+                    backend::Message::CommandComplete(body) => {
+                        let complete = body.tag().map_err(Error::from).and_then(|tag| {
+                            body_to_affected_rows(&body).map(|rows| CommandComplete { tag: tag.into(), rows })
+                        });
+                        return match complete {
+                            Ok(complete) => {
+                                self.state = State::Complete(Some(complete));
+                                None
+                            }
+                            Err(e) => Some(Err(e)),
+                        };
+                    }
+                    backend::Message::EmptyQueryResponse => {
+                        self.state = State::Complete(None);
+                        return None;
+                    }
+                    backend::Message::ReadyForQuery(_) => {
+                        self.state = State::Done;
+                        return None;
+                    }
                     _ => return Some(Err(Error::UnexpectedMessage)),
                 },
                 Err(e) => return Some(Err(e)),
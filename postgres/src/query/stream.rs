@@ -0,0 +1,185 @@
+use core::{future::poll_fn, pin::Pin};
+
+use std::{error, io};
+
+use postgres_protocol::message::{backend, frontend};
+use postgres_types::{to_sql_checked, IsNull, Type};
+use tokio::io::{AsyncRead, ReadBuf};
+use xitca_io::bytes::BytesMut;
+
+use crate::{client::Client, error::Error, statement::Statement, ToSql};
+
+// size of chunk read from reader and sent to driver.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+impl Client {
+    /// Executes a statement with it's last parameter streamed from a reader, returning the number
+    /// of rows modified.
+    ///
+    /// The last parameter must be `bytea` or `text` like type and it's value is read from given
+    /// reader in chunks and written to database as it goes. It's useful for very large value that
+    /// is not desirable to be materialized in memory at once. `len` is the exact length of the
+    /// value in bytes and reader must produce exactly `len` bytes.
+    ///
+    /// The connection is exclusively occupied by streamed value until it's finished. Other
+    /// requests sent to the same client in the meantime wait until then.
+    ///
+    /// When reader fails or ends before `len` bytes are produced the statement is not executed
+    /// and [Error::Io] is returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_postgres::{Client, Error};
+    /// # async fn stream(cli: &Client) -> Result<(), Error> {
+    /// let stmt = cli.prepare("INSERT INTO blob (name, data) VALUES ($1, $2)", &[]).await?;
+    ///
+    /// // any type implementing tokio::io::AsyncRead can be used as reader.
+    /// let data: &[u8] = &[7; 1024 * 1024];
+    /// let rows = cli.execute_streamed(stmt.as_ref(), &[&"blob"], data.len(), data).await?;
+    /// assert_eq!(rows, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if given params slice length plus one does not match the length of [Statement::params].
+    pub async fn execute_streamed<R>(
+        &self,
+        stmt: &Statement,
+        params: &[&(dyn ToSql + Sync)],
+        len: usize,
+        mut reader: R,
+    ) -> Result<u64, Error>
+    where
+        R: AsyncRead + Unpin,
+    {
+        assert_eq!(
+            stmt.params().len(),
+            params.len() + 1,
+            "expected {} parameters but got {} and a streamed one",
+            stmt.params().len(),
+            params.len()
+        );
+
+        let ty = stmt.params().last().unwrap();
+        if !(<&[u8] as ToSql>::accepts(ty) || <&str as ToSql>::accepts(ty)) {
+            return Err(invalid_input(format!("streamed parameter can not be {ty} type")));
+        }
+
+        let value_len = i32::try_from(len).map_err(|_| invalid_input("streamed parameter is too large"))?;
+
+        let mut head = self.try_buf_and_split(|buf| encode(buf, stmt, params, value_len))?;
+        // result format codes at the end of bind message go after streamed value.
+        let tail = head.split_off(head.len() - 4);
+
+        let (mut res, tx) = self.send_streamed(head, len + tail.len())?;
+
+        let res_stream = async {
+            let mut remaining = len;
+            while remaining > 0 {
+                let mut chunk = BytesMut::zeroed(remaining.min(CHUNK_SIZE));
+                let mut read_buf = ReadBuf::new(&mut chunk);
+                poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut read_buf)).await?;
+                let n = read_buf.filled().len();
+                if n == 0 {
+                    return Err(Error::from(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "reader ended before streamed parameter is complete",
+                    )));
+                }
+                chunk.truncate(n);
+                remaining -= n;
+                tx.send(chunk).await?;
+            }
+
+            let mut tail = tail;
+            frontend::execute("", 0, &mut tail).map_err(|_| Error::ToDo)?;
+            frontend::sync(&mut tail);
+            tx.send(tail).await.map_err(Error::from)
+        };
+
+        if let Err(e) = res_stream.await {
+            // dropped sender makes driver finish the bind message with padding and sync it
+            // without executing. drain the response so connection is ready for next request.
+            drop(tx);
+            while let Ok(msg) = res.recv().await {
+                if matches!(msg, backend::Message::ReadyForQuery(_)) {
+                    break;
+                }
+            }
+            return Err(e);
+        }
+
+        drop(tx);
+
+        match res.recv().await? {
+            backend::Message::BindComplete => res.try_into_row_affected().await,
+            _ => Err(Error::UnexpectedMessage),
+        }
+    }
+}
+
+// encode bind message with streamed value left out. the last 4 bytes are result format codes
+// which go after streamed value.
+fn encode(buf: &mut BytesMut, stmt: &Statement, params: &[&(dyn ToSql + Sync)], len: i32) -> Result<(), Error> {
+    let params = params
+        .iter()
+        .map(|p| *p as &dyn ToSql)
+        .chain([&Placeholder as &dyn ToSql])
+        .collect::<Vec<_>>();
+
+    super::encode::encode_bind(stmt, params.iter().copied(), "", buf)?;
+
+    // placeholder writes nothing. patch message length and value length with streamed one.
+    let msg_len = i32::from_be_bytes(buf[1..5].try_into().unwrap()) + len;
+    buf[1..5].copy_from_slice(&msg_len.to_be_bytes());
+
+    let at = buf.len() - 4;
+    buf[at - 4..at].copy_from_slice(&len.to_be_bytes());
+
+    Ok(())
+}
+
+fn invalid_input(msg: impl Into<Box<dyn error::Error + Send + Sync>>) -> Error {
+    Error::from(io::Error::new(io::ErrorKind::InvalidInput, msg))
+}
+
+// stand in of streamed value when encoding bind message.
+#[derive(Debug)]
+struct Placeholder;
+
+impl ToSql for Placeholder {
+    fn to_sql(&self, _: &Type, _: &mut BytesMut) -> Result<IsNull, Box<dyn error::Error + Sync + Send>> {
+        Ok(IsNull::No)
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode_streamed() {
+        let stmt = Statement::new(String::from("s"), vec![Type::TEXT, Type::BYTEA], Vec::new());
+        let value: &[u8] = b"hello,world";
+
+        let mut expected = BytesMut::new();
+        super::super::encode::encode_bind(&stmt, [&"name" as &dyn ToSql, &value].into_iter(), "", &mut expected)
+            .unwrap();
+
+        let mut buf = BytesMut::new();
+        encode(&mut buf, &stmt, &[&"name"], value.len() as i32).unwrap();
+        let tail = buf.split_off(buf.len() - 4);
+        buf.extend_from_slice(value);
+        buf.extend_from_slice(&tail);
+
+        assert_eq!(buf, expected);
+    }
+}
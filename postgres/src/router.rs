@@ -0,0 +1,285 @@
+//! routing between primary server and read replicas.
+//!
+//! [Router] holds a connection to primary server and any number of connections to its streaming
+//! replicas. Writes are routed to primary and reads are spread across replicas. Optional read
+//! your writes guarantee is offered per session by recording the WAL position(LSN) of primary
+//! after session's writes and waiting for replica to replay up to it before session's reads.
+
+use core::{
+    fmt,
+    future::Future,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use alloc::vec::Vec;
+
+use tokio::time::{sleep, Instant};
+
+use crate::{client::Client, error::Error, from_sql::FromSqlError, iter::AsyncIterator};
+
+/// Routing target of a query.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Route {
+    /// Route to primary server. Used for writes and reads that can not tolerate replication lag.
+    Primary,
+    /// Route to any replica. Reads may observe stale data.
+    Replica,
+    /// Route to a replica that has replayed WAL up to given position. The position is the one
+    /// returned by [Router::record_write] after session's last write so reads observe writes of
+    /// the same session regardless of writes from other sessions.
+    /// Fallback to primary when replica does not catch up in time.
+    ReadYourWrites(Lsn),
+}
+
+/// Log sequence number of postgres WAL.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Lsn(pub u64);
+
+impl Lsn {
+    /// Parse lsn from its text format. e.g. `16/B374D848`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (hi, lo) = s.split_once('/')?;
+        let hi = u32::from_str_radix(hi, 16).ok()?;
+        let lo = u32::from_str_radix(lo, 16).ok()?;
+        Some(Self((hi as u64) << 32 | lo as u64))
+    }
+}
+
+impl fmt::Display for Lsn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 as u32)
+    }
+}
+
+/// Router of queries between primary and replicas.
+///
+/// # Examples
+/// ```rust
+/// use xitca_postgres::{router::{Route, Router}, Error};
+///
+/// async fn route(router: &Router) -> Result<(), Error> {
+///     // write to primary and record its WAL position for current session.
+///     router.route(Route::Primary).await?.execute_simple("INSERT INTO foo VALUES (1)").await?;
+///     let lsn = router.record_write().await?;
+///
+///     // read from replica that has observed the insert above.
+///     let cli = router.route(Route::ReadYourWrites(lsn)).await?;
+///     let _ = cli.query_simple("SELECT * FROM foo").await?;
+///     Ok(())
+/// }
+/// ```
+pub struct Router {
+    primary: Client,
+    replicas: Vec<Client>,
+    next: AtomicUsize,
+    replay_timeout: Duration,
+    replay_interval: Duration,
+}
+
+impl Router {
+    /// Construct a router with given primary and replica clients.
+    pub fn new(primary: Client, replicas: Vec<Client>) -> Self {
+        Self {
+            primary,
+            replicas,
+            next: AtomicUsize::new(0),
+            replay_timeout: Duration::from_secs(1),
+            replay_interval: Duration::from_millis(10),
+        }
+    }
+
+    /// Set max duration [Route::ReadYourWrites] waits for replica to replay recorded writes
+    /// before falling back to primary.
+    ///
+    /// Default to 1 second. Zero duration disables waiting.
+    pub fn replay_timeout(mut self, dur: Duration) -> Self {
+        self.replay_timeout = dur;
+        self
+    }
+
+    /// Set interval of checking replica's replay position when waiting for it to catch up.
+    ///
+    /// Default to 10 milliseconds.
+    pub fn replay_interval(mut self, dur: Duration) -> Self {
+        self.replay_interval = dur;
+        self
+    }
+
+    /// Reference of primary client.
+    #[inline]
+    pub fn primary(&self) -> &Client {
+        &self.primary
+    }
+
+    /// Reference of replica clients.
+    #[inline]
+    pub fn replicas(&self) -> &[Client] {
+        &self.replicas
+    }
+
+    /// Get client for given route. Primary client is returned when there is no replica.
+    pub async fn route(&self, route: Route) -> Result<&Client, Error> {
+        match route {
+            Route::Primary => Ok(&self.primary),
+            Route::Replica => Ok(self.next_replica().unwrap_or(&self.primary)),
+            Route::ReadYourWrites(lsn) => self.read_your_writes(lsn).await,
+        }
+    }
+
+    /// Record current WAL position of primary. Session keeps the returned position and passes it
+    /// to [Route::ReadYourWrites] so it's following reads would observe all it's writes done
+    /// before this call.
+    pub async fn record_write(&self) -> Result<Lsn, Error> {
+        query_lsn(&self.primary, "SELECT pg_current_wal_lsn()::text")
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    fn next_replica(&self) -> Option<&Client> {
+        next(&self.replicas, &self.next)
+    }
+
+    async fn read_your_writes(&self, target: Lsn) -> Result<&Client, Error> {
+        let Some(replica) = self.next_replica() else {
+            return Ok(&self.primary);
+        };
+
+        let replayed = wait_replay(target, self.replay_timeout, self.replay_interval, || {
+            query_lsn(replica, "SELECT pg_last_wal_replay_lsn()::text")
+        })
+        .await?;
+
+        Ok(if replayed { replica } else { &self.primary })
+    }
+}
+
+// round robin between items.
+fn next<'a, T>(items: &'a [T], next: &AtomicUsize) -> Option<&'a T> {
+    if items.is_empty() {
+        return None;
+    }
+    let idx = next.fetch_add(1, Ordering::Relaxed) % items.len();
+    items.get(idx)
+}
+
+// check replay position with given function until it reaches target or timeout.
+// return false when timed out.
+async fn wait_replay<F, Fut>(
+    target: Lsn,
+    timeout: Duration,
+    interval: Duration,
+    mut replay_lsn: F,
+) -> Result<bool, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<Lsn>, Error>>,
+{
+    if target.0 == 0 {
+        return Ok(true);
+    }
+
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        match replay_lsn().await? {
+            // null lsn means the server is not in recovery and it's not a replica.
+            None => return Ok(true),
+            Some(lsn) if lsn >= target => return Ok(true),
+            Some(_) => {}
+        }
+
+        if Instant::now() + interval > deadline {
+            return Ok(false);
+        }
+
+        sleep(interval).await;
+    }
+}
+
+async fn query_lsn(cli: &Client, stmt: &str) -> Result<Option<Lsn>, Error> {
+    let mut stream = cli.query_simple(stmt).await?;
+    let row = stream.next().await.ok_or(Error::UnexpectedMessage)??;
+    match row.try_get(0)? {
+        Some(lsn) => Lsn::parse(lsn)
+            .map(Some)
+            .ok_or_else(|| Error::from(FromSqlError::from(format!("invalid pg_lsn: {lsn}")))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lsn() {
+        let lsn = Lsn::parse("16/B374D848").unwrap();
+        assert_eq!(lsn, Lsn(0x16_B374_D848));
+        assert_eq!(lsn.to_string(), "16/B374D848");
+        assert!(Lsn::parse("0/0").unwrap() < lsn);
+        assert!(Lsn::parse("B374D848").is_none());
+        assert!(Lsn::parse("16/zz").is_none());
+    }
+
+    #[test]
+    fn round_robin() {
+        let counter = AtomicUsize::new(0);
+        assert!(next::<usize>(&[], &counter).is_none());
+
+        let items = [0, 1, 2];
+        let picked = (0..4).map(|_| *next(&items, &counter).unwrap()).collect::<Vec<_>>();
+        assert_eq!(picked, [0, 1, 2, 0]);
+    }
+
+    #[tokio::test]
+    async fn replay() {
+        use core::cell::Cell;
+
+        let interval = Duration::from_millis(1);
+        let timeout = Duration::from_millis(50);
+
+        // session without write does not wait.
+        let calls = Cell::new(0);
+        let check = || {
+            calls.set(calls.get() + 1);
+            async { Ok(Some(Lsn(0))) }
+        };
+        assert!(wait_replay(Lsn(0), timeout, interval, check).await.unwrap());
+        assert_eq!(calls.get(), 0);
+
+        // replica catches up after a few checks.
+        let replayed = Cell::new(0);
+        let check = || {
+            replayed.set(replayed.get() + 1);
+            let lsn = Lsn(replayed.get());
+            async move { Ok(Some(lsn)) }
+        };
+        assert!(wait_replay(Lsn(3), timeout, interval, check).await.unwrap());
+        assert_eq!(replayed.get(), 3);
+
+        // server not in recovery is treated as up to date.
+        assert!(wait_replay(Lsn(3), timeout, interval, || async { Ok(None) })
+            .await
+            .unwrap());
+
+        // replica lagging behind session's write falls back to primary.
+        let calls = Cell::new(0);
+        let check = || {
+            calls.set(calls.get() + 1);
+            async { Ok(Some(Lsn(2))) }
+        };
+        assert!(!wait_replay(Lsn(3), timeout, interval, check).await.unwrap());
+        assert!(calls.get() > 1);
+
+        // another session with older write is not affected by the lagging one.
+        assert!(wait_replay(Lsn(2), timeout, interval, || async { Ok(Some(Lsn(2))) })
+            .await
+            .unwrap());
+
+        // error of checking is surfaced.
+        assert!(wait_replay(Lsn(3), timeout, interval, || async { Err(Error::ToDo) })
+            .await
+            .is_err());
+    }
+}
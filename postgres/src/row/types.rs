@@ -5,7 +5,7 @@ use postgres_protocol::message::backend::DataRowBody;
 use postgres_types::FromSql;
 use xitca_io::bytes::Bytes;
 
-use crate::{column::Column, error::Error, from_sql::FromSqlExt, Type};
+use crate::{column::Column, error::Error, from_sql::FromSqlExt, util::buf_pool::ColumnRange, Type};
 
 use super::traits::RowIndexAndType;
 
@@ -24,7 +24,7 @@ mod marker {
 pub struct GenericRow<'a, M> {
     columns: &'a [Column],
     body: DataRowBody,
-    ranges: &'a mut Vec<Option<Range<usize>>>,
+    ranges: &'a [ColumnRange],
     _marker: PhantomData<M>,
 }
 
@@ -38,15 +38,15 @@ impl<'a, C> GenericRow<'a, C> {
     pub(crate) fn try_new(
         columns: &'a [Column],
         body: DataRowBody,
-        ranges: &'a mut Vec<Option<Range<usize>>>,
+        ranges: &'a mut Vec<ColumnRange>,
     ) -> Result<Self, Error> {
-        let mut iter = body.ranges().enumerate();
+        // ranges are parsed once per row into buffer reused across rows. column values are
+        // borrowed from body's bytes afterwards.
+        ranges.clear();
+        let mut iter = body.ranges();
         ranges.reserve(iter.size_hint().0);
-        while let Some((idx, range)) = iter.next()? {
-            match ranges.get_mut(idx) {
-                Some(r) => *r = range,
-                None => ranges.push(range),
-            }
+        while let Some(range) = iter.next()? {
+            ranges.push(range);
         }
         Ok(Self {
             columns,
@@ -31,6 +31,10 @@ impl Client {
     where
         D: Drive,
     {
+        if cfg.get_statement_tracking() {
+            self.enable_statement_tracking();
+        }
+
         self.auth(drv, cfg).await?;
 
         loop {
@@ -39,9 +43,7 @@ impl Client {
                 backend::Message::BackendKeyData(_) => {
                     // TODO: handle process id and secret key.
                 }
-                backend::Message::ParameterStatus(_) => {
-                    // TODO: handle parameters
-                }
+                backend::Message::ParameterStatus(body) => self.parameters.lock().unwrap().try_set(&body)?,
                 _ => {
                     // TODO: other session message handling?
                 }
@@ -2,11 +2,14 @@
 
 use alloc::{boxed::Box, vec::Vec};
 
-use postgres_protocol::message::frontend;
+use postgres_protocol::message::{backend, frontend};
 
-use super::{client::Client, column::Column, Type};
+use super::{client::Client, column::Column, error::Error, Type};
 
 /// Guarded statement that would cancel itself when dropped.
+///
+/// Statements dropped are not closed immediately. Their close messages are batched and sent to
+/// database together with the next request of the client or when client is dropped.
 pub struct StatementGuarded<'a> {
     statement: Option<Statement>,
     client: &'a Client,
@@ -30,17 +33,16 @@ impl<'a> StatementGuarded<'a> {
         self.statement.take().unwrap()
     }
 
+    /// Close the statement and wait for database to confirm it. See [Statement::close].
+    pub async fn close(mut self) -> Result<(), Error> {
+        let statement = self.statement.take().unwrap();
+        statement.close(self.client).await
+    }
+
     fn cancel(&mut self) {
         if let Some(statement) = self.statement.take() {
-            if !self.client.closed() {
-                let res = self
-                    .client
-                    .try_buf_and_split(|b| frontend::close(b'S', &statement.name, b).map(|_| frontend::sync(b)));
-
-                if let Ok(msg) = res {
-                    self.client.do_send(msg);
-                }
-            }
+            self.client.untrack_statement(&statement.name);
+            self.client.close_later(statement.name);
         }
     }
 }
@@ -87,6 +89,29 @@ impl Statement {
         &self.columns
     }
 
+    /// Close the statement on database side and wait for database to confirm it.
+    ///
+    /// Unlike dropping [StatementGuarded] error of closing is returned to caller. Statement must
+    /// not be used with the client afterwards. Closing a statement that is already closed is not
+    /// an error.
+    pub async fn close(self, client: &Client) -> Result<(), Error> {
+        client.untrack_statement(&self.name);
+
+        let msg = client.try_buf_and_split(|b| frontend::close(b'S', &self.name, b).map(|_| frontend::sync(b)))?;
+
+        let mut res = client.send(msg).await?;
+
+        match res.recv().await? {
+            backend::Message::CloseComplete => {}
+            _ => return Err(Error::UnexpectedMessage),
+        }
+
+        match res.recv().await? {
+            backend::Message::ReadyForQuery(_) => Ok(()),
+            _ => Err(Error::UnexpectedMessage),
+        }
+    }
+
     /// Convert self to a drop guarded statement which would cancel on drop.
     pub fn into_guarded(self, client: &Client) -> StatementGuarded<'_> {
         StatementGuarded {
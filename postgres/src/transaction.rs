@@ -1,6 +1,14 @@
 use postgres_protocol::message::frontend;
+use postgres_types::Oid;
 
-use super::{client::Client, error::Error, query::RowStream, statement::Statement, BorrowToSql, ToSql};
+use super::{
+    client::Client,
+    error::Error,
+    large_object::{LargeObject, Mode},
+    query::RowStream,
+    statement::Statement,
+    BorrowToSql, ToSql,
+};
 
 impl Client {
     pub async fn transaction(&mut self) -> Result<Transaction<'_>, Error> {
@@ -51,6 +59,22 @@ impl Transaction<'_> {
         self.client.query_raw(stmt, params).await
     }
 
+    /// Create a new empty large object and return its [Oid].
+    pub async fn create_large_object(&self) -> Result<Oid, Error> {
+        LargeObject::create(self.client).await
+    }
+
+    /// Open large object with given [Oid] and access mode. The returned [LargeObject] can be used
+    /// for streaming its content in chunks until the transaction ends.
+    pub async fn open_large_object(&self, oid: Oid, mode: Mode) -> Result<LargeObject<'_>, Error> {
+        LargeObject::open(self.client, oid, mode).await
+    }
+
+    /// Remove large object with given [Oid].
+    pub async fn unlink_large_object(&self, oid: Oid) -> Result<(), Error> {
+        LargeObject::unlink(self.client, oid).await
+    }
+
     pub async fn commit(mut self) -> Result<(), Error> {
         let res = self.client.encode_send_simple("COMMIT").await?;
         self.state = State::Finish;
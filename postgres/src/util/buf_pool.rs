@@ -0,0 +1,116 @@
+//! pool of reusable buffers shared between [Client](crate::Client) and it's row streams.
+//!
+//! Row streams take buffer from pool when query starts and give it back when they are dropped so
+//! hot query loops on the same client reuse allocations instead of growing new buffers for every
+//! query.
+
+use core::ops::{Deref, DerefMut, Range};
+
+// streams are Send regardless of single-thread feature so pool always use thread safe lock.
+use std::sync::{Arc, Mutex};
+
+/// byte range of a column value inside a data row. `None` for null value.
+pub(crate) type ColumnRange = Option<Range<usize>>;
+
+// max number of idle buffers kept by pool. concurrent streams beyond it allocate and drop their
+// own buffers.
+const MAX_IDLE: usize = 8;
+
+pub(crate) struct BufPool<T>(Arc<Mutex<Vec<Vec<T>>>>);
+
+impl<T> Clone for BufPool<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> BufPool<T> {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(Mutex::new(Vec::new())))
+    }
+
+    pub(crate) fn get(&self) -> PooledBuf<T> {
+        let buf = self.0.lock().unwrap().pop().unwrap_or_default();
+        PooledBuf {
+            buf,
+            pool: Some(self.clone()),
+        }
+    }
+}
+
+/// buffer taken from [BufPool]. cleared and returned to pool on drop.
+pub(crate) struct PooledBuf<T> {
+    buf: Vec<T>,
+    pool: Option<BufPool<T>>,
+}
+
+impl<T> PooledBuf<T> {
+    /// buffer not belonging to any pool.
+    pub(crate) fn detached() -> Self {
+        Self {
+            buf: Vec::new(),
+            pool: None,
+        }
+    }
+}
+
+impl<T> Deref for PooledBuf<T> {
+    type Target = Vec<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.buf
+    }
+}
+
+impl<T> DerefMut for PooledBuf<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buf
+    }
+}
+
+impl<T> Drop for PooledBuf<T> {
+    fn drop(&mut self) {
+        if let Some(ref pool) = self.pool {
+            if self.buf.capacity() == 0 {
+                return;
+            }
+            let mut idle = pool.0.lock().unwrap();
+            if idle.len() < MAX_IDLE {
+                self.buf.clear();
+                idle.push(core::mem::take(&mut self.buf));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reuse() {
+        let pool = BufPool::<usize>::new();
+
+        let mut buf = pool.get();
+        buf.extend([1, 2, 3]);
+        let ptr = buf.as_ptr();
+        drop(buf);
+
+        let buf = pool.get();
+        assert!(buf.is_empty());
+        assert!(buf.capacity() >= 3);
+        assert_eq!(buf.as_ptr(), ptr);
+
+        // buffer taken while the only idle one is in use is a new one.
+        let buf2 = pool.get();
+        assert_eq!(buf2.capacity(), 0);
+
+        let mut detached = PooledBuf::<usize>::detached();
+        detached.push(1);
+        drop(detached);
+        drop(buf);
+        assert_eq!(pool.0.lock().unwrap().len(), 1);
+    }
+}
@@ -1 +1,2 @@
+pub(crate) mod buf_pool;
 pub(crate) mod lock;
@@ -6,7 +6,7 @@ use std::io;
 use xitca_io::net::Stream;
 
 use crate::{
-    net::AsListener,
+    net::{AsListener, TcpConfig, TcpKeepalive},
     server::{IntoServiceObj, Server, ServerFuture, ServiceObj},
 };
 
@@ -14,12 +14,21 @@ pub struct Builder {
     pub(crate) server_threads: usize,
     pub(crate) worker_threads: usize,
     pub(crate) worker_max_blocking_threads: usize,
+    pub(crate) worker_core_affinity: Vec<usize>,
+    pub(crate) max_connections: Option<usize>,
+    pub(crate) max_connections_per_worker: Option<usize>,
+    pub(crate) max_accept_rate: Option<u32>,
     pub(crate) listeners: HashMap<String, Vec<Box<dyn AsListener>>>,
     pub(crate) factories: HashMap<String, ServiceObj>,
     pub(crate) enable_signal: bool,
     pub(crate) shutdown_timeout: Duration,
     pub(crate) on_worker_start: Box<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>,
-    backlog: u32,
+    pub(crate) on_graceful_shutdown: Vec<Box<dyn Fn() + Send + Sync>>,
+    #[cfg(feature = "io-uring")]
+    pub(crate) io_uring_entries: u32,
+    #[cfg(feature = "io-uring")]
+    pub(crate) io_uring_multishot_accept: bool,
+    tcp: TcpConfig,
 }
 
 impl Default for Builder {
@@ -35,12 +44,21 @@ impl Builder {
             server_threads: 1,
             worker_threads: std::thread::available_parallelism().map(|size| size.get()).unwrap_or(1),
             worker_max_blocking_threads: 512,
+            worker_core_affinity: Vec::new(),
+            max_connections: None,
+            max_connections_per_worker: None,
+            max_accept_rate: None,
             listeners: HashMap::new(),
             factories: HashMap::new(),
             enable_signal: true,
             shutdown_timeout: Duration::from_secs(30),
             on_worker_start: Box::new(|| Box::pin(async {})),
-            backlog: 2048,
+            on_graceful_shutdown: Vec::new(),
+            #[cfg(feature = "io-uring")]
+            io_uring_entries: 256,
+            #[cfg(feature = "io-uring")]
+            io_uring_multishot_accept: false,
+            tcp: TcpConfig::new(),
         }
     }
 
@@ -89,6 +107,70 @@ impl Builder {
         self
     }
 
+    /// Pin worker threads to given cpu cores.
+    ///
+    /// Worker with index `n` is pinned to core `cores[n % cores.len()]`. Empty cores disable the
+    /// pinning which is the default.
+    ///
+    /// # Examples:
+    /// ```
+    /// # use xitca_server::Builder;
+    /// // pin 4 workers to the first 4 cores.
+    /// let builder = Builder::new().worker_threads(4).worker_core_affinity(0..4);
+    /// ```
+    ///
+    /// *. Pinning only works on linux and is ignored on other platforms.
+    pub fn worker_core_affinity<I>(mut self, cores: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.worker_core_affinity = cores.into_iter().collect();
+        self
+    }
+
+    /// Set max number of concurrent connections of the server across all workers.
+    ///
+    /// When the limit is reached workers stop accepting new connections until existing ones are
+    /// closed. Pending connections are queued in listener's backlog.
+    ///
+    /// Default to no limit.
+    ///
+    /// # Panics:
+    /// When received 0 as number of connections.
+    pub fn max_connections(mut self, num: usize) -> Self {
+        assert_ne!(num, 0, "Max connections must be higher than 0");
+        self.max_connections = Some(num);
+        self
+    }
+
+    /// Set max number of concurrent connections of every worker.
+    ///
+    /// See [Builder::max_connections] for behavior when the limit is reached.
+    ///
+    /// Default to no limit.
+    ///
+    /// # Panics:
+    /// When received 0 as number of connections.
+    pub fn max_connections_per_worker(mut self, num: usize) -> Self {
+        assert_ne!(num, 0, "Max connections per worker must be higher than 0");
+        self.max_connections_per_worker = Some(num);
+        self
+    }
+
+    /// Set max number of connections the server accepts per second across all workers.
+    ///
+    /// Accepting is evenly paced so a burst of incoming connections is spread over time.
+    ///
+    /// Default to no limit.
+    ///
+    /// # Panics:
+    /// When received 0 as rate.
+    pub fn max_accept_rate(mut self, per_sec: u32) -> Self {
+        assert_ne!(per_sec, 0, "Max accept rate must be higher than 0");
+        self.max_accept_rate = Some(per_sec);
+        self
+    }
+
     /// Disable signal listening.
     /// Server would only be shutdown from [ServerHandle](crate::server::ServerHandle)
     pub fn disable_signal(mut self) -> Self {
@@ -107,8 +189,106 @@ impl Builder {
         self
     }
 
+    /// Set max length of pending connections queue of Tcp listeners bound after this call.
+    ///
+    /// Default set to 2048.
     pub fn backlog(mut self, num: u32) -> Self {
-        self.backlog = num;
+        self.tcp.backlog = num;
+        self
+    }
+
+    /// Enable SO_REUSEPORT listener sharding for Tcp listeners bound after this call.
+    ///
+    /// Instead of sharing one listener between all workers every worker would bind it's own
+    /// listener to the same address and kernel distributes incoming connections across them.
+    /// Combined with [Builder::worker_core_affinity] connections can be accepted and handled on
+    /// the same core.
+    ///
+    /// *. Sharding only works on unix and is ignored on other platforms.
+    pub fn reuse_port(mut self) -> Self {
+        self.tcp.reuse_port = true;
+        self
+    }
+
+    /// Set TCP_NODELAY for Tcp listeners bound after this call.
+    ///
+    /// Default to operating system's setting.
+    pub fn tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp.nodelay = Some(nodelay);
+        self
+    }
+
+    /// Enable SO_KEEPALIVE for Tcp listeners bound after this call.
+    ///
+    /// `idle` is the time a connection stays idle before the first probe is sent. `interval` is the
+    /// time between probes and `retries` is the number of unanswered probes before connection is
+    /// dropped.
+    ///
+    /// *. `interval` and `retries` are only applied on linux, android, macos, freebsd and netbsd.
+    pub fn tcp_keepalive(mut self, idle: Duration, interval: Duration, retries: u32) -> Self {
+        self.tcp.keepalive = Some(TcpKeepalive {
+            idle,
+            interval,
+            retries,
+        });
+        self
+    }
+
+    /// Set SO_SNDBUF in bytes for Tcp listeners bound after this call.
+    ///
+    /// Default to operating system's setting.
+    pub fn send_buffer_size(mut self, size: usize) -> Self {
+        self.tcp.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Set SO_RCVBUF in bytes for Tcp listeners bound after this call.
+    ///
+    /// Default to operating system's setting.
+    pub fn recv_buffer_size(mut self, size: usize) -> Self {
+        self.tcp.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Set IP_TOS (or IPV6_TCLASS for ipv6 address) for Tcp listeners bound after this call.
+    ///
+    /// *. IPV6_TCLASS is only applied on linux and android.
+    pub fn ip_tos(mut self, tos: u32) -> Self {
+        self.tcp.tos = Some(tos);
+        self
+    }
+
+    /// Set number of submission queue entries of each worker's io_uring instance.
+    ///
+    /// Larger queue allows more in flight io operations before the worker have to submit them to
+    /// kernel. It can reduce syscall overhead at high connection rate. The kernel would round up
+    /// the number to power of two.
+    ///
+    /// Default set to 256.
+    ///
+    /// # Panics:
+    /// When received 0 as number of entries.
+    #[cfg(feature = "io-uring")]
+    pub fn io_uring_entries(mut self, num: u32) -> Self {
+        assert_ne!(num, 0, "io_uring entries must be higher than 0");
+        self.io_uring_entries = num;
+        self
+    }
+
+    /// Enable multishot accept for Tcp and Unix listeners.
+    ///
+    /// When enabled each worker arms one multishot accept operation per listener on a dedicated
+    /// io_uring instance. Kernel keeps accepting connections with the single submission and worker
+    /// collects them from completion queue instead of issuing one accept syscall per connection.
+    /// Requires linux kernel 5.19 or newer.
+    ///
+    /// Connections accepted while connection limits are reached stay in completion queue until
+    /// a permit is available, in the same way they stay in listener's backlog when disabled.
+    ///
+    /// Default set to false.
+    #[cfg(feature = "io-uring")]
+    pub fn io_uring_multishot_accept(mut self, enable: bool) -> Self {
+        self.io_uring_multishot_accept = enable;
         self
     }
 
@@ -131,6 +311,20 @@ impl Builder {
         self
     }
 
+    /// Callback called when graceful shutdown starts and before server stops accepting new
+    /// connections.
+    ///
+    /// Useful for notifying long lived connections like websocket to close themselves so they don't
+    /// hold workers until [Builder::shutdown_timeout] is reached. Can be called multiple times for
+    /// registering multiple callbacks.
+    pub fn on_graceful_shutdown<F>(mut self, on_shutdown: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_graceful_shutdown.push(Box::new(on_shutdown));
+        self
+    }
+
     pub fn listen<N, F, St>(self, name: N, listener: net::TcpListener, service: F) -> Self
     where
         N: AsRef<str>,
@@ -189,12 +383,16 @@ impl Builder {
         F: IntoServiceObj<St>,
         St: TryFrom<Stream> + 'static,
     {
-        let listener = net::TcpListener::bind(addr)?;
-        listener.set_nonblocking(true)?;
+        #[cfg(unix)]
+        if self.tcp.reuse_port {
+            let listener = crate::net::ReusePortListener {
+                addr,
+                config: self.tcp.clone(),
+            };
+            return Ok(self._listen(name, listener, service));
+        }
 
-        let socket = socket2::SockRef::from(&listener);
-        socket.set_reuse_address(true)?;
-        socket.listen(self.backlog as _)?;
+        let listener = self.tcp.bind(addr)?;
 
         Ok(self.listen(name, listener, service))
     }
@@ -256,7 +454,7 @@ impl Builder {
 
         self = self._bind(name.as_ref(), addr, service)?;
 
-        let builder = xitca_io::net::UdpListenerBuilder::new(addr, config).backlog(self.backlog);
+        let builder = xitca_io::net::UdpListenerBuilder::new(addr, config).backlog(self.tcp.backlog);
 
         self.listeners
             .get_mut(name.as_ref())
@@ -284,7 +482,7 @@ impl Builder {
             .next()
             .ok_or_else(|| io::Error::new(io::ErrorKind::AddrNotAvailable, "Can not parse SocketAddr"))?;
 
-        let builder = xitca_io::net::UdpListenerBuilder::new(addr, config).backlog(self.backlog);
+        let builder = xitca_io::net::UdpListenerBuilder::new(addr, config).backlog(self.tcp.backlog);
 
         Ok(self._listen(name, Some(builder), service))
     }
@@ -27,4 +27,74 @@ mod test {
             .listen("test", listener, fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }))
             .build();
     }
+
+    #[test]
+    fn test_builder_socket_options() {
+        use std::time::Duration;
+
+        let mut server = crate::builder::Builder::new()
+            .backlog(128)
+            .tcp_nodelay(true)
+            .tcp_keepalive(Duration::from_secs(60), Duration::from_secs(10), 3)
+            .send_buffer_size(64 * 1024)
+            .recv_buffer_size(64 * 1024)
+            .ip_tos(0x10)
+            .bind(
+                "test",
+                "127.0.0.1:0",
+                fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }),
+            )
+            .unwrap()
+            .build();
+        server.handle().unwrap().stop(false);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_builder_reuse_port() {
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(2)
+            .worker_core_affinity([0])
+            .reuse_port()
+            .bind(
+                "test",
+                "localhost:0",
+                fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }),
+            )
+            .unwrap()
+            .build();
+        server.handle().unwrap().stop(false);
+    }
+
+    #[cfg(feature = "io-uring")]
+    #[test]
+    fn test_builder_multishot_accept() {
+        use std::{sync::mpsc, time::Duration};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (tx, rx) = mpsc::sync_channel(1);
+
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(1)
+            .io_uring_multishot_accept(true)
+            .listen(
+                "test",
+                listener,
+                fn_service(move |_: TcpStream| {
+                    let tx = tx.clone();
+                    async move {
+                        tx.send(()).unwrap();
+                        Ok::<_, ()>(())
+                    }
+                }),
+            )
+            .build();
+
+        let _client = std::net::TcpStream::connect(addr).unwrap();
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        server.handle().unwrap().stop(false);
+    }
 }
@@ -1,4 +1,4 @@
-use std::{io, net};
+use std::{io, net, time::Duration};
 
 #[cfg(feature = "http3")]
 use xitca_io::net::UdpListenerBuilder;
@@ -13,6 +13,12 @@ use tracing::info;
 /// Otherwise it could panic.
 pub(crate) trait AsListener: Send {
     fn as_listener(&mut self) -> io::Result<Listener>;
+
+    /// when returning true [AsListener::as_listener] is called once for every worker and each worker
+    /// accept from it's own listener.
+    fn is_sharded(&self) -> bool {
+        false
+    }
 }
 
 impl AsListener for Option<net::TcpListener> {
@@ -28,6 +34,131 @@ impl AsListener for Option<net::TcpListener> {
     }
 }
 
+/// Socket options applied to Tcp listeners bound by [Builder](crate::Builder). Accepted connections
+/// inherit options from the listener.
+#[derive(Clone)]
+pub(crate) struct TcpConfig {
+    pub(crate) backlog: u32,
+    pub(crate) reuse_port: bool,
+    pub(crate) nodelay: Option<bool>,
+    pub(crate) keepalive: Option<TcpKeepalive>,
+    pub(crate) send_buffer_size: Option<usize>,
+    pub(crate) recv_buffer_size: Option<usize>,
+    pub(crate) tos: Option<u32>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct TcpKeepalive {
+    pub(crate) idle: Duration,
+    pub(crate) interval: Duration,
+    pub(crate) retries: u32,
+}
+
+impl TcpConfig {
+    pub(crate) const fn new() -> Self {
+        Self {
+            backlog: 2048,
+            reuse_port: false,
+            nodelay: None,
+            keepalive: None,
+            send_buffer_size: None,
+            recv_buffer_size: None,
+            tos: None,
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn bind(&self, addr: net::SocketAddr) -> io::Result<net::TcpListener> {
+        use socket2::{Domain, Socket, Type};
+
+        let socket = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+        socket.set_reuse_address(true)?;
+
+        #[cfg(unix)]
+        if self.reuse_port {
+            socket.set_reuse_port(true)?;
+        }
+
+        if let Some(nodelay) = self.nodelay {
+            socket.set_nodelay(nodelay)?;
+        }
+
+        if let Some(keepalive) = self.keepalive {
+            let params = socket2::TcpKeepalive::new().with_time(keepalive.idle);
+            #[cfg(any(
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "linux",
+                target_os = "macos",
+                target_os = "netbsd"
+            ))]
+            let params = params.with_interval(keepalive.interval).with_retries(keepalive.retries);
+            socket.set_tcp_keepalive(&params)?;
+        }
+
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+
+        // receive buffer must be set before listen so tcp window scale can be negotiated with it.
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+
+        if let Some(tos) = self.tos {
+            match addr {
+                #[cfg(not(any(
+                    target_os = "fuchsia",
+                    target_os = "redox",
+                    target_os = "solaris",
+                    target_os = "illumos",
+                    target_os = "haiku"
+                )))]
+                net::SocketAddr::V4(_) => socket.set_tos(tos)?,
+                #[cfg(any(target_os = "android", target_os = "linux"))]
+                net::SocketAddr::V6(_) => socket.set_tclass_v6(tos)?,
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
+
+        socket.set_nonblocking(true)?;
+        socket.bind(&addr.into())?;
+        socket.listen(self.backlog as _)?;
+
+        Ok(socket.into())
+    }
+}
+
+/// Tcp listener bind to the same address with SO_REUSEPORT for every worker. kernel would
+/// distribute incoming connections across them.
+#[cfg(all(unix, not(target_family = "wasm")))]
+pub(crate) struct ReusePortListener {
+    pub(crate) addr: net::SocketAddr,
+    pub(crate) config: TcpConfig,
+}
+
+#[cfg(all(unix, not(target_family = "wasm")))]
+impl AsListener for ReusePortListener {
+    fn as_listener(&mut self) -> io::Result<Listener> {
+        let tcp = TcpListener::from_std(self.config.bind(self.addr)?)?;
+
+        // following shards must bind to the same port when binding to port 0.
+        self.addr = tcp.local_addr()?;
+
+        info!(
+            "Started Tcp listening with SO_REUSEPORT on: {:?}",
+            tcp.local_addr().ok()
+        );
+
+        Ok(Listener::Tcp(tcp))
+    }
+
+    fn is_sharded(&self) -> bool {
+        true
+    }
+}
+
 #[cfg(unix)]
 impl AsListener for Option<std::os::unix::net::UnixListener> {
     fn as_listener(&mut self) -> io::Result<Listener> {
@@ -17,13 +17,17 @@ use std::{
 
 use tokio::{
     runtime::Runtime,
-    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    sync::{
+        mpsc::{UnboundedReceiver, UnboundedSender},
+        Semaphore,
+    },
 };
 
 use crate::{builder::Builder, worker};
 
 pub struct Server {
     is_graceful_shutdown: Arc<AtomicBool>,
+    on_graceful_shutdown: Vec<Box<dyn Fn() + Send + Sync>>,
     tx_cmd: UnboundedSender<Command>,
     rx_cmd: UnboundedReceiver<Command>,
     rt: Option<Runtime>,
@@ -34,6 +38,9 @@ impl Server {
     #[cfg(target_family = "wasm")]
     pub fn new(builder: Builder) -> io::Result<Self> {
         let Builder {
+            max_connections,
+            max_connections_per_worker,
+            max_accept_rate,
             listeners,
             factories,
             shutdown_timeout,
@@ -59,6 +66,9 @@ impl Server {
 
         let is_graceful_shutdown = Arc::new(AtomicBool::new(false));
 
+        let limit = accept_limit(max_connections, max_accept_rate);
+        let limit = worker::AcceptLimit::new(limit.0, max_connections_per_worker, limit.1);
+
         let on_start_fut = on_worker_start();
 
         let fut = async {
@@ -69,7 +79,7 @@ impl Server {
 
             for (name, factory) in factories.iter() {
                 let (h, s) = factory
-                    .call((name, &listeners))
+                    .call((name, &listeners, &limit))
                     .await
                     .map_err(|_| io::Error::from(io::ErrorKind::Other))?;
                 handles.extend(h);
@@ -92,10 +102,19 @@ impl Server {
             server_threads,
             worker_threads,
             worker_max_blocking_threads,
+            worker_core_affinity,
+            max_connections,
+            max_connections_per_worker,
+            max_accept_rate,
             listeners,
             factories,
             shutdown_timeout,
             on_worker_start,
+            on_graceful_shutdown,
+            #[cfg(feature = "io-uring")]
+            io_uring_entries,
+            #[cfg(feature = "io-uring")]
+            io_uring_multishot_accept,
             ..
         } = builder;
 
@@ -106,22 +125,33 @@ impl Server {
             .worker_threads(server_threads)
             .build()?;
 
+        // listeners for every worker. shared listener is cloned into all of them and sharded
+        // listener is constructed once per worker.
         let fut = async {
-            listeners
-                .into_iter()
-                .flat_map(|(name, listeners)| {
-                    listeners.into_iter().map(move |mut l| {
-                        let l = l.as_listener()?;
-                        Ok((name.to_owned(), Arc::new(l)))
-                    })
-                })
-                .collect::<Result<Vec<_>, io::Error>>()
+            let mut workers = vec![Vec::new(); worker_threads];
+            for (name, listeners) in listeners {
+                for mut l in listeners {
+                    if l.is_sharded() {
+                        for worker in workers.iter_mut() {
+                            worker.push((name.clone(), Arc::new(l.as_listener()?)));
+                        }
+                    } else {
+                        let l = Arc::new(l.as_listener()?);
+                        for worker in workers.iter_mut() {
+                            worker.push((name.clone(), l.clone()));
+                        }
+                    }
+                }
+            }
+            Ok::<_, io::Error>(workers)
         };
 
         // use a spawned thread to work around possible nest runtime issue.
         // *. Server::new is most likely already inside a tokio runtime.
         let listeners = thread::scope(|s| s.spawn(|| rt.block_on(fut)).join()).unwrap()?;
 
+        let (global_limit, accept_rate) = accept_limit(max_connections, max_accept_rate);
+
         let is_graceful_shutdown = Arc::new(AtomicBool::new(false));
         let is_graceful_shutdown2 = is_graceful_shutdown.clone();
 
@@ -136,14 +166,31 @@ impl Server {
                     for idx in 0..worker_threads {
                         let thread = thread::Builder::new().name(format!("xitca-server-worker-{idx}"));
 
-                        let task = || async {
+                        let listeners = &listeners[idx];
+                        let core = (!worker_core_affinity.is_empty())
+                            .then(|| worker_core_affinity[idx % worker_core_affinity.len()]);
+                        let limit = worker::AcceptLimit::new(
+                            global_limit.clone(),
+                            max_connections_per_worker,
+                            accept_rate.clone(),
+                        );
+                        #[cfg(feature = "io-uring")]
+                        let limit = limit.multishot(io_uring_multishot_accept);
+                        let (factories, on_worker_start, is_graceful_shutdown) =
+                            (&factories, &on_worker_start, &is_graceful_shutdown);
+
+                        let task = move || async move {
+                            if let Some(core) = core {
+                                pin_to_core(core);
+                            }
+
                             on_worker_start().await;
 
                             let mut handles = Vec::new();
                             let mut services = Vec::new();
 
                             for (name, factory) in factories.iter() {
-                                match factory.call((name, &listeners)).await {
+                                match factory.call((name, listeners, &limit)).await {
                                     Ok((h, s)) => {
                                         handles.extend(h);
                                         services.push(s);
@@ -152,7 +199,7 @@ impl Server {
                                 }
                             }
 
-                            worker::wait_for_stop(handles, services, shutdown_timeout, &is_graceful_shutdown).await;
+                            worker::wait_for_stop(handles, services, shutdown_timeout, is_graceful_shutdown).await;
                         };
 
                         #[cfg(not(feature = "io-uring"))]
@@ -171,7 +218,7 @@ impl Server {
                         {
                             thread.spawn_scoped(scope, move || {
                                 let _ = worker_max_blocking_threads;
-                                tokio_uring::start(task())
+                                tokio_uring::builder().entries(io_uring_entries).start(task())
                             })?;
                         }
                     }
@@ -184,6 +231,7 @@ impl Server {
 
         Ok(Self {
             is_graceful_shutdown,
+            on_graceful_shutdown,
             tx_cmd,
             rx_cmd,
             rt: Some(rt),
@@ -193,6 +241,9 @@ impl Server {
 
     pub(crate) fn stop(&mut self, graceful: bool) {
         if let Some(rt) = self.rt.take() {
+            if graceful {
+                self.on_graceful_shutdown.iter().for_each(|func| func());
+            }
             self.is_graceful_shutdown.store(graceful, Ordering::SeqCst);
             rt.shutdown_background();
             mem::take(&mut self.worker_join_handles).into_iter().for_each(|handle| {
@@ -202,7 +253,29 @@ impl Server {
     }
 }
 
+// construct limits shared by all workers.
+fn accept_limit(
+    max_connections: Option<usize>,
+    max_accept_rate: Option<u32>,
+) -> (Option<Arc<Semaphore>>, Option<Arc<worker::AcceptRate>>) {
+    (
+        max_connections.map(|max| Arc::new(Semaphore::new(max))),
+        max_accept_rate.map(|rate| Arc::new(worker::AcceptRate::new(rate))),
+    )
+}
+
 enum Command {
     GracefulStop,
     ForceStop,
 }
+
+#[cfg(not(target_family = "wasm"))]
+fn pin_to_core(core: usize) {
+    #[cfg(target_os = "linux")]
+    if let Err(e) = xitca_unsafe_collection::affinity::set_for_current(core) {
+        tracing::error!("failed to pin worker to cpu core {core}: {e}");
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = core;
+}
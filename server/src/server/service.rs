@@ -4,11 +4,11 @@ use tokio::task::JoinHandle;
 use xitca_io::net::{Listener, Stream};
 use xitca_service::{ready::ReadyService, Service};
 
-use crate::worker::{self, ServiceAny};
+use crate::worker::{self, AcceptLimit, ServiceAny};
 
 pub type ServiceObj = Box<
     dyn for<'a> xitca_service::object::ServiceObject<
-            (&'a str, &'a [(String, Arc<Listener>)]),
+            (&'a str, &'a [(String, Arc<Listener>)], &'a AcceptLimit),
             Response = (Vec<JoinHandle<()>>, ServiceAny),
             Error = (),
         > + Send
@@ -20,7 +20,7 @@ struct Container<F, Req> {
     _t: PhantomData<fn(Req)>,
 }
 
-impl<'a, F, Req> Service<(&'a str, &'a [(String, Arc<Listener>)])> for Container<F, Req>
+impl<'a, F, Req> Service<(&'a str, &'a [(String, Arc<Listener>)], &'a AcceptLimit)> for Container<F, Req>
 where
     F: IntoServiceObj<Req>,
     Req: TryFrom<Stream> + 'static,
@@ -30,7 +30,7 @@ where
 
     async fn call(
         &self,
-        (name, listeners): (&'a str, &'a [(String, Arc<Listener>)]),
+        (name, listeners, limit): (&'a str, &'a [(String, Arc<Listener>)], &'a AcceptLimit),
     ) -> Result<Self::Response, Self::Error> {
         let service = self.inner.call(()).await.map_err(|_| ())?;
         let service = Rc::new(service);
@@ -38,7 +38,7 @@ where
         let handles = listeners
             .iter()
             .filter(|(n, _)| n == name)
-            .map(|(_, listener)| worker::start(listener, &service))
+            .map(|(_, listener)| worker::start(listener, &service, limit))
             .collect::<Vec<_>>();
 
         Ok((handles, service as _))
@@ -0,0 +1,133 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::sleep_until,
+};
+
+/// limits and options on accepting connections shared by all listeners of one worker.
+#[derive(Clone, Default)]
+pub struct AcceptLimit {
+    global: Option<Arc<Semaphore>>,
+    local: Option<Arc<Semaphore>>,
+    rate: Option<Arc<AcceptRate>>,
+    #[cfg(feature = "io-uring")]
+    pub(super) multishot: bool,
+}
+
+impl AcceptLimit {
+    /// construct limit for one worker. `global` and `rate` are shared across all workers.
+    pub(crate) fn new(global: Option<Arc<Semaphore>>, local: Option<usize>, rate: Option<Arc<AcceptRate>>) -> Self {
+        Self {
+            global,
+            local: local.map(|max| Arc::new(Semaphore::new(max))),
+            rate,
+            #[cfg(feature = "io-uring")]
+            multishot: false,
+        }
+    }
+
+    /// accept connections with io_uring multishot accept. see [Builder::io_uring_multishot_accept].
+    ///
+    /// [Builder::io_uring_multishot_accept]: crate::Builder::io_uring_multishot_accept
+    #[cfg(feature = "io-uring")]
+    pub(crate) fn multishot(mut self, multishot: bool) -> Self {
+        self.multishot = multishot;
+        self
+    }
+
+    /// wait until accepting a new connection is allowed. returned permit must be held for the
+    /// lifetime of accepted connection.
+    pub(crate) async fn acquire(&self) -> AcceptPermit {
+        // local limit is acquired first so a busy worker does not hold global permit while waiting.
+        let local = match self.local {
+            Some(ref sem) => Some(sem.clone().acquire_owned().await.expect("semaphore must not be closed")),
+            None => None,
+        };
+
+        let global = match self.global {
+            Some(ref sem) => Some(sem.clone().acquire_owned().await.expect("semaphore must not be closed")),
+            None => None,
+        };
+
+        if let Some(ref rate) = self.rate {
+            rate.wait().await;
+        }
+
+        AcceptPermit {
+            _local: local,
+            _global: global,
+        }
+    }
+}
+
+pub(crate) struct AcceptPermit {
+    _local: Option<OwnedSemaphorePermit>,
+    _global: Option<OwnedSemaphorePermit>,
+}
+
+/// evenly paced accept rate shared by all workers.
+pub(crate) struct AcceptRate {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl AcceptRate {
+    /// # Panics:
+    /// When pass 0 as connections per second.
+    pub(crate) fn new(per_sec: u32) -> Self {
+        assert_ne!(per_sec, 0, "accept rate must be higher than 0");
+        Self {
+            interval: Duration::from_secs(1) / per_sec,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    // reserve the next time slot and wait for it.
+    async fn wait(&self) {
+        let slot = {
+            let mut next = self.next.lock().unwrap();
+            let slot = Instant::now().max(*next);
+            *next = slot + self.interval;
+            slot
+        };
+        sleep_until(slot.into()).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn limit() {
+        let limit = AcceptLimit::new(Some(Arc::new(Semaphore::new(2))), Some(1), None);
+        let limit2 = AcceptLimit::new(limit.global.clone(), Some(1), None);
+
+        let permit = limit.acquire().await;
+        let _permit2 = limit2.acquire().await;
+
+        // global limit reached.
+        assert!(limit.global.as_ref().unwrap().available_permits() == 0);
+        assert!(tokio::time::timeout(Duration::from_millis(10), limit.acquire())
+            .await
+            .is_err());
+
+        drop(permit);
+        let _permit = limit.acquire().await;
+    }
+
+    #[tokio::test]
+    async fn rate() {
+        let limit = AcceptLimit::new(None, None, Some(Arc::new(AcceptRate::new(100))));
+
+        let now = Instant::now();
+        for _ in 0..5 {
+            let _ = limit.acquire().await;
+        }
+        assert!(now.elapsed() >= Duration::from_millis(40));
+    }
+}
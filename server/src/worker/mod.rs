@@ -1,5 +1,9 @@
+mod limit;
 mod shutdown;
 
+#[cfg(feature = "io-uring")]
+mod uring;
+
 use core::{any::Any, sync::atomic::AtomicBool, time::Duration};
 
 use std::{io, rc::Rc, sync::Arc, thread};
@@ -11,10 +15,12 @@ use xitca_service::{ready::ReadyService, Service};
 
 use self::shutdown::ShutdownHandle;
 
+pub(crate) use self::limit::{AcceptLimit, AcceptRate};
+
 // erase Rc<S: ReadyService<_>> type and only use it for counting the reference counter of Rc.
 pub(crate) type ServiceAny = Rc<dyn Any>;
 
-pub(crate) fn start<S, Req>(listener: &Arc<Listener>, service: &Rc<S>) -> JoinHandle<()>
+pub(crate) fn start<S, Req>(listener: &Arc<Listener>, service: &Rc<S>, limit: &AcceptLimit) -> JoinHandle<()>
 where
     S: ReadyService + Service<Req> + 'static,
     S::Ready: 'static,
@@ -22,18 +28,26 @@ where
 {
     let listener = listener.clone();
     let service = service.clone();
+    let limit = limit.clone();
 
     tokio::task::spawn_local(async move {
+        let mut accept = Accept::new(listener, &limit);
+
         loop {
             let ready = service.ready().await;
 
-            match listener.accept().await {
+            // stop accepting when limit is reached. pending connections would be queued in listener's
+            // backlog and push back to clients when the backlog is full.
+            let permit = limit.acquire().await;
+
+            match accept.accept().await {
                 Ok(stream) => {
                     if let Ok(req) = TryFrom::try_from(stream) {
                         let service = service.clone();
                         tokio::task::spawn_local(async move {
                             let _ = service.call(req).await;
                             drop(ready);
+                            drop(permit);
                         });
                     }
                 }
@@ -50,6 +64,43 @@ where
     })
 }
 
+// accept connections from listener directly or through io_uring multishot accept.
+struct Accept {
+    listener: Arc<Listener>,
+    #[cfg(feature = "io-uring")]
+    multishot: Option<uring::MultishotAccept>,
+}
+
+impl Accept {
+    fn new(listener: Arc<Listener>, _limit: &AcceptLimit) -> Self {
+        #[cfg(feature = "io-uring")]
+        let multishot = _limit
+            .multishot
+            .then(|| {
+                uring::MultishotAccept::new(&listener).unwrap_or_else(|e| {
+                    error!("Error setting up multishot accept. Fallback to regular accept: {e}");
+                    None
+                })
+            })
+            .flatten();
+
+        Self {
+            listener,
+            #[cfg(feature = "io-uring")]
+            multishot,
+        }
+    }
+
+    async fn accept(&mut self) -> io::Result<Stream> {
+        #[cfg(feature = "io-uring")]
+        if let Some(ref mut multishot) = self.multishot {
+            return multishot.accept().await;
+        }
+
+        self.listener.accept().await
+    }
+}
+
 pub(crate) async fn wait_for_stop(
     handles: Vec<JoinHandle<()>>,
     services: Vec<ServiceAny>,
@@ -0,0 +1,131 @@
+use std::{
+    collections::VecDeque,
+    io, net,
+    os::{fd::OwnedFd, unix},
+};
+
+use tokio::io::unix::AsyncFd;
+use xitca_io::net::{Listener, Stream};
+use xitca_unsafe_collection::io_uring;
+
+// completion queue is sized to hold accepted connections between two polls of the ring.
+const ENTRIES: u32 = 256;
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Tcp,
+    Unix,
+}
+
+/// multishot accept driven by worker's async runtime. readiness of io_uring instance is watched
+/// by runtime's reactor and accepted connections are collected when it's readable.
+pub(super) struct MultishotAccept {
+    ring: AsyncFd<io_uring::MultishotAccept>,
+    kind: Kind,
+    accepted: VecDeque<io::Result<OwnedFd>>,
+}
+
+impl MultishotAccept {
+    /// construct multishot accept for given listener. return None when listener type is not
+    /// supported.
+    pub(super) fn new(listener: &Listener) -> io::Result<Option<Self>> {
+        let (mut ring, kind) = match *listener {
+            Listener::Tcp(ref tcp) => (io_uring::MultishotAccept::new(tcp, ENTRIES)?, Kind::Tcp),
+            #[cfg(unix)]
+            Listener::Unix(ref unix) => (io_uring::MultishotAccept::new(unix, ENTRIES)?, Kind::Unix),
+            #[allow(unreachable_patterns)]
+            _ => return Ok(None),
+        };
+
+        ring.arm()?;
+
+        Ok(Some(Self {
+            ring: AsyncFd::new(ring)?,
+            kind,
+            accepted: VecDeque::new(),
+        }))
+    }
+
+    pub(super) async fn accept(&mut self) -> io::Result<Stream> {
+        loop {
+            if let Some(res) = self.accepted.pop_front() {
+                match res.and_then(|fd| self.stream(fd)) {
+                    Ok(Some(stream)) => return Ok(stream),
+                    // connection is closed before it's address can be queried.
+                    Ok(None) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            let mut guard = self.ring.readable_mut().await?;
+
+            let accepted = &mut self.accepted;
+            if guard.get_inner_mut().complete(|res| accepted.push_back(res)) == 0 {
+                guard.clear_ready();
+            }
+
+            guard.get_inner_mut().arm()?;
+        }
+    }
+
+    fn stream(&self, fd: OwnedFd) -> io::Result<Option<Stream>> {
+        let res = match self.kind {
+            Kind::Tcp => {
+                let stream = net::TcpStream::from(fd);
+                stream.peer_addr().map(|addr| Stream::Tcp(stream, addr))
+            }
+            Kind::Unix => {
+                let stream = unix::net::UnixStream::from(fd);
+                stream.peer_addr().map(|addr| Stream::Unix(stream, addr))
+            }
+        };
+
+        match res {
+            Ok(stream) => Ok(Some(stream)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotConnected => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+
+    use xitca_io::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn accept() {
+        tokio_uring::start(async {
+            let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.set_nonblocking(true).unwrap();
+            let addr = listener.local_addr().unwrap();
+            let listener = Listener::Tcp(TcpListener::from_std(listener).unwrap());
+
+            let mut accept = MultishotAccept::new(&listener).unwrap().unwrap();
+
+            let mut clients = (0..3)
+                .map(|_| net::TcpStream::connect(addr).unwrap())
+                .collect::<Vec<_>>();
+
+            for _ in 0..clients.len() {
+                let Stream::Tcp(mut stream, peer) = accept.accept().await.unwrap() else {
+                    panic!("unexpected stream type")
+                };
+                let client = clients.iter_mut().find(|c| c.local_addr().unwrap() == peer).unwrap();
+
+                stream.set_nonblocking(false).unwrap();
+                stream.write_all(b"996").unwrap();
+                let mut buf = [0; 3];
+                client.read_exact(&mut buf).unwrap();
+                assert_eq!(&buf, b"996");
+            }
+
+            // connection accepted after all pending ones are collected wakes up accept again.
+            let _client = net::TcpStream::connect(addr).unwrap();
+            assert!(accept.accept().await.is_ok());
+        })
+    }
+}
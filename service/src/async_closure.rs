@@ -7,6 +7,10 @@ use core::future::Future;
 /// It is necessary in the the HRTB bounds for async fn's with reference parameters because it
 /// allows the output future to be bound to the parameter lifetime.
 ///     `F: for<'a> AsyncClosure<(&'a u8,) Output=u8>`
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not an async function that can receive `{Arg}` as arguments",
+    note = "async function with up to 16 arguments is supported"
+)]
 pub trait AsyncClosure<Arg> {
     type Output;
     type Future: Future<Output = Self::Output>;
@@ -42,3 +46,10 @@ async_closure_impl! { A, B, C, D, E, F }
 async_closure_impl! { A, B, C, D, E, F, G }
 async_closure_impl! { A, B, C, D, E, F, G, H }
 async_closure_impl! { A, B, C, D, E, F, G, H, I }
+async_closure_impl! { A, B, C, D, E, F, G, H, I, J }
+async_closure_impl! { A, B, C, D, E, F, G, H, I, J, K }
+async_closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L }
+async_closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M }
+async_closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, N }
+async_closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, N, O }
+async_closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P }
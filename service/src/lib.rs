@@ -56,6 +56,9 @@ extern crate alloc;
 #[cfg(feature = "alloc")]
 pub mod object;
 
+#[cfg(feature = "alloc")]
+pub use self::service::{BuildError, BuildLayer};
+
 #[cfg(feature = "alloc")]
 /// boxed [core::future::Future] trait object with no extra auto trait bound(`!Send` and `!Sync`).
 pub type BoxFuture<'a, Res, Err> =
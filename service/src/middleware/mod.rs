@@ -2,6 +2,8 @@
 //!
 //! [ServiceExt::enclosed]: crate::service::ServiceExt::enclosed
 
+mod ready_gate;
 mod unchecked_ready;
 
+pub use ready_gate::{ReadyGate, ReadyGateError, ReadyGateService};
 pub use unchecked_ready::UncheckedReady;
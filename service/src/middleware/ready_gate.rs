@@ -0,0 +1,193 @@
+use core::{
+    convert::Infallible,
+    fmt,
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
+
+use crate::{ready::ReadyService, service::Service};
+
+/// A middleware awaiting [ReadyService::ready] of inner service before every [Service::call].
+///
+/// Readiness is awaited together with a timer future produced by the given closure. When the
+/// timer resolves first the request is rejected with [ReadyGateError::Overloaded] without
+/// calling inner service. Otherwise the output of [ReadyService::ready] is held until the call
+/// finishes so permit like readiness is released after the request is handled.
+///
+/// The timer closure keeps the middleware runtime agnostic. e.g. `|| tokio::time::sleep(dur)`.
+///
+/// # Examples
+/// ```rust
+/// # use core::{convert::Infallible, future::pending};
+/// # use xitca_service::{fn_service, middleware::{ReadyGate, ReadyGateError}, Service, ServiceExt};
+/// # async fn gate() {
+/// let service = fn_service(|req: &'static str| async move { Ok::<_, Infallible>(req) })
+///     // timer that never fires waits for readiness indefinitely.
+///     .enclosed(ReadyGate::new(pending::<()>))
+///     .call(())
+///     .await
+///     .unwrap();
+///
+/// let res = service.call("996").await;
+/// assert!(matches!(res, Ok("996")));
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct ReadyGate<F> {
+    timer: F,
+}
+
+impl<F> ReadyGate<F> {
+    /// Construct a new gate with closure producing timer future for every request.
+    pub const fn new(timer: F) -> Self {
+        Self { timer }
+    }
+}
+
+impl<F, S> Service<S> for ReadyGate<F>
+where
+    F: Clone,
+{
+    type Response = ReadyGateService<S, F>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(ReadyGateService {
+            service,
+            timer: self.timer.clone(),
+        })
+    }
+}
+
+pub struct ReadyGateService<S, F> {
+    service: S,
+    timer: F,
+}
+
+impl<S, F, Fut, Req> Service<Req> for ReadyGateService<S, F>
+where
+    S: Service<Req> + ReadyService,
+    F: Fn() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Response = S::Response;
+    type Error = ReadyGateError<S::Error>;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let mut ready = pin!(self.service.ready());
+        let mut timer = pin!((self.timer)());
+
+        let _ready = poll_fn(|cx| {
+            if let Poll::Ready(ready) = ready.as_mut().poll(cx) {
+                return Poll::Ready(Ok(ready));
+            }
+            timer.as_mut().poll(cx).map(|_| Err(ReadyGateError::Overloaded))
+        })
+        .await?;
+
+        self.service.call(req).await.map_err(ReadyGateError::Service)
+    }
+}
+
+/// Gating happens inside [Service::call] so the service always appears ready to outer stack.
+impl<S, F> ReadyService for ReadyGateService<S, F> {
+    type Ready = ();
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {}
+}
+
+/// Error type of [ReadyGateService].
+pub enum ReadyGateError<E> {
+    /// Inner service did not become ready before timer resolved.
+    Overloaded,
+    /// Error produced by inner service.
+    Service(E),
+}
+
+impl<E> fmt::Debug for ReadyGateError<E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Overloaded => f.write_str("Overloaded"),
+            Self::Service(ref e) => fmt::Debug::fmt(e, f),
+        }
+    }
+}
+
+impl<E> fmt::Display for ReadyGateError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Overloaded => f.write_str("service is overloaded and not ready in time"),
+            Self::Service(ref e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> std::error::Error for ReadyGateError<E>
+where
+    E: std::error::Error,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Self::Overloaded => None,
+            Self::Service(ref e) => std::error::Error::source(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{cell::Cell, future::pending};
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::ServiceExt;
+
+    use super::*;
+
+    struct Flaky(Cell<bool>);
+
+    impl Service<u8> for Flaky {
+        type Response = u8;
+        type Error = Infallible;
+
+        async fn call(&self, req: u8) -> Result<Self::Response, Self::Error> {
+            Ok(req)
+        }
+    }
+
+    impl ReadyService for Flaky {
+        type Ready = ();
+
+        async fn ready(&self) -> Self::Ready {
+            if !self.0.get() {
+                pending::<()>().await
+            }
+        }
+    }
+
+    #[test]
+    fn gate() {
+        let service = crate::fn_build(|_| async { Ok::<_, Infallible>(Flaky(Cell::new(true))) })
+            .enclosed(ReadyGate::new(|| async {}))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        assert!(matches!(service.call(1).now_or_panic(), Ok(1)));
+
+        service.service.0.set(false);
+        assert!(matches!(
+            service.call(1).now_or_panic(),
+            Err(ReadyGateError::Overloaded)
+        ));
+    }
+}
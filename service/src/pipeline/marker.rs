@@ -6,6 +6,14 @@ pub struct BuildMapErr;
 pub struct MapErr;
 pub struct BuildAndThen;
 pub struct AndThen;
+pub struct BuildRace;
+pub struct Race;
+pub struct BuildFanout;
+pub struct Fanout;
 pub struct BuildEnclosed;
 pub struct BuildEnclosedFn;
 pub struct EnclosedFn;
+#[cfg(feature = "alloc")]
+pub struct BuildBoxed;
+#[cfg(feature = "alloc")]
+pub struct BuildTry;
@@ -2,9 +2,11 @@
 
 mod and_then;
 mod enclosed_fn;
+mod fanout;
 mod function;
 mod map;
 mod map_err;
+mod race;
 
 use core::{future::Future, ops::Deref, pin::Pin};
 
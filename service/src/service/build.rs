@@ -0,0 +1,266 @@
+use core::{
+    any::type_name,
+    fmt::{self, Debug, Display, Formatter},
+    future::Future,
+};
+
+use alloc::{format, string::String};
+
+use crate::pipeline::{
+    marker::{BuildBoxed, BuildEnclosed, BuildEnclosedFn, BuildMap, BuildMapErr, BuildTry},
+    PipelineE, PipelineT,
+};
+
+use super::{FnService, Service};
+
+/// Type erased error of building a service. Carries type name and position of the layer failed
+/// to build.
+///
+/// Layers are indexed from the innermost service starting from 0. Every combinator of
+/// [ServiceExt](crate::ServiceExt) counts as one layer.
+pub struct BuildError {
+    layer: &'static str,
+    index: usize,
+    message: String,
+}
+
+impl BuildError {
+    fn new<L>(index: usize, e: impl Debug) -> Self {
+        Self {
+            layer: type_name::<L>(),
+            index,
+            message: format!("{e:?}"),
+        }
+    }
+
+    /// Type name of the layer failed to build.
+    pub fn layer(&self) -> &'static str {
+        self.layer
+    }
+
+    /// Index of the layer failed to build.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Debug formatted error of the layer failed to build.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Debug for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuildError")
+            .field("layer", &self.layer)
+            .field("index", &self.index)
+            .field("message", &self.message)
+            .finish()
+    }
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "layer {} ({}) failed to build: {}",
+            self.index, self.layer, self.message
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
+
+/// Trait for service factory that can erase it's build error into [BuildError] with context of
+/// the failing layer. Used by [ServiceExt::try_build](crate::ServiceExt::try_build).
+///
+/// Implemented for [fn_service](crate::fn_service), factories produced by combinators of
+/// [ServiceExt](crate::ServiceExt) and any factory wrapped by
+/// [ServiceExt::boxed_build](crate::ServiceExt::boxed_build).
+pub trait BuildLayer<Arg>: Service<Arg> {
+    /// Count of layers of service factory.
+    const LAYERS: usize;
+
+    /// Erase build error.
+    fn build_error(e: Self::Error) -> BuildError;
+}
+
+impl<F, Arg, Fut, Res, Err> BuildLayer<Arg> for FnService<F>
+where
+    F: Fn(Arg) -> Fut,
+    Fut: Future<Output = Result<Res, Err>>,
+    Err: Debug,
+{
+    const LAYERS: usize = 1;
+
+    fn build_error(e: Self::Error) -> BuildError {
+        BuildError::new::<Self>(0, e)
+    }
+}
+
+impl<F, Arg, T> BuildLayer<Arg> for PipelineT<F, T, BuildEnclosed>
+where
+    F: BuildLayer<Arg>,
+    T: Service<F::Response>,
+    T::Error: Debug,
+{
+    const LAYERS: usize = F::LAYERS + 1;
+
+    fn build_error(e: Self::Error) -> BuildError {
+        match e {
+            PipelineE::First(e) => F::build_error(e),
+            PipelineE::Second(e) => BuildError::new::<T>(F::LAYERS, e),
+        }
+    }
+}
+
+macro_rules! passthrough_impl {
+    ($marker: ty) => {
+        impl<F, Arg, T> BuildLayer<Arg> for PipelineT<F, T, $marker>
+        where
+            F: BuildLayer<Arg>,
+            T: Clone,
+        {
+            const LAYERS: usize = F::LAYERS + 1;
+
+            fn build_error(e: Self::Error) -> BuildError {
+                F::build_error(e)
+            }
+        }
+    };
+}
+
+passthrough_impl!(BuildEnclosedFn);
+passthrough_impl!(BuildMap);
+passthrough_impl!(BuildMapErr);
+
+impl<F, Arg> Service<Arg> for PipelineT<F, (), BuildBoxed>
+where
+    F: Service<Arg>,
+    F::Error: Debug,
+{
+    type Response = F::Response;
+    type Error = BuildError;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        self.first.call(arg).await.map_err(|e| BuildError::new::<F>(0, e))
+    }
+}
+
+impl<F, Arg> BuildLayer<Arg> for PipelineT<F, (), BuildBoxed>
+where
+    F: Service<Arg>,
+    F::Error: Debug,
+{
+    const LAYERS: usize = 1;
+
+    fn build_error(e: Self::Error) -> BuildError {
+        e
+    }
+}
+
+impl<F, Arg> Service<Arg> for PipelineT<F, (), BuildTry>
+where
+    F: BuildLayer<Arg>,
+{
+    type Response = F::Response;
+    type Error = BuildError;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        self.first.call(arg).await.map_err(F::build_error)
+    }
+}
+
+impl<F, Arg> BuildLayer<Arg> for PipelineT<F, (), BuildTry>
+where
+    F: BuildLayer<Arg>,
+{
+    const LAYERS: usize = F::LAYERS;
+
+    fn build_error(e: Self::Error) -> BuildError {
+        e
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::convert::Infallible;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{fn_service, ServiceExt};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct FailError;
+
+    struct Fail;
+
+    impl<S> Service<S> for Fail {
+        type Response = S;
+        type Error = FailError;
+
+        async fn call(&self, _: S) -> Result<Self::Response, Self::Error> {
+            Err(FailError)
+        }
+    }
+
+    struct Pass;
+
+    impl<S> Service<S> for Pass {
+        type Response = S;
+        type Error = Infallible;
+
+        async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+            Ok(service)
+        }
+    }
+
+    async fn index(req: &'static str) -> Result<&'static str, Infallible> {
+        Ok(req)
+    }
+
+    #[test]
+    fn try_build() {
+        let err = fn_service(index)
+            .enclosed(Pass)
+            .map(|res: &'static str| res)
+            .enclosed(Fail)
+            .enclosed(Pass)
+            .try_build()
+            .call(())
+            .now_or_panic()
+            .err()
+            .unwrap();
+
+        assert_eq!(err.index(), 3);
+        assert_eq!(err.layer(), type_name::<Fail>());
+        assert_eq!(err.message(), "FailError");
+
+        let service = fn_service(index)
+            .enclosed(Pass)
+            .try_build()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(service.call("996").now_or_panic().unwrap(), "996");
+    }
+
+    #[test]
+    fn boxed_build() {
+        let err = fn_service(index)
+            .enclosed(Fail)
+            .boxed_build()
+            .enclosed(Pass)
+            .try_build()
+            .call(())
+            .now_or_panic()
+            .err()
+            .unwrap();
+
+        assert_eq!(err.index(), 0);
+        assert_eq!(err.message(), "FailError");
+    }
+}
@@ -54,6 +54,103 @@ pub trait ServiceExt<Arg>: Service<Arg> {
     {
         PipelineT::new(self, factory)
     }
+
+    /// Race another service factory who's service takes the same request type as `Self`'s
+    /// service. Request is cloned and sent to both services concurrently and the first
+    /// successful response wins while the other call is cancelled.
+    ///
+    /// A failed call does not end the race. When both calls fail the error of the last one is
+    /// returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use core::{convert::Infallible, future::pending};
+    /// # use xitca_service::{fn_service, Service, ServiceExt};
+    /// # async fn race() {
+    /// // hedge a slow backend with a fast one.
+    /// let service = fn_service(|_: ()| async { pending::<Result<&str, Infallible>>().await })
+    ///     .race(fn_service(|_: ()| async { Ok("fast") }))
+    ///     .call(())
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// assert_eq!(service.call(()).await.unwrap(), "fast");
+    /// # }
+    /// ```
+    fn race<F>(self, factory: F) -> PipelineT<Self, F, marker::BuildRace>
+    where
+        F: Service<Arg>,
+        Self: Sized,
+    {
+        PipelineT::new(self, factory)
+    }
+
+    /// Fan out request to another service factory who's service takes the same request type as
+    /// `Self`'s service. Request is cloned and sent to both services concurrently and results of
+    /// both calls are collected into a tuple once they all finish.
+    ///
+    /// Useful for mirroring traffic where outcome of each service is inspected separately.
+    fn fanout<F>(self, factory: F) -> PipelineT<Self, F, marker::BuildFanout>
+    where
+        F: Service<Arg>,
+        Self: Sized,
+    {
+        PipelineT::new(self, factory)
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Erase build error of Self into [BuildError](crate::BuildError) with type name of Self as
+    /// failing layer.
+    ///
+    /// Useful for service factory not implementing [BuildLayer](crate::BuildLayer) so it can be
+    /// used as the innermost layer of [Self::try_build].
+    fn boxed_build(self) -> PipelineT<Self, (), marker::BuildBoxed>
+    where
+        Self::Error: core::fmt::Debug,
+        Self: Sized,
+    {
+        PipelineT::new(self, ())
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Erase nested build errors of a stack of layers composed by combinators like
+    /// [Self::enclosed] into [BuildError](crate::BuildError) which carries type name and index
+    /// of the layer failed to build.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use core::convert::Infallible;
+    /// # use xitca_service::{fn_service, Service, ServiceExt};
+    /// struct Fail;
+    ///
+    /// impl<S> Service<S> for Fail {
+    ///     type Response = S;
+    ///     type Error = &'static str;
+    ///
+    ///     async fn call(&self, _: S) -> Result<Self::Response, Self::Error> {
+    ///         Err("missing config")
+    ///     }
+    /// }
+    ///
+    /// # async fn build() {
+    /// let err = fn_service(|_: ()| async { Ok::<_, Infallible>(()) })
+    ///     .enclosed(Fail)
+    ///     .try_build()
+    ///     .call(())
+    ///     .await
+    ///     .err()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(err.index(), 1);
+    /// assert!(err.layer().ends_with("Fail"));
+    /// # }
+    /// ```
+    fn try_build(self) -> PipelineT<Self, (), marker::BuildTry>
+    where
+        Self: crate::BuildLayer<Arg> + Sized,
+    {
+        PipelineT::new(self, ())
+    }
 }
 
 impl<S, Arg> ServiceExt<Arg> for S where S: Service<Arg> {}
@@ -156,6 +253,47 @@ mod test {
         assert_eq!(res, "251");
     }
 
+    #[test]
+    fn race() {
+        async fn fail(_: &'static str) -> Result<&'static str, ()> {
+            Err(())
+        }
+
+        async fn pending(_: &'static str) -> Result<&'static str, ()> {
+            core::future::pending().await
+        }
+
+        let service = fn_service(pending)
+            .race(fn_service(index))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(service.call("996").now_or_panic().unwrap(), "996");
+
+        let service = fn_service(fail)
+            .race(fn_service(index))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(service.call("996").now_or_panic().unwrap(), "996");
+
+        let service = fn_service(fail).race(fn_service(fail)).call(()).now_or_panic().unwrap();
+        assert!(service.call("996").now_or_panic().is_err());
+    }
+
+    #[test]
+    fn fanout() {
+        let service = fn_service(index)
+            .fanout(fn_service(|_: &'static str| async { Err::<(), _>(()) }))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let (res1, res2) = service.call("996").now_or_panic().unwrap();
+        assert_eq!(res1, Ok("996"));
+        assert_eq!(res2, Err(()));
+    }
+
     #[cfg(feature = "alloc")]
     #[test]
     fn enclosed_opt() {
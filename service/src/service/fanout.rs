@@ -0,0 +1,71 @@
+use core::{
+    convert::Infallible,
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
+
+use crate::pipeline::{
+    marker::{BuildFanout, Fanout},
+    PipelineT,
+};
+
+use super::Service;
+
+impl<SF, Arg, SF1> Service<Arg> for PipelineT<SF, SF1, BuildFanout>
+where
+    SF: Service<Arg>,
+    Arg: Clone,
+    SF1: Service<Arg>,
+    SF1::Error: From<SF::Error>,
+{
+    type Response = PipelineT<SF::Response, SF1::Response, Fanout>;
+    type Error = SF1::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let first = self.first.call(arg.clone()).await?;
+        let second = self.second.call(arg).await?;
+        Ok(PipelineT::new(first, second))
+    }
+}
+
+impl<S, Req, S1> Service<Req> for PipelineT<S, S1, Fanout>
+where
+    S: Service<Req>,
+    S1: Service<Req>,
+    Req: Clone,
+{
+    type Response = (Result<S::Response, S::Error>, Result<S1::Response, S1::Error>);
+    type Error = Infallible;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let mut first = pin!(self.first.call(req.clone()));
+        let mut second = pin!(self.second.call(req));
+
+        let (mut res1, mut res2) = (None, None);
+
+        poll_fn(|cx| {
+            if res1.is_none() {
+                if let Poll::Ready(res) = first.as_mut().poll(cx) {
+                    res1 = Some(res);
+                }
+            }
+
+            if res2.is_none() {
+                if let Poll::Ready(res) = second.as_mut().poll(cx) {
+                    res2 = Some(res);
+                }
+            }
+
+            match (res1.take(), res2.take()) {
+                (Some(res1), Some(res2)) => Poll::Ready(Ok((res1, res2))),
+                (r1, r2) => {
+                    res1 = r1;
+                    res2 = r2;
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
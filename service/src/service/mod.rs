@@ -1,11 +1,18 @@
 mod and_then;
+#[cfg(feature = "alloc")]
+mod build;
 mod enclosed;
 mod enclosed_fn;
 mod ext;
+mod fanout;
 mod function;
 mod map;
 mod map_err;
 mod opt;
+mod race;
+
+#[cfg(feature = "alloc")]
+pub use self::build::{BuildError, BuildLayer};
 
 pub use self::{
     ext::ServiceExt,
@@ -0,0 +1,71 @@
+use core::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
+
+use crate::pipeline::{
+    marker::{BuildRace, Race},
+    PipelineT,
+};
+
+use super::Service;
+
+impl<SF, Arg, SF1> Service<Arg> for PipelineT<SF, SF1, BuildRace>
+where
+    SF: Service<Arg>,
+    Arg: Clone,
+    SF1: Service<Arg>,
+    SF1::Error: From<SF::Error>,
+{
+    type Response = PipelineT<SF::Response, SF1::Response, Race>;
+    type Error = SF1::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let first = self.first.call(arg.clone()).await?;
+        let second = self.second.call(arg).await?;
+        Ok(PipelineT::new(first, second))
+    }
+}
+
+impl<S, Req, S1> Service<Req> for PipelineT<S, S1, Race>
+where
+    S: Service<Req>,
+    S1: Service<Req, Response = S::Response, Error = S::Error>,
+    Req: Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let mut first = pin!(self.first.call(req.clone()));
+        let mut second = pin!(self.second.call(req));
+
+        // a failed call is not polled again and it's error is dropped unless the other call
+        // failed too.
+        let (mut first_failed, mut second_failed) = (false, false);
+
+        poll_fn(|cx| {
+            if !first_failed {
+                if let Poll::Ready(res) = first.as_mut().poll(cx) {
+                    match res {
+                        Err(_) if !second_failed => first_failed = true,
+                        res => return Poll::Ready(res),
+                    }
+                }
+            }
+
+            if !second_failed {
+                if let Poll::Ready(res) = second.as_mut().poll(cx) {
+                    match res {
+                        Err(_) if !first_failed => second_failed = true,
+                        res => return Poll::Ready(res),
+                    }
+                }
+            }
+
+            Poll::Pending
+        })
+        .await
+    }
+}
@@ -9,14 +9,17 @@ use xitca_client::Client;
 use xitca_http::{
     body::{BoxStream, ResponseBody},
     bytes::{Bytes, BytesMut},
+    config::HttpServiceConfig,
     h1,
     http::{
         header::{self, HeaderValue, CONNECTION},
         Method, Request, RequestExt, Response,
     },
+    DrainHandle, HttpServiceBuilder,
 };
-use xitca_service::fn_service;
-use xitca_test::{test_h1_server, Error};
+use xitca_io::net::Stream as NetStream;
+use xitca_service::{fn_service, ServiceExt};
+use xitca_test::{test_h1_server, test_server, Error};
 
 #[tokio::test]
 async fn h1_get() -> Result<(), Error> {
@@ -141,6 +144,196 @@ async fn h1_close_connection() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h1_lingering_close() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    // request body is dropped by service without being read.
+    stream.write_all(b"POST /drop_body HTTP/1.1\r\ncontent-length: 1048576\r\n\r\n")?;
+    stream.write_all(&[b'a'; 1024])?;
+
+    let mut res = Vec::new();
+    let mut buf = [0; 128];
+    loop {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        res.extend_from_slice(&buf[..n]);
+    }
+
+    let res = String::from_utf8(res)?;
+    assert!(res.starts_with("HTTP/1.1 200 OK"));
+    assert!(res.contains("\r\nconnection: close\r\n"));
+
+    // server still drains the remaining body after response is fully received.
+    stream.write_all(&[b'a'; 1024])?;
+
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_malformed_request() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    stream.write_all(b"GET / HTTP/1.1\r\nbad header\r\n\r\n")?;
+
+    let mut res = Vec::new();
+    let mut buf = [0; 128];
+    loop {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        res.extend_from_slice(&buf[..n]);
+    }
+
+    let res = String::from_utf8(res)?;
+    assert!(res.starts_with("HTTP/1.1 400 Bad Request"));
+    assert!(res.contains("\r\nconnection: close\r\n"));
+    assert!(res.contains("\r\ncontent-length: 15\r\n"));
+    assert!(res.ends_with("\r\n\r\n400 Bad Request"));
+
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_request_body_timeout() -> Result<(), Error> {
+    async fn collect(req: Request<RequestExt<xitca_http::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+        let mut body = req.into_body();
+        while let Some(chunk) = body.next().await {
+            chunk?;
+        }
+        Ok(Response::new(Bytes::from("collected").into()))
+    }
+
+    let config = HttpServiceConfig::new()
+        .request_body_timeout(Duration::from_millis(500))
+        .request_body_min_bytes(16);
+    let service = fn_service(collect).enclosed(HttpServiceBuilder::with_config(config));
+    let mut handle = test_server::<_, NetStream>(service)?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    // body sent fast enough is collected.
+    stream.write_all(b"POST / HTTP/1.1\r\ncontent-length: 64\r\n\r\n")?;
+    for _ in 0..4 {
+        std::thread::sleep(Duration::from_millis(200));
+        stream.write_all(&[b'a'; 16])?;
+    }
+
+    let mut buf = [0; 128];
+    let n = stream.read(&mut buf)?;
+    let res = std::str::from_utf8(&buf[..n])?;
+    assert!(res.starts_with("HTTP/1.1 200 OK"));
+    assert!(res.ends_with("collected"));
+
+    // body trickled slower than minimum bytes per timeout is rejected.
+    stream.write_all(b"POST / HTTP/1.1\r\ncontent-length: 64\r\n\r\n")?;
+    for _ in 0..3 {
+        stream.write_all(b"a")?;
+        std::thread::sleep(Duration::from_millis(200));
+    }
+
+    let mut res = Vec::new();
+    loop {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        res.extend_from_slice(&buf[..n]);
+    }
+
+    let res = String::from_utf8(res)?;
+    assert!(res.starts_with("HTTP/1.1 408 Request Timeout"));
+    assert!(res.contains("\r\nconnection: close\r\n"));
+
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_drain() -> Result<(), Error> {
+    async fn slow(_: Request<RequestExt<xitca_http::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        Ok(Response::new(Bytes::from("slow").into()))
+    }
+
+    fn read_to_end(stream: &mut TcpStream) -> Result<String, Error> {
+        let mut res = Vec::new();
+        let mut buf = [0; 128];
+        loop {
+            let n = stream.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            res.extend_from_slice(&buf[..n]);
+        }
+        Ok(String::from_utf8(res)?)
+    }
+
+    let drain = DrainHandle::new();
+    let service = fn_service(slow).enclosed(HttpServiceBuilder::new().drain(drain.clone()));
+    let mut handle = test_server::<_, NetStream>(service)?;
+
+    let mut busy = TcpStream::connect(handle.addr())?;
+    busy.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut idle = TcpStream::connect(handle.addr())?;
+    idle.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    busy.write_all(b"GET / HTTP/1.1\r\n\r\n")?;
+    std::thread::sleep(Duration::from_millis(200));
+
+    let task = std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(drain.drain(Duration::from_secs(5)))
+    });
+
+    // in-flight request is finished and connection is closed after it.
+    let res = read_to_end(&mut busy)?;
+    assert!(res.starts_with("HTTP/1.1 200 OK"));
+    assert!(res.contains("\r\nconnection: close\r\n"));
+    assert!(res.ends_with("slow"));
+
+    // idle connection is closed without response.
+    assert!(read_to_end(&mut idle)?.is_empty());
+
+    assert!(task.join().unwrap());
+
+    // new connection is refused.
+    let mut new = TcpStream::connect(handle.addr())?;
+    new.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let _ = new.write_all(b"GET / HTTP/1.1\r\n\r\n");
+    assert!(read_to_end(&mut new).unwrap_or_default().is_empty());
+
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
 // Request head size is limited by ReadBuf's max size which is 1MB by default.
 // If the default setting changed this test must be chagned to reflex it.
 #[tokio::test]
@@ -220,6 +413,128 @@ async fn h1_keepalive() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h1_throttle() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+
+    let now = std::time::Instant::now();
+
+    let res = c
+        .post(&server_url)?
+        .text(vec![b'a'; 4096])
+        .throttle_upload(8192)
+        .throttle_download(8192)
+        .send()
+        .await?;
+    assert_eq!(res.status().as_u16(), 200);
+    let body = res.string().await?;
+    assert_eq!(body.len(), 4096);
+
+    // both upload and download are paced to half a second.
+    assert!(now.elapsed() >= Duration::from_millis(1000));
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_drop_body_stream() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/peer_addr", handle.ip_port_string());
+    let server_url_large = format!("http://{}/peer_addr_large", handle.ip_port_string());
+
+    let c = Client::new();
+
+    // drop large body stream in the middle. connection can not be reused.
+    let mut body = c.get(&server_url_large)?.send().await?.into_body_stream();
+    let chunk = body.next().await.unwrap()?;
+    let peer = chunk.split(|b| *b == b'\n').next().unwrap().to_vec();
+    drop(body);
+
+    let peer2 = c.get(&server_url)?.send().await?.body().await?;
+    assert_ne!(peer, peer2);
+
+    // drop small body stream without reading. the body is drained and connection is reused.
+    drop(c.get(&server_url)?.send().await?.into_body_stream());
+
+    let peer3 = c.get(&server_url)?.send().await?.body().await?;
+    assert_eq!(peer2, peer3);
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_warm_up() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/peer_addr", handle.ip_port_string());
+
+    let c = Client::builder().set_warm_up_size(2).finish();
+
+    c.warm_up(&[server_url.as_str()]).await?;
+
+    // pooled connections are used in turn. two distinct peers prove both of them are pre-connected.
+    let mut peers = Vec::new();
+    for _ in 0..2 {
+        let res = c.get(&server_url)?.send().await?;
+        assert_eq!(res.status().as_u16(), 200);
+        peers.push(res.string().await?);
+    }
+    assert_ne!(peers[0], peers[1]);
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_send_all() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+
+    let reqs = || (0..8).map(|_| c.get(&server_url).unwrap());
+
+    let mut idx = Vec::new();
+    let mut stream = c.send_all(reqs(), 3);
+    while let Some((i, res)) = stream.next().await {
+        assert_eq!(res?.string().await?, "GET Response");
+        idx.push(i);
+    }
+    idx.sort();
+    assert_eq!(idx, (0..8).collect::<Vec<_>>());
+
+    let mut idx = Vec::new();
+    let mut stream = c.send_all(reqs(), 3).ordered();
+    while let Some((i, res)) = stream.next().await {
+        assert_eq!(res?.string().await?, "GET Response");
+        idx.push(i);
+    }
+    assert_eq!(idx, (0..8).collect::<Vec<_>>());
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
 async fn handle(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/") => Ok(Response::new(Bytes::from("GET Response").into())),
@@ -254,6 +569,12 @@ async fn handle(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<Re
 
             Ok(Response::new(Bytes::new().into()))
         }
+        (&Method::GET, "/peer_addr_large") => {
+            let mut body = BytesMut::from(format!("{}\n", req.body().socket_addr()).as_bytes());
+            body.extend_from_slice(&[b'a'; 1024 * 1024]);
+            Ok(Response::new(body.freeze().into()))
+        }
+        (&Method::GET, "/peer_addr") => Ok(Response::new(Bytes::from(req.body().socket_addr().to_string()).into())),
         (&Method::GET, "/close_connection") => {
             let mut res = Response::new(Bytes::new().into());
             res.headers_mut().insert(CONNECTION, HeaderValue::from_static("close"));
@@ -0,0 +1,113 @@
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+};
+
+use xitca_http::{
+    body::{RequestBody, ResponseBody},
+    bytes::Bytes,
+    config::HttpServiceConfig,
+    http::{Method, Request, RequestExt, Response, Version},
+    HttpServiceBuilder,
+};
+use xitca_io::net::Stream as NetStream;
+use xitca_service::{fn_service, ServiceExt};
+use xitca_test::{test_server, Error};
+
+const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+// empty SETTINGS frame.
+const SETTINGS: &[u8] = &[0, 0, 0, 4, 0, 0, 0, 0, 0];
+
+#[tokio::test]
+async fn h2c_upgrade() -> Result<(), Error> {
+    let service = fn_service(handle).enclosed(HttpServiceBuilder::with_config(HttpServiceConfig::new().h2c_upgrade()));
+    let mut handle = test_server::<_, NetStream>(service)?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+
+    stream.write_all(
+        b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: Upgrade, HTTP2-Settings\r\nupgrade: h2c\r\nhttp2-settings: \r\n\r\n",
+    )?;
+
+    let buf = read_until(&mut stream, b"\r\n\r\n")?;
+    assert!(buf.starts_with(b"HTTP/1.1 101 Switching Protocols\r\n"));
+
+    stream.write_all(PREFACE)?;
+    stream.write_all(SETTINGS)?;
+
+    // response of upgraded request is sent on stream 1 over http/2.
+    read_until(&mut stream, b"h2 Response")?;
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h2c_prior_knowledge() -> Result<(), Error> {
+    let service = fn_service(handle).enclosed(HttpServiceBuilder::with_config(
+        HttpServiceConfig::new().peek_protocol(),
+    ));
+    let mut handle = test_server::<_, NetStream>(service)?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+
+    stream.write_all(PREFACE)?;
+    stream.write_all(SETTINGS)?;
+    stream.write_all(&headers_frame())?;
+
+    read_until(&mut stream, b"h2 Response")?;
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+// HEADERS frame of GET request on stream 1 with header fields encoded as literal without indexing.
+fn headers_frame() -> Vec<u8> {
+    let mut block = Vec::new();
+
+    for (name, value) in [
+        (":method", "GET"),
+        (":scheme", "http"),
+        (":path", "/"),
+        (":authority", "localhost"),
+    ] {
+        block.push(0);
+        block.push(name.len() as u8);
+        block.extend_from_slice(name.as_bytes());
+        block.push(value.len() as u8);
+        block.extend_from_slice(value.as_bytes());
+    }
+
+    // END_STREAM | END_HEADERS
+    let mut frame = vec![0, 0, block.len() as u8, 1, 0x5, 0, 0, 0, 1];
+    frame.extend_from_slice(&block);
+    frame
+}
+
+fn read_until(stream: &mut TcpStream, pat: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 1024];
+
+    loop {
+        let n = stream.read(&mut chunk)?;
+        assert_ne!(n, 0, "connection closed unexpectedly");
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.windows(pat.len()).any(|w| w == pat) {
+            return Ok(buf);
+        }
+    }
+}
+
+async fn handle(req: Request<RequestExt<RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+    match (req.method(), req.version()) {
+        (&Method::GET, Version::HTTP_2) => Ok(Response::new(Bytes::from("h2 Response").into())),
+        _ => Ok(Response::new(Bytes::from("h1 Response").into())),
+    }
+}
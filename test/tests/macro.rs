@@ -113,3 +113,109 @@ fn state_borrow() {
     assert_eq!(string.as_str(), "996");
     assert_eq!(num, &251);
 }
+
+mod cache {
+    use std::{
+        fmt::Debug,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use futures_util::Stream;
+    use xitca_service::Service;
+    use xitca_web::{
+        bytes::Bytes,
+        codegen::cache,
+        handler::{handler_service, state::StateRef},
+        http::{Uri, WebRequest, WebResponse},
+        test::collect_string_body,
+        App, WebContext,
+    };
+
+    static PATH: AtomicUsize = AtomicUsize::new(0);
+    static QUERY: AtomicUsize = AtomicUsize::new(0);
+    static EXPIRE: AtomicUsize = AtomicUsize::new(0);
+    static STATE: AtomicUsize = AtomicUsize::new(0);
+
+    #[cache(ttl = "1h", key = "path")]
+    async fn path(_: &WebContext<'_>) -> String {
+        PATH.fetch_add(1, Ordering::Relaxed).to_string()
+    }
+
+    // key default to query.
+    #[cache(ttl = "1h")]
+    async fn query(_: &WebContext<'_>) -> String {
+        QUERY.fetch_add(1, Ordering::Relaxed).to_string()
+    }
+
+    #[cache(ttl = "50ms", key = "path")]
+    async fn expire(_: &WebContext<'_>) -> String {
+        EXPIRE.fetch_add(1, Ordering::Relaxed).to_string()
+    }
+
+    #[cache(ttl = "1m", state = usize)]
+    async fn state(StateRef(num): StateRef<'_, usize>) -> String {
+        format!("{num}-{}", STATE.fetch_add(1, Ordering::Relaxed))
+    }
+
+    async fn get<S, B, E>(service: &S, uri: &'static str) -> String
+    where
+        S: Service<WebRequest, Response = WebResponse<B>>,
+        S::Error: Debug,
+        B: Stream<Item = Result<Bytes, E>>,
+        E: Debug,
+    {
+        let mut req = WebRequest::default();
+        *req.uri_mut() = Uri::from_static(uri);
+        let res = service.call(req).await.unwrap();
+        collect_string_body(res.into_body()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn key() {
+        let service = App::new()
+            .at("/path", path_service())
+            .at("/query", query_service())
+            .at("/nocache", handler_service(path))
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        assert_eq!(get(&service, "/path?a=1").await, "0");
+        assert_eq!(get(&service, "/path?a=2").await, "0");
+
+        assert_eq!(get(&service, "/query?a=1").await, "0");
+        assert_eq!(get(&service, "/query?a=1").await, "0");
+        assert_eq!(get(&service, "/query?a=2").await, "1");
+
+        // handler function is not affected by attribute.
+        assert_eq!(get(&service, "/nocache").await, "1");
+        assert_eq!(get(&service, "/nocache").await, "2");
+    }
+
+    #[tokio::test]
+    async fn ttl() {
+        let service = App::new().at("/", expire_service()).finish().call(()).await.unwrap();
+
+        assert_eq!(get(&service, "/").await, "0");
+        assert_eq!(get(&service, "/").await, "0");
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(get(&service, "/").await, "1");
+    }
+
+    #[tokio::test]
+    async fn with_state() {
+        let service = App::with_state(996usize)
+            .at("/", state_service())
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        assert_eq!(get(&service, "/").await, "996-0");
+        assert_eq!(get(&service, "/").await, "996-0");
+    }
+}
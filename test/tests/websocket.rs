@@ -67,6 +67,36 @@ async fn message_h2() -> Result<(), Error> {
     handle.await.map_err(Into::into)
 }
 
+#[tokio::test]
+async fn message_h2_pooled() -> Result<(), Error> {
+    let mut handle = test_h2_server(fn_service(handler))?;
+
+    let server_url = format!("wss://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+
+    // pool a http/2 connection.
+    let mut ws = c.ws2(&server_url)?.send().await?;
+
+    // http/1 websocket request is tunneled through pooled http/2 connection.
+    let (mut tx, mut rx) = c.ws(&server_url)?.send().await?.split();
+
+    tx.send(Message::Text(Bytes::from("Hello,World!"))).await?;
+    let msg = rx.next().await.unwrap()?;
+    assert_eq!(msg, Message::Text(Bytes::from("Hello,World!")));
+
+    tx.send(Message::Close(None)).await?;
+    let msg = rx.next().await.unwrap()?;
+    assert_eq!(msg, Message::Close(None));
+
+    ws.send(Message::Close(None)).await?;
+    let msg = ws.next().await.unwrap()?;
+    assert_eq!(msg, Message::Close(None));
+
+    handle.try_handle()?.stop(true);
+    handle.await.map_err(Into::into)
+}
+
 async fn handler<B, E>(
     req: Request<B>,
 ) -> Result<Response<ResponseBody<impl Stream<Item = Result<Bytes, impl std::fmt::Debug>>>>, Error>
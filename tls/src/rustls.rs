@@ -56,6 +56,11 @@ where
         &self.conn
     }
 
+    /// construct stream from io and connection that already finished handshake.
+    pub fn from_parts(io: Io, conn: C) -> Self {
+        TlsStream { io, conn }
+    }
+
     /// finish handshake with given io and connection type.
     /// # Examples:
     /// ```rust
@@ -0,0 +1,39 @@
+//! cpu affinity of threads.
+
+use core::mem;
+
+use std::io;
+
+/// pin current thread to given cpu core. core is indexed from 0.
+pub fn set_for_current(core: usize) -> io::Result<()> {
+    if core >= libc::CPU_SETSIZE as usize {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "cpu core out of bound"));
+    }
+
+    // SAFETY: cpu_set_t is a plain bit mask and all zero value is an empty set.
+    let mut set = unsafe { mem::zeroed::<libc::cpu_set_t>() };
+
+    // SAFETY: core is checked to be in range of cpu_set_t.
+    unsafe { libc::CPU_SET(core, &mut set) };
+
+    // SAFETY: pid 0 refers to the calling thread and set is a valid cpu_set_t with matching size.
+    match unsafe { libc::sched_setaffinity(0, mem::size_of::<libc::cpu_set_t>(), &set) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pin() {
+        std::thread::spawn(|| {
+            set_for_current(0).unwrap();
+            assert!(set_for_current(libc::CPU_SETSIZE as usize).is_err());
+        })
+        .join()
+        .unwrap();
+    }
+}
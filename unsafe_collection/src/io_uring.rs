@@ -0,0 +1,128 @@
+//! io_uring operations not offered by async runtime.
+
+use std::{
+    io,
+    os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd, RawFd},
+};
+
+use io_uring::{cqueue, opcode, types::Fd, IoUring};
+
+/// multishot accept on a dedicated io_uring instance. one submission keeps accepting connections
+/// on listener until kernel terminates it.
+///
+/// The ring's file descriptor is exposed through [AsRawFd] and becomes readable when accepted
+/// connections are ready to be collected with [MultishotAccept::complete].
+pub struct MultishotAccept {
+    ring: IoUring,
+    listener: OwnedFd,
+    armed: bool,
+}
+
+impl MultishotAccept {
+    /// construct with a listening socket and size of completion queue. file descriptor of listener
+    /// is duplicated so the socket is kept open by constructed type on it's own.
+    pub fn new(listener: &impl AsFd, entries: u32) -> io::Result<Self> {
+        let listener = listener.as_fd().try_clone_to_owned()?;
+        let ring = IoUring::builder().setup_cqsize(entries).build(1)?;
+        Ok(Self {
+            ring,
+            listener,
+            armed: false,
+        })
+    }
+
+    /// submit multishot accept when it's not armed. kernel terminates multishot accept on error
+    /// and completion queue overflow and it must be armed again.
+    pub fn arm(&mut self) -> io::Result<()> {
+        if self.armed {
+            return Ok(());
+        }
+
+        let entry = opcode::AcceptMulti::new(Fd(self.listener.as_raw_fd()))
+            .flags(libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK)
+            .build();
+
+        // SAFETY: multishot accept does not reference any memory owned by user space.
+        unsafe { self.ring.submission().push(&entry) }
+            .map_err(|_| io::Error::other("io_uring submission queue is full"))?;
+        self.ring.submit()?;
+
+        self.armed = true;
+        Ok(())
+    }
+
+    /// collect accepted connections from completion queue and return the number of them.
+    pub fn complete<F>(&mut self, mut on_accept: F) -> usize
+    where
+        F: FnMut(io::Result<OwnedFd>),
+    {
+        let mut n = 0;
+        for cqe in self.ring.completion() {
+            if !cqueue::more(cqe.flags()) {
+                self.armed = false;
+            }
+            on_accept(from_result(cqe.result()));
+            n += 1;
+        }
+        n
+    }
+}
+
+impl AsRawFd for MultishotAccept {
+    fn as_raw_fd(&self) -> RawFd {
+        self.ring.as_raw_fd()
+    }
+}
+
+impl Drop for MultishotAccept {
+    fn drop(&mut self) {
+        // close connections accepted but not collected.
+        self.complete(drop);
+    }
+}
+
+fn from_result(res: i32) -> io::Result<OwnedFd> {
+    if res < 0 {
+        Err(io::Error::from_raw_os_error(-res))
+    } else {
+        // SAFETY: kernel hands over the ownership of accepted file descriptor.
+        Ok(unsafe { OwnedFd::from_raw_fd(res) })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::{TcpListener, TcpStream};
+
+    use super::*;
+
+    #[test]
+    fn accept() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mut accept = MultishotAccept::new(&listener, 16).unwrap();
+        accept.arm().unwrap();
+
+        let clients = (0..3).map(|_| TcpStream::connect(addr).unwrap()).collect::<Vec<_>>();
+
+        let mut accepted = Vec::new();
+        while accepted.len() < clients.len() {
+            accept.complete(|res| accepted.push(TcpStream::from(res.unwrap())));
+        }
+
+        for client in clients {
+            let addr = client.local_addr().unwrap();
+            assert!(accepted.iter().any(|s| s.peer_addr().unwrap() == addr));
+        }
+
+        // single submission keeps accepting.
+        let _client = TcpStream::connect(addr).unwrap();
+        while accept.complete(|res| drop(res.unwrap())) == 0 {}
+
+        // listener closed by caller is kept open by multishot accept.
+        drop(listener);
+        let _client = TcpStream::connect(addr).unwrap();
+        while accept.complete(|res| drop(res.unwrap())) == 0 {}
+    }
+}
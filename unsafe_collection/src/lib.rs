@@ -10,5 +10,9 @@ pub mod no_hash;
 pub mod small_str;
 pub mod uninit;
 
+#[cfg(all(feature = "affinity", target_os = "linux"))]
+pub mod affinity;
 #[cfg(feature = "bytes")]
 pub mod bytes;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+pub mod io_uring;
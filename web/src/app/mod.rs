@@ -12,22 +12,24 @@ use std::error;
 use futures_core::stream::Stream;
 use xitca_http::util::{
     middleware::context::{Context, ContextBuilder},
-    service::router::{IntoObject, Router, RouterGen},
+    service::router::{DynamicRouter, IntoObject, Router, RouterGen, RouterHandle},
 };
 
 use crate::{
     body::ResponseBody,
     bytes::Bytes,
-    context::WebContext,
+    context::{ResponseHeaders, WebContext},
     dev::service::{ready::ReadyService, AsyncClosure, EnclosedFactory, EnclosedFnFactory, Service, ServiceExt},
     handler::Responder,
     http::{Request, RequestExt, WebResponse},
+    service::route_doc::{RouteDoc, RouteDocs},
 };
 
 /// composed application type with router, stateful context and default middlewares.
 pub struct App<CF = (), R = ()> {
     ctx_factory: CF,
     router: R,
+    docs: RouteDocs,
 }
 
 impl App {
@@ -53,6 +55,7 @@ impl App {
         App {
             ctx_factory,
             router: Router::new(),
+            docs: RouteDocs::default(),
         }
     }
 }
@@ -70,6 +73,135 @@ impl<CF, Obj> App<CF, Router<Obj>> {
         self.router = self.router.insert(path, factory);
         self
     }
+
+    /// insert routed service with given path and documentation metadata to application.
+    ///
+    /// Documentation can be accessed with [App::route_docs]. See [RouteDocs] for detail.
+    pub fn at_doc<Fut, C, E, F, B>(self, path: &'static str, doc: RouteDoc, factory: F) -> App<CF, Router<Obj>>
+    where
+        CF: Fn() -> Fut,
+        Fut: Future<Output = Result<C, E>>,
+        F: RouterGen + Service + Send + Sync,
+        F::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<F::ErrGen<F>, (), Object = Obj>,
+    {
+        self.docs.insert(path, doc);
+        self.at(path, factory)
+    }
+}
+
+impl<CF, R> App<CF, R> {
+    /// Shared registry of documentation of routes inserted with [App::at_doc]. It's also a
+    /// service that can be inserted as introspection endpoint.
+    pub fn route_docs(&self) -> RouteDocs {
+        self.docs.clone()
+    }
+}
+
+impl<CF, Obj> App<CF, Router<Obj>> {
+    /// Finish App build with a router that can be updated at runtime. No other App method can be
+    /// called afterwards.
+    ///
+    /// Returned [AppHandle] can insert and remove routes of a running App. Changes are picked up
+    /// by every worker thread on it's next request.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use xitca_web::{handler::handler_service, App, WebContext};
+    /// let (app, handle) = App::new()
+    ///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+    ///     .finish_dynamic();
+    ///
+    /// // add route from plugin or admin api.
+    /// handle.at("/plugin", handler_service(|_: &WebContext<'_>| async { "plugin" }));
+    /// // remove route.
+    /// handle.remove("/plugin");
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn finish_dynamic<Fut, C, CErr, ReqB, ResB, SE, B, BE>(
+        self,
+    ) -> (
+        impl Service<
+            Response = impl ReadyService
+                           + Service<
+                Request<RequestExt<ReqB>>,
+                Response = WebResponse<ResponseBody<ResB>>,
+                Error = Infallible,
+            >,
+            Error = impl fmt::Debug,
+        >,
+        AppHandle<Obj>,
+    )
+    where
+        CF: Fn() -> Fut + Send + Sync,
+        Fut: Future<Output = Result<C, CErr>>,
+        CErr: fmt::Debug,
+        C: 'static,
+        DynamicRouter<Obj>: Service + Send + Sync,
+        <DynamicRouter<Obj> as Service>::Error: fmt::Debug,
+        <DynamicRouter<Obj> as Service>::Response:
+            ReadyService + for<'r> Service<WebContext<'r, C, ReqB>, Response = WebResponse<ResB>, Error = SE>,
+        SE: for<'r> Responder<WebContext<'r, C, ReqB>, Output = WebResponse>,
+        ReqB: 'static,
+        ResB: Stream<Item = Result<B, BE>>,
+    {
+        let (router, handle) = self.router.into_dynamic();
+        let docs = self.docs.clone();
+        let app = App {
+            ctx_factory: self.ctx_factory,
+            router,
+            docs: self.docs,
+        };
+        (app.finish(), AppHandle { handle, docs })
+    }
+}
+
+/// Handle for updating routes of App finished with [App::finish_dynamic].
+pub struct AppHandle<Obj> {
+    handle: RouterHandle<Obj>,
+    docs: RouteDocs,
+}
+
+impl<Obj> Clone for AppHandle<Obj> {
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle.clone(),
+            docs: self.docs.clone(),
+        }
+    }
+}
+
+impl<Obj> AppHandle<Obj> {
+    /// insert routed service with given path to running application. Existing service on the same
+    /// path would be replaced.
+    pub fn at<C, F, B>(&self, path: &'static str, factory: F) -> &Self
+    where
+        F: RouterGen + Service + Send + Sync,
+        F::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<F::ErrGen<F>, (), Object = Obj>,
+    {
+        self.handle.insert(path, factory);
+        self
+    }
+
+    /// insert routed service with given path and documentation metadata to running application.
+    /// Existing service on the same path would be replaced.
+    pub fn at_doc<C, F, B>(&self, path: &'static str, doc: RouteDoc, factory: F) -> &Self
+    where
+        F: RouterGen + Service + Send + Sync,
+        F::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<F::ErrGen<F>, (), Object = Obj>,
+    {
+        self.docs.insert(path, doc);
+        self.at(path, factory)
+    }
+
+    /// remove routed service and it's documentation from given path of running application.
+    /// Return false when there is no service on the path.
+    pub fn remove(&self, path: &str) -> bool {
+        self.docs.remove(path);
+        self.handle.remove(path)
+    }
 }
 
 impl<CF, R, Fut, C, CErr> App<CF, R>
@@ -89,6 +221,7 @@ where
         App {
             ctx_factory: self.ctx_factory,
             router: self.router.enclosed(transform),
+            docs: self.docs,
         }
     }
 
@@ -100,6 +233,7 @@ where
         App {
             ctx_factory: self.ctx_factory,
             router: self.router.enclosed_fn(transform),
+            docs: self.docs,
         }
     }
 
@@ -122,7 +256,9 @@ where
         ReqB: 'static,
         ResB: Stream<Item = Result<B, BE>>,
     {
-        let App { ctx_factory, router } = self;
+        let App {
+            ctx_factory, router, ..
+        } = self;
         router
             .enclosed_fn(map_req_res)
             .enclosed(ContextBuilder::new(ctx_factory))
@@ -205,6 +341,67 @@ where
     {
         crate::server::HttpServer::serve(self.finish())
     }
+
+    #[cfg(feature = "__server")]
+    /// Finish App build and serve it with [HttpServer] using given [HttpServiceConfig]. No other
+    /// App method can be called afterwards.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use xitca_web::{config::HttpServiceConfig, handler::handler_service, App, WebContext};
+    /// let config = HttpServiceConfig::new()
+    ///     .request_head_timeout(Duration::from_secs(3))
+    ///     .max_read_buf_size::<{ 64 * 1024 }>();
+    ///
+    /// let server = App::new()
+    ///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello" }))
+    ///     .finish_with_config(config);
+    /// ```
+    ///
+    /// [HttpServer]: crate::server::HttpServer
+    /// [HttpServiceConfig]: crate::config::HttpServiceConfig
+    pub fn finish_with_config<
+        ReqB,
+        ResB,
+        SE,
+        B,
+        BE,
+        const HEADER_LIMIT: usize,
+        const READ_BUF_LIMIT: usize,
+        const WRITE_BUF_LIMIT: usize,
+    >(
+        self,
+        config: crate::config::HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
+    ) -> crate::server::HttpServer<
+        impl Service<
+            Response = impl ReadyService
+                           + Service<
+                Request<RequestExt<ReqB>>,
+                Response = WebResponse<ResponseBody<ResB>>,
+                Error = Infallible,
+            >,
+            Error = impl fmt::Debug,
+        >,
+        HEADER_LIMIT,
+        READ_BUF_LIMIT,
+        WRITE_BUF_LIMIT,
+    >
+    where
+        CF: 'static,
+        Fut: 'static,
+        C: 'static,
+        CErr: 'static,
+        R: 'static,
+        R::Response: ReadyService + for<'r> Service<WebContext<'r, C, ReqB>, Response = WebResponse<ResB>, Error = SE>,
+        SE: for<'r> Responder<WebContext<'r, C, ReqB>, Output = WebResponse> + 'static,
+        ReqB: 'static,
+        ResB: Stream<Item = Result<B, BE>> + 'static,
+        B: 'static,
+        BE: 'static,
+    {
+        crate::server::HttpServer::serve_with_config(self.finish(), config)
+    }
 }
 
 /// object safe [App] instance. used for case where naming [App]'s type is needed.
@@ -229,13 +426,18 @@ where
     let (ext, body) = ext.replace_body(());
     let mut req = Request::from_parts(parts, ext);
     let mut body = RefCell::new(body);
-    let mut req = WebContext::new(&mut req, &mut body, state);
+    let res_headers = RefCell::new(ResponseHeaders::default());
+    let mut req = WebContext::new(&mut req, &mut body, state, &res_headers);
 
-    match service.call(req.reborrow()).await {
-        Ok(res) => Ok(res.map(|body| ResponseBody::stream(body))),
+    let mut res = match service.call(req.reborrow()).await {
+        Ok(res) => res.map(|body| ResponseBody::stream(body)),
         // TODO: mutate response header according to outcome of drop_stream_cast?
-        Err(e) => Ok(e.respond_to(req).await.map(|body| body.drop_stream_cast())),
-    }
+        Err(e) => e.respond_to(req).await.map(|body| body.drop_stream_cast()),
+    };
+
+    res_headers.into_inner().merge(res.headers_mut());
+
+    Ok(res)
 }
 
 #[cfg(test)]
@@ -337,6 +539,7 @@ mod test {
                 req: req.req,
                 body,
                 ctx: req.ctx,
+                res_headers: req.res_headers,
             };
             match service.call(req2).await {
                 Ok(res) => Ok(res),
@@ -414,4 +617,93 @@ mod test {
 
         assert_eq!(res.status().as_u16(), 200);
     }
+
+    #[test]
+    fn app_dynamic() {
+        async fn handler(StateRef(state): StateRef<'_, String>) -> String {
+            state.to_string()
+        }
+
+        let (service, handle) = App::with_state(String::from("state"))
+            .at("/", get(handler_service(handler)))
+            .finish_dynamic();
+
+        let service = service.call(()).now_or_panic().ok().unwrap();
+
+        let req = |path| {
+            Request::builder()
+                .uri(path)
+                .body(RequestExt::<RequestBody>::default())
+                .unwrap()
+        };
+
+        let res = service.call(req("/dyn")).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 404);
+
+        handle.at("/dyn", get(handler_service(handler)));
+        let res = service.call(req("/dyn")).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+
+        assert!(handle.remove("/dyn"));
+        let res = service.call(req("/dyn")).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 404);
+
+        let res = service.call(req("/")).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+    }
+
+    #[test]
+    fn staged_response_header() {
+        use crate::http::header::{HeaderValue, CACHE_CONTROL, SET_COOKIE};
+
+        async fn handler(ctx: &WebContext<'_>) -> &'static str {
+            ctx.insert_response_header(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+            ctx.append_response_header(SET_COOKIE, HeaderValue::from_static("a=1"));
+            "996"
+        }
+
+        async fn handler_with_headers(_: &WebContext<'_>) -> WebResponse {
+            let mut res = WebResponse::new(ResponseBody::from("996"));
+            res.headers_mut()
+                .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+            res.headers_mut().insert(SET_COOKIE, HeaderValue::from_static("b=2"));
+            res
+        }
+
+        async fn middleware_fn<S, C, B, Res, Err>(service: &S, ctx: WebContext<'_, C, B>) -> Result<Res, Err>
+        where
+            S: for<'r> Service<WebContext<'r, C, B>, Response = Res, Error = Err>,
+        {
+            ctx.insert_response_header(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+            ctx.append_response_header(SET_COOKIE, HeaderValue::from_static("a=1"));
+            service.call(ctx).await
+        }
+
+        let service = App::new()
+            .at("/", get(handler_service(handler)))
+            .at(
+                "/headers",
+                get(handler_service(handler_with_headers)).enclosed_fn(middleware_fn),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let req = |path| {
+            Request::builder()
+                .uri(path)
+                .body(RequestExt::<RequestBody>::default())
+                .unwrap()
+        };
+
+        let res = service.call(req("/")).now_or_panic().unwrap();
+        assert_eq!(res.headers().get(CACHE_CONTROL).unwrap(), "no-store");
+        assert_eq!(res.headers().get(SET_COOKIE).unwrap(), "a=1");
+
+        let res = service.call(req("/headers")).now_or_panic().unwrap();
+        assert_eq!(res.headers().get(CACHE_CONTROL).unwrap(), "no-store");
+        let cookies = res.headers().get_all(SET_COOKIE).iter().collect::<Vec<_>>();
+        assert_eq!(cookies, ["b=2", "a=1"]);
+    }
 }
@@ -4,7 +4,7 @@ use std::error;
 
 use futures_core::stream::Stream;
 
-pub use xitca_http::body::{none_body_hint, BoxStream, RequestBody, ResponseBody, NONE_BODY_HINT};
+pub use xitca_http::body::{none_body_hint, BoxStream, Once, RequestBody, ResponseBody, NONE_BODY_HINT};
 
 /// an extended trait for [Stream] that specify additional type info of the [Stream::Item] type.
 pub trait BodyStream: Stream<Item = Result<Self::Chunk, Self::Error>> {
@@ -21,3 +21,174 @@ where
     type Chunk = T;
     type Error = E;
 }
+
+#[cfg(feature = "tower-http-compat")]
+pub use http_body_compat::{HttpBody, HttpBodyStream};
+
+#[cfg(feature = "tower-http-compat")]
+mod http_body_compat {
+    use core::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use http_body::{Body, SizeHint};
+    use pin_project_lite::pin_project;
+
+    use crate::{
+        bytes::{Buf, Bytes},
+        http::header::HeaderMap,
+    };
+
+    use super::*;
+
+    pin_project! {
+        /// adapter type for using xitca-web's body types(like [RequestBody] and [ResponseBody]) as
+        /// [http_body::Body].
+        pub struct HttpBody<B> {
+            #[pin]
+            body: B
+        }
+    }
+
+    impl<B> HttpBody<B> {
+        pub const fn new(body: B) -> Self {
+            Self { body }
+        }
+
+        pub fn into_inner(self) -> B {
+            self.body
+        }
+    }
+
+    impl<B> From<B> for HttpBody<B>
+    where
+        B: Stream,
+    {
+        fn from(body: B) -> Self {
+            Self::new(body)
+        }
+    }
+
+    impl<B, E> Body for HttpBody<B>
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+    {
+        type Data = Bytes;
+        type Error = E;
+
+        #[inline]
+        fn poll_data(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            self.project().body.poll_next(cx)
+        }
+
+        #[inline]
+        fn poll_trailers(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+            Poll::Ready(Ok(None))
+        }
+
+        fn size_hint(&self) -> SizeHint {
+            let mut hint = SizeHint::new();
+            let (low, upper) = self.body.size_hint();
+            hint.set_lower(low as u64);
+            if let Some(upper) = upper {
+                hint.set_upper(upper as u64);
+            }
+            hint
+        }
+    }
+
+    pin_project! {
+        /// adapter type for using [http_body::Body] types(like hyper and axum's body types) as
+        /// xitca-web's body type. It can be converted to [ResponseBody] and [BoxStream] directly.
+        pub struct HttpBodyStream<B> {
+            #[pin]
+            body: B
+        }
+    }
+
+    impl<B> HttpBodyStream<B> {
+        pub const fn new(body: B) -> Self {
+            Self { body }
+        }
+
+        pub fn into_inner(self) -> B {
+            self.body
+        }
+    }
+
+    impl<B> From<B> for HttpBodyStream<B>
+    where
+        B: Body,
+    {
+        fn from(body: B) -> Self {
+            Self::new(body)
+        }
+    }
+
+    impl<B> Stream for HttpBodyStream<B>
+    where
+        B: Body,
+    {
+        type Item = Result<Bytes, B::Error>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.project()
+                .body
+                .poll_data(cx)
+                .map_ok(|mut data| data.copy_to_bytes(data.remaining()))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let hint = self.body.size_hint();
+            (hint.lower() as usize, hint.upper().map(|num| num as usize))
+        }
+    }
+
+    impl<B> From<HttpBodyStream<B>> for BoxStream
+    where
+        B: Body + 'static,
+        B::Error: error::Error + Send + Sync,
+    {
+        fn from(body: HttpBodyStream<B>) -> Self {
+            BoxStream::new(body)
+        }
+    }
+
+    impl<B> From<HttpBodyStream<B>> for ResponseBody
+    where
+        B: Body + 'static,
+        B::Error: error::Error + Send + Sync,
+    {
+        fn from(body: HttpBodyStream<B>) -> Self {
+            ResponseBody::box_stream(body)
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use core::future::poll_fn;
+
+        use xitca_unsafe_collection::futures::NowOrPanic;
+
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let body = HttpBody::from(ResponseBody::<BoxStream>::bytes(Bytes::from_static(b"996")));
+
+            let size = Body::size_hint(&body);
+            assert_eq!(size.exact(), Some(3));
+
+            let body = HttpBodyStream::from(body);
+            assert_eq!(Stream::size_hint(&body), (3, Some(3)));
+
+            let mut body = ResponseBody::from(body);
+            let chunk = poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+                .now_or_panic()
+                .unwrap()
+                .unwrap();
+            assert_eq!(chunk, "996");
+        }
+    }
+}
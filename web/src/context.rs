@@ -5,9 +5,14 @@ use core::{
     mem,
 };
 
+use std::net::SocketAddr;
+
 use super::{
     body::{RequestBody, ResponseBody},
-    http::{BorrowReq, BorrowReqMut, IntoResponse, Request, RequestExt, WebRequest, WebResponse},
+    http::{
+        header::{HeaderMap, HeaderName, HeaderValue},
+        BorrowReq, BorrowReqMut, IntoResponse, Request, RequestExt, WebRequest, WebResponse,
+    },
 };
 
 /// web context type focus on stateful and side effect based request data access.
@@ -15,11 +20,22 @@ pub struct WebContext<'a, C = (), B = RequestBody> {
     pub(crate) req: &'a mut WebRequest<()>,
     pub(crate) body: &'a mut RefCell<B>,
     pub(crate) ctx: &'a C,
+    pub(crate) res_headers: &'a RefCell<ResponseHeaders>,
 }
 
 impl<'a, C, B> WebContext<'a, C, B> {
-    pub(crate) fn new(req: &'a mut WebRequest<()>, body: &'a mut RefCell<B>, ctx: &'a C) -> Self {
-        Self { req, body, ctx }
+    pub(crate) fn new(
+        req: &'a mut WebRequest<()>,
+        body: &'a mut RefCell<B>,
+        ctx: &'a C,
+        res_headers: &'a RefCell<ResponseHeaders>,
+    ) -> Self {
+        Self {
+            req,
+            body,
+            ctx,
+            res_headers,
+        }
     }
 
     /// Reborrow Self so the ownership of WebRequest is not lost.
@@ -51,6 +67,7 @@ impl<'a, C, B> WebContext<'a, C, B> {
             req: self.req,
             body: self.body,
             ctx: self.ctx,
+            res_headers: self.res_headers,
         }
     }
 
@@ -72,6 +89,13 @@ impl<'a, C, B> WebContext<'a, C, B> {
         self.req
     }
 
+    /// Get socket address of the connection peer. For request forwarded by proxy this is the
+    /// address of proxy rather than the client.
+    #[inline]
+    pub fn peer_addr(&self) -> SocketAddr {
+        *self.req.body().socket_addr()
+    }
+
     /// Get a immutable reference of [RequestBody]
     #[inline]
     pub fn body(&self) -> Ref<'_, B> {
@@ -117,6 +141,35 @@ impl<'a, C, B> WebContext<'a, C, B> {
         self.req.as_response(body.into())
     }
 
+    /// Stage a response header that would be inserted into final response produced by
+    /// [App](crate::App). Staged header replaces the header with the same name set by handler or
+    /// [Responder](crate::handler::Responder).
+    ///
+    /// Useful for middleware and handlers that want to add headers before the response exists.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use xitca_web::{http::header::{HeaderValue, CACHE_CONTROL}, WebContext};
+    /// async fn handler(ctx: &WebContext<'_>) -> &'static str {
+    ///     ctx.insert_response_header(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    ///     "the response would have cache-control header"
+    /// }
+    /// ```
+    pub fn insert_response_header(&self, name: HeaderName, value: HeaderValue) {
+        self.res_headers.borrow_mut().insert(name, value);
+    }
+
+    /// Stage a response header that would be appended to final response produced by
+    /// [App](crate::App). Existing headers with the same name are kept.
+    pub fn append_response_header(&self, name: HeaderName, value: HeaderValue) {
+        self.res_headers.borrow_mut().append(name, value);
+    }
+
+    /// Get a mutable reference of [ResponseHeaders] staged for final response.
+    pub fn response_headers(&self) -> RefMut<'_, ResponseHeaders> {
+        self.res_headers.borrow_mut()
+    }
+
     pub(crate) fn take_body_ref(&self) -> B
     where
         B: Default,
@@ -132,6 +185,49 @@ impl<'a, C, B> WebContext<'a, C, B> {
     }
 }
 
+/// Response headers staged before response is produced.
+///
+/// Merged into final response by [App](crate::App). See [WebContext::insert_response_header]
+/// and [WebContext::append_response_header].
+#[derive(Clone, Debug, Default)]
+pub struct ResponseHeaders {
+    insert: HeaderMap,
+    append: HeaderMap,
+}
+
+impl ResponseHeaders {
+    /// Stage header replacing the header with the same name in final response.
+    pub fn insert(&mut self, name: HeaderName, value: HeaderValue) {
+        self.append.remove(&name);
+        self.insert.insert(name, value);
+    }
+
+    /// Stage header appending to the headers with the same name in final response.
+    pub fn append(&mut self, name: HeaderName, value: HeaderValue) {
+        self.append.append(name, value);
+    }
+
+    /// Check if no header is staged.
+    pub fn is_empty(&self) -> bool {
+        self.insert.is_empty() && self.append.is_empty()
+    }
+
+    pub(crate) fn merge(self, headers: &mut HeaderMap) {
+        if self.is_empty() {
+            return;
+        }
+
+        for (name, value) in self.insert {
+            if let Some(name) = name {
+                headers.insert(name, value);
+            }
+        }
+        for (name, value) in self.append.iter() {
+            headers.append(name, value.clone());
+        }
+    }
+}
+
 #[cfg(test)]
 impl<C> WebContext<'_, C> {
     pub(crate) fn new_test(ctx: C) -> TestWebContext<C> {
@@ -139,6 +235,7 @@ impl<C> WebContext<'_, C> {
             req: Request::new(RequestExt::default()),
             body: RefCell::new(RequestBody::None),
             ctx,
+            res_headers: RefCell::default(),
         }
     }
 }
@@ -166,6 +263,7 @@ pub(crate) struct TestWebContext<C> {
     pub(crate) req: Request<RequestExt<()>>,
     pub(crate) body: RefCell<RequestBody>,
     pub(crate) ctx: C,
+    pub(crate) res_headers: RefCell<ResponseHeaders>,
 }
 
 #[cfg(test)]
@@ -175,6 +273,7 @@ impl<C> TestWebContext<C> {
             req: &mut self.req,
             body: &mut self.body,
             ctx: &self.ctx,
+            res_headers: &self.res_headers,
         }
     }
 }
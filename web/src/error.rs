@@ -1,5 +1,19 @@
 //! web error types.
 
+use core::{fmt, time::Duration};
+
+use std::error;
+
+use crate::{
+    context::WebContext,
+    handler::Responder,
+    http::{
+        const_header_value::{JSON, TEXT_UTF8},
+        header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, RETRY_AFTER},
+        StatusCode, WebResponse,
+    },
+};
+
 pub use xitca_http::{
     error::BodyError,
     util::service::{
@@ -7,3 +21,206 @@ pub use xitca_http::{
         router::{MatchError, RouterError},
     },
 };
+
+/// Backpressure error shared by middlewares and services rejecting requests for being over
+/// capacity.
+///
+/// Responds with `429 Too Many Requests` or `503 Service Unavailable` and a `Retry-After` header
+/// in whole seconds(rounded up) when retry delay is known. Request accepting `application/json`
+/// gets a JSON body in the form of `{"error":"<reason>","status":429,"retry_after":1}` where
+/// `retry_after` is `null` for unknown delay. Other requests get reason as plain text body.
+///
+/// # Examples:
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{error::Throttled, handler::handler_service, App, WebContext};
+/// async fn handler(_: &WebContext<'_>) -> Result<&'static str, Throttled> {
+///     Err(Throttled::too_many_requests("Quota exceeded.").retry_after(Duration::from_secs(30)))
+/// }
+///
+/// App::new().at("/", handler_service(handler));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Throttled {
+    status: StatusCode,
+    reason: &'static str,
+    retry_after: Option<Duration>,
+}
+
+impl Throttled {
+    /// Construct a `429 Too Many Requests` error for client exceeding it's own limit.
+    pub const fn too_many_requests(reason: &'static str) -> Self {
+        Self {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            reason,
+            retry_after: None,
+        }
+    }
+
+    /// Construct a `503 Service Unavailable` error for server being overloaded as a whole.
+    pub const fn service_unavailable(reason: &'static str) -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            reason,
+            retry_after: None,
+        }
+    }
+
+    /// Set the delay client should wait before retrying.
+    pub const fn retry_after(mut self, dur: Duration) -> Self {
+        self.retry_after = Some(dur);
+        self
+    }
+
+    /// Status code of response.
+    pub const fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Delay client should wait before retrying, if known.
+    pub const fn get_retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    fn retry_after_secs(&self) -> Option<u64> {
+        self.retry_after
+            .map(|dur| dur.as_secs() + u64::from(dur.subsec_nanos() > 0))
+    }
+}
+
+impl fmt::Display for Throttled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.reason)
+    }
+}
+
+impl error::Error for Throttled {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for Throttled {
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        let json = accepts_json(ctx.req().headers());
+
+        let secs = self.retry_after_secs();
+
+        let mut res = if json {
+            let mut body = String::from("{\"error\":");
+            push_json_str(&mut body, self.reason);
+            body.push_str(",\"status\":");
+            body.push_str(self.status.as_str());
+            body.push_str(",\"retry_after\":");
+            match secs {
+                Some(secs) => body.push_str(&secs.to_string()),
+                None => body.push_str("null"),
+            }
+            body.push('}');
+
+            let mut res = ctx.into_response(body);
+            res.headers_mut().insert(CONTENT_TYPE, JSON);
+            res
+        } else {
+            let mut res = ctx.into_response(self.reason);
+            res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+            res
+        };
+
+        *res.status_mut() = self.status;
+        if let Some(secs) = secs {
+            res.headers_mut().insert(RETRY_AFTER, HeaderValue::from(secs));
+        }
+        res
+    }
+}
+
+/// App wide format of response body rendered by [MatchError] and [MethodNotAllowed] responders.
+///
+/// Configured through [Extension](crate::middleware::Extension) middleware. By default the
+/// responders produce empty body. With [ErrorBody::Json] request accepting `application/json`
+/// gets a JSON body describing the error:
+/// - `404 Not Found`: `{"error":"route not found","status":404,"path":"/foo/","suggestion":"/foo"}`
+///   where `suggestion` is the path with trailing slash fixed or `null` when unknown.
+/// - `405 Method Not Allowed`: `{"error":"method not allowed","status":405,"allowed":["GET","POST"]}`
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{error::ErrorBody, handler::handler_service, middleware::Extension, App, WebContext};
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello" }))
+///     .enclosed(Extension::new(ErrorBody::Json));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ErrorBody {
+    /// Empty response body.
+    #[default]
+    Empty,
+    /// JSON response body for request accepting `application/json`.
+    Json,
+}
+
+impl ErrorBody {
+    // check if JSON body should be rendered for given request.
+    pub(crate) fn is_json<C, B>(ctx: &WebContext<'_, C, B>) -> bool {
+        matches!(ctx.req().extensions().get::<Self>(), Some(Self::Json)) && accepts_json(ctx.req().headers())
+    }
+}
+
+fn accepts_json(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.contains("application/json"))
+}
+
+// write string as quoted JSON string to buf.
+pub(crate) fn push_json_str(buf: &mut String, s: &str) {
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            c if c.is_control() => buf.push(' '),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::test::collect_string_body;
+
+    use super::*;
+
+    #[test]
+    fn throttled() {
+        let mut ctx = WebContext::new_test(());
+        let ctx = ctx.as_web_ctx();
+        let res = Throttled::too_many_requests("slow down")
+            .retry_after(Duration::from_millis(1500))
+            .respond_to(ctx)
+            .now_or_panic();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers().get(RETRY_AFTER).unwrap(), "2");
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), TEXT_UTF8);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, "slow down");
+
+        let mut ctx = WebContext::new_test(());
+        ctx.req
+            .headers_mut()
+            .insert(ACCEPT, HeaderValue::from_static("application/json"));
+        let ctx = ctx.as_web_ctx();
+        let res = Throttled::service_unavailable("\"busy\"")
+            .respond_to(ctx)
+            .now_or_panic();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(res.headers().get(RETRY_AFTER).is_none());
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), JSON);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, r#"{"error":"\"busy\"","status":503,"retry_after":null}"#);
+    }
+}
@@ -11,10 +11,12 @@ use crate::{
     bytes::Bytes,
     context::WebContext,
     error::BodyError,
-    http::{header::HeaderName, StatusCode, WebResponse},
+    http::{const_header_value::JSON, header::HeaderName, header::CONTENT_TYPE, StatusCode, WebResponse},
 };
 
-use super::Responder;
+#[cfg(feature = "signature")]
+use super::signature::SignatureError;
+use super::{valid::ValidationErrors, Responder};
 
 type BoxedError = Box<dyn error::Error + Send + Sync + 'static>;
 
@@ -30,6 +32,11 @@ pub enum ExtractError<E = BodyError> {
     HeaderNotFound(HeaderName),
     /// Error of parsing bytes to Rust types.
     Parse(ParseError),
+    /// Violations of [Validate](super::valid::Validate) from [Valid](super::valid::Valid) extractor.
+    Validate(ValidationErrors),
+    /// Rejected request of [SignedPayload](super::signature::SignedPayload) extractor.
+    #[cfg(feature = "signature")]
+    Signature(SignatureError),
     /// fallback boxed error type.
     Boxed(BoxedError),
 }
@@ -41,6 +48,9 @@ impl<E: fmt::Display> fmt::Display for ExtractError<E> {
             Self::ExtensionNotFound => f.write_str("Extension can not be found"),
             Self::HeaderNotFound(ref name) => write!(f, "HeaderName: {name} not found."),
             Self::Parse(ref e) => fmt::Display::fmt(e, f),
+            Self::Validate(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "signature")]
+            Self::Signature(ref e) => fmt::Display::fmt(e, f),
             Self::Boxed(ref e) => fmt::Display::fmt(e, f),
         }
     }
@@ -58,9 +68,34 @@ impl<'r, C, B, E> Responder<WebContext<'r, C, B>> for ExtractError<E> {
     type Output = WebResponse;
 
     async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
-        let mut res = ctx.into_response(Bytes::new());
-        *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
-        res
+        match self {
+            Self::Validate(e) => {
+                let mut res = ctx.into_response(e.to_json());
+                res.headers_mut().insert(CONTENT_TYPE, JSON);
+                *res.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+                res
+            }
+            #[cfg(feature = "signature")]
+            Self::Signature(e) => e.respond_to(ctx).await,
+            _ => {
+                let mut res = ctx.into_response(Bytes::new());
+                *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                res
+            }
+        }
+    }
+}
+
+impl<E> From<ValidationErrors> for ExtractError<E> {
+    fn from(e: ValidationErrors) -> Self {
+        Self::Validate(e)
+    }
+}
+
+#[cfg(feature = "signature")]
+impl<E> From<SignatureError> for ExtractError<E> {
+    fn from(e: SignatureError) -> Self {
+        Self::Signature(e)
     }
 }
 
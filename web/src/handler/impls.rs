@@ -6,9 +6,9 @@ use crate::{
     body::BodyStream,
     bytes::Bytes,
     context::WebContext,
-    error::{MatchError, MethodNotAllowed},
+    error::{push_json_str, ErrorBody, MatchError, MethodNotAllowed},
     http::{
-        const_header_value::TEXT_UTF8,
+        const_header_value::{JSON, TEXT_UTF8},
         header::{ALLOW, CONTENT_TYPE},
         StatusCode, WebResponse,
     },
@@ -140,7 +140,28 @@ impl<'r, C, B> Responder<WebContext<'r, C, B>> for MatchError {
     type Output = WebResponse;
 
     async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
-        let mut res = ctx.into_response(Bytes::new());
+        let mut res = if ErrorBody::is_json(&ctx) {
+            let path = ctx.req().uri().path();
+
+            let suggestion = match self {
+                MatchError::MissingTrailingSlash => Some(format!("{path}/")),
+                MatchError::ExtraTrailingSlash => path.strip_suffix('/').map(String::from),
+                MatchError::NotFound => None,
+            };
+
+            let mut body = String::from("{\"error\":\"route not found\",\"status\":404,\"path\":");
+            push_json_str(&mut body, path);
+            body.push_str(",\"suggestion\":");
+            match suggestion {
+                Some(ref suggestion) => push_json_str(&mut body, suggestion),
+                None => body.push_str("null"),
+            }
+            body.push('}');
+
+            json_response(ctx, body)
+        } else {
+            ctx.into_response(Bytes::new())
+        };
         *res.status_mut() = StatusCode::NOT_FOUND;
         res
     }
@@ -150,8 +171,6 @@ impl<'r, C, B> Responder<WebContext<'r, C, B>> for MethodNotAllowed {
     type Output = WebResponse;
 
     async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
-        let mut res = ctx.into_response(Bytes::new());
-
         let allowed = self.allowed_methods();
 
         let len = allowed.iter().fold(0, |a, m| a + m.as_str().len() + 1);
@@ -164,6 +183,22 @@ impl<'r, C, B> Responder<WebContext<'r, C, B>> for MethodNotAllowed {
         }
         methods.pop();
 
+        let mut res = if ErrorBody::is_json(&ctx) {
+            let mut body = String::from("{\"error\":\"method not allowed\",\"status\":405,\"allowed\":[");
+            for method in allowed {
+                push_json_str(&mut body, method.as_str());
+                body.push(',');
+            }
+            if !allowed.is_empty() {
+                body.pop();
+            }
+            body.push_str("]}");
+
+            json_response(ctx, body)
+        } else {
+            ctx.into_response(Bytes::new())
+        };
+
         res.headers_mut().insert(ALLOW, methods.parse().unwrap());
         *res.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
 
@@ -171,11 +206,30 @@ impl<'r, C, B> Responder<WebContext<'r, C, B>> for MethodNotAllowed {
     }
 }
 
+fn json_response<C, B>(ctx: WebContext<'_, C, B>, body: String) -> WebResponse {
+    let mut res = ctx.into_response(body);
+    res.headers_mut().insert(CONTENT_TYPE, JSON);
+    res
+}
+
 #[cfg(test)]
 mod test {
-    use xitca_http::BodyError;
+    use xitca_http::{BodyError, RequestBody};
     use xitca_unsafe_collection::futures::NowOrPanic;
 
+    use crate::{
+        dev::service::Service,
+        handler::handler_service,
+        http::{
+            header::{HeaderValue, ACCEPT},
+            Method, Request, RequestExt, Uri,
+        },
+        middleware::Extension,
+        route::get,
+        test::collect_string_body,
+        App,
+    };
+
     use super::*;
 
     #[test]
@@ -194,4 +248,58 @@ mod test {
 
         <()>::from_request(&req).now_or_panic().unwrap();
     }
+
+    #[test]
+    fn error_body_json() {
+        async fn index() -> &'static str {
+            "996"
+        }
+
+        let service = App::new()
+            .at("/foo", get(handler_service(index)))
+            .enclosed(Extension::new(ErrorBody::Json))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let req = |method, uri, json| {
+            let mut req = Request::new(RequestExt::<RequestBody>::default());
+            *req.method_mut() = method;
+            *req.uri_mut() = Uri::from_static(uri);
+            if json {
+                req.headers_mut()
+                    .insert(ACCEPT, HeaderValue::from_static("application/json"));
+            }
+            req
+        };
+
+        let res = service.call(req(Method::POST, "/foo", true)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers().get(ALLOW).unwrap(), "GET");
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), JSON);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, r#"{"error":"method not allowed","status":405,"allowed":["GET"]}"#);
+
+        let res = service.call(req(Method::GET, "/foo/", true)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(
+            body,
+            r#"{"error":"route not found","status":404,"path":"/foo/","suggestion":"/foo"}"#
+        );
+
+        let res = service.call(req(Method::GET, "/bar", true)).now_or_panic().unwrap();
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(
+            body,
+            r#"{"error":"route not found","status":404,"path":"/bar","suggestion":null}"#
+        );
+
+        // client not accepting json gets empty body.
+        let res = service.call(req(Method::GET, "/bar", false)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert!(body.is_empty());
+    }
 }
@@ -12,6 +12,6 @@ pub use error::ExtractError;
 pub use types::*;
 
 #[cfg(not(target_family = "wasm"))]
-pub use sync::handler_sync_service;
+pub use sync::{handler_sync_service, HandlerServiceSync, QueueFull, SyncPool, SyncPoolBuilder};
 
 pub use xitca_http::util::service::handler::{handler_service, FromRequest, Responder};
@@ -1,14 +1,21 @@
 #![allow(non_snake_case)]
 
-use core::{
-    convert::Infallible,
-    future::{ready, Ready},
-    marker::PhantomData,
+use core::{convert::Infallible, fmt, marker::PhantomData, time::Duration};
+
+use std::{
+    error,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
 };
 
-use super::{FromRequest, Responder};
+use tokio::sync::{oneshot, Semaphore};
+use xitca_http::util::service::router::{RouterGen, RouterMapErr};
+use xitca_service::Service;
 
-use xitca_service::{fn_build, FnService, Service};
+use crate::{context::WebContext, error::Throttled, http::WebResponse};
+
+use super::{FromRequest, Responder};
 
 /// synchronous version of [handler_service]
 ///
@@ -17,6 +24,9 @@ use xitca_service::{fn_build, FnService, Service};
 /// compared to [handler_service] where the arguments must be types that impl [FromRequest] trait,
 /// being thread safe with `Send` trait bound and with `'static` lifetime.
 ///
+/// By default function is run on tokio's global blocking thread pool. See [HandlerServiceSync::with_pool]
+/// for running it on a dedicated and bounded thread pool.
+///
 /// # Examples:
 /// ```rust
 /// # use xitca_web::{
@@ -38,23 +48,99 @@ use xitca_service::{fn_build, FnService, Service};
 /// ```
 ///
 /// [handler_service]: super::handler_service
-pub fn handler_sync_service<Arg, F, T, O>(
-    func: F,
-) -> FnService<impl Fn(Arg) -> Ready<Result<HandlerServiceSync<F, T, O>, Infallible>>>
+pub fn handler_sync_service<F, T, O>(func: F) -> HandlerServiceSync<F, T, O, marker::BuilderMark>
 where
     F: Closure<T> + Send + Clone,
 {
-    fn_build(move |_| {
-        ready(Ok(HandlerServiceSync {
-            func: func.clone(),
-            _p: PhantomData,
-        }))
-    })
+    HandlerServiceSync {
+        func,
+        pool: None,
+        _p: PhantomData,
+    }
 }
 
-pub struct HandlerServiceSync<F, T, O> {
+pub struct HandlerServiceSync<F, T, O, M = marker::ServiceMark> {
     func: F,
-    _p: PhantomData<(T, O)>,
+    pool: Option<SyncPool>,
+    _p: PhantomData<fn(T, O, M)>,
+}
+
+// marker for specialized trait implement on HandlerServiceSync
+mod marker {
+    pub struct BuilderMark;
+    pub struct PoolBuilderMark;
+    pub struct ServiceMark;
+    pub struct PoolServiceMark;
+}
+
+impl<F, T, O> HandlerServiceSync<F, T, O, marker::BuilderMark> {
+    /// Run function on given [SyncPool] instead of tokio's global blocking thread pool.
+    ///
+    /// When the pool is busy and it's queue stays full for longer than [SyncPoolBuilder::spawn_timeout]
+    /// the request would be responded with `503 Service Unavailable`.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use xitca_web::{handler::{handler_service, handler_sync_service, uri::UriOwn, SyncPool}, App, WebContext};
+    /// let pool = SyncPool::builder()
+    ///     .size(4)
+    ///     .queue_depth(64)
+    ///     .spawn_timeout(Duration::from_millis(500))
+    ///     .build();
+    ///
+    /// App::new()
+    ///     .at("/", handler_sync_service(|_: UriOwn| "run on dedicated pool").with_pool(pool.clone()))
+    ///     .at("/foo", handler_sync_service(|_: UriOwn| "pool is shared").with_pool(pool))
+    ///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "" }));
+    /// ```
+    pub fn with_pool(self, pool: SyncPool) -> HandlerServiceSync<F, T, O, marker::PoolBuilderMark> {
+        HandlerServiceSync {
+            func: self.func,
+            pool: Some(pool),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<F, T, O> Service for HandlerServiceSync<F, T, O, marker::BuilderMark>
+where
+    F: Clone,
+{
+    type Response = HandlerServiceSync<F, T, O>;
+    type Error = Infallible;
+
+    async fn call(&self, _: ()) -> Result<Self::Response, Self::Error> {
+        Ok(HandlerServiceSync {
+            func: self.func.clone(),
+            pool: None,
+            _p: PhantomData,
+        })
+    }
+}
+
+impl<F, T, O> Service for HandlerServiceSync<F, T, O, marker::PoolBuilderMark>
+where
+    F: Clone,
+{
+    type Response = HandlerServiceSync<F, T, O, marker::PoolServiceMark>;
+    type Error = Infallible;
+
+    async fn call(&self, _: ()) -> Result<Self::Response, Self::Error> {
+        Ok(HandlerServiceSync {
+            func: self.func.clone(),
+            pool: self.pool.clone(),
+            _p: PhantomData,
+        })
+    }
+}
+
+impl<F, T, O, M> RouterGen for HandlerServiceSync<F, T, O, M> {
+    type ErrGen<R> = RouterMapErr<R>;
+
+    fn err_gen<R>(route: R) -> Self::ErrGen<R> {
+        RouterMapErr(route)
+    }
 }
 
 impl<F, Req, T, O> Service<Req> for HandlerServiceSync<F, T, O>
@@ -78,6 +164,183 @@ where
     }
 }
 
+impl<F, Req, T, O> Service<Req> for HandlerServiceSync<F, T, O, marker::PoolServiceMark>
+where
+    T: FromRequest<'static, Req>,
+    F: Closure<T> + Send + Clone + 'static,
+    F: for<'a> Closure<T::Type<'a>, Output = O>,
+    O: Responder<Req> + Send + 'static,
+    for<'a> T::Type<'a>: Send + 'static,
+    // pool rejects function with QueueFull when it's busy.
+    QueueFull: Responder<Req, Output = O::Output>,
+{
+    type Response = O::Output;
+    type Error = T::Error;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let extract = T::Type::<'_>::from_request(&req).await?;
+        let func = self.func.clone();
+        let res = match self.pool {
+            Some(ref pool) => match pool.spawn(move || func.call(extract)).await {
+                Ok(res) => res,
+                Err(e) => return Ok(e.respond_to(req).await),
+            },
+            None => tokio::task::spawn_blocking(move || func.call(extract)).await.unwrap(),
+        };
+        Ok(res.respond_to(req).await)
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A dedicated and bounded thread pool for [handler_sync_service]. See [HandlerServiceSync::with_pool].
+///
+/// Cloned pool shares the same threads and queue. Threads are shutdown when all pools are dropped
+/// and queued functions are finished.
+#[derive(Clone)]
+pub struct SyncPool {
+    tx: mpsc::Sender<Job>,
+    permits: Arc<Semaphore>,
+    spawn_timeout: Duration,
+}
+
+impl SyncPool {
+    /// construct a builder for pool with default configuration.
+    pub fn builder() -> SyncPoolBuilder {
+        SyncPoolBuilder::new()
+    }
+
+    async fn spawn<F, R>(&self, func: F) -> Result<R, QueueFull>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let permit = tokio::time::timeout(self.spawn_timeout, self.permits.clone().acquire_owned())
+            .await
+            .map_err(|_| QueueFull)?
+            .expect("SyncPool semaphore must not be closed");
+
+        let (tx, rx) = oneshot::channel();
+
+        self.tx
+            .send(Box::new(move || {
+                let res = panic::catch_unwind(AssertUnwindSafe(func));
+                drop(permit);
+                let _ = tx.send(res);
+            }))
+            .expect("SyncPool threads must outlive pool");
+
+        match rx.await.expect("SyncPool must finish spawned function") {
+            Ok(res) => Ok(res),
+            Err(e) => panic::resume_unwind(e),
+        }
+    }
+}
+
+/// builder type for [SyncPool].
+pub struct SyncPoolBuilder {
+    size: usize,
+    queue_depth: usize,
+    spawn_timeout: Duration,
+}
+
+impl Default for SyncPoolBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncPoolBuilder {
+    /// construct a new builder with default configuration.
+    ///
+    /// - pool size equals to available parallelism of current machine.
+    /// - queue depth is 256.
+    /// - spawn timeout is 3 seconds.
+    pub fn new() -> Self {
+        Self {
+            size: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            queue_depth: 256,
+            spawn_timeout: Duration::from_secs(3),
+        }
+    }
+
+    /// Set number of threads in pool.
+    ///
+    /// # Panics:
+    /// When size is 0.
+    pub fn size(mut self, size: usize) -> Self {
+        assert_ne!(size, 0, "SyncPool must have at least one thread");
+        self.size = size;
+        self
+    }
+
+    /// Set max number of functions waiting in queue when all threads are busy.
+    pub fn queue_depth(mut self, queue_depth: usize) -> Self {
+        self.queue_depth = queue_depth;
+        self
+    }
+
+    /// Set max duration a function waits for entering the queue when it's full.
+    /// After the duration passed request would be responded with `503 Service Unavailable`.
+    pub fn spawn_timeout(mut self, dur: Duration) -> Self {
+        self.spawn_timeout = dur;
+        self
+    }
+
+    /// Finish builder and start threads of the pool.
+    pub fn build(self) -> SyncPool {
+        let (tx, rx) = mpsc::channel::<Job>();
+        let rx = Arc::new(Mutex::new(rx));
+
+        for i in 0..self.size {
+            let rx = rx.clone();
+            thread::Builder::new()
+                .name(format!("xitca-web-sync-{i}"))
+                .spawn(move || loop {
+                    // lock guard must be dropped before running job.
+                    let job = match rx.lock().unwrap().recv() {
+                        Ok(job) => job,
+                        Err(_) => return,
+                    };
+                    job();
+                })
+                .expect("failed to spawn SyncPool thread");
+        }
+
+        SyncPool {
+            tx,
+            permits: Arc::new(Semaphore::new(self.size + self.queue_depth)),
+            spawn_timeout: self.spawn_timeout,
+        }
+    }
+}
+
+/// error type when [SyncPool] is busy and it's queue is full.
+#[derive(Debug)]
+pub struct QueueFull;
+
+impl fmt::Display for QueueFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SyncPool queue is full")
+    }
+}
+
+impl error::Error for QueueFull {}
+
+impl From<QueueFull> for Throttled {
+    fn from(_: QueueFull) -> Self {
+        Throttled::service_unavailable("SyncPool queue is full")
+    }
+}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for QueueFull {
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        Throttled::from(self).respond_to(ctx).await
+    }
+}
+
 #[doc(hidden)]
 /// sync version of xitca_service::AsyncClosure trait.
 pub trait Closure<Arg> {
@@ -112,3 +375,103 @@ closure_impl! { A, B, C, D, E, F }
 closure_impl! { A, B, C, D, E, F, G }
 closure_impl! { A, B, C, D, E, F, G, H }
 closure_impl! { A, B, C, D, E, F, G, H, I }
+closure_impl! { A, B, C, D, E, F, G, H, I, J }
+closure_impl! { A, B, C, D, E, F, G, H, I, J, K }
+closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L }
+closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M }
+closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, N }
+// O is taken by output type.
+closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, N, P }
+closure_impl! { A, B, C, D, E, F, G, H, I, J, K, L, M, N, P, Q }
+
+#[cfg(test)]
+mod test {
+    use core::{
+        future::{poll_fn, Future},
+        pin::pin,
+        task::Poll,
+    };
+
+    use std::sync::Barrier;
+
+    use crate::{
+        body::RequestBody,
+        http::{StatusCode, WebRequest},
+        App,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn foreign_request() {
+        // request type QueueFull can not respond to.
+        struct Req(u8);
+
+        struct Num(u8);
+
+        impl<'a> FromRequest<'a, Req> for Num {
+            type Type<'b> = Num;
+            type Error = Infallible;
+
+            async fn from_request(req: &'a Req) -> Result<Self, Self::Error> {
+                Ok(Num(req.0))
+            }
+        }
+
+        struct Double(u8);
+
+        impl Responder<Req> for Double {
+            type Output = u8;
+
+            async fn respond_to(self, _: Req) -> Self::Output {
+                self.0 * 2
+            }
+        }
+
+        let service = handler_sync_service(|Num(n)| Double(n)).call(()).await.unwrap();
+        assert_eq!(service.call(Req(3)).await.unwrap(), 6);
+    }
+
+    #[tokio::test]
+    async fn pool_queue_full() {
+        let pool = SyncPool::builder()
+            .size(1)
+            .queue_depth(0)
+            .spawn_timeout(Duration::ZERO)
+            .build();
+
+        let barrier = Arc::new(Barrier::new(2));
+        let barrier2 = barrier.clone();
+
+        let service = App::new()
+            .at(
+                "/",
+                handler_sync_service(move || {
+                    barrier2.wait();
+                    "996"
+                })
+                .with_pool(pool),
+            )
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let mut fut = pin!(service.call(WebRequest::<RequestBody>::default()));
+
+        // first request occupies the only thread of pool.
+        poll_fn(|cx| {
+            assert!(fut.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        let res = service.call(WebRequest::<RequestBody>::default()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        barrier.wait();
+
+        let res = fut.await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
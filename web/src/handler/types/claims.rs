@@ -0,0 +1,56 @@
+//! type extractor for claims of authenticated request.
+
+use core::{fmt, ops::Deref};
+
+use std::sync::Arc;
+
+use crate::{
+    body::BodyStream,
+    context::WebContext,
+    handler::{error::ExtractError, FromRequest},
+};
+
+/// Extract claims of bearer token validated by [Bearer](crate::middleware::auth::Bearer)
+/// middleware.
+///
+/// Extracting from request exempted from authentication or enclosed by no middleware results in
+/// [ExtractError::ExtensionNotFound] error.
+pub struct Claims<T>(pub Arc<T>);
+
+impl<T> Clone for Claims<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Claims<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Claims({:?})", self.0)
+    }
+}
+
+impl<T> Deref for Claims<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, 'r, C, B, T> FromRequest<'a, WebContext<'r, C, B>> for Claims<T>
+where
+    T: Send + Sync + 'static,
+    B: BodyStream,
+{
+    type Type<'b> = Claims<T>;
+    type Error = ExtractError<B::Error>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        ctx.req()
+            .extensions()
+            .get::<Claims<T>>()
+            .cloned()
+            .ok_or(ExtractError::ExtensionNotFound)
+    }
+}
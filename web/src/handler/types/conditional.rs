@@ -0,0 +1,355 @@
+//! conditional and range request evaluation for buffered response.
+
+use core::{fmt, str::FromStr};
+
+use httpdate::HttpDate;
+
+use crate::{
+    body::ResponseBody,
+    context::WebContext,
+    handler::Responder,
+    http::{
+        header::{
+            HeaderMap, HeaderValue, ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, ETAG, IF_MODIFIED_SINCE,
+            IF_NONE_MATCH, IF_RANGE, LAST_MODIFIED, RANGE,
+        },
+        Method, StatusCode, WebResponse,
+    },
+};
+
+/// A wrapper type evaluating conditional and range request headers against response produced by
+/// inner [Responder].
+///
+/// Validators are taken from `ETag` and `Last-Modified` headers of inner response. Request with
+/// matching `If-None-Match` (or `If-Modified-Since` when the former is absent) is responded with
+/// `304 Not Modified`. Single `Range` of bytes (guarded by optional `If-Range`) is responded
+/// with `206 Partial Content` or `416 Range Not Satisfiable`.
+///
+/// Only `GET` and `HEAD` request with `200 OK` response of fully buffered body is evaluated.
+/// Streaming response is passed through untouched.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{conditional::WithConditional, handler_service},
+/// #   http::{header::{HeaderValue, ETAG}, WebResponse},
+/// #   body::ResponseBody,
+/// #   route::get,
+/// #   App, WebContext
+/// # };
+/// async fn report(_: &WebContext<'_>) -> WithConditional<WebResponse> {
+///     let mut res = WebResponse::new(ResponseBody::from("monthly report"));
+///     res.headers_mut().insert(ETAG, HeaderValue::from_static("\"v1\""));
+///     WithConditional(res)
+/// }
+///
+/// App::new().at("/report", get(handler_service(report)));
+/// ```
+pub struct WithConditional<T>(pub T);
+
+impl<T> fmt::Debug for WithConditional<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithConditional").field("value", &self.0).finish()
+    }
+}
+
+impl<'r, C, B, T> Responder<WebContext<'r, C, B>> for WithConditional<T>
+where
+    T: Responder<WebContext<'r, C, B>, Output = WebResponse>,
+{
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        let cond = Conditions::new(ctx.req().method(), ctx.req().headers());
+        let res = self.0.respond_to(ctx).await;
+        match cond {
+            Some(cond) => cond.evaluate(res),
+            None => res,
+        }
+    }
+}
+
+// request headers copied before request is consumed by inner responder.
+struct Conditions {
+    if_none_match: Option<HeaderValue>,
+    if_modified_since: Option<HeaderValue>,
+    range: Option<HeaderValue>,
+    if_range: Option<HeaderValue>,
+}
+
+impl Conditions {
+    fn new(method: &Method, headers: &HeaderMap) -> Option<Self> {
+        if !matches!(*method, Method::GET | Method::HEAD) {
+            return None;
+        }
+
+        Some(Self {
+            if_none_match: headers.get(IF_NONE_MATCH).cloned(),
+            if_modified_since: headers.get(IF_MODIFIED_SINCE).cloned(),
+            range: headers.get(RANGE).cloned(),
+            if_range: headers.get(IF_RANGE).cloned(),
+        })
+    }
+
+    fn evaluate(self, res: WebResponse) -> WebResponse {
+        if res.status() != StatusCode::OK {
+            return res;
+        }
+
+        let (mut parts, body) = res.into_parts();
+
+        let bytes = match body {
+            ResponseBody::Bytes { bytes } => bytes,
+            body => return WebResponse::from_parts(parts, body),
+        };
+
+        if self.not_modified(&parts.headers) {
+            parts.status = StatusCode::NOT_MODIFIED;
+            parts.headers.remove(CONTENT_LENGTH);
+            return WebResponse::from_parts(parts, ResponseBody::None);
+        }
+
+        parts.headers.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+        let Some(range) = self.range.as_ref().and_then(|v| v.to_str().ok()) else {
+            return WebResponse::from_parts(parts, ResponseBody::Bytes { bytes });
+        };
+
+        if let Some(ref if_range) = self.if_range {
+            if !if_range_match(if_range, &parts.headers) {
+                return WebResponse::from_parts(parts, ResponseBody::Bytes { bytes });
+            }
+        }
+
+        let len = bytes.len();
+        let body = match parse_range(range, len) {
+            ByteRange::Ignore => ResponseBody::Bytes { bytes },
+            ByteRange::Satisfiable(start, end) => {
+                parts.status = StatusCode::PARTIAL_CONTENT;
+                parts.headers.remove(CONTENT_LENGTH);
+                parts
+                    .headers
+                    .insert(CONTENT_RANGE, header_value(format!("bytes {start}-{end}/{len}")));
+                ResponseBody::Bytes {
+                    bytes: bytes.slice(start..=end),
+                }
+            }
+            ByteRange::Unsatisfiable => {
+                parts.status = StatusCode::RANGE_NOT_SATISFIABLE;
+                parts.headers.remove(CONTENT_LENGTH);
+                parts
+                    .headers
+                    .insert(CONTENT_RANGE, header_value(format!("bytes */{len}")));
+                ResponseBody::None
+            }
+        };
+
+        WebResponse::from_parts(parts, body)
+    }
+
+    fn not_modified(&self, headers: &HeaderMap) -> bool {
+        not_modified(self.if_none_match.as_ref(), self.if_modified_since.as_ref(), headers)
+    }
+}
+
+/// evaluate `If-None-Match` and `If-Modified-Since` request headers against validators of
+/// response headers.
+pub(crate) fn not_modified(
+    if_none_match: Option<&HeaderValue>,
+    if_modified_since: Option<&HeaderValue>,
+    headers: &HeaderMap,
+) -> bool {
+    // If-Modified-Since is ignored when If-None-Match is present according to RFC 9110.
+    match if_none_match {
+        Some(tags) => match (tags.to_str(), headers.get(ETAG)) {
+            (Ok(tags), Some(etag)) => etag_match(tags, etag.as_bytes()),
+            _ => false,
+        },
+        None => match (
+            to_http_date(if_modified_since),
+            to_http_date(headers.get(LAST_MODIFIED)),
+        ) {
+            (Some(since), Some(modified)) => modified <= since,
+            _ => false,
+        },
+    }
+}
+
+/// weak comparison of `If-None-Match` header value against entity tag according to RFC 9110.
+pub(crate) fn etag_match(tags: &str, etag: &[u8]) -> bool {
+    let etag = etag.strip_prefix(b"W/").unwrap_or(etag);
+    tags.trim() == "*"
+        || tags
+            .split(',')
+            .map(|tag| tag.trim())
+            .any(|tag| tag.strip_prefix("W/").unwrap_or(tag).as_bytes() == etag)
+}
+
+// If-Range is either a strong entity tag or a date that must exactly match the validator.
+fn if_range_match(if_range: &HeaderValue, headers: &HeaderMap) -> bool {
+    let bytes = if_range.as_bytes();
+    if bytes.starts_with(b"\"") {
+        headers.get(ETAG).is_some_and(|etag| etag.as_bytes() == bytes)
+    } else if bytes.starts_with(b"W/") {
+        false
+    } else {
+        match (to_http_date(Some(if_range)), to_http_date(headers.get(LAST_MODIFIED))) {
+            (Some(date), Some(modified)) => date == modified,
+            _ => false,
+        }
+    }
+}
+
+enum ByteRange {
+    Ignore,
+    Satisfiable(usize, usize),
+    Unsatisfiable,
+}
+
+// parse single range of bytes. multiple ranges and malformed header are ignored and full
+// response is sent instead.
+fn parse_range(range: &str, len: usize) -> ByteRange {
+    let Some(spec) = range.trim().strip_prefix("bytes=") else {
+        return ByteRange::Ignore;
+    };
+
+    if spec.contains(',') {
+        return ByteRange::Ignore;
+    }
+
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return ByteRange::Ignore;
+    };
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return ByteRange::Ignore,
+        // suffix range of last n bytes.
+        ("", suffix) => match suffix.parse::<usize>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(n) => (len.saturating_sub(n), len.wrapping_sub(1)),
+            Err(_) => return ByteRange::Ignore,
+        },
+        (start, "") => match start.parse::<usize>() {
+            Ok(start) => (start, len.wrapping_sub(1)),
+            Err(_) => return ByteRange::Ignore,
+        },
+        (start, end) => match (start.parse::<usize>(), end.parse::<usize>()) {
+            (Ok(start), Ok(end)) if start <= end => (start, end.min(len.wrapping_sub(1))),
+            _ => return ByteRange::Ignore,
+        },
+    };
+
+    if start >= len {
+        ByteRange::Unsatisfiable
+    } else {
+        ByteRange::Satisfiable(start, end)
+    }
+}
+
+fn to_http_date(header: Option<&HeaderValue>) -> Option<HttpDate> {
+    header
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| HttpDate::from_str(v).ok())
+}
+
+fn header_value(value: String) -> HeaderValue {
+    // formatted numbers are always valid header value.
+    HeaderValue::try_from(value).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{bytes::Bytes, http::header::HeaderName, test::collect_body};
+
+    use super::*;
+
+    const LAST: &str = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+    struct Report;
+
+    impl<'r, C, B> Responder<WebContext<'r, C, B>> for Report {
+        type Output = WebResponse;
+
+        async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+            let mut res = ctx.into_response(Bytes::from_static(b"0123456789"));
+            res.headers_mut().insert(ETAG, HeaderValue::from_static("\"v1\""));
+            res.headers_mut().insert(LAST_MODIFIED, HeaderValue::from_static(LAST));
+            res
+        }
+    }
+
+    fn respond(headers: &[(HeaderName, &'static str)]) -> WebResponse {
+        let mut ctx = WebContext::new_test(());
+        for (name, value) in headers {
+            ctx.req.headers_mut().insert(name, HeaderValue::from_static(value));
+        }
+        let res = WithConditional(Report).respond_to(ctx.as_web_ctx()).now_or_panic();
+        res
+    }
+
+    fn body(res: WebResponse) -> Vec<u8> {
+        collect_body(res.into_body()).now_or_panic().unwrap()
+    }
+
+    #[test]
+    fn not_modified() {
+        let res = respond(&[]);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(body(res), b"0123456789");
+
+        let res = respond(&[(IF_NONE_MATCH, "\"v0\", W/\"v1\"")]);
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(ETAG).unwrap(), "\"v1\"");
+        assert!(body(res).is_empty());
+
+        let res = respond(&[(IF_MODIFIED_SINCE, LAST)]);
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+        let res = respond(&[(IF_NONE_MATCH, "\"v0\""), (IF_MODIFIED_SINCE, LAST)]);
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = respond(&[(IF_MODIFIED_SINCE, "Sat, 05 Nov 1994 08:49:37 GMT")]);
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn range() {
+        let res = respond(&[(RANGE, "bytes=2-4")]);
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes 2-4/10");
+        assert_eq!(body(res), b"234");
+
+        let res = respond(&[(RANGE, "bytes=7-")]);
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes 7-9/10");
+        assert_eq!(body(res), b"789");
+
+        let res = respond(&[(RANGE, "bytes=-3")]);
+        assert_eq!(body(res), b"789");
+
+        let res = respond(&[(RANGE, "bytes=8-100")]);
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes 8-9/10");
+
+        let res = respond(&[(RANGE, "bytes=10-")]);
+        assert_eq!(res.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes */10");
+
+        let res = respond(&[(RANGE, "bytes=0-1,4-5")]);
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = respond(&[(RANGE, "bytes=2-4"), (IF_RANGE, "\"v1\"")]);
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+
+        let res = respond(&[(RANGE, "bytes=2-4"), (IF_RANGE, LAST)]);
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+
+        let res = respond(&[(RANGE, "bytes=2-4"), (IF_RANGE, "\"v0\"")]);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(body(res), b"0123456789");
+    }
+}
@@ -0,0 +1,165 @@
+//! response generator for long polling.
+
+use core::{fmt, future::Future, time::Duration};
+
+use tokio::sync::watch;
+use xitca_unsafe_collection::futures::{Select, SelectOutput};
+
+use crate::{
+    bytes::Bytes,
+    context::WebContext,
+    handler::Responder,
+    http::{StatusCode, WebResponse},
+};
+
+/// Default max duration a long polling request waits for update.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A wrapper type waiting for an update up to a deadline and responding with it.
+///
+/// Update is produced by inner future where `Some(T)` is responded with `T`'s [Responder] and
+/// `None` (nothing to deliver) is responded with `204 No Content`. Reaching deadline without
+/// update is responded with `204 No Content` too so client can start next poll.
+///
+/// # Cancel safety
+/// When client disconnects the connection dispatcher drops handler future and `LongPoll` with
+/// it. Update would not be lost in this case as long as inner future is cancel safe. The
+/// [watch()] constructor is cancel safe: value of [watch::Receiver] is only marked as
+/// seen when it's about to be responded.
+///
+/// # Examples:
+/// ```rust
+/// # use core::time::Duration;
+/// # use std::sync::Arc;
+/// # use tokio::sync::watch;
+/// # use xitca_web::{
+/// #   handler::{handler_service, long_poll::{self, LongPoll}},
+/// #   route::get,
+/// #   App, WebContext
+/// # };
+/// # use core::future::Future;
+/// async fn poll(
+///     ctx: &WebContext<'_, Arc<watch::Sender<String>>>,
+/// ) -> LongPoll<impl Future<Output = Option<String>>> {
+///     // new subscriber treats current value as seen and waits for the next one.
+///     long_poll::watch(ctx.state().subscribe()).timeout(Duration::from_secs(10))
+/// }
+///
+/// let (tx, _) = watch::channel(String::new());
+///
+/// App::with_state(Arc::new(tx)).at("/poll", get(handler_service(poll)));
+/// ```
+pub struct LongPoll<F> {
+    fut: F,
+    timeout: Duration,
+}
+
+impl<F> fmt::Debug for LongPoll<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LongPoll").field("timeout", &self.timeout).finish()
+    }
+}
+
+impl<F, T> LongPoll<F>
+where
+    F: Future<Output = Option<T>>,
+{
+    /// Construct from a future resolving to optional update.
+    ///
+    /// See [DEFAULT_TIMEOUT] for default deadline.
+    pub fn new(fut: F) -> Self {
+        Self {
+            fut,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Set max duration waiting for update.
+    pub fn timeout(mut self, dur: Duration) -> Self {
+        self.timeout = dur;
+        self
+    }
+}
+
+impl<'r, C, B, F, T> Responder<WebContext<'r, C, B>> for LongPoll<F>
+where
+    F: Future<Output = Option<T>>,
+    T: Responder<WebContext<'r, C, B>, Output = WebResponse>,
+{
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        match self.fut.select(tokio::time::sleep(self.timeout)).await {
+            SelectOutput::A(Some(value)) => value.respond_to(ctx).await,
+            SelectOutput::A(None) | SelectOutput::B(_) => {
+                let mut res = ctx.into_response(Bytes::new());
+                *res.status_mut() = StatusCode::NO_CONTENT;
+                res
+            }
+        }
+    }
+}
+
+/// Construct [LongPoll] from a [watch::Receiver] resolving to it's value when it's changed.
+///
+/// Value already seen by receiver is not considered as update. Note that a cloned receiver
+/// inherits seen state from the original one while [watch::Sender::subscribe] produces receiver
+/// having current value seen. Closed channel resolves to `None`
+/// immediately.
+pub fn watch<T>(mut rx: watch::Receiver<T>) -> LongPoll<impl Future<Output = Option<T>>>
+where
+    T: Clone,
+{
+    LongPoll::new(async move {
+        // changed is cancel safe and value is only marked as seen after it resolves.
+        rx.changed().await.ok()?;
+        Some(rx.borrow_and_update().clone())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{dev::service::Service, handler::handler_service, http::WebRequest, test::collect_string_body, App};
+
+    use super::*;
+
+    async fn poll(
+        ctx: &WebContext<'_, Arc<watch::Sender<&'static str>>>,
+    ) -> LongPoll<impl Future<Output = Option<&'static str>>> {
+        watch(ctx.state().subscribe()).timeout(Duration::from_millis(10))
+    }
+
+    #[tokio::test]
+    async fn long_poll() {
+        let (tx, _) = watch::channel("init");
+        let tx = Arc::new(tx);
+
+        let service = App::with_state(tx.clone())
+            .at("/", handler_service(poll))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(WebRequest::default()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+        let (res, _) = tokio::join!(service.call(WebRequest::default()), async {
+            tokio::task::yield_now().await;
+            tx.send("update").unwrap();
+        });
+        let res = res.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(collect_string_body(res.into_body()).await.unwrap(), "update");
+
+        // closed channel resolves to none.
+        let rx = tx.subscribe();
+        drop(service);
+        drop(tx);
+        assert!(watch(rx).fut.now_or_panic().is_none());
+    }
+}
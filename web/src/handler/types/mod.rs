@@ -1,12 +1,18 @@
 pub mod body;
+pub mod claims;
+pub mod conditional;
 pub mod extension;
 pub mod header;
 pub mod html;
+pub mod long_poll;
 pub mod path;
+pub mod registry;
 pub mod request;
+pub mod route;
 pub mod state;
 pub mod string;
 pub mod uri;
+pub mod valid;
 pub mod vec;
 
 #[cfg(feature = "params")]
@@ -21,5 +27,11 @@ pub mod json;
 #[cfg(feature = "multipart")]
 pub mod multipart;
 
+#[cfg(feature = "signature")]
+pub mod signature;
+
+#[cfg(feature = "signature")]
+pub mod signed_url;
+
 #[cfg(feature = "websocket")]
 pub mod websocket;
@@ -5,6 +5,8 @@ use crate::{
     handler::{error::ExtractError, FromRequest},
 };
 
+pub use http_multipart::{Checksum, Field, SaveError, Storage};
+
 pub type Multipart<B = RequestBody> = http_multipart::Multipart<B>;
 
 impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for Multipart<B>
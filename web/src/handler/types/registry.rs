@@ -0,0 +1,278 @@
+//! registry of long lived realtime connections like websocket and server sent events.
+
+use core::{
+    borrow::Borrow,
+    hash::Hash,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use futures_core::stream::Stream;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use xitca_unsafe_collection::bytes::BytesStr;
+
+use crate::{
+    body::BodyStream,
+    bytes::Bytes,
+    context::WebContext,
+    handler::{error::ExtractError, FromRequest},
+};
+
+/// Command sent from [Registry] to registered [Connection].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Command {
+    /// Text message to be sent to client.
+    Text(BytesStr),
+    /// Binary message to be sent to client.
+    Binary(Bytes),
+    /// Close the connection. Websocket connection sends close frame to client before closing.
+    Close,
+}
+
+/// Registry of realtime connections keyed by user defined id.
+///
+/// Registry is cheap to clone and can be shared between threads. It can be stored in application
+/// state and extracted as handler argument. Multiple connections can be registered with the same
+/// id. e.g. one user with multiple browser tabs.
+///
+/// Registered connections are not closed on server shutdown by default. Pass [Registry::close_all]
+/// to graceful shutdown callback of server so they are notified to close before server stops.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::{handler_service, registry::{Command, Registry}}, App, WebContext};
+/// async fn notify(registry: Registry<u64>, _: &WebContext<'_, Registry<u64>>) -> &'static str {
+///     // send message to all connections of user 996.
+///     registry.send(&996, Command::Text("hello".into()));
+///     // close connections of user 251.
+///     registry.kick(&251);
+///     "ok"
+/// }
+///
+/// App::with_state(Registry::<u64>::new()).at("/notify", handler_service(notify));
+/// ```
+pub struct Registry<Id> {
+    inner: Arc<Mutex<Inner<Id>>>,
+}
+
+struct Inner<Id> {
+    next_key: u64,
+    conns: HashMap<Id, Vec<(u64, UnboundedSender<Command>)>>,
+}
+
+impl<Id> Clone for Registry<Id> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<Id> Default for Registry<Id> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id> Registry<Id> {
+    /// Construct an empty registry.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                next_key: 0,
+                conns: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Total number of registered connections.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().conns.values().map(Vec::len).sum()
+    }
+
+    /// Return true when there is no registered connection.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Send command to all registered connections.
+    pub fn broadcast(&self, cmd: Command) {
+        let inner = self.inner.lock().unwrap();
+        for (_, tx) in inner.conns.values().flatten() {
+            let _ = tx.send(cmd.clone());
+        }
+    }
+
+    /// Close all registered connections.
+    pub fn close_all(&self) {
+        self.broadcast(Command::Close);
+    }
+}
+
+impl<Id> Registry<Id>
+where
+    Id: Eq + Hash + Clone,
+{
+    /// Register a new connection with given id.
+    ///
+    /// Connection is removed from registry when returned [Connection] is dropped.
+    pub fn register(&self, id: Id) -> Connection<Id> {
+        let (tx, rx) = unbounded_channel();
+        let mut inner = self.inner.lock().unwrap();
+        let key = inner.next_key;
+        inner.next_key += 1;
+        inner.conns.entry(id.clone()).or_default().push((key, tx));
+        Connection {
+            id,
+            key,
+            rx,
+            registry: self.clone(),
+        }
+    }
+
+    /// Ids with at least one registered connection.
+    pub fn ids(&self) -> Vec<Id> {
+        self.inner.lock().unwrap().conns.keys().cloned().collect()
+    }
+
+    /// Send command to all connections registered with given id. Return false when there is no
+    /// such connection.
+    pub fn send(&self, id: &Id, cmd: Command) -> bool {
+        let inner = self.inner.lock().unwrap();
+        match inner.conns.get(id) {
+            Some(conns) => {
+                for (_, tx) in conns {
+                    let _ = tx.send(cmd.clone());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Close all connections registered with given id. Return false when there is no such
+    /// connection.
+    pub fn kick(&self, id: &Id) -> bool {
+        self.send(id, Command::Close)
+    }
+
+    fn remove(&self, id: &Id, key: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(conns) = inner.conns.get_mut(id) {
+            conns.retain(|(k, _)| *k != key);
+            if conns.is_empty() {
+                inner.conns.remove(id);
+            }
+        }
+    }
+}
+
+/// A registered connection receiving [Command] from [Registry].
+///
+/// Can be passed to [WebSocket](crate::handler::websocket::WebSocket) for automatic handling of
+/// commands or polled directly as [Stream] for other kind of connections like server sent events.
+pub struct Connection<Id>
+where
+    Id: Eq + Hash + Clone,
+{
+    id: Id,
+    key: u64,
+    rx: UnboundedReceiver<Command>,
+    registry: Registry<Id>,
+}
+
+impl<Id> Connection<Id>
+where
+    Id: Eq + Hash + Clone,
+{
+    /// Id connection is registered with.
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    /// Receive next command from registry.
+    pub async fn recv(&mut self) -> Option<Command> {
+        self.rx.recv().await
+    }
+}
+
+// id is never pinned.
+impl<Id> Unpin for Connection<Id> where Id: Eq + Hash + Clone {}
+
+impl<Id> Stream for Connection<Id>
+where
+    Id: Eq + Hash + Clone,
+{
+    type Item = Command;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().rx.poll_recv(cx)
+    }
+}
+
+impl<Id> Drop for Connection<Id>
+where
+    Id: Eq + Hash + Clone,
+{
+    fn drop(&mut self) {
+        self.registry.remove(&self.id, self.key);
+    }
+}
+
+impl<'a, 'r, C, B, Id> FromRequest<'a, WebContext<'r, C, B>> for Registry<Id>
+where
+    C: Borrow<Registry<Id>>,
+    B: BodyStream,
+    Id: 'static,
+{
+    type Type<'b> = Registry<Id>;
+    type Error = ExtractError<B::Error>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        Ok(ctx.state().borrow().clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    #[test]
+    fn registry() {
+        let registry = Registry::new();
+
+        let mut conn1 = registry.register(1);
+        let mut conn2 = registry.register(1);
+        let mut conn3 = registry.register(2);
+        assert_eq!(registry.len(), 3);
+
+        let mut ids = registry.ids();
+        ids.sort();
+        assert_eq!(ids, [1, 2]);
+
+        assert!(registry.send(&1, Command::Text("996".into())));
+        assert!(!registry.send(&3, Command::Close));
+        assert_eq!(conn1.recv().now_or_panic(), Some(Command::Text("996".into())));
+        assert_eq!(conn2.recv().now_or_panic(), Some(Command::Text("996".into())));
+
+        assert!(registry.kick(&2));
+        assert_eq!(conn3.recv().now_or_panic(), Some(Command::Close));
+
+        registry.close_all();
+        assert_eq!(conn1.recv().now_or_panic(), Some(Command::Close));
+
+        drop(conn1);
+        drop(conn2);
+        assert_eq!(registry.ids(), [2]);
+        drop(conn3);
+        assert!(registry.is_empty());
+    }
+}
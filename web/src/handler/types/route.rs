@@ -0,0 +1,63 @@
+//! type extractor for matched route pattern.
+
+pub use xitca_http::util::service::router::MatchedPath;
+
+use crate::{
+    body::BodyStream,
+    context::WebContext,
+    handler::{error::ExtractError, FromRequest},
+};
+
+/// Extract route pattern matched by [App](crate::App) router. e.g. `/users/:id`.
+///
+/// Unlike the concrete request path the pattern has bounded cardinality which makes it suitable
+/// for metrics labels and access log aggregation.
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for MatchedPath
+where
+    B: BodyStream,
+{
+    type Type<'b> = MatchedPath;
+    type Error = ExtractError<B::Error>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        Ok(ctx.req().body().matched_path().clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_http::RequestBody;
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        dev::service::Service,
+        handler::handler_service,
+        http::{Request, RequestExt, Uri},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    async fn handler(path: MatchedPath) -> String {
+        path.as_str().to_owned()
+    }
+
+    #[test]
+    fn matched_path() {
+        let service = App::new()
+            .at("/users/:id", handler_service(handler))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut req = Request::new(RequestExt::<RequestBody>::default());
+        *req.uri_mut() = Uri::from_static("/users/996");
+
+        let res = service.call(req).now_or_panic().unwrap();
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, "/users/:id");
+    }
+}
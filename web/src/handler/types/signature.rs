@@ -0,0 +1,502 @@
+//! type extractor for webhook payload signed with HMAC signature.
+
+use core::{
+    borrow::Borrow,
+    fmt,
+    future::poll_fn,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    pin::pin,
+};
+
+use std::{
+    error,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+
+use crate::{
+    body::BodyStream,
+    bytes::{Bytes, BytesMut},
+    context::WebContext,
+    handler::{
+        error::{_ParseError, ExtractError},
+        FromRequest, Responder,
+    },
+    http::{
+        const_header_value::TEXT_UTF8,
+        header::{HeaderMap, HeaderName, CONTENT_LENGTH, CONTENT_TYPE},
+        StatusCode, WebResponse,
+    },
+};
+
+pub const DEFAULT_LIMIT: usize = 1024 * 1024;
+
+/// Scheme of signing webhook payload. Implement it for signature headers other than built in
+/// [GitHub] and [Stripe] schemes.
+pub trait SignatureScheme {
+    /// verify signature carried by request headers against payload with shared secret.
+    fn verify(secret: &[u8], headers: &HeaderMap, payload: &[u8]) -> Result<(), SignatureError>;
+}
+
+/// Shared secret of [SignatureScheme] S. It must be reachable from application state through
+/// [Borrow] trait for [SignedPayload] extractor to work.
+pub struct SignatureSecret<S> {
+    secret: Box<[u8]>,
+    _scheme: PhantomData<fn() -> S>,
+}
+
+impl<S> SignatureSecret<S> {
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into().into_boxed_slice(),
+            _scheme: PhantomData,
+        }
+    }
+}
+
+impl<S> Clone for SignatureSecret<S> {
+    fn clone(&self) -> Self {
+        Self {
+            secret: self.secret.clone(),
+            _scheme: PhantomData,
+        }
+    }
+}
+
+impl<S> fmt::Debug for SignatureSecret<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignatureSecret").finish_non_exhaustive()
+    }
+}
+
+/// Type that can be constructed from verified payload of [SignedPayload].
+pub trait FromPayload: Sized {
+    fn from_payload<E>(payload: Bytes) -> Result<Self, ExtractError<E>>;
+}
+
+impl FromPayload for Bytes {
+    fn from_payload<E>(payload: Bytes) -> Result<Self, ExtractError<E>> {
+        Ok(payload)
+    }
+}
+
+impl FromPayload for Vec<u8> {
+    fn from_payload<E>(payload: Bytes) -> Result<Self, ExtractError<E>> {
+        Ok(payload.into())
+    }
+}
+
+impl FromPayload for String {
+    fn from_payload<E>(payload: Bytes) -> Result<Self, ExtractError<E>> {
+        String::from_utf8(payload.into()).map_err(|e| _ParseError::String(e.utf8_error()).into())
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T, const LIMIT: usize> FromPayload for super::json::Json<T, LIMIT>
+where
+    T: serde::de::DeserializeOwned,
+{
+    fn from_payload<E>(payload: Bytes) -> Result<Self, ExtractError<E>> {
+        Ok(super::json::Json(serde_json::from_slice(&payload)?))
+    }
+}
+
+/// Extract type for webhook payload signed by [SignatureScheme] S.
+///
+/// Request body is buffered up to LIMIT bytes and verified against signature headers with
+/// [SignatureSecret] borrowed from application state. Payload is parsed to T only after
+/// verification succeeded. Rejected request is responded by [SignatureError].
+///
+/// Default limit is [DEFAULT_LIMIT] in bytes.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{
+/// #       handler_service,
+/// #       signature::{GitHub, SignatureSecret, SignedPayload},
+/// #   },
+/// #   App, WebContext,
+/// # };
+/// type Ctx<'a> = WebContext<'a, SignatureSecret<GitHub>>;
+///
+/// async fn webhook(payload: SignedPayload<GitHub>, _: &Ctx<'_>) -> String {
+///     format!("received {} bytes", payload.len())
+/// }
+///
+/// App::with_state(SignatureSecret::<GitHub>::new("secret"))
+///     .at("/webhook", handler_service(webhook));
+/// ```
+pub struct SignedPayload<S, T = Bytes, const LIMIT: usize = DEFAULT_LIMIT> {
+    payload: T,
+    _scheme: PhantomData<fn() -> S>,
+}
+
+impl<S, T, const LIMIT: usize> SignedPayload<S, T, LIMIT> {
+    pub fn into_inner(self) -> T {
+        self.payload
+    }
+}
+
+impl<S, T, const LIMIT: usize> fmt::Debug for SignedPayload<S, T, LIMIT>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignedPayload")
+            .field("payload", &self.payload)
+            .field("limit", &LIMIT)
+            .finish()
+    }
+}
+
+impl<S, T, const LIMIT: usize> Deref for SignedPayload<S, T, LIMIT> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.payload
+    }
+}
+
+impl<S, T, const LIMIT: usize> DerefMut for SignedPayload<S, T, LIMIT> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.payload
+    }
+}
+
+impl<'a, 'r, C, B, S, T, const LIMIT: usize> FromRequest<'a, WebContext<'r, C, B>> for SignedPayload<S, T, LIMIT>
+where
+    C: Borrow<SignatureSecret<S>>,
+    B: BodyStream + Default,
+    S: SignatureScheme + 'static,
+    T: FromPayload,
+{
+    type Type<'b> = SignedPayload<S, T, LIMIT>;
+    type Error = ExtractError<B::Error>;
+
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        let headers = ctx.req().headers();
+
+        let len = headers
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+
+        if len.is_some_and(|len| len > LIMIT) {
+            return Err(SignatureError::TooLarge.into());
+        }
+
+        let mut body = pin!(ctx.take_body_ref());
+
+        let mut buf = BytesMut::with_capacity(len.unwrap_or(0));
+
+        while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            let chunk = chunk.map_err(ExtractError::Body)?;
+            buf.extend_from_slice(chunk.as_ref());
+            if buf.len() > LIMIT {
+                return Err(SignatureError::TooLarge.into());
+            }
+        }
+
+        let secret: &SignatureSecret<S> = ctx.state().borrow();
+        S::verify(&secret.secret, headers, &buf)?;
+
+        T::from_payload(buf.freeze()).map(|payload| SignedPayload {
+            payload,
+            _scheme: PhantomData,
+        })
+    }
+}
+
+const X_HUB_SIGNATURE_256: HeaderName = HeaderName::from_static("x-hub-signature-256");
+const STRIPE_SIGNATURE: HeaderName = HeaderName::from_static("stripe-signature");
+
+/// GitHub webhook scheme. Payload is signed with HMAC-SHA256 and carried by
+/// `X-Hub-Signature-256: sha256=<hex>` header.
+pub struct GitHub;
+
+impl SignatureScheme for GitHub {
+    fn verify(secret: &[u8], headers: &HeaderMap, payload: &[u8]) -> Result<(), SignatureError> {
+        let value = headers
+            .get(X_HUB_SIGNATURE_256)
+            .ok_or(SignatureError::Missing)?
+            .to_str()
+            .map_err(|_| SignatureError::Malformed)?;
+
+        let signature = value
+            .strip_prefix("sha256=")
+            .and_then(decode_hex)
+            .ok_or(SignatureError::Malformed)?;
+
+        if verify_hmac_sha256(secret, &[payload], &signature) {
+            Ok(())
+        } else {
+            Err(SignatureError::Mismatch)
+        }
+    }
+}
+
+/// Stripe webhook scheme. `<timestamp>.<payload>` is signed with HMAC-SHA256 and carried by
+/// `Stripe-Signature: t=<timestamp>,v1=<hex>` header. Multiple `v1` signatures are accepted for
+/// secret rolling. Signature with timestamp older than [Stripe::TOLERANCE] in seconds is rejected
+/// to prevent replay attack.
+pub struct Stripe;
+
+impl Stripe {
+    pub const TOLERANCE: u64 = 300;
+}
+
+impl SignatureScheme for Stripe {
+    fn verify(secret: &[u8], headers: &HeaderMap, payload: &[u8]) -> Result<(), SignatureError> {
+        let value = headers
+            .get(STRIPE_SIGNATURE)
+            .ok_or(SignatureError::Missing)?
+            .to_str()
+            .map_err(|_| SignatureError::Malformed)?;
+
+        let mut timestamp = None;
+        let mut signatures = Vec::new();
+
+        for item in value.split(',') {
+            match item.trim().split_once('=') {
+                Some(("t", t)) => timestamp = Some(t),
+                Some(("v1", sig)) => signatures.push(decode_hex(sig).ok_or(SignatureError::Malformed)?),
+                Some(_) => {}
+                None => return Err(SignatureError::Malformed),
+            }
+        }
+
+        let timestamp = timestamp.ok_or(SignatureError::Malformed)?;
+        let secs = timestamp.parse::<u64>().map_err(|_| SignatureError::Malformed)?;
+
+        if signatures.is_empty() {
+            return Err(SignatureError::Missing);
+        }
+
+        let signed = [timestamp.as_bytes(), b".", payload];
+        if !signatures.iter().any(|sig| verify_hmac_sha256(secret, &signed, sig)) {
+            return Err(SignatureError::Mismatch);
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if now.abs_diff(secs) > Self::TOLERANCE {
+            return Err(SignatureError::Expired);
+        }
+
+        Ok(())
+    }
+}
+
+// compare in constant time.
+pub(super) fn verify_hmac_sha256(secret: &[u8], parts: &[&[u8]], signature: &[u8]) -> bool {
+    // hmac accepts key of any length.
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+    for part in parts {
+        mac.update(part);
+    }
+    mac.verify_slice(signature).is_ok()
+}
+
+pub(super) fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let pairs = hex.as_bytes().chunks_exact(2);
+
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+
+    fn digit(b: u8) -> Option<u8> {
+        match b {
+            b'0'..=b'9' => Some(b - b'0'),
+            b'a'..=b'f' => Some(b - b'a' + 10),
+            b'A'..=b'F' => Some(b - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    pairs.map(|pair| Some(digit(pair[0])? << 4 | digit(pair[1])?)).collect()
+}
+
+/// Error type of rejected [SignedPayload].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SignatureError {
+    /// signature header is absent.
+    Missing,
+    /// signature header is not in the format of scheme.
+    Malformed,
+    /// signature does not match payload.
+    Mismatch,
+    /// signature is out of tolerance of scheme.
+    Expired,
+    /// payload is larger than limit.
+    TooLarge,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Missing => f.write_str("Payload signature is missing."),
+            Self::Malformed => f.write_str("Payload signature is malformed."),
+            Self::Mismatch => f.write_str("Payload signature mismatch."),
+            Self::Expired => f.write_str("Payload signature is expired."),
+            Self::TooLarge => f.write_str("Payload is too large."),
+        }
+    }
+}
+
+impl error::Error for SignatureError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for SignatureError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        let status = match self {
+            Self::Malformed => StatusCode::BAD_REQUEST,
+            Self::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::UNAUTHORIZED,
+        };
+        let mut res = ctx.into_response(format!("{self}"));
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        *res.status_mut() = status;
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::fmt::Write;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::BoxStream,
+        dev::service::Service,
+        error::BodyError,
+        handler::handler_service,
+        http::{header::HeaderValue, Request, RequestExt},
+        App,
+    };
+
+    use super::*;
+
+    fn sign(parts: &[&[u8]]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().iter().fold(String::new(), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        })
+    }
+
+    fn req(headers: Vec<(HeaderName, String)>) -> Request<RequestExt<BoxStream>> {
+        use futures_util::stream;
+
+        let body = stream::once(async { Ok::<_, BodyError>(Bytes::from_static(b"{\"hello\":\"world\"}")) });
+        let ext = RequestExt::default().map_body(|_: ()| BoxStream::new(body));
+        let mut req = Request::new(ext);
+        for (name, value) in headers {
+            req.headers_mut().insert(name, HeaderValue::from_str(&value).unwrap());
+        }
+        req
+    }
+
+    #[test]
+    fn github() {
+        async fn handler(payload: SignedPayload<GitHub, String>) -> String {
+            payload.into_inner()
+        }
+
+        let service = App::with_state(SignatureSecret::<GitHub>::new("secret"))
+            .at("/", handler_service(handler))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let signature = format!("sha256={}", sign(&[b"{\"hello\":\"world\"}"]));
+        let res = service
+            .call(req(vec![(X_HUB_SIGNATURE_256, signature)]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.call(req(vec![])).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let signature = format!("sha256={}", sign(&[b"nah"]));
+        let res = service
+            .call(req(vec![(X_HUB_SIGNATURE_256, signature)]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let res = service
+            .call(req(vec![(X_HUB_SIGNATURE_256, "sha256=zz".into())]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn stripe() {
+        async fn handler(_: SignedPayload<Stripe>) -> &'static str {
+            "ok"
+        }
+
+        let service = App::with_state(SignatureSecret::<Stripe>::new("secret"))
+            .at("/", handler_service(handler))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .to_string();
+        let signature = sign(&[now.as_bytes(), b".", b"{\"hello\":\"world\"}"]);
+
+        let value = format!("t={now},v1={},v1={signature}", sign(&[b"old"]));
+        let res = service
+            .call(req(vec![(STRIPE_SIGNATURE, value)]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let old = (now.parse::<u64>().unwrap() - Stripe::TOLERANCE - 1).to_string();
+        let signature = sign(&[old.as_bytes(), b".", b"{\"hello\":\"world\"}"]);
+        let value = format!("t={old},v1={signature}");
+        let res = service
+            .call(req(vec![(STRIPE_SIGNATURE, value)]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn too_large() {
+        async fn handler(_: SignedPayload<GitHub, Bytes, 4>) -> &'static str {
+            "ok"
+        }
+
+        let service = App::with_state(SignatureSecret::<GitHub>::new("secret"))
+            .at("/", handler_service(handler))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req(vec![])).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
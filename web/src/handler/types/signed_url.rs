@@ -0,0 +1,276 @@
+//! HMAC signed url with expiry and type extractor validating it.
+
+use core::{borrow::Borrow, fmt, fmt::Write, time::Duration};
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+
+use crate::{
+    body::BodyStream,
+    context::WebContext,
+    handler::{error::ExtractError, FromRequest},
+    http::Uri,
+};
+
+use super::signature::{decode_hex, verify_hmac_sha256, SignatureError};
+
+const EXPIRES: &str = "expires";
+const SIGNATURE: &str = "signature";
+
+/// Signer minting and verifying url signed with HMAC-SHA256 and valid until expiry. It must be
+/// reachable from application state through [Borrow] trait for [SignedUrl] extractor to work.
+///
+/// Signed url carries `expires=<unix timestamp>&signature=<hex>` as the last query parameters.
+/// Signature covers path, query and expiry so none of them can be altered.
+#[derive(Clone)]
+pub struct UrlSigner {
+    secret: Box<[u8]>,
+}
+
+impl fmt::Debug for UrlSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UrlSigner").finish_non_exhaustive()
+    }
+}
+
+impl UrlSigner {
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into().into_boxed_slice(),
+        }
+    }
+
+    /// Sign path and optional query of url. Signed url is valid until expires.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use std::time::{Duration, SystemTime};
+    /// # use xitca_web::handler::signed_url::UrlSigner;
+    /// let signer = UrlSigner::new("secret");
+    /// let url = signer.sign("/download/report.pdf?inline=1", SystemTime::now() + Duration::from_secs(60));
+    /// assert!(url.starts_with("/download/report.pdf?inline=1&expires="));
+    /// ```
+    pub fn sign(&self, path_and_query: &str, expires: SystemTime) -> String {
+        let secs = expires.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let sep = if path_and_query.contains('?') { '&' } else { '?' };
+        let mut url = format!("{path_and_query}{sep}{EXPIRES}={secs}");
+
+        // hmac accepts key of any length.
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).unwrap();
+        mac.update(url.as_bytes());
+
+        url.push('&');
+        url.push_str(SIGNATURE);
+        url.push('=');
+        for b in mac.finalize().into_bytes() {
+            let _ = write!(url, "{b:02x}");
+        }
+
+        url
+    }
+
+    /// Sign path and optional query of url. Signed url is valid for given duration from now.
+    pub fn sign_for(&self, path_and_query: &str, dur: Duration) -> String {
+        self.sign(path_and_query, SystemTime::now() + dur)
+    }
+
+    /// Sign url generated from route pattern registered to [App](crate::App) with given params.
+    /// Signed url is valid for given duration from now.
+    ///
+    /// `:name` and `*name` segments of pattern are replaced with percent encoded value of
+    /// param with the same name. `/` in value of `*name` segment is kept as is.
+    ///
+    /// Return None when any param of pattern is absent.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use xitca_web::handler::signed_url::UrlSigner;
+    /// let signer = UrlSigner::new("secret");
+    /// let url = signer
+    ///     .sign_route("/files/:user/*path", &[("user", "bob"), ("path", "a b/c.txt")], Duration::from_secs(60))
+    ///     .unwrap();
+    /// assert!(url.starts_with("/files/bob/a%20b/c.txt?expires="));
+    /// ```
+    pub fn sign_route(&self, pattern: &str, params: &[(&str, &str)], dur: Duration) -> Option<String> {
+        route(pattern, params).map(|url| self.sign_for(&url, dur))
+    }
+
+    /// Verify signature and expiry of url.
+    pub fn verify(&self, uri: &Uri) -> Result<(), SignatureError> {
+        let path_and_query = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+
+        let (signed, signature) = path_and_query
+            .rsplit_once(&format!("&{SIGNATURE}="))
+            .ok_or(SignatureError::Missing)?;
+
+        let signature = decode_hex(signature).ok_or(SignatureError::Malformed)?;
+
+        if !verify_hmac_sha256(&self.secret, &[signed.as_bytes()], &signature) {
+            return Err(SignatureError::Mismatch);
+        }
+
+        // expires is right before signature and covered by it.
+        let secs = signed
+            .rsplit_once(&format!("{EXPIRES}="))
+            .and_then(|(_, secs)| secs.parse::<u64>().ok())
+            .ok_or(SignatureError::Malformed)?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        if now > secs {
+            return Err(SignatureError::Expired);
+        }
+
+        Ok(())
+    }
+}
+
+fn route(pattern: &str, params: &[(&str, &str)]) -> Option<String> {
+    let mut url = String::with_capacity(pattern.len());
+
+    for (i, segment) in pattern.split('/').enumerate() {
+        if i > 0 {
+            url.push('/');
+        }
+
+        let (name, keep_slash) = match segment.as_bytes().first() {
+            Some(b':') => (&segment[1..], false),
+            Some(b'*') => (&segment[1..], true),
+            _ => {
+                url.push_str(segment);
+                continue;
+            }
+        };
+
+        let (_, value) = params.iter().find(|(n, _)| *n == name)?;
+
+        for b in value.bytes() {
+            match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => url.push(b as char),
+                b'/' if keep_slash => url.push('/'),
+                _ => {
+                    let _ = write!(url, "%{b:02X}");
+                }
+            }
+        }
+    }
+
+    Some(url)
+}
+
+/// Extract type guarding request with url signed by [UrlSigner] borrowed from application
+/// state. Rejected request is responded by [SignatureError].
+///
+/// # Examples:
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{
+/// #   handler::{
+/// #       handler_service,
+/// #       signed_url::{SignedUrl, UrlSigner},
+/// #   },
+/// #   App, WebContext,
+/// # };
+/// type Ctx<'a> = WebContext<'a, UrlSigner>;
+///
+/// // mint a signed url to download endpoint.
+/// async fn share(ctx: &Ctx<'_>) -> String {
+///     ctx.state()
+///         .sign_route("/download/:name", &[("name", "report.pdf")], Duration::from_secs(600))
+///         .unwrap()
+/// }
+///
+/// async fn download(_: SignedUrl, _: &Ctx<'_>) -> &'static str {
+///     "report"
+/// }
+///
+/// App::with_state(UrlSigner::new("secret"))
+///     .at("/share", handler_service(share))
+///     .at("/download/:name", handler_service(download));
+/// ```
+#[derive(Debug)]
+pub struct SignedUrl;
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for SignedUrl
+where
+    C: Borrow<UrlSigner>,
+    B: BodyStream,
+{
+    type Type<'b> = SignedUrl;
+    type Error = ExtractError<B::Error>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        let signer: &UrlSigner = ctx.state().borrow();
+        signer.verify(ctx.req().uri())?;
+        Ok(SignedUrl)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        dev::service::Service,
+        handler::handler_service,
+        http::{Request, RequestExt, StatusCode},
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn route_pattern() {
+        assert_eq!(route("/a/:b/c", &[("b", "x y")]).unwrap(), "/a/x%20y/c");
+        assert_eq!(route("/a/*b", &[("b", "x/y?")]).unwrap(), "/a/x/y%3F");
+        assert_eq!(route("/a/:b", &[("b", "x/y")]).unwrap(), "/a/x%2Fy");
+        assert!(route("/a/:b", &[]).is_none());
+    }
+
+    #[test]
+    fn signed_url() {
+        async fn handler(_: SignedUrl, _: &WebContext<'_, UrlSigner>) -> &'static str {
+            "ok"
+        }
+
+        let signer = UrlSigner::new("secret");
+
+        let service = App::with_state(signer.clone())
+            .at("/files/:name", handler_service(handler))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |url: &str| {
+            let req = Request::builder().uri(url).body(RequestExt::default()).unwrap();
+            service.call(req).now_or_panic().unwrap().status()
+        };
+
+        let url = signer
+            .sign_route("/files/:name", &[("name", "a.txt")], Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(call(&url), StatusCode::OK);
+
+        assert_eq!(call("/files/a.txt"), StatusCode::UNAUTHORIZED);
+        assert_eq!(call(&url.replace("a.txt", "b.txt")), StatusCode::UNAUTHORIZED);
+        assert_eq!(call(&url.replace("expires=", "expires=1")), StatusCode::UNAUTHORIZED);
+
+        let (unsigned, _) = url.rsplit_once('=').unwrap();
+        assert_eq!(call(&format!("{unsigned}=zz")), StatusCode::BAD_REQUEST);
+
+        let url = signer.sign("/files/a.txt?inline=1", UNIX_EPOCH + Duration::from_secs(1));
+        assert_eq!(call(&url), StatusCode::UNAUTHORIZED);
+        assert!(matches!(
+            signer.verify(&url.parse().unwrap()),
+            Err(SignatureError::Expired)
+        ));
+    }
+}
@@ -0,0 +1,279 @@
+//! type extractor for validating output of other extractors.
+
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use std::{borrow::Cow, error};
+
+use crate::{
+    body::BodyStream,
+    context::WebContext,
+    handler::{error::ExtractError, FromRequest},
+};
+
+/// Trait for validating a type after it's extracted from request.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::handler::valid::{Validate, ValidationErrors};
+/// struct User {
+///     name: String,
+///     age: u8,
+/// }
+///
+/// impl Validate for User {
+///     fn validate(&self) -> Result<(), ValidationErrors> {
+///         let mut errors = ValidationErrors::new();
+///         if self.name.is_empty() {
+///             errors.add("name", "must not be empty");
+///         }
+///         if self.age < 18 {
+///             errors.add("age", "must be at least 18");
+///         }
+///         errors.into_result()
+///     }
+/// }
+/// ```
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+/// Collection of field level violations produced by [Validate].
+///
+/// When returned from [Valid] extractor it's converted to a `422 Unprocessable Entity` response
+/// with json body in the form of `{"errors":[{"field":"name","message":"must not be empty"}]}`.
+#[derive(Debug, Default)]
+pub struct ValidationErrors {
+    errors: Vec<(Cow<'static, str>, Cow<'static, str>)>,
+}
+
+impl ValidationErrors {
+    pub const fn new() -> Self {
+        Self { errors: Vec::new() }
+    }
+
+    /// Add a violation of given field with error message.
+    pub fn add(&mut self, field: impl Into<Cow<'static, str>>, message: impl Into<Cow<'static, str>>) {
+        self.errors.push((field.into(), message.into()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Iterate over field and message pairs of violations.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.errors.iter().map(|(field, msg)| (field.as_ref(), msg.as_ref()))
+    }
+
+    /// Return Ok when there is no violation.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let mut buf = String::from(r#"{"errors":["#);
+        for (idx, (field, msg)) in self.iter().enumerate() {
+            if idx != 0 {
+                buf.push(',');
+            }
+            buf.push_str(r#"{"field":"#);
+            json_str(field, &mut buf);
+            buf.push_str(r#","message":"#);
+            json_str(msg, &mut buf);
+            buf.push('}');
+        }
+        buf.push_str("]}");
+        buf
+    }
+}
+
+fn json_str(s: &str, buf: &mut String) {
+    use fmt::Write;
+
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(buf, "\\u{:04x}", c as u32);
+            }
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Validation failed:")?;
+        for (field, msg) in self.iter() {
+            write!(f, " {field}: {msg};")?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ValidationErrors {}
+
+/// Extract type that run [Validate::validate] on the output of inner extractor.
+///
+/// Works with `Json` and `Query` extractors (`json` and `urlencoded` feature) whose inner type
+/// implements [Validate]. e.g. `Valid<Json<User>>` extract json body to `User` type and validate
+/// it. Violations are responded with `422 Unprocessable Entity` status code.
+pub struct Valid<T>(pub T);
+
+impl<T> fmt::Debug for Valid<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Valid").field("value", &self.0).finish()
+    }
+}
+
+impl<T> Deref for Valid<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Valid<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, 'r, C, B, T> FromRequest<'a, WebContext<'r, C, B>> for Valid<T>
+where
+    B: BodyStream,
+    T: for<'a2, 'r2> FromRequest<'a2, WebContext<'r2, C, B>, Error = ExtractError<B::Error>> + Validate,
+{
+    type Type<'b> = Valid<T>;
+    type Error = ExtractError<B::Error>;
+
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        let value = T::from_request(ctx).await?;
+        value.validate()?;
+        Ok(Valid(value))
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T, const LIMIT: usize> Validate for super::json::Json<T, LIMIT>
+where
+    T: Validate,
+{
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.0.validate()
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl<T> Validate for super::query::Query<T>
+where
+    T: Validate,
+{
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.0.validate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::Responder,
+        http::{header::CONTENT_TYPE, StatusCode},
+        test::collect_string_body,
+    };
+
+    use super::*;
+
+    struct Name(&'static str);
+
+    impl Validate for Name {
+        fn validate(&self) -> Result<(), ValidationErrors> {
+            let mut errors = ValidationErrors::new();
+            if self.0.is_empty() {
+                errors.add("name", "must not be \"empty\"");
+            }
+            errors.into_result()
+        }
+    }
+
+    #[test]
+    fn validation_errors() {
+        assert!(Name("foo").validate().is_ok());
+
+        let errors = Name("").validate().err().unwrap();
+        assert_eq!(
+            errors.to_json(),
+            r#"{"errors":[{"field":"name","message":"must not be \"empty\""}]}"#
+        );
+
+        let mut ctx = WebContext::new_test(());
+        let ctx = ctx.as_web_ctx();
+
+        let res = ExtractError::<()>::from(errors).respond_to(ctx).now_or_panic();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(
+            body,
+            r#"{"errors":[{"field":"name","message":"must not be \"empty\""}]}"#
+        );
+    }
+
+    #[cfg(feature = "urlencoded")]
+    #[test]
+    fn query() {
+        use crate::{handler::query::Query, http::Uri};
+
+        #[derive(serde::Deserialize)]
+        struct Page {
+            size: usize,
+        }
+
+        impl Validate for Page {
+            fn validate(&self) -> Result<(), ValidationErrors> {
+                let mut errors = ValidationErrors::new();
+                if self.size > 100 {
+                    errors.add("size", "must not be greater than 100");
+                }
+                errors.into_result()
+            }
+        }
+
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+
+        *ctx.req_mut().uri_mut() = Uri::from_static("/?size=10");
+        let Valid(Query(page)) = Valid::<Query<Page>>::from_request(&ctx).now_or_panic().unwrap();
+        assert_eq!(page.size, 10);
+
+        *ctx.req_mut().uri_mut() = Uri::from_static("/?size=101");
+        match Valid::<Query<Page>>::from_request(&ctx).now_or_panic() {
+            Err(ExtractError::Validate(errors)) => {
+                assert_eq!(errors.iter().next().unwrap(), ("size", "must not be greater than 100"))
+            }
+            _ => panic!("validation must fail"),
+        };
+    }
+}
@@ -1,5 +1,6 @@
 use core::{
-    future::{poll_fn, Future},
+    future::{pending, poll_fn, Future},
+    hash::Hash,
     pin::{pin, Pin},
     time::Duration,
 };
@@ -7,7 +8,7 @@ use core::{
 use futures_core::stream::Stream;
 use http_ws::{
     stream::{RequestStream, ResponseSender, WsError},
-    HandshakeError, Item, Message as WsMessage, WsOutput,
+    CloseCode, HandshakeError, Item, Message as WsMessage, WsOutput,
 };
 use tokio::time::{sleep, Instant};
 use xitca_unsafe_collection::{
@@ -19,7 +20,11 @@ use crate::{
     body::{BodyStream, RequestBody, ResponseBody},
     bytes::Bytes,
     context::WebContext,
-    handler::{error::ExtractError, FromRequest, Responder},
+    handler::{
+        error::ExtractError,
+        registry::{Command, Connection},
+        FromRequest, Responder,
+    },
     http::{
         header::{CONNECTION, SEC_WEBSOCKET_VERSION, UPGRADE},
         WebResponse,
@@ -43,6 +48,8 @@ type OnErrCB<E> = Box<dyn FnMut(WsError<E>) -> BoxFuture<'static>>;
 
 type OnCloseCB = Box<dyn FnOnce() -> BoxFuture<'static>>;
 
+type CommandStream = Pin<Box<dyn Stream<Item = Command>>>;
+
 pub struct WebSocket<B = RequestBody>
 where
     B: BodyStream,
@@ -53,6 +60,7 @@ where
     on_msg: OnMsgCB,
     on_err: OnErrCB<B::Error>,
     on_close: OnCloseCB,
+    conn: Option<CommandStream>,
 }
 
 impl<B> WebSocket<B>
@@ -73,6 +81,7 @@ where
             on_msg: Box::new(|_, _| boxed_future()),
             on_err: Box::new(|_| boxed_future()),
             on_close: Box::new(|| boxed_future()),
+            conn: None,
         }
     }
 
@@ -127,6 +136,18 @@ where
         self.on_close = Box::new(|| Box::pin(func()));
         self
     }
+
+    /// Register websocket connection to [Registry](crate::handler::registry::Registry).
+    ///
+    /// Text and binary commands from registry are sent to client as websocket messages and close
+    /// command sends close frame with [CloseCode::Away] before closing the connection.
+    pub fn set_connection<Id>(&mut self, conn: Connection<Id>) -> &mut Self
+    where
+        Id: Eq + Hash + Clone + 'static,
+    {
+        self.conn = Some(Box::pin(conn));
+        self
+    }
 }
 
 impl<E> From<HandshakeError> for ExtractError<E> {
@@ -171,6 +192,7 @@ where
             on_msg,
             on_err,
             on_close,
+            conn,
         } = self;
 
         let (decode, res, tx) = ws;
@@ -183,12 +205,14 @@ where
             on_msg,
             on_err,
             on_close,
+            conn,
         ));
 
         res.map(ResponseBody::box_stream)
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn spawn_task<B>(
     ping_interval: Duration,
     max_unanswered_ping: u8,
@@ -197,6 +221,7 @@ async fn spawn_task<B>(
     mut on_msg: OnMsgCB,
     mut on_err: OnErrCB<B::Error>,
     on_close: OnCloseCB,
+    mut conn: Option<CommandStream>,
 ) where
     B: BodyStream,
 {
@@ -210,7 +235,25 @@ async fn spawn_task<B>(
         let mut un_answered_ping = 0u8;
 
         loop {
-            match poll_fn(|cx| decode.as_mut().poll_next(cx)).select(sleep.as_mut()).await {
+            let msg = poll_fn(|cx| decode.as_mut().poll_next(cx)).select(sleep.as_mut());
+
+            let res = match msg.select(next_command(&mut conn)).await {
+                SelectOutput::A(res) => res,
+                SelectOutput::B(cmd) => {
+                    match cmd {
+                        Some(Command::Text(txt)) => tx.send(WsMessage::Text(txt.into_inner())).await?,
+                        Some(Command::Binary(bin)) => tx.send(WsMessage::Binary(bin)).await?,
+                        // registry closed the connection or dropped.
+                        Some(Command::Close) | None => {
+                            tx.send(WsMessage::Close(Some(CloseCode::Away.into()))).await?;
+                            break;
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            match res {
                 SelectOutput::A(Some(Ok(msg))) => {
                     let msg = match msg {
                         WsMessage::Pong(_) => {
@@ -259,3 +302,10 @@ async fn spawn_task<B>(
 
     on_close().await;
 }
+
+async fn next_command(conn: &mut Option<CommandStream>) -> Option<Command> {
+    match conn {
+        Some(conn) => poll_fn(|cx| conn.as_mut().poll_next(cx)).await,
+        None => pending().await,
+    }
+}
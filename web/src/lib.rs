@@ -45,6 +45,119 @@ pub mod codegen {
     /// # }
     /// ```
     pub use xitca_codegen::State;
+
+    /// Attribute macro for checking argument and return types of async function used with
+    /// [handler_service](crate::handler::handler_service) individually.
+    ///
+    /// Compile error of mismatched handler type would point to the exact argument(or return type)
+    /// that does not implement [FromRequest](crate::handler::FromRequest) (or
+    /// [Responder](crate::handler::Responder)) instead of the whole argument tuple.
+    ///
+    /// Application state type and request body type can be specified with `state = <Type>` and
+    /// `body = <Type>`. By default they are `()` and [RequestBody](crate::body::RequestBody).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use xitca_web::{codegen::debug_handler, handler::{handler_service, state::StateRef, uri::UriRef}, App, WebContext};
+    /// #[debug_handler(state = usize)]
+    /// async fn index(StateRef(num): StateRef<'_, usize>, _: UriRef<'_>) -> String {
+    ///     num.to_string()
+    /// }
+    ///
+    /// App::with_state(996usize)
+    ///     .at("/", handler_service(index))
+    /// #   .at("/nah", handler_service(nah));
+    /// # async fn nah(_: &WebContext<'_, usize>) -> &'static str {
+    /// #   // needed to infer the body type of request
+    /// #   ""
+    /// # }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use xitca_web::codegen::debug_handler;
+    /// struct NotExtractor;
+    ///
+    /// // compile error points to NotExtractor.
+    /// #[debug_handler]
+    /// async fn index(_: NotExtractor) -> &'static str {
+    ///     ""
+    /// }
+    /// ```
+    pub use xitca_codegen::debug_handler;
+
+    /// Attribute macro for caching response of handler with
+    /// [Cache](crate::middleware::cache::Cache) middleware.
+    ///
+    /// A companion function named `<handler>_service` is generated and it returns the handler service
+    /// wrapped with the middleware as [CacheHandler](crate::middleware::cache::CacheHandler). The
+    /// middleware is configured with `ttl = "<duration>"` and optional `key = "path" | "query"`
+    /// arguments. Duration accepts `ms`, `s`, `m` and `h` units. Key default to `query`.
+    ///
+    /// Application state type and request body type can be specified with `state = <Type>` and
+    /// `body = <Type>`. By default they are `()` and [RequestBody](crate::body::RequestBody).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use xitca_web::{codegen::cache, handler::handler_service, App, WebContext};
+    /// #[cache(ttl = "30s", key = "path")]
+    /// async fn index(_: &WebContext<'_>) -> &'static str {
+    ///     "hello,world!"
+    /// }
+    ///
+    /// App::new()
+    ///     .at("/", index_service())
+    ///     // handler function itself is left untouched.
+    ///     .at("/nocache", handler_service(index));
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use xitca_web::{codegen::cache, WebContext};
+    /// // unknown duration unit.
+    /// #[cache(ttl = "30x")]
+    /// async fn index(_: &WebContext<'_>) -> &'static str {
+    ///     ""
+    /// }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use xitca_web::{codegen::cache, WebContext};
+    /// // ttl is required.
+    /// #[cache(key = "path")]
+    /// async fn index(_: &WebContext<'_>) -> &'static str {
+    ///     ""
+    /// }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use xitca_web::{codegen::cache, WebContext};
+    /// // unknown key.
+    /// #[cache(ttl = "30s", key = "header")]
+    /// async fn index(_: &WebContext<'_>) -> &'static str {
+    ///     ""
+    /// }
+    /// ```
+    pub use xitca_codegen::cache;
+
+    #[doc(hidden)]
+    /// helper functions used by code generated by [debug_handler].
+    pub mod __private {
+        use crate::{
+            handler::{FromRequest, Responder},
+            WebContext,
+        };
+
+        pub fn assert_from_request<'a, T, C: 'a, B: 'a>()
+        where
+            T: FromRequest<'a, WebContext<'a, C, B>>,
+        {
+        }
+
+        pub fn assert_responder<'a, T, C: 'a, B: 'a>()
+        where
+            T: Responder<WebContext<'a, C, B>>,
+        {
+        }
+    }
 }
 
 pub mod http {
@@ -70,9 +183,17 @@ pub mod dev {
     pub use xitca_service as service;
 }
 
-pub use app::{App, AppObject};
+#[cfg(feature = "__server")]
+pub mod config {
+    //! http service configuration used by [HttpServer](crate::HttpServer).
+    pub use xitca_http::config::{
+        DuplicateHeader, HttpServiceConfig, DEFAULT_HEADER_LIMIT, DEFAULT_READ_BUF_LIMIT, DEFAULT_WRITE_BUF_LIMIT,
+    };
+}
+
+pub use app::{App, AppHandle, AppObject};
 pub use body::BodyStream;
-pub use context::WebContext;
+pub use context::{ResponseHeaders, WebContext};
 #[cfg(feature = "__server")]
 pub use server::HttpServer;
 
@@ -0,0 +1,496 @@
+//! bearer token authentication middleware.
+
+use core::{convert::Infallible, fmt};
+
+use std::{error, sync::Arc};
+
+use crate::{
+    bytes::Bytes,
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    handler::{claims::Claims, Responder},
+    http::{
+        const_header_value::TEXT_UTF8,
+        header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, WWW_AUTHENTICATE},
+        StatusCode, WebResponse,
+    },
+};
+
+#[cfg(feature = "jwt")]
+pub use self::jwt::Jwt;
+
+/// Validator of bearer token carried by `Authorization: Bearer <token>` header.
+///
+/// Implemented for closures in the form of `Fn(&str) -> Result<T, AuthError>`. With `jwt`
+/// feature enabled `Jwt` validator is provided for JSON Web Token signed with HS256.
+pub trait Validate {
+    /// Claims produced by valid token. Extracted by handlers with [Claims] extractor.
+    type Claims: Send + Sync + 'static;
+
+    /// Validate token and produce claims of it.
+    fn validate(&self, token: &str) -> Result<Self::Claims, AuthError>;
+}
+
+impl<F, T> Validate for F
+where
+    F: Fn(&str) -> Result<T, AuthError>,
+    T: Send + Sync + 'static,
+{
+    type Claims = T;
+
+    #[inline]
+    fn validate(&self, token: &str) -> Result<Self::Claims, AuthError> {
+        self(token)
+    }
+}
+
+/// A middleware authenticating requests with bearer token.
+///
+/// Token is validated by [Validate] type and produced claims can be extracted by handlers with
+/// [Claims] extractor. Rejected request is responded with `401 Unauthorized` or `403 Forbidden`
+/// according to [AuthError] returned by validator.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{claims::Claims, handler_service},
+/// #   middleware::auth::{AuthError, Bearer},
+/// #   App, WebContext
+/// # };
+/// async fn index(user: Claims<String>, _: &WebContext<'_>) -> String {
+///     format!("hello, {}", *user)
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(index))
+///     .at("/health", handler_service(|_: &WebContext<'_>| async { "ok" }))
+///     .enclosed(
+///         Bearer::new(|token: &str| match token {
+///             "996" => Ok(String::from("alice")),
+///             _ => Err(AuthError::Invalid),
+///         })
+///         // health check is reachable without token.
+///         .exempt("/health"),
+///     );
+/// ```
+pub struct Bearer<V> {
+    validator: Arc<V>,
+    exempt: Vec<String>,
+    unauthorized: Option<(HeaderValue, Bytes)>,
+    forbidden: Option<(HeaderValue, Bytes)>,
+}
+
+impl<V> Clone for Bearer<V> {
+    fn clone(&self) -> Self {
+        Self {
+            validator: self.validator.clone(),
+            exempt: self.exempt.clone(),
+            unauthorized: self.unauthorized.clone(),
+            forbidden: self.forbidden.clone(),
+        }
+    }
+}
+
+impl<V> Bearer<V> {
+    /// Construct a middleware validating bearer token with given validator.
+    pub fn new(validator: V) -> Self {
+        Self {
+            validator: Arc::new(validator),
+            exempt: Vec::new(),
+            unauthorized: None,
+            forbidden: None,
+        }
+    }
+
+    /// Skip authentication of request with exact matching path. Can be called multiple times for
+    /// exempting multiple paths.
+    pub fn exempt(mut self, path: impl Into<String>) -> Self {
+        self.exempt.push(path.into());
+        self
+    }
+
+    /// Set content type and body of `401 Unauthorized` response.
+    ///
+    /// Default to plain text description of [AuthError].
+    pub fn unauthorized_body(mut self, content_type: HeaderValue, body: impl Into<Bytes>) -> Self {
+        self.unauthorized = Some((content_type, body.into()));
+        self
+    }
+
+    /// Set content type and body of `403 Forbidden` response.
+    ///
+    /// Default to plain text description of [AuthError].
+    pub fn forbidden_body(mut self, content_type: HeaderValue, body: impl Into<Bytes>) -> Self {
+        self.forbidden = Some((content_type, body.into()));
+        self
+    }
+}
+
+impl<S, V> Service<S> for Bearer<V> {
+    type Response = BearerService<S, V>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(BearerService {
+            service,
+            bearer: self.clone(),
+        })
+    }
+}
+
+pub struct BearerService<S, V> {
+    service: S,
+    bearer: Bearer<V>,
+}
+
+impl<S, V> BearerService<S, V>
+where
+    V: Validate,
+{
+    fn authorize(&self, headers: &HeaderMap) -> Result<V::Claims, BearerError> {
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| {
+                let (scheme, token) = v.split_once(' ')?;
+                scheme.eq_ignore_ascii_case("bearer").then(|| token.trim())
+            })
+            .ok_or(AuthError::Missing);
+
+        token
+            .and_then(|token| self.bearer.validator.validate(token))
+            .map_err(|error| {
+                let body = match error {
+                    AuthError::Forbidden => self.bearer.forbidden.clone(),
+                    _ => self.bearer.unauthorized.clone(),
+                };
+                BearerError { error, body }
+            })
+    }
+}
+
+pub type BearerServiceError<E> = PipelineE<BearerError, E>;
+
+impl<'r, S, C, B, V, Res, Err> Service<WebContext<'r, C, B>> for BearerService<S, V>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+    V: Validate,
+{
+    type Response = Res;
+    type Error = BearerServiceError<Err>;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let path = ctx.req().uri().path();
+
+        if !self.bearer.exempt.iter().any(|exempt| exempt == path) {
+            let claims = self.authorize(ctx.req().headers()).map_err(BearerServiceError::First)?;
+            ctx.req_mut().extensions_mut().insert(Claims(Arc::new(claims)));
+        }
+
+        self.service.call(ctx).await.map_err(BearerServiceError::Second)
+    }
+}
+
+impl<S, V> ReadyService for BearerService<S, V>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// Reason of rejecting bearer token.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum AuthError {
+    /// `Authorization` header is absent or not in bearer scheme.
+    Missing,
+    /// token is malformed or has invalid signature.
+    Invalid,
+    /// token is expired.
+    Expired,
+    /// token is valid but not allowed to access requested resource.
+    Forbidden,
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Missing => f.write_str("Bearer token is missing."),
+            Self::Invalid => f.write_str("Bearer token is invalid."),
+            Self::Expired => f.write_str("Bearer token is expired."),
+            Self::Forbidden => f.write_str("Bearer token is not allowed to access resource."),
+        }
+    }
+}
+
+impl error::Error for AuthError {}
+
+/// Error type of [Bearer] middleware.
+pub struct BearerError {
+    error: AuthError,
+    body: Option<(HeaderValue, Bytes)>,
+}
+
+impl BearerError {
+    /// Reason of rejecting request.
+    pub fn error(&self) -> AuthError {
+        self.error
+    }
+}
+
+impl fmt::Debug for BearerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BearerError").field("error", &self.error).finish()
+    }
+}
+
+impl fmt::Display for BearerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl error::Error for BearerError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for BearerError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        // challenge follows RFC 6750 section 3.
+        let (status, challenge) = match self.error {
+            AuthError::Missing => (StatusCode::UNAUTHORIZED, "Bearer"),
+            AuthError::Invalid | AuthError::Expired => (StatusCode::UNAUTHORIZED, "Bearer error=\"invalid_token\""),
+            AuthError::Forbidden => (StatusCode::FORBIDDEN, "Bearer error=\"insufficient_scope\""),
+        };
+
+        let mut res = match self.body {
+            Some((content_type, body)) => {
+                let mut res = ctx.into_response(body);
+                res.headers_mut().insert(CONTENT_TYPE, content_type);
+                res
+            }
+            None => {
+                let mut res = ctx.into_response(format!("{}", self.error));
+                res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+                res
+            }
+        };
+        res.headers_mut()
+            .insert(WWW_AUTHENTICATE, HeaderValue::from_static(challenge));
+        *res.status_mut() = status;
+        res
+    }
+}
+
+#[cfg(feature = "jwt")]
+mod jwt {
+    use core::marker::PhantomData;
+
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use hmac::{Hmac, KeyInit, Mac};
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+    use sha2::Sha256;
+
+    use super::{AuthError, Validate};
+
+    /// Validator of JSON Web Token signed with HS256 algorithm.
+    ///
+    /// Token with `alg` other than `HS256` is rejected. Registered `exp` and `nbf` claims are
+    /// checked against system time when present and the payload is deserialized to T.
+    pub struct Jwt<T> {
+        secret: Box<[u8]>,
+        leeway: u64,
+        _claims: PhantomData<fn() -> T>,
+    }
+
+    impl<T> Jwt<T> {
+        /// Construct a validator with shared secret of HS256 signature.
+        pub fn hs256(secret: impl Into<Vec<u8>>) -> Self {
+            Self {
+                secret: secret.into().into_boxed_slice(),
+                leeway: 0,
+                _claims: PhantomData,
+            }
+        }
+
+        /// Set seconds of clock skew tolerated when checking `exp` and `nbf` claims.
+        ///
+        /// Default to 0.
+        pub fn leeway(mut self, secs: u64) -> Self {
+            self.leeway = secs;
+            self
+        }
+    }
+
+    impl<T> Validate for Jwt<T>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        type Claims = T;
+
+        fn validate(&self, token: &str) -> Result<Self::Claims, AuthError> {
+            let mut parts = token.split('.');
+            let (Some(header), Some(payload), Some(signature), None) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                return Err(AuthError::Invalid);
+            };
+
+            if decode_json(header)?.get("alg").and_then(Value::as_str) != Some("HS256") {
+                return Err(AuthError::Invalid);
+            }
+
+            let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| AuthError::Invalid)?;
+            // hmac accepts key of any length.
+            let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).unwrap();
+            mac.update(&token.as_bytes()[..header.len() + 1 + payload.len()]);
+            mac.verify_slice(&signature).map_err(|_| AuthError::Invalid)?;
+
+            let claims = decode_json(payload)?;
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+
+            if let Some(exp) = claims.get("exp").and_then(Value::as_u64) {
+                if now > exp.saturating_add(self.leeway) {
+                    return Err(AuthError::Expired);
+                }
+            }
+
+            if let Some(nbf) = claims.get("nbf").and_then(Value::as_u64) {
+                if now.saturating_add(self.leeway) < nbf {
+                    return Err(AuthError::Invalid);
+                }
+            }
+
+            serde_json::from_value(claims).map_err(|_| AuthError::Invalid)
+        }
+    }
+
+    fn decode_json(part: &str) -> Result<Value, AuthError> {
+        let bytes = URL_SAFE_NO_PAD.decode(part).map_err(|_| AuthError::Invalid)?;
+        serde_json::from_slice(&bytes).map_err(|_| AuthError::Invalid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{const_header_value::JSON, Request, RequestExt, WebRequest},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    async fn handler(claims: Claims<u32>, _: &WebContext<'_>) -> String {
+        claims.to_string()
+    }
+
+    fn req(uri: &'static str, auth: Option<&'static str>) -> WebRequest {
+        let mut req = Request::builder().uri(uri).body(RequestExt::default()).unwrap();
+        if let Some(auth) = auth {
+            req.headers_mut().insert(AUTHORIZATION, HeaderValue::from_static(auth));
+        }
+        req
+    }
+
+    #[test]
+    fn bearer() {
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .at("/health", handler_service(|_: &WebContext<'_>| async { "ok" }))
+            .enclosed(
+                Bearer::new(|token: &str| match token {
+                    "996" => Ok(996u32),
+                    "251" => Err(AuthError::Forbidden),
+                    _ => Err(AuthError::Invalid),
+                })
+                .exempt("/health")
+                .forbidden_body(JSON, "{\"error\":\"forbidden\"}"),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req("/", Some("Bearer 996"))).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "996");
+
+        let res = service.call(req("/", None)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(res.headers().get(WWW_AUTHENTICATE).unwrap(), "Bearer");
+
+        let res = service.call(req("/", Some("Basic 996"))).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let res = service.call(req("/", Some("bearer 007"))).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            res.headers().get(WWW_AUTHENTICATE).unwrap(),
+            "Bearer error=\"invalid_token\""
+        );
+
+        let res = service.call(req("/", Some("Bearer 251"))).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), JSON);
+        assert_eq!(
+            collect_string_body(res.into_body()).now_or_panic().unwrap(),
+            "{\"error\":\"forbidden\"}"
+        );
+
+        let res = service.call(req("/health", None)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn jwt() {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+        use hmac::{Hmac, KeyInit, Mac};
+        use sha2::Sha256;
+
+        fn sign(header: &str, payload: &str, secret: &[u8]) -> String {
+            let msg = format!("{}.{}", URL_SAFE_NO_PAD.encode(header), URL_SAFE_NO_PAD.encode(payload));
+            let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+            mac.update(msg.as_bytes());
+            let sig = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+            format!("{msg}.{sig}")
+        }
+
+        #[derive(serde::Deserialize)]
+        struct User {
+            sub: String,
+        }
+
+        let jwt = Jwt::<User>::hs256("secret");
+
+        let token = sign(r#"{"alg":"HS256","typ":"JWT"}"#, r#"{"sub":"alice"}"#, b"secret");
+        assert_eq!(jwt.validate(&token).unwrap().sub, "alice");
+
+        let token = sign(r#"{"alg":"HS256"}"#, r#"{"sub":"alice"}"#, b"wrong");
+        assert_eq!(jwt.validate(&token).err(), Some(AuthError::Invalid));
+
+        let token = sign(r#"{"alg":"none"}"#, r#"{"sub":"alice"}"#, b"secret");
+        assert_eq!(jwt.validate(&token).err(), Some(AuthError::Invalid));
+
+        let token = sign(r#"{"alg":"HS256"}"#, r#"{"sub":"alice","exp":1}"#, b"secret");
+        assert_eq!(jwt.validate(&token).err(), Some(AuthError::Expired));
+
+        assert_eq!(jwt.validate("not.a.token.at.all").err(), Some(AuthError::Invalid));
+    }
+}
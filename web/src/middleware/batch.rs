@@ -0,0 +1,713 @@
+//! batch request middleware.
+
+use core::{cell::RefCell, convert::Infallible, fmt, future::poll_fn, pin::pin};
+
+use std::error;
+
+use futures_core::stream::Stream;
+use serde_json::{Map, Value};
+
+use crate::{
+    body::{BodyStream, ResponseBody},
+    bytes::{Bytes, BytesMut},
+    context::{ResponseHeaders, WebContext},
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    handler::Responder,
+    http::{
+        const_header_value::{JSON, TEXT_UTF8},
+        header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, TRANSFER_ENCODING},
+        IntoResponse, Method, StatusCode, Uri, WebRequest, WebResponse,
+    },
+};
+
+/// Default max size of batch request body in bytes.
+pub const DEFAULT_LIMIT: usize = 1024 * 1024;
+
+/// Default max count of sub-requests in one batch request.
+pub const DEFAULT_MAX_REQUESTS: usize = 32;
+
+/// A middleware splitting batch request into sub-requests, dispatching them through the enclosed
+/// service and aggregating their responses into one response.
+///
+/// Only `POST` request to the batch path is handled. Other requests are passed through to the
+/// enclosed service. Sub-requests are dispatched one after another in the order they appear in
+/// batch request. They inherit headers of batch request except `Content-Type`, `Content-Length`
+/// and `Transfer-Encoding`, and their own headers take precedence over the inherited ones.
+///
+/// Two formats of batch request are supported according to it's `Content-Type`:
+///
+/// - `multipart/mixed`: every part is an `application/http` message containing one request.
+///   e.g. `GET /users/1 HTTP/1.1`. Response is `multipart/mixed` with every part containing
+///   the response of sub-request in the same order. `Content-ID` header of request part is
+///   echoed back in response part as `response-<id>`.
+/// - `application/json`: an array of `{ "method": "GET", "path": "/users/1", "headers": {},
+///   "body": null }` objects where only `path` is required. String body is sent as is and other
+///   body is sent as json. Response is an array of `{ "status": 200, "headers": {}, "body": null }`
+///   objects where json response body is embedded as json and other body is embedded as string.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::batch::Batch, App, WebContext};
+/// App::new()
+///     .at("/users", handler_service(|_: &WebContext<'_>| async { "users" }))
+///     .at("/posts", handler_service(|_: &WebContext<'_>| async { "posts" }))
+///     // fetch users and posts with one request to /batch.
+///     .enclosed(Batch::new("/batch").max_requests(8));
+/// ```
+#[derive(Clone)]
+pub struct Batch {
+    path: String,
+    limit: usize,
+    max_requests: usize,
+}
+
+impl Batch {
+    /// Construct a middleware handling batch request with exact matching path.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            limit: DEFAULT_LIMIT,
+            max_requests: DEFAULT_MAX_REQUESTS,
+        }
+    }
+
+    /// Set max size of batch request body in bytes.
+    ///
+    /// See [DEFAULT_LIMIT] for default value.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Set max count of sub-requests in one batch request.
+    ///
+    /// See [DEFAULT_MAX_REQUESTS] for default value.
+    pub fn max_requests(mut self, max: usize) -> Self {
+        self.max_requests = max;
+        self
+    }
+}
+
+impl<S> Service<S> for Batch {
+    type Response = BatchService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(BatchService {
+            service,
+            batch: self.clone(),
+        })
+    }
+}
+
+pub struct BatchService<S> {
+    service: S,
+    batch: Batch,
+}
+
+pub type BatchServiceError<E> = PipelineE<BatchError, E>;
+
+impl<'r, S, C, B, ResB, BE, Err> Service<WebContext<'r, C, B>> for BatchService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResponseBody<ResB>>, Error = Err>,
+    Err: for<'r2> Responder<WebContext<'r2, C, B>, Output = WebResponse>,
+    B: BodyStream + From<Bytes> + Default,
+    ResB: Stream<Item = Result<Bytes, BE>>,
+{
+    type Response = WebResponse<ResponseBody<ResB>>;
+    type Error = BatchServiceError<Err>;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        if ctx.req().method() != Method::POST || ctx.req().uri().path() != self.batch.path {
+            return self.service.call(ctx).await.map_err(BatchServiceError::Second);
+        }
+
+        let format = Format::from_headers(ctx.req().headers()).map_err(BatchServiceError::First)?;
+        let body = read_body(ctx.take_body_mut(), self.batch.limit)
+            .await
+            .map_err(BatchServiceError::First)?;
+        let requests = format.decode(&body).map_err(BatchServiceError::First)?;
+
+        if requests.len() > self.batch.max_requests {
+            return Err(BatchServiceError::First(BatchError::TooManyRequests));
+        }
+
+        let mut responses = Vec::with_capacity(requests.len());
+
+        for sub in requests {
+            let id = sub.id.clone();
+            let (mut req, body) = sub.into_request(ctx.req()).map_err(BatchServiceError::First)?;
+            let mut body = RefCell::new(B::from(body));
+            let res_headers = RefCell::new(ResponseHeaders::default());
+            let mut sub_ctx = WebContext::new(&mut req, &mut body, ctx.state(), &res_headers);
+
+            let mut res = match self.service.call(sub_ctx.reborrow()).await {
+                Ok(res) => SubResponse::collect(res, id).await,
+                Err(e) => SubResponse::collect(e.respond_to(sub_ctx).await, id).await,
+            };
+
+            res_headers.into_inner().merge(&mut res.headers);
+            responses.push(res);
+        }
+
+        let (content_type, body) = format.encode(responses);
+        let mut res = ctx.req_mut().as_response(ResponseBody::Bytes { bytes: body });
+        res.headers_mut().insert(CONTENT_TYPE, content_type);
+        Ok(res)
+    }
+}
+
+impl<S> ReadyService for BatchService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+async fn read_body<B>(body: B, limit: usize) -> Result<Bytes, BatchError>
+where
+    B: BodyStream,
+{
+    let mut body = pin!(body);
+    let mut buf = BytesMut::new();
+
+    while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+        let chunk = chunk.map_err(|_| BatchError::Body)?;
+        buf.extend_from_slice(chunk.as_ref());
+        if buf.len() > limit {
+            return Err(BatchError::PayloadTooLarge);
+        }
+    }
+
+    Ok(buf.freeze())
+}
+
+enum Format {
+    Multipart { boundary: String },
+    Json,
+}
+
+impl Format {
+    fn from_headers(headers: &HeaderMap) -> Result<Self, BatchError> {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .ok_or(BatchError::UnsupportedMediaType)?;
+
+        let mut params = content_type.split(';').map(str::trim);
+        let mime = params.next().unwrap_or_default();
+
+        if mime.eq_ignore_ascii_case("application/json") {
+            return Ok(Self::Json);
+        }
+
+        if mime.eq_ignore_ascii_case("multipart/mixed") {
+            let boundary = params
+                .filter_map(|param| param.split_once('='))
+                .find(|(key, _)| key.trim().eq_ignore_ascii_case("boundary"))
+                .map(|(_, value)| value.trim().trim_matches('"'))
+                .filter(|boundary| !boundary.is_empty())
+                .ok_or(BatchError::Malformed)?;
+            return Ok(Self::Multipart {
+                boundary: boundary.to_owned(),
+            });
+        }
+
+        Err(BatchError::UnsupportedMediaType)
+    }
+
+    fn decode(&self, body: &Bytes) -> Result<Vec<SubRequest>, BatchError> {
+        match *self {
+            Self::Multipart { ref boundary } => decode_multipart(body, boundary),
+            Self::Json => decode_json(body),
+        }
+    }
+
+    fn encode(&self, responses: Vec<SubResponse>) -> (HeaderValue, Bytes) {
+        match *self {
+            Self::Multipart { ref boundary } => encode_multipart(responses, boundary),
+            Self::Json => (JSON, encode_json(responses)),
+        }
+    }
+}
+
+struct SubRequest {
+    id: Option<String>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl SubRequest {
+    fn into_request(self, batch: &WebRequest<()>) -> Result<(WebRequest<()>, Bytes), BatchError> {
+        let mut req = WebRequest::<()>::default();
+        *req.method_mut() = self.method;
+        *req.uri_mut() = self.uri;
+        *req.version_mut() = batch.version();
+        *req.body_mut().socket_addr_mut() = *batch.body().socket_addr();
+
+        let headers = req.headers_mut();
+        for (name, value) in batch.headers() {
+            if !matches!(*name, CONTENT_TYPE | CONTENT_LENGTH | TRANSFER_ENCODING) {
+                headers.append(name, value.clone());
+            }
+        }
+
+        let mut name = None;
+        let mut own = self.headers;
+        for (next, value) in own.drain() {
+            if let Some(next) = next {
+                headers.remove(&next);
+                name = Some(next);
+            }
+            // drain always yields name for the first value.
+            headers.append(name.clone().unwrap(), value);
+        }
+
+        if !self.body.is_empty() {
+            headers.insert(CONTENT_LENGTH, HeaderValue::from(self.body.len()));
+        }
+
+        Ok((req, self.body))
+    }
+}
+
+struct SubResponse {
+    id: Option<String>,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl SubResponse {
+    async fn collect<B, E>(res: WebResponse<ResponseBody<B>>, id: Option<String>) -> Self
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+    {
+        let (parts, body) = res.into_parts();
+        let mut body = pin!(body);
+        let mut buf = BytesMut::new();
+
+        while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            match chunk {
+                Ok(bytes) => buf.extend_from_slice(&bytes),
+                Err(_) => {
+                    // response body failed half way. replace it with an empty server error.
+                    return Self {
+                        id,
+                        status: StatusCode::INTERNAL_SERVER_ERROR,
+                        headers: HeaderMap::new(),
+                        body: Bytes::new(),
+                    };
+                }
+            }
+        }
+
+        Self {
+            id,
+            status: parts.status,
+            headers: parts.headers,
+            body: buf.freeze(),
+        }
+    }
+}
+
+// split body into head and the rest at the first empty line. body without empty line is all head.
+fn split_head(buf: &[u8]) -> (&[u8], &[u8]) {
+    let mut start = 0;
+    while let Some(pos) = buf[start..].iter().position(|b| *b == b'\n') {
+        let end = start + pos;
+        let line = &buf[start..end];
+        if line.is_empty() || line == b"\r" {
+            return (&buf[..start], &buf[end + 1..]);
+        }
+        start = end + 1;
+    }
+    (buf, &[])
+}
+
+fn lines(head: &[u8]) -> impl Iterator<Item = &str> {
+    head.split(|b| *b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.is_empty())
+        .map(|line| core::str::from_utf8(line).unwrap_or_default())
+}
+
+fn find(buf: &[u8], needle: &[u8]) -> Option<usize> {
+    buf.windows(needle.len()).position(|window| window == needle)
+}
+
+fn parse_header(line: &str) -> Result<(HeaderName, HeaderValue), BatchError> {
+    let (name, value) = line.split_once(':').ok_or(BatchError::Malformed)?;
+    let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| BatchError::Malformed)?;
+    let value = HeaderValue::from_str(value.trim()).map_err(|_| BatchError::Malformed)?;
+    Ok((name, value))
+}
+
+fn decode_multipart(body: &Bytes, boundary: &str) -> Result<Vec<SubRequest>, BatchError> {
+    let delimiter = format!("--{boundary}");
+    let delimiter = delimiter.as_bytes();
+
+    // skip preamble.
+    let start = find(body, delimiter).ok_or(BatchError::Malformed)?;
+    let mut rest = &body[start + delimiter.len()..];
+
+    let mut requests = Vec::new();
+
+    loop {
+        // close delimiter ends the body.
+        if rest.starts_with(b"--") {
+            return Ok(requests);
+        }
+
+        // skip transport padding after delimiter.
+        let line_end = rest.iter().position(|b| *b == b'\n').ok_or(BatchError::Malformed)?;
+        rest = &rest[line_end + 1..];
+
+        let end = find(rest, delimiter).ok_or(BatchError::Malformed)?;
+        let part = &rest[..end];
+        let part = part.strip_suffix(b"\n").unwrap_or(part);
+        let part = part.strip_suffix(b"\r").unwrap_or(part);
+        rest = &rest[end + delimiter.len()..];
+
+        let (part_head, message) = split_head(part);
+
+        let mut id = None;
+        for line in lines(part_head) {
+            let (name, value) = parse_header(line)?;
+            if name.as_str() == "content-id" {
+                id = value.to_str().ok().map(str::to_owned);
+            }
+        }
+
+        let (head, msg_body) = split_head(message);
+        let mut head = lines(head);
+
+        let request_line = head.next().ok_or(BatchError::Malformed)?;
+        let mut request_line = request_line.split_whitespace();
+        let method = request_line.next().ok_or(BatchError::Malformed)?;
+        let method = Method::from_bytes(method.as_bytes()).map_err(|_| BatchError::Malformed)?;
+        let uri = request_line.next().ok_or(BatchError::Malformed)?;
+        let uri = uri.parse::<Uri>().map_err(|_| BatchError::Malformed)?;
+
+        let mut headers = HeaderMap::new();
+        for line in head {
+            let (name, value) = parse_header(line)?;
+            headers.append(name, value);
+        }
+
+        requests.push(SubRequest {
+            id,
+            method,
+            uri,
+            headers,
+            body: body.slice_ref(msg_body),
+        });
+    }
+}
+
+fn encode_multipart(responses: Vec<SubResponse>, boundary: &str) -> (HeaderValue, Bytes) {
+    let boundary = format!("batch_{boundary}");
+    let mut buf = BytesMut::new();
+
+    for res in responses {
+        buf.extend_from_slice(format!("--{boundary}\r\ncontent-type: application/http\r\n").as_bytes());
+        if let Some(id) = res.id {
+            buf.extend_from_slice(format!("content-id: response-{id}\r\n").as_bytes());
+        }
+        let reason = res.status.canonical_reason().unwrap_or_default();
+        buf.extend_from_slice(format!("\r\nHTTP/1.1 {} {reason}\r\n", res.status.as_str()).as_bytes());
+
+        for (name, value) in res.headers.iter() {
+            if matches!(*name, CONTENT_LENGTH | TRANSFER_ENCODING) {
+                continue;
+            }
+            buf.extend_from_slice(name.as_str().as_bytes());
+            buf.extend_from_slice(b": ");
+            buf.extend_from_slice(value.as_bytes());
+            buf.extend_from_slice(b"\r\n");
+        }
+        buf.extend_from_slice(format!("content-length: {}\r\n\r\n", res.body.len()).as_bytes());
+        buf.extend_from_slice(&res.body);
+        buf.extend_from_slice(b"\r\n");
+    }
+
+    buf.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+    // boundary is taken from valid header value.
+    let content_type = HeaderValue::try_from(format!("multipart/mixed; boundary={boundary}")).unwrap();
+
+    (content_type, buf.freeze())
+}
+
+fn decode_json(body: &Bytes) -> Result<Vec<SubRequest>, BatchError> {
+    let Value::Array(items) = serde_json::from_slice(body).map_err(|_| BatchError::Malformed)? else {
+        return Err(BatchError::Malformed);
+    };
+
+    items
+        .into_iter()
+        .map(|item| {
+            let Value::Object(mut item) = item else {
+                return Err(BatchError::Malformed);
+            };
+
+            let method = match item.remove("method") {
+                Some(Value::String(method)) => {
+                    Method::from_bytes(method.as_bytes()).map_err(|_| BatchError::Malformed)?
+                }
+                None => Method::GET,
+                Some(_) => return Err(BatchError::Malformed),
+            };
+
+            let uri = match item.remove("path") {
+                Some(Value::String(path)) => path.parse::<Uri>().map_err(|_| BatchError::Malformed)?,
+                _ => return Err(BatchError::Malformed),
+            };
+
+            let mut headers = HeaderMap::new();
+            match item.remove("headers") {
+                Some(Value::Object(map)) => {
+                    for (name, value) in map {
+                        let Value::String(value) = value else {
+                            return Err(BatchError::Malformed);
+                        };
+                        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| BatchError::Malformed)?;
+                        let value = HeaderValue::try_from(value).map_err(|_| BatchError::Malformed)?;
+                        headers.append(name, value);
+                    }
+                }
+                None | Some(Value::Null) => {}
+                Some(_) => return Err(BatchError::Malformed),
+            }
+
+            let body = match item.remove("body") {
+                None | Some(Value::Null) => Bytes::new(),
+                Some(Value::String(body)) => Bytes::from(body),
+                Some(value) => {
+                    if !headers.contains_key(CONTENT_TYPE) {
+                        headers.insert(CONTENT_TYPE, JSON);
+                    }
+                    // serializing json value is infallible.
+                    Bytes::from(serde_json::to_vec(&value).unwrap())
+                }
+            };
+
+            Ok(SubRequest {
+                id: None,
+                method,
+                uri,
+                headers,
+                body,
+            })
+        })
+        .collect()
+}
+
+fn encode_json(responses: Vec<SubResponse>) -> Bytes {
+    let responses = responses
+        .into_iter()
+        .map(|res| {
+            let is_json = res
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.contains("json"));
+
+            let mut headers = Map::new();
+            for name in res.headers.keys() {
+                let value = res
+                    .headers
+                    .get_all(name)
+                    .iter()
+                    .map(|v| String::from_utf8_lossy(v.as_bytes()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                headers.insert(name.as_str().to_owned(), Value::String(value));
+            }
+
+            let body = if res.body.is_empty() {
+                Value::Null
+            } else if let Some(value) = is_json.then(|| serde_json::from_slice(&res.body).ok()).flatten() {
+                value
+            } else {
+                Value::String(String::from_utf8_lossy(&res.body).into_owned())
+            };
+
+            let mut obj = Map::new();
+            obj.insert("status".to_owned(), Value::from(res.status.as_u16()));
+            obj.insert("headers".to_owned(), Value::Object(headers));
+            obj.insert("body".to_owned(), body);
+            Value::Object(obj)
+        })
+        .collect::<Vec<_>>();
+
+    // serializing json value is infallible.
+    Bytes::from(serde_json::to_vec(&responses).unwrap())
+}
+
+/// Error type of [Batch] middleware.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BatchError {
+    /// Content type of batch request is neither `multipart/mixed` nor `application/json`.
+    UnsupportedMediaType,
+    /// Batch request body or one of it's sub-requests is malformed.
+    Malformed,
+    /// Batch request body is larger than configured limit.
+    PayloadTooLarge,
+    /// Batch request contains more sub-requests than configured max count.
+    TooManyRequests,
+    /// Failed to read batch request body.
+    Body,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::UnsupportedMediaType => f.write_str("Batch request must be multipart/mixed or application/json."),
+            Self::Malformed => f.write_str("Batch request is malformed."),
+            Self::PayloadTooLarge => f.write_str("Batch request body is too large."),
+            Self::TooManyRequests => f.write_str("Batch request contains too many sub-requests."),
+            Self::Body => f.write_str("Failed to read batch request body."),
+        }
+    }
+}
+
+impl error::Error for BatchError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for BatchError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        let status = match self {
+            Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Malformed | Self::TooManyRequests | Self::Body => StatusCode::BAD_REQUEST,
+        };
+        let mut res = ctx.into_response(format!("{self}"));
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        *res.status_mut() = status;
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{header::AUTHORIZATION, Request, RequestExt},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    fn req(content_type: &'static str, body: &'static str) -> WebRequest {
+        let body = RequestExt::default().map_body(|_: ()| Bytes::from_static(body.as_bytes()).into());
+        Request::builder()
+            .method(Method::POST)
+            .uri("/batch")
+            .header(CONTENT_TYPE, content_type)
+            .header(AUTHORIZATION, "Bearer 996")
+            .body(body)
+            .unwrap()
+    }
+
+    async fn echo(ctx: &WebContext<'_>) -> String {
+        let auth = ctx.req().headers().get(AUTHORIZATION).unwrap().to_str().unwrap();
+        format!("{} {auth}", ctx.req().uri())
+    }
+
+    async fn post(body: String) -> String {
+        format!("posted {body}")
+    }
+
+    #[test]
+    fn batch() {
+        let service = App::new()
+            .at("/echo", handler_service(echo))
+            .at("/post", handler_service(post))
+            .enclosed(Batch::new("/batch").max_requests(3))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let body = "preamble\r\n\
+            --xyz\r\n\
+            content-type: application/http\r\n\
+            content-id: 1\r\n\
+            \r\n\
+            GET /echo?q=1 HTTP/1.1\r\n\
+            authorization: Bearer 251\r\n\
+            \r\n\
+            --xyz\r\n\
+            content-type: application/http\r\n\
+            \r\n\
+            POST /post HTTP/1.1\r\n\
+            content-type: text/plain\r\n\
+            \r\n\
+            hello\r\n\
+            --xyz\r\n\
+            content-type: application/http\r\n\
+            \r\n\
+            GET /nah HTTP/1.1\r\n\
+            --xyz--\r\n";
+
+        let res = service
+            .call(req("multipart/mixed; boundary=xyz", body))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(CONTENT_TYPE).unwrap(),
+            "multipart/mixed; boundary=batch_xyz"
+        );
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        let parts = body.split("--batch_xyz").collect::<Vec<_>>();
+        assert_eq!(parts.len(), 5);
+        assert!(parts[1].contains("content-id: response-1\r\n"));
+        assert!(parts[1].contains("\r\nHTTP/1.1 200 OK\r\n"));
+        assert!(parts[1].ends_with("\r\n\r\n/echo?q=1 Bearer 251\r\n"));
+        assert!(parts[2].ends_with("\r\n\r\nposted hello\r\n"));
+        assert!(parts[3].contains("\r\nHTTP/1.1 404 Not Found\r\n"));
+        assert_eq!(parts[4], "--\r\n");
+
+        let body = r#"[
+            { "path": "/echo" },
+            { "method": "POST", "path": "/post", "body": { "id": 1 } },
+            { "path": "/echo", "headers": { "authorization": "Bearer 007" } }
+        ]"#;
+
+        let res = service.call(req("application/json", body)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        let Value::Array(res) = serde_json::from_str::<Value>(&body).unwrap() else {
+            panic!("batch response must be array")
+        };
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0]["status"], 200);
+        assert_eq!(res[0]["body"], "/echo Bearer 996");
+        assert_eq!(res[1]["body"], r#"posted {"id":1}"#);
+        assert_eq!(res[2]["body"], "/echo Bearer 007");
+
+        let body = r#"[{ "path": "/echo" }, { "path": "/echo" }, { "path": "/echo" }, { "path": "/echo" }]"#;
+        let res = service.call(req("application/json", body)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = service.call(req("text/plain", "")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let res = service.call(req("application/json", "{}")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
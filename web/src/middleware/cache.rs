@@ -0,0 +1,598 @@
+//! in memory response caching middleware.
+
+use core::{
+    cell::RefCell,
+    convert::Infallible,
+    marker::PhantomData,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use std::{collections::HashMap, error, rc::Rc, time::Instant};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use xitca_http::util::service::router::{RouterGen, RouterMapErr};
+
+use crate::{
+    bytes::{Bytes, BytesMut},
+    context::WebContext,
+    dev::service::{
+        object::{self, BoxedSyncServiceObject},
+        ready::ReadyService,
+        Service,
+    },
+    http::{
+        header::{AUTHORIZATION, CACHE_CONTROL, SET_COOKIE, VARY},
+        HeaderMap, Method, StatusCode, WebResponse,
+    },
+    middleware::eraser::TypeEraser,
+};
+
+/// Part of request uri used as cache key.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CacheKey {
+    /// Request path without query string. Requests differ only in query share the same response.
+    Path,
+    /// Request path and query string.
+    #[default]
+    Query,
+}
+
+/// A middleware caching `200 OK` response of `GET` request in memory for given duration.
+///
+/// Response body is streamed to client as is and stored when it's fully sent. Following requests
+/// with the same [CacheKey] are served from cache until the entry expires. Response with body
+/// error is not stored.
+///
+/// Request with `Authorization` header bypasses cache. Response with `Set-Cookie` or `Vary` header,
+/// or `Cache-Control` header containing `private` or `no-store` directive is not stored. Response
+/// with body larger than [Cache::max_body_size] is not stored. When cache is full with
+/// [Cache::max_entries] the entry closest to expire is evicted for new one.
+///
+/// Cache storage is owned by every service instance built from this middleware. In multi-thread
+/// server each worker thread keeps it's own cache.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{
+/// #   dev::service::ServiceExt,
+/// #   handler::handler_service,
+/// #   middleware::cache::{Cache, CacheKey},
+/// #   route::get,
+/// #   App, WebContext
+/// # };
+/// App::new()
+///     .at(
+///         "/",
+///         get(handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///             .enclosed(Cache::new(Duration::from_secs(30)).key(CacheKey::Path).max_entries(16)),
+///     );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Cache {
+    ttl: Duration,
+    key: CacheKey,
+    max_entries: usize,
+    max_body_size: usize,
+}
+
+impl Cache {
+    /// Construct a cache middleware keeping response for given duration.
+    pub const fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            key: CacheKey::Query,
+            max_entries: 1024,
+            max_body_size: 1024 * 1024,
+        }
+    }
+
+    /// Set part of request uri used as cache key.
+    ///
+    /// Default to [CacheKey::Query].
+    pub const fn key(mut self, key: CacheKey) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Set max number of responses can be cached.
+    ///
+    /// Default to 1024.
+    pub const fn max_entries(mut self, max: usize) -> Self {
+        self.max_entries = max;
+        self
+    }
+
+    /// Set max size in byte unit of response body can be cached.
+    ///
+    /// Default to 1MiB.
+    pub const fn max_body_size(mut self, max: usize) -> Self {
+        self.max_body_size = max;
+        self
+    }
+}
+
+impl<S> Service<S> for Cache {
+    type Response = CacheService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(CacheService {
+            service,
+            cache: *self,
+            entries: Rc::new(RefCell::new(HashMap::new())),
+        })
+    }
+}
+
+/// Handler service wrapped with [Cache] middleware. Response body type is erased so the service
+/// can be routed along side other handler services.
+///
+/// Usually constructed by function generated with `#[cache]` attribute macro from
+/// `xitca_web::codegen` module.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{handler::handler_service, middleware::cache::{Cache, CacheHandler}, App, WebContext};
+/// async fn index(_: &WebContext<'_>) -> &'static str {
+///     "hello,world!"
+/// }
+///
+/// App::new()
+///     .at("/", CacheHandler::new(handler_service(index), Cache::new(Duration::from_secs(30))))
+///     .at("/nah", handler_service(index));
+/// ```
+pub struct CacheHandler<C, B, Err> {
+    builder: BoxedSyncServiceObject<(), CacheHandlerObject<C, B, Err>, Infallible>,
+}
+
+type CacheHandlerObject<C, B, Err> =
+    Box<dyn for<'r> object::ServiceObject<WebContext<'r, C, B>, Response = WebResponse, Error = Err>>;
+
+impl<C, B, Err> CacheHandler<C, B, Err>
+where
+    C: 'static,
+    B: 'static,
+{
+    /// Wrap given handler service with cache middleware.
+    pub fn new<F, ResB, E>(service: F, cache: Cache) -> Self
+    where
+        F: Service<Error = Infallible> + Send + Sync + 'static,
+        F::Response: for<'r> Service<WebContext<'r, C, B>, Response = WebResponse<ResB>, Error = Err> + 'static,
+        ResB: Stream<Item = Result<Bytes, E>> + 'static,
+        E: error::Error + Send + Sync + 'static,
+    {
+        struct Builder<F, C, B>(F, Cache, PhantomData<fn(C, B)>);
+
+        impl<F, C, B, Err, ResB, E> Service for Builder<F, C, B>
+        where
+            F: Service<Error = Infallible>,
+            F::Response: for<'r> Service<WebContext<'r, C, B>, Response = WebResponse<ResB>, Error = Err> + 'static,
+            ResB: Stream<Item = Result<Bytes, E>> + 'static,
+            E: error::Error + Send + Sync + 'static,
+        {
+            type Response = CacheHandlerObject<C, B, Err>;
+            type Error = Infallible;
+
+            async fn call(&self, arg: ()) -> Result<Self::Response, Self::Error> {
+                let service = self.0.call(arg).await?;
+                let service = self.1.call(service).await?;
+                let service = TypeEraser::response_body().call(service).await?;
+                Ok(Box::new(service))
+            }
+        }
+
+        Self {
+            builder: Box::new(Builder(service, cache, PhantomData)),
+        }
+    }
+}
+
+impl<C, B, Err> Service for CacheHandler<C, B, Err> {
+    type Response = CacheHandlerObject<C, B, Err>;
+    type Error = Infallible;
+
+    #[inline]
+    async fn call(&self, arg: ()) -> Result<Self::Response, Self::Error> {
+        self.builder.call(arg).await
+    }
+}
+
+impl<C, B, Err> RouterGen for CacheHandler<C, B, Err> {
+    type ErrGen<R> = RouterMapErr<R>;
+
+    fn err_gen<R>(route: R) -> Self::ErrGen<R> {
+        RouterMapErr(route)
+    }
+}
+
+type Entries = Rc<RefCell<HashMap<String, Entry>>>;
+
+struct Entry {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    expires: Instant,
+}
+
+pub struct CacheService<S> {
+    service: S,
+    cache: Cache,
+    entries: Entries,
+}
+
+impl<'r, S, C, B, ResB, Err> Service<WebContext<'r, C, B>> for CacheService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = Err>,
+{
+    type Response = WebResponse<CacheBody<ResB>>;
+    type Error = Err;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        if ctx.req().method() != Method::GET || ctx.req().headers().contains_key(AUTHORIZATION) {
+            let res = self.service.call(ctx).await?;
+            return Ok(res.map(|body| CacheBody::miss(body, None)));
+        }
+
+        let uri = ctx.req().uri();
+        let key = match self.cache.key {
+            CacheKey::Path => uri.path(),
+            CacheKey::Query => uri.path_and_query().map(|pq| pq.as_str()).unwrap_or_else(|| uri.path()),
+        }
+        .to_owned();
+
+        let now = Instant::now();
+
+        if let Some(entry) = self.entries.borrow().get(&key).filter(|entry| entry.expires > now) {
+            let mut res = WebResponse::new(CacheBody::hit(entry.body.clone()));
+            *res.status_mut() = entry.status;
+            *res.headers_mut() = entry.headers.clone();
+            return Ok(res);
+        }
+
+        let res = self.service.call(ctx).await?;
+
+        if res.status() != StatusCode::OK || !is_cacheable(res.headers()) {
+            return Ok(res.map(|body| CacheBody::miss(body, None)));
+        }
+
+        let store = Store {
+            entries: self.entries.clone(),
+            max_entries: self.cache.max_entries,
+            max_body_size: self.cache.max_body_size,
+            key,
+            status: res.status(),
+            headers: res.headers().clone(),
+            body: BytesMut::new(),
+            expires: now + self.cache.ttl,
+        };
+
+        Ok(res.map(|body| CacheBody::miss(body, Some(store))))
+    }
+}
+
+impl<S> ReadyService for CacheService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+fn is_cacheable(headers: &HeaderMap) -> bool {
+    if headers.contains_key(SET_COOKIE) || headers.contains_key(VARY) {
+        return false;
+    }
+
+    !headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .flat_map(|v| v.to_str().unwrap_or_default().split(','))
+        .map(|directive| directive.split('=').next().unwrap_or_default().trim())
+        .any(|directive| directive.eq_ignore_ascii_case("private") || directive.eq_ignore_ascii_case("no-store"))
+}
+
+// pending cache entry collecting response body as it's streamed.
+struct Store {
+    entries: Entries,
+    max_entries: usize,
+    max_body_size: usize,
+    key: String,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: BytesMut,
+    expires: Instant,
+}
+
+impl Store {
+    // collect chunk of response body. return false when body is too large to be cached.
+    fn extend(&mut self, chunk: &[u8]) -> bool {
+        if chunk.len() > self.max_body_size - self.body.len() {
+            return false;
+        }
+        self.body.extend_from_slice(chunk);
+        true
+    }
+
+    fn finish(self) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut entries = self.entries.borrow_mut();
+        entries.retain(|_, entry| entry.expires > now);
+
+        if entries.len() >= self.max_entries && !entries.contains_key(&self.key) {
+            let key = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires)
+                .map(|(key, _)| key.clone());
+            if let Some(key) = key {
+                entries.remove(&key);
+            }
+        }
+
+        entries.insert(
+            self.key,
+            Entry {
+                status: self.status,
+                headers: self.headers,
+                body: self.body.freeze(),
+                expires: self.expires,
+            },
+        );
+    }
+}
+
+pin_project! {
+    /// Response body type of [CacheService].
+    #[project = CacheBodyProj]
+    pub enum CacheBody<B> {
+        Hit {
+            body: Option<Bytes>,
+        },
+        Miss {
+            #[pin]
+            body: B,
+            store: Option<Store>,
+        },
+    }
+}
+
+impl<B> CacheBody<B> {
+    fn hit(body: Bytes) -> Self {
+        Self::Hit { body: Some(body) }
+    }
+
+    fn miss(body: B, store: Option<Store>) -> Self {
+        Self::Miss { body, store }
+    }
+}
+
+impl<B, E> Stream for CacheBody<B>
+where
+    B: Stream<Item = Result<Bytes, E>>,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.project() {
+            CacheBodyProj::Hit { body } => Poll::Ready(body.take().map(Ok)),
+            CacheBodyProj::Miss { body, store } => {
+                let res = ready!(body.poll_next(cx));
+                match res {
+                    Some(Ok(ref chunk)) => {
+                        if let Some(s) = store {
+                            if !s.extend(chunk) {
+                                drop(store.take());
+                            }
+                        }
+                    }
+                    Some(Err(_)) => drop(store.take()),
+                    None => {
+                        if let Some(store) = store.take() {
+                            store.finish();
+                        }
+                    }
+                }
+                Poll::Ready(res)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Hit { body } => {
+                let len = body.as_ref().map(Bytes::len).unwrap_or(0);
+                (len, Some(len))
+            }
+            Self::Miss { body, .. } => body.size_hint(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::cell::Cell;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::ResponseBody,
+        handler::handler_service,
+        http::{header::HeaderValue, Uri, WebRequest},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    thread_local! {
+        static COUNT: Cell<usize> = const { Cell::new(0) };
+    }
+
+    async fn handler(_: &WebContext<'_>) -> String {
+        COUNT.with(|c| {
+            c.set(c.get() + 1);
+            c.get().to_string()
+        })
+    }
+
+    #[test]
+    fn cache() {
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Cache::new(Duration::from_secs(30)))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let get = |uri| {
+            let mut req = WebRequest::default();
+            *req.uri_mut() = Uri::from_static(uri);
+            let res = service.call(req).now_or_panic().unwrap();
+            collect_string_body(res.into_body()).now_or_panic().unwrap()
+        };
+
+        assert_eq!(get("/"), "1");
+        assert_eq!(get("/"), "1");
+        // query is part of default cache key.
+        assert_eq!(get("/?foo=bar"), "2");
+        assert_eq!(get("/?foo=bar"), "2");
+
+        let mut req = WebRequest::default();
+        *req.method_mut() = Method::POST;
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "3");
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Cache::new(Duration::from_secs(30)).key(CacheKey::Path))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let get = |uri| {
+            let mut req = WebRequest::default();
+            *req.uri_mut() = Uri::from_static(uri);
+            let res = service.call(req).now_or_panic().unwrap();
+            collect_string_body(res.into_body()).now_or_panic().unwrap()
+        };
+
+        assert_eq!(get("/?foo=bar"), "4");
+        assert_eq!(get("/?foo=bar"), "4");
+        assert_eq!(get("/?foo=baz"), "4");
+    }
+
+    #[test]
+    fn uncacheable() {
+        // respond with header named after request path.
+        async fn header_handler(ctx: &WebContext<'_>) -> WebResponse {
+            let mut res = WebResponse::new(ResponseBody::from(handler(ctx).await));
+            let (name, value) = match ctx.req().uri().path() {
+                "/cookie" => (SET_COOKIE, "id=996"),
+                "/vary" => (VARY, "accept-encoding"),
+                "/private" => (CACHE_CONTROL, "max-age=60, Private"),
+                "/no-store" => (CACHE_CONTROL, "no-store"),
+                _ => (CACHE_CONTROL, "public, max-age=60"),
+            };
+            res.headers_mut().insert(name, HeaderValue::from_static(value));
+            res
+        }
+
+        let service = App::new()
+            .at("/*path", handler_service(header_handler))
+            .enclosed(Cache::new(Duration::from_secs(30)))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let get = |uri, auth| {
+            let mut req = WebRequest::default();
+            *req.uri_mut() = Uri::from_static(uri);
+            if auth {
+                req.headers_mut()
+                    .insert(AUTHORIZATION, HeaderValue::from_static("Bearer xitca"));
+            }
+            let res = service.call(req).now_or_panic().unwrap();
+            collect_string_body(res.into_body())
+                .now_or_panic()
+                .unwrap()
+                .parse::<usize>()
+                .unwrap()
+        };
+
+        for uri in ["/cookie", "/vary", "/private", "/no-store"] {
+            assert_ne!(get(uri, false), get(uri, false), "{uri} must not be cached");
+        }
+
+        let n = get("/public", false);
+        assert_eq!(get("/public", false), n);
+        // authorized request bypasses cache.
+        assert_ne!(get("/public", true), n);
+    }
+
+    #[test]
+    fn capacity() {
+        async fn large(_: &WebContext<'_>) -> String {
+            COUNT.with(|c| {
+                c.set(c.get() + 1);
+                format!("{:08}", c.get())
+            })
+        }
+
+        let service = App::new()
+            .at("/*path", handler_service(large))
+            .enclosed(Cache::new(Duration::from_secs(30)).max_entries(2).max_body_size(8))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let get = |uri| {
+            let mut req = WebRequest::default();
+            *req.uri_mut() = Uri::from_static(uri);
+            let res = service.call(req).now_or_panic().unwrap();
+            collect_string_body(res.into_body()).now_or_panic().unwrap()
+        };
+
+        let a = get("/a");
+        assert_eq!(get("/a"), a);
+        let b = get("/b");
+        assert_eq!(get("/b"), b);
+
+        // cache is full and the oldest entry is evicted.
+        let c = get("/c");
+        assert_eq!(get("/c"), c);
+        assert_eq!(get("/b"), b);
+        assert_ne!(get("/a"), a);
+
+        let service = App::new()
+            .at("/", handler_service(large))
+            .enclosed(Cache::new(Duration::from_secs(30)).max_body_size(7))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let get = || {
+            let res = service.call(WebRequest::default()).now_or_panic().unwrap();
+            collect_string_body(res.into_body()).now_or_panic().unwrap()
+        };
+
+        // body larger than limit is not cached.
+        assert_ne!(get(), get());
+    }
+}
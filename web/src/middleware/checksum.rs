@@ -0,0 +1,422 @@
+//! request body checksum verification middleware.
+
+use std::{
+    cell::{Cell, RefCell},
+    convert::Infallible,
+    error, fmt,
+    pin::Pin,
+    rc::Rc,
+    task::{ready, Context, Poll},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_core::stream::Stream;
+use md5::{Digest, Md5};
+use pin_project_lite::pin_project;
+use sha2::{Sha256, Sha512};
+use xitca_http::Request;
+
+use crate::{
+    body::BodyStream,
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    handler::Responder,
+    http::{
+        const_header_value::TEXT_UTF8,
+        header::{HeaderMap, HeaderName, CONTENT_TYPE},
+        StatusCode, WebResponse,
+    },
+};
+
+const CONTENT_MD5: HeaderName = HeaderName::from_static("content-md5");
+const DIGEST: HeaderName = HeaderName::from_static("digest");
+const CONTENT_DIGEST: HeaderName = HeaderName::from_static("content-digest");
+
+/// A middleware verifying request body against checksum headers.
+///
+/// Supported headers are `Content-MD5`, `Digest`(RFC 3230) and `Content-Digest`(RFC 9530) with
+/// `md5`, `sha-256` and `sha-512` algorithms. Checksums are computed incrementally while body is
+/// streamed to the service and every checksum of supported algorithm must match. Digest of other
+/// algorithms are ignored.
+///
+/// Malformed checksum header is rejected with `400 Bad Request` before service is called. When
+/// checksum mismatch the end of request body is replaced with [ChecksumError::Mismatch] error and
+/// the response of service is discarded in favor of `400 Bad Request` no matter how service
+/// handled the body error.
+///
+/// Checksums are computed on body bytes as received. The middleware must enclose decompress
+/// middleware when they are used together.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{
+/// #   body::RequestBody,
+/// #   handler::handler_service,
+/// #   middleware::checksum::{Checksum, ChecksumBody},
+/// #   App, WebContext
+/// # };
+/// // body is verified when it's collected by extractor.
+/// async fn webhook(body: Vec<u8>, _: &WebContext<'_, (), ChecksumBody<RequestBody>>) -> String {
+///     format!("received {} bytes", body.len())
+/// }
+///
+/// App::new()
+///     .at("/webhook", handler_service(webhook))
+///     // request without checksum header is rejected.
+///     .enclosed(Checksum::new().set_required(true));
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct Checksum {
+    required: bool,
+}
+
+impl Checksum {
+    /// Construct a new middleware verifying checksum when request carries one.
+    pub const fn new() -> Self {
+        Self { required: false }
+    }
+
+    /// Reject request without checksum of supported algorithm with [ChecksumError::Missing].
+    ///
+    /// Default to false.
+    pub const fn set_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+}
+
+impl<S> Service<S> for Checksum {
+    type Response = ChecksumService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(ChecksumService {
+            service,
+            checksum: *self,
+        })
+    }
+}
+
+pub struct ChecksumService<S> {
+    service: S,
+    checksum: Checksum,
+}
+
+pub type ChecksumServiceError<E> = PipelineE<ChecksumError, E>;
+
+impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for ChecksumService<S>
+where
+    B: BodyStream + Default,
+    S: for<'r2> Service<WebContext<'r2, C, ChecksumBody<B>>, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = ChecksumServiceError<Err>;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let expected = expected_checksums(ctx.req().headers()).map_err(ChecksumServiceError::First)?;
+
+        if expected.is_empty() && self.checksum.required {
+            return Err(ChecksumServiceError::First(ChecksumError::Missing));
+        }
+
+        let (parts, ext) = ctx.take_request().into_parts();
+        let WebContext { ctx, res_headers, .. } = ctx;
+        let (ext, body) = ext.replace_body(());
+        let mismatch = Rc::new(Cell::new(false));
+        let mut body = RefCell::new(ChecksumBody::new(body, expected, mismatch.clone()));
+        let mut req = Request::from_parts(parts, ext);
+
+        let ctx = WebContext::new(&mut req, &mut body, ctx, res_headers);
+
+        let res = self.service.call(ctx).await;
+
+        if mismatch.get() {
+            return Err(ChecksumServiceError::First(ChecksumError::Mismatch));
+        }
+
+        res.map_err(ChecksumServiceError::Second)
+    }
+}
+
+impl<S> ReadyService for ChecksumService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+enum Hasher {
+    Md5(Md5),
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+impl Hasher {
+    fn from_algorithm(algorithm: &str) -> Option<Self> {
+        if algorithm.eq_ignore_ascii_case("md5") {
+            Some(Self::Md5(Md5::new()))
+        } else if algorithm.eq_ignore_ascii_case("sha-256") {
+            Some(Self::Sha256(Sha256::new()))
+        } else if algorithm.eq_ignore_ascii_case("sha-512") {
+            Some(Self::Sha512(Sha512::new()))
+        } else {
+            None
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(h) => h.update(data),
+            Self::Sha256(h) => h.update(data),
+            Self::Sha512(h) => h.update(data),
+        }
+    }
+
+    fn verify(self, expected: &[u8]) -> bool {
+        match self {
+            Self::Md5(h) => h.finalize().as_slice() == expected,
+            Self::Sha256(h) => h.finalize().as_slice() == expected,
+            Self::Sha512(h) => h.finalize().as_slice() == expected,
+        }
+    }
+}
+
+// collect checksums of supported algorithms from request headers.
+fn expected_checksums(headers: &HeaderMap) -> Result<Vec<(Hasher, Vec<u8>)>, ChecksumError> {
+    let mut expected = Vec::new();
+
+    let decode = |value: &str| STANDARD.decode(value.trim()).map_err(|_| ChecksumError::Malformed);
+
+    let values = |name| {
+        headers
+            .get_all(name)
+            .into_iter()
+            .map(|v| v.to_str().map_err(|_| ChecksumError::Malformed))
+    };
+
+    for value in values(CONTENT_MD5) {
+        expected.push((Hasher::Md5(Md5::new()), decode(value?)?));
+    }
+
+    // Digest: md5=<base64>, sha-256=<base64>
+    for value in values(DIGEST) {
+        for item in value?.split(',') {
+            let (algorithm, digest) = item.split_once('=').ok_or(ChecksumError::Malformed)?;
+            if let Some(hasher) = Hasher::from_algorithm(algorithm.trim()) {
+                expected.push((hasher, decode(digest)?));
+            }
+        }
+    }
+
+    // Content-Digest: sha-256=:<base64>:, sha-512=:<base64>:
+    for value in values(CONTENT_DIGEST) {
+        for item in value?.split(',') {
+            let (algorithm, digest) = item.split_once('=').ok_or(ChecksumError::Malformed)?;
+            let digest = digest
+                .trim()
+                .strip_prefix(':')
+                .and_then(|d| d.strip_suffix(':'))
+                .ok_or(ChecksumError::Malformed)?;
+            if let Some(hasher) = Hasher::from_algorithm(algorithm.trim()) {
+                expected.push((hasher, decode(digest)?));
+            }
+        }
+    }
+
+    Ok(expected)
+}
+
+pin_project! {
+    pub struct ChecksumBody<B> {
+        expected: Vec<(Hasher, Vec<u8>)>,
+        mismatch: Rc<Cell<bool>>,
+        #[pin]
+        body: B
+    }
+}
+
+impl<B: Default> Default for ChecksumBody<B> {
+    fn default() -> Self {
+        Self {
+            expected: Vec::new(),
+            mismatch: Rc::default(),
+            body: B::default(),
+        }
+    }
+}
+
+impl<B> ChecksumBody<B> {
+    fn new(body: B, expected: Vec<(Hasher, Vec<u8>)>, mismatch: Rc<Cell<bool>>) -> Self {
+        Self {
+            expected,
+            mismatch,
+            body,
+        }
+    }
+}
+
+impl<B> Stream for ChecksumBody<B>
+where
+    B: BodyStream,
+{
+    type Item = Result<B::Chunk, ChecksumBodyError<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        match ready!(this.body.poll_next(cx)) {
+            Some(res) => {
+                let chunk = res.map_err(ChecksumBodyError::Second)?;
+                for (hasher, _) in this.expected.iter_mut() {
+                    hasher.update(chunk.as_ref());
+                }
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            None => {
+                let mismatch = this
+                    .expected
+                    .drain(..)
+                    .any(|(hasher, expected)| !hasher.verify(&expected));
+                if mismatch {
+                    this.mismatch.set(true);
+                    Poll::Ready(Some(Err(ChecksumBodyError::First(ChecksumError::Mismatch))))
+                } else {
+                    Poll::Ready(None)
+                }
+            }
+        }
+    }
+}
+
+pub type ChecksumBodyError<E> = PipelineE<ChecksumError, E>;
+
+/// Error type of [Checksum] middleware.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ChecksumError {
+    /// Request does not carry checksum of supported algorithm.
+    Missing,
+    /// Checksum header value can not be parsed.
+    Malformed,
+    /// Checksum of request body does not match the one in header.
+    Mismatch,
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Missing => f.write_str("Request body checksum is missing."),
+            Self::Malformed => f.write_str("Request body checksum is malformed."),
+            Self::Mismatch => f.write_str("Request body checksum mismatch."),
+        }
+    }
+}
+
+impl error::Error for ChecksumError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for ChecksumError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, req: WebContext<'r, C, B>) -> Self::Output {
+        let mut res = req.into_response(format!("{self}"));
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        *res.status_mut() = StatusCode::BAD_REQUEST;
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::BoxStream,
+        bytes::Bytes,
+        error::BodyError,
+        handler::handler_service,
+        http::{header::HeaderValue, RequestExt},
+        App,
+    };
+
+    use super::*;
+
+    async fn handler(body: Vec<u8>) -> String {
+        String::from_utf8(body).unwrap()
+    }
+
+    fn req(headers: &[(HeaderName, &'static str)]) -> Request<RequestExt<BoxStream>> {
+        use futures_util::stream::{self, StreamExt};
+
+        let item = |chunk| async move { Ok::<_, BodyError>(Bytes::from_static(chunk)) };
+        let body = stream::once(item(b"hello,")).chain(stream::once(item(b"world!")));
+        let ext = RequestExt::default().map_body(|_: ()| BoxStream::new(body));
+        let mut req = Request::new(ext);
+        for (name, value) in headers {
+            req.headers_mut().insert(name, HeaderValue::from_static(value));
+        }
+        req
+    }
+
+    #[test]
+    fn checksum() {
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Checksum::new())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req(&[])).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service
+            .call(req(&[(CONTENT_MD5, "wOhOhwh03TftDRZMeYbwOg==")]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service
+            .call(req(&[
+                (
+                    DIGEST,
+                    "unknown=abc, SHA-256=7B4L2HUiaUOtDoh3vbpMpEnEy4WRpTY5Icnx7iAITDQ=",
+                ),
+                (CONTENT_DIGEST, "sha-256=:7B4L2HUiaUOtDoh3vbpMpEnEy4WRpTY5Icnx7iAITDQ=:"),
+            ]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service
+            .call(req(&[(CONTENT_MD5, "AAAAAAAAAAAAAAAAAAAAAA==")]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = service
+            .call(req(&[(CONTENT_DIGEST, "sha-256=nah")]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn required() {
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Checksum::new().set_required(true))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req(&[(DIGEST, "unknown=abc")])).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
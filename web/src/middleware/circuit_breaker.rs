@@ -0,0 +1,461 @@
+use core::{convert::Infallible, fmt, time::Duration};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use crate::{
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    error::Throttled,
+    http::WebResponse,
+};
+
+/// State of [CircuitBreaker].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// Requests are passed to inner service and their outcome is tracked.
+    Closed,
+    /// Requests are rejected without reaching inner service.
+    Open,
+    /// Limited count of probing requests are passed to inner service to decide if it recovered.
+    HalfOpen,
+}
+
+type Callback = Arc<dyn Fn(State, State) + Send + Sync>;
+
+/// A middleware tracking failures of inner service and short-circuiting requests when it goes
+/// unhealthy.
+///
+/// Outcome of request is a failure when inner service returns error, responds with `5xx` status
+/// code or takes longer than optional slow call threshold. When the ratio of failures in a
+/// rolling window reaches threshold (and the window has seen enough requests) the breaker opens
+/// and rejects requests with [Throttled] error(`503 Service Unavailable` with `Retry-After`
+/// header). After open duration the breaker becomes half-open and lets a limited count of probing
+/// requests through. It closes when all of them succeeded and opens again when any of them failed.
+///
+/// Breaker state is shared between all clones of the middleware and all worker threads it runs on.
+///
+/// # Examples:
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{handler::handler_service, middleware::circuit_breaker::CircuitBreaker, App, WebContext};
+/// App::new()
+///     .at("/upstream", handler_service(|_: &WebContext<'_>| async { "proxied" }))
+///     .enclosed(
+///         CircuitBreaker::new()
+///             .set_failure_ratio(0.5)
+///             .set_open_duration(Duration::from_secs(10))
+///             .on_state_change(|from, to| println!("circuit breaker: {from:?} -> {to:?}")),
+///     );
+/// ```
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    failure_ratio: f64,
+    min_requests: usize,
+    window: Duration,
+    slow_call: Option<Duration>,
+    open_duration: Duration,
+    half_open_probes: usize,
+    on_state_change: Option<Callback>,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("failure_ratio", &self.failure_ratio)
+            .field("min_requests", &self.min_requests)
+            .field("window", &self.window)
+            .field("slow_call", &self.slow_call)
+            .field("open_duration", &self.open_duration)
+            .field("half_open_probes", &self.half_open_probes)
+            .field("state", &self.state())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CircuitBreaker {
+    /// Construct a closed breaker opening at 50% failures out of at least 10 requests in a 10
+    /// seconds window. It stays open for 30 seconds and then probes inner service with one request.
+    pub fn new() -> Self {
+        Self {
+            failure_ratio: 0.5,
+            min_requests: 10,
+            window: Duration::from_secs(10),
+            slow_call: None,
+            open_duration: Duration::from_secs(30),
+            half_open_probes: 1,
+            on_state_change: None,
+            inner: Arc::new(Mutex::new(Inner::new(Instant::now()))),
+        }
+    }
+
+    /// Set the ratio of failed requests in window that opens the breaker.
+    ///
+    /// # Panics:
+    /// When ratio is not in the range of `(0, 1]`.
+    pub fn set_failure_ratio(mut self, ratio: f64) -> Self {
+        assert!(
+            ratio > 0.0 && ratio <= 1.0,
+            "failure ratio must be in the range of (0, 1]"
+        );
+        self.failure_ratio = ratio;
+        self
+    }
+
+    /// Set the min count of requests in window before failure ratio is evaluated.
+    pub fn set_min_requests(mut self, count: usize) -> Self {
+        self.min_requests = count;
+        self
+    }
+
+    /// Set the duration of window requests are tracked in. Counts are reset when window elapsed.
+    pub fn set_window(mut self, dur: Duration) -> Self {
+        self.window = dur;
+        self
+    }
+
+    /// Treat request taking longer than given duration as failure.
+    ///
+    /// Default to none where latency is not tracked.
+    pub fn set_slow_call(mut self, dur: Duration) -> Self {
+        self.slow_call = Some(dur);
+        self
+    }
+
+    /// Set the duration breaker stays open before probing inner service.
+    pub fn set_open_duration(mut self, dur: Duration) -> Self {
+        self.open_duration = dur;
+        self
+    }
+
+    /// Set the count of probing requests let through in half-open state.
+    ///
+    /// # Panics:
+    /// When count is 0.
+    pub fn set_half_open_probes(mut self, count: usize) -> Self {
+        assert_ne!(count, 0, "There must be at least one probing request");
+        self.half_open_probes = count;
+        self
+    }
+
+    /// Set callback called with previous and next state on every state transition.
+    ///
+    /// Callback is called outside of breaker lock on the thread where transition happens.
+    pub fn on_state_change<F>(mut self, func: F) -> Self
+    where
+        F: Fn(State, State) + Send + Sync + 'static,
+    {
+        self.on_state_change = Some(Arc::new(func));
+        self
+    }
+
+    /// Current state of breaker.
+    pub fn state(&self) -> State {
+        self.inner.lock().unwrap().state
+    }
+}
+
+impl<S> Service<S> for CircuitBreaker {
+    type Response = CircuitBreakerService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(CircuitBreakerService {
+            service,
+            breaker: self.clone(),
+        })
+    }
+}
+
+pub struct CircuitBreakerService<S> {
+    service: S,
+    breaker: CircuitBreaker,
+}
+
+pub type CircuitBreakerServiceError<E> = PipelineE<Throttled, E>;
+
+impl<'r, S, C, B, ResB, Err> Service<WebContext<'r, C, B>> for CircuitBreakerService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = Err>,
+{
+    type Response = WebResponse<ResB>;
+    type Error = CircuitBreakerServiceError<Err>;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let permit = self.breaker.acquire().map_err(CircuitBreakerServiceError::First)?;
+
+        let start = Instant::now();
+        let res = self.service.call(ctx).await;
+
+        let failed = match res {
+            Ok(ref res) => res.status().is_server_error(),
+            Err(_) => true,
+        } || self.breaker.slow_call.is_some_and(|dur| start.elapsed() > dur);
+
+        permit.finish(failed);
+
+        res.map_err(CircuitBreakerServiceError::Second)
+    }
+}
+
+impl<S> ReadyService for CircuitBreakerService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+struct Inner {
+    state: State,
+    // start of current window in closed state and start of open state.
+    since: Instant,
+    total: usize,
+    failures: usize,
+    probes: usize,
+    probe_successes: usize,
+}
+
+impl Inner {
+    fn new(now: Instant) -> Self {
+        Self {
+            state: State::Closed,
+            since: now,
+            total: 0,
+            failures: 0,
+            probes: 0,
+            probe_successes: 0,
+        }
+    }
+
+    fn transition(&mut self, state: State, now: Instant) -> Option<(State, State)> {
+        let from = self.state;
+        *self = Self::new(now);
+        self.state = state;
+        Some((from, state))
+    }
+}
+
+impl CircuitBreaker {
+    fn acquire(&self) -> Result<Permit<'_>, Throttled> {
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+
+        let mut transition = None;
+
+        if inner.state == State::Open {
+            let elapsed = now.duration_since(inner.since);
+            if elapsed < self.open_duration {
+                return Err(Throttled::service_unavailable("Circuit breaker is open.")
+                    .retry_after(self.open_duration - elapsed));
+            }
+            transition = inner.transition(State::HalfOpen, now);
+        }
+
+        let res = match inner.state {
+            State::Closed => {
+                if now.duration_since(inner.since) >= self.window {
+                    *inner = Inner::new(now);
+                }
+                Ok(Permit {
+                    breaker: self,
+                    probe: false,
+                    done: false,
+                })
+            }
+            _ if inner.probes < self.half_open_probes => {
+                inner.probes += 1;
+                Ok(Permit {
+                    breaker: self,
+                    probe: true,
+                    done: false,
+                })
+            }
+            _ => Err(Throttled::service_unavailable("Circuit breaker is half-open.")),
+        };
+
+        drop(inner);
+        self.notify(transition);
+
+        res
+    }
+
+    fn record(&self, probe: bool, failed: bool) {
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+
+        let transition = match (inner.state, probe) {
+            (State::HalfOpen, true) => {
+                inner.probes -= 1;
+                if failed {
+                    inner.transition(State::Open, now)
+                } else {
+                    inner.probe_successes += 1;
+                    if inner.probe_successes >= self.half_open_probes {
+                        inner.transition(State::Closed, now)
+                    } else {
+                        None
+                    }
+                }
+            }
+            (State::Closed, false) => {
+                inner.total += 1;
+                inner.failures += usize::from(failed);
+                if inner.total >= self.min_requests && inner.failures as f64 >= inner.total as f64 * self.failure_ratio
+                {
+                    inner.transition(State::Open, now)
+                } else {
+                    None
+                }
+            }
+            // outcome of request started in another state is outdated.
+            _ => None,
+        };
+
+        drop(inner);
+        self.notify(transition);
+    }
+
+    // release probing slot of request cancelled before it's outcome is known.
+    fn cancel(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == State::HalfOpen {
+            inner.probes -= 1;
+        }
+    }
+
+    fn notify(&self, transition: Option<(State, State)>) {
+        if let (Some(func), Some((from, to))) = (self.on_state_change.as_ref(), transition) {
+            func(from, to);
+        }
+    }
+}
+
+struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    done: bool,
+}
+
+impl Permit<'_> {
+    fn finish(mut self, failed: bool) {
+        self.done = true;
+        self.breaker.record(self.probe, failed);
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if !self.done && self.probe {
+            self.breaker.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::ResponseBody,
+        handler::handler_service,
+        http::{header::RETRY_AFTER, Request, RequestExt, StatusCode},
+        App,
+    };
+
+    use super::*;
+
+    async fn handler(ctx: &WebContext<'_>) -> WebResponse {
+        let mut res = WebResponse::new(ResponseBody::from("upstream"));
+        if ctx.req().uri().path() != "/ok" {
+            *res.status_mut() = StatusCode::BAD_GATEWAY;
+        }
+        res
+    }
+
+    #[test]
+    fn circuit_breaker() {
+        let transitions = Arc::new(Mutex::new(Vec::new()));
+
+        let breaker = CircuitBreaker::new()
+            .set_min_requests(2)
+            .set_open_duration(Duration::from_millis(50))
+            .on_state_change({
+                let transitions = transitions.clone();
+                move |from, to| transitions.lock().unwrap().push((from, to))
+            });
+
+        let service = App::new()
+            .at("/ok", handler_service(handler))
+            .at("/err", handler_service(handler))
+            .enclosed(breaker.clone())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |path: &str| {
+            let req = Request::builder().uri(path).body(RequestExt::default()).unwrap();
+            service.call(req).now_or_panic().unwrap()
+        };
+
+        assert_eq!(call("/ok").status(), StatusCode::OK);
+        assert_eq!(call("/err").status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(breaker.state(), State::Open);
+
+        let res = call("/ok");
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers().get(RETRY_AFTER).unwrap(), "1");
+
+        std::thread::sleep(Duration::from_millis(60));
+
+        // failed probe opens breaker again.
+        assert_eq!(call("/err").status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(breaker.state(), State::Open);
+
+        std::thread::sleep(Duration::from_millis(60));
+
+        assert_eq!(call("/ok").status(), StatusCode::OK);
+        assert_eq!(breaker.state(), State::Closed);
+
+        assert_eq!(
+            *transitions.lock().unwrap(),
+            [
+                (State::Closed, State::Open),
+                (State::Open, State::HalfOpen),
+                (State::HalfOpen, State::Open),
+                (State::Open, State::HalfOpen),
+                (State::HalfOpen, State::Closed),
+            ]
+        );
+    }
+
+    #[test]
+    fn cancelled_probe() {
+        let breaker = CircuitBreaker::new().set_min_requests(1);
+        breaker.record(false, true);
+        assert_eq!(breaker.state(), State::Open);
+
+        breaker.inner.lock().unwrap().since -= Duration::from_secs(60);
+
+        let permit = breaker.acquire().ok().unwrap();
+        assert_eq!(breaker.state(), State::HalfOpen);
+        assert!(breaker.acquire().is_err());
+
+        drop(permit);
+        assert!(breaker.acquire().is_ok());
+    }
+}
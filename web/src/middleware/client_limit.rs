@@ -0,0 +1,359 @@
+use core::{convert::Infallible, fmt, hash::Hash, time::Duration};
+
+use std::{
+    collections::HashMap,
+    error,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    error::Throttled,
+    handler::Responder,
+    http::{WebRequest, WebResponse},
+};
+
+/// A middleware limiting concurrent in-flight requests per client.
+///
+/// Requests are grouped by a client key (peer ip address by default). Each key can have at most
+/// `max_in_flight` requests handled concurrently and `max_queued` requests waiting in a first come
+/// first serve queue. Requests beyond that are rejected with [Throttled] error(`429 Too Many
+/// Requests`) so one client can not monopolize the concurrency of server.
+///
+/// Client state is shared between all clones of the middleware and all worker threads it runs on.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::client_limit::ClientLimit, App, WebContext};
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///     // at most 8 concurrent requests and 32 queued requests for every client ip.
+///     .enclosed(ClientLimit::new(8).set_max_queued(32));
+/// ```
+pub struct ClientLimit<F = fn(&WebRequest<()>) -> IpAddr, K = IpAddr> {
+    key: F,
+    max_in_flight: usize,
+    max_queued: usize,
+    retry_after: Option<Duration>,
+    clients: Arc<Mutex<HashMap<K, Client>>>,
+}
+
+impl<F, K> Clone for ClientLimit<F, K>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            max_in_flight: self.max_in_flight,
+            max_queued: self.max_queued,
+            retry_after: self.retry_after,
+            clients: self.clients.clone(),
+        }
+    }
+}
+
+impl ClientLimit {
+    /// Construct a middleware keyed by peer ip address with given max in-flight requests per client.
+    ///
+    /// # Panics:
+    /// When `max_in_flight` is 0.
+    pub fn new(max_in_flight: usize) -> Self {
+        assert_ne!(
+            max_in_flight, 0,
+            "There must be at least one in-flight request per client"
+        );
+        Self {
+            key: peer_ip,
+            max_in_flight,
+            max_queued: 0,
+            retry_after: None,
+            clients: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+fn peer_ip(req: &WebRequest<()>) -> IpAddr {
+    req.body().socket_addr().ip()
+}
+
+impl<F, K> ClientLimit<F, K> {
+    /// Set max count of requests waiting for in-flight slot per client.
+    ///
+    /// Default to 0 where requests beyond in-flight limit are rejected immediately.
+    pub fn set_max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = max_queued;
+        self
+    }
+
+    /// Set `Retry-After` delay advertised to rejected client.
+    ///
+    /// Default to none where no `Retry-After` header is sent.
+    pub fn set_retry_after(mut self, dur: Duration) -> Self {
+        self.retry_after = Some(dur);
+        self
+    }
+
+    /// Change how client key is extracted from request. e.g. from an api key header.
+    pub fn key<F1, K1>(self, key: F1) -> ClientLimit<F1, K1>
+    where
+        F1: Fn(&WebRequest<()>) -> K1,
+        K1: Hash + Eq,
+    {
+        ClientLimit {
+            key,
+            max_in_flight: self.max_in_flight,
+            max_queued: self.max_queued,
+            retry_after: self.retry_after,
+            clients: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<S, F, K> Service<S> for ClientLimit<F, K>
+where
+    F: Clone,
+{
+    type Response = ClientLimitService<S, F, K>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(ClientLimitService {
+            service,
+            limit: self.clone(),
+        })
+    }
+}
+
+pub struct ClientLimitService<S, F, K> {
+    service: S,
+    limit: ClientLimit<F, K>,
+}
+
+pub type ClientLimitServiceError<E> = PipelineE<Throttled, E>;
+
+impl<'r, S, C, B, F, K, Res, Err> Service<WebContext<'r, C, B>> for ClientLimitService<S, F, K>
+where
+    F: Fn(&WebRequest<()>) -> K,
+    K: Hash + Eq + Clone,
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = ClientLimitServiceError<Err>;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let key = (self.limit.key)(ctx.req());
+        let _guard = self.limit.acquire(key).await.map_err(|e| {
+            let mut e = Throttled::from(e);
+            if let Some(dur) = self.limit.retry_after {
+                e = e.retry_after(dur);
+            }
+            ClientLimitServiceError::First(e)
+        })?;
+        self.service.call(ctx).await.map_err(ClientLimitServiceError::Second)
+    }
+}
+
+impl<S, F, K> ReadyService for ClientLimitService<S, F, K>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+struct Client {
+    semaphore: Arc<Semaphore>,
+    // count of in-flight and queued requests.
+    count: usize,
+}
+
+impl<F, K> ClientLimit<F, K>
+where
+    K: Hash + Eq + Clone,
+{
+    async fn acquire(&self, key: K) -> Result<ClientPermit<'_, K>, ClientLimitError> {
+        let (slot, semaphore) = {
+            let mut clients = self.clients.lock().unwrap();
+            let client = clients.entry(key.clone()).or_insert_with(|| Client {
+                semaphore: Arc::new(Semaphore::new(self.max_in_flight)),
+                count: 0,
+            });
+
+            if client.count >= self.max_in_flight + self.max_queued {
+                return Err(ClientLimitError::TooManyRequests);
+            }
+
+            client.count += 1;
+
+            let slot = ClientSlot {
+                key,
+                clients: &self.clients,
+            };
+
+            (slot, client.semaphore.clone())
+        };
+
+        // semaphore is never closed.
+        let permit = semaphore.acquire_owned().await.unwrap();
+
+        Ok(ClientPermit {
+            _permit: permit,
+            _slot: slot,
+        })
+    }
+}
+
+// field order matters. permit must be released before slot.
+struct ClientPermit<'a, K>
+where
+    K: Hash + Eq,
+{
+    _permit: OwnedSemaphorePermit,
+    _slot: ClientSlot<'a, K>,
+}
+
+// slot removes client from map when it's the last in-flight/queued request of the client.
+struct ClientSlot<'a, K>
+where
+    K: Hash + Eq,
+{
+    key: K,
+    clients: &'a Mutex<HashMap<K, Client>>,
+}
+
+impl<K> Drop for ClientSlot<'_, K>
+where
+    K: Hash + Eq,
+{
+    fn drop(&mut self) {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get_mut(&self.key) {
+            client.count -= 1;
+            if client.count == 0 {
+                clients.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Error type of [ClientLimit] middleware.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ClientLimitError {
+    /// Client has too many in-flight and queued requests.
+    TooManyRequests,
+}
+
+impl fmt::Display for ClientLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::TooManyRequests => f.write_str("Too many concurrent requests from client."),
+        }
+    }
+}
+
+impl error::Error for ClientLimitError {}
+
+impl From<ClientLimitError> for Throttled {
+    fn from(e: ClientLimitError) -> Self {
+        match e {
+            ClientLimitError::TooManyRequests => {
+                Throttled::too_many_requests("Too many concurrent requests from client.")
+            }
+        }
+    }
+}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for ClientLimitError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        Throttled::from(self).respond_to(ctx).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{
+        future::{poll_fn, Future},
+        pin::pin,
+        task::Poll,
+    };
+
+    use std::net::SocketAddr;
+
+    use tokio::sync::Notify;
+
+    use crate::{
+        dev::service::fn_service,
+        http::{header::RETRY_AFTER, Request, RequestExt, StatusCode},
+        App,
+    };
+
+    use super::*;
+
+    async fn handler(ctx: WebContext<'_, &'static Notify>) -> Result<WebResponse, Infallible> {
+        if ctx.req().uri().path() == "/wait" {
+            ctx.state().notified().await;
+        }
+        Ok(ctx.into_response(""))
+    }
+
+    fn req(path: &str, addr: [u8; 4]) -> WebRequest {
+        let mut req = Request::builder().uri(path).body(RequestExt::default()).unwrap();
+        *req.body_mut().socket_addr_mut() = SocketAddr::from((addr, 8080));
+        req
+    }
+
+    #[tokio::test]
+    async fn client_limit() {
+        let notify = Box::leak(Box::new(Notify::new()));
+
+        let service = App::with_state(&*notify)
+            .at("/wait", fn_service(handler))
+            .at("/", fn_service(handler))
+            .enclosed(
+                ClientLimit::new(1)
+                    .set_max_queued(1)
+                    .set_retry_after(Duration::from_secs(1)),
+            )
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let mut in_flight = pin!(service.call(req("/wait", [127, 0, 0, 1])));
+        let mut queued = pin!(service.call(req("/", [127, 0, 0, 1])));
+
+        poll_fn(|cx| {
+            assert!(in_flight.as_mut().poll(cx).is_pending());
+            assert!(queued.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        let res = service.call(req("/", [127, 0, 0, 1])).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers().get(RETRY_AFTER).unwrap(), "1");
+
+        let res = service.call(req("/", [127, 0, 0, 2])).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        notify.notify_one();
+
+        assert_eq!(in_flight.await.unwrap().status(), StatusCode::OK);
+        assert_eq!(queued.await.unwrap().status(), StatusCode::OK);
+
+        let res = service.call(req("/", [127, 0, 0, 1])).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
@@ -1,6 +1,11 @@
 use core::convert::Infallible;
 
-use http_encoding::{encoder, Coder, ContentEncoding};
+use http_encoding::{Coder, ContentEncoding};
+
+pub use http_encoding::FlushPolicy;
+
+#[cfg(feature = "compress-zs")]
+pub use http_encoding::ZstdOptions;
 
 use crate::{
     body::{BodyStream, NONE_BODY_HINT},
@@ -11,20 +16,57 @@ use crate::{
 /// A compress middleware look into [WebRequest]'s `Accept-Encoding` header and
 /// apply according compression to [WebResponse]'s body according to enabled compress feature.
 /// `compress-x` feature must be enabled for this middleware to function correctly.
-#[derive(Clone)]
-pub struct Compress;
+///
+/// Streaming response body is flushed according to [FlushPolicy]. By default `text/event-stream`
+/// response is flushed on every chunk so compressed events are not held in encoder buffer.
+#[derive(Clone, Copy)]
+pub struct Compress {
+    flush: FlushPolicy,
+    #[cfg(feature = "compress-zs")]
+    zstd: ZstdOptions,
+}
+
+impl Default for Compress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compress {
+    pub const fn new() -> Self {
+        Self {
+            flush: FlushPolicy::Auto,
+            #[cfg(feature = "compress-zs")]
+            zstd: ZstdOptions::new(),
+        }
+    }
+
+    /// Set flush policy of compressed response body.
+    pub fn set_flush(mut self, flush: FlushPolicy) -> Self {
+        self.flush = flush;
+        self
+    }
+
+    /// Set quality and window options of zstd compressed response body.
+    #[cfg(feature = "compress-zs")]
+    pub fn set_zstd(mut self, zstd: ZstdOptions) -> Self {
+        self.zstd = zstd;
+        self
+    }
+}
 
 impl<S> Service<S> for Compress {
     type Response = CompressService<S>;
     type Error = Infallible;
 
     async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
-        Ok(CompressService { service })
+        Ok(CompressService { service, config: *self })
     }
 }
 
 pub struct CompressService<S> {
     service: S,
+    config: Compress,
 }
 
 impl<S, Req, ResB> Service<Req> for CompressService<S>
@@ -48,7 +90,20 @@ where
             _ => {}
         }
 
-        Ok(encoder(res, encoding))
+        #[cfg(feature = "compress-zs")]
+        {
+            Ok(http_encoding::encoder_with_zstd(
+                res,
+                encoding,
+                self.config.flush,
+                self.config.zstd,
+            ))
+        }
+
+        #[cfg(not(feature = "compress-zs"))]
+        {
+            Ok(http_encoding::encoder_with_flush(res, encoding, self.config.flush))
+        }
     }
 }
 
@@ -63,3 +118,60 @@ where
         self.service.ready().await
     }
 }
+
+#[cfg(all(test, feature = "compress-zs"))]
+mod test {
+    use http_encoding::try_decoder;
+    use xitca_http::body::Once;
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        bytes::Bytes,
+        handler::handler_service,
+        http::{
+            header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING},
+            Request, WebRequest,
+        },
+        test::collect_body,
+        App,
+    };
+
+    use super::*;
+
+    async fn handler() -> String {
+        "what is the goal of life\n".repeat(64)
+    }
+
+    #[test]
+    fn zstd() {
+        let mut req = <WebRequest as Default>::default();
+        req.headers_mut()
+            .insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip;q=0.5, zstd"));
+
+        let res = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Compress::new().set_zstd(ZstdOptions::new().quality(9).window_log(16)))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(req)
+            .now_or_panic()
+            .ok()
+            .unwrap();
+
+        let (parts, body) = res.into_parts();
+        assert_eq!(parts.headers.get(CONTENT_ENCODING).unwrap(), "zstd");
+
+        let body = collect_body(body).now_or_panic().unwrap();
+        let expected = handler().now_or_panic();
+        assert!(body.len() < expected.len());
+
+        let mut req = Request::new(());
+        req.headers_mut()
+            .insert(CONTENT_ENCODING, HeaderValue::from_static("zstd"));
+        let decoder = try_decoder(&req, Once::new(Bytes::from(body))).unwrap();
+        let body = collect_body(decoder).now_or_panic().unwrap();
+        assert_eq!(body, expected.as_bytes());
+    }
+}
@@ -0,0 +1,248 @@
+use core::{convert::Infallible, fmt};
+
+use std::{borrow::Cow, error, sync::Arc};
+
+use crate::{
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    handler::Responder,
+    http::{
+        const_header_value::TEXT_UTF8,
+        header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, TRANSFER_ENCODING},
+        Method, StatusCode, WebResponse,
+    },
+};
+
+const ACCEPT_POST: HeaderName = HeaderName::from_static("accept-post");
+const ACCEPT_PATCH: HeaderName = HeaderName::from_static("accept-patch");
+
+/// A middleware for rejecting request body with `Content-Type` not in allow-list.
+///
+/// Can be enclosed by App for all routes or by individual route service. Rejected request is
+/// responded with `415 Unsupported Media Type` and `Accept-Post` (or `Accept-Patch` for PATCH
+/// method) header listing allowed types. Request without body and `Content-Type` is passed through.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::handler_service,
+/// #   middleware::content_type::AllowContentType,
+/// #   route::post,
+/// #   App, WebContext
+/// # };
+/// # use xitca_web::dev::service::ServiceExt;
+/// App::new()
+///     .at(
+///         "/json",
+///         post(handler_service(|_: &WebContext<'_>| async { "json only" }))
+///             .enclosed(AllowContentType::new().allow("application/json")),
+///     )
+///     // media type wildcard is supported.
+///     .enclosed(AllowContentType::new().allow("application/*").allow("text/*"));
+/// ```
+#[derive(Clone)]
+pub struct AllowContentType {
+    types: Vec<Cow<'static, str>>,
+}
+
+impl Default for AllowContentType {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AllowContentType {
+    /// Construct a middleware with empty allow-list which reject all request with body.
+    pub const fn new() -> Self {
+        Self { types: Vec::new() }
+    }
+
+    /// Add media type to allow-list. Media type can be in `type/subtype` or `type/*` form.
+    /// Parameters like `charset` are ignored when matching.
+    pub fn allow(mut self, media_type: impl Into<Cow<'static, str>>) -> Self {
+        self.types.push(media_type.into());
+        self
+    }
+}
+
+impl<S> Service<S> for AllowContentType {
+    type Response = AllowContentTypeService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        let accept = HeaderValue::from_str(&self.types.join(", ")).expect("media type must be valid header value");
+        Ok(AllowContentTypeService {
+            service,
+            types: self.types.clone().into(),
+            accept,
+        })
+    }
+}
+
+pub struct AllowContentTypeService<S> {
+    service: S,
+    types: Arc<[Cow<'static, str>]>,
+    accept: HeaderValue,
+}
+
+pub type AllowContentTypeServiceError<E> = PipelineE<ContentTypeError, E>;
+
+impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for AllowContentTypeService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = AllowContentTypeServiceError<Err>;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        if !self.is_allowed(ctx.req().headers()) {
+            return Err(AllowContentTypeServiceError::First(ContentTypeError::Unsupported(
+                self.accept.clone(),
+            )));
+        }
+        self.service
+            .call(ctx)
+            .await
+            .map_err(AllowContentTypeServiceError::Second)
+    }
+}
+
+impl<S> AllowContentTypeService<S> {
+    fn is_allowed(&self, headers: &HeaderMap) -> bool {
+        let Some(value) = headers.get(CONTENT_TYPE) else {
+            return !has_body(headers);
+        };
+
+        let Some(media_type) = value.to_str().ok().and_then(|v| v.split(';').next()).map(str::trim) else {
+            return false;
+        };
+
+        self.types.iter().any(|ty| match ty.strip_suffix("/*") {
+            Some(prefix) => media_type
+                .split_once('/')
+                .map(|(t, _)| t.eq_ignore_ascii_case(prefix))
+                .unwrap_or(false),
+            None => media_type.eq_ignore_ascii_case(ty),
+        })
+    }
+}
+
+fn has_body(headers: &HeaderMap) -> bool {
+    headers.contains_key(TRANSFER_ENCODING)
+        || headers
+            .get(CONTENT_LENGTH)
+            .map(|len| len.as_bytes() != b"0")
+            .unwrap_or(false)
+}
+
+impl<S> ReadyService for AllowContentTypeService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// Error type of [AllowContentType] middleware.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ContentTypeError {
+    /// Content-Type of request is not allowed. Contains comma separated allowed media types.
+    Unsupported(HeaderValue),
+}
+
+impl fmt::Display for ContentTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Unsupported(_) => f.write_str("Content-Type of request body is not supported."),
+        }
+    }
+}
+
+impl error::Error for ContentTypeError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for ContentTypeError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, req: WebContext<'r, C, B>) -> Self::Output {
+        let name = if req.req().method() == Method::PATCH {
+            ACCEPT_PATCH
+        } else {
+            ACCEPT_POST
+        };
+
+        let msg = format!("{self}");
+        let Self::Unsupported(accept) = self;
+
+        let mut res = req.into_response(msg);
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        res.headers_mut().insert(name, accept);
+        *res.status_mut() = StatusCode::UNSUPPORTED_MEDIA_TYPE;
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        dev::service::ServiceExt,
+        handler::handler_service,
+        http::{Request, RequestExt, WebRequest},
+        route::post,
+        App,
+    };
+
+    use super::*;
+
+    fn req(content_type: Option<&'static str>, len: &'static str) -> WebRequest {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .body(RequestExt::default())
+            .unwrap();
+        if let Some(ty) = content_type {
+            req.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(ty));
+        }
+        req.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from_static(len));
+        req
+    }
+
+    #[test]
+    fn allow_list() {
+        let service = App::new()
+            .at(
+                "/",
+                post(handler_service(|_: &WebContext<'_>| async { "" }))
+                    .enclosed(AllowContentType::new().allow("application/json").allow("text/*")),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service
+            .call(req(Some("application/json; charset=utf-8"), "2"))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.call(req(Some("Text/Plain"), "2")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.call(req(None, "0")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.call(req(None, "2")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let res = service.call(req(Some("application/xml"), "2")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(res.headers().get(ACCEPT_POST).unwrap(), "application/json, text/*");
+    }
+}
@@ -1,9 +1,23 @@
-use core::{cell::RefCell, convert::Infallible};
+use core::{
+    cell::{Cell, RefCell},
+    convert::Infallible,
+    fmt,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use std::{error, rc::Rc};
 
-use http_encoding::{error::EncodingError, Coder};
+use futures_core::stream::Stream;
+use http_encoding::{
+    error::{CoderError, EncodingError, OutputLimitExceeded},
+    Code, FeaturedCode,
+};
+use pin_project_lite::pin_project;
 
 use crate::{
     body::BodyStream,
+    bytes::Bytes,
     context::WebContext,
     dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
     handler::Responder,
@@ -13,45 +27,107 @@ use crate::{
 /// A decompress middleware look into [WebContext]'s `Content-Encoding` header and
 /// apply according decompression to it according to enabled compress feature.
 /// `compress-x` feature must be enabled for this middleware to function correctly.
-#[derive(Clone)]
-pub struct Decompress;
+///
+/// Optional decompressed size ceiling and compression ratio check can be set to protect handlers
+/// from zip-bomb style payloads. When exceeded the request body stream would yield error and the
+/// middleware respond with `413 Payload Too Large`. Decompression is done in budget of the limits
+/// and it stops as soon as they are crossed, without expanding the rest of payload.
+#[derive(Clone, Copy)]
+pub struct Decompress {
+    max_size: usize,
+    max_ratio: usize,
+}
+
+/// [Decompress] middleware without limits. Kept for `.enclosed(Decompress)` style construction.
+#[allow(non_upper_case_globals)]
+pub const Decompress: Decompress = Decompress::new();
+
+impl Default for Decompress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decompress {
+    pub const fn new() -> Self {
+        Self {
+            max_size: usize::MAX,
+            max_ratio: usize::MAX,
+        }
+    }
+
+    /// Set max size in byte unit the decompressed request body can be.
+    pub fn set_max_size(mut self, size: usize) -> Self {
+        self.max_size = size;
+        self
+    }
+
+    /// Set max ratio between decompressed and compressed request body size.
+    ///
+    /// The ratio is only checked after decompressed size goes beyond 64KiB so small and highly
+    /// compressible payloads are not rejected.
+    pub fn set_max_ratio(mut self, ratio: usize) -> Self {
+        self.max_ratio = ratio;
+        self
+    }
+
+    fn is_limited(&self) -> bool {
+        self.max_size != usize::MAX || self.max_ratio != usize::MAX
+    }
+}
 
 impl<S> Service<S> for Decompress {
     type Response = DecompressService<S>;
     type Error = Infallible;
 
     async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
-        Ok(DecompressService { service })
+        Ok(DecompressService { service, config: *self })
     }
 }
 
 pub struct DecompressService<S> {
     service: S,
+    config: Decompress,
 }
 
-pub type DecompressServiceError<E> = PipelineE<EncodingError, E>;
+pub type DecompressServiceError<E> = PipelineE<DecompressError, E>;
 
 impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for DecompressService<S>
 where
     B: BodyStream + Default,
-    S: for<'rs> Service<WebContext<'rs, C, Coder<B>>, Response = Res, Error = Err>,
+    S: for<'rs> Service<WebContext<'rs, C, DecompressBody<B>>, Response = Res, Error = Err>,
 {
     type Response = Res;
     type Error = DecompressServiceError<Err>;
 
     async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
         let (parts, ext) = ctx.take_request().into_parts();
-        let ctx = ctx.ctx;
+        let WebContext { ctx, res_headers, .. } = ctx;
         let (ext, body) = ext.replace_body(());
         let req = Request::from_parts(parts, ());
 
-        let decoder = http_encoding::try_decoder(&req, body).map_err(DecompressServiceError::First)?;
-        let mut body = RefCell::new(decoder);
+        let counter = self.config.is_limited().then(|| Rc::new(Counter::new(self.config)));
+
+        let (body, coder) = http_encoding::try_decoder(&req, body)
+            .map_err(|e| DecompressServiceError::First(DecompressError::Encoding(e)))?
+            .into_parts();
+        let mut body = RefCell::new(DecompressBody {
+            body,
+            coder,
+            counter: counter.clone(),
+        });
         let mut req = req.map(|_| ext);
 
-        let ctx = WebContext::new(&mut req, &mut body, ctx);
+        let ctx = WebContext::new(&mut req, &mut body, ctx, res_headers);
+
+        let res = self.service.call(ctx).await;
+
+        // limit error take priority as handler error is likely a result of aborted body stream.
+        if let Some(e) = counter.and_then(|counter| counter.check()) {
+            return Err(DecompressServiceError::First(e));
+        }
 
-        self.service.call(ctx).await.map_err(DecompressServiceError::Second)
+        res.map_err(DecompressServiceError::Second)
     }
 }
 
@@ -67,6 +143,168 @@ where
     }
 }
 
+const RATIO_CHECK_THRESHOLD: usize = 64 * 1024;
+
+struct Counter {
+    config: Decompress,
+    compressed: Cell<usize>,
+    decompressed: Cell<usize>,
+}
+
+impl Counter {
+    fn new(config: Decompress) -> Self {
+        Self {
+            config,
+            compressed: Cell::new(0),
+            decompressed: Cell::new(0),
+        }
+    }
+
+    // max decompressed size allowed for compressed bytes counted so far.
+    fn allowed(&self) -> usize {
+        let ratio = self
+            .config
+            .max_ratio
+            .saturating_add(1)
+            .saturating_mul(self.compressed.get().max(1))
+            .saturating_sub(1)
+            .max(RATIO_CHECK_THRESHOLD);
+        self.config.max_size.min(ratio)
+    }
+
+    fn remaining(&self) -> usize {
+        self.allowed().saturating_sub(self.decompressed.get())
+    }
+
+    fn check(&self) -> Option<DecompressError> {
+        let decompressed = self.decompressed.get();
+
+        if decompressed > self.config.max_size {
+            return Some(DecompressError::BodyOverSize(self.config.max_size));
+        }
+
+        if decompressed > RATIO_CHECK_THRESHOLD && decompressed / self.compressed.get().max(1) > self.config.max_ratio {
+            return Some(DecompressError::RatioOverLimit(self.config.max_ratio));
+        }
+
+        None
+    }
+}
+
+pin_project! {
+    /// decompressed request body stream produced by [Decompress] middleware.
+    #[derive(Default)]
+    pub struct DecompressBody<B> {
+        #[pin]
+        body: B,
+        coder: FeaturedCode,
+        counter: Option<Rc<Counter>>,
+    }
+}
+
+impl<B> Stream for DecompressBody<B>
+where
+    B: BodyStream,
+{
+    type Item = Result<Bytes, DecompressBodyError<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(e) = this.counter.as_ref().and_then(|counter| counter.check()) {
+                return Poll::Ready(Some(Err(DecompressBodyError::First(e))));
+            }
+
+            let (res, eof) = match ready!(this.body.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => {
+                    if let Some(counter) = this.counter.as_ref() {
+                        counter.compressed.set(counter.compressed.get() + chunk.as_ref().len());
+                        Code::<B::Chunk>::set_output_limit(this.coder, counter.remaining());
+                    }
+                    (this.coder.code(chunk), false)
+                }
+                Some(Err(e)) => return Poll::Ready(Some(Err(DecompressBodyError::Second(CoderError::Stream(e))))),
+                None => {
+                    if let Some(counter) = this.counter.as_ref() {
+                        Code::<B::Chunk>::set_output_limit(this.coder, counter.remaining());
+                    }
+                    (Code::<B::Chunk>::code_eof(this.coder), true)
+                }
+            };
+
+            match res {
+                Ok(Some(chunk)) => {
+                    if let Some(counter) = this.counter.as_ref() {
+                        counter.decompressed.set(counter.decompressed.get() + chunk.len());
+                        if let Some(e) = counter.check() {
+                            return Poll::Ready(Some(Err(DecompressBodyError::First(e))));
+                        }
+                    }
+                    return Poll::Ready(Some(Ok(chunk)));
+                }
+                Ok(None) if eof => return Poll::Ready(None),
+                Ok(None) => {}
+                // coder stopped right at the limit. mark the counter as exceeded and let check
+                // produce the according error.
+                Err(e) if e.get_ref().is_some_and(|e| e.is::<OutputLimitExceeded>()) => {
+                    if let Some(counter) = this.counter.as_ref() {
+                        counter.decompressed.set(counter.allowed().saturating_add(1));
+                    }
+                }
+                Err(e) => return Poll::Ready(Some(Err(DecompressBodyError::Second(CoderError::Io(e))))),
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        Code::<B::Chunk>::size_hint(&self.coder, &self.body)
+    }
+}
+
+pub type DecompressBodyError<E> = PipelineE<DecompressError, CoderError<E>>;
+
+/// Error type of [Decompress] middleware.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecompressError {
+    /// Content-Encoding of request is not supported.
+    Encoding(EncodingError),
+    /// Decompressed body size reached limit.
+    BodyOverSize(usize),
+    /// Ratio between decompressed and compressed body size reached limit.
+    RatioOverLimit(usize),
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Encoding(ref e) => fmt::Display::fmt(e, f),
+            Self::BodyOverSize(size) => write!(f, "Decompressed body size reached limit: {size} bytes."),
+            Self::RatioOverLimit(ratio) => write!(f, "Decompression ratio reached limit: {ratio}."),
+        }
+    }
+}
+
+impl error::Error for DecompressError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for DecompressError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, req: WebContext<'r, C, B>) -> Self::Output {
+        match self {
+            Self::Encoding(e) => e.respond_to(req).await,
+            e => {
+                let mut res = req.into_response(format!("{e}"));
+                res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+                *res.status_mut() = StatusCode::PAYLOAD_TOO_LARGE;
+                res
+            }
+        }
+    }
+}
+
 impl<'r, C, B> Responder<WebContext<'r, C, B>> for EncodingError {
     type Output = WebResponse;
 
@@ -80,11 +318,16 @@ impl<'r, C, B> Responder<WebContext<'r, C, B>> for EncodingError {
 
 #[cfg(test)]
 mod test {
+    use core::future::poll_fn;
+
     use http_encoding::{encoder, ContentEncoding};
     use xitca_http::body::Once;
     use xitca_unsafe_collection::futures::NowOrPanic;
 
-    use crate::{bytes::Bytes, http::header::CONTENT_ENCODING};
+    use crate::{
+        bytes::Bytes,
+        http::header::{HeaderValue, CONTENT_ENCODING},
+    };
 
     use crate::{
         body::ResponseBody,
@@ -141,30 +384,29 @@ mod test {
             .unwrap();
     }
 
-    #[cfg(any(feature = "compress-br", feature = "compress-gz", feature = "compress-de"))]
-    #[test]
-    fn compressed() {
-        // a hack to generate a compressed client request from server response.
-        let res = WebResponse::<ResponseBody>::new(ResponseBody::bytes(Bytes::from_static(Q)));
+    // a hack to generate a compressed client request from server response.
+    fn compressed_req(body: Bytes) -> WebRequest<Once<Bytes>> {
+        let res = WebResponse::<ResponseBody>::new(ResponseBody::bytes(body));
 
+        // pick the first enabled encoding.
         #[allow(unreachable_code)]
         let encoding = || {
-            #[cfg(all(feature = "compress-br", not(any(feature = "compress-gz", feature = "compress-de"))))]
+            #[cfg(feature = "compress-br")]
             {
                 return ContentEncoding::Br;
             }
 
-            #[cfg(all(feature = "compress-gz", not(any(feature = "compress-br", feature = "compress-de"))))]
+            #[cfg(feature = "compress-gz")]
             {
                 return ContentEncoding::Gzip;
             }
 
-            #[cfg(all(feature = "compress-de", not(any(feature = "compress-br", feature = "compress-gz"))))]
+            #[cfg(feature = "compress-de")]
             {
                 return ContentEncoding::Deflate;
             }
 
-            ContentEncoding::Br
+            ContentEncoding::Zstd
         };
 
         let encoding = encoding();
@@ -178,6 +420,11 @@ mod test {
         req.headers_mut()
             .insert(CONTENT_ENCODING, parts.headers.remove(CONTENT_ENCODING).unwrap());
 
+        req
+    }
+
+    #[test]
+    fn compressed() {
         App::new()
             .at("/", handler_service(handler))
             .enclosed(Decompress)
@@ -185,9 +432,132 @@ mod test {
             .call(())
             .now_or_panic()
             .unwrap()
-            .call(req)
+            .call(compressed_req(Bytes::from_static(Q)))
             .now_or_panic()
             .ok()
             .unwrap();
     }
+
+    #[test]
+    fn limit() {
+        async fn drain(_: Vec<u8>) -> &'static str {
+            A
+        }
+
+        let bomb = || compressed_req(Bytes::from(vec![0; 1024 * 1024]));
+
+        let service = |decompress: Decompress| {
+            App::new()
+                .at("/", handler_service(drain))
+                .enclosed(decompress)
+                .finish()
+                .call(())
+                .now_or_panic()
+                .unwrap()
+        };
+
+        let res = service(Decompress::new().set_max_size(1024))
+            .call(bomb())
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let res = service(Decompress::new().set_max_ratio(10))
+            .call(bomb())
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let res = service(Decompress::new().set_max_size(Q.len()).set_max_ratio(10))
+            .call(compressed_req(Bytes::from_static(Q)))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn limit_bounded() {
+        // zeros compress into a single small chunk that would expand to 8MiB in one go.
+        let (parts, ext) = compressed_req(Bytes::from(vec![0; 8 * 1024 * 1024])).into_parts();
+        let (_, body) = ext.replace_body(());
+        let (body, coder) = http_encoding::try_decoder(Request::from_parts(parts, ()), body)
+            .ok()
+            .unwrap()
+            .into_parts();
+
+        let mut body = DecompressBody {
+            body,
+            coder,
+            counter: Some(Rc::new(Counter::new(Decompress::new().set_max_size(1024)))),
+        };
+
+        let mut decompressed = 0;
+        loop {
+            match poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).now_or_panic() {
+                Some(Ok(chunk)) => decompressed += chunk.len(),
+                Some(Err(e)) => {
+                    assert!(matches!(
+                        e,
+                        DecompressBodyError::First(DecompressError::BodyOverSize(1024))
+                    ));
+                    break;
+                }
+                None => panic!("decompressed body must reach limit"),
+            }
+        }
+        assert!(decompressed <= 1024);
+    }
+
+    #[cfg(feature = "compress-zs")]
+    #[test]
+    fn zstd() {
+        // Q compressed by zstd cli.
+        const FRAME: &[u8] = &[
+            0x28, 0xb5, 0x2f, 0xfd, 0x24, 0x18, 0xc1, 0x00, 0x00, 0x77, 0x68, 0x61, 0x74, 0x20, 0x69, 0x73, 0x20, 0x74,
+            0x68, 0x65, 0x20, 0x67, 0x6f, 0x61, 0x6c, 0x20, 0x6f, 0x66, 0x20, 0x6c, 0x69, 0x66, 0x65, 0x4f, 0x19, 0x72,
+            0x0f,
+        ];
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Decompress)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let req = |body: &'static [u8]| {
+            let mut req = <WebRequest as Default>::default().map(|ext| ext.map_body(|_| Once::new(Bytes::from(body))));
+            req.headers_mut()
+                .insert(CONTENT_ENCODING, HeaderValue::from_static("zstd"));
+            req
+        };
+
+        let res = service.call(req(FRAME)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // invalid frame fails body stream.
+        let res = service.call(req(b"what is the goal of life")).now_or_panic().unwrap();
+        assert_ne!(res.status(), StatusCode::OK);
+    }
+
+    #[cfg(not(feature = "compress-zs"))]
+    #[test]
+    fn zstd_unsupported() {
+        let mut req = <WebRequest as Default>::default().map(|ext| ext.map_body(|_| Once::new(Bytes::from_static(Q))));
+        req.headers_mut()
+            .insert(CONTENT_ENCODING, HeaderValue::from_static("zstd"));
+
+        let res = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Decompress)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(req)
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
 }
@@ -0,0 +1,212 @@
+//! unread request body draining middleware.
+
+use core::{convert::Infallible, future::poll_fn, pin::pin, time::Duration};
+
+use crate::{
+    body::BodyStream,
+    context::WebContext,
+    dev::service::{ready::ReadyService, Service},
+    http::{
+        header::{HeaderValue, CONNECTION, CONTENT_LENGTH},
+        Version, WebResponse,
+    },
+};
+
+/// Default max size of drained request body in bytes.
+pub const DEFAULT_LIMIT: usize = 64 * 1024;
+
+/// Default max duration of draining request body.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A middleware draining request body left unread by service after it produced a response.
+///
+/// Without it request body not fully consumed by service makes http/1 connection closed after
+/// response as the leftover bytes can not be told apart from the next request. With it the
+/// remaining body is read and discarded before response is sent so connection can be kept alive.
+/// For http/2 it consumes the body so the stream is ended gracefully instead of being reset.
+///
+/// Draining is bounded by size and duration. When request body is known to be larger than limit
+/// from `Content-Length` header, or draining goes beyond limit, timeout or fails, it's given up
+/// and http/1 response carries `Connection: close` header to close the connection explicitly.
+///
+/// # Examples:
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{handler::handler_service, middleware::drain::Drain, App, WebContext};
+/// // handler ignores request body.
+/// async fn handler(_: &WebContext<'_>) -> &'static str {
+///     "ok"
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     .enclosed(Drain::new(16 * 1024).set_timeout(Duration::from_millis(500)));
+/// ```
+#[derive(Clone, Copy)]
+pub struct Drain {
+    limit: usize,
+    timeout: Duration,
+}
+
+impl Default for Drain {
+    fn default() -> Self {
+        Self::new(DEFAULT_LIMIT)
+    }
+}
+
+impl Drain {
+    /// Construct a middleware draining unread request body up to given limit in bytes.
+    pub const fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Set max duration of draining request body.
+    ///
+    /// Default to [DEFAULT_TIMEOUT].
+    pub const fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl<S> Service<S> for Drain {
+    type Response = DrainService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(DrainService { service, drain: *self })
+    }
+}
+
+pub struct DrainService<S> {
+    service: S,
+    drain: Drain,
+}
+
+impl<'r, S, C, B, ResB, Err> Service<WebContext<'r, C, B>> for DrainService<S>
+where
+    B: BodyStream + Default,
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = Err>,
+{
+    type Response = WebResponse<ResB>;
+    type Error = Err;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        // request head is read before calling service as it may be taken by service.
+        let too_large = ctx
+            .req()
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok())
+            .is_some_and(|len| len > self.drain.limit);
+        let http1 = ctx.req().version() < Version::HTTP_2;
+
+        let mut res = self.service.call(ctx.reborrow()).await?;
+
+        let drained = !too_large
+            && tokio::time::timeout(self.drain.timeout, drain(ctx.take_body_mut(), self.drain.limit))
+                .await
+                .unwrap_or(false);
+
+        if !drained && http1 {
+            res.headers_mut().insert(CONNECTION, HeaderValue::from_static("close"));
+        }
+
+        Ok(res)
+    }
+}
+
+impl<S> ReadyService for DrainService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+// read and discard body until it ends. return false when body is larger than limit or errors.
+async fn drain<B>(body: B, limit: usize) -> bool
+where
+    B: BodyStream,
+{
+    let mut body = pin!(body);
+    let mut len = 0;
+
+    while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+        match chunk {
+            Ok(chunk) => {
+                len += chunk.as_ref().len();
+                if len > limit {
+                    return false;
+                }
+            }
+            Err(_) => return false,
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use futures_util::stream::{self, StreamExt};
+
+    use crate::{
+        body::BoxStream,
+        bytes::Bytes,
+        error::BodyError,
+        handler::handler_service,
+        http::{Request, RequestExt},
+        App,
+    };
+
+    use super::*;
+
+    // request with body counting the bytes read from it.
+    fn req(chunks: &[&'static str], read: &Rc<Cell<usize>>) -> Request<RequestExt<BoxStream>> {
+        let read = read.clone();
+        let body = stream::iter(chunks.to_vec()).map(move |chunk| {
+            read.set(read.get() + chunk.len());
+            Ok::<_, BodyError>(Bytes::from_static(chunk.as_bytes()))
+        });
+        Request::new(RequestExt::default().map_body(|_: ()| BoxStream::new(body)))
+    }
+
+    #[tokio::test]
+    async fn drain_unread_body() {
+        let service = App::new()
+            .at("/", handler_service(|| async { "ok" }))
+            .enclosed(Drain::new(8))
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let read = Rc::default();
+        let res = service.call(req(&["1234", "5678"], &read)).await.ok().unwrap();
+        assert_eq!(read.get(), 8);
+        assert!(res.headers().get(CONNECTION).is_none());
+
+        let read = Rc::default();
+        let res = service.call(req(&["1234", "56789"], &read)).await.ok().unwrap();
+        assert_eq!(read.get(), 9);
+        assert_eq!(res.headers().get(CONNECTION).unwrap(), "close");
+
+        let read = Rc::default();
+        let mut req = req(&["1234", "56789"], &read);
+        req.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(9));
+        let res = service.call(req).await.ok().unwrap();
+        assert_eq!(read.get(), 0);
+        assert_eq!(res.headers().get(CONNECTION).unwrap(), "close");
+    }
+}
@@ -0,0 +1,441 @@
+//! sampled request/response dump for debugging.
+
+use core::{
+    cell::RefCell,
+    convert::Infallible,
+    fmt,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{ready, Context, Poll},
+};
+
+use std::sync::{Arc, Mutex};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use xitca_http::Request;
+
+use crate::{
+    body::BodyStream,
+    context::WebContext,
+    dev::service::{ready::ReadyService, Service},
+    http::{header::HeaderMap, header::HeaderName, Method, StatusCode, Uri, Version, WebResponse},
+};
+
+type Sink = Arc<dyn Fn(Dump) + Send + Sync>;
+
+/// A middleware capturing request and response of sampled requests for production debugging.
+///
+/// A request is dumped when it's one of every n requests set by [RequestDump::set_sample_interval]
+/// or when it carries the header set by [RequestDump::set_trigger_header]. Headers are captured
+/// in full and bodies are captured up to [RequestDump::set_body_limit] bytes. Request body is only
+/// captured for the part consumed by handler.
+///
+/// Captured [Dump] is passed to sink when response body is finished or dropped. By default it's
+/// logged with `tracing::info`.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   body::RequestBody,
+/// #   handler::handler_service,
+/// #   http::header::HeaderName,
+/// #   middleware::dump::{DumpBody, RequestDump},
+/// #   route::get,
+/// #   App, WebContext
+/// # };
+/// // request body type is wrapped by middleware.
+/// async fn index(_: &WebContext<'_, (), DumpBody<RequestBody>>) -> &'static str {
+///     "hello"
+/// }
+///
+/// let (tx, rx) = std::sync::mpsc::channel();
+///
+/// App::new()
+///     .at("/", get(handler_service(index)))
+///     .enclosed(
+///         RequestDump::new()
+///             // dump one of every 1000 requests.
+///             .set_sample_interval(1000)
+///             // dump request with x-debug-dump header.
+///             .set_trigger_header(HeaderName::from_static("x-debug-dump"))
+///             // send dump to a channel instead of logging.
+///             .set_sink(move |dump| {
+///                 let _ = tx.send(dump);
+///             }),
+///     );
+/// # drop(rx);
+/// ```
+#[derive(Clone)]
+pub struct RequestDump {
+    interval: usize,
+    header: Option<HeaderName>,
+    body_limit: usize,
+    sink: Sink,
+}
+
+impl Default for RequestDump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestDump {
+    /// Construct a middleware that dumps nothing until sampling is configured.
+    pub fn new() -> Self {
+        Self {
+            interval: 0,
+            header: None,
+            body_limit: 1024,
+            sink: Arc::new(|dump| tracing::info!("{dump:?}")),
+        }
+    }
+
+    /// Dump one of every n requests. Default to 0 where no request is sampled.
+    pub fn set_sample_interval(mut self, n: usize) -> Self {
+        self.interval = n;
+        self
+    }
+
+    /// Dump every request carrying given header regardless of sample interval.
+    pub fn set_trigger_header(mut self, name: HeaderName) -> Self {
+        self.header = Some(name);
+        self
+    }
+
+    /// Set max size in byte unit captured for request and response body. Default to 1024.
+    pub fn set_body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = limit;
+        self
+    }
+
+    /// Set the sink receiving captured dumps. It's called on the thread handling the request and
+    /// should not block.
+    pub fn set_sink<F>(mut self, sink: F) -> Self
+    where
+        F: Fn(Dump) + Send + Sync + 'static,
+    {
+        self.sink = Arc::new(sink);
+        self
+    }
+}
+
+impl<S> Service<S> for RequestDump {
+    type Response = RequestDumpService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(RequestDumpService {
+            service,
+            dump: self.clone(),
+            count: AtomicUsize::new(0),
+        })
+    }
+}
+
+pub struct RequestDumpService<S> {
+    service: S,
+    dump: RequestDump,
+    count: AtomicUsize,
+}
+
+impl<S> RequestDumpService<S> {
+    fn sampled(&self, headers: &HeaderMap) -> bool {
+        if let Some(ref name) = self.dump.header {
+            if headers.contains_key(name) {
+                return true;
+            }
+        }
+        self.dump.interval != 0
+            && self
+                .count
+                .fetch_add(1, Ordering::Relaxed)
+                .is_multiple_of(self.dump.interval)
+    }
+}
+
+impl<'r, S, C, B, ResB, Err> Service<WebContext<'r, C, B>> for RequestDumpService<S>
+where
+    B: BodyStream + Default,
+    S: for<'r2> Service<WebContext<'r2, C, DumpBody<B>>, Response = WebResponse<ResB>, Error = Err>,
+    ResB: BodyStream,
+{
+    type Response = WebResponse<DumpBody<ResB>>;
+    type Error = Err;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let (parts, ext) = ctx.take_request().into_parts();
+        let WebContext { ctx, res_headers, .. } = ctx;
+
+        let record = self.sampled(&parts.headers).then(|| {
+            Arc::new(Record {
+                dump: Mutex::new(Some(Dump {
+                    method: parts.method.clone(),
+                    uri: parts.uri.clone(),
+                    version: parts.version,
+                    request_headers: parts.headers.clone(),
+                    request_body: Vec::new(),
+                    request_body_size: 0,
+                    status: None,
+                    response_headers: HeaderMap::new(),
+                    response_body: Vec::new(),
+                    response_body_size: 0,
+                })),
+                limit: self.dump.body_limit,
+                sink: self.dump.sink.clone(),
+            })
+        });
+
+        let (ext, body) = ext.replace_body(());
+        let mut body = RefCell::new(DumpBody::new(body, record.clone(), Side::Request));
+        let mut req = Request::from_parts(parts, ext);
+
+        let res = self
+            .service
+            .call(WebContext::new(&mut req, &mut body, ctx, res_headers))
+            .await;
+
+        // drop request body before response so it can't record after dump is emitted.
+        drop(body);
+
+        match res {
+            Ok(res) => {
+                if let Some(ref record) = record {
+                    if let Some(dump) = record.dump.lock().unwrap().as_mut() {
+                        dump.status = Some(res.status());
+                        dump.response_headers = res.headers().clone();
+                    }
+                }
+                Ok(res.map(|body| DumpBody::new(body, record, Side::Response)))
+            }
+            Err(e) => {
+                if let Some(record) = record {
+                    record.emit();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<S> ReadyService for RequestDumpService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// Captured request and response. Bodies are truncated when their size is larger than captured
+/// bytes.
+#[non_exhaustive]
+pub struct Dump {
+    pub method: Method,
+    pub uri: Uri,
+    pub version: Version,
+    pub request_headers: HeaderMap,
+    pub request_body: Vec<u8>,
+    /// Size of request body consumed by handler.
+    pub request_body_size: usize,
+    /// `None` when service returns error instead of response.
+    pub status: Option<StatusCode>,
+    pub response_headers: HeaderMap,
+    pub response_body: Vec<u8>,
+    /// Size of response body yielded before it's finished or dropped.
+    pub response_body_size: usize,
+}
+
+impl fmt::Debug for Dump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dump")
+            .field("method", &self.method)
+            .field("uri", &self.uri)
+            .field("version", &self.version)
+            .field("request_headers", &self.request_headers)
+            .field("request_body", &String::from_utf8_lossy(&self.request_body))
+            .field("request_body_size", &self.request_body_size)
+            .field("status", &self.status)
+            .field("response_headers", &self.response_headers)
+            .field("response_body", &String::from_utf8_lossy(&self.response_body))
+            .field("response_body_size", &self.response_body_size)
+            .finish()
+    }
+}
+
+struct Record {
+    dump: Mutex<Option<Dump>>,
+    limit: usize,
+    sink: Sink,
+}
+
+impl Record {
+    fn capture(&self, side: Side, chunk: &[u8]) {
+        if let Some(dump) = self.dump.lock().unwrap().as_mut() {
+            let (buf, size) = match side {
+                Side::Request => (&mut dump.request_body, &mut dump.request_body_size),
+                Side::Response => (&mut dump.response_body, &mut dump.response_body_size),
+            };
+            *size += chunk.len();
+            let remain = self.limit.saturating_sub(buf.len());
+            buf.extend_from_slice(&chunk[..remain.min(chunk.len())]);
+        }
+    }
+
+    fn emit(&self) {
+        let dump = self.dump.lock().unwrap().take();
+        if let Some(dump) = dump {
+            (self.sink)(dump);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Request,
+    Response,
+}
+
+pin_project! {
+    /// Body type capturing bytes of sampled request and response.
+    pub struct DumpBody<B> {
+        #[pin]
+        body: B,
+        record: Option<Arc<Record>>,
+        side: Side,
+    }
+
+    impl<B> PinnedDrop for DumpBody<B> {
+        fn drop(this: Pin<&mut Self>) {
+            let this = this.project();
+            if let (Side::Response, Some(record)) = (*this.side, this.record.take()) {
+                record.emit();
+            }
+        }
+    }
+}
+
+impl<B: Default> Default for DumpBody<B> {
+    fn default() -> Self {
+        Self::new(B::default(), None, Side::Request)
+    }
+}
+
+impl<B> DumpBody<B> {
+    fn new(body: B, record: Option<Arc<Record>>, side: Side) -> Self {
+        Self { body, record, side }
+    }
+}
+
+impl<B> Stream for DumpBody<B>
+where
+    B: BodyStream,
+{
+    type Item = Result<B::Chunk, B::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let res = ready!(this.body.poll_next(cx));
+
+        if let Some(ref record) = *this.record {
+            match res {
+                Some(Ok(ref chunk)) => record.capture(*this.side, chunk.as_ref()),
+                None if matches!(this.side, Side::Response) => {
+                    record.emit();
+                    *this.record = None;
+                }
+                _ => {}
+            }
+        }
+
+        Poll::Ready(res)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.body.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        handler::handler_service,
+        http::{header::HeaderValue, WebRequest},
+        route::get,
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    async fn hello(_: &WebContext<'_, (), DumpBody<RequestBody>>) -> &'static str {
+        "hello,world"
+    }
+
+    #[test]
+    fn dump() {
+        let (tx, rx) = mpsc::channel();
+
+        let service = App::new()
+            .at("/", get(handler_service(hello)))
+            .enclosed(
+                RequestDump::new()
+                    .set_trigger_header(HeaderName::from_static("x-dump"))
+                    .set_body_limit(5)
+                    .set_sink(move |dump| tx.send(dump).unwrap()),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let body = service.call(WebRequest::default()).now_or_panic().unwrap().into_body();
+        collect_string_body(body).now_or_panic().unwrap();
+        assert!(rx.try_recv().is_err());
+
+        let mut req = WebRequest::default();
+        req.headers_mut()
+            .insert(HeaderName::from_static("x-dump"), HeaderValue::from_static("1"));
+        let body = service.call(req).now_or_panic().unwrap().into_body();
+        assert_eq!(collect_string_body(body).now_or_panic().unwrap(), "hello,world");
+
+        let dump = rx.try_recv().unwrap();
+        assert_eq!(dump.method, Method::GET);
+        assert!(dump.request_headers.contains_key("x-dump"));
+        assert_eq!(dump.status, Some(StatusCode::OK));
+        assert_eq!(dump.response_body, b"hello");
+        assert_eq!(dump.response_body_size, 11);
+    }
+
+    #[test]
+    fn sample_interval() {
+        let (tx, rx) = mpsc::channel();
+
+        let service = App::new()
+            .at("/", get(handler_service(hello)))
+            .enclosed(
+                RequestDump::new()
+                    .set_sample_interval(2)
+                    .set_sink(move |dump| tx.send(dump).unwrap()),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for _ in 0..4 {
+            drop(service.call(WebRequest::default()).now_or_panic().unwrap());
+        }
+
+        assert_eq!(rx.try_iter().count(), 2);
+    }
+}
@@ -0,0 +1,307 @@
+//! custom error page middleware.
+
+use core::{convert::Infallible, future::Future, pin::Pin};
+
+use std::sync::Arc;
+
+use crate::{
+    context::WebContext,
+    dev::service::{ready::ReadyService, Service},
+    handler::Responder,
+    http::{HeaderMap, Method, StatusCode, Uri, WebResponse},
+};
+
+type BoxFuture = Pin<Box<dyn Future<Output = WebResponse>>>;
+
+type StatusFn = Arc<dyn Fn(ErrorInfo) -> BoxFuture + Send + Sync>;
+
+/// Information of request and the status code of it's response passed to error handlers of
+/// [ErrorHandler] middleware.
+#[derive(Clone, Debug)]
+pub struct ErrorInfo {
+    /// Status code of response produced by service.
+    pub status: StatusCode,
+    /// Method of request.
+    pub method: Method,
+    /// Uri of request.
+    pub uri: Uri,
+    /// Headers of request. Useful for content negotiation of error page.
+    pub headers: HeaderMap,
+}
+
+/// Handler of typed error produced by service. See [ErrorHandler::error] for detail.
+pub trait HandleError<Err> {
+    /// Handle error and produce response. Return error as is when it's not handled.
+    fn handle(&self, err: Err, info: ErrorInfo) -> impl Future<Output = Result<WebResponse, Err>>;
+}
+
+impl<Err> HandleError<Err> for () {
+    #[inline]
+    async fn handle(&self, err: Err, _: ErrorInfo) -> Result<WebResponse, Err> {
+        Err(err)
+    }
+}
+
+impl<F, Fut, Err> HandleError<Err> for F
+where
+    F: Fn(Err, ErrorInfo) -> Fut,
+    Fut: Future<Output = WebResponse>,
+{
+    #[inline]
+    async fn handle(&self, err: Err, info: ErrorInfo) -> Result<WebResponse, Err> {
+        Ok(self(err, info).await)
+    }
+}
+
+/// A middleware rewriting error responses with user provided async closures. Useful for branded
+/// error pages without touching every handler.
+///
+/// Responses are intercepted by status code with [ErrorHandler::status]. Errors of service are
+/// rendered to responses with their [Responder] implementation before interception so a `404 Not
+/// Found` from unmatched route is intercepted as well. Typed errors can be handled directly with
+/// [ErrorHandler::error] before they are rendered.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::handler_service,
+/// #   http::{StatusCode, WebResponse},
+/// #   middleware::error_handler::{ErrorHandler, ErrorInfo},
+/// #   App, WebContext,
+/// # };
+/// async fn not_found(info: ErrorInfo) -> WebResponse {
+///     let mut res = WebResponse::new(format!("<h1>{} is gone</h1>", info.uri.path()).into());
+///     *res.status_mut() = info.status;
+///     res
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello" }))
+///     .enclosed(ErrorHandler::new().status(StatusCode::NOT_FOUND, not_found));
+/// ```
+pub struct ErrorHandler<F = ()> {
+    status: Vec<(StatusCode, StatusFn)>,
+    error: Arc<F>,
+}
+
+impl Default for ErrorHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorHandler {
+    /// Construct a middleware intercepting nothing.
+    pub fn new() -> Self {
+        Self {
+            status: Vec::new(),
+            error: Arc::new(()),
+        }
+    }
+
+    /// Handle typed error of service with given async closure producing a new response. Response
+    /// produced by it is not intercepted by status handlers.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use xitca_web::{
+    /// #   error::RouterError,
+    /// #   handler::handler_service,
+    /// #   http::{StatusCode, WebResponse},
+    /// #   middleware::error_handler::{ErrorHandler, ErrorInfo},
+    /// #   App, WebContext,
+    /// # };
+    /// # use std::convert::Infallible;
+    /// App::new()
+    ///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello" }))
+    ///     .enclosed(ErrorHandler::new().error(|_: RouterError<Infallible>, info: ErrorInfo| async move {
+    ///         let mut res: WebResponse = WebResponse::new(format!("{} {} failed", info.method, info.uri).into());
+    ///         *res.status_mut() = StatusCode::BAD_REQUEST;
+    ///         res
+    ///     }));
+    /// ```
+    pub fn error<F>(self, func: F) -> ErrorHandler<F> {
+        ErrorHandler {
+            status: self.status,
+            error: Arc::new(func),
+        }
+    }
+}
+
+impl<F> ErrorHandler<F> {
+    /// Rewrite response with given status code with async closure producing a new response.
+    /// Multiple status codes can be intercepted by calling this method multiple times.
+    pub fn status<H, Fut>(mut self, status: StatusCode, func: H) -> Self
+    where
+        H: Fn(ErrorInfo) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = WebResponse> + 'static,
+    {
+        self.status.push((status, Arc::new(move |info| Box::pin(func(info)))));
+        self
+    }
+}
+
+impl<F> Clone for ErrorHandler<F> {
+    fn clone(&self) -> Self {
+        Self {
+            status: self.status.clone(),
+            error: self.error.clone(),
+        }
+    }
+}
+
+impl<F, S> Service<S> for ErrorHandler<F> {
+    type Response = ErrorHandlerService<F, S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(ErrorHandlerService {
+            service,
+            handler: self.clone(),
+        })
+    }
+}
+
+pub struct ErrorHandlerService<F, S> {
+    service: S,
+    handler: ErrorHandler<F>,
+}
+
+impl<'r, F, S, C, B, Err> Service<WebContext<'r, C, B>> for ErrorHandlerService<F, S>
+where
+    F: HandleError<Err>,
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse, Error = Err>,
+    Err: for<'r2> Responder<WebContext<'r2, C, B>, Output = WebResponse>,
+{
+    type Response = WebResponse;
+    type Error = Infallible;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        // request head is collected before calling service as it may be taken by service.
+        let mut info = ErrorInfo {
+            status: StatusCode::OK,
+            method: ctx.req().method().clone(),
+            uri: ctx.req().uri().clone(),
+            headers: ctx.req().headers().clone(),
+        };
+
+        let res = match self.service.call(ctx.reborrow()).await {
+            Ok(res) => res,
+            Err(e) => match self.handler.error.handle(e, info.clone()).await {
+                Ok(res) => return Ok(res),
+                Err(e) => e.respond_to(ctx.reborrow()).await,
+            },
+        };
+
+        match self.handler.status.iter().find(|(status, _)| *status == res.status()) {
+            Some((status, func)) => {
+                info.status = *status;
+                Ok(func(info).await)
+            }
+            None => Ok(res),
+        }
+    }
+}
+
+impl<F, S> ReadyService for ErrorHandlerService<F, S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::fmt;
+
+    use futures_core::stream::Stream;
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        bytes::Bytes,
+        error::{BodyError, RouterError},
+        handler::handler_service,
+        http::{Request, RequestExt},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    async fn handler(ctx: &WebContext<'_>) -> WebResponse {
+        let mut res = WebResponse::new("ok".into());
+        if ctx.req().uri().path() == "/500" {
+            *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        }
+        res
+    }
+
+    async fn page(info: ErrorInfo) -> WebResponse {
+        let mut res = WebResponse::new(format!("{} {}", info.status.as_u16(), info.uri.path()).into());
+        *res.status_mut() = info.status;
+        res
+    }
+
+    fn call<S, ResB>(service: &S, path: &str) -> (StatusCode, String)
+    where
+        S: Service<Request<RequestExt<RequestBody>>, Response = WebResponse<ResB>>,
+        S::Error: fmt::Debug,
+        ResB: Stream<Item = Result<Bytes, BodyError>>,
+    {
+        let req = Request::builder().uri(path).body(RequestExt::default()).unwrap();
+        let res = service.call(req).now_or_panic().unwrap();
+        let status = res.status();
+        (status, collect_string_body(res.into_body()).now_or_panic().unwrap())
+    }
+
+    #[test]
+    fn status() {
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .at("/500", handler_service(handler))
+            .enclosed(
+                ErrorHandler::new()
+                    .status(StatusCode::NOT_FOUND, page)
+                    .status(StatusCode::INTERNAL_SERVER_ERROR, page),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        assert_eq!(call(&service, "/"), (StatusCode::OK, String::from("ok")));
+        assert_eq!(
+            call(&service, "/foo"),
+            (StatusCode::NOT_FOUND, String::from("404 /foo"))
+        );
+        assert_eq!(
+            call(&service, "/500"),
+            (StatusCode::INTERNAL_SERVER_ERROR, String::from("500 /500"))
+        );
+    }
+
+    #[test]
+    fn typed_error() {
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(ErrorHandler::new().status(StatusCode::NOT_FOUND, page).error(
+                |_: RouterError<_>, info: ErrorInfo| async move {
+                    let mut res = WebResponse::new(format!("gone {}", info.uri.path()).into());
+                    *res.status_mut() = StatusCode::GONE;
+                    res
+                },
+            ))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        assert_eq!(call(&service, "/foo"), (StatusCode::GONE, String::from("gone /foo")));
+    }
+}
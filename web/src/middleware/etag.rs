@@ -0,0 +1,176 @@
+//! entity tag and conditional request middleware.
+
+use core::convert::Infallible;
+
+use crate::{
+    body::ResponseBody,
+    context::WebContext,
+    dev::service::{ready::ReadyService, Service},
+    handler::conditional::not_modified,
+    http::{
+        header::{HeaderValue, CONTENT_LENGTH, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH},
+        Method, StatusCode, WebResponse,
+    },
+};
+
+/// A middleware generating weak `ETag` for buffered response body and evaluating conditional
+/// request headers against it.
+///
+/// Only `200 OK` response of `GET` and `HEAD` request is handled. Response already carrying
+/// `ETag` header keeps it. Request with matching `If-None-Match` (or `If-Modified-Since` against
+/// `Last-Modified` when the former is absent) is responded with `304 Not Modified` and empty body.
+///
+/// Streaming response body is passed through untouched as it's content can not be known without
+/// buffering it.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::etag::Etag, App, WebContext};
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///     .enclosed(Etag);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Etag;
+
+impl<S> Service<S> for Etag {
+    type Response = EtagService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(EtagService { service })
+    }
+}
+
+pub struct EtagService<S> {
+    service: S,
+}
+
+impl<'r, S, C, B, ResB, Err> Service<WebContext<'r, C, B>> for EtagService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResponseBody<ResB>>, Error = Err>,
+{
+    type Response = WebResponse<ResponseBody<ResB>>;
+    type Error = Err;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let req = ctx.req();
+
+        if !matches!(*req.method(), Method::GET | Method::HEAD) {
+            return self.service.call(ctx).await;
+        }
+
+        let if_none_match = req.headers().get(IF_NONE_MATCH).cloned();
+        let if_modified_since = req.headers().get(IF_MODIFIED_SINCE).cloned();
+
+        let mut res = self.service.call(ctx).await?;
+
+        if res.status() != StatusCode::OK {
+            return Ok(res);
+        }
+
+        let ResponseBody::Bytes { ref bytes } = *res.body() else {
+            return Ok(res);
+        };
+
+        if !res.headers().contains_key(ETAG) {
+            let etag = weak_etag(bytes);
+            res.headers_mut().insert(ETAG, etag);
+        }
+
+        if not_modified(if_none_match.as_ref(), if_modified_since.as_ref(), res.headers()) {
+            *res.status_mut() = StatusCode::NOT_MODIFIED;
+            res.headers_mut().remove(CONTENT_LENGTH);
+            *res.body_mut() = ResponseBody::None;
+        }
+
+        Ok(res)
+    }
+}
+
+impl<S> ReadyService for EtagService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+// W/"<length>-<fnv-1a hash>" in hex. the hash is stable across processes so the tag stays valid
+// for clients after server restarts.
+fn weak_etag(bytes: &[u8]) -> HeaderValue {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    // formatted numbers are always valid header value.
+    HeaderValue::try_from(format!("W/\"{:x}-{hash:x}\"", bytes.len())).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::BoxStream,
+        bytes::Bytes,
+        error::BodyError,
+        handler::handler_service,
+        http::{Request, RequestExt, WebRequest},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    fn req(uri: &'static str, if_none_match: Option<&str>) -> WebRequest {
+        let mut req = Request::builder().uri(uri).body(RequestExt::default()).unwrap();
+        if let Some(tag) = if_none_match {
+            req.headers_mut()
+                .insert(IF_NONE_MATCH, HeaderValue::from_str(tag).unwrap());
+        }
+        req
+    }
+
+    #[test]
+    fn etag() {
+        async fn stream(_: &WebContext<'_>) -> WebResponse {
+            let body = futures_util::stream::once(async { Ok::<_, BodyError>(Bytes::from_static(b"996")) });
+            WebResponse::new(ResponseBody::box_stream(BoxStream::new(body)))
+        }
+
+        let service = App::new()
+            .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+            .at("/stream", handler_service(stream))
+            .enclosed(Etag)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req("/", None)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let etag = res.headers().get(ETAG).unwrap().to_str().unwrap().to_owned();
+        assert!(etag.starts_with("W/\""));
+        assert_eq!(
+            collect_string_body(res.into_body()).now_or_panic().unwrap(),
+            "hello,world!"
+        );
+
+        let res = service.call(req("/", Some(&etag))).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(ETAG).unwrap(), etag.as_str());
+        assert!(collect_string_body(res.into_body()).now_or_panic().unwrap().is_empty());
+
+        let res = service.call(req("/", Some("\"nah\""))).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = service.call(req("/stream", Some("*"))).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res.headers().contains_key(ETAG));
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "996");
+    }
+}
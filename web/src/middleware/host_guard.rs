@@ -0,0 +1,244 @@
+//! host header validation middleware.
+
+use core::{convert::Infallible, fmt};
+
+use std::error;
+
+use crate::{
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    handler::Responder,
+    http::{
+        const_header_value::TEXT_UTF8,
+        header::{CONTENT_TYPE, HOST},
+        uri::Authority,
+        Request, StatusCode, WebResponse,
+    },
+};
+
+/// A middleware validating host of request against allowed host names. Protects against DNS
+/// rebinding and host header injection.
+///
+/// Host is taken from authority of request uri(`:authority` pseudo header of http/2 and absolute
+/// form request target of http/1) and falls back to `Host` header. Port is ignored and matching
+/// is case insensitive. Allowed host in the form of `*.example.com` matches any subdomain of
+/// `example.com` but not `example.com` itself.
+///
+/// Request without host or with malformed or repeated `Host` header is rejected with `400 Bad
+/// Request`. Request with host not allowed is rejected with `421 Misdirected Request`.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::host_guard::HostGuard, App, WebContext};
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///     .enclosed(HostGuard::new().allow("example.com").allow("*.example.com"));
+/// ```
+#[derive(Clone, Default)]
+pub struct HostGuard {
+    allow: Vec<HostPattern>,
+}
+
+impl HostGuard {
+    /// Construct a middleware with empty allow list where all requests are rejected.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow host name. Leading `*.` matches any subdomain.
+    pub fn allow(mut self, host: &str) -> Self {
+        let host = normalize(host);
+        let pattern = match host.strip_prefix("*.") {
+            Some(suffix) => HostPattern::Subdomain(format!(".{suffix}")),
+            None => HostPattern::Exact(host),
+        };
+        self.allow.push(pattern);
+        self
+    }
+
+    fn check<Ext>(&self, req: &Request<Ext>) -> Result<(), HostGuardError> {
+        let host = host(req)?;
+        if self.allow.iter().any(|pattern| pattern.matches(&host)) {
+            Ok(())
+        } else {
+            Err(HostGuardError::Misdirected(host))
+        }
+    }
+}
+
+#[derive(Clone)]
+enum HostPattern {
+    Exact(String),
+    // suffix with leading dot.
+    Subdomain(String),
+}
+
+impl HostPattern {
+    fn matches(&self, host: &str) -> bool {
+        match self {
+            Self::Exact(h) => h == host,
+            Self::Subdomain(suffix) => host.len() > suffix.len() && host.ends_with(suffix.as_str()),
+        }
+    }
+}
+
+fn host<Ext>(req: &Request<Ext>) -> Result<String, HostGuardError> {
+    if let Some(host) = req.uri().host() {
+        return Ok(normalize(host));
+    }
+
+    let mut headers = req.headers().get_all(HOST).iter();
+    let value = headers.next().ok_or(HostGuardError::Missing)?;
+    if headers.next().is_some() {
+        return Err(HostGuardError::Malformed);
+    }
+
+    // authority grammar rejects userinfo, path and other characters injected into host.
+    let authority = Authority::try_from(value.as_bytes()).map_err(|_| HostGuardError::Malformed)?;
+    if authority.as_str().contains('@') || authority.host().is_empty() {
+        return Err(HostGuardError::Malformed);
+    }
+
+    Ok(normalize(authority.host()))
+}
+
+fn normalize(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+impl<S> Service<S> for HostGuard {
+    type Response = HostGuardService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(HostGuardService {
+            service,
+            guard: self.clone(),
+        })
+    }
+}
+
+pub struct HostGuardService<S> {
+    service: S,
+    guard: HostGuard,
+}
+
+pub type HostGuardServiceError<E> = PipelineE<HostGuardError, E>;
+
+impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for HostGuardService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = HostGuardServiceError<Err>;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        self.guard.check(ctx.req()).map_err(HostGuardServiceError::First)?;
+        self.service.call(ctx).await.map_err(HostGuardServiceError::Second)
+    }
+}
+
+impl<S> ReadyService for HostGuardService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// Error type of [HostGuard] middleware.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum HostGuardError {
+    /// Request has no host. Responded with `400 Bad Request`.
+    Missing,
+    /// `Host` header is malformed or repeated. Responded with `400 Bad Request`.
+    Malformed,
+    /// Host of request is not allowed. Responded with `421 Misdirected Request`.
+    Misdirected(String),
+}
+
+impl fmt::Display for HostGuardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => f.write_str("Host is missing."),
+            Self::Malformed => f.write_str("Host header is malformed."),
+            Self::Misdirected(host) => write!(f, "Host {host} is not allowed."),
+        }
+    }
+}
+
+impl error::Error for HostGuardError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for HostGuardError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        let status = match self {
+            Self::Missing | Self::Malformed => StatusCode::BAD_REQUEST,
+            Self::Misdirected(_) => StatusCode::MISDIRECTED_REQUEST,
+        };
+        let mut res = ctx.into_response(format!("{self}"));
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        *res.status_mut() = status;
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{header::HeaderValue, RequestExt, WebRequest},
+        App,
+    };
+
+    use super::*;
+
+    fn req(uri: &str, hosts: &[&'static str]) -> WebRequest {
+        let mut req = Request::builder().uri(uri).body(RequestExt::default()).unwrap();
+        for host in hosts {
+            req.headers_mut().append(HOST, HeaderValue::from_static(host));
+        }
+        req
+    }
+
+    #[test]
+    fn host_guard() {
+        let service = App::new()
+            .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+            .enclosed(HostGuard::new().allow("Example.com").allow("*.example.com"))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let status = |req| service.call(req).now_or_panic().unwrap().status();
+
+        assert_eq!(status(req("/", &["example.com"])), StatusCode::OK);
+        assert_eq!(status(req("/", &["EXAMPLE.com.:8080"])), StatusCode::OK);
+        assert_eq!(status(req("/", &["api.example.com"])), StatusCode::OK);
+        assert_eq!(status(req("/", &["a.b.example.com"])), StatusCode::OK);
+        assert_eq!(status(req("http://api.example.com/", &[])), StatusCode::OK);
+
+        assert_eq!(status(req("/", &["evil.com"])), StatusCode::MISDIRECTED_REQUEST);
+        assert_eq!(status(req("/", &["badexample.com"])), StatusCode::MISDIRECTED_REQUEST);
+        assert_eq!(status(req("/", &["127.0.0.1"])), StatusCode::MISDIRECTED_REQUEST);
+        // absolute form request target takes precedence over host header.
+        assert_eq!(
+            status(req("http://evil.com/", &["example.com"])),
+            StatusCode::MISDIRECTED_REQUEST
+        );
+
+        assert_eq!(status(req("/", &[])), StatusCode::BAD_REQUEST);
+        assert_eq!(status(req("/", &["example.com", "evil.com"])), StatusCode::BAD_REQUEST);
+        assert_eq!(status(req("/", &["evil.com@example.com"])), StatusCode::BAD_REQUEST);
+        assert_eq!(status(req("/", &["example.com/path"])), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,484 @@
+//! ip address allow/deny list middleware.
+
+use core::{convert::Infallible, fmt, str::FromStr};
+
+use std::{
+    error,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use crate::{
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    handler::Responder,
+    http::{
+        const_header_value::TEXT_UTF8,
+        header::{HeaderMap, HeaderName, CONTENT_TYPE, FORWARDED},
+        StatusCode, WebResponse,
+    },
+};
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// A middleware filtering requests by client ip address with CIDR allow and deny lists.
+///
+/// A request is rejected with `403 Forbidden` before reaching the enclosed service when client
+/// ip matches any deny entry, or when allow list is not empty and client ip matches none of it's
+/// entries. Deny list takes precedence over allow list.
+///
+/// Client ip is the peer address of connection by default. When the peer is a trusted proxy the
+/// forwarding header set by [IpFilter::forwarded_header] is walked from the nearest hop backwards
+/// and the first address not belonging to trusted proxies is used. The other forwarding header is
+/// never looked at as it's passed through by proxy untouched and can be forged by client. Client
+/// ip that can not be determined from the header only passes when allow list is empty.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::ip_filter::{ForwardedHeader, IpFilter}, App, WebContext};
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///     .enclosed(
+///         IpFilter::new()
+///             .allow("10.0.0.0/8")
+///             .deny("10.0.0.1")
+///             // trust forwarding header added by load balancer.
+///             .trust_proxy("192.168.1.0/24")
+///             .forwarded_header(ForwardedHeader::Forwarded),
+///     );
+/// ```
+#[derive(Clone, Default)]
+pub struct IpFilter {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+    proxies: Vec<IpNet>,
+    header: ForwardedHeader,
+}
+
+/// Forwarding header carrying client ip from trusted proxy.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ForwardedHeader {
+    /// `Forwarded` header from RFC 7239.
+    Forwarded,
+    /// `X-Forwarded-For` header.
+    #[default]
+    XForwardedFor,
+}
+
+impl IpFilter {
+    /// Construct a middleware with empty lists where all requests are allowed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow client ip in given network. Network is in CIDR notation or a single ip address.
+    ///
+    /// # Panics:
+    /// When network is not valid.
+    pub fn allow(mut self, net: &str) -> Self {
+        self.allow.push(parse(net));
+        self
+    }
+
+    /// Deny client ip in given network. Network is in CIDR notation or a single ip address.
+    ///
+    /// # Panics:
+    /// When network is not valid.
+    pub fn deny(mut self, net: &str) -> Self {
+        self.deny.push(parse(net));
+        self
+    }
+
+    /// Trust forwarding header from peer in given network. Network is in CIDR notation or a single
+    /// ip address.
+    ///
+    /// # Panics:
+    /// When network is not valid.
+    pub fn trust_proxy(mut self, net: &str) -> Self {
+        self.proxies.push(parse(net));
+        self
+    }
+
+    /// Set the forwarding header trusted proxies write client ip to.
+    ///
+    /// Default to [ForwardedHeader::XForwardedFor].
+    pub fn forwarded_header(mut self, header: ForwardedHeader) -> Self {
+        self.header = header;
+        self
+    }
+
+    fn client_ip(&self, peer: SocketAddr, headers: &HeaderMap) -> Option<IpAddr> {
+        let peer = peer.ip().to_canonical();
+
+        if !contains(&self.proxies, peer) {
+            return Some(peer);
+        }
+
+        let mut hops = match self.header {
+            ForwardedHeader::Forwarded => forwarded(headers),
+            ForwardedHeader::XForwardedFor => x_forwarded_for(headers),
+        };
+
+        // walk from the nearest hop. every hop before the first untrusted one is added by trusted
+        // proxies and can not be forged by client.
+        hops.reverse();
+        for hop in hops {
+            match hop {
+                Some(ip) if contains(&self.proxies, ip) => continue,
+                hop => return hop,
+            }
+        }
+
+        // all hops are trusted proxies or there is no forwarding header.
+        Some(peer)
+    }
+
+    fn check(&self, ip: Option<IpAddr>) -> Result<(), IpFilterError> {
+        let allowed = match ip {
+            Some(ip) => !contains(&self.deny, ip) && (self.allow.is_empty() || contains(&self.allow, ip)),
+            None => self.allow.is_empty(),
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(IpFilterError { ip })
+        }
+    }
+}
+
+fn parse(net: &str) -> IpNet {
+    net.parse()
+        .unwrap_or_else(|_| panic!("{net} is not valid CIDR notation or ip address"))
+}
+
+fn contains(nets: &[IpNet], ip: IpAddr) -> bool {
+    nets.iter().any(|net| net.contains(ip))
+}
+
+// for=<node> pairs of Forwarded header in order of hops. RFC 7239 section 4.
+fn forwarded(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    headers
+        .get_all(FORWARDED)
+        .iter()
+        .flat_map(|v| v.to_str().unwrap_or_default().split(','))
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.trim().split_once('=')?;
+                key.eq_ignore_ascii_case("for").then(|| node(value.trim_matches('"')))
+            })
+        })
+        .collect()
+}
+
+fn x_forwarded_for(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .flat_map(|v| v.to_str().unwrap_or_default().split(','))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(node)
+        .collect()
+}
+
+// node can be ip address with optional port where ipv6 address is bracketed when port is present.
+// obfuscated identifier and "unknown" produce None.
+fn node(node: &str) -> Option<IpAddr> {
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip.to_canonical());
+    }
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr.ip().to_canonical());
+    }
+    node.strip_prefix('[')
+        .and_then(|node| node.strip_suffix(']'))
+        .and_then(|ip| ip.parse::<Ipv6Addr>().ok())
+        .map(|ip| IpAddr::V6(ip).to_canonical())
+}
+
+// network in CIDR notation with host bits cleared.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => mask_v4(ip, self.prefix) == net,
+            (IpAddr::V6(net), IpAddr::V6(ip)) => mask_v6(ip, self.prefix) == net,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpNet {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().map_err(|_| ())?)),
+            None => (s, None),
+        };
+
+        let addr = addr.parse::<IpAddr>().map_err(|_| ())?.to_canonical();
+
+        let net = match addr {
+            IpAddr::V4(addr) => {
+                let prefix = prefix.unwrap_or(32);
+                if prefix > 32 {
+                    return Err(());
+                }
+                Self {
+                    addr: IpAddr::V4(mask_v4(addr, prefix)),
+                    prefix,
+                }
+            }
+            IpAddr::V6(addr) => {
+                let prefix = prefix.unwrap_or(128);
+                if prefix > 128 {
+                    return Err(());
+                }
+                Self {
+                    addr: IpAddr::V6(mask_v6(addr, prefix)),
+                    prefix,
+                }
+            }
+        };
+
+        Ok(net)
+    }
+}
+
+fn mask_v4(ip: Ipv4Addr, prefix: u8) -> Ipv4Addr {
+    let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+    Ipv4Addr::from(u32::from(ip) & mask)
+}
+
+fn mask_v6(ip: Ipv6Addr, prefix: u8) -> Ipv6Addr {
+    let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+    Ipv6Addr::from(u128::from(ip) & mask)
+}
+
+impl<S> Service<S> for IpFilter {
+    type Response = IpFilterService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(IpFilterService {
+            service,
+            filter: self.clone(),
+        })
+    }
+}
+
+pub struct IpFilterService<S> {
+    service: S,
+    filter: IpFilter,
+}
+
+pub type IpFilterServiceError<E> = PipelineE<IpFilterError, E>;
+
+impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for IpFilterService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = IpFilterServiceError<Err>;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let ip = self.filter.client_ip(ctx.peer_addr(), ctx.req().headers());
+        self.filter.check(ip).map_err(IpFilterServiceError::First)?;
+        self.service.call(ctx).await.map_err(IpFilterServiceError::Second)
+    }
+}
+
+impl<S> ReadyService for IpFilterService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// Error type of [IpFilter] middleware. Responded with `403 Forbidden`.
+#[derive(Debug)]
+pub struct IpFilterError {
+    ip: Option<IpAddr>,
+}
+
+impl IpFilterError {
+    /// Client ip address of rejected request. None when it can not be determined from forwarding
+    /// headers of trusted proxy.
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.ip
+    }
+}
+
+impl fmt::Display for IpFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.ip {
+            Some(ip) => write!(f, "Client ip {ip} is not allowed."),
+            None => f.write_str("Unknown client ip is not allowed."),
+        }
+    }
+}
+
+impl error::Error for IpFilterError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for IpFilterError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        let mut res = ctx.into_response(format!("{self}"));
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        *res.status_mut() = StatusCode::FORBIDDEN;
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{header::HeaderValue, Request, RequestExt, WebRequest},
+        App,
+    };
+
+    use super::*;
+
+    fn req(addr: [u8; 4], forwarded: Option<(HeaderName, &'static str)>) -> WebRequest {
+        let mut req = Request::builder().body(RequestExt::default()).unwrap();
+        *req.body_mut().socket_addr_mut() = SocketAddr::from((addr, 8080));
+        if let Some((name, value)) = forwarded {
+            req.headers_mut().insert(name, HeaderValue::from_static(value));
+        }
+        req
+    }
+
+    #[test]
+    fn ip_net() {
+        let net = "10.1.2.3/8".parse::<IpNet>().unwrap();
+        assert!(net.contains(IpAddr::from([10, 255, 0, 1])));
+        assert!(!net.contains(IpAddr::from([11, 0, 0, 1])));
+
+        let net = "fe80::/10".parse::<IpNet>().unwrap();
+        assert!(net.contains("fe80::1".parse().unwrap()));
+        assert!(!net.contains("::1".parse().unwrap()));
+        assert!(!net.contains(IpAddr::from([10, 0, 0, 1])));
+
+        assert!("0.0.0.0/0"
+            .parse::<IpNet>()
+            .unwrap()
+            .contains(IpAddr::from([1, 2, 3, 4])));
+        assert!("::ffff:10.0.0.1"
+            .parse::<IpNet>()
+            .unwrap()
+            .contains(IpAddr::from([10, 0, 0, 1])));
+
+        assert!("10.0.0.0/33".parse::<IpNet>().is_err());
+        assert!("10.0.0/8".parse::<IpNet>().is_err());
+    }
+
+    #[test]
+    fn forwarded_node() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            FORWARDED,
+            HeaderValue::from_static("for=192.0.2.60;proto=http, For=\"[2001:db8::1]:4711\""),
+        );
+        headers.append(FORWARDED, HeaderValue::from_static("for=unknown, for=10.0.0.1:80"));
+        assert_eq!(
+            forwarded(&headers),
+            [
+                Some(IpAddr::from([192, 0, 2, 60])),
+                Some("2001:db8::1".parse().unwrap()),
+                None,
+                Some(IpAddr::from([10, 0, 0, 1])),
+            ]
+        );
+    }
+
+    fn service(header: ForwardedHeader) -> impl Fn(WebRequest) -> StatusCode {
+        let service = App::new()
+            .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+            .enclosed(
+                IpFilter::new()
+                    .allow("10.0.0.0/8")
+                    .deny("10.0.0.1")
+                    .trust_proxy("192.168.1.0/24")
+                    .forwarded_header(header),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        move |req| service.call(req).now_or_panic().unwrap().status()
+    }
+
+    #[test]
+    fn ip_filter() {
+        let status = service(ForwardedHeader::XForwardedFor);
+
+        assert_eq!(status(req([10, 0, 0, 2], None)), StatusCode::OK);
+        assert_eq!(status(req([10, 0, 0, 1], None)), StatusCode::FORBIDDEN);
+        assert_eq!(status(req([127, 0, 0, 1], None)), StatusCode::FORBIDDEN);
+
+        // forwarding header from untrusted peer is ignored.
+        let xff = (X_FORWARDED_FOR, "10.0.0.2");
+        assert_eq!(status(req([127, 0, 0, 1], Some(xff))), StatusCode::FORBIDDEN);
+
+        // client ip is the first untrusted hop from trusted proxy.
+        let xff = (X_FORWARDED_FOR, "127.0.0.1, 10.0.0.2, 192.168.1.3");
+        assert_eq!(status(req([192, 168, 1, 2], Some(xff))), StatusCode::OK);
+        let xff = (X_FORWARDED_FOR, "10.0.0.2, 127.0.0.1");
+        assert_eq!(status(req([192, 168, 1, 2], Some(xff))), StatusCode::FORBIDDEN);
+
+        // unknown client is not in allow list.
+        let xff = (X_FORWARDED_FOR, "unknown");
+        assert_eq!(status(req([192, 168, 1, 2], Some(xff))), StatusCode::FORBIDDEN);
+
+        // proxy itself is not in allow list.
+        assert_eq!(status(req([192, 168, 1, 2], None)), StatusCode::FORBIDDEN);
+
+        let status = service(ForwardedHeader::Forwarded);
+
+        let fwd = (FORWARDED, "for=10.0.0.1");
+        assert_eq!(status(req([192, 168, 1, 2], Some(fwd))), StatusCode::FORBIDDEN);
+        let fwd = (FORWARDED, "for=\"10.0.0.3:4711\"");
+        assert_eq!(status(req([192, 168, 1, 2], Some(fwd))), StatusCode::OK);
+        let fwd = (FORWARDED, "for=unknown");
+        assert_eq!(status(req([192, 168, 1, 2], Some(fwd))), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn spoof() {
+        // request with client forged header passing through trusted proxy that only appends to the
+        // configured one.
+        let spoofed = |forged: (HeaderName, &'static str), appended: (HeaderName, &'static str)| {
+            let mut req = req([192, 168, 1, 2], Some(appended));
+            req.headers_mut().insert(forged.0, HeaderValue::from_static(forged.1));
+            req
+        };
+
+        let status = service(ForwardedHeader::XForwardedFor);
+        let spoof = spoofed((FORWARDED, "for=10.0.0.2"), (X_FORWARDED_FOR, "127.0.0.1"));
+        assert_eq!(status(spoof), StatusCode::FORBIDDEN);
+
+        let status = service(ForwardedHeader::Forwarded);
+        let spoof = spoofed((X_FORWARDED_FOR, "10.0.0.2"), (FORWARDED, "for=127.0.0.1"));
+        assert_eq!(status(spoof), StatusCode::FORBIDDEN);
+
+        // forged header alone is ignored and proxy peer address is used.
+        let spoof = req([192, 168, 1, 2], Some((X_FORWARDED_FOR, "10.0.0.2")));
+        assert_eq!(status(spoof), StatusCode::FORBIDDEN);
+    }
+}
@@ -8,10 +8,12 @@ use std::{
 
 use futures_core::stream::Stream;
 use pin_project_lite::pin_project;
+use tracing::error;
 use xitca_http::Request;
 
 use crate::{
-    body::BodyStream,
+    body::{BodyStream, NONE_BODY_HINT},
+    bytes::Bytes,
     context::WebContext,
     dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
     handler::Responder,
@@ -21,6 +23,7 @@ use crate::{
 #[derive(Copy, Clone)]
 pub struct Limit {
     request_body_size: usize,
+    response_body_size: usize,
 }
 
 impl Default for Limit {
@@ -33,6 +36,7 @@ impl Limit {
     pub fn new() -> Self {
         Self {
             request_body_size: usize::MAX,
+            response_body_size: usize::MAX,
         }
     }
 
@@ -41,6 +45,20 @@ impl Limit {
         self.request_body_size = size;
         self
     }
+
+    /// Set max size in byte unit the response body can be.
+    ///
+    /// Response with known body size beyond limit is rejected with [LimitError::ResponseBodyOverSize]
+    /// which would be converted to 500 internal server error response. Streaming response body
+    /// can only be checked while it's being sent. When it goes beyond limit the body is aborted
+    /// with [LimitError::ResponseBodyOverSize] and the connection would be closed as the status
+    /// and headers of response are already sent.
+    ///
+    /// Both conditions are logged as error event.
+    pub fn set_response_body_max_size(mut self, size: usize) -> Self {
+        self.response_body_size = size;
+        self
+    }
 }
 
 impl<S> Service<S> for Limit {
@@ -59,24 +77,38 @@ pub struct LimitService<S> {
 
 pub type LimitServiceError<E> = PipelineE<LimitError, E>;
 
-impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for LimitService<S>
+impl<'r, S, C, B, ResB, BE, Err> Service<WebContext<'r, C, B>> for LimitService<S>
 where
     B: BodyStream + Default,
-    S: for<'r2> Service<WebContext<'r2, C, LimitBody<B>>, Response = Res, Error = Err>,
+    B::Chunk: Into<Bytes>,
+    S: for<'r2> Service<WebContext<'r2, C, LimitBody<B>>, Response = WebResponse<ResB>, Error = Err>,
+    ResB: Stream<Item = Result<Bytes, BE>>,
 {
-    type Response = Res;
+    type Response = WebResponse<LimitResponseBody<ResB>>;
     type Error = LimitServiceError<Err>;
 
     async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
         let (parts, ext) = ctx.take_request().into_parts();
-        let ctx = ctx.ctx;
+        let WebContext { ctx, res_headers, .. } = ctx;
         let (ext, body) = ext.replace_body(());
         let mut body = RefCell::new(LimitBody::new(body, self.limit.request_body_size));
         let mut req = Request::from_parts(parts, ext);
 
-        let ctx = WebContext::new(&mut req, &mut body, ctx);
+        let ctx = WebContext::new(&mut req, &mut body, ctx, res_headers);
 
-        self.service.call(ctx).await.map_err(LimitServiceError::Second)
+        let res = self.service.call(ctx).await.map_err(LimitServiceError::Second)?;
+
+        let limit = self.limit.response_body_size;
+        match res.body().size_hint() {
+            NONE_BODY_HINT => {}
+            (_, Some(size)) if size > limit => {
+                error!("response body size {size} bytes is beyond limit: {limit} bytes");
+                return Err(LimitServiceError::First(LimitError::ResponseBodyOverSize(limit)));
+            }
+            _ => {}
+        }
+
+        Ok(res.map(|body| LimitResponseBody::new(body, limit)))
     }
 }
 
@@ -93,9 +125,14 @@ where
 }
 
 pin_project! {
+    /// request body type of [LimitService].
+    ///
+    /// Chunk going beyond limit is split at the limit. The allowed prefix of it is yielded and
+    /// followed by [LimitError::BodyOverSize] error so bytes beyond limit are never observed.
     pub struct LimitBody<B> {
         limit: usize,
         record: usize,
+        over_size: bool,
         #[pin]
         body: B
     }
@@ -106,6 +143,7 @@ impl<B: Default> Default for LimitBody<B> {
         Self {
             limit: 0,
             record: 0,
+            over_size: false,
             body: B::default(),
         }
     }
@@ -113,33 +151,99 @@ impl<B: Default> Default for LimitBody<B> {
 
 impl<B> LimitBody<B> {
     fn new(body: B, limit: usize) -> Self {
-        Self { limit, record: 0, body }
+        Self {
+            limit,
+            record: 0,
+            over_size: false,
+            body,
+        }
     }
 }
 
 impl<B> Stream for LimitBody<B>
 where
     B: BodyStream,
+    B::Chunk: Into<Bytes>,
 {
-    type Item = Result<B::Chunk, LimitBodyError<B::Error>>;
+    type Item = Result<Bytes, LimitBodyError<B::Error>>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
 
-        if *this.record >= *this.limit {
+        if *this.over_size {
             return Poll::Ready(Some(Err(LimitBodyError::First(LimitError::BodyOverSize(*this.limit)))));
         }
 
+        match ready!(this.body.poll_next(cx)) {
+            Some(res) => {
+                let mut chunk = res.map_err(LimitBodyError::Second)?.into();
+                let remaining = *this.limit - *this.record;
+
+                if chunk.len() > remaining {
+                    *this.over_size = true;
+                    if remaining == 0 {
+                        return Poll::Ready(Some(Err(LimitBodyError::First(LimitError::BodyOverSize(*this.limit)))));
+                    }
+                    chunk.truncate(remaining);
+                }
+
+                *this.record += chunk.len();
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+pin_project! {
+    /// response body type of [LimitService].
+    pub struct LimitResponseBody<B> {
+        limit: usize,
+        record: usize,
+        #[pin]
+        body: B
+    }
+}
+
+impl<B> LimitResponseBody<B> {
+    fn new(body: B, limit: usize) -> Self {
+        Self { limit, record: 0, body }
+    }
+}
+
+impl<B, E> Stream for LimitResponseBody<B>
+where
+    B: Stream<Item = Result<Bytes, E>>,
+{
+    type Item = Result<Bytes, LimitBodyError<E>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        if *this.record > *this.limit {
+            return Poll::Ready(None);
+        }
+
         match ready!(this.body.poll_next(cx)) {
             Some(res) => {
                 let chunk = res.map_err(LimitBodyError::Second)?;
-                *this.record += chunk.as_ref().len();
-                // TODO: for now there is no way to split a chunk if it goes beyond body limit.
+                *this.record = this.record.saturating_add(chunk.len());
+                if *this.record > *this.limit {
+                    error!("response body is aborted for going beyond limit: {} bytes", this.limit);
+                    return Poll::Ready(Some(Err(LimitBodyError::First(LimitError::ResponseBodyOverSize(
+                        *this.limit,
+                    )))));
+                }
                 Poll::Ready(Some(Ok(chunk)))
             }
             None => Poll::Ready(None),
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.body.size_hint()
+    }
 }
 
 pub type LimitBodyError<E> = PipelineE<LimitError, E>;
@@ -147,12 +251,14 @@ pub type LimitBodyError<E> = PipelineE<LimitError, E>;
 #[derive(Debug)]
 pub enum LimitError {
     BodyOverSize(usize),
+    ResponseBodyOverSize(usize),
 }
 
 impl fmt::Display for LimitError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             Self::BodyOverSize(size) => write!(f, "Body size reached limit: {size} bytes."),
+            Self::ResponseBodyOverSize(size) => write!(f, "Response body size reached limit: {size} bytes."),
         }
     }
 }
@@ -163,10 +269,20 @@ impl<'r, C, B> Responder<WebContext<'r, C, B>> for LimitError {
     type Output = WebResponse;
 
     async fn respond_to(self, req: WebContext<'r, C, B>) -> Self::Output {
-        let mut res = req.into_response(format!("{self}"));
-        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
-        *res.status_mut() = StatusCode::BAD_REQUEST;
-        res
+        match self {
+            Self::BodyOverSize(_) => {
+                let mut res = req.into_response(format!("{self}"));
+                res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+                *res.status_mut() = StatusCode::BAD_REQUEST;
+                res
+            }
+            // detail of server side failure is logged and not exposed to client.
+            Self::ResponseBodyOverSize(_) => {
+                let mut res = req.into_response(Bytes::new());
+                *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                res
+            }
+        }
     }
 }
 
@@ -177,8 +293,7 @@ mod test {
     use xitca_unsafe_collection::futures::NowOrPanic;
 
     use crate::{
-        body::BoxStream,
-        bytes::Bytes,
+        body::{BoxStream, RequestBody, ResponseBody},
         error::BodyError,
         handler::{body::Body, handler_service},
         http::{Request, RequestExt},
@@ -227,4 +342,104 @@ mod test {
 
         assert_eq!(body, chunk);
     }
+
+    #[test]
+    fn request_body_split_at_limit() {
+        use futures_util::stream::{self, StreamExt};
+
+        async fn handler<B: BodyStream>(Body(body): Body<B>) -> String {
+            let mut body = pin!(body);
+            let mut res = String::new();
+
+            while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+                match chunk {
+                    Ok(chunk) => res.push_str(std::str::from_utf8(chunk.as_ref()).unwrap()),
+                    Err(_) => {
+                        res.push_str("|over size");
+                        break;
+                    }
+                }
+            }
+
+            res
+        }
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Limit::new().set_request_body_max_size(15))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let req = |chunks: &'static [&'static str]| {
+            let body = stream::iter(chunks).map(|c| Ok::<_, BodyError>(Bytes::from_static(c.as_bytes())));
+            Request::new(RequestExt::default().map_body(|_: ()| BoxStream::new(body)))
+        };
+
+        let call = |chunks| {
+            let body = service.call(req(chunks)).now_or_panic().ok().unwrap().into_body();
+            String::from_utf8(collect_body(body).now_or_panic().unwrap()).unwrap()
+        };
+
+        assert_eq!(call(&["hello,world!", "hello"]), "hello,world!hel|over size");
+        assert_eq!(call(&["hello,world!", "hel", "lo"]), "hello,world!hel|over size");
+        assert_eq!(call(&["hello,world!", "hel"]), "hello,world!hel");
+    }
+
+    #[test]
+    fn response_body_over_limit() {
+        use futures_util::stream::{self, StreamExt};
+
+        async fn stream() -> WebResponse {
+            let item = || async { Ok::<_, BodyError>(Bytes::from_static(b"hello,world!")) };
+            let body = stream::once(item()).chain(stream::once(item()));
+            WebResponse::new(ResponseBody::box_stream(body))
+        }
+
+        let service = App::new()
+            .at("/sized", handler_service(|| async { "hello,world!" }))
+            .at("/stream", handler_service(stream))
+            .enclosed(Limit::new().set_response_body_max_size(12))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let req = |path| {
+            Request::builder()
+                .uri(path)
+                .body(RequestExt::<RequestBody>::default())
+                .unwrap()
+        };
+
+        let res = service.call(req("/sized")).now_or_panic().ok().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(collect_body(res.into_body()).now_or_panic().unwrap(), b"hello,world!");
+
+        let res = service.call(req("/stream")).now_or_panic().ok().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let mut body = pin!(res.into_body());
+        let chunk = poll_fn(|cx| body.as_mut().poll_next(cx))
+            .now_or_panic()
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk, "hello,world!");
+        assert!(poll_fn(|cx| body.as_mut().poll_next(cx))
+            .now_or_panic()
+            .unwrap()
+            .is_err());
+        assert!(poll_fn(|cx| body.as_mut().poll_next(cx)).now_or_panic().is_none());
+
+        let service = App::new()
+            .at("/sized", handler_service(|| async { "hello,world!" }))
+            .enclosed(Limit::new().set_response_body_max_size(11))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req("/sized")).now_or_panic().ok().unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
 }
@@ -0,0 +1,398 @@
+//! structured access logging middleware.
+
+use core::{
+    convert::Infallible,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use std::{net::SocketAddr, time::Instant};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use tracing::field;
+
+use crate::{
+    context::WebContext,
+    dev::service::{ready::ReadyService, Service},
+    http::{header::CONTENT_LENGTH, Method, StatusCode, WebResponse},
+};
+
+/// Selection of fields recorded by [Logger] for every request.
+///
+/// All fields are selected by default.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Format {
+    method: bool,
+    path: bool,
+    status: bool,
+    latency: bool,
+    request_size: bool,
+    response_size: bool,
+    peer: bool,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Format {
+    /// Construct a format with all fields selected.
+    pub const fn new() -> Self {
+        Self {
+            method: true,
+            path: true,
+            status: true,
+            latency: true,
+            request_size: true,
+            response_size: true,
+            peer: true,
+        }
+    }
+
+    /// Construct a format with no field selected.
+    pub const fn empty() -> Self {
+        Self {
+            method: false,
+            path: false,
+            status: false,
+            latency: false,
+            request_size: false,
+            response_size: false,
+            peer: false,
+        }
+    }
+
+    /// Select request method as `method` field.
+    pub const fn method(mut self, value: bool) -> Self {
+        self.method = value;
+        self
+    }
+
+    /// Select request path as `path` field. Query string is not recorded.
+    pub const fn path(mut self, value: bool) -> Self {
+        self.path = value;
+        self
+    }
+
+    /// Select response status code as `status` field.
+    pub const fn status(mut self, value: bool) -> Self {
+        self.status = value;
+        self
+    }
+
+    /// Select duration from receiving request to finishing response body as `latency` field.
+    pub const fn latency(mut self, value: bool) -> Self {
+        self.latency = value;
+        self
+    }
+
+    /// Select request body size as `request_size` field. The size is taken from `Content-Length`
+    /// header and it's not recorded when the header is absent.
+    pub const fn request_size(mut self, value: bool) -> Self {
+        self.request_size = value;
+        self
+    }
+
+    /// Select number of response body bytes sent as `response_size` field.
+    pub const fn response_size(mut self, value: bool) -> Self {
+        self.response_size = value;
+        self
+    }
+
+    /// Select socket address of peer as `peer` field.
+    pub const fn peer(mut self, value: bool) -> Self {
+        self.peer = value;
+        self
+    }
+}
+
+/// A middleware emitting one `tracing` event per request for access logging.
+///
+/// Event is emitted at `INFO` level with `xitca_web::access` as target when response body is
+/// finished or dropped. Response body is counted as it's streamed and never buffered. When the
+/// enclosed service returns error the event is emitted immediately with `error` field set.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::handler_service,
+/// #   middleware::logger::{Format, Logger},
+/// #   App, WebContext
+/// # };
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///     .at("/health", handler_service(|_: &WebContext<'_>| async { "ok" }))
+///     .enclosed(
+///         Logger::new()
+///             .format(Format::new().peer(false))
+///             // health check is too noisy to be logged.
+///             .skip_path("/health"),
+///     );
+/// ```
+#[derive(Clone, Default)]
+pub struct Logger {
+    format: Format,
+    skip: Vec<String>,
+}
+
+impl Logger {
+    /// Construct a logger recording all fields of [Format] for every request.
+    pub fn new() -> Self {
+        Self {
+            format: Format::new(),
+            skip: Vec::new(),
+        }
+    }
+
+    /// Set fields recorded for every request.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Skip logging request with exact matching path. Can be called multiple times for
+    /// skipping multiple paths.
+    pub fn skip_path(mut self, path: impl Into<String>) -> Self {
+        self.skip.push(path.into());
+        self
+    }
+}
+
+impl<S> Service<S> for Logger {
+    type Response = LoggerService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(LoggerService {
+            service,
+            format: self.format,
+            skip: self.skip.clone(),
+        })
+    }
+}
+
+pub struct LoggerService<S> {
+    service: S,
+    format: Format,
+    skip: Vec<String>,
+}
+
+impl<'r, S, C, B, ResB, Err> Service<WebContext<'r, C, B>> for LoggerService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = Err>,
+{
+    type Response = WebResponse<LoggerBody<ResB>>;
+    type Error = Err;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let path = ctx.req().uri().path();
+
+        if self.skip.iter().any(|skip| skip == path) {
+            let res = self.service.call(ctx).await?;
+            return Ok(res.map(|body| LoggerBody { body, record: None }));
+        }
+
+        let req = ctx.req();
+        let mut record = Record {
+            format: self.format,
+            start: Instant::now(),
+            method: req.method().clone(),
+            path: String::from(path),
+            peer: *req.body().socket_addr(),
+            request_size: req
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok()),
+            status: None,
+            response_size: 0,
+        };
+
+        match self.service.call(ctx).await {
+            Ok(res) => {
+                record.status = Some(res.status());
+                Ok(res.map(|body| LoggerBody {
+                    body,
+                    record: Some(record),
+                }))
+            }
+            // record is dropped and event is emitted without status.
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<S> ReadyService for LoggerService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+pin_project! {
+    /// Response body type of [LoggerService]. Counting bytes of inner body and emitting access log
+    /// event when it's finished or dropped.
+    pub struct LoggerBody<B> {
+        #[pin]
+        body: B,
+        record: Option<Record>,
+    }
+}
+
+impl<B, T, E> Stream for LoggerBody<B>
+where
+    B: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]>,
+{
+    type Item = Result<T, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let res = ready!(this.body.poll_next(cx));
+        match res {
+            Some(Ok(ref chunk)) => {
+                if let Some(record) = this.record {
+                    record.response_size += chunk.as_ref().len() as u64;
+                }
+            }
+            // emit event as soon as body is finished. the connection may keep the body around.
+            None => drop(this.record.take()),
+            _ => {}
+        }
+        Poll::Ready(res)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.body.size_hint()
+    }
+}
+
+struct Record {
+    format: Format,
+    start: Instant,
+    method: Method,
+    path: String,
+    peer: SocketAddr,
+    request_size: Option<u64>,
+    status: Option<StatusCode>,
+    response_size: u64,
+}
+
+impl Drop for Record {
+    fn drop(&mut self) {
+        let f = self.format;
+        let latency = self.start.elapsed();
+        tracing::info!(
+            target: "xitca_web::access",
+            method = f.method.then(|| field::display(&self.method)),
+            path = f.path.then_some(self.path.as_str()),
+            status = f.status.then_some(self.status.map(|s| s.as_u16())).flatten(),
+            latency = f.latency.then(|| field::debug(latency)),
+            request_size = f.request_size.then_some(self.request_size).flatten(),
+            response_size = f.response_size.then_some(self.response_size),
+            peer = f.peer.then(|| field::display(self.peer)),
+            error = self.status.is_none().then_some(true),
+            "access"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fmt::{self, Write},
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{Uri, WebRequest},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    // subscriber writing fields of access events into shared string.
+    struct Capture(Arc<Mutex<String>>);
+
+    impl Visit for Capture {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            write!(self.0.lock().unwrap(), "{}={:?} ", field.name(), value).unwrap();
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, meta: &Metadata<'_>) -> bool {
+            meta.target() == "xitca_web::access"
+        }
+
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            event.record(&mut Capture(self.0.clone()));
+        }
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    #[test]
+    fn access_log() {
+        let log = Arc::new(Mutex::new(String::new()));
+
+        tracing::subscriber::with_default(Capture(log.clone()), || {
+            let service = App::new()
+                .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+                .at("/health", handler_service(|_: &WebContext<'_>| async { "ok" }))
+                .enclosed(Logger::new().format(Format::new().latency(false)).skip_path("/health"))
+                .finish()
+                .call(())
+                .now_or_panic()
+                .unwrap();
+
+            let res = service.call(WebRequest::default()).now_or_panic().unwrap();
+            // event is emitted when response body is finished.
+            assert!(log.lock().unwrap().is_empty());
+            let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+            assert_eq!(body, "hello,world!");
+
+            let mut req = WebRequest::default();
+            *req.uri_mut() = Uri::from_static("/health");
+            let res = service.call(req).now_or_panic().unwrap();
+            collect_string_body(res.into_body()).now_or_panic().unwrap();
+        });
+
+        let log = log.lock().unwrap();
+        assert!(log.contains("method=GET "));
+        assert!(log.contains("path=\"/\" "));
+        assert!(log.contains("status=200 "));
+        assert!(log.contains("response_size=12 "));
+        assert!(log.contains("peer="));
+        assert!(!log.contains("latency="));
+        assert!(!log.contains("request_size="));
+        assert!(!log.contains("/health"));
+    }
+}
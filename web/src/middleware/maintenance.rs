@@ -0,0 +1,235 @@
+//! maintenance mode middleware.
+
+use core::{
+    convert::Infallible,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use std::sync::Arc;
+
+use crate::{
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    error::Throttled,
+};
+
+/// Default delay client should wait before retrying when maintenance mode is on.
+pub const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Handle toggling maintenance mode of [Maintenance] middleware at runtime.
+///
+/// Handle is cheap to clone and shared by all clones of the middleware and all worker threads it
+/// runs on.
+#[derive(Clone, Debug, Default)]
+pub struct MaintenanceHandle(Arc<AtomicBool>);
+
+impl MaintenanceHandle {
+    /// Construct a handle with maintenance mode off.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turn on maintenance mode.
+    pub fn enable(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Turn off maintenance mode.
+    pub fn disable(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+
+    /// Check if maintenance mode is on.
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// A middleware answering requests with `503 Service Unavailable` and `Retry-After` header when
+/// maintenance mode is turned on with [MaintenanceHandle]. Enables maintenance window without
+/// redeploying.
+///
+/// Requests with path allowed by [Maintenance::allow] are passed to inner service regardless of
+/// maintenance mode. Useful for health check and admin endpoints.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::maintenance::Maintenance, App, WebContext};
+/// let maintenance = Maintenance::new().allow("/health");
+/// let handle = maintenance.handle();
+///
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///     .at("/health", handler_service(|_: &WebContext<'_>| async { "ok" }))
+///     .enclosed(maintenance);
+///
+/// // requests other than health check are rejected from now on.
+/// handle.enable();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Maintenance {
+    handle: MaintenanceHandle,
+    allow: Vec<String>,
+    retry_after: Duration,
+}
+
+impl Default for Maintenance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Maintenance {
+    /// Construct a middleware with maintenance mode off and nothing allowed.
+    pub fn new() -> Self {
+        Self::with_handle(MaintenanceHandle::new())
+    }
+
+    /// Construct a middleware toggled by given handle. Useful for sharing one handle between
+    /// multiple apps.
+    pub fn with_handle(handle: MaintenanceHandle) -> Self {
+        Self {
+            handle,
+            allow: Vec::new(),
+            retry_after: DEFAULT_RETRY_AFTER,
+        }
+    }
+
+    /// Get handle toggling maintenance mode of middleware.
+    pub fn handle(&self) -> MaintenanceHandle {
+        self.handle.clone()
+    }
+
+    /// Allow request with given path or path under it when maintenance mode is on. `/admin`
+    /// matches `/admin` and `/admin/users` but not `/administrator`.
+    pub fn allow(mut self, path: impl Into<String>) -> Self {
+        let mut path = path.into();
+        while path.len() > 1 && path.ends_with('/') {
+            path.pop();
+        }
+        self.allow.push(path);
+        self
+    }
+
+    /// Set the delay client should wait before retrying.
+    ///
+    /// Default to [DEFAULT_RETRY_AFTER].
+    pub fn set_retry_after(mut self, dur: Duration) -> Self {
+        self.retry_after = dur;
+        self
+    }
+
+    fn check(&self, path: &str) -> Result<(), Throttled> {
+        if !self.handle.is_enabled() || self.allow.iter().any(|allow| is_allowed(allow, path)) {
+            return Ok(());
+        }
+        Err(Throttled::service_unavailable("Service is under maintenance.").retry_after(self.retry_after))
+    }
+}
+
+fn is_allowed(allow: &str, path: &str) -> bool {
+    match path.strip_prefix(allow) {
+        Some(rest) => rest.is_empty() || allow == "/" || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+impl<S> Service<S> for Maintenance {
+    type Response = MaintenanceService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(MaintenanceService {
+            service,
+            maintenance: self.clone(),
+        })
+    }
+}
+
+pub struct MaintenanceService<S> {
+    service: S,
+    maintenance: Maintenance,
+}
+
+pub type MaintenanceServiceError<E> = PipelineE<Throttled, E>;
+
+impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for MaintenanceService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = MaintenanceServiceError<Err>;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        self.maintenance
+            .check(ctx.req().uri().path())
+            .map_err(MaintenanceServiceError::First)?;
+        self.service.call(ctx).await.map_err(MaintenanceServiceError::Second)
+    }
+}
+
+impl<S> ReadyService for MaintenanceService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{header::RETRY_AFTER, Request, RequestExt, StatusCode},
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn maintenance() {
+        let maintenance = Maintenance::new()
+            .allow("/health/")
+            .set_retry_after(Duration::from_secs(30));
+        let handle = maintenance.handle();
+
+        let service = App::new()
+            .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+            .at("/health", handler_service(|_: &WebContext<'_>| async { "ok" }))
+            .at("/health/db", handler_service(|_: &WebContext<'_>| async { "ok" }))
+            .at("/healthz", handler_service(|_: &WebContext<'_>| async { "ok" }))
+            .enclosed(maintenance)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |path: &str| {
+            let req = Request::builder().uri(path).body(RequestExt::default()).unwrap();
+            service.call(req).now_or_panic().unwrap()
+        };
+
+        assert_eq!(call("/").status(), StatusCode::OK);
+
+        handle.enable();
+        assert!(handle.is_enabled());
+
+        let res = call("/");
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers().get(RETRY_AFTER).unwrap(), "30");
+
+        assert_eq!(call("/health").status(), StatusCode::OK);
+        assert_eq!(call("/health/db").status(), StatusCode::OK);
+        assert_eq!(call("/healthz").status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        handle.disable();
+        assert_eq!(call("/").status(), StatusCode::OK);
+    }
+}
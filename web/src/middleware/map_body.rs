@@ -0,0 +1,239 @@
+use core::{cell::RefCell, convert::Infallible};
+
+use crate::{
+    context::WebContext,
+    dev::service::{ready::ReadyService, Service},
+    http::{Request, WebResponse},
+};
+
+/// A middleware for transforming request and response body streams.
+///
+/// The request body is handed to the request transformer before reaching the enclosed service and
+/// the response body produced by the enclosed service is handed to the response transformer.
+/// A transformer is any `Fn(B) -> B2` closure. Side that is not set stays untouched.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{
+/// #   body::{RequestBody, ResponseBody},
+/// #   handler::handler_service,
+/// #   middleware::map_body::MapBody,
+/// #   WebContext,
+/// #   App,
+/// # };
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///     .enclosed(
+///         MapBody::new()
+///             // wrap request body with user type for auditing, decryption etc.
+///             .request(|body: RequestBody| body)
+///             // wrap response body with user type for checksumming, encryption etc.
+///             .response(|body: ResponseBody| body),
+///     );
+/// ```
+#[derive(Clone)]
+pub struct MapBody<FReq = Identity, FRes = Identity> {
+    req: FReq,
+    res: FRes,
+}
+
+impl Default for MapBody {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MapBody {
+    /// Construct a middleware that pass through both request and response body.
+    pub const fn new() -> Self {
+        Self {
+            req: Identity,
+            res: Identity,
+        }
+    }
+}
+
+impl<FReq, FRes> MapBody<FReq, FRes> {
+    /// Set transformer for request body.
+    pub fn request<F>(self, func: F) -> MapBody<F, FRes> {
+        MapBody {
+            req: func,
+            res: self.res,
+        }
+    }
+
+    /// Set transformer for response body.
+    pub fn response<F>(self, func: F) -> MapBody<FReq, F> {
+        MapBody {
+            req: self.req,
+            res: func,
+        }
+    }
+}
+
+/// Trait for transforming a body type to another.
+///
+/// Implemented for all `Fn(B) -> B2` types and [Identity].
+pub trait BodyMap<B> {
+    type Output;
+
+    fn map(&self, body: B) -> Self::Output;
+}
+
+impl<F, B, B2> BodyMap<B> for F
+where
+    F: Fn(B) -> B2,
+{
+    type Output = B2;
+
+    #[inline]
+    fn map(&self, body: B) -> Self::Output {
+        self(body)
+    }
+}
+
+/// A [BodyMap] that return the body as is.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Identity;
+
+impl<B> BodyMap<B> for Identity {
+    type Output = B;
+
+    #[inline]
+    fn map(&self, body: B) -> Self::Output {
+        body
+    }
+}
+
+impl<S, FReq, FRes> Service<S> for MapBody<FReq, FRes>
+where
+    FReq: Clone,
+    FRes: Clone,
+{
+    type Response = MapBodyService<S, FReq, FRes>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(MapBodyService {
+            service,
+            req: self.req.clone(),
+            res: self.res.clone(),
+        })
+    }
+}
+
+pub struct MapBodyService<S, FReq, FRes> {
+    service: S,
+    req: FReq,
+    res: FRes,
+}
+
+impl<'r, S, C, B, ResB, FReq, FRes, Err> Service<WebContext<'r, C, B>> for MapBodyService<S, FReq, FRes>
+where
+    B: Default,
+    FReq: BodyMap<B>,
+    FRes: BodyMap<ResB>,
+    S: for<'r2> Service<WebContext<'r2, C, FReq::Output>, Response = WebResponse<ResB>, Error = Err>,
+{
+    type Response = WebResponse<FRes::Output>;
+    type Error = Err;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let (parts, ext) = ctx.take_request().into_parts();
+        let WebContext { ctx, res_headers, .. } = ctx;
+        let (ext, body) = ext.replace_body(());
+        let mut body = RefCell::new(self.req.map(body));
+        let mut req = Request::from_parts(parts, ext);
+
+        let ctx = WebContext::new(&mut req, &mut body, ctx, res_headers);
+
+        let res = self.service.call(ctx).await?;
+        Ok(res.map(|body| self.res.map(body)))
+    }
+}
+
+impl<S, FReq, FRes> ReadyService for MapBodyService<S, FReq, FRes>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{
+        pin::Pin,
+        task::{ready, Context, Poll},
+    };
+
+    use futures_core::stream::Stream;
+    use pin_project_lite::pin_project;
+    use xitca_http::body::Once;
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::{BodyStream, ResponseBody},
+        bytes::Bytes,
+        handler::handler_service,
+        http::WebRequest,
+        test::collect_body,
+        App,
+    };
+
+    use super::*;
+
+    pin_project! {
+        #[derive(Default)]
+        struct Upper<B> {
+            #[pin]
+            body: B
+        }
+    }
+
+    impl<B> Stream for Upper<B>
+    where
+        B: BodyStream,
+    {
+        type Item = Result<Bytes, B::Error>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let res = ready!(self.project().body.poll_next(cx));
+            Poll::Ready(res.map(|res| res.map(|chunk| Bytes::from(chunk.as_ref().to_ascii_uppercase()))))
+        }
+    }
+
+    async fn handler(vec: Vec<u8>) -> Vec<u8> {
+        assert_eq!(vec, b"HELLO");
+        [vec.as_slice(), b",world!"].concat()
+    }
+
+    #[test]
+    fn map_request_and_response() {
+        let req =
+            <WebRequest as Default>::default().map(|ext| ext.map_body(|_| Once::new(Bytes::from_static(b"hello"))));
+
+        let res = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(
+                MapBody::new()
+                    .request(|body: Once<Bytes>| Upper { body })
+                    .response(|body: ResponseBody| Upper { body }),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(req)
+            .now_or_panic()
+            .ok()
+            .unwrap();
+
+        let body = collect_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, b"HELLO,WORLD!");
+    }
+}
@@ -1,18 +1,49 @@
 //! middleware types.
 
-#[cfg(any(feature = "compress-br", feature = "compress-gz", feature = "compress-de"))]
+#[cfg(any(
+    feature = "compress-br",
+    feature = "compress-gz",
+    feature = "compress-de",
+    feature = "compress-zs"
+))]
 pub mod compress;
-#[cfg(any(feature = "compress-br", feature = "compress-gz", feature = "compress-de"))]
+#[cfg(any(
+    feature = "compress-br",
+    feature = "compress-gz",
+    feature = "compress-de",
+    feature = "compress-zs"
+))]
 pub mod decompress;
 #[cfg(feature = "tower-http-compat")]
 pub mod tower_http_compat;
+#[cfg(feature = "checksum")]
+pub mod checksum;
+#[cfg(feature = "batch")]
+pub mod batch;
 
+pub mod auth;
+pub mod cache;
+pub mod circuit_breaker;
+pub mod client_limit;
+pub mod content_type;
+pub mod drain;
+pub mod dump;
 pub mod eraser;
+pub mod error_handler;
+pub mod etag;
+pub mod host_guard;
+pub mod ip_filter;
 pub mod limit;
+pub mod logger;
+pub mod maintenance;
+pub mod map_body;
+pub mod replay;
 pub mod sync;
+pub mod tenant;
+pub mod trace_context;
 
-pub use xitca_http::util::middleware::{Extension, Logger};
-pub use xitca_service::middleware::UncheckedReady;
+pub use xitca_http::util::middleware::{Extension, FramingAudit, Logger};
+pub use xitca_service::middleware::{ReadyGate, UncheckedReady};
 
 #[cfg(test)]
 mod test {
@@ -0,0 +1,270 @@
+//! request body buffering middleware.
+
+use core::{convert::Infallible, fmt, future::poll_fn, pin::pin};
+
+use std::error;
+
+use crate::{
+    body::BodyStream,
+    bytes::{Bytes, BytesMut},
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    handler::{ExtractError, FromRequest, Responder},
+    http::{
+        const_header_value::TEXT_UTF8,
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+        StatusCode, WebResponse,
+    },
+};
+
+/// Default max size of buffered request body in bytes.
+pub const DEFAULT_LIMIT: usize = 1024 * 1024;
+
+/// A middleware eagerly buffering request body so it can be consumed more than once.
+///
+/// Request body is collected into memory up to a limit before inner service is called. Buffered
+/// body is put back as the request body and a [ReplayBody] sharing the same buffer is inserted
+/// into request [Extensions](crate::http::Extensions). Downstream middlewares (signature
+/// verification, audit logging, etc) can inspect the body through [ReplayBody] or consume it and
+/// put a fresh copy back with [ReplayBody::replay] so handler can still extract it.
+///
+/// Request body larger than limit is rejected with [ReplayError::TooLarge] error(`413 Payload
+/// Too Large`).
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::replay::{Replay, ReplayBody}, App, WebContext};
+/// // buffered body can be extracted as many times as needed.
+/// async fn handler(raw: ReplayBody, body: String, _: &WebContext<'_>) -> String {
+///     assert_eq!(raw.bytes(), body.as_bytes());
+///     body
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     .enclosed(Replay::new(64 * 1024));
+/// ```
+#[derive(Clone, Copy)]
+pub struct Replay {
+    limit: usize,
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self::new(DEFAULT_LIMIT)
+    }
+}
+
+impl Replay {
+    /// Construct a middleware buffering request body up to given limit in bytes.
+    pub fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+}
+
+impl<S> Service<S> for Replay {
+    type Response = ReplayService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(ReplayService {
+            service,
+            limit: self.limit,
+        })
+    }
+}
+
+pub struct ReplayService<S> {
+    service: S,
+    limit: usize,
+}
+
+pub type ReplayServiceError<E> = PipelineE<ReplayError, E>;
+
+impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for ReplayService<S>
+where
+    B: BodyStream + Default + From<Bytes>,
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = ReplayServiceError<Err>;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let len = ctx
+            .req()
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+
+        if len.is_some_and(|len| len > self.limit) {
+            return Err(ReplayServiceError::First(ReplayError::TooLarge(self.limit)));
+        }
+
+        let mut body = pin!(ctx.take_body_mut());
+        let mut buf = BytesMut::with_capacity(len.unwrap_or(0));
+
+        while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            let chunk = chunk.map_err(|_| ReplayServiceError::First(ReplayError::Body))?;
+            buf.extend_from_slice(chunk.as_ref());
+            if buf.len() > self.limit {
+                return Err(ReplayServiceError::First(ReplayError::TooLarge(self.limit)));
+            }
+        }
+
+        let replay = ReplayBody(buf.freeze());
+        *ctx.body_get_mut() = replay.replay();
+        ctx.req_mut().extensions_mut().insert(replay);
+
+        self.service.call(ctx).await.map_err(ReplayServiceError::Second)
+    }
+}
+
+impl<S> ReadyService for ReplayService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// Request body buffered by [Replay] middleware. Cloning it is cheap as the buffer is shared.
+///
+/// It's also a type extractor that can be used together with other body extractors. Extracting
+/// it without [Replay] middleware results in [ExtractError::ExtensionNotFound] error.
+#[derive(Clone, Debug)]
+pub struct ReplayBody(Bytes);
+
+impl ReplayBody {
+    /// Buffered bytes of request body.
+    #[inline]
+    pub fn bytes(&self) -> &Bytes {
+        &self.0
+    }
+
+    /// Construct a fresh request body from the start of buffer. Useful for putting request body
+    /// back after consuming it in middleware.
+    #[inline]
+    pub fn replay<B>(&self) -> B
+    where
+        B: From<Bytes>,
+    {
+        B::from(self.0.clone())
+    }
+}
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for ReplayBody
+where
+    B: BodyStream,
+{
+    type Type<'b> = ReplayBody;
+    type Error = ExtractError<B::Error>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        ctx.req()
+            .extensions()
+            .get::<ReplayBody>()
+            .cloned()
+            .ok_or(ExtractError::ExtensionNotFound)
+    }
+}
+
+/// Error type of [Replay] middleware.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ReplayError {
+    /// Request body is larger than limit in bytes.
+    TooLarge(usize),
+    /// Failed to read request body.
+    Body,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::TooLarge(limit) => write!(f, "Request body size reached limit: {limit} bytes."),
+            Self::Body => f.write_str("Failed to read request body."),
+        }
+    }
+}
+
+impl error::Error for ReplayError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for ReplayError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, ctx: WebContext<'r, C, B>) -> Self::Output {
+        let status = match self {
+            Self::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Body => StatusCode::BAD_REQUEST,
+        };
+        let mut res = ctx.into_response(format!("{self}"));
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        *res.status_mut() = status;
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        dev::service::ServiceExt,
+        handler::handler_service,
+        http::{Request, RequestExt},
+        test::{collect_body, collect_string_body},
+        App,
+    };
+
+    use super::*;
+
+    async fn handler(raw: ReplayBody, body: String) -> String {
+        assert_eq!(raw.bytes(), body.as_bytes());
+        body
+    }
+
+    // consume request body in middleware and put it back for handler.
+    async fn audit<S, C, Res, Err>(s: &S, mut ctx: WebContext<'_, C>) -> Result<Res, Err>
+    where
+        S: for<'r> Service<WebContext<'r, C>, Response = Res, Error = Err>,
+    {
+        let body = collect_body(ctx.take_body_mut()).await.unwrap();
+        assert_eq!(body, b"hello,world!");
+
+        let replay = ctx.req().extensions().get::<ReplayBody>().unwrap().clone();
+        *ctx.body_get_mut() = replay.replay();
+
+        s.call(ctx).await
+    }
+
+    fn req(body: &'static str) -> Request<RequestExt<RequestBody>> {
+        let ext = RequestExt::default().map_body(|_: ()| RequestBody::from(Bytes::from_static(body.as_bytes())));
+        Request::new(ext)
+    }
+
+    #[test]
+    fn replay() {
+        let service = App::new()
+            .at("/", handler_service(handler).enclosed_fn(audit))
+            .enclosed(Replay::new(12))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req("hello,world!")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, "hello,world!");
+
+        let res = service.call(req("hello,world!!")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
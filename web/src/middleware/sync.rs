@@ -1,39 +1,81 @@
-use core::convert::Infallible;
+use core::{
+    cell::RefCell,
+    convert::Infallible,
+    future::poll_fn,
+    pin::{pin, Pin},
+};
 
 use std::sync::mpsc::{sync_channel, Receiver};
 
+use futures_core::stream::Stream;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
 
 use crate::{
+    body::{BodyStream, Once, ResponseBody},
+    bytes::{Bytes, BytesMut},
     context::WebContext,
     dev::service::{ready::ReadyService, Service},
-    http::{Request, RequestExt, Response, WebResponse},
+    http::{Request, Response, StatusCode, WebRequest, WebResponse},
 };
 
 /// experimental type for sync function as middleware.
-pub struct SyncMiddleware<F>(F);
+///
+/// request and response body are buffered in memory so the sync function can inspect and modify them.
+/// See [SyncMiddleware::set_body_max_size] for limiting the size of buffered body.
+pub struct SyncMiddleware<F> {
+    func: F,
+    body_limit: usize,
+}
 
 impl<F> SyncMiddleware<F> {
     /// construct a new middleware with given sync function.
-    /// the function must be actively calling [Next::call] and finish it to drive inner services to completion.
+    /// the function can call [Next::call] to drive inner services to completion for as many times as it needs, or
+    /// produce a response on it's own without calling it at all to short circuit the request.
     /// panic in sync function middleware would result in a panic at task level and it's client connection would
     /// be terminated immediately.
     pub fn new<E>(func: F) -> Self
     where
-        F: Fn(Request<RequestExt<()>>, &mut Next<E>) -> Result<Response<()>, E> + Send + Sync + 'static,
+        F: Fn(WebRequest<Bytes>, &mut Next<E>) -> Result<WebResponse<Bytes>, E> + Send + Sync + 'static,
         E: Send + 'static,
     {
-        Self(func)
+        Self {
+            func,
+            body_limit: 1024 * 1024,
+        }
+    }
+
+    /// Set max size in byte unit the buffered request and response body can be. Default to 1 MiB.
+    ///
+    /// request body goes beyond the limit would be responded with `413 Payload Too Large`. response
+    /// body goes beyond the limit would be replaced with an empty `500 Internal Server Error` response
+    /// before passing to the sync function.
+    pub fn set_body_max_size(mut self, size: usize) -> Self {
+        self.body_limit = size;
+        self
+    }
+}
+
+impl<F> Clone for SyncMiddleware<F>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            func: self.func.clone(),
+            body_limit: self.body_limit,
+        }
     }
 }
 
 pub struct Next<E> {
-    tx: UnboundedSender<Request<RequestExt<()>>>,
-    rx: Receiver<Result<Response<()>, E>>,
+    tx: UnboundedSender<WebRequest<Bytes>>,
+    rx: Receiver<Result<WebResponse<Bytes>, E>>,
 }
 
 impl<E> Next<E> {
-    pub fn call(&mut self, req: Request<RequestExt<()>>) -> Result<Response<()>, E> {
+    /// call inner services with given request and wait for it's response. response body is buffered in memory
+    /// and inner service's error is returned as is.
+    pub fn call(&mut self, req: WebRequest<Bytes>) -> Result<WebResponse<Bytes>, E> {
         self.tx.send(req).unwrap();
         self.rx.recv().unwrap()
     }
@@ -48,7 +90,8 @@ where
 
     async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
         Ok(SyncService {
-            func: self.0.clone(),
+            func: self.func.clone(),
+            body_limit: self.body_limit,
             service,
         })
     }
@@ -56,21 +99,37 @@ where
 
 pub struct SyncService<F, S> {
     func: F,
+    body_limit: usize,
     service: S,
 }
 
-impl<'r, F, S, C, B, ResB, Err> Service<WebContext<'r, C, B>> for SyncService<F, S>
+impl<'r, F, S, C, B, ResB, BE, Err> Service<WebContext<'r, C, B>> for SyncService<F, S>
 where
-    F: Fn(Request<RequestExt<()>>, &mut Next<Err>) -> Result<Response<()>, Err> + Send + Clone + 'static,
-    S: for<'r2> Service<WebContext<'r, C, B>, Response = WebResponse<ResB>, Error = Err>,
+    F: Fn(WebRequest<Bytes>, &mut Next<Err>) -> Result<WebResponse<Bytes>, Err> + Send + Clone + 'static,
+    B: BodyStream + Default,
+    S: for<'r2> Service<WebContext<'r2, C, Once<Bytes>>, Response = WebResponse<ResB>, Error = Err>,
+    ResB: Stream<Item = Result<Bytes, BE>>,
     Err: Send + 'static,
 {
-    type Response = WebResponse<ResB>;
+    type Response = WebResponse;
     type Error = Err;
 
     async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let (parts, ext) = ctx.take_request().into_parts();
+        let (ext, body) = ext.replace_body(());
+
+        let body = match collect(body, self.body_limit).await {
+            Ok(body) => body,
+            Err(status) => {
+                let mut res = ctx.into_response(Bytes::new());
+                *res.status_mut() = status;
+                return Ok(res);
+            }
+        };
+
+        let req = Request::from_parts(parts, ext.map_body(|_| body));
+
         let func = self.func.clone();
-        let req = std::mem::take(ctx.req_mut());
 
         let (tx, mut rx) = unbounded_channel();
         let (tx2, rx2) = sync_channel(1);
@@ -78,33 +137,64 @@ where
         let mut next = Next { tx, rx: rx2 };
         let handle = tokio::task::spawn_blocking(move || func(req, &mut next));
 
-        *ctx.req_mut() = match rx.recv().await {
-            Some(req) => req,
-            None => {
-                // tx is dropped which means spawned thread exited already. join it and panic if necessary.
-                match handle.await.unwrap() {
-                    Ok(_) => todo!("there is no support for body type yet"),
-                    Err(e) => return Err(e),
+        // serve every call of Next until it's dropped. sync function exits(or panics) at that point.
+        while let Some(req) = rx.recv().await {
+            let (parts, ext) = req.into_parts();
+            let (ext, body) = ext.replace_body(());
+            let mut req = Request::from_parts(parts, ext);
+            let mut body = RefCell::new(Once::new(body));
+
+            let res = match self
+                .service
+                .call(WebContext::new(&mut req, &mut body, ctx.ctx, ctx.res_headers))
+                .await
+            {
+                Ok(res) => {
+                    let (parts, body) = res.into_parts();
+                    match collect(body, self.body_limit).await {
+                        Ok(body) => Ok(Response::from_parts(parts, body)),
+                        Err(_) => {
+                            let mut res = Response::new(Bytes::new());
+                            *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            Ok(res)
+                        }
+                    }
                 }
-            }
-        };
+                Err(e) => Err(e),
+            };
 
-        match self.service.call(ctx).await {
-            Ok(res) => {
-                let (parts, body) = res.into_parts();
-                tx2.send(Ok(Response::from_parts(parts, ()))).unwrap();
-                let res = handle.await.unwrap()?;
-                Ok(res.map(|_| body))
-            }
-            Err(e) => {
-                tx2.send(Err(e)).unwrap();
-                let res = handle.await.unwrap()?;
-                Ok(res.map(|_| todo!("there is no support for body type yet")))
-            }
+            // sync function is blocked on receiving response and can not be gone.
+            tx2.send(res).unwrap();
         }
+
+        // tx is dropped which means spawned thread exited already. join it and panic if necessary.
+        handle.await.unwrap().map(|res| res.map(ResponseBody::bytes))
     }
 }
 
+// collect body stream to bytes with size limit. error is converted to according response status code.
+async fn collect<B, T, E>(body: B, limit: usize) -> Result<Bytes, StatusCode>
+where
+    B: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]>,
+{
+    let mut body = pin!(body);
+    let mut buf = BytesMut::new();
+
+    while let Some(chunk) = poll_fn(|cx| Pin::as_mut(&mut body).poll_next(cx)).await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        let chunk = chunk.as_ref();
+
+        if buf.len() + chunk.len() > limit {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        buf.extend_from_slice(chunk);
+    }
+
+    Ok(buf.freeze())
+}
+
 impl<F, S> ReadyService for SyncService<F, S>
 where
     S: ReadyService,
@@ -119,32 +209,83 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::{bytes::Bytes, dev::service::fn_service, http::StatusCode, App};
+    use crate::{
+        dev::service::fn_service,
+        http::{header::CONTENT_LENGTH, RequestExt},
+        App,
+    };
 
     use super::*;
 
-    async fn handler(req: WebContext<'_, &'static str>) -> Result<WebResponse, Infallible> {
+    async fn handler(mut req: WebContext<'_, &'static str, Once<Bytes>>) -> Result<WebResponse, Infallible> {
         assert_eq!(*req.state(), "996");
-        Ok(req.into_response(Bytes::new()))
+        let body = collect(req.take_body_mut(), usize::MAX).await.unwrap();
+        Ok(req.into_response(body))
+    }
+
+    fn middleware<E>(mut req: WebRequest<Bytes>, next: &mut Next<E>) -> Result<WebResponse<Bytes>, E> {
+        match req.uri().path() {
+            "/early" => Ok(Response::new(Bytes::from_static(b"early"))),
+            "/twice" => {
+                let mut call = |body: &'static [u8]| {
+                    let ext = RequestExt::default().map_body(|_: ()| Bytes::from_static(body));
+                    next.call(Request::builder().uri("/").body(ext).unwrap())
+                };
+                let first = call(b"996")?;
+                let second = call(b"251")?;
+                let mut body = BytesMut::from(first.body().as_ref());
+                body.extend_from_slice(second.body());
+                Ok(first.map(|_| body.freeze()))
+            }
+            _ => {
+                assert_eq!(req.body().body(), "996");
+                *req.body_mut().body_mut() = Bytes::from_static(b"251");
+                next.call(req).map(|res| {
+                    res.map(|body| {
+                        assert_eq!(body, "251");
+                        Bytes::from_static(b"007")
+                    })
+                })
+            }
+        }
+    }
+
+    fn req(path: &str, body: &'static str) -> WebRequest<Once<Bytes>> {
+        let mut req = Request::builder().uri(path).body(Default::default()).unwrap();
+        req.headers_mut().insert(CONTENT_LENGTH, body.len().into());
+        req.map(|ext: RequestExt<()>| ext.map_body(|_| Once::new(Bytes::from_static(body.as_bytes()))))
     }
 
-    fn middleware<E>(req: Request<RequestExt<()>>, next: &mut Next<E>) -> Result<Response<()>, E> {
-        next.call(req)
+    async fn body<B, E>(res: WebResponse<B>) -> Bytes
+    where
+        B: Stream<Item = Result<Bytes, E>>,
+    {
+        collect(res.into_body(), usize::MAX).await.unwrap()
     }
 
     #[tokio::test]
     async fn sync_middleware() {
-        let res = App::with_state("996")
+        let service = App::with_state("996")
             .at("/", fn_service(handler))
-            .enclosed(SyncMiddleware::new(middleware))
+            .enclosed(SyncMiddleware::new(middleware).set_body_max_size(3))
             .finish()
             .call(())
             .await
-            .unwrap()
-            .call(Request::default())
-            .await
             .unwrap();
 
+        let res = service.call(req("/", "996")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(body(res).await, "007");
+
+        let res = service.call(req("/early", "996")).await.unwrap();
         assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(body(res).await, "early");
+
+        let res = service.call(req("/twice", "996")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(body(res).await, "996251");
+
+        let res = service.call(req("/", "9960")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
     }
 }
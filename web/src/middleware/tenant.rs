@@ -0,0 +1,342 @@
+//! multi-tenant state resolution.
+
+use core::{convert::Infallible, fmt, ops::Deref};
+
+use std::{collections::HashMap, error, sync::Arc};
+
+use crate::{
+    body::BodyStream,
+    context::WebContext,
+    dev::service::{pipeline::PipelineE, ready::ReadyService, Service},
+    handler::{ExtractError, FromRequest, Responder},
+    http::{
+        const_header_value::TEXT_UTF8,
+        header::{CONTENT_TYPE, HOST},
+        StatusCode, WebResponse,
+    },
+};
+
+/// A middleware resolving per tenant state from a registry for every request.
+///
+/// Tenant is identified by either host name of request or the first segment of request path.
+/// Resolved state can be extracted by handlers with [TenantState] extractor. Request can not be
+/// resolved to any registered tenant is responded with `404 Not Found` unless a fallback state
+/// is set with [Tenants::fallback].
+///
+/// Path prefix is not stripped from request path and routes are expected to include it.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::handler_service,
+/// #   middleware::tenant::{TenantState, Tenants},
+/// #   route::get,
+/// #   App, WebContext
+/// # };
+/// struct Db {
+///     name: &'static str,
+/// }
+///
+/// async fn index(db: TenantState<Db>, _: &WebContext<'_>) -> &'static str {
+///     db.name
+/// }
+///
+/// App::new()
+///     .at("/", get(handler_service(index)))
+///     .enclosed(
+///         Tenants::by_host()
+///             .insert("acme.example.com", Db { name: "acme" })
+///             .insert("globex.example.com", Db { name: "globex" }),
+///     );
+/// ```
+pub struct Tenants<T> {
+    resolve: Resolve,
+    registry: HashMap<Box<str>, Arc<T>>,
+    fallback: Option<Arc<T>>,
+}
+
+#[derive(Clone, Copy)]
+enum Resolve {
+    Host,
+    PathPrefix,
+}
+
+impl<T> Clone for Tenants<T> {
+    fn clone(&self) -> Self {
+        Self {
+            resolve: self.resolve,
+            registry: self.registry.clone(),
+            fallback: self.fallback.clone(),
+        }
+    }
+}
+
+impl<T> Tenants<T> {
+    /// Construct a registry identifying tenant by host name of request. Port is ignored and host
+    /// names are matched case insensitively.
+    pub fn by_host() -> Self {
+        Self::new(Resolve::Host)
+    }
+
+    /// Construct a registry identifying tenant by the first segment of request path. For example
+    /// request to `/acme/orders` is resolved to tenant registered as `acme`.
+    pub fn by_path_prefix() -> Self {
+        Self::new(Resolve::PathPrefix)
+    }
+
+    fn new(resolve: Resolve) -> Self {
+        Self {
+            resolve,
+            registry: HashMap::new(),
+            fallback: None,
+        }
+    }
+
+    /// Register state of a tenant with given host name or path segment.
+    pub fn insert(mut self, key: impl Into<String>, state: T) -> Self {
+        let mut key = key.into();
+        if let Resolve::Host = self.resolve {
+            key.make_ascii_lowercase();
+        }
+        self.registry.insert(key.into_boxed_str(), Arc::new(state));
+        self
+    }
+
+    /// Set state for request not resolved to any registered tenant.
+    pub fn fallback(mut self, state: T) -> Self {
+        self.fallback = Some(Arc::new(state));
+        self
+    }
+
+    fn resolve<C, B>(&self, ctx: &WebContext<'_, C, B>) -> Option<&Arc<T>> {
+        let req = ctx.req();
+        let state = match self.resolve {
+            Resolve::Host => req
+                .headers()
+                .get(HOST)
+                .and_then(|host| host.to_str().ok())
+                .or_else(|| req.uri().host())
+                .and_then(|host| {
+                    // strip port and leave ipv6 address in brackets intact.
+                    let host = match host.rsplit_once(':') {
+                        Some((host, port)) if !port.contains(']') => host,
+                        _ => host,
+                    };
+                    match host.bytes().any(|b| b.is_ascii_uppercase()) {
+                        true => self.registry.get(host.to_ascii_lowercase().as_str()),
+                        false => self.registry.get(host),
+                    }
+                }),
+            Resolve::PathPrefix => req
+                .uri()
+                .path()
+                .trim_start_matches('/')
+                .split('/')
+                .next()
+                .and_then(|segment| self.registry.get(segment)),
+        };
+        state.or(self.fallback.as_ref())
+    }
+}
+
+impl<S, T> Service<S> for Tenants<T> {
+    type Response = TenantsService<S, T>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(TenantsService {
+            service,
+            tenants: self.clone(),
+        })
+    }
+}
+
+pub struct TenantsService<S, T> {
+    service: S,
+    tenants: Tenants<T>,
+}
+
+pub type TenantsServiceError<E> = PipelineE<TenantError, E>;
+
+impl<'r, S, C, B, T, Res, Err> Service<WebContext<'r, C, B>> for TenantsService<S, T>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+    T: Send + Sync + 'static,
+{
+    type Response = Res;
+    type Error = TenantsServiceError<Err>;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let state = self
+            .tenants
+            .resolve(&ctx)
+            .cloned()
+            .ok_or(TenantsServiceError::First(TenantError::NotFound))?;
+        ctx.req_mut().extensions_mut().insert(TenantState(state));
+        self.service.call(ctx).await.map_err(TenantsServiceError::Second)
+    }
+}
+
+impl<S, T> ReadyService for TenantsService<S, T>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// Extract state of tenant resolved by [Tenants] middleware.
+pub struct TenantState<T>(pub Arc<T>);
+
+impl<T> Clone for TenantState<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TenantState<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TenantState({:?})", self.0)
+    }
+}
+
+impl<T> Deref for TenantState<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, 'r, C, B, T> FromRequest<'a, WebContext<'r, C, B>> for TenantState<T>
+where
+    T: Send + Sync + 'static,
+    B: BodyStream,
+{
+    type Type<'b> = TenantState<T>;
+    type Error = ExtractError<B::Error>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        ctx.req()
+            .extensions()
+            .get::<TenantState<T>>()
+            .cloned()
+            .ok_or(ExtractError::ExtensionNotFound)
+    }
+}
+
+/// Error type of [Tenants] middleware.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TenantError {
+    /// Request can not be resolved to any registered tenant.
+    NotFound,
+}
+
+impl fmt::Display for TenantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::NotFound => f.write_str("Tenant can not be found."),
+        }
+    }
+}
+
+impl error::Error for TenantError {}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for TenantError {
+    type Output = WebResponse;
+
+    async fn respond_to(self, req: WebContext<'r, C, B>) -> Self::Output {
+        let mut res = req.into_response(format!("{self}"));
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        *res.status_mut() = StatusCode::NOT_FOUND;
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{header::HeaderValue, Request, RequestExt, WebRequest},
+        route::get,
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    async fn handler(state: TenantState<&'static str>, _: &WebContext<'_>) -> &'static str {
+        *state
+    }
+
+    fn req(uri: &'static str, host: Option<&'static str>) -> WebRequest {
+        let mut req = Request::builder().uri(uri).body(RequestExt::default()).unwrap();
+        if let Some(host) = host {
+            req.headers_mut().insert(HOST, HeaderValue::from_static(host));
+        }
+        req
+    }
+
+    #[test]
+    fn by_host() {
+        let service = App::new()
+            .at("/", get(handler_service(handler)))
+            .enclosed(
+                Tenants::by_host()
+                    .insert("Acme.com", "acme")
+                    .insert("globex.com", "globex"),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for (host, tenant) in [
+            ("acme.com", "acme"),
+            ("ACME.com:8080", "acme"),
+            ("globex.com", "globex"),
+        ] {
+            let body = service.call(req("/", Some(host))).now_or_panic().unwrap().into_body();
+            assert_eq!(collect_string_body(body).now_or_panic().unwrap(), tenant);
+        }
+
+        let res = service.call(req("/", Some("initech.com"))).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let res = service.call(req("/", None)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn by_path_prefix() {
+        let service = App::new()
+            .at("/:tenant/index", get(handler_service(handler)))
+            .enclosed(Tenants::by_path_prefix().insert("acme", "acme").fallback("default"))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let body = service
+            .call(req("/acme/index", None))
+            .now_or_panic()
+            .unwrap()
+            .into_body();
+        assert_eq!(collect_string_body(body).now_or_panic().unwrap(), "acme");
+
+        let body = service
+            .call(req("/globex/index", None))
+            .now_or_panic()
+            .unwrap()
+            .into_body();
+        assert_eq!(collect_string_body(body).now_or_panic().unwrap(), "default");
+    }
+}
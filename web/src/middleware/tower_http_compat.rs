@@ -125,9 +125,12 @@ where
 
             let mut req = Request::from_parts(parts, ext);
             let mut body = RefCell::new(body);
-            let req = WebContext::new(&mut req, &mut body, &ctx);
+            let res_headers = RefCell::default();
+            let req = WebContext::new(&mut req, &mut body, &ctx, &res_headers);
 
-            service.call(req).await.map(|res| res.map(CompatBody::new))
+            let mut res = service.call(req).await?;
+            res_headers.into_inner().merge(res.headers_mut());
+            Ok(res.map(CompatBody::new))
         })
     }
 }
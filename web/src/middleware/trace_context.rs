@@ -0,0 +1,331 @@
+//! W3C trace context propagation.
+
+use core::{convert::Infallible, fmt};
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+use tracing::{info_span, Instrument};
+
+use crate::{
+    context::WebContext,
+    dev::service::{ready::ReadyService, Service},
+    http::header::{HeaderMap, HeaderName, HeaderValue},
+};
+
+/// `traceparent` header name.
+pub const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+/// `tracestate` header name.
+pub const TRACESTATE: HeaderName = HeaderName::from_static("tracestate");
+
+const FLAG_SAMPLED: u8 = 0x01;
+
+/// Trace context of a request following [W3C Trace Context](https://www.w3.org/TR/trace-context/).
+///
+/// Inserted into request extensions by [TracePropagation] middleware and can be extracted with
+/// [ExtensionRef](crate::handler::extension::ExtensionRef).
+#[derive(Clone)]
+pub struct TraceContext {
+    trace_id: u128,
+    span_id: u64,
+    parent_id: Option<u64>,
+    flags: u8,
+    state: Option<HeaderValue>,
+}
+
+impl TraceContext {
+    /// Start a new sampled trace without remote parent.
+    pub fn new() -> Self {
+        Self {
+            trace_id: (u128::from(random_id()) << 64) | u128::from(random_id()),
+            span_id: random_id(),
+            parent_id: None,
+            flags: FLAG_SAMPLED,
+            state: None,
+        }
+    }
+
+    /// Continue trace from `traceparent` and `tracestate` headers with a new span id.
+    ///
+    /// Returns `None` when `traceparent` header is missing or malformed. `tracestate` is ignored
+    /// in that case as required by the specification.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let (trace_id, parent_id, flags) = parse_traceparent(headers.get(TRACEPARENT)?.as_bytes())?;
+        Some(Self {
+            trace_id,
+            span_id: random_id(),
+            parent_id: Some(parent_id),
+            flags,
+            state: headers.get(TRACESTATE).cloned(),
+        })
+    }
+
+    /// Id of the whole trace.
+    #[inline]
+    pub fn trace_id(&self) -> u128 {
+        self.trace_id
+    }
+
+    /// Id of the span of current request.
+    #[inline]
+    pub fn span_id(&self) -> u64 {
+        self.span_id
+    }
+
+    /// Id of the remote parent span. `None` when trace is started by current request.
+    #[inline]
+    pub fn parent_id(&self) -> Option<u64> {
+        self.parent_id
+    }
+
+    /// Check if caller has recorded trace data.
+    #[inline]
+    pub fn sampled(&self) -> bool {
+        self.flags & FLAG_SAMPLED != 0
+    }
+
+    /// Vendor specific trace state received from caller.
+    #[inline]
+    pub fn trace_state(&self) -> Option<&HeaderValue> {
+        self.state.as_ref()
+    }
+
+    /// Format `traceparent` header value with span id of current request as parent id.
+    pub fn traceparent(&self) -> HeaderValue {
+        let value = format!("00-{:032x}-{:016x}-{:02x}", self.trace_id, self.span_id, self.flags);
+        HeaderValue::try_from(value).expect("traceparent must be valid header value")
+    }
+
+    /// Inject `traceparent` and `tracestate` headers into headers of an outgoing request so the
+    /// downstream service continues current trace.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_web::{handler::extension::ExtensionRef, http::header::HeaderMap, middleware::trace_context::TraceContext};
+    /// async fn handler(ExtensionRef(ctx): ExtensionRef<'_, TraceContext>) {
+    ///     // with xitca-client this would be the headers of request obtained from `Request::headers_mut`.
+    ///     let mut headers = HeaderMap::new();
+    ///     ctx.inject(&mut headers);
+    /// }
+    /// ```
+    pub fn inject(&self, headers: &mut HeaderMap) {
+        headers.insert(TRACEPARENT, self.traceparent());
+        match self.state {
+            Some(ref state) => headers.insert(TRACESTATE, state.clone()),
+            None => headers.remove(TRACESTATE),
+        };
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceContext")
+            .field("trace_id", &format_args!("{:032x}", self.trace_id))
+            .field("span_id", &format_args!("{:016x}", self.span_id))
+            .field("parent_id", &self.parent_id.map(|id| format!("{id:016x}")))
+            .field("flags", &format_args!("{:02x}", self.flags))
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+/// A middleware propagating [W3C Trace Context](https://www.w3.org/TR/trace-context/).
+///
+/// For every request a [TraceContext] is continued from `traceparent` and `tracestate` headers or
+/// started anew when they are absent or malformed. The context is inserted into request
+/// extensions and the rest of request handling is instrumented with a `tracing` span named
+/// `request` carrying `trace_id`, `span_id` and `parent_id` fields.
+///
+/// Outgoing requests made by handlers can continue the trace with [TraceContext::inject].
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{extension::ExtensionRef, handler_service},
+/// #   middleware::trace_context::{TraceContext, TracePropagation},
+/// #   route::get,
+/// #   App, WebContext
+/// # };
+/// async fn index(ExtensionRef(ctx): ExtensionRef<'_, TraceContext>, _: &WebContext<'_>) -> String {
+///     format!("{:032x}", ctx.trace_id())
+/// }
+///
+/// App::new()
+///     .at("/", get(handler_service(index)))
+///     .enclosed(TracePropagation);
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct TracePropagation;
+
+impl<S> Service<S> for TracePropagation {
+    type Response = TracePropagationService<S>;
+    type Error = Infallible;
+
+    async fn call(&self, service: S) -> Result<Self::Response, Self::Error> {
+        Ok(TracePropagationService { service })
+    }
+}
+
+pub struct TracePropagationService<S> {
+    service: S,
+}
+
+impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for TracePropagationService<S>
+where
+    S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = Err;
+
+    async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let trace = TraceContext::from_headers(ctx.req().headers()).unwrap_or_default();
+
+        let span = info_span!(
+            "request",
+            trace_id = %format_args!("{:032x}", trace.trace_id),
+            span_id = %format_args!("{:016x}", trace.span_id),
+            parent_id = trace.parent_id.map(|id| tracing::field::display(format!("{id:016x}"))),
+        );
+
+        ctx.req_mut().extensions_mut().insert(trace);
+
+        self.service.call(ctx).instrument(span).await
+    }
+}
+
+impl<S> ReadyService for TracePropagationService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+// random non zero id. RandomState is seeded randomly and every new instance hashes differently.
+fn random_id() -> u64 {
+    loop {
+        let id = RandomState::new().build_hasher().finish();
+        if id != 0 {
+            return id;
+        }
+    }
+}
+
+// parse traceparent header value in form of {version}-{trace-id}-{parent-id}-{trace-flags}.
+fn parse_traceparent(value: &[u8]) -> Option<(u128, u64, u8)> {
+    if value.len() < 55 {
+        return None;
+    }
+
+    let (head, rest) = value.split_at(55);
+
+    let version = hex(&head[..2])? as u8;
+    match version {
+        0xff => return None,
+        // version 00 has fixed length.
+        0x00 if !rest.is_empty() => return None,
+        // future versions may append fields after a dash.
+        _ if !rest.is_empty() && rest[0] != b'-' => return None,
+        _ => {}
+    }
+
+    if head[2] != b'-' || head[35] != b'-' || head[52] != b'-' {
+        return None;
+    }
+
+    let trace_id = hex(&head[3..35])?;
+    let parent_id = hex(&head[36..52])? as u64;
+    let flags = hex(&head[53..55])? as u8;
+
+    (trace_id != 0 && parent_id != 0).then_some((trace_id, parent_id, flags))
+}
+
+// parse lower case hex digits.
+fn hex(digits: &[u8]) -> Option<u128> {
+    digits.iter().try_fold(0u128, |n, d| {
+        let d = match d {
+            b'0'..=b'9' => d - b'0',
+            b'a'..=b'f' => d - b'a' + 10,
+            _ => return None,
+        };
+        Some((n << 4) | u128::from(d))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::{extension::ExtensionRef, handler_service},
+        http::WebRequest,
+        route::get,
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn traceparent() {
+        let (trace_id, parent_id, flags) =
+            parse_traceparent(b"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        assert_eq!(trace_id, 0x4bf92f3577b34da6a3ce929d0e0e4736);
+        assert_eq!(parent_id, 0x00f067aa0ba902b7);
+        assert_eq!(flags, 1);
+
+        assert!(parse_traceparent(b"01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-future").is_some());
+        assert!(parse_traceparent(b"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-future").is_none());
+        assert!(parse_traceparent(b"ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").is_none());
+        assert!(parse_traceparent(b"00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none());
+        assert!(parse_traceparent(b"00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01").is_none());
+        assert!(parse_traceparent(b"00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01").is_none());
+        assert!(parse_traceparent(b"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7").is_none());
+    }
+
+    #[test]
+    fn propagation() {
+        async fn handler(ExtensionRef(ctx): ExtensionRef<'_, TraceContext>, _: &WebContext<'_>) -> String {
+            let mut headers = HeaderMap::new();
+            ctx.inject(&mut headers);
+            headers.get(TRACEPARENT).unwrap().to_str().unwrap().to_owned()
+        }
+
+        let service = App::new()
+            .at("/", get(handler_service(handler)))
+            .enclosed(TracePropagation)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut req = WebRequest::default();
+        req.headers_mut().insert(
+            TRACEPARENT,
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+        let body = service.call(req).now_or_panic().unwrap().into_body();
+        let value = collect_string_body(body).now_or_panic().unwrap();
+
+        // trace id and flags are continued and parent id is replaced with new span id.
+        assert!(value.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+        assert!(value.ends_with("-01"));
+        assert!(!value.contains("00f067aa0ba902b7"));
+
+        let body = service.call(WebRequest::default()).now_or_panic().unwrap().into_body();
+        let value = collect_string_body(body).now_or_panic().unwrap();
+        assert!(parse_traceparent(value.as_bytes()).is_some());
+    }
+}
@@ -25,6 +25,8 @@ pub struct HttpServer<
     service: Arc<S>,
     builder: Builder,
     config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
+    #[cfg(any(feature = "openssl", feature = "rustls"))]
+    tls_handshake_pool: Option<xitca_http::HandshakePool>,
 }
 
 impl<S> HttpServer<S>
@@ -32,10 +34,27 @@ where
     S: Send + Sync + 'static,
 {
     pub fn serve(service: S) -> Self {
+        Self::serve_with_config(service, HttpServiceConfig::default())
+    }
+}
+
+impl<S, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIMIT: usize>
+    HttpServer<S, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>
+where
+    S: Send + Sync + 'static,
+{
+    /// Serve service with given [HttpServiceConfig]. Equivalent of [HttpServer::serve] followed by
+    /// [HttpServer::config].
+    pub fn serve_with_config(
+        service: S,
+        config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
+    ) -> Self {
         Self {
             service: Arc::new(service),
             builder: Builder::new(),
-            config: HttpServiceConfig::default(),
+            config,
+            #[cfg(any(feature = "openssl", feature = "rustls"))]
+            tls_handshake_pool: None,
         }
     }
 }
@@ -75,6 +94,40 @@ where
         self
     }
 
+    /// Pin worker threads to given cpu cores.
+    ///
+    /// Worker with index `n` is pinned to core `cores[n % cores.len()]`. Pinning only works on
+    /// linux and is ignored on other platforms.
+    pub fn worker_core_affinity<I>(mut self, cores: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.builder = self.builder.worker_core_affinity(cores);
+        self
+    }
+
+    /// Set max number of concurrent connections of the server across all workers.
+    ///
+    /// When the limit is reached workers stop accepting new connections until existing ones are
+    /// closed. Default to no limit.
+    pub fn max_connections(mut self, num: usize) -> Self {
+        self.builder = self.builder.max_connections(num);
+        self
+    }
+
+    /// Set max number of concurrent connections of every worker. Default to no limit.
+    pub fn max_connections_per_worker(mut self, num: usize) -> Self {
+        self.builder = self.builder.max_connections_per_worker(num);
+        self
+    }
+
+    /// Set max number of connections the server accepts per second across all workers.
+    /// Default to no limit.
+    pub fn max_accept_rate(mut self, per_sec: u32) -> Self {
+        self.builder = self.builder.max_accept_rate(per_sec);
+        self
+    }
+
     /// Disable signal listening.
     ///
     /// `tokio::signal` is used for listening and it only functions in tokio runtime 1.x.
@@ -84,11 +137,109 @@ where
         self
     }
 
+    /// Callback called when graceful shutdown starts.
+    ///
+    /// Pair with [Registry::close_all](crate::handler::registry::Registry::close_all) to send close
+    /// frames to registered realtime connections before server stops.
+    pub fn on_graceful_shutdown<F>(mut self, on_shutdown: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.builder = self.builder.on_graceful_shutdown(on_shutdown);
+        self
+    }
+
     pub fn backlog(mut self, num: u32) -> Self {
         self.builder = self.builder.backlog(num);
         self
     }
 
+    /// Enable SO_REUSEPORT listener sharding for addresses bound after this call.
+    ///
+    /// Every worker binds it's own listener and kernel distributes incoming connections across
+    /// them. Sharding only works on unix and is ignored on other platforms.
+    pub fn reuse_port(mut self) -> Self {
+        self.builder = self.builder.reuse_port();
+        self
+    }
+
+    /// Set TCP_NODELAY for addresses bound after this call.
+    pub fn tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.builder = self.builder.tcp_nodelay(nodelay);
+        self
+    }
+
+    /// Enable SO_KEEPALIVE for addresses bound after this call.
+    ///
+    /// See [Builder::tcp_keepalive] for detail.
+    pub fn tcp_keepalive(mut self, idle: Duration, interval: Duration, retries: u32) -> Self {
+        self.builder = self.builder.tcp_keepalive(idle, interval, retries);
+        self
+    }
+
+    /// Set SO_SNDBUF in bytes for addresses bound after this call.
+    pub fn send_buffer_size(mut self, size: usize) -> Self {
+        self.builder = self.builder.send_buffer_size(size);
+        self
+    }
+
+    /// Set SO_RCVBUF in bytes for addresses bound after this call.
+    pub fn recv_buffer_size(mut self, size: usize) -> Self {
+        self.builder = self.builder.recv_buffer_size(size);
+        self
+    }
+
+    /// Set IP_TOS (or IPV6_TCLASS for ipv6 address) for addresses bound after this call.
+    pub fn ip_tos(mut self, tos: u32) -> Self {
+        self.builder = self.builder.ip_tos(tos);
+        self
+    }
+
+    /// Replace http service config with given one. Settings made to config through other
+    /// [HttpServer] methods before this call are overwritten.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use xitca_web::{config::HttpServiceConfig, handler::handler_service, App, HttpServer, WebContext};
+    /// let config = HttpServiceConfig::new()
+    ///     .keep_alive_timeout(Duration::from_secs(30))
+    ///     .max_request_headers::<128>();
+    ///
+    /// let server = App::new()
+    ///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello" }))
+    ///     .serve()
+    ///     .config(config);
+    /// ```
+    pub fn config<const HEADER_LIMIT_2: usize, const READ_BUF_LIMIT_2: usize, const WRITE_BUF_LIMIT_2: usize>(
+        self,
+        config: HttpServiceConfig<HEADER_LIMIT_2, READ_BUF_LIMIT_2, WRITE_BUF_LIMIT_2>,
+    ) -> HttpServer<S, HEADER_LIMIT_2, READ_BUF_LIMIT_2, WRITE_BUF_LIMIT_2> {
+        HttpServer {
+            service: self.service,
+            builder: self.builder,
+            config,
+            #[cfg(any(feature = "openssl", feature = "rustls"))]
+            tls_handshake_pool: self.tls_handshake_pool,
+        }
+    }
+
+    /// Enable peek into connection to figure out it's protocol regardless of alpn negotiation.
+    ///
+    /// See [HttpServiceConfig::peek_protocol] for detail.
+    pub fn peek_protocol(mut self) -> Self {
+        self.config = self.config.peek_protocol();
+        self
+    }
+
+    /// Enable Http/1 request with `Upgrade: h2c` header be upgraded to Http/2 protocol.
+    ///
+    /// See [HttpServiceConfig::h2c_upgrade] for detail.
+    pub fn h2c_upgrade(mut self) -> Self {
+        self.config = self.config.h2c_upgrade();
+        self
+    }
+
     /// Disable vectored write even when IO is able to perform it.
     ///
     /// This is beneficial when dealing with small size of response body.
@@ -156,6 +307,17 @@ where
         self.mutate_const_generic::<HEADER_LIMIT_2, READ_BUF_LIMIT, WRITE_BUF_LIMIT>()
     }
 
+    /// Run tls handshakes of addresses bound with tls after this call on a dedicated thread pool.
+    ///
+    /// The pool has given number of threads and at most `max_pending` handshakes can be queued or
+    /// running in it. A burst of new tls connections would be queued on the pool instead of
+    /// occupying workers that are processing requests of established connections.
+    #[cfg(any(feature = "openssl", feature = "rustls"))]
+    pub fn tls_handshake_pool(mut self, threads: usize, max_pending: usize) -> std::io::Result<Self> {
+        self.tls_handshake_pool = Some(xitca_http::HandshakePool::new(threads, max_pending)?);
+        Ok(self)
+    }
+
     #[doc(hidden)]
     pub fn on_worker_start<FS, Fut>(mut self, on_start: FS) -> Self
     where
@@ -249,10 +411,12 @@ where
 
         let acceptor = builder.build();
 
-        let service = self
-            .service
-            .clone()
-            .enclosed(HttpServiceBuilder::with_config(config).openssl(acceptor).with_logger());
+        let service = HttpServiceBuilder::with_config(config);
+        let service = match self.tls_handshake_pool {
+            Some(ref pool) => service.openssl_with_handshake_pool(acceptor, pool.clone()),
+            None => service.openssl(acceptor),
+        };
+        let service = self.service.clone().enclosed(service.with_logger());
 
         self.builder = self.builder.bind("xitca-web-openssl", addr, service)?;
 
@@ -284,11 +448,12 @@ where
 
         let config = std::sync::Arc::new(config);
 
-        let service = self.service.clone().enclosed(
-            HttpServiceBuilder::with_config(service_config)
-                .rustls(config)
-                .with_logger(),
-        );
+        let service = HttpServiceBuilder::with_config(service_config);
+        let service = match self.tls_handshake_pool {
+            Some(ref pool) => service.rustls_with_handshake_pool(config, pool.clone()),
+            None => service.rustls(config),
+        };
+        let service = self.service.clone().enclosed(service.with_logger());
 
         self.builder = self.builder.bind("xitca-web-rustls", addr, service)?;
 
@@ -321,12 +486,9 @@ where
     fn mutate_const_generic<const HEADER_LIMIT2: usize, const READ_BUF_LIMIT2: usize, const WRITE_BUF_LIMIT2: usize>(
         self,
     ) -> HttpServer<S, HEADER_LIMIT2, READ_BUF_LIMIT2, WRITE_BUF_LIMIT2> {
-        HttpServer {
-            service: self.service,
-            builder: self.builder,
-            config: self
-                .config
-                .mutate_const_generic::<HEADER_LIMIT2, READ_BUF_LIMIT2, WRITE_BUF_LIMIT2>(),
-        }
+        let config = self
+            .config
+            .mutate_const_generic::<HEADER_LIMIT2, READ_BUF_LIMIT2, WRITE_BUF_LIMIT2>();
+        self.config(config)
     }
 }
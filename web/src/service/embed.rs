@@ -0,0 +1,335 @@
+//! static asset embedding service.
+
+use core::{convert::Infallible, fmt::Write, marker::PhantomData};
+
+use std::borrow::Cow;
+
+use rust_embed::{EmbeddedFile, RustEmbed};
+use xitca_http::util::service::router::{RouterGen, RouterMapErr};
+
+use crate::{
+    bytes::Bytes,
+    context::WebContext,
+    dev::service::{ready::ReadyService, Service},
+    handler::conditional::etag_match,
+    http::{
+        header::{
+            HeaderMap, HeaderValue, ACCEPT_ENCODING, ALLOW, CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_NONE_MATCH, VARY,
+        },
+        Method, StatusCode, WebResponse,
+    },
+};
+
+/// Service serving static assets embedded into binary at compile time with [RustEmbed] derive.
+///
+/// Path of asset is taken from the last parameter of matched route and the service is expected
+/// to be mounted with a catch-all route parameter. Path of request is used when route has no
+/// parameter. Directory path (empty or ending with `/`) is resolved to `index.html` in it.
+///
+/// Every response carries a strong `ETag` from content hash of asset and request with matching
+/// `If-None-Match` header is responded with `304 Not Modified`. When pre-compressed variant of
+/// asset (`<path>.br` or `<path>.gz`) is embedded alongside it the variant is served to client
+/// accepting the encoding.
+///
+/// # Examples:
+/// ```rust,ignore
+/// use rust_embed::RustEmbed;
+/// use xitca_web::{service::embed::ServeEmbed, App};
+///
+/// #[derive(RustEmbed)]
+/// #[folder = "assets/"]
+/// struct Assets;
+///
+/// App::new().at("/assets/*path", ServeEmbed::<Assets>::new());
+/// ```
+pub struct ServeEmbed<E> {
+    _embed: PhantomData<fn() -> E>,
+}
+
+impl<E> ServeEmbed<E> {
+    /// Construct a new service serving assets of given [RustEmbed] type.
+    pub const fn new() -> Self
+    where
+        E: RustEmbed,
+    {
+        Self { _embed: PhantomData }
+    }
+}
+
+impl<E> Clone for ServeEmbed<E> {
+    fn clone(&self) -> Self {
+        Self { _embed: PhantomData }
+    }
+}
+
+impl<E: RustEmbed> Default for ServeEmbed<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Service for ServeEmbed<E> {
+    type Response = ServeEmbedService<E>;
+    type Error = Infallible;
+
+    async fn call(&self, _: ()) -> Result<Self::Response, Self::Error> {
+        Ok(ServeEmbedService { _embed: PhantomData })
+    }
+}
+
+impl<E> RouterGen for ServeEmbed<E> {
+    type ErrGen<R> = RouterMapErr<R>;
+
+    fn err_gen<R>(route: R) -> Self::ErrGen<R> {
+        RouterMapErr(route)
+    }
+}
+
+pub struct ServeEmbedService<E> {
+    _embed: PhantomData<fn() -> E>,
+}
+
+// pre-compressed variants in the order of preference.
+const VARIANTS: [(&str, &str); 2] = [("br", ".br"), ("gzip", ".gz")];
+
+impl<'r, C, B, E> Service<WebContext<'r, C, B>> for ServeEmbedService<E>
+where
+    E: RustEmbed,
+{
+    type Response = WebResponse;
+    type Error = Infallible;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let req = ctx.req();
+
+        if !matches!(*req.method(), Method::GET | Method::HEAD) {
+            let mut res = ctx.into_response(Bytes::new());
+            *res.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+            res.headers_mut().insert(ALLOW, HeaderValue::from_static("GET, HEAD"));
+            return Ok(res);
+        }
+
+        let path = req
+            .body()
+            .params()
+            .iter()
+            .last()
+            .map(|(_, value)| value)
+            .unwrap_or_else(|| req.uri().path())
+            .trim_start_matches('/');
+
+        let path = match path {
+            "" => Cow::Borrowed("index.html"),
+            path if path.ends_with('/') => Cow::Owned(format!("{path}index.html")),
+            path => Cow::Borrowed(path),
+        };
+
+        let Some(file) = E::get(&path) else {
+            let mut res = ctx.into_response(Bytes::new());
+            *res.status_mut() = StatusCode::NOT_FOUND;
+            return Ok(res);
+        };
+
+        let mut vary = false;
+        let mut variant = None;
+
+        for (encoding, ext) in VARIANTS {
+            if let Some(file) = E::get(&format!("{path}{ext}")) {
+                vary = true;
+                if variant.is_none() && accept_encoding(req.headers(), encoding) {
+                    variant = Some((encoding, file));
+                }
+            }
+        }
+
+        let content_type = HeaderValue::from_str(file.metadata.mimetype()).ok();
+        let (encoding, file) = match variant {
+            Some((encoding, file)) => (Some(encoding), file),
+            None => (None, file),
+        };
+
+        let etag = etag(&file);
+
+        if let Some(tags) = req.headers().get(IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+            if etag_match(tags, etag.as_bytes()) {
+                let mut res = ctx.into_response(Bytes::new());
+                *res.status_mut() = StatusCode::NOT_MODIFIED;
+                res.headers_mut().insert(ETAG, etag);
+                if vary {
+                    res.headers_mut()
+                        .insert(VARY, HeaderValue::from_static("accept-encoding"));
+                }
+                return Ok(res);
+            }
+        }
+
+        let body = match file.data {
+            Cow::Borrowed(data) => Bytes::from_static(data),
+            Cow::Owned(data) => Bytes::from(data),
+        };
+
+        let mut res = ctx.into_response(body);
+        let headers = res.headers_mut();
+        headers.insert(ETAG, etag);
+        if let Some(content_type) = content_type {
+            headers.insert(CONTENT_TYPE, content_type);
+        }
+        if let Some(encoding) = encoding {
+            headers.insert(CONTENT_ENCODING, HeaderValue::from_static(encoding));
+        }
+        if vary {
+            headers.insert(VARY, HeaderValue::from_static("accept-encoding"));
+        }
+
+        Ok(res)
+    }
+}
+
+impl<E> ReadyService for ServeEmbedService<E> {
+    type Ready = ();
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {}
+}
+
+fn etag(file: &EmbeddedFile) -> HeaderValue {
+    let mut tag = String::with_capacity(66);
+    tag.push('"');
+    for b in file.metadata.sha256_hash() {
+        let _ = write!(tag, "{b:02x}");
+    }
+    tag.push('"');
+    // hex digits in quotes is always valid header value.
+    HeaderValue::from_str(&tag).unwrap()
+}
+
+fn accept_encoding(headers: &HeaderMap, encoding: &str) -> bool {
+    headers
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|item| {
+            let mut params = item.split(';');
+            let name = params.next().unwrap_or_default().trim();
+            let rejected = params.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q == 0.0)
+            });
+            (name.eq_ignore_ascii_case(encoding) || name == "*") && !rejected
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        http::{Request, RequestExt, WebRequest},
+        test::collect_body,
+        App,
+    };
+
+    use super::*;
+
+    #[derive(RustEmbed)]
+    #[folder = "tests/fixtures/embed/"]
+    struct Assets;
+
+    fn req(uri: &'static str, headers: &[(&'static str, &'static str)]) -> WebRequest {
+        let mut req = Request::builder().uri(uri).body(RequestExt::default()).unwrap();
+        for (name, value) in headers {
+            req.headers_mut().insert(*name, HeaderValue::from_static(value));
+        }
+        req
+    }
+
+    #[test]
+    fn serve() {
+        let service = App::new()
+            .at("/assets/*path", ServeEmbed::<Assets>::new())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req("/assets/style.css", &[])).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/css");
+        assert!(res.headers().get(VARY).is_none());
+        let body = collect_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, Assets::get("style.css").unwrap().data.as_ref());
+
+        let res = service.call(req("/assets/missing.js", &[])).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let mut post = req("/assets/style.css", &[]);
+        *post.method_mut() = Method::POST;
+        let res = service.call(post).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[test]
+    fn etag() {
+        let service = App::new()
+            .at("/*path", ServeEmbed::<Assets>::new())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req("/style.css", &[])).now_or_panic().unwrap();
+        let etag = res.headers().get(ETAG).unwrap().to_str().unwrap().to_owned();
+        assert!(etag.starts_with('"') && etag.ends_with('"'));
+
+        let tag = Box::leak(format!("\"nah\", W/{etag}").into_boxed_str());
+        let res = service
+            .call(req("/style.css", &[("if-none-match", tag)]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(ETAG).unwrap(), etag.as_str());
+
+        let res = service
+            .call(req("/style.css", &[("if-none-match", "\"nah\"")]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn compressed_variant() {
+        let service = App::new()
+            .at("/", ServeEmbed::<Assets>::new())
+            .at("/*path", ServeEmbed::<Assets>::new())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service
+            .call(req("/", &[("accept-encoding", "br, gzip")]))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/html");
+        assert_eq!(res.headers().get(VARY).unwrap(), "accept-encoding");
+        let gz_etag = res.headers().get(ETAG).unwrap().clone();
+        let body = collect_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, Assets::get("index.html.gz").unwrap().data.as_ref());
+
+        let res = service
+            .call(req("/index.html", &[("accept-encoding", "gzip;q=0")]))
+            .now_or_panic()
+            .unwrap();
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.headers().get(VARY).unwrap(), "accept-encoding");
+        assert_ne!(res.headers().get(ETAG).unwrap(), gz_etag);
+        let body = collect_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, Assets::get("index.html").unwrap().data.as_ref());
+    }
+}
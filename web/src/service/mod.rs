@@ -1,4 +1,8 @@
 //! service types
 
+#[cfg(feature = "embed")]
+pub mod embed;
 #[cfg(feature = "tower-http-compat")]
 pub mod tower_http_compat;
+
+pub mod route_doc;
@@ -0,0 +1,250 @@
+//! route documentation metadata and introspection service.
+
+use core::convert::Infallible;
+
+use std::sync::{Arc, RwLock};
+
+use xitca_http::util::service::router::{RouterGen, RouterMapErr};
+
+use crate::{
+    body::BodyStream,
+    context::WebContext,
+    dev::service::{ready::ReadyService, Service},
+    error::push_json_str,
+    handler::ExtractError,
+    http::{const_header_value::JSON, header::CONTENT_TYPE, WebResponse},
+};
+
+/// Documentation metadata attached to route with [App::at_doc](crate::App::at_doc).
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::service::route_doc::RouteDoc;
+/// let doc = RouteDoc::new().summary("list users").tag("users").auth("bearer");
+/// assert_eq!(doc.get_summary(), Some("list users"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RouteDoc {
+    summary: Option<String>,
+    tags: Vec<String>,
+    auth: Option<String>,
+}
+
+impl RouteDoc {
+    /// Construct an empty documentation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set short summary of what the route does.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Add a tag for grouping routes. Can be called multiple times.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Set authentication requirement of the route. e.g. `bearer`.
+    pub fn auth(mut self, auth: impl Into<String>) -> Self {
+        self.auth = Some(auth.into());
+        self
+    }
+
+    pub fn get_summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn get_tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn get_auth(&self) -> Option<&str> {
+        self.auth.as_deref()
+    }
+}
+
+/// Shared registry of [RouteDoc] of an [App](crate::App). Obtained from
+/// [App::route_docs](crate::App::route_docs) for programmatic access.
+///
+/// It's also a service serving documented routes as JSON array in the form of
+/// `[{"path":"/users/:id","summary":"get user","tags":["users"],"auth":"bearer"}]` where absent
+/// summary and auth are `null`. Routes documented after it's registered are included as well.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{handler::handler_service, service::route_doc::RouteDoc, App, WebContext};
+/// let app = App::new().at_doc(
+///     "/users",
+///     RouteDoc::new().summary("list users").tag("users"),
+///     handler_service(|_: &WebContext<'_>| async { "[]" }),
+/// );
+///
+/// let docs = app.route_docs();
+/// assert_eq!(docs.get("/users").unwrap().get_summary(), Some("list users"));
+///
+/// // introspection endpoint.
+/// app.at("/_routes", docs);
+/// ```
+#[derive(Clone, Default)]
+pub struct RouteDocs(Arc<RwLock<Vec<(&'static str, RouteDoc)>>>);
+
+impl RouteDocs {
+    /// Get documentation of route with given path.
+    pub fn get(&self, path: &str) -> Option<RouteDoc> {
+        self.0
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(p, _)| *p == path)
+            .map(|(_, doc)| doc.clone())
+    }
+
+    /// Snapshot of all documented routes in the order they are registered.
+    pub fn routes(&self) -> Vec<(&'static str, RouteDoc)> {
+        self.0.read().unwrap().clone()
+    }
+
+    /// Render all documented routes as JSON array.
+    pub fn to_json(&self) -> String {
+        let docs = self.0.read().unwrap();
+
+        let mut buf = String::from("[");
+        for (i, (path, doc)) in docs.iter().enumerate() {
+            if i > 0 {
+                buf.push(',');
+            }
+            buf.push_str("{\"path\":");
+            push_json_str(&mut buf, path);
+            buf.push_str(",\"summary\":");
+            push_json_opt(&mut buf, doc.get_summary());
+            buf.push_str(",\"tags\":[");
+            for (i, tag) in doc.get_tags().iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                push_json_str(&mut buf, tag);
+            }
+            buf.push_str("],\"auth\":");
+            push_json_opt(&mut buf, doc.get_auth());
+            buf.push('}');
+        }
+        buf.push(']');
+        buf
+    }
+
+    // insert documentation of route. existing one on the same path is replaced.
+    pub(crate) fn insert(&self, path: &'static str, doc: RouteDoc) {
+        let mut docs = self.0.write().unwrap();
+        match docs.iter_mut().find(|(p, _)| *p == path) {
+            Some((_, d)) => *d = doc,
+            None => docs.push((path, doc)),
+        }
+    }
+
+    pub(crate) fn remove(&self, path: &str) {
+        self.0.write().unwrap().retain(|(p, _)| *p != path);
+    }
+}
+
+fn push_json_opt(buf: &mut String, s: Option<&str>) {
+    match s {
+        Some(s) => push_json_str(buf, s),
+        None => buf.push_str("null"),
+    }
+}
+
+impl Service for RouteDocs {
+    type Response = Self;
+    type Error = Infallible;
+
+    async fn call(&self, _: ()) -> Result<Self::Response, Self::Error> {
+        Ok(self.clone())
+    }
+}
+
+impl RouterGen for RouteDocs {
+    type ErrGen<R> = RouterMapErr<R>;
+
+    fn err_gen<R>(route: R) -> Self::ErrGen<R> {
+        RouterMapErr(route)
+    }
+}
+
+// error type is the same as handler with type extractors so it can be mixed with them in router.
+impl<'r, C, B> Service<WebContext<'r, C, B>> for RouteDocs
+where
+    B: BodyStream,
+{
+    type Response = WebResponse;
+    type Error = ExtractError<B::Error>;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let mut res = ctx.into_response(self.to_json());
+        res.headers_mut().insert(CONTENT_TYPE, JSON);
+        Ok(res)
+    }
+}
+
+impl ReadyService for RouteDocs {
+    type Ready = ();
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {}
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_http::RequestBody;
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{Request, RequestExt, Uri},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn introspect() {
+        let app = App::new()
+            .at_doc(
+                "/users/:id",
+                RouteDoc::new().summary("get \"user\"").tag("users").auth("bearer"),
+                handler_service(|_: &WebContext<'_>| async { "user" }),
+            )
+            .at("/health", handler_service(|_: &WebContext<'_>| async { "ok" }));
+
+        let docs = app.route_docs();
+        assert!(docs.get("/health").is_none());
+        assert_eq!(docs.routes().len(), 1);
+
+        let (app, handle) = app.at("/_routes", docs.clone()).finish_dynamic();
+        let service = app.call(()).now_or_panic().unwrap();
+
+        handle.at_doc(
+            "/admin",
+            RouteDoc::new(),
+            handler_service(|_: &WebContext<'_>| async { "admin" }),
+        );
+
+        let mut req = Request::new(RequestExt::<RequestBody>::default());
+        *req.uri_mut() = Uri::from_static("/_routes");
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), JSON);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(
+            body,
+            "[{\"path\":\"/users/:id\",\"summary\":\"get \\\"user\\\"\",\"tags\":[\"users\"],\"auth\":\"bearer\"},\
+             {\"path\":\"/admin\",\"summary\":null,\"tags\":[],\"auth\":null}]"
+        );
+
+        handle.remove("/admin");
+        assert!(docs.get("/admin").is_none());
+    }
+}
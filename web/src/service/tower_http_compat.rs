@@ -1,7 +1,9 @@
 use std::{
     cell::RefCell,
     convert::Infallible,
+    future::Future,
     pin::Pin,
+    rc::Rc,
     task::{Context, Poll},
 };
 
@@ -99,6 +101,75 @@ impl<S> ReadyService for TowerCompatService<S> {
     async fn ready(&self) -> Self::Ready {}
 }
 
+/// A bridge type that expose xitca-web's service as [tower_service::Service]. It's the inverse of
+/// [TowerHttpCompat] and can be used for mounting xitca-web application inside tower/hyper
+/// infrastructures.
+///
+/// Futures produced by xitca-web's services are not thread safe and therefore this type is only
+/// suitable for single threaded runtime.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{body::Once, bytes::Bytes, dev::service::Service, handler::handler_service, http::Request, App};
+/// # use xitca_web::service::tower_http_compat::{CompatBody, TowerServiceCompat};
+/// # fn assert_tower<S: tower_service::Service<Request<CompatBody<Once<Bytes>>>>>(_: S) {}
+/// # async fn compat() {
+/// let service = App::new()
+///     .at("/", handler_service(|| async { "hello,world!" }))
+///     .finish()
+///     .call(())
+///     .await
+///     .unwrap();
+///
+/// // service can be passed to any place that accept tower::Service.
+/// let service = TowerServiceCompat::new(service);
+/// # assert_tower(service);
+/// # }
+/// ```
+pub struct TowerServiceCompat<S> {
+    service: Rc<S>,
+}
+
+impl<S> TowerServiceCompat<S> {
+    pub fn new(service: S) -> Self {
+        Self {
+            service: Rc::new(service),
+        }
+    }
+}
+
+impl<S> Clone for TowerServiceCompat<S> {
+    fn clone(&self) -> Self {
+        Self {
+            service: self.service.clone(),
+        }
+    }
+}
+
+impl<S, B, ResB> tower_service::Service<Request<B>> for TowerServiceCompat<S>
+where
+    S: Service<Request<RequestExt<CompatBody<B>>>, Response = WebResponse<ResB>> + 'static,
+    B: Body + 'static,
+{
+    type Response = Response<CompatBody<ResB>>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    #[inline]
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let service = self.service.clone();
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let req = Request::from_parts(parts, RequestExt::default().map_body(|_: ()| CompatBody::new(body)));
+            service.call(req).await.map(|res| res.map(CompatBody::new))
+        })
+    }
+}
+
 pin_project! {
     pub struct CompatBody<B> {
         #[pin]
@@ -175,6 +246,27 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn tower_service_compat() {
+        use xitca_unsafe_collection::futures::NowOrPanic;
+
+        use crate::{handler::handler_service, App};
+
+        let service = App::new()
+            .at("/", handler_service(|| async { "996" }))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut service = TowerServiceCompat::new(service);
+
+        let req = Request::new(CompatBody::new(Once::new(Bytes::new())));
+        let res = tower_service::Service::call(&mut service, req).now_or_panic().unwrap();
+
+        assert_eq!(res.status().as_u16(), 200);
+    }
+
     #[test]
     fn body_compat() {
         let buf = Bytes::from_static(b"996");